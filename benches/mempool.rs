@@ -0,0 +1,168 @@
+// mempool capacity benchmark, `cargo bench --bench mempool`
+// fills pools of several sizes with signed transfers from random senders and nonces,
+// then reports add, replace, evict and get_pending latencies
+use alloy::primitives::{Address, B256, Bytes, U256};
+use alloy_signer::Signature;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use speed_blockchain::crypto::SignatureCache;
+use speed_blockchain::{CHAIN_ID, KeyPair, Mempool, Transaction, TransactionKind};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// the node runs with 1_000, larger pools show how the operations scale
+const POOL_SIZES: [usize; 3] = [1_000, 5_000, 20_000];
+const SENDERS: usize = 200;
+const GWEI: u64 = 1_000_000_000;
+
+struct Latencies {
+    op: &'static str,
+    samples: Vec<Duration>,
+}
+
+impl Latencies {
+    fn new(op: &'static str) -> Self {
+        Self {
+            op,
+            samples: Vec::new(),
+        }
+    }
+
+    fn time<T>(&mut self, op: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = op();
+        self.samples.push(start.elapsed());
+        result
+    }
+
+    fn percentile(&self, p: usize) -> Duration {
+        let mut sorted = self.samples.clone();
+        sorted.sort();
+        sorted[(sorted.len() * p / 100).min(sorted.len() - 1)]
+    }
+
+    fn report(&self, pool_size: usize) {
+        let total: Duration = self.samples.iter().sum();
+        println!(
+            "{:>8} {:<18} p50 {:>10.1?}  p99 {:>10.1?}  max {:>10.1?}  {:>10.0} ops/s",
+            pool_size,
+            self.op,
+            self.percentile(50),
+            self.percentile(99),
+            self.samples.iter().max().unwrap(),
+            self.samples.len() as f64 / total.as_secs_f64(),
+        );
+    }
+}
+
+async fn signed(keypair: &KeyPair, nonce: u64, gas_price: u64) -> Transaction {
+    let mut tx = Transaction {
+        from: keypair.address,
+        to: Address::repeat_byte(0xbb),
+        amount: U256::from(1_000),
+        timestamp: 1,
+        nonce,
+        chain_id: CHAIN_ID,
+        kind: TransactionKind::Transfer,
+        gas_limit: U256::from(30_000),
+        gas_price: U256::from(gas_price * GWEI),
+        max_priority_fee_per_gas: None,
+        memo: Bytes::new(),
+        data: Bytes::new(),
+        signature: Signature::new(U256::ZERO, U256::ZERO, false),
+        hash: B256::ZERO,
+    };
+    tx.sign(keypair).await.unwrap();
+    tx
+}
+
+// `count` transactions from random senders, nonces shuffled within each sender
+async fn random_load(rng: &mut StdRng, keypairs: &[KeyPair], count: usize) -> Vec<Transaction> {
+    let mut per_sender = vec![0u64; keypairs.len()];
+    let mut slots = Vec::with_capacity(count);
+    for _ in 0..count {
+        let sender = rng.gen_range(0..keypairs.len());
+        slots.push((sender, per_sender[sender]));
+        per_sender[sender] += 1;
+    }
+    slots.shuffle(rng);
+
+    let mut transactions = Vec::with_capacity(count);
+    for (sender, nonce) in slots {
+        let gas_price = rng.gen_range(1..=100);
+        transactions.push(signed(&keypairs[sender], nonce, gas_price).await);
+    }
+    transactions
+}
+
+async fn bench_pool(rng: &mut StdRng, keypairs: &[KeyPair], pool_size: usize) -> Vec<Latencies> {
+    let churn = pool_size / 5;
+    let load = random_load(rng, keypairs, pool_size).await;
+    let mut replacements = Vec::with_capacity(churn);
+    for tx in load.choose_multiple(rng, churn) {
+        let keypair = keypairs.iter().find(|k| k.address == tx.from).unwrap();
+        let bumped = tx.gas_price / U256::from(GWEI) + U256::from(1);
+        replacements.push(signed(keypair, tx.nonce, bumped.to::<u64>()).await);
+    }
+    let mut newcomers = Vec::with_capacity(churn);
+    for i in 0..churn {
+        let keypair = &keypairs[i % keypairs.len()];
+        newcomers.push(signed(keypair, 1_000_000 + i as u64, 1_000).await);
+    }
+
+    // first pass recovers every signature, as for gossip nobody has seen yet
+    let signatures = Arc::new(SignatureCache::new(pool_size * 2));
+    let mut cold = Latencies::new("add (recover sig)");
+    let mut pool = Mempool::new(pool_size).with_signature_cache(signatures.clone());
+    for tx in &load {
+        cold.time(|| pool.add_transaction(tx)).unwrap();
+    }
+
+    // same transactions again with the senders cached, only the pool's bookkeeping
+    let mut add = Latencies::new("add");
+    let mut pool = Mempool::new(pool_size).with_signature_cache(signatures.clone());
+    for tx in &load {
+        add.time(|| pool.add_transaction(tx)).unwrap();
+    }
+
+    for tx in replacements.iter().chain(&newcomers) {
+        signatures.is_signature_valid(tx);
+    }
+    let mut replace = Latencies::new("replace");
+    for tx in &replacements {
+        replace.time(|| pool.add_transaction(tx)).unwrap();
+    }
+    let mut evict = Latencies::new("evict");
+    for tx in &newcomers {
+        evict.time(|| pool.add_transaction(tx)).unwrap();
+    }
+
+    let mut get_pending = Latencies::new("get_pending");
+    for _ in 0..100 {
+        get_pending.time(|| pool.get_all_transactions());
+    }
+
+    vec![cold, add, replace, evict, get_pending]
+}
+
+#[tokio::main]
+async fn main() {
+    let mut rng = StdRng::seed_from_u64(1795);
+    let keypairs: Vec<KeyPair> = (0..SENDERS)
+        .map(|i| KeyPair::generate(format!("bench-{}", i)))
+        .collect();
+
+    let mut results = Vec::new();
+    for pool_size in POOL_SIZES {
+        results.push((pool_size, bench_pool(&mut rng, &keypairs, pool_size).await));
+    }
+
+    // the pool logs every transaction, so the table comes last
+    println!("\n📊 mempool latencies");
+    for (pool_size, latencies) in &results {
+        for latency in latencies {
+            latency.report(*pool_size);
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use alloy::primitives::B256;
+use anyhow::{Result, anyhow};
+
+use super::{Args, rpc_client};
+use crate::rpc::RpcAccessStatus;
+use crate::rpc::admin::AdminRpcClient;
+
+// `speed admin ...`: manage a running node through the admin rpc namespace
+pub async fn admin(args: &Args) -> Result<()> {
+    let client = rpc_client(args)?;
+
+    match (args.positional(1), args.positional(2)) {
+        (Some("peer"), Some("list")) => {
+            let peers = client.peers().await?;
+            if peers.is_empty() {
+                println!("No peers");
+            }
+            for peer in peers {
+                if peer.banned {
+                    println!("{} banned", peer.peer_id);
+                    continue;
+                }
+                println!(
+                    "{} connected {} last seen {}s ago, {} ping failures",
+                    peer.peer_id,
+                    peer.address.as_deref().unwrap_or("-"),
+                    peer.last_seen_secs.unwrap_or_default(),
+                    peer.ping_failures
+                );
+            }
+        }
+        (Some("peer"), Some("stats")) => {
+            let stats = client.peer_stats().await?;
+            if stats.is_empty() {
+                println!("No gossip received yet");
+            }
+            for peer in stats {
+                println!(
+                    "{} {} messages, {} bytes, {} duplicates, {:.1}% invalid",
+                    peer.peer_id,
+                    peer.total.messages,
+                    peer.total.bytes,
+                    peer.total.duplicates,
+                    peer.invalid_ratio * 100.0
+                );
+                for (kind, stats) in &peer.by_kind {
+                    println!(
+                        "  {:?}: {} messages, {} bytes, {} duplicates, {} invalid",
+                        kind, stats.messages, stats.bytes, stats.duplicates, stats.invalid
+                    );
+                }
+            }
+        }
+        (Some("peer"), Some("ban")) => {
+            let peer_id = required_positional(args, 3, "peer id")?;
+            match client.ban_peer(peer_id.to_string()).await? {
+                true => println!("⛔ Banned {}", peer_id),
+                false => println!("{} was already banned", peer_id),
+            }
+        }
+        (Some("peer"), Some("unban")) => {
+            let peer_id = required_positional(args, 3, "peer id")?;
+            match client.unban_peer(peer_id.to_string()).await? {
+                true => println!("✅ Unbanned {}", peer_id),
+                false => println!("{} was not banned", peer_id),
+            }
+        }
+        (Some("mempool"), Some("clear")) => {
+            let removed = client.clear_mempool().await?;
+            println!("🧹 Dropped {} pending transactions", removed);
+        }
+        (Some("mempool"), Some("drop-tx")) => {
+            let tx_hash: B256 = required_positional(args, 3, "transaction hash")?
+                .parse()
+                .map_err(|e| anyhow!("Invalid transaction hash: {}", e))?;
+            match client.drop_transaction(tx_hash).await? {
+                true => println!("🧹 Dropped {}", tx_hash),
+                false => println!("{} is not pending", tx_hash),
+            }
+        }
+        (Some("set-log-level"), Some(level)) => {
+            let level = client.set_log_level(level.to_string()).await?;
+            println!("Log level set to {}", level);
+        }
+        (Some("trigger-snapshot"), _) => {
+            let snapshot = client.trigger_snapshot().await?;
+            println!(
+                "📸 Snapshot of block #{} ({} accounts, state root {}) written to {}",
+                snapshot.block_number, snapshot.accounts, snapshot.state_root, snapshot.path
+            );
+        }
+        (Some("export-checkpoint"), _) => {
+            let checkpoint = client.export_checkpoint().await?;
+            println!(
+                "📍 Checkpoint of block #{} {} (state root {}) written to {}",
+                checkpoint.block_number,
+                checkpoint.block_hash,
+                checkpoint.state_root,
+                checkpoint.path
+            );
+        }
+        (Some("rpc"), Some("status")) => {
+            print_rpc_access(&client.rpc_access().await?);
+        }
+        (Some("rpc"), Some(toggle @ ("enable" | "disable"))) => {
+            let namespace = required_positional(args, 3, "namespace")?;
+            let status = client
+                .set_rpc_namespace(namespace.to_string(), toggle == "enable")
+                .await?;
+            print_rpc_access(&status);
+        }
+        (Some("rpc"), Some("rotate-tokens")) => {
+            // comma separated, keep the old token listed until every client has the new one
+            let tokens = args
+                .value("tokens")
+                .unwrap_or_default()
+                .split(',')
+                .map(|token| token.trim().to_string())
+                .filter(|token| !token.is_empty())
+                .collect();
+            print_rpc_access(&client.rotate_rpc_tokens(tokens).await?);
+        }
+        _ => return Err(anyhow!("Unknown admin command, run `speed` for usage")),
+    }
+
+    Ok(())
+}
+
+fn required_positional<'a>(args: &'a Args, index: usize, name: &str) -> Result<&'a str> {
+    args.positional(index)
+        .ok_or_else(|| anyhow!("Missing {}", name))
+}
+
+fn print_rpc_access(status: &RpcAccessStatus) {
+    println!(
+        "Enabled namespaces: {}",
+        status.enabled_namespaces.join(", ")
+    );
+    if !status.disabled_namespaces.is_empty() {
+        println!(
+            "Disabled namespaces: {}",
+            status.disabled_namespaces.join(", ")
+        );
+    }
+    match status.tokens {
+        0 => println!("No token required"),
+        tokens => println!("{} tokens accepted", tokens),
+    }
+}
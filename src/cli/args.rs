@@ -0,0 +1,79 @@
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+// minimal command line parser: positional arguments and `--flag [value]` options
+#[derive(Debug, Clone, Default)]
+pub struct Args {
+    positionals: Vec<String>,
+    flags: HashMap<String, Option<String>>,
+}
+
+impl Args {
+    pub fn parse(raw: &[String]) -> Self {
+        let mut args = Args::default();
+        let mut iter = raw.iter().peekable();
+
+        while let Some(arg) = iter.next() {
+            match arg.strip_prefix("--") {
+                Some(flag) => {
+                    // support both `--name=value` and `--name value`
+                    if let Some((name, value)) = flag.split_once('=') {
+                        args.flags.insert(name.to_string(), Some(value.to_string()));
+                        continue;
+                    }
+
+                    let value = match iter.peek() {
+                        Some(next) if !next.starts_with("--") => iter.next().cloned(),
+                        _ => None,
+                    };
+                    args.flags.insert(flag.to_string(), value);
+                }
+                None => args.positionals.push(arg.clone()),
+            }
+        }
+
+        args
+    }
+
+    // get positional argument by index
+    pub fn positional(&self, index: usize) -> Option<&str> {
+        self.positionals.get(index).map(String::as_str)
+    }
+
+    // check if a boolean flag is set
+    pub fn has_flag(&self, name: &str) -> bool {
+        self.flags.contains_key(name)
+    }
+
+    // get raw flag value
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.flags.get(name).and_then(|v| v.as_deref())
+    }
+
+    // get a required flag and parse it
+    pub fn required<T: FromStr>(&self, name: &str) -> Result<T>
+    where
+        T::Err: std::fmt::Display,
+    {
+        let value = self
+            .value(name)
+            .ok_or_else(|| anyhow!("Missing required option --{}", name))?;
+        value
+            .parse()
+            .map_err(|e| anyhow!("Invalid value for --{}: {}", name, e))
+    }
+
+    // get an optional flag and parse it, falling back to default
+    pub fn optional<T: FromStr>(&self, name: &str, default: T) -> Result<T>
+    where
+        T::Err: std::fmt::Display,
+    {
+        match self.value(name) {
+            Some(value) => value
+                .parse()
+                .map_err(|e| anyhow!("Invalid value for --{}: {}", name, e)),
+            None => Ok(default),
+        }
+    }
+}
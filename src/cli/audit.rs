@@ -0,0 +1,58 @@
+use anyhow::{Result, anyhow};
+use std::fs::File;
+use std::io::Write;
+
+use super::{Args, node_config};
+use crate::consensus::{read_audit_log, verify_entries};
+
+// `speed audit verify|export`: check the signing audit log, or copy part of it for a dispute
+pub fn audit(args: &Args) -> Result<()> {
+    let path = match args.value("file") {
+        Some(path) => path.into(),
+        None => {
+            let config = node_config(args)?;
+            let data_dir: String = args.optional("data-dir", config.data_dir.clone())?;
+            config.signing_audit.path(&data_dir)
+        }
+    };
+    let entries = read_audit_log(&path)?;
+
+    match args.positional(1) {
+        Some("verify") => {
+            let summary = verify_entries(&entries)?;
+            println!(
+                "✅ {} entries verified in {}, head {}",
+                summary.entries,
+                path.display(),
+                summary.head
+            );
+            Ok(())
+        }
+        Some("export") => {
+            let out: String = args.required("out")?;
+            let from_slot: u64 = args.optional("from-slot", 0)?;
+            let to_slot: u64 = args.optional("to-slot", u64::MAX)?;
+
+            // the whole log is checked, the export is a contiguous run that still verifies alone
+            verify_entries(&entries)?;
+            let first = entries.iter().position(|e| e.slot >= from_slot);
+            let last = entries.iter().rposition(|e| e.slot <= to_slot);
+            let exported = match (first, last) {
+                (Some(first), Some(last)) if first <= last => &entries[first..=last],
+                _ => &[][..],
+            };
+
+            let mut file = File::create(&out)?;
+            for entry in exported {
+                writeln!(file, "{}", serde_json::to_string(entry)?)?;
+            }
+            let summary = verify_entries(exported)?;
+            println!(
+                "✅ Exported {} entries to {}, anchored at {}, head {}",
+                summary.entries, out, summary.anchor, summary.head
+            );
+            Ok(())
+        }
+        _ => Err(anyhow!("Unknown audit command, run `speed` for usage")),
+    }
+}
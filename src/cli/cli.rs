@@ -0,0 +1,144 @@
+use anyhow::Result;
+
+use super::{
+    Args, admin, approve, audit, authorize_session, batch_transfer, call_contract, call_precompile,
+    commit_memo, config_check, deploy_contract, dump_debug, export_chain, register_name,
+    register_network_address, register_validator, reindex, reveal_memo, selftest, send_transaction,
+    stake_top_up, stake_withdraw, transfer_from, transfer_name, unstake,
+};
+use crate::rpc::client_version;
+use crate::{MultiChainConfig, MultiChainNode, NodeConfig, SpeedNode};
+
+const USAGE: &str = "Usage (every rpc command takes --rpc-token <token>):
+  speed version
+  speed selftest
+  speed node [--config <file>] [--port <p2p port>] [--rpc-port <port>]
+             [--dry-run] [--dev]
+             [--min-peers <n>] [--max-head-lag <slots>] [--admin-rpc]
+             [--max-gas-price-multiplier <n>]
+             [--rpc-tls-self-signed] [--verify-blocks none|full|<n>]
+             [--chain-spec <toml or json file>]
+  speed node --chains <multi-chain config file>
+  speed config check [--config <file>] [node options]
+  speed tx send --key <name> --to <address|name> --amount <wei>
+                [--gas-limit <gas>] [--gas-price <wei>] [--priority-fee <wei>]
+                [--rpc <url>] [--force] [--max-gas-price-multiplier <n>]
+                [--request-id <id>] [--memo <text>|0x<hex>] [--data 0x<hex>]
+                [--from <account> when --key is its session key]
+  speed tx register-address --key <validator> --multiaddr <multiaddr> [--rpc <url>]
+  speed tx approve --key <owner> --spender <address> --allowance <wei> [--rpc <url>]
+  speed tx authorize-session --key <name> --session-key <address> --max-value <wei>
+                            [--expiry-slot <slot>] [--rpc <url>]
+  speed tx register-name --key <name> --name <name> [--rpc <url>]
+  speed tx transfer-name --key <owner> --name <name> --to <address|name> [--rpc <url>]
+  speed tx transfer-from --key <spender> --owner <address> --to <address|name> --amount <wei>
+                         [--rpc <url>]
+  speed tx batch-transfer --key <name> --outputs <address>:<wei>,<address>:<wei>,...
+                          [--rpc <url>] [--force]
+  speed tx call --key <name> --to <precompile address> [--input 0x<hex>] [--rpc <url>]
+  speed tx deploy --key <name> --code <.wasm or .wat file> [--amount <wei>] [--rpc <url>]
+  speed tx call-contract --key <name> --to <contract address> [--input 0x<hex>]
+                         [--amount <wei>] [--gas-limit <gas>] [--rpc <url>]
+  speed tx commit --key <name> --payload <text>|0x<hex> [--salt 0x<32 bytes>] [--rpc <url>]
+  speed tx reveal --key <name> --payload <text>|0x<hex> --salt 0x<32 bytes> [--rpc <url>]
+  speed tx stake-top-up|stake-withdraw --key <validator> --amount <stake> [--rpc <url>]
+  speed tx register-validator --key <name> --amount <stake> [--rpc <url>]
+  speed tx unstake --key <validator> [--rpc <url>]
+  speed export --out <file> [--format ndjson] [--data-dir <dir>] [--from <block>]
+               [--to <block>] [--resume]
+  speed reindex [--data-dir <dir>] [--dev] [--restart]
+  speed admin peer list|stats|ban <peer id>|unban <peer id> [--rpc <url>]
+  speed admin mempool clear|drop-tx <tx hash> [--rpc <url>]
+  speed admin set-log-level off|error|warn|info|debug|trace [--rpc <url>]
+  speed admin trigger-snapshot [--rpc <url>]
+  speed admin export-checkpoint [--rpc <url>]
+  speed admin rpc status|enable <namespace>|disable <namespace> [--rpc <url>]
+  speed admin rpc rotate-tokens --tokens <token>,<token>,... [--rpc <url>]
+  speed audit verify|export [--file <audit log>] [--data-dir <dir>] [--out <file>]
+                           [--from-slot <slot>] [--to-slot <slot>]
+  speed dump-debug [--rpc <url>] [--config <file>] [--log-file <file>] [--log-lines <n>]
+                   [--events <n>] [--out <file>]";
+
+// entry point of the `speed` command line
+pub async fn run(raw: &[String]) -> Result<()> {
+    let args = Args::parse(raw);
+
+    match (args.positional(0), args.positional(1)) {
+        (Some("node"), _) => run_node(&args).await,
+        (Some("version"), _) => {
+            println!("{}", client_version());
+            Ok(())
+        }
+        (Some("selftest"), _) => selftest().await,
+        (Some("config"), Some("check")) => config_check(&args),
+        (Some("export"), _) => export_chain(&args),
+        (Some("reindex"), _) => reindex(&args).await,
+        (Some("admin"), _) => admin(&args).await,
+        (Some("audit"), _) => audit(&args),
+        (Some("dump-debug"), _) => dump_debug(&args).await,
+        (Some("tx"), Some("send")) => send_transaction(&args).await,
+        (Some("tx"), Some("register-address")) => register_network_address(&args).await,
+        (Some("tx"), Some("approve")) => approve(&args).await,
+        (Some("tx"), Some("authorize-session")) => authorize_session(&args).await,
+        (Some("tx"), Some("register-name")) => register_name(&args).await,
+        (Some("tx"), Some("transfer-name")) => transfer_name(&args).await,
+        (Some("tx"), Some("transfer-from")) => transfer_from(&args).await,
+        (Some("tx"), Some("batch-transfer")) => batch_transfer(&args).await,
+        (Some("tx"), Some("call")) => call_precompile(&args).await,
+        (Some("tx"), Some("deploy")) => deploy_contract(&args).await,
+        (Some("tx"), Some("call-contract")) => call_contract(&args).await,
+        (Some("tx"), Some("commit")) => commit_memo(&args).await,
+        (Some("tx"), Some("reveal")) => reveal_memo(&args).await,
+        (Some("tx"), Some("stake-top-up")) => stake_top_up(&args).await,
+        (Some("tx"), Some("register-validator")) => register_validator(&args).await,
+        (Some("tx"), Some("unstake")) => unstake(&args).await,
+        (Some("tx"), Some("stake-withdraw")) => stake_withdraw(&args).await,
+        _ => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+    }
+}
+
+// load node config from `--config` and apply command line overrides
+pub fn node_config(args: &Args) -> Result<NodeConfig> {
+    let mut config = match args.value("config") {
+        Some(path) => NodeConfig::load(path)?,
+        None => NodeConfig::default(),
+    };
+
+    config.port = args.optional("port", config.port)?;
+    config.rpc_port = args.optional("rpc-port", config.rpc_port)?;
+    config.dry_run |= args.has_flag("dry-run");
+    config.dev |= args.has_flag("dev");
+    config.admin_rpc |= args.has_flag("admin-rpc");
+    if let Some(path) = args.value("chain-spec") {
+        config.chain_spec = Some(path.to_string());
+    }
+    if args.has_flag("rpc-tls-self-signed") {
+        config
+            .rpc_tls
+            .get_or_insert_with(Default::default)
+            .self_signed = true;
+    }
+    config.startup_verification = args.optional("verify-blocks", config.startup_verification)?;
+    config.proposer_safety.min_peers =
+        args.optional("min-peers", config.proposer_safety.min_peers)?;
+    config.proposer_safety.max_head_lag_slots =
+        args.optional("max-head-lag", config.proposer_safety.max_head_lag_slots)?;
+    config.max_gas_price_multiplier =
+        args.optional("max-gas-price-multiplier", config.max_gas_price_multiplier)?;
+
+    Ok(config)
+}
+
+// start a node (or several chains) and run until shutdown
+async fn run_node(args: &Args) -> Result<()> {
+    if let Some(path) = args.value("chains") {
+        let node = MultiChainNode::new(MultiChainConfig::load(path)?).await?;
+        return node.run().await;
+    }
+
+    let node = SpeedNode::new(node_config(args)?).await?;
+    node.run().await
+}
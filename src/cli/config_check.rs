@@ -0,0 +1,35 @@
+use anyhow::{Result, anyhow};
+
+use super::{Args, node_config};
+use crate::check_config;
+
+// `speed config check`: resolve and validate the config without starting the node
+pub fn config_check(args: &Args) -> Result<()> {
+    let config = node_config(args)?;
+    let report = check_config(&config);
+
+    println!("Effective configuration:");
+    println!("{}", serde_json::to_string_pretty(&config)?);
+
+    println!("Genesis validators ({}):", report.validators.len());
+    for (address, stake) in &report.validators {
+        println!("  {} stake {}", address, stake);
+    }
+
+    for warning in &report.warnings {
+        println!("⚠️  {}", warning);
+    }
+    for error in &report.errors {
+        println!("❌ {}", error);
+    }
+
+    if !report.is_valid() {
+        return Err(anyhow!(
+            "Configuration is invalid ({} errors)",
+            report.errors.len()
+        ));
+    }
+
+    println!("✅ Configuration is valid");
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::time::SystemTime;
+
+use super::{Args, node_config, rpc_client};
+use crate::core::{ChainInfo, DebugState};
+use crate::rpc::{admin::AdminRpcClient, client_version, rpc::SpeedBlockchainRpcClient};
+use crate::{NodeConfig, PeerInfo, unix_millis};
+
+// log lines kept from `--log-file` unless `--log-lines` says otherwise
+pub const DEBUG_LOG_LINES: usize = 500;
+
+// everything `speed dump-debug` collects, written as one json file
+// sections the node could not serve are left out and their error kept in `errors`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugBundle {
+    pub created_at_ms: u64,
+    // version of the cli that wrote the bundle, the node reports its own
+    pub cli_version: String,
+    pub node_version: Option<String>,
+    // validator key redacted
+    pub config: NodeConfig,
+    // head and finalized blocks, validator count, sync status
+    pub chain: Option<ChainInfo>,
+    // consensus engine state, mempool summary and recent consensus events
+    pub state: Option<DebugState>,
+    pub peers: Option<Vec<PeerInfo>>,
+    // last lines of the node's log file, oldest first
+    pub logs: Vec<String>,
+    // section -> why it is missing
+    pub errors: BTreeMap<String, String>,
+}
+
+// `speed dump-debug`: collect node state for a bug report, without private keys
+pub async fn dump_debug(args: &Args) -> Result<()> {
+    let config = node_config(args)?.redacted();
+    let events: Option<usize> = match args.value("events") {
+        Some(_) => Some(args.required("events")?),
+        None => None,
+    };
+    let log_lines: usize = args.optional("log-lines", DEBUG_LOG_LINES)?;
+    let created_at_ms = unix_millis(SystemTime::now());
+    let out: String = args.optional("out", format!("speed-debug-{}.json", created_at_ms / 1000))?;
+
+    let mut errors = BTreeMap::new();
+    let client = rpc_client(args)?;
+    let node_version = section(&mut errors, "node_version", client.client_version().await);
+    let chain = section(&mut errors, "chain", client.get_chain_info().await);
+    // the admin namespace is only served with `admin_rpc` enabled
+    let state = section(&mut errors, "state", client.debug_state(events).await);
+    let peers = section(&mut errors, "peers", client.peers().await);
+
+    let logs = match args.value("log-file") {
+        Some(path) => section(&mut errors, "logs", tail_lines(path, log_lines)).unwrap_or_default(),
+        None => Vec::new(),
+    };
+
+    let bundle = DebugBundle {
+        created_at_ms,
+        cli_version: client_version(),
+        node_version,
+        config,
+        chain,
+        state,
+        peers,
+        logs,
+        errors,
+    };
+    fs::write(&out, serde_json::to_vec_pretty(&bundle)?)
+        .with_context(|| format!("Failed to write {}", out))?;
+
+    println!("📦 Debug bundle written to {}", out);
+    for (section, error) in &bundle.errors {
+        println!("⚠️  Missing {}: {}", section, error);
+    }
+    Ok(())
+}
+
+// the last `count` lines of a file, oldest first
+pub fn tail_lines<P: AsRef<Path>>(path: P, count: usize) -> Result<Vec<String>> {
+    let path = path.as_ref();
+    let file =
+        File::open(path).with_context(|| format!("Failed to open log file {}", path.display()))?;
+
+    let mut lines = VecDeque::with_capacity(count);
+    for line in BufReader::new(file).lines() {
+        let line = line.with_context(|| format!("Failed to read log file {}", path.display()))?;
+        if count == 0 {
+            continue;
+        }
+        if lines.len() == count {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+    Ok(lines.into())
+}
+
+// keep a section's value, or why it could not be collected
+fn section<T, E: std::fmt::Display>(
+    errors: &mut BTreeMap<String, String>,
+    name: &str,
+    result: std::result::Result<T, E>,
+) -> Option<T> {
+    result
+        .map_err(|e| errors.insert(name.to_string(), e.to_string()))
+        .ok()
+}
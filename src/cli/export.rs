@@ -0,0 +1,33 @@
+use anyhow::{Result, anyhow};
+
+use super::{Args, node_config};
+use crate::Storage;
+use crate::export::NdjsonExporter;
+
+// `speed export`: dump the stored chain for analytics, the node must be stopped
+pub fn export_chain(args: &Args) -> Result<()> {
+    let format = args.value("format").unwrap_or("ndjson");
+    if format != "ndjson" {
+        return Err(anyhow!("Unsupported export format: {}", format));
+    }
+
+    let out: String = args.required("out")?;
+    let data_dir: String = args.optional("data-dir", node_config(args)?.data_dir)?;
+    let from: u64 = args.optional("from", 0)?;
+    let to: Option<u64> = match args.value("to") {
+        Some(_) => Some(args.required("to")?),
+        None => None,
+    };
+
+    let exporter = NdjsonExporter::new(Storage::new(&data_dir)?);
+    let summary = exporter.export(&out, from, to, args.has_flag("resume"))?;
+
+    match (summary.first_block, summary.last_block) {
+        (Some(first), Some(last)) => println!(
+            "✅ Exported blocks #{}..=#{} ({} blocks, {} records) to {}",
+            first, last, summary.blocks, summary.records, out
+        ),
+        _ => println!("✅ Nothing to export, {} is up to date", out),
+    }
+    Ok(())
+}
@@ -0,0 +1,21 @@
+pub mod admin;
+pub mod args;
+pub mod audit;
+pub mod cli;
+pub mod config_check;
+pub mod debug_dump;
+pub mod export;
+pub mod reindex;
+pub mod selftest;
+pub mod tx;
+
+pub use admin::*;
+pub use args::*;
+pub use audit::*;
+pub use cli::*;
+pub use config_check::*;
+pub use debug_dump::*;
+pub use export::*;
+pub use reindex::*;
+pub use selftest::*;
+pub use tx::*;
@@ -0,0 +1,35 @@
+use anyhow::Result;
+
+use super::{Args, node_config};
+use crate::Storage;
+use crate::reindex::{ReindexOptions, Reindexer};
+
+// `speed reindex`: backfill receipts and indexes of an existing database, the node must be stopped
+pub async fn reindex(args: &Args) -> Result<()> {
+    let config = node_config(args)?;
+    let data_dir: String = args.optional("data-dir", config.data_dir)?;
+    let options = ReindexOptions {
+        dev: config.dev,
+        restart: args.has_flag("restart"),
+        block_rewards: config.block_rewards,
+        consensus: config.consensus,
+    };
+
+    let reindexer = Reindexer::new(Storage::new(&data_dir)?);
+    let summary = reindexer.run(&options).await?;
+
+    println!(
+        "✅ Reindexed {} blocks ({} already indexed), backfilled {} receipts and {} state diffs",
+        summary.blocks,
+        summary.already_indexed,
+        summary.receipts_backfilled,
+        summary.state_diffs_backfilled
+    );
+    if summary.state_root_mismatches > 0 {
+        println!(
+            "⚠️  {} blocks replayed to a different state root, was the chain run with --dev?",
+            summary.state_root_mismatches
+        );
+    }
+    Ok(())
+}
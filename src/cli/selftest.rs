@@ -0,0 +1,286 @@
+use alloy::primitives::{B256, Bytes, U256};
+use alloy_signer::Signature;
+use anyhow::{Result, anyhow};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{
+    BlockProcessResult, Blockchain, CHAIN_ID, GasCalculator, GasConfig, KeyPair, Transaction,
+    TransactionKind, TransactionSource,
+};
+
+const SELFTEST_SLOT_DURATION: u64 = 1;
+const SELFTEST_MIN_STAKE: u64 = 100;
+const TO_GWEI: u64 = 1_000_000_000;
+const TO_ETH: u128 = 1_000_000_000_000_000_000;
+
+// outcome of a single self-test step
+struct Check {
+    name: &'static str,
+    result: Result<String>,
+}
+
+// `speed selftest`: run the whole local pipeline on throwaway chains and report
+pub async fn selftest() -> Result<()> {
+    let dir = selftest_dir();
+    println!("🧪 Running self-test in {}", dir.display());
+
+    let checks = run_checks(&dir).await;
+    let _ = std::fs::remove_dir_all(&dir);
+
+    println!();
+    println!("Self-test report:");
+    let mut failed = 0;
+    for check in &checks {
+        match &check.result {
+            Ok(detail) => println!("  ✅ PASS {:<28} {}", check.name, detail),
+            Err(e) => {
+                failed += 1;
+                println!("  ❌ FAIL {:<28} {}", check.name, e)
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(anyhow!(
+            "Self-test failed: {} of {} checks",
+            failed,
+            checks.len()
+        ));
+    }
+    println!("✅ Self-test passed ({} checks)", checks.len());
+    Ok(())
+}
+
+// unique scratch directory for the throwaway databases
+fn selftest_dir() -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    std::env::temp_dir().join(format!("speed-selftest-{}-{}", std::process::id(), nanos))
+}
+
+// each step records its result, later steps stop once a step they depend on failed
+async fn run_checks(dir: &Path) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    // 1. keys
+    let validator = KeyPair::generate("selftest-validator".into());
+    let alice = KeyPair::generate("selftest-alice".into());
+    let bob = KeyPair::generate("selftest-bob".into());
+    checks.push(Check {
+        name: "generate keys",
+        result: Ok(format!("validator {}", validator.address)),
+    });
+
+    // 2. a proposing node and a following node sharing the same genesis
+    let chains = (|| -> Result<(Blockchain, Blockchain)> {
+        let validators = vec![(validator.address, SELFTEST_MIN_STAKE)];
+        let proposer = Blockchain::new(
+            &dir.join("proposer").to_string_lossy(),
+            SELFTEST_MIN_STAKE,
+            SELFTEST_SLOT_DURATION,
+            validators.clone(),
+            Some(validator.clone()),
+        )?;
+        let follower = Blockchain::new(
+            &dir.join("follower").to_string_lossy(),
+            SELFTEST_MIN_STAKE,
+            SELFTEST_SLOT_DURATION,
+            validators,
+            None,
+        )?;
+        Ok((proposer, follower))
+    })();
+    let (proposer, follower) = match chains {
+        Ok(chains) => chains,
+        Err(e) => {
+            checks.push(Check {
+                name: "start ephemeral nodes",
+                result: Err(e),
+            });
+            return checks;
+        }
+    };
+    checks.push(Check {
+        name: "start ephemeral nodes",
+        result: Ok("proposer + follower".to_string()),
+    });
+
+    let funding = U256::from(10 * TO_ETH);
+    for chain in [&proposer, &follower] {
+        let mut state = chain.execution_engine.state_manager.lock().await;
+        state.fund_account(&alice.address, funding);
+    }
+
+    // 3. submit signed transactions
+    let amount = U256::from(TO_ETH);
+    let mut submitted = 0;
+    let mut submit_error = None;
+    for nonce in 0..2 {
+        match signed_transfer(&alice, &bob, amount, nonce).await {
+            Ok(tx) => match proposer
+                .add_transaction_to_mempool(&tx, TransactionSource::Local)
+                .await
+            {
+                Ok(_) => submitted += 1,
+                Err(e) => submit_error = Some(e),
+            },
+            Err(e) => submit_error = Some(e),
+        }
+    }
+    let submit_result = match submit_error {
+        Some(e) => Err(e),
+        None => Ok(format!("{} transactions in mempool", submitted)),
+    };
+    let submit_ok = submit_result.is_ok();
+    checks.push(Check {
+        name: "submit transactions",
+        result: submit_result,
+    });
+    if !submit_ok {
+        return checks;
+    }
+
+    // 4. produce a block, blocks are only built once the genesis slot has passed
+    tokio::time::sleep(Duration::from_secs(SELFTEST_SLOT_DURATION) + Duration::from_millis(100))
+        .await;
+    let block = match proposer.produce_block().await {
+        Ok(block) if block.transactions.len() == submitted => block,
+        Ok(block) => {
+            checks.push(Check {
+                name: "produce block",
+                result: Err(anyhow!(
+                    "block has {} of {} transactions",
+                    block.transactions.len(),
+                    submitted
+                )),
+            });
+            return checks;
+        }
+        Err(e) => {
+            checks.push(Check {
+                name: "produce block",
+                result: Err(e),
+            });
+            return checks;
+        }
+    };
+    checks.push(Check {
+        name: "produce block",
+        result: Ok(format!(
+            "block #{} with {} transactions",
+            block.header.index,
+            block.transactions.len()
+        )),
+    });
+
+    // 5. balances after execution
+    let gas_cost: U256 = block
+        .transactions
+        .iter()
+        .map(|tx| GasCalculator::calculate_instrinsic_gas(tx, &GasConfig::default()) * tx.gas_price)
+        .sum();
+    let expected_alice = funding - amount * U256::from(submitted) - gas_cost;
+    let expected_bob = amount * U256::from(submitted);
+    let alice_balance = proposer.execution_engine.get_balance(&alice.address).await;
+    let bob_balance = proposer.execution_engine.get_balance(&bob.address).await;
+    checks.push(Check {
+        name: "apply transfers",
+        result: if alice_balance == expected_alice && bob_balance == expected_bob {
+            Ok(format!("bob received {}", bob_balance))
+        } else {
+            Err(anyhow!(
+                "alice {} (expected {}), bob {} (expected {})",
+                alice_balance,
+                expected_alice,
+                bob_balance,
+                expected_bob
+            ))
+        },
+    });
+
+    // 6. the follower validates and imports the block
+    let import_result = match block.header.validator_signature {
+        Some(signature) => {
+            follower
+                .process_received_block(block.clone(), validator.address, signature)
+                .await
+        }
+        None => Err(anyhow!("produced block is not signed")),
+    };
+    let imported = matches!(import_result, Ok(BlockProcessResult::Accepted(_)));
+    checks.push(Check {
+        name: "validate block on follower",
+        result: match import_result {
+            Ok(BlockProcessResult::Accepted(hash)) => {
+                Ok(format!("accepted 0x{}", hex::encode(hash)))
+            }
+            Ok(BlockProcessResult::Rejected(_, reason)) => Err(anyhow!("rejected: {}", reason)),
+            Ok(BlockProcessResult::Forked(hash)) => Err(anyhow!("kept on a side branch: {}", hash)),
+            Ok(BlockProcessResult::Queued(_, reason)) => Err(anyhow!("held: {}", reason)),
+            Err(e) => Err(e),
+        },
+    });
+    if !imported {
+        return checks;
+    }
+
+    // 7. both nodes must agree on the resulting state
+    let proposer_root = proposer
+        .execution_engine
+        .state_manager
+        .lock()
+        .await
+        .get_state_root();
+    let follower_root = follower
+        .execution_engine
+        .state_manager
+        .lock()
+        .await
+        .get_state_root();
+    checks.push(Check {
+        name: "verify state roots",
+        result: if proposer_root == follower_root && proposer_root == block.header.state_root {
+            Ok(format!("0x{}", hex::encode(proposer_root)))
+        } else {
+            Err(anyhow!(
+                "block 0x{}, proposer 0x{}, follower 0x{}",
+                hex::encode(block.header.state_root),
+                hex::encode(proposer_root),
+                hex::encode(follower_root)
+            ))
+        },
+    });
+
+    checks
+}
+
+// signed transfer from `from` to `to`
+async fn signed_transfer(
+    from: &KeyPair,
+    to: &KeyPair,
+    amount: U256,
+    nonce: u64,
+) -> Result<Transaction> {
+    let mut tx = Transaction {
+        from: from.address,
+        to: to.address,
+        amount,
+        timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        nonce,
+        chain_id: CHAIN_ID,
+        kind: TransactionKind::Transfer,
+        gas_limit: U256::ZERO,
+        gas_price: U256::from(TO_GWEI),
+        max_priority_fee_per_gas: None,
+        memo: Bytes::new(),
+        data: Bytes::new(),
+        signature: Signature::new(U256::ZERO, U256::ZERO, false),
+        hash: B256::ZERO,
+    };
+    tx.gas_limit = GasCalculator::calculate_instrinsic_gas(&tx, &GasConfig::default());
+    tx.sign(from).await?;
+    Ok(tx)
+}
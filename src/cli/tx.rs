@@ -0,0 +1,336 @@
+use alloy::primitives::{Address, B256, Bytes, U256};
+use alloy_signer::Signature;
+use anyhow::{Result, anyhow};
+use jsonrpsee::http_client::{HeaderMap, HeaderValue, HttpClient, HttpClientBuilder};
+
+use super::Args;
+use crate::rpc::{DEFAULT_MAX_GAS_PRICE_MULTIPLIER, FeeProtection, rpc::SpeedBlockchainRpcClient};
+use crate::{
+    COMMITMENTS_PRECOMPILE, GasCalculator, GasConfig, KeyPair, MAX_MEMO_LENGTH, RPC_PORT,
+    StateTransition, Transaction, TransactionKind, TransferOutput, memo_commitment,
+    precompile_address,
+};
+
+// `speed tx send`: build, sign and submit a transfer with fee protection
+pub async fn send_transaction(args: &Args) -> Result<()> {
+    let to = recipient(args, "to").await?;
+    let amount: U256 = args.required("amount")?;
+
+    submit(args, to, amount, TransactionKind::Transfer).await
+}
+
+// `speed tx register-address`: announce this validator's p2p multiaddr on-chain
+pub async fn register_network_address(args: &Args) -> Result<()> {
+    let multiaddr: String = args.required("multiaddr")?;
+    let kind = TransactionKind::RegisterNetworkAddress { multiaddr };
+
+    submit(args, Address::ZERO, U256::ZERO, kind).await
+}
+
+// `speed tx approve`: allow a spender to move up to `--allowance` of the sender's tokens
+pub async fn approve(args: &Args) -> Result<()> {
+    let spender: Address = args.required("spender")?;
+    let allowance: U256 = args.required("allowance")?;
+    let kind = TransactionKind::Approve { spender, allowance };
+
+    submit(args, spender, U256::ZERO, kind).await
+}
+
+// `speed tx authorize-session`: let `--session-key` sign transfers and calls for the sender,
+// spending at most `--max-value` in value and fees until `--expiry-slot`, zero revokes it
+pub async fn authorize_session(args: &Args) -> Result<()> {
+    let session_key: Address = args.required("session-key")?;
+    let max_value: U256 = args.required("max-value")?;
+    let expiry_slot: u64 = args.optional("expiry-slot", u64::MAX)?;
+    let kind = TransactionKind::AuthorizeSessionKey {
+        session_key,
+        max_value,
+        expiry_slot,
+    };
+
+    submit(args, session_key, U256::ZERO, kind).await
+}
+
+// `speed tx transfer-from`: move an owner's tokens using a previous approval
+pub async fn transfer_from(args: &Args) -> Result<()> {
+    let owner: Address = args.required("owner")?;
+    let to = recipient(args, "to").await?;
+    let amount: U256 = args.required("amount")?;
+
+    submit(args, to, amount, TransactionKind::TransferFrom { owner }).await
+}
+
+// `speed tx register-name`: claim a name that resolves to the sender
+pub async fn register_name(args: &Args) -> Result<()> {
+    let name: String = args.required("name")?;
+    StateTransition::validate_name(&name).map_err(|e| anyhow!("{}", e))?;
+    let kind = TransactionKind::RegisterName { name };
+
+    submit(args, Address::ZERO, U256::ZERO, kind).await
+}
+
+// `speed tx transfer-name`: hand a name the sender owns to `--to`
+pub async fn transfer_name(args: &Args) -> Result<()> {
+    let name: String = args.required("name")?;
+    let to = recipient(args, "to").await?;
+    let kind = TransactionKind::TransferName { name };
+
+    submit(args, to, U256::ZERO, kind).await
+}
+
+// `speed tx call`: run the precompile at `--to` with `--input`, its output lands in the receipt
+pub async fn call_precompile(args: &Args) -> Result<()> {
+    let to: Address = args.required("to")?;
+    let input: Bytes = args.optional("input", Bytes::new())?;
+
+    submit(args, to, U256::ZERO, TransactionKind::Call { input }).await
+}
+
+// `speed tx deploy`: deploy the wasm contract in `--code`, binary or text, with `--amount`
+pub async fn deploy_contract(args: &Args) -> Result<()> {
+    let path: String = args.required("code")?;
+    let code = std::fs::read(&path).map_err(|e| anyhow!("Failed to read {}: {}", path, e))?;
+    let amount: U256 = args.optional("amount", U256::ZERO)?;
+    let kind = TransactionKind::DeployContract { code: code.into() };
+
+    submit(args, Address::ZERO, amount, kind).await
+}
+
+// `speed tx call-contract`: run the contract at `--to` with `--input`, sending it `--amount`
+// gas above the intrinsic gas is the contract's fuel, so pass a `--gas-limit` that leaves some
+pub async fn call_contract(args: &Args) -> Result<()> {
+    let to: Address = args.required("to")?;
+    let input: Bytes = args.optional("input", Bytes::new())?;
+    let amount: U256 = args.optional("amount", U256::ZERO)?;
+
+    submit(args, to, amount, TransactionKind::CallContract { input }).await
+}
+
+// `speed tx commit`: put only a hash of `--payload` on-chain, the salt is needed to reveal it
+pub async fn commit_memo(args: &Args) -> Result<()> {
+    let keypair = KeyPair::generate(args.required("key")?);
+    let payload = parse_memo(&args.required::<String>("payload")?)?;
+    let salt: B256 = args.optional("salt", B256::from(rand::random::<[u8; 32]>()))?;
+
+    let commitment = memo_commitment(&keypair.address, &salt, &payload);
+    println!("🔒 Commitment: {}", commitment);
+    println!("🧂 Salt (keep it to reveal): {}", salt);
+
+    let mut input = vec![0u8];
+    input.extend_from_slice(commitment.as_slice());
+    let kind = TransactionKind::Call {
+        input: Bytes::from(input),
+    };
+    submit(
+        args,
+        precompile_address(COMMITMENTS_PRECOMPILE),
+        U256::ZERO,
+        kind,
+    )
+    .await
+}
+
+// `speed tx reveal`: publish the `--payload` and `--salt` of an earlier commitment
+pub async fn reveal_memo(args: &Args) -> Result<()> {
+    let payload = parse_memo(&args.required::<String>("payload")?)?;
+    let salt: B256 = args.required("salt")?;
+
+    let mut input = vec![1u8];
+    input.extend_from_slice(salt.as_slice());
+    input.extend_from_slice(&payload);
+    let kind = TransactionKind::Call {
+        input: Bytes::from(input),
+    };
+    submit(
+        args,
+        precompile_address(COMMITMENTS_PRECOMPILE),
+        U256::ZERO,
+        kind,
+    )
+    .await
+}
+
+// `--<option>` as an address, or a registered name resolved by the node
+pub async fn recipient(args: &Args, option: &str) -> Result<Address> {
+    let raw: String = args.required(option)?;
+    if let Ok(address) = raw.parse::<Address>() {
+        return Ok(address);
+    }
+    if StateTransition::validate_name(&raw).is_err() {
+        return Err(anyhow!(
+            "Invalid value for --{}: not an address or name",
+            option
+        ));
+    }
+
+    let address = rpc_client(args)?
+        .resolve_name(raw.clone())
+        .await?
+        .ok_or_else(|| anyhow!("Name '{}' is not registered", raw))?;
+    println!("🏷️  {} resolves to {}", raw, address);
+    Ok(address)
+}
+
+// `speed tx batch-transfer`: pay several recipients in one transaction, all or nothing
+pub async fn batch_transfer(args: &Args) -> Result<()> {
+    let raw: String = args.required("outputs")?;
+    let outputs = parse_outputs(&raw)?;
+    let kind = TransactionKind::BatchTransfer { outputs };
+
+    submit(args, Address::ZERO, U256::ZERO, kind).await
+}
+
+// `0xabc..:100,0xdef..:200` into transfer outputs
+pub fn parse_outputs(raw: &str) -> Result<Vec<TransferOutput>> {
+    raw.split(',')
+        .map(|pair| {
+            let (to, amount) = pair
+                .trim()
+                .split_once(':')
+                .ok_or_else(|| anyhow!("Invalid output '{}', expected <address>:<wei>", pair))?;
+            Ok(TransferOutput {
+                to: to.parse()?,
+                amount: amount.parse()?,
+            })
+        })
+        .collect()
+}
+
+// `speed tx stake-top-up`: lock more stake, effective from the next epoch
+pub async fn stake_top_up(args: &Args) -> Result<()> {
+    let amount: U256 = args.required("amount")?;
+
+    submit(args, Address::ZERO, amount, TransactionKind::StakeTopUp).await
+}
+
+// `speed tx register-validator`: stake and join the validator set from the next epoch
+pub async fn register_validator(args: &Args) -> Result<()> {
+    let amount: U256 = args.required("amount")?;
+
+    submit(
+        args,
+        Address::ZERO,
+        amount,
+        TransactionKind::RegisterValidator,
+    )
+    .await
+}
+
+// `speed tx unstake`: leave the validator set, the stake is paid back after the exit delay
+pub async fn unstake(args: &Args) -> Result<()> {
+    submit(args, Address::ZERO, U256::ZERO, TransactionKind::Unstake).await
+}
+
+// `speed tx stake-withdraw`: release part of the stake at the end of the epoch
+pub async fn stake_withdraw(args: &Args) -> Result<()> {
+    let amount: U256 = args.required("amount")?;
+    let kind = TransactionKind::StakeWithdraw { amount };
+
+    submit(args, Address::ZERO, U256::ZERO, kind).await
+}
+
+// `--memo` as text, or raw bytes when 0x prefixed
+pub fn parse_memo(raw: &str) -> Result<Bytes> {
+    let memo = match raw.strip_prefix("0x") {
+        Some(hex) => Bytes::from(hex::decode(hex)?),
+        None => Bytes::copy_from_slice(raw.as_bytes()),
+    };
+    if memo.len() > MAX_MEMO_LENGTH {
+        return Err(anyhow!(
+            "Memo is {} bytes, max {}",
+            memo.len(),
+            MAX_MEMO_LENGTH
+        ));
+    }
+    Ok(memo)
+}
+
+// build rpc client from `--rpc`, defaults to the local node
+// `--rpc-token` is sent as a bearer token to nodes that require one
+pub fn rpc_client(args: &Args) -> Result<HttpClient> {
+    let default_rpc = format!("http://127.0.0.1:{}", RPC_PORT);
+    let rpc_url = args.value("rpc").unwrap_or(&default_rpc);
+
+    let mut headers = HeaderMap::new();
+    if let Some(token) = args.value("rpc-token") {
+        headers.insert(
+            "authorization",
+            HeaderValue::from_str(&format!("Bearer {}", token))?,
+        );
+    }
+    Ok(HttpClientBuilder::default()
+        .set_headers(headers)
+        .build(rpc_url)?)
+}
+
+// sign a transaction with the `--key` account and submit it to the node
+async fn submit(args: &Args, to: Address, amount: U256, kind: TransactionKind) -> Result<()> {
+    let force = args.has_flag("force");
+
+    let key_name: String = args.required("key")?;
+    let keypair = KeyPair::generate(key_name);
+    // `--key` is a session key of `--from`
+    let from: Address = args.optional("from", keypair.address)?;
+
+    let client = rpc_client(args)?;
+
+    // fetch current chain values for the sender
+    let estimate = client.gas_price().await?;
+    let balance = client.get_balance(from).await?;
+    let nonce = client.get_transaction_count(from).await?;
+    let chain_id = client.get_chain_info().await?.chain_id;
+    let gas_price: U256 = args.optional("gas-price", estimate)?;
+    // caps the tip above the base fee, `--gas-price` is then the max fee per gas
+    let max_priority_fee_per_gas = match args.value("priority-fee") {
+        Some(_) => Some(args.required("priority-fee")?),
+        None => None,
+    };
+    let memo = match args.value("memo") {
+        Some(raw) => parse_memo(raw)?,
+        None => Bytes::new(),
+    };
+    let data: Bytes = args.optional("data", Bytes::new())?;
+
+    let mut tx = Transaction {
+        from,
+        to,
+        amount,
+        timestamp: chrono::Utc::now().timestamp() as u64,
+        nonce,
+        chain_id,
+        kind,
+        memo,
+        data,
+        gas_limit: U256::ZERO,
+        gas_price,
+        max_priority_fee_per_gas,
+        signature: Signature::new(U256::ZERO, U256::ZERO, false),
+        hash: B256::ZERO,
+    };
+    // the fee depends on the encoded size, which doesn't change with the gas limit value
+    let intrinsic_gas = GasCalculator::calculate_instrinsic_gas(&tx, &GasConfig::default());
+    tx.gas_limit = args.optional("gas-limit", intrinsic_gas)?;
+
+    // client side protection, reject before anything leaves the wallet
+    let multiplier = args.optional("max-gas-price-multiplier", DEFAULT_MAX_GAS_PRICE_MULTIPLIER)?;
+    let violations = FeeProtection::new(multiplier).check(&tx, estimate, balance);
+    for violation in &violations {
+        println!("⚠️  {}", violation);
+    }
+    if !violations.is_empty() && !force {
+        return Err(anyhow!(
+            "Transaction not sent, re-run with --force to override fee protection"
+        ));
+    }
+
+    tx.sign(&keypair).await?;
+
+    // pass the same `--request-id` when retrying, the node returns the first hash
+    let request_id = args.value("request-id").map(|id| id.to_string());
+    let tx_hash = client
+        .send_raw_transaction(tx.encode_raw(), Some(force), request_id)
+        .await?;
+
+    println!("✅ Transaction sent: {}", tx_hash);
+    Ok(())
+}
@@ -2,3 +2,9 @@
 pub const DB_PATH: &str = "blockchain_db";
 pub const MIN_STAKE: u64 = 100;
 pub const SLOT_DURATION: u64 = 10; // 10 secs
+pub const P2P_PORT: u16 = 4001;
+pub const RPC_PORT: u16 = 8545;
+pub const CHAIN_ID: u64 = 1;
+pub const VALIDATORS_FILE: &str = "validators.json";
+pub const STATE_WARMUP_BLOCKS: u64 = 128; // blocks replayed into state on startup
+pub const SLOTS_PER_EPOCH: u64 = 32; // slots grouped into one accounting epoch
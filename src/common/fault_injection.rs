@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+// faults injected during soak tests, only active with the `fault-injection` feature
+// rates are probabilities between 0.0 and 1.0
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FaultConfig {
+    // delay storage writes to simulate a slow disk
+    pub storage_write_delay_ms: u64,
+    pub storage_write_delay_rate: f64,
+    // drop incoming gossip messages
+    pub gossip_drop_rate: f64,
+    // hold the state lock longer than needed
+    pub state_lock_stall_ms: u64,
+    pub state_lock_stall_rate: f64,
+}
+
+static FAULTS: OnceLock<FaultConfig> = OnceLock::new();
+
+// install process wide faults, only the first call takes effect
+pub fn install_faults(config: FaultConfig) {
+    if cfg!(feature = "fault-injection") {
+        println!("💥 Fault injection enabled: {:?}", config);
+        let _ = FAULTS.set(config);
+    } else {
+        println!("⚠️  Fault config ignored, build with --features fault-injection");
+    }
+}
+
+// randomly block the current thread before a storage write
+pub fn inject_storage_write_delay() {
+    #[cfg(feature = "fault-injection")]
+    if let Some(faults) = FAULTS.get()
+        && roll(faults.storage_write_delay_rate)
+    {
+        std::thread::sleep(std::time::Duration::from_millis(
+            faults.storage_write_delay_ms,
+        ));
+    }
+}
+
+// whether an incoming gossip message should be dropped
+pub fn inject_gossip_drop() -> bool {
+    #[cfg(feature = "fault-injection")]
+    if let Some(faults) = FAULTS.get() {
+        return roll(faults.gossip_drop_rate);
+    }
+
+    false
+}
+
+// randomly stall while holding the state lock
+pub async fn inject_state_lock_stall() {
+    #[cfg(feature = "fault-injection")]
+    if let Some(faults) = FAULTS.get()
+        && roll(faults.state_lock_stall_rate)
+    {
+        tokio::time::sleep(std::time::Duration::from_millis(faults.state_lock_stall_ms)).await;
+    }
+}
+
+#[cfg(feature = "fault-injection")]
+fn roll(rate: f64) -> bool {
+    use rand::Rng;
+    rate > 0.0 && rand::thread_rng().gen_bool(rate.min(1.0))
+}
@@ -0,0 +1,36 @@
+use anyhow::{Result, anyhow};
+use std::sync::OnceLock;
+use tracing_subscriber::{Registry, filter::LevelFilter, fmt, prelude::*, reload};
+
+// handle to change the tracing level of a running node
+static LOG_LEVEL: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+
+// install the tracing subscriber used by libp2p and jsonrpsee internals
+// off by default so node output is unchanged, operators raise it with admin_setLogLevel
+pub fn init_logging() {
+    LOG_LEVEL.get_or_init(|| {
+        let (filter, handle) = reload::Layer::new(LevelFilter::OFF);
+        let _ = tracing_subscriber::registry()
+            .with(filter)
+            .with(fmt::layer())
+            .try_init();
+        handle
+    });
+}
+
+// change the tracing level, `off`, `error`, `warn`, `info`, `debug` or `trace`
+pub fn set_log_level(level: &str) -> Result<LevelFilter> {
+    let level: LevelFilter = level.parse().map_err(|_| {
+        anyhow!(
+            "Invalid log level {}, expected off, error, warn, info, debug or trace",
+            level
+        )
+    })?;
+
+    let handle = LOG_LEVEL
+        .get()
+        .ok_or_else(|| anyhow!("Logging is not initialized"))?;
+    handle.modify(|filter| *filter = level)?;
+
+    Ok(level)
+}
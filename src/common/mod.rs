@@ -1,5 +1,9 @@
 pub mod constants;
+pub mod fault_injection;
+pub mod logging;
 pub mod types;
 
 pub use constants::*;
+pub use fault_injection::*;
+pub use logging::*;
 pub use types::*;
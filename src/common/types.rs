@@ -9,6 +9,10 @@ use crate::{Block, Transaction};
 pub enum BlockProcessResult {
     Accepted(B256),
     Rejected(B256, String),
+    // kept on a side branch that isn't longer than ours, the head didn't change
+    Forked(B256),
+    // held until its slot starts or its parent is imported, then replayed
+    Queued(B256, String),
 }
 
 // Validation result
@@ -18,7 +22,9 @@ pub enum ValidationResult {
     Invalid(String),
 }
 
-#[derive(Debug, Clone)]
+// duty of a validator in one slot, proposer when the schedule picked it and attestor otherwise
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ValidatorRole {
     Proposer,
     Attestor,
@@ -42,9 +48,13 @@ pub enum AttestationVote {
 #[derive(Debug, Clone)]
 pub enum NetworkMessage {
     NewBlock {
-        block: Block,
+        // boxed like the transaction, the small messages stay small
+        block: Box<Block>,
         proposer_id: Address,
         signature: Signature,
+        // libp2p peer id that relayed the block, and when it arrived (unix ms)
+        from_peer: String,
+        received_at_ms: u64,
     },
     Attestation {
         block_hash: B256,
@@ -53,8 +63,13 @@ pub enum NetworkMessage {
         signature: Signature,
     },
     NewTransaction {
-        transaction: Transaction,
-        from_peer: Address,
+        transaction: Box<Transaction>,
+        // libp2p peer id that relayed the transaction
+        from_peer: String,
+    },
+    // local only: number of live peer connections, sent whenever it may have changed
+    PeerCount {
+        connected: usize,
     },
 }
 
@@ -75,4 +90,19 @@ pub enum BlockchainMessage {
     NewTransaction {
         transaction: Transaction,
     },
+    // local only: every validator multiaddr the network layer should keep dialing, replaces
+    // the previous set, never gossiped
+    ValidatorAddresses {
+        addresses: Vec<String>,
+    },
+    // local only: slot of our best block, bounds how old gossiped blocks may be
+    HeadSlot {
+        slot: u64,
+    },
+    // local only: publish our block again as NewBlock, peers that already have it drop the copy
+    RebroadcastBlock {
+        block: Block,
+        proposer: Address,
+        signature: Signature,
+    },
 }
@@ -0,0 +1,235 @@
+use alloy::primitives::{Address, B256, U256};
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, UNIX_EPOCH};
+use toml_edit::{DocumentMut, Item, Table};
+
+use crate::consensus::ConsensusGenesis;
+use crate::core::BlockHeader;
+use crate::{
+    Account, Block, CHAIN_ID, ContractRuntimeKind, GasConfig, MIN_STAKE, SLOT_DURATION,
+    StateManager,
+};
+
+// what every node of a chain starts from, loaded from a toml or json file
+// the genesis block, state and slot clock are derived from it, the same on every node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChainSpec {
+    pub chain_id: u64,
+    // unix seconds slot 0 starts at, also the genesis block timestamp
+    pub genesis_time: u64,
+    // seconds
+    pub slot_duration: u64,
+    pub min_stake: u64,
+    pub gas: GasConfig,
+    pub validators: Vec<GenesisValidator>,
+    // balances at genesis, in wei
+    pub alloc: BTreeMap<Address, U256>,
+    // engine contract transactions run on, "none" or "wasm"
+    pub contract_runtime: ContractRuntimeKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenesisValidator {
+    pub address: Address,
+    pub stake: u64,
+}
+
+impl Default for ChainSpec {
+    fn default() -> Self {
+        Self {
+            chain_id: CHAIN_ID,
+            genesis_time: 0,
+            slot_duration: SLOT_DURATION,
+            min_stake: MIN_STAKE,
+            gas: GasConfig::default(),
+            validators: Vec::new(),
+            alloc: BTreeMap::new(),
+            contract_runtime: ContractRuntimeKind::default(),
+        }
+    }
+}
+
+impl ChainSpec {
+    // load a chain spec from a `.toml` file, any other extension is read as json
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let data = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read chain spec {}", path.display()))?;
+
+        let spec = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml(&data),
+            _ => serde_json::from_str(&data).map_err(Into::into),
+        }
+        .with_context(|| format!("Failed to parse chain spec {}", path.display()))?;
+        spec.validate()?;
+
+        Ok(spec)
+    }
+
+    // toml is read into the same shape as the json spec, then deserialized from that
+    pub fn from_toml(data: &str) -> Result<Self> {
+        let document: DocumentMut = data.parse()?;
+        Ok(serde_json::from_value(toml_table(document.as_table()))?)
+    }
+
+    // a spec nodes could start from, but not necessarily agree on anything else
+    pub fn validate(&self) -> Result<()> {
+        if self.genesis_time == 0 {
+            return Err(anyhow!("Chain spec has no genesis_time"));
+        }
+        if self.slot_duration == 0 {
+            return Err(anyhow!(
+                "Chain spec slot_duration must be at least one second"
+            ));
+        }
+        if self.validators.is_empty() {
+            return Err(anyhow!("Chain spec has no validators"));
+        }
+
+        let mut addresses = HashSet::new();
+        for validator in &self.validators {
+            if !addresses.insert(validator.address) {
+                return Err(anyhow!(
+                    "Genesis validator {} is listed twice",
+                    validator.address
+                ));
+            }
+            if validator.stake < self.min_stake {
+                return Err(anyhow!(
+                    "Genesis validator {} stakes {}, below the minimum of {}",
+                    validator.address,
+                    validator.stake,
+                    self.min_stake
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // (address, stake) pairs of the genesis validator set
+    pub fn validators(&self) -> Vec<(Address, u64)> {
+        self.validators
+            .iter()
+            .map(|validator| (validator.address, validator.stake))
+            .collect()
+    }
+
+    // add `balances` to the genesis allocation, accounts the spec already funds are kept
+    pub fn with_dev_accounts(mut self, balances: &[(Address, U256)]) -> Self {
+        for (address, balance) in balances {
+            self.alloc.entry(*address).or_insert(*balance);
+        }
+        self
+    }
+
+    // account state before block #1
+    pub fn genesis_state(&self) -> StateManager {
+        let mut state = StateManager::new();
+        state.load_accounts(
+            self.alloc
+                .iter()
+                .map(|(address, balance)| Account {
+                    balance: *balance,
+                    nonce: 0,
+                    address: *address,
+                })
+                .collect(),
+        );
+        state
+    }
+
+    // block #0, the parent of block #1, committing to the genesis time and state
+    pub fn genesis_block(&self) -> Block {
+        let mut header = BlockHeader::new(
+            0,
+            0,
+            Address::ZERO,
+            B256::ZERO,
+            Block::calculate_transactions_root(&[]),
+            self.genesis_state().get_state_root(),
+        );
+        header.timestamp = self.genesis_time;
+        Block::new(header, Vec::new())
+    }
+
+    // slot clock from the genesis time, proposer seed from the genesis block
+    pub fn consensus_genesis(&self) -> ConsensusGenesis {
+        ConsensusGenesis::new(
+            UNIX_EPOCH + Duration::from_secs(self.genesis_time),
+            self.genesis_block().header.hash().0,
+        )
+    }
+
+    // what the spec adds to the chain spec hash on top of stake, slot duration and validators
+    pub fn chain_spec_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&self.chain_id.to_be_bytes());
+        data.extend_from_slice(self.genesis_block().header.hash().as_slice());
+        for value in [
+            self.gas.intrinsic_gas,
+            self.gas.gas_per_byte,
+            self.gas.min_gas_price,
+            self.gas.block_gas_limit,
+            self.gas.batch_output_gas,
+            self.gas.memo_byte_gas,
+            self.gas.name_byte_gas,
+        ] {
+            data.extend_from_slice(&value.to_be_bytes::<32>());
+        }
+        data.extend_from_slice(&(self.gas.max_tx_size as u64).to_be_bytes());
+        // chains without a base fee keep the hash they had before
+        if let Some(base_fee) = &self.gas.base_fee {
+            data.extend_from_slice(b"base_fee:");
+            data.extend_from_slice(&base_fee.initial_base_fee.to_be_bytes::<32>());
+            data.extend_from_slice(&base_fee.elasticity_multiplier.to_be_bytes());
+            data.extend_from_slice(&base_fee.max_change_denominator.to_be_bytes());
+        }
+        // chains without contracts keep the hash they had before
+        if self.contract_runtime != ContractRuntimeKind::None {
+            data.extend_from_slice(b"contracts:");
+            data.extend_from_slice(format!("{:?}", self.contract_runtime).as_bytes());
+        }
+        data
+    }
+}
+
+fn toml_table(table: &Table) -> Value {
+    Value::Object(
+        table
+            .iter()
+            .map(|(key, item)| (key.to_string(), toml_item(item)))
+            .collect(),
+    )
+}
+
+fn toml_item(item: &Item) -> Value {
+    match item {
+        Item::None => Value::Null,
+        Item::Value(value) => toml_value(value),
+        Item::Table(table) => toml_table(table),
+        Item::ArrayOfTables(tables) => Value::Array(tables.iter().map(toml_table).collect()),
+    }
+}
+
+fn toml_value(value: &toml_edit::Value) -> Value {
+    match value {
+        toml_edit::Value::String(s) => Value::String(s.value().clone()),
+        toml_edit::Value::Integer(i) => Value::from(*i.value()),
+        toml_edit::Value::Float(f) => Value::from(*f.value()),
+        toml_edit::Value::Boolean(b) => Value::Bool(*b.value()),
+        toml_edit::Value::Datetime(d) => Value::String(d.value().to_string()),
+        toml_edit::Value::Array(array) => Value::Array(array.iter().map(toml_value).collect()),
+        toml_edit::Value::InlineTable(table) => Value::Object(
+            table
+                .iter()
+                .map(|(key, value)| (key.to_string(), toml_value(value)))
+                .collect::<Map<_, _>>(),
+        ),
+    }
+}
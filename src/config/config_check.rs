@@ -0,0 +1,314 @@
+use alloy::primitives::Address;
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Duration;
+
+use super::NodeConfig;
+use crate::consensus::RandomnessBeacon;
+use crate::core::Checkpoint;
+use crate::{ChainSpec, GasConfig, MIN_STAKE, TxPolicyConfig};
+
+// time we assume a block needs to reach every validator
+pub const ASSUMED_BLOCK_PROPAGATION: Duration = Duration::from_secs(2);
+// validators needed to tolerate one faulty validator with a 2/3 quorum
+pub const MIN_FAULT_TOLERANT_VALIDATORS: usize = 4;
+
+// problems found in a config, errors prevent the node from starting
+#[derive(Debug, Clone, Default)]
+pub struct ConfigReport {
+    pub validators: Vec<(Address, u64)>,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl ConfigReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+// validate cross-field constraints of a resolved config, without starting anything
+pub fn check_config(config: &NodeConfig) -> ConfigReport {
+    let mut report = ConfigReport::default();
+
+    if config.port == config.rpc_port {
+        report
+            .errors
+            .push(format!("p2p port and rpc port are both {}", config.port));
+    }
+
+    // genesis validators and chain parameters, from the chain spec when there is one
+    let spec = match &config.chain_spec {
+        Some(path) => match ChainSpec::load(path) {
+            Ok(spec) => Some(spec),
+            Err(e) => {
+                report.errors.push(format!("{:#}", e));
+                None
+            }
+        },
+        None => {
+            match config.load_validators() {
+                Ok(validators) => report.validators = validators,
+                Err(e) => report.errors.push(format!("{:#}", e)),
+            }
+            None
+        }
+    };
+    if let Some(spec) = &spec {
+        report.validators = spec.validators();
+    }
+    let min_stake = spec.as_ref().map_or(MIN_STAKE, |spec| spec.min_stake);
+
+    // the checkpoint file must hold the trusted block, its state and this chain's genesis
+    if let Some(trusted) = &config.checkpoint {
+        match Checkpoint::load(Path::new(&trusted.path)) {
+            Ok(checkpoint) => {
+                if let Err(e) = checkpoint.verify(&trusted.block_hash) {
+                    report.errors.push(format!("checkpoint: {}", e));
+                }
+                if let Some(spec) = &spec
+                    && spec.consensus_genesis() != checkpoint.genesis
+                {
+                    report
+                        .errors
+                        .push("checkpoint is not from the chain in the chain spec".to_string());
+                }
+            }
+            Err(e) => report.errors.push(format!("{:#}", e)),
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for (address, _) in &report.validators {
+        if !seen.insert(*address) {
+            report
+                .errors
+                .push(format!("validator {} is listed more than once", address));
+        }
+    }
+
+    let eligible = report
+        .validators
+        .iter()
+        .filter(|(_, stake)| *stake >= min_stake)
+        .count();
+    if eligible < report.validators.len() {
+        report.warnings.push(format!(
+            "{} validators are below the minimum stake of {} and will be ignored",
+            report.validators.len() - eligible,
+            min_stake
+        ));
+    }
+
+    // quorum
+    if eligible == 0 {
+        report
+            .errors
+            .push("no validator meets the minimum stake, no block can be proposed".to_string());
+    } else if eligible < MIN_FAULT_TOLERANT_VALIDATORS {
+        report.warnings.push(format!(
+            "{} eligible validators cannot reach a 2/3 quorum with one validator down (need {})",
+            eligible, MIN_FAULT_TOLERANT_VALIDATORS
+        ));
+    }
+
+    let consensus = &config.consensus;
+    let quorum = consensus.quorum;
+    if quorum.denominator == 0 || quorum.numerator > quorum.denominator {
+        report.errors.push(format!(
+            "consensus.quorum {}/{} is not a share of the stake",
+            quorum.numerator, quorum.denominator
+        ));
+    } else if 2 * quorum.numerator <= quorum.denominator {
+        report.warnings.push(format!(
+            "consensus.quorum {}/{} is not above half the stake, two conflicting blocks can both be certified",
+            quorum.numerator, quorum.denominator
+        ));
+    }
+    if consensus.slots_per_epoch == 0 {
+        report
+            .errors
+            .push("consensus.slots_per_epoch must be at least 1".to_string());
+    }
+
+    let safety = &config.proposer_safety;
+    if eligible > 0 && safety.min_peers >= eligible {
+        report.warnings.push(format!(
+            "proposer_safety.min_peers is {} but there are only {} other validators",
+            safety.min_peers,
+            eligible - 1
+        ));
+    }
+
+    // gas limits
+    let gas = spec
+        .as_ref()
+        .map_or_else(GasConfig::default, |spec| spec.gas.clone());
+    if gas.block_gas_limit < gas.intrinsic_gas {
+        report.errors.push(format!(
+            "block gas limit {} is below the intrinsic gas {}, no transaction fits in a block",
+            gas.block_gas_limit, gas.intrinsic_gas
+        ));
+    }
+    if let Some(target) = consensus.block_gas_target
+        && target > gas.block_gas_limit
+    {
+        report.warnings.push(format!(
+            "consensus.block_gas_target {} is above the block gas limit {} and has no effect",
+            target, gas.block_gas_limit
+        ));
+    }
+
+    // slot duration vs network latency
+    let slot_duration = Duration::from_secs(
+        spec.as_ref()
+            .map_or(consensus.slot_duration, |spec| spec.slot_duration),
+    );
+    if slot_duration <= ASSUMED_BLOCK_PROPAGATION {
+        report.errors.push(format!(
+            "slot duration {:?} does not leave time for block propagation ({:?})",
+            slot_duration, ASSUMED_BLOCK_PROPAGATION
+        ));
+    }
+
+    if let Some(adaptive) = &config.adaptive_slots {
+        if adaptive.min_slot_ms == 0 || adaptive.min_slot_ms > adaptive.max_slot_ms {
+            report.errors.push(format!(
+                "adaptive_slots.min_slot_ms must be between 1 and max_slot_ms ({}), got {}",
+                adaptive.max_slot_ms, adaptive.min_slot_ms
+            ));
+        } else if Duration::from_millis(adaptive.min_slot_ms) <= ASSUMED_BLOCK_PROPAGATION {
+            report.warnings.push(format!(
+                "adaptive_slots.min_slot_ms {} does not leave time for block propagation ({:?}) if latency reports are too low",
+                adaptive.min_slot_ms, ASSUMED_BLOCK_PROPAGATION
+            ));
+        }
+        if adaptive.latency_multiple == 0 {
+            report
+                .errors
+                .push("adaptive_slots.latency_multiple must be at least 1".to_string());
+        }
+        report.warnings.push(
+            "adaptive_slots is experimental, every validator must use the same settings"
+                .to_string(),
+        );
+    }
+
+    if let Some(beacon) = &config.randomness_beacon {
+        if let Err(e) = RandomnessBeacon::new(beacon.clone()) {
+            report.errors.push(format!("randomness_beacon: {}", e));
+        }
+        if beacon.chain_hash.is_empty() {
+            report
+                .errors
+                .push("randomness_beacon.chain_hash is not set".to_string());
+        }
+        if beacon.genesis_time == 0 || beacon.period_seconds == 0 {
+            report.errors.push(
+                "randomness_beacon.genesis_time and period_seconds must be copied from the beacon's /info"
+                    .to_string(),
+            );
+        }
+    }
+
+    let fraction = config.rebroadcast.after_slot_fraction;
+    if config.rebroadcast.enabled && !(fraction > 0.0 && fraction < 1.0) {
+        report.errors.push(format!(
+            "rebroadcast.after_slot_fraction must be between 0 and 1, got {}",
+            fraction
+        ));
+    }
+
+    if config.sync_holding.enabled && config.sync_holding.capacity == 0 {
+        report.warnings.push(
+            "sync_holding.capacity is 0, transactions gossiped during sync are dropped".to_string(),
+        );
+    }
+
+    let future_blocks = &config.future_blocks;
+    if future_blocks.enabled && future_blocks.capacity == 0 {
+        report
+            .warnings
+            .push("future_blocks.capacity is 0, blocks arriving early are rejected".to_string());
+    }
+
+    if config.gossip_throttle.enabled
+        && Duration::from_millis(config.gossip_throttle.window_ms) >= slot_duration
+    {
+        report.warnings.push(format!(
+            "gossip_throttle.window_ms {} covers the whole slot, gossip transactions wait until our block is built",
+            config.gossip_throttle.window_ms
+        ));
+    }
+
+    // a block import can legitimately take most of a slot
+    if config.watchdog.enabled
+        && config.watchdog.restart_stalled_loop
+        && Duration::from_millis(config.watchdog.stall_threshold_ms) < slot_duration
+    {
+        report.warnings.push(format!(
+            "watchdog.stall_threshold_ms {} is shorter than a slot, slow but healthy steps would be restarted",
+            config.watchdog.stall_threshold_ms
+        ));
+    }
+
+    if let TxPolicyConfig::Allowlist(allowlist) = &config.tx_policy
+        && allowlist.senders.is_empty()
+    {
+        report.errors.push(
+            "tx_policy allowlist has no senders, every transaction would be rejected".to_string(),
+        );
+    }
+
+    if let Some(tls) = &config.rpc_tls {
+        match tls.paths(&config.data_dir) {
+            Ok((cert, key)) if !tls.self_signed => {
+                for path in [cert, key] {
+                    if !path.exists() {
+                        report
+                            .errors
+                            .push(format!("rpc_tls file {} does not exist", path.display()));
+                    }
+                }
+            }
+            Ok(_) => {}
+            Err(e) => report.errors.push(e.to_string()),
+        }
+        if tls.bind_address.parse::<std::net::IpAddr>().is_err() {
+            report.errors.push(format!(
+                "rpc_tls bind_address {} is not an ip address",
+                tls.bind_address
+            ));
+        }
+    }
+
+    if !config.relay.validators.is_empty() {
+        for target in &config.relay.validators {
+            if !target.url.starts_with("http://") && !target.url.starts_with("https://") {
+                report
+                    .errors
+                    .push(format!("relay target {} is not an http(s) url", target.url));
+            }
+        }
+        if config.relay.timeout_ms == 0 {
+            report
+                .errors
+                .push("relay.timeout_ms must be at least 1".to_string());
+        }
+    }
+
+    if config.max_gas_price_multiplier == 0 {
+        report.errors.push(
+            "max_gas_price_multiplier must be at least 1, 0 refuses every unforced transaction"
+                .to_string(),
+        );
+    }
+
+    if config.faults.is_some() && !cfg!(feature = "fault-injection") {
+        report.warnings.push(
+            "faults are configured but the node is built without `fault-injection`".to_string(),
+        );
+    }
+
+    report
+}
@@ -0,0 +1,9 @@
+pub mod chain_spec;
+pub mod config_check;
+pub mod multi_chain_config;
+pub mod node_config;
+
+pub use chain_spec::*;
+pub use config_check::*;
+pub use multi_chain_config::*;
+pub use node_config::*;
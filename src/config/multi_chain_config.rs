@@ -0,0 +1,59 @@
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use super::NodeConfig;
+
+// several independent chains served by one process
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MultiChainConfig {
+    pub chains: Vec<NodeConfig>,
+}
+
+impl MultiChainConfig {
+    // load multi-chain config from a json file and validate it
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let data = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read multi-chain config {}", path.display()))?;
+
+        let config: Self = serde_json::from_str(&data)
+            .with_context(|| format!("Failed to parse multi-chain config {}", path.display()))?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    // chains must not share a chain id, data directory or port
+    pub fn validate(&self) -> Result<()> {
+        if self.chains.is_empty() {
+            return Err(anyhow!("Multi-chain config has no chains"));
+        }
+
+        let mut chain_ids = HashSet::new();
+        let mut data_dirs = HashSet::new();
+        let mut ports = HashSet::new();
+
+        for chain in &self.chains {
+            if !chain_ids.insert(chain.chain_id) {
+                return Err(anyhow!("Duplicate chain id {}", chain.chain_id));
+            }
+            if !data_dirs.insert(chain.data_dir.as_str()) {
+                return Err(anyhow!("Duplicate data dir {}", chain.data_dir));
+            }
+            for port in [chain.port, chain.rpc_port] {
+                if !ports.insert(port) {
+                    return Err(anyhow!(
+                        "Port {} used twice (chain {})",
+                        port,
+                        chain.chain_id
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,442 @@
+use alloy::primitives::{Address, B256, U256};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::consensus::{ConsensusConfig, RandomnessBeaconConfig, SIGNING_AUDIT_FILE};
+use crate::core::FutureBlockConfig;
+use crate::reindex::VerifyDepth;
+use crate::rpc::DEFAULT_MAX_GAS_PRICE_MULTIPLIER;
+use crate::{
+    AdmissionPolicy, BlockRewardConfig, CHAIN_ID, DB_PATH, FaultConfig, GossipSigningConfig,
+    P2P_PORT, PREFETCH_TRANSACTIONS, ParallelExecutionConfig, REPLAY_HORIZON_SLOTS, RPC_PORT,
+    RelayConfig, RpcAccessConfig, RpcTlsConfig, SLOT_DURATION, STATE_WARMUP_BLOCKS,
+    SYNC_HOLDING_CAPACITY, SyncHoldingConfig, TxPolicyConfig, VALIDATORS_FILE,
+};
+
+// stands in for secrets in redacted configs
+pub const REDACTED: &str = "<redacted>";
+
+// node level settings, loaded from a json file, every field is optional
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NodeConfig {
+    pub chain_id: u64,
+    pub data_dir: String,
+    pub validators_file: String,
+    // toml or json chain spec the genesis block, state and validators come from,
+    // when unset the chain starts from `validators_file` and the built-in parameters
+    pub chain_spec: Option<String>,
+    // start an empty database from this trusted block and state instead of from genesis
+    pub checkpoint: Option<CheckpointConfig>,
+    // name the validator keypair is derived from
+    pub validator_key: String,
+    pub port: u16,
+    pub rpc_port: u16,
+    // serve the rpc over https/wss, plain http when unset
+    pub rpc_tls: Option<RpcTlsConfig>,
+    // rpc namespaces switched off and bearer tokens required, both changeable through admin_*
+    pub rpc_access: RpcAccessConfig,
+    // forward rpc transactions straight to validators, and the relayers this node accepts
+    pub relay: RelayConfig,
+    // build the block we would have proposed, but never commit or broadcast it
+    pub dry_run: bool,
+    // pre-fund the well-known dev accounts, for local development only
+    pub dev: bool,
+    // serve the admin_* rpc namespace (peers, mempool, log level, snapshots)
+    pub admin_rpc: bool,
+    // accounts touched in this many recent blocks are loaded before rpc starts, 0 disables
+    pub state_warmup_blocks: u64,
+    // newest stored blocks checked and replayed before the node starts: "none", "full" or a count
+    pub startup_verification: VerifyDepth,
+    // checks a proposer must pass before building a block
+    pub proposer_safety: ProposerSafetyConfig,
+    // re-broadcast our own block once when attestations are slow to arrive
+    pub rebroadcast: RebroadcastConfig,
+    // verify signatures and read sender accounts of pending transactions before our slot
+    pub proposal_prefetch: ProposalPrefetchConfig,
+    // defer gossip transactions at the start of our own slots, while the block is built
+    pub gossip_throttle: GossipThrottleConfig,
+    // dump diagnostics when the service loop or a shared lock is stuck
+    pub watchdog: WatchdogConfig,
+    // keep gossiped blocks we rejected, with the reason, for `speed_getRejectedBlocks`
+    pub quarantine: QuarantineConfig,
+    // hold gossiped blocks that arrive before their slot or their parent, and replay them
+    pub future_blocks: FutureBlockConfig,
+    // tokens minted to each block's proposer, every validator must use the same schedule
+    pub block_rewards: BlockRewardConfig,
+    // stake burned for signing two blocks in one slot, every validator must use the same settings
+    pub slashing: SlashingConfig,
+    // deactivate validators that miss too many duties in an epoch, every validator must use
+    // the same settings
+    pub liveness: LivenessConfig,
+    // rewards and inactivity penalties paid into account balances when an epoch closes,
+    // every validator must use the same settings
+    pub epoch_rewards: EpochRewardConfig,
+    // slot duration, epoch length, quorum and clock skew, every validator must use the same
+    // settings; a chain spec's slot duration takes precedence
+    pub consensus: ConsensusConfig,
+    // hash chained log of every proposal and attestation the validator key signs
+    pub signing_audit: SigningAuditConfig,
+    // experimental: slot duration follows the propagation latency proposers report in headers,
+    // part of the chain spec, every validator must use the same settings
+    pub adaptive_slots: Option<AdaptiveSlotConfig>,
+    // drand network mixed into the proposer schedule of every epoch after the first,
+    // part of the chain spec
+    pub randomness_beacon: Option<RandomnessBeaconConfig>,
+    // gossiped blocks more than this many slots behind our head are dropped
+    pub replay_horizon_slots: u64,
+    // whether transaction gossip must be signed by its author, blocks always are
+    pub gossip_signing: GossipSigningConfig,
+    // per-source mempool admission rules
+    pub mempool: AdmissionPolicy,
+    // rpc submissions paying more than this multiple of the gas price estimate are refused
+    // unless forced
+    pub max_gas_price_multiplier: u64,
+    // gossip transactions received while syncing are held, then validated and admitted
+    pub sync_holding: SyncHoldingConfig,
+    // application transaction rules, e.g. a sender allowlist
+    pub tx_policy: TxPolicyConfig,
+    // run block transactions that touch disjoint accounts on several cores
+    pub parallel_execution: ParallelExecutionConfig,
+    // soak-test faults, requires the `fault-injection` feature
+    pub faults: Option<FaultConfig>,
+}
+
+// keeps an isolated or lagging validator from building useless forks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProposerSafetyConfig {
+    // connected peers required to propose, 0 disables the check
+    pub min_peers: usize,
+    // how far our head may trail the best slot seen on the network
+    pub max_head_lag_slots: u64,
+}
+
+impl Default for ProposerSafetyConfig {
+    fn default() -> Self {
+        Self {
+            min_peers: 0,
+            max_head_lag_slots: 4,
+        }
+    }
+}
+
+// proposer liveness under lossy gossip
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RebroadcastConfig {
+    pub enabled: bool,
+    // part of the slot to wait for a quorum of attestations before re-broadcasting
+    pub after_slot_fraction: f64,
+}
+
+impl Default for RebroadcastConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            after_slot_fraction: 0.5,
+        }
+    }
+}
+
+// block production warm-up, run one slot ahead of the node's own proposals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProposalPrefetchConfig {
+    pub enabled: bool,
+    // pending transactions warmed up, in packing order
+    pub max_transactions: usize,
+    // also select the block's transactions ahead of time and keep them current
+    pub block_template: bool,
+}
+
+impl Default for ProposalPrefetchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_transactions: PREFETCH_TRANSACTIONS,
+            block_template: true,
+        }
+    }
+}
+
+// gossip transactions arriving in the first `window_ms` of a slot this node proposes in are
+// queued and admitted once the block is built or the window is over
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GossipThrottleConfig {
+    pub enabled: bool,
+    pub window_ms: u64,
+    // transactions queued per window, later ones are dropped
+    pub capacity: usize,
+}
+
+impl Default for GossipThrottleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window_ms: 2_000,
+            capacity: SYNC_HOLDING_CAPACITY,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SigningAuditConfig {
+    pub enabled: bool,
+    // `signing_audit.jsonl` under the data directory when unset
+    pub path: Option<String>,
+}
+
+impl Default for SigningAuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            path: None,
+        }
+    }
+}
+
+impl SigningAuditConfig {
+    pub fn path(&self, data_dir: &str) -> PathBuf {
+        match &self.path {
+            Some(path) => PathBuf::from(path),
+            None => Path::new(data_dir).join(SIGNING_AUDIT_FILE),
+        }
+    }
+}
+
+// stall detection for the blockchain service loop and the shared mutexes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchdogConfig {
+    pub enabled: bool,
+    pub check_interval_ms: u64,
+    // a loop step or lock hold longer than this is a stall, 3 slots by default
+    pub stall_threshold_ms: u64,
+    // abandon the stalled loop step and carry on with the next one
+    pub restart_stalled_loop: bool,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            check_interval_ms: 1_000,
+            stall_threshold_ms: 3 * SLOT_DURATION * 1_000,
+            restart_stalled_loop: false,
+        }
+    }
+}
+
+// storage of rejected blocks, off by default since peers choose what fills it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuarantineConfig {
+    pub enabled: bool,
+    // newest rejected blocks kept, older ones are deleted
+    pub max_blocks: usize,
+}
+
+impl Default for QuarantineConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_blocks: 1024,
+        }
+    }
+}
+
+// penalty for double proposals, applied when the epoch of the evidence closes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SlashingConfig {
+    // percent of the current stake burned per proven double proposal
+    pub slash_percent: u64,
+    // slashes after which the validator is deactivated, whatever stake it has left
+    pub max_slashes: u32,
+}
+
+impl Default for SlashingConfig {
+    fn default() -> Self {
+        Self {
+            slash_percent: 10,
+            max_slashes: 3,
+        }
+    }
+}
+
+// validators missing more than `max_missed_percent` of their duties in an epoch are
+// deactivated when it closes, see `liveness_records`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LivenessConfig {
+    pub enabled: bool,
+    pub max_missed_percent: u64,
+    // validators with fewer duties in the epoch are not judged
+    pub min_duties: u64,
+    // count accept votes as duties too, they are what this node received, so validators
+    // with a different view of the votes can disagree on who is deactivated
+    pub count_attestations: bool,
+}
+
+impl Default for LivenessConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_missed_percent: 50,
+            min_duties: 2,
+            count_attestations: false,
+        }
+    }
+}
+
+// balance changes settled for every validator when an epoch with blocks closes, see
+// `epoch_settlements`; both are 0 by default, which settles nothing
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EpochRewardConfig {
+    // minted to the proposer for each of its blocks in the epoch, in wei
+    pub proposal_reward: U256,
+    // burned from the validator's balance for each slot it missed, in wei
+    pub inactivity_penalty: U256,
+}
+
+impl EpochRewardConfig {
+    pub fn enabled(&self) -> bool {
+        !self.proposal_reward.is_zero() || !self.inactivity_penalty.is_zero()
+    }
+}
+
+// bounds and pace of the adaptive slot duration, see `AdaptiveSlots`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdaptiveSlotConfig {
+    pub min_slot_ms: u64,
+    pub max_slot_ms: u64,
+    // target slot duration as a multiple of the reported p90 propagation latency
+    pub latency_multiple: u64,
+    // largest change from one block to the next, in percent of the current duration
+    pub max_step_percent: u64,
+    // recent gossiped blocks a proposer measures the latency over, local to each node
+    pub window_blocks: usize,
+}
+
+impl Default for AdaptiveSlotConfig {
+    fn default() -> Self {
+        Self {
+            min_slot_ms: 3_000,
+            max_slot_ms: SLOT_DURATION * 1_000,
+            latency_multiple: 4,
+            max_step_percent: 10,
+            window_blocks: 32,
+        }
+    }
+}
+
+// weak subjectivity start, the hash must come from a source the operator trusts,
+// e.g. `admin_exportCheckpoint` on their own node or a block explorer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckpointConfig {
+    pub block_hash: B256,
+    // checkpoint json written by `admin_exportCheckpoint`
+    pub path: String,
+}
+
+impl Default for NodeConfig {
+    fn default() -> Self {
+        Self {
+            chain_id: CHAIN_ID,
+            data_dir: DB_PATH.to_string(),
+            validators_file: VALIDATORS_FILE.to_string(),
+            chain_spec: None,
+            checkpoint: None,
+            validator_key: "node".to_string(),
+            port: P2P_PORT,
+            rpc_port: RPC_PORT,
+            rpc_tls: None,
+            rpc_access: RpcAccessConfig::default(),
+            relay: RelayConfig::default(),
+            dry_run: false,
+            dev: false,
+            admin_rpc: false,
+            state_warmup_blocks: STATE_WARMUP_BLOCKS,
+            startup_verification: VerifyDepth::None,
+            proposer_safety: ProposerSafetyConfig::default(),
+            rebroadcast: RebroadcastConfig::default(),
+            proposal_prefetch: ProposalPrefetchConfig::default(),
+            gossip_throttle: GossipThrottleConfig::default(),
+            watchdog: WatchdogConfig::default(),
+            quarantine: QuarantineConfig::default(),
+            future_blocks: FutureBlockConfig::default(),
+            block_rewards: BlockRewardConfig::default(),
+            slashing: SlashingConfig::default(),
+            liveness: LivenessConfig::default(),
+            epoch_rewards: EpochRewardConfig::default(),
+            consensus: ConsensusConfig::default(),
+            signing_audit: SigningAuditConfig::default(),
+            adaptive_slots: None,
+            randomness_beacon: None,
+            replay_horizon_slots: REPLAY_HORIZON_SLOTS,
+            gossip_signing: GossipSigningConfig::default(),
+            mempool: AdmissionPolicy::default(),
+            max_gas_price_multiplier: DEFAULT_MAX_GAS_PRICE_MULTIPLIER,
+            sync_holding: SyncHoldingConfig::default(),
+            tx_policy: TxPolicyConfig::default(),
+            parallel_execution: ParallelExecutionConfig::default(),
+            faults: None,
+        }
+    }
+}
+
+impl NodeConfig {
+    // load config from a json file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let data = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+
+        serde_json::from_str(&data)
+            .with_context(|| format!("Failed to parse config file {}", path.display()))
+    }
+
+    // copy safe to share in bug reports, the validator key name derives the private key
+    pub fn redacted(&self) -> Self {
+        let mut config = self.clone();
+        if !config.validator_key.is_empty() {
+            config.validator_key = REDACTED.to_string();
+        }
+        for token in &mut config.rpc_access.tokens {
+            *token = REDACTED.to_string();
+        }
+        for token in config
+            .relay
+            .validators
+            .iter_mut()
+            .filter_map(|target| target.token.as_mut())
+        {
+            *token = REDACTED.to_string();
+        }
+        config
+    }
+
+    // load validators address and stake from the validators file, for testing purposes
+    pub fn load_validators(&self) -> Result<Vec<(Address, u64)>> {
+        let data = fs::read_to_string(&self.validators_file)
+            .with_context(|| format!("Failed to read validators file {}", self.validators_file))?;
+        let addresses: Vec<(&str, u64)> = serde_json::from_str(&data)
+            .with_context(|| format!("Failed to parse validators file {}", self.validators_file))?;
+
+        let mut validators = Vec::new();
+        for (addr, stake) in addresses {
+            let addr = Address::parse_checksummed(addr, Some(1))
+                .map_err(|_| anyhow::anyhow!("Invalid address: {}", addr))?;
+            validators.push((addr, stake));
+        }
+
+        Ok(validators)
+    }
+}
@@ -0,0 +1,91 @@
+use crate::AdaptiveSlotConfig;
+use crate::core::SlotTiming;
+
+// experimental slot clock whose duration follows the propagation latency proposers report
+// every block carries its slot timing, and the duration after a block depends on that
+// header alone, so every node holding the block runs the same clock, also after a restart
+#[derive(Debug, Clone)]
+pub struct AdaptiveSlots {
+    config: AdaptiveSlotConfig,
+}
+
+impl AdaptiveSlots {
+    pub fn new(config: AdaptiveSlotConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &AdaptiveSlotConfig {
+        &self.config
+    }
+
+    // duration of the slots after a block: one step of at most `max_step_percent` towards
+    // `latency_multiple` times the latency its proposer reported, unchanged without a report
+    pub fn next_duration_ms(&self, head: &SlotTiming) -> u64 {
+        let (min, max) = (self.config.min_slot_ms, self.config.max_slot_ms);
+        let current = head.slot_duration_ms.clamp(min, max);
+        if head.propagation_ms == 0 {
+            return current;
+        }
+
+        let target = head
+            .propagation_ms
+            .saturating_mul(self.config.latency_multiple)
+            .clamp(min, max);
+        let step = (current * self.config.max_step_percent / 100).max(1);
+        if target > current {
+            (current + step).min(target)
+        } else {
+            current.saturating_sub(step).max(target)
+        }
+    }
+
+    // the head's own slot keeps its duration, every slot after it lasts the next duration
+    // slots before the head are approximated with the head's duration
+    pub fn slot_start_ms(&self, head: &SlotTiming, head_slot: u64, slot: u64) -> u64 {
+        if slot <= head_slot {
+            return head
+                .slot_start_ms
+                .saturating_sub((head_slot - slot) * head.slot_duration_ms);
+        }
+        let next_start = head.slot_start_ms + head.slot_duration_ms;
+        next_start + (slot - head_slot - 1) * self.next_duration_ms(head)
+    }
+
+    // slot at a wall clock time, in ms since the unix epoch
+    pub fn slot_at(&self, head: &SlotTiming, head_slot: u64, now_ms: u64) -> u64 {
+        let next_start = head.slot_start_ms + head.slot_duration_ms;
+        if now_ms < next_start {
+            return head_slot;
+        }
+        head_slot + 1 + (now_ms - next_start) / self.next_duration_ms(head).max(1)
+    }
+
+    // timing a block at `slot` on top of the head has to carry
+    pub fn timing_for(
+        &self,
+        head: &SlotTiming,
+        head_slot: u64,
+        slot: u64,
+        propagation_ms: u64,
+    ) -> SlotTiming {
+        SlotTiming {
+            slot_start_ms: self.slot_start_ms(head, head_slot, slot),
+            slot_duration_ms: self.next_duration_ms(head),
+            propagation_ms,
+        }
+    }
+
+    // the settings every validator must share, added to the chain spec hash
+    // the measurement window is local to each proposer and left out
+    pub fn chain_spec_bytes(&self) -> Vec<u8> {
+        [
+            self.config.min_slot_ms,
+            self.config.max_slot_ms,
+            self.config.latency_multiple,
+            self.config.max_step_percent,
+        ]
+        .iter()
+        .flat_map(|value| value.to_be_bytes())
+        .collect()
+    }
+}
@@ -0,0 +1,213 @@
+use alloy::primitives::{B256, keccak256};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::error::BeaconError;
+use crate::core::Blockchain;
+
+// hash-to-curve domains drand signs rounds with
+const DST_G1: &[u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
+const DST_G2: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+// how a drand network signs its rounds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BeaconScheme {
+    // signatures on g2 over the previous signature and the round, the default network
+    #[serde(rename = "pedersen-bls-chained")]
+    Chained,
+    // signatures on g2 over the round only
+    #[serde(rename = "pedersen-bls-unchained")]
+    Unchained,
+    // signatures on g1 over the round only, quicknet
+    #[serde(rename = "bls-unchained-g1-rfc9380")]
+    UnchainedG1,
+}
+
+// drand network the node reads randomness from, part of the chain spec
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RandomnessBeaconConfig {
+    // http api, rounds are read from `<url>/<chain_hash>/public/<round>`
+    pub url: String,
+    // the rest is copied from `<url>/<chain_hash>/info`
+    pub chain_hash: String,
+    // hex, on g1 for the pedersen schemes and on g2 for the g1 scheme
+    pub public_key: String,
+    pub scheme: BeaconScheme,
+    // unix seconds of round 1
+    pub genesis_time: u64,
+    pub period_seconds: u64,
+}
+
+impl Default for RandomnessBeaconConfig {
+    fn default() -> Self {
+        Self {
+            url: "https://api.drand.sh".to_string(),
+            chain_hash: String::new(),
+            public_key: String::new(),
+            scheme: BeaconScheme::UnchainedG1,
+            genesis_time: 0,
+            period_seconds: 3,
+        }
+    }
+}
+
+// one round as served by the drand http api, hex without 0x
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BeaconRound {
+    pub round: u64,
+    pub randomness: String,
+    pub signature: String,
+    // chained scheme only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_signature: Option<String>,
+}
+
+// verifies and fetches drand rounds, and keeps the epoch randomness of the chain fed
+#[derive(Debug, Clone)]
+pub struct RandomnessBeacon {
+    config: RandomnessBeaconConfig,
+    public_key: Vec<u8>,
+}
+
+impl RandomnessBeacon {
+    pub fn new(config: RandomnessBeaconConfig) -> Result<Self, BeaconError> {
+        let public_key = hex::decode(config.public_key.trim_start_matches("0x"))
+            .map_err(|_| BeaconError::InvalidPublicKey)?;
+        let valid = match config.scheme {
+            BeaconScheme::Chained | BeaconScheme::Unchained => {
+                blst::min_pk::PublicKey::key_validate(&public_key).is_ok()
+            }
+            BeaconScheme::UnchainedG1 => {
+                blst::min_sig::PublicKey::key_validate(&public_key).is_ok()
+            }
+        };
+        if !valid {
+            return Err(BeaconError::InvalidPublicKey);
+        }
+        Ok(Self { config, public_key })
+    }
+
+    pub fn config(&self) -> &RandomnessBeaconConfig {
+        &self.config
+    }
+
+    // latest round published at a wall clock time, 0 before the beacon's genesis
+    pub fn round_at(&self, time: SystemTime) -> u64 {
+        let genesis = UNIX_EPOCH + Duration::from_secs(self.config.genesis_time);
+        match time.duration_since(genesis) {
+            Ok(elapsed) => elapsed.as_secs() / self.config.period_seconds.max(1) + 1,
+            Err(_) => 0,
+        }
+    }
+
+    // checks the round's signature and that its randomness is the sha256 of it
+    pub fn verify(&self, round: &BeaconRound) -> Result<B256, BeaconError> {
+        let signature = decode_hex(&round.signature, round.round)?;
+        let mut message = Sha256::new();
+        if self.config.scheme == BeaconScheme::Chained {
+            let previous = round
+                .previous_signature
+                .as_deref()
+                .ok_or(BeaconError::MissingPreviousSignature { round: round.round })?;
+            message.update(decode_hex(previous, round.round)?);
+        }
+        message.update(round.round.to_be_bytes());
+        let message = message.finalize();
+
+        let verified = match self.config.scheme {
+            BeaconScheme::Chained | BeaconScheme::Unchained => {
+                let public_key = blst::min_pk::PublicKey::from_bytes(&self.public_key);
+                let signature = blst::min_pk::Signature::from_bytes(&signature);
+                matches!((public_key, signature), (Ok(pk), Ok(sig))
+                    if sig.verify(true, &message, DST_G2, &[], &pk, true) == blst::BLST_ERROR::BLST_SUCCESS)
+            }
+            BeaconScheme::UnchainedG1 => {
+                let public_key = blst::min_sig::PublicKey::from_bytes(&self.public_key);
+                let signature = blst::min_sig::Signature::from_bytes(&signature);
+                matches!((public_key, signature), (Ok(pk), Ok(sig))
+                    if sig.verify(true, &message, DST_G1, &[], &pk, true) == blst::BLST_ERROR::BLST_SUCCESS)
+            }
+        };
+        if !verified {
+            return Err(BeaconError::InvalidSignature { round: round.round });
+        }
+
+        let randomness = B256::from_slice(&Sha256::digest(&signature));
+        if decode_hex(&round.randomness, round.round)? != randomness.as_slice() {
+            return Err(BeaconError::RandomnessMismatch { round: round.round });
+        }
+        Ok(randomness)
+    }
+
+    pub async fn fetch(&self, round: u64) -> Result<BeaconRound> {
+        let url = format!(
+            "{}/{}/public/{}",
+            self.config.url.trim_end_matches('/'),
+            self.config.chain_hash,
+            round
+        );
+        reqwest::get(&url)
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("Failed to fetch beacon round from {}", url))?
+            .json()
+            .await
+            .with_context(|| format!("Failed to parse beacon round from {}", url))
+    }
+
+    // fetch the rounds of the current and the next epoch ahead of time, every period
+    pub async fn run(self, blockchain: Blockchain) {
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(self.config.period_seconds.max(1)));
+        loop {
+            interval.tick().await;
+            for (epoch, round) in blockchain.missing_beacon_rounds(&self).await {
+                let randomness = match self.fetch(round).await {
+                    Ok(beacon_round) if beacon_round.round == round => self.verify(&beacon_round),
+                    Ok(beacon_round) => Err(BeaconError::UnexpectedRound {
+                        requested: round,
+                        got: beacon_round.round,
+                    }),
+                    Err(e) => {
+                        println!("⚠️  Beacon round {} for epoch {}: {:#}", round, epoch, e);
+                        continue;
+                    }
+                };
+                match randomness {
+                    Ok(randomness) => {
+                        blockchain
+                            .set_epoch_randomness(epoch, round, randomness)
+                            .await
+                    }
+                    Err(e) => println!("⚠️  Beacon round {} for epoch {}: {}", round, epoch, e),
+                }
+            }
+        }
+    }
+
+    // the settings every validator must share, added to the chain spec hash
+    pub fn chain_spec_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&self.public_key);
+        data.extend_from_slice(self.config.chain_hash.as_bytes());
+        data.extend_from_slice(&self.config.genesis_time.to_be_bytes());
+        data.extend_from_slice(&self.config.period_seconds.to_be_bytes());
+        data
+    }
+}
+
+// proposer seed of an epoch once beacon randomness is mixed in
+pub fn mix_epoch_seed(base_seed: [u8; 32], epoch: u64, randomness: B256) -> [u8; 32] {
+    let mut data = Vec::with_capacity(32 + 8 + 32);
+    data.extend_from_slice(&base_seed);
+    data.extend_from_slice(&epoch.to_be_bytes());
+    data.extend_from_slice(randomness.as_slice());
+    keccak256(data).0
+}
+
+fn decode_hex(value: &str, round: u64) -> Result<Vec<u8>, BeaconError> {
+    hex::decode(value.trim_start_matches("0x")).map_err(|_| BeaconError::InvalidEncoding { round })
+}
@@ -0,0 +1,96 @@
+use alloy::primitives::U256;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::{SLOT_DURATION, SLOTS_PER_EPOCH};
+
+// share of the total stake, `numerator / denominator`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Quorum {
+    pub numerator: u64,
+    pub denominator: u64,
+}
+
+impl Default for Quorum {
+    fn default() -> Self {
+        Self {
+            numerator: 2,
+            denominator: 3,
+        }
+    }
+}
+
+impl Quorum {
+    // stake needed out of `total_stake`, rounded up
+    pub fn of(&self, total_stake: u64) -> u64 {
+        (self.numerator as u128 * total_stake as u128).div_ceil(self.denominator.max(1) as u128)
+            as u64
+    }
+}
+
+// parameters every validator of a chain must agree on, passed to the consensus engine
+// the defaults are the values nodes ran with before they were configurable
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConsensusConfig {
+    // seconds per slot of the fixed slot clock, a chain spec's slot duration takes precedence
+    pub slot_duration: u64,
+    // slots per epoch, for proposer schedules, accounting and epoch checkpoints
+    pub slots_per_epoch: u64,
+    // accepting stake a finality certificate needs, the proposer's stake counts towards it
+    pub quorum: Quorum,
+    // how far a block timestamp may be ahead of our clock, in seconds
+    pub max_clock_skew_secs: u64,
+    // a block timestamp may not be before the median of this many latest blocks, 0 turns
+    // the median check off; never before the parent's either way
+    pub median_time_blocks: u64,
+    // gas our proposals are packed up to, the block gas limit when unset; local to each
+    // proposer, validators don't check it
+    pub block_gas_target: Option<U256>,
+}
+
+impl Default for ConsensusConfig {
+    fn default() -> Self {
+        Self {
+            slot_duration: SLOT_DURATION,
+            slots_per_epoch: SLOTS_PER_EPOCH,
+            quorum: Quorum::default(),
+            max_clock_skew_secs: 30,
+            median_time_blocks: 11,
+            block_gas_target: None,
+        }
+    }
+}
+
+impl ConsensusConfig {
+    pub fn slot_duration(&self) -> Duration {
+        Duration::from_secs(self.slot_duration)
+    }
+
+    // epoch a slot belongs to
+    pub fn epoch_of(&self, slot: u64) -> u64 {
+        slot / self.slots_per_epoch.max(1)
+    }
+
+    // first slot of an epoch
+    pub fn epoch_start_slot(&self, epoch: u64) -> u64 {
+        epoch * self.slots_per_epoch.max(1)
+    }
+
+    // last slot of an epoch
+    pub fn epoch_end_slot(&self, epoch: u64) -> u64 {
+        self.epoch_start_slot(epoch + 1) - 1
+    }
+
+    // added to the chain spec hash, the slot duration is hashed on its own
+    pub fn chain_spec_bytes(&self) -> Vec<u8> {
+        [
+            self.slots_per_epoch.to_be_bytes().as_slice(),
+            &self.quorum.numerator.to_be_bytes(),
+            &self.quorum.denominator.to_be_bytes(),
+            &self.max_clock_skew_secs.to_be_bytes(),
+            &self.median_time_blocks.to_be_bytes(),
+        ]
+        .concat()
+    }
+}
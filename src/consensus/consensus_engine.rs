@@ -1,25 +1,82 @@
-use alloy::primitives::{B256, keccak256};
-use std::time::{Duration, SystemTime};
-
+use alloy::primitives::{Address, B256};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::adaptive_slots::AdaptiveSlots;
+use super::config::ConsensusConfig;
 use super::error::{ConsensusError, ValidatorError};
-use super::proposer::ProposerSelection;
-use super::validator::ValidatorSet;
-use crate::core::{Block, BlockHeader, Transaction};
-use crate::{ExecutionResult, KeyPair};
+use super::proposer::{EpochSchedule, ProposerDuty, ProposerElection, ProposerSelection};
+use super::randomness::BlockRandomness;
+use super::slashing::SlashableEvidence;
+use super::slot_clock::{SlotClock, SlotTicks};
+use super::validator::{
+    EXIT_DELAY_SLOTS, SlashOutcome, StakeSettlement, StoredValidatorSet, ValidatorExit,
+    ValidatorSet,
+};
+use crate::core::{Block, BlockHeader, SlotTiming, Transaction};
+use crate::{ExecutionResult, KeyPair, PendingStake, SlashingConfig, ValidatorRole, unix_millis};
 use anyhow::{Result, anyhow};
 
+// slot clock origin and proposer seed, written on first start and reused after restarts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsensusGenesis {
+    pub genesis_time_ms: u64,
+    pub randomness_seed: B256,
+}
+
+impl ConsensusGenesis {
+    pub fn new(genesis_time: SystemTime, randomness_seed: [u8; 32]) -> Self {
+        Self {
+            genesis_time_ms: unix_millis(genesis_time),
+            randomness_seed: B256::from(randomness_seed),
+        }
+    }
+
+    pub fn genesis_time(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(self.genesis_time_ms)
+    }
+
+    pub fn slot_clock(&self, slot_duration: Duration) -> SlotClock {
+        SlotClock::new(self.genesis_time(), slot_duration)
+    }
+}
+
+// the engine's view of its head block, enough to rewind to it in a reorg
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConsensusHead {
+    pub number: u64,
+    pub hash: B256,
+    pub slot: u64,
+    pub timing: Option<SlotTiming>,
+    // timestamps of the latest blocks up to the head, oldest first
+    pub timestamps: Vec<u64>,
+}
+
 pub struct ConsensusEngine {
-    // Block timing
-    slot_duration: Duration,
-    genesis_time: SystemTime,
+    // slot duration, epoch length, quorum and clock skew of the chain
+    config: ConsensusConfig,
+
+    // Block timing, slots of the fixed clock count from the chain's genesis time
+    slot_clock: SlotClock,
     current_slot: u64,
 
+    // slot clock driven by block headers, fixed slots when unset
+    adaptive_slots: Option<AdaptiveSlots>,
+
     // Current consensus state
     current_block_number: u64,
     current_block_hash: B256,
+    // timing carried by the head, none before the first adaptive block
+    head_timing: Option<SlotTiming>,
+    // timestamps of the latest `median_time_blocks` blocks up to the head, oldest first
+    recent_timestamps: Vec<u64>,
 
     // proposer selection
     proposer_selection: ProposerSelection,
+    // hashes of the committed blocks, seeding future epochs
+    block_randomness: BlockRandomness,
 
     // Validator info (for block signing)
     local_keypair: Option<KeyPair>,
@@ -28,25 +85,59 @@ pub struct ConsensusEngine {
 impl ConsensusEngine {
     /// Create consensus engine using
     pub fn new(
-        slot_duration_seconds: u64,
+        config: ConsensusConfig,
+        genesis: &ConsensusGenesis,
         validator_set: ValidatorSet, // Your ValidatorSet
-        randomness_seed: [u8; 32],
         local_keypair: Option<KeyPair>,
     ) -> Self {
         // Use your ProposerSelection
-        let proposer_selection = ProposerSelection::new(validator_set, randomness_seed);
+        let mut proposer_selection =
+            ProposerSelection::new(validator_set, genesis.randomness_seed.0);
+        proposer_selection.set_slots_per_epoch(config.slots_per_epoch);
 
         Self {
-            slot_duration: Duration::from_secs(slot_duration_seconds),
-            genesis_time: SystemTime::now(),
+            slot_clock: genesis.slot_clock(config.slot_duration()),
+            config,
             current_slot: 0,
+            adaptive_slots: None,
             current_block_number: 0,
             current_block_hash: B256::ZERO,
+            head_timing: None,
+            recent_timestamps: Vec::new(),
             proposer_selection,
+            block_randomness: BlockRandomness::new(),
             local_keypair,
         }
     }
 
+    pub fn config(&self) -> &ConsensusConfig {
+        &self.config
+    }
+
+    // replace the parameters the engine was built with, before any block is imported
+    pub fn set_config(&mut self, config: ConsensusConfig) {
+        self.slot_clock = SlotClock::new(self.slot_clock.genesis_time(), config.slot_duration());
+        self.proposer_selection
+            .set_slots_per_epoch(config.slots_per_epoch);
+        self.config = config;
+        let timestamps = std::mem::take(&mut self.recent_timestamps);
+        self.set_recent_timestamps(timestamps);
+    }
+
+    // derive slot durations from block headers from now on
+    pub fn enable_adaptive_slots(&mut self, adaptive_slots: AdaptiveSlots) {
+        self.adaptive_slots = Some(adaptive_slots);
+    }
+
+    pub fn adaptive_slots(&self) -> Option<&AdaptiveSlots> {
+        self.adaptive_slots.as_ref()
+    }
+
+    // elect proposers with a custom election instead of by stake
+    pub fn set_proposer_election(&mut self, election: Arc<dyn ProposerElection>) {
+        self.proposer_selection.set_election(election);
+    }
+
     /// Validate incoming block
     pub async fn validate_block(&self, block: &Block) -> Result<bool> {
         // Basic validations
@@ -58,6 +149,15 @@ impl ConsensusEngine {
             return Ok(false);
         }
 
+        // slots only move forward, any slots in between were skipped by their proposers
+        if block.header.slot <= self.current_slot {
+            println!(
+                "Block #{} is for slot {}, head is already at slot {}",
+                block.header.index, block.header.slot, self.current_slot
+            );
+            return Ok(false);
+        }
+
         // CORE: Validate proposer using YOUR ProposerSelection
         let expected_proposer = self
             .proposer_selection
@@ -76,18 +176,88 @@ impl ConsensusEngine {
         let now = SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)?
             .as_secs();
-        if block.header.timestamp > now + 30 {
+        // a slot that hasn't started can't be claimed, or the head would jump ahead for everyone
+        let skew_ms = self.config.max_clock_skew_secs * 1000;
+        let slot_start_ms = unix_millis(self.slot_start(block.header.slot));
+        if slot_start_ms > unix_millis(SystemTime::now()) + skew_ms {
+            println!(
+                "Block #{} is for slot {}, which has not started",
+                block.header.index, block.header.slot
+            );
             return Ok(false);
         }
-
-        // Validate hashes
-        let calculated_tx_root = self.calculate_transactions_root(&block.transactions);
-        if calculated_tx_root != block.header.transactions_root {
+        // stamped while its slot ran, give or take the clock skew
+        let slot_end_ms = unix_millis(self.slot_start(block.header.slot + 1));
+        let timestamp_ms = block.header.timestamp * 1000;
+        if timestamp_ms + skew_ms < slot_start_ms || timestamp_ms > slot_end_ms + skew_ms {
+            println!(
+                "Block #{} timestamp {} is outside slot {}",
+                block.header.index, block.header.timestamp, block.header.slot
+            );
             return Ok(false);
         }
+        if block.header.timestamp > now + self.config.max_clock_skew_secs {
+            println!(
+                "Block #{} timestamp {} is more than {}s ahead of our clock",
+                block.header.index, block.header.timestamp, self.config.max_clock_skew_secs
+            );
+            return Ok(false);
+        }
+        // time doesn't go back along the chain, not even within the median of recent blocks
+        if let Some(&parent) = self.recent_timestamps.last()
+            && block.header.timestamp < parent
+        {
+            println!(
+                "Block #{} timestamp {} is before its parent's {}",
+                block.header.index, block.header.timestamp, parent
+            );
+            return Ok(false);
+        }
+        if let Some(median) = self.median_time()
+            && block.header.timestamp < median
+        {
+            println!(
+                "Block #{} timestamp {} is before the median time {} of recent blocks",
+                block.header.index, block.header.timestamp, median
+            );
+            return Ok(false);
+        }
+
+        // the slot timing follows from the parent, a block can't pick its own
+        match (&self.adaptive_slots, &block.header.slot_timing) {
+            (None, None) => {}
+            (Some(adaptive), Some(timing)) => {
+                let expected = adaptive.timing_for(
+                    &self.head_timing(),
+                    self.current_slot,
+                    block.header.slot,
+                    timing.propagation_ms,
+                );
+                if *timing != expected {
+                    println!(
+                        "Invalid slot timing: expected {:?}, got {:?}",
+                        expected, timing
+                    );
+                    return Ok(false);
+                }
+            }
+            (Some(_), None) => {
+                println!("Block #{} has no slot timing", block.header.index);
+                return Ok(false);
+            }
+            (None, Some(_)) => {
+                println!(
+                    "Block #{} has a slot timing, adaptive slots are disabled",
+                    block.header.index
+                );
+                return Ok(false);
+            }
+        }
 
-        let calculated_hash = self.calculate_block_hash(&block.header);
-        if calculated_hash != block.header.hash() {
+        // Validate hashes
+        // the block hash is always derived from the header, only the root can be forged
+        let calculated_tx_root = Block::calculate_transactions_root(&block.transactions);
+        if calculated_tx_root != block.header.transactions_root {
             return Ok(false);
         }
 
@@ -120,11 +290,30 @@ impl ConsensusEngine {
     }
 
     /// Create block template
-    pub async fn create_block(&self, transactions: Vec<Transaction>) -> Result<Block> {
+    /// `propagation_ms` is the latency reported in the header on adaptive slot chains
+    pub async fn create_block(
+        &self,
+        transactions: Vec<Transaction>,
+        propagation_ms: u64,
+    ) -> Result<Block> {
+        let transactions_root = Block::calculate_transactions_root(&transactions);
+        self.create_block_with_root(transactions, transactions_root, propagation_ms)
+            .await
+    }
+
+    // block template whose transactions root the caller already computed
+    pub async fn create_block_with_root(
+        &self,
+        transactions: Vec<Transaction>,
+        transactions_root: B256,
+        propagation_ms: u64,
+    ) -> Result<Block> {
         let current_slot = self.calculate_current_slot()?;
+        // a clock behind the parent's would get the block rejected
         let timestamp = SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)?
-            .as_secs();
+            .as_secs()
+            .max(self.min_timestamp());
 
         // Use your ProposerSelection to get proposer
         let proposer = self
@@ -139,7 +328,20 @@ impl ConsensusEngine {
             slot: current_slot,
             proposer,
             state_root: B256::ZERO,
-            transactions_root: self.calculate_transactions_root(&transactions),
+            transactions_root,
+            slot_timing: self.adaptive_slots.as_ref().map(|adaptive| {
+                adaptive.timing_for(
+                    &self.head_timing(),
+                    self.current_slot,
+                    current_slot,
+                    propagation_ms,
+                )
+            }),
+            receipts_root: None,
+            logs_bloom: None,
+            base_fee_per_gas: None,
+            gas_used: None,
+            gas_limit: None,
             validator_signature: None,
         };
 
@@ -161,11 +363,15 @@ impl ConsensusEngine {
     ) -> Result<Block> {
         // Update with execution results
         block.header.state_root = execution_result.state_root;
+        block.header.receipts_root = Some(execution_result.receipts_root);
+        block.header.logs_bloom = Some(execution_result.logs_bloom);
+        block.header.gas_used = Some(execution_result.total_gas_used);
 
         // Sign if we're the proposer
         if let Some(keypair) = &self.local_keypair {
             if keypair.address == block.header.proposer {
-                let _signature = keypair.sign_hash(&block.header.hash()).await?;
+                let signature = keypair.sign_hash(&block.header.hash()).await?;
+                block.header.validator_signature = Some(signature);
                 println!(
                     "Block #{} signed by proposer {}",
                     block.header.index, keypair.address
@@ -178,10 +384,22 @@ impl ConsensusEngine {
 
     // update consensus engine value
     pub async fn update_best_block(&mut self, block: &Block) -> Result<()> {
+        let previous_duration = self.slot_duration();
+
         // Update internal state
-        self.current_block_number = block.header.index;
-        self.current_block_hash = block.header.hash();
-        self.current_slot = block.header.slot;
+        let mut timestamps = std::mem::take(&mut self.recent_timestamps);
+        self.set_head(block);
+        timestamps.push(block.header.timestamp);
+        self.set_recent_timestamps(timestamps);
+        self.block_randomness.mix(block.header.hash());
+
+        if self.adaptive_slots.is_some() && self.slot_duration() != previous_duration {
+            println!(
+                "⏱️  Slot duration {}ms -> {}ms",
+                previous_duration.as_millis(),
+                self.slot_duration().as_millis()
+            );
+        }
 
         println!(
             "Consensus engine updated to block #{}, slot {}",
@@ -190,35 +408,320 @@ impl ConsensusEngine {
         Ok(())
     }
 
-    // calculate block hash
-    fn calculate_block_hash(&self, header: &BlockHeader) -> B256 {
-        let mut data = Vec::new();
-        data.extend_from_slice(&header.index.to_be_bytes());
-        data.extend_from_slice(header.parent_hash.as_slice());
-        data.extend_from_slice(&header.timestamp.to_be_bytes());
-        data.extend_from_slice(&header.slot.to_be_bytes());
-        data.extend_from_slice(header.proposer.as_slice());
-        data.extend_from_slice(header.state_root.as_slice());
-        data.extend_from_slice(header.transactions_root.as_slice());
-        keccak256(data)
+    // point the head at a stored block, used on startup to resume where we stopped
+    pub fn set_head(&mut self, block: &Block) {
+        self.current_block_number = block.header.index;
+        self.current_block_hash = block.header.hash();
+        self.current_slot = block.header.slot;
+        self.head_timing = block.header.slot_timing;
+        self.recent_timestamps = vec![block.header.timestamp];
     }
 
-    // calculate transaction root hash
-    // go through all transactions add them and hash it
-    fn calculate_transactions_root(&self, transactions: &[Transaction]) -> B256 {
-        if transactions.is_empty() {
-            return B256::ZERO;
+    // timestamps of the head and the blocks before it, oldest first, only the latest are kept
+    pub fn set_recent_timestamps(&mut self, mut timestamps: Vec<u64>) {
+        let keep = self.config.median_time_blocks.max(1) as usize;
+        if timestamps.len() > keep {
+            timestamps.drain(..timestamps.len() - keep);
         }
+        self.recent_timestamps = timestamps;
+    }
 
-        let mut data = Vec::new();
-        for tx in transactions {
-            data.extend_from_slice(tx.hash.as_slice());
+    // median timestamp of the latest blocks, None without blocks or with the check off
+    pub fn median_time(&self) -> Option<u64> {
+        if self.config.median_time_blocks == 0 || self.recent_timestamps.is_empty() {
+            return None;
         }
-        keccak256(data)
+        let mut timestamps = self.recent_timestamps.clone();
+        timestamps.sort_unstable();
+        Some(timestamps[timestamps.len() / 2])
+    }
+
+    // earliest timestamp the next block may have
+    pub fn min_timestamp(&self) -> u64 {
+        let parent = self.recent_timestamps.last().copied().unwrap_or_default();
+        parent.max(self.median_time().unwrap_or_default())
+    }
+
+    pub fn head(&self) -> ConsensusHead {
+        ConsensusHead {
+            number: self.current_block_number,
+            hash: self.current_block_hash,
+            slot: self.current_slot,
+            timing: self.head_timing,
+            timestamps: self.recent_timestamps.clone(),
+        }
+    }
+
+    // move the head back to an earlier block, with the block randomness it had then
+    pub fn rewind(&mut self, head: &ConsensusHead, randomness: BlockRandomness) {
+        self.current_block_number = head.number;
+        self.current_block_hash = head.hash;
+        self.current_slot = head.slot;
+        self.head_timing = head.timing;
+        self.recent_timestamps = head.timestamps.clone();
+        self.restore_block_randomness(randomness);
+    }
+
+    // apply network addresses registered on-chain to the validator set
+    pub fn apply_network_registrations(&mut self, registrations: &[(Address, String)]) {
+        let validator_set = self.proposer_selection.validator_set_mut();
+
+        for (address, multiaddr) in registrations {
+            if validator_set.set_network_address(address, multiaddr.clone()) {
+                println!("📇 Validator {} network address: {}", address, multiaddr);
+            } else {
+                println!("Ignoring network address of non-validator {}", address);
+            }
+        }
+    }
+
+    // apply stake changes queued during the epoch that just ended, `slot` starts the new one
+    // exit requests leave the set `EXIT_DELAY_SLOTS` later
+    pub fn settle_stake_changes(
+        &mut self,
+        pending: &BTreeMap<Address, PendingStake>,
+        slot: u64,
+    ) -> Vec<StakeSettlement> {
+        let validator_set = self.proposer_selection.validator_set_mut();
+
+        pending
+            .iter()
+            .map(|(address, changes)| {
+                let mut settlement = validator_set.settle_stake(*address, changes);
+                let exit_slot = slot + EXIT_DELAY_SLOTS;
+                if changes.exit && validator_set.request_exit(address, exit_slot) {
+                    settlement.exit_slot = Some(exit_slot);
+                }
+                settlement
+            })
+            .collect()
+    }
+
+    // take validators whose exit delay passed by `slot` out of the set
+    pub fn process_exits(&mut self, slot: u64) -> Vec<ValidatorExit> {
+        self.proposer_selection
+            .validator_set_mut()
+            .process_exits(slot)
+    }
+
+    // slash the offenders of double proposal and double vote evidence, in order
+    pub fn slash_offenders<E: SlashableEvidence>(
+        &mut self,
+        evidence: &[E],
+        config: &SlashingConfig,
+    ) -> Vec<SlashOutcome> {
+        let validator_set = self.proposer_selection.validator_set_mut();
+
+        evidence
+            .iter()
+            .filter_map(|e| {
+                validator_set.slash(&e.offender(), config.slash_percent, config.max_slashes)
+            })
+            .collect()
+    }
+
+    // proposer scheduled for a slot, None without active validators
+    pub fn scheduled_proposer(&self, slot: u64) -> Option<Address> {
+        self.proposer_selection.selector_proposer(slot).ok()
+    }
+
+    // proposers of the `n_slots` slots after the current one, stopping at the end of the
+    // next epoch: the epochs after it are seeded by blocks that don't exist yet
+    pub fn upcoming_proposers(&self, n_slots: u64) -> Result<Vec<ProposerDuty>> {
+        let current_slot = self.calculate_current_slot()?;
+        let horizon = self
+            .config
+            .epoch_start_slot(self.config.epoch_of(current_slot) + 2);
+        let last_slot = current_slot.saturating_add(n_slots).min(horizon - 1);
+        (current_slot + 1..=last_slot)
+            .map(|slot| {
+                let proposer = self
+                    .proposer_selection
+                    .selector_proposer(slot)
+                    .map_err(|e| anyhow!("Proposer selection failed: {:?}", e))?;
+                Ok(ProposerDuty {
+                    slot,
+                    epoch: self.config.epoch_of(slot),
+                    proposer,
+                })
+            })
+            .collect()
+    }
+
+    // proposers of a whole epoch, None without active validators or for epochs too old
+    pub fn epoch_schedule(&self, epoch: u64) -> Option<Arc<EpochSchedule>> {
+        self.proposer_selection.epoch_schedule(epoch).ok()
+    }
+
+    // seed `epoch`'s schedule with the hashes of every block committed so far
+    pub fn seed_epoch_from_blocks(&mut self, epoch: u64) -> B256 {
+        let randomness = self.block_randomness.seed_epoch(epoch);
+        self.proposer_selection
+            .set_block_randomness(epoch, randomness);
+        randomness
+    }
+
+    pub fn block_randomness(&self) -> &BlockRandomness {
+        &self.block_randomness
+    }
+
+    // resume the block randomness stored before a restart
+    pub fn restore_block_randomness(&mut self, randomness: BlockRandomness) {
+        for (&epoch, &seed) in &randomness.epochs {
+            self.proposer_selection.set_block_randomness(epoch, seed);
+        }
+        self.block_randomness = randomness;
+    }
+
+    // the validator set and its frozen copies, as written to storage
+    pub fn stored_validator_set(&self) -> StoredValidatorSet {
+        StoredValidatorSet {
+            validators: self
+                .validator_set()
+                .validators()
+                .into_iter()
+                .cloned()
+                .collect(),
+            snapshots: self.proposer_selection.validator_snapshots().clone(),
+        }
+    }
+
+    // resume the validator set stored before a restart, in place of the genesis validators
+    pub fn restore_validator_set(&mut self, stored: StoredValidatorSet) {
+        let min_stake = self.validator_set().min_stake();
+        self.proposer_selection.restore_validators(
+            ValidatorSet::from_validators(min_stake, stored.validators),
+            stored.snapshots,
+        );
+    }
+
+    // changes made to the validator set so far reach the schedule from `from_epoch` on
+    pub fn freeze_validator_set(&mut self, from_epoch: u64) {
+        self.proposer_selection.freeze_validator_set(from_epoch);
+    }
+
+    // mix external randomness into an epoch's proposer schedule
+    pub fn set_epoch_randomness(&mut self, epoch: u64, randomness: B256) {
+        self.proposer_selection
+            .set_epoch_randomness(epoch, randomness);
+    }
+
+    pub fn has_epoch_randomness(&self, epoch: u64) -> bool {
+        self.proposer_selection.has_epoch_randomness(epoch)
+    }
+
+    pub fn randomness_epochs(&self) -> Vec<u64> {
+        self.proposer_selection.randomness_epochs()
+    }
+
+    // start of an epoch on the fixed slot clock, the same on every node with our genesis
+    pub fn nominal_epoch_start(&self, epoch: u64) -> SystemTime {
+        self.slot_clock
+            .slot_start(self.config.epoch_start_slot(epoch))
+    }
+
+    // address of the validator key this node signs with
+    pub fn local_validator(&self) -> Option<Address> {
+        self.local_keypair.as_ref().map(|keypair| keypair.address)
+    }
+
+    // what the local validator does in `slot`, every active validator proposes when
+    // selected and attests otherwise, None without a key or an active validator
+    pub fn role_in_slot(&self, slot: u64) -> Option<ValidatorRole> {
+        let validator = self.local_validator()?;
+        if !self.validator_set().is_active_validator(&validator) {
+            return None;
+        }
+        match self.scheduled_proposer(slot) == Some(validator) {
+            true => Some(ValidatorRole::Proposer),
+            false => Some(ValidatorRole::Attestor),
+        }
+    }
+
+    // access current validator set
+    pub fn validator_set(&self) -> &ValidatorSet {
+        self.proposer_selection.validator_set()
+    }
+
+    // membership changes decided by consensus, the current epoch keeps the proposers it
+    // started with, changes count from the set frozen at the next epoch close
+    pub fn validator_set_mut(&mut self) -> &mut ValidatorSet {
+        self.proposer_selection.validator_set_mut()
+    }
+
+    // slot of the best block we have
+    pub fn head_slot(&self) -> u64 {
+        self.current_slot
+    }
+
+    // empty slots between the head and a block for `slot`, their proposers were offline
+    pub fn skipped_slots(&self, slot: u64) -> u64 {
+        slot.saturating_sub(self.current_slot + 1)
+    }
+
+    // number and hash of the best block we have
+    pub fn head_block(&self) -> (u64, B256) {
+        (self.current_block_number, self.current_block_hash)
+    }
+
+    // get slot duration, on adaptive chains the one of the slots after the head
+    pub fn slot_duration(&self) -> Duration {
+        match &self.adaptive_slots {
+            Some(adaptive) => Duration::from_millis(adaptive.next_duration_ms(&self.head_timing())),
+            None => self.slot_clock.slot_duration(),
+        }
+    }
+
+    // the fixed slot clock, adaptive chains only use it until their first adaptive block
+    pub fn slot_clock(&self) -> SlotClock {
+        self.slot_clock
+    }
+
+    // when the service checks whether to propose: at every slot start, or on adaptive
+    // chains often enough to not miss a slot once slots get shorter
+    pub fn proposal_ticks(&self) -> SlotTicks {
+        match &self.adaptive_slots {
+            Some(adaptive) => SlotClock::new(
+                self.slot_clock.genesis_time(),
+                Duration::from_millis(adaptive.config().min_slot_ms / 4),
+            )
+            .ticks(),
+            None => self.slot_clock.ticks(),
+        }
+    }
+
+    // wall clock time a slot starts at on this node
+    pub fn slot_start(&self, slot: u64) -> SystemTime {
+        match &self.adaptive_slots {
+            Some(adaptive) => {
+                let start_ms = adaptive.slot_start_ms(&self.head_timing(), self.current_slot, slot);
+                UNIX_EPOCH + Duration::from_millis(start_ms)
+            }
+            None => self.slot_clock.slot_start(slot),
+        }
+    }
+
+    // head timing, the fixed slot clock stands in for heads that don't carry one
+    fn head_timing(&self) -> SlotTiming {
+        self.head_timing.unwrap_or_else(|| SlotTiming {
+            slot_start_ms: unix_millis(self.slot_clock.slot_start(self.current_slot)),
+            slot_duration_ms: self.slot_clock.slot_duration().as_millis() as u64,
+            propagation_ms: 0,
+        })
+    }
+
+    // slot for the current wall clock time
+    pub fn current_slot(&self) -> Result<u64> {
+        self.calculate_current_slot()
     }
 
     fn calculate_current_slot(&self) -> Result<u64> {
-        let elapsed = SystemTime::now().duration_since(self.genesis_time)?;
-        Ok(elapsed.as_secs() / self.slot_duration.as_secs())
+        if let Some(adaptive) = &self.adaptive_slots {
+            let now_ms = unix_millis(SystemTime::now());
+            return Ok(adaptive.slot_at(&self.head_timing(), self.current_slot, now_ms));
+        }
+        self.slot_clock
+            .current_slot()
+            .ok_or_else(|| anyhow!("Genesis time is in the future"))
     }
 }
@@ -0,0 +1,141 @@
+use alloy::primitives::{Address, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use super::config::ConsensusConfig;
+
+// a block committed during the epoch, as seen by the accounting
+#[derive(Debug, Clone)]
+pub struct EpochProposal {
+    pub slot: u64,
+    pub proposer: Address,
+    // tips its transactions paid the proposer, failed ones included
+    pub fees: U256,
+    // minted to the proposer, see `BlockRewardConfig`
+    pub block_reward: U256,
+}
+
+// what a validator did and earned in one epoch, for staking dashboards
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EpochStatement {
+    pub validator: Address,
+    pub epoch: u64,
+    pub start_slot: u64,
+    pub end_slot: u64, // inclusive
+    pub proposals_made: u64,
+    // slots the validator was scheduled for without a committed block
+    pub proposals_missed: u64,
+    // accept votes for blocks of this epoch, as received by this node
+    pub attestations_included: u64,
+    // block rewards and fees of the validator's blocks
+    pub rewards: U256,
+    // stake burned for double proposals settled with this epoch
+    pub penalties: U256,
+    pub end_stake: u64,
+}
+
+// build one statement per validator from the epoch's schedule, blocks and attestations
+pub fn epoch_statements(
+    config: &ConsensusConfig,
+    epoch: u64,
+    schedule: &[(u64, Address)],
+    proposals: &[EpochProposal],
+    attestations: &HashMap<Address, u64>,
+    validators: &[(Address, u64)],
+    penalties: &HashMap<Address, u64>,
+) -> Vec<EpochStatement> {
+    validators
+        .iter()
+        .map(|(validator, stake)| {
+            let made: Vec<&EpochProposal> = proposals
+                .iter()
+                .filter(|p| p.proposer == *validator)
+                .collect();
+            let missed = schedule
+                .iter()
+                .filter(|(slot, proposer)| {
+                    proposer == validator && !made.iter().any(|p| p.slot == *slot)
+                })
+                .count();
+
+            EpochStatement {
+                validator: *validator,
+                epoch,
+                start_slot: config.epoch_start_slot(epoch),
+                end_slot: config.epoch_end_slot(epoch),
+                proposals_made: made.len() as u64,
+                proposals_missed: missed as u64,
+                attestations_included: attestations.get(validator).copied().unwrap_or(0),
+                rewards: made.iter().map(|p| p.fees + p.block_reward).sum(),
+                penalties: U256::from(penalties.get(validator).copied().unwrap_or(0)),
+                end_stake: *stake,
+            }
+        })
+        .collect()
+}
+
+// what a committed block adds to the epoch rollup
+#[derive(Debug, Clone)]
+pub struct EpochBlockUsage {
+    pub gas_used: U256,
+    // sender of every transaction in the block
+    pub senders: Vec<Address>,
+}
+
+// chain wide totals of one closed epoch, so dashboards don't scan its blocks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EpochStats {
+    pub epoch: u64,
+    pub start_slot: u64,
+    pub end_slot: u64, // inclusive
+    pub blocks: u64,
+    // scheduled slots without a committed block
+    pub missed_slots: u64,
+    pub transactions: u64,
+    pub total_gas: U256,
+    pub unique_senders: u64,
+    // gas used over the block gas limit, averaged over the epoch's blocks, 0.0 to 1.0
+    pub avg_block_fullness: f64,
+    pub active_validators: u64,
+    // active validators that proposed a block or had an accept vote received
+    pub participating_validators: u64,
+}
+
+// roll up the epoch's blocks, participation comes from the validator statements
+pub fn epoch_stats(
+    config: &ConsensusConfig,
+    epoch: u64,
+    statements: &[EpochStatement],
+    blocks: &[EpochBlockUsage],
+    block_gas_limit: U256,
+) -> EpochStats {
+    let senders: HashSet<&Address> = blocks.iter().flat_map(|b| &b.senders).collect();
+
+    let avg_block_fullness = if blocks.is_empty() || block_gas_limit.is_zero() {
+        0.0
+    } else {
+        let limit = block_gas_limit.saturating_to::<u64>() as f64;
+        blocks
+            .iter()
+            .map(|b| b.gas_used.saturating_to::<u64>() as f64 / limit)
+            .sum::<f64>()
+            / blocks.len() as f64
+    };
+
+    EpochStats {
+        epoch,
+        start_slot: config.epoch_start_slot(epoch),
+        end_slot: config.epoch_end_slot(epoch),
+        blocks: blocks.len() as u64,
+        missed_slots: statements.iter().map(|s| s.proposals_missed).sum(),
+        transactions: blocks.iter().map(|b| b.senders.len() as u64).sum(),
+        total_gas: blocks.iter().map(|b| b.gas_used).sum(),
+        unique_senders: senders.len() as u64,
+        avg_block_fullness,
+        active_validators: statements.len() as u64,
+        participating_validators: statements
+            .iter()
+            .filter(|s| s.proposals_made > 0 || s.attestations_included > 0)
+            .count() as u64,
+    }
+}
@@ -0,0 +1,39 @@
+use alloy::primitives::B256;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckpointStatus {
+    // the epoch closed, no certificate covers its checkpoint block yet
+    Pending,
+    // a finality certificate covers the checkpoint block or a later canonical block
+    Justified,
+    // justified, and the next epoch's checkpoint was justified on top of it
+    Finalized,
+}
+
+// last canonical block of a closed epoch, the block before the epoch's first slot
+// when the epoch had none; closed epochs are never reorged, so neither is this
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EpochCheckpoint {
+    pub epoch: u64,
+    pub number: u64,
+    pub hash: B256,
+    pub slot: u64,
+    pub status: CheckpointStatus,
+}
+
+impl EpochCheckpoint {
+    // certificates cover every ancestor of their block, so the highest certified block
+    // justifies all checkpoints at or below it
+    pub fn covered_by(&self, certified_number: u64) -> bool {
+        self.number <= certified_number
+    }
+}
+
+// latest justified and finalized epoch checkpoints, `speed_getFinalityCheckpoints`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FinalityCheckpoints {
+    pub justified: Option<EpochCheckpoint>,
+    pub finalized: Option<EpochCheckpoint>,
+}
@@ -1,5 +1,9 @@
+use alloy::primitives::Address;
+
+#[derive(Debug, PartialEq)]
 pub enum StakeError {
     InsufficientStake,
+    AlreadyValidator,
 }
 
 #[derive(Debug)]
@@ -10,4 +14,30 @@ pub enum ConsensusError {
     SigningFailed(String),
 }
 
-pub enum ValidatorError {}
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ValidatorError {
+    #[error("{0} is not a validator")]
+    UnknownValidator(Address),
+    #[error("Stake {stake} is below the minimum of {min_stake}")]
+    InsufficientStake { stake: u64, min_stake: u64 },
+    #[error("Validator {0} is the last active validator")]
+    LastActiveValidator(Address),
+    #[error("Validator {0} is exiting")]
+    Exiting(Address),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BeaconError {
+    #[error("Invalid beacon public key")]
+    InvalidPublicKey,
+    #[error("Beacon round {round} is not valid hex")]
+    InvalidEncoding { round: u64 },
+    #[error("Beacon round {round} has no previous signature")]
+    MissingPreviousSignature { round: u64 },
+    #[error("Beacon round {round} signature verification failed")]
+    InvalidSignature { round: u64 },
+    #[error("Beacon round {round} randomness is not the hash of its signature")]
+    RandomnessMismatch { round: u64 },
+    #[error("Requested beacon round {requested}, got {got}")]
+    UnexpectedRound { requested: u64, got: u64 },
+}
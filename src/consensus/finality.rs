@@ -0,0 +1,167 @@
+use alloy::primitives::{Address, B256, keccak256};
+use alloy_signer::Signature;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use super::config::Quorum;
+use crate::core::BlockHeader;
+use crate::{Attestation, AttestationVote};
+
+// message hash a validator signs when voting on a block
+pub fn attestation_hash(block_hash: &B256, vote: &AttestationVote) -> B256 {
+    keccak256(format!("ATTEST:{}:{:?}", hex::encode(block_hash), vote).as_bytes())
+}
+
+// keccak of address (20 bytes) and stake (u64 big endian) per validator, sorted by address
+pub fn validator_set_commitment(validators: &[CertifiedValidator]) -> B256 {
+    let mut sorted = validators.to_vec();
+    sorted.sort_by_key(|v| v.address);
+
+    let mut data = Vec::with_capacity(sorted.len() * 28);
+    for validator in sorted {
+        data.extend_from_slice(validator.address.as_slice());
+        data.extend_from_slice(&validator.stake.to_be_bytes());
+    }
+    keccak256(data)
+}
+
+// accepting stake that makes a block safe, more than half of `total_stake`
+pub fn safe_quorum(total_stake: u64) -> u64 {
+    total_stake / 2 + 1
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CertifiedValidator {
+    pub address: Address,
+    pub stake: u64,
+}
+
+// an accept vote, signed over `attestation_hash(block_hash, Accept)`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CertifiedAttestation {
+    pub validator: Address,
+    pub signature: Signature,
+}
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum FinalityError {
+    #[error("Header hashes to {actual}, certificate is for {expected}")]
+    HashMismatch { expected: B256, actual: B256 },
+    #[error("Validator set commitment does not match the listed validators")]
+    CommitmentMismatch,
+    #[error("Proposer {0} signature is missing, invalid or not from the validator set")]
+    InvalidProposerSignature(Address),
+    #[error("Attestation of {0} is invalid, duplicated or not from the validator set")]
+    InvalidAttestation(Address),
+    #[error("{stake} of {quorum} stake signed, short of finality")]
+    NoQuorum { stake: u64, quorum: u64 },
+}
+
+// proof that a quorum of the validator set accepted a block, checkable without a node
+// a verifier trusts `validator_set_commitment` from elsewhere and calls `verify`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinalityCertificate {
+    pub block_hash: B256,
+    // carries the proposer's signature over the block hash
+    pub header: BlockHeader,
+    // sorted by validator, the proposer is not repeated here
+    pub attestations: Vec<CertifiedAttestation>,
+    // active validators when the certificate was assembled, sorted by address
+    pub validators: Vec<CertifiedValidator>,
+    pub validator_set_commitment: B256,
+    // share of the listed stake that signed, the chain's `ConsensusConfig::quorum`
+    // a verifier checks it is the chain's like the commitment; 2/3 for older certificates
+    #[serde(default)]
+    pub quorum: Quorum,
+}
+
+impl FinalityCertificate {
+    // keep valid accept votes of the set, None while they fall short of `quorum`
+    pub fn assemble(
+        header: BlockHeader,
+        attestations: &[Attestation],
+        mut validators: Vec<CertifiedValidator>,
+        quorum: Quorum,
+    ) -> Option<Self> {
+        validators.sort_by_key(|v| v.address);
+        let block_hash = header.hash();
+        let accept = attestation_hash(&block_hash, &AttestationVote::Accept);
+
+        let mut certified: Vec<CertifiedAttestation> = Vec::new();
+        for attestation in attestations {
+            let validator = attestation.validator_id;
+            let valid = attestation.vote == AttestationVote::Accept
+                && validator != header.proposer
+                && validators.iter().any(|v| v.address == validator)
+                && !certified.iter().any(|a| a.validator == validator)
+                && matches!(
+                    attestation.signature.recover_address_from_prehash(&accept),
+                    Ok(signer) if signer == validator
+                );
+            if valid {
+                certified.push(CertifiedAttestation {
+                    validator,
+                    signature: attestation.signature,
+                });
+            }
+        }
+        certified.sort_by_key(|a| a.validator);
+
+        let certificate = Self {
+            block_hash,
+            validator_set_commitment: validator_set_commitment(&validators),
+            header,
+            attestations: certified,
+            validators,
+            quorum,
+        };
+        certificate.verify().ok().map(|_| certificate)
+    }
+
+    // check every signature against the listed set and that their stake reaches a quorum
+    pub fn verify(&self) -> Result<(), FinalityError> {
+        let actual = self.header.hash();
+        if actual != self.block_hash {
+            return Err(FinalityError::HashMismatch {
+                expected: self.block_hash,
+                actual,
+            });
+        }
+        if validator_set_commitment(&self.validators) != self.validator_set_commitment {
+            return Err(FinalityError::CommitmentMismatch);
+        }
+
+        let in_set = |address: &Address| self.validators.iter().any(|v| v.address == *address);
+        let proposer = self.header.proposer;
+        if !in_set(&proposer) || self.header.verify_signature().is_err() {
+            return Err(FinalityError::InvalidProposerSignature(proposer));
+        }
+
+        let accept = attestation_hash(&self.block_hash, &AttestationVote::Accept);
+        let mut signers = HashSet::from([proposer]);
+        for attestation in &self.attestations {
+            let signed = matches!(
+                attestation.signature.recover_address_from_prehash(&accept),
+                Ok(signer) if signer == attestation.validator
+            );
+            if !signed || !in_set(&attestation.validator) || !signers.insert(attestation.validator)
+            {
+                return Err(FinalityError::InvalidAttestation(attestation.validator));
+            }
+        }
+
+        let total_stake = self.validators.iter().map(|v| v.stake).sum();
+        let stake = self
+            .validators
+            .iter()
+            .filter(|v| signers.contains(&v.address))
+            .map(|v| v.stake)
+            .sum();
+        let quorum = self.quorum.of(total_stake);
+        if stake < quorum {
+            return Err(FinalityError::NoQuorum { stake, quorum });
+        }
+
+        Ok(())
+    }
+}
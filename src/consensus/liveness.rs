@@ -0,0 +1,61 @@
+use alloy::primitives::Address;
+use serde::{Deserialize, Serialize};
+
+use super::epoch::EpochStatement;
+use crate::LivenessConfig;
+
+// duties a validator had in a closed epoch and how many of them it missed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LivenessRecord {
+    pub validator: Address,
+    pub epoch: u64,
+    // slots it was scheduled for
+    pub proposal_duties: u64,
+    pub proposals_missed: u64,
+    // blocks of the epoch proposed by someone else, each one should get its accept vote
+    pub attestation_duties: u64,
+    pub attestations_missed: u64,
+}
+
+impl LivenessRecord {
+    pub fn duties(&self, config: &LivenessConfig) -> u64 {
+        match config.count_attestations {
+            true => self.proposal_duties + self.attestation_duties,
+            false => self.proposal_duties,
+        }
+    }
+
+    pub fn missed(&self, config: &LivenessConfig) -> u64 {
+        match config.count_attestations {
+            true => self.proposals_missed + self.attestations_missed,
+            false => self.proposals_missed,
+        }
+    }
+
+    // more than `max_missed_percent` of at least `min_duties` duties missed
+    pub fn is_offline(&self, config: &LivenessConfig) -> bool {
+        let duties = self.duties(config);
+        duties > 0
+            && duties >= config.min_duties
+            && self.missed(config) * 100 > duties * config.max_missed_percent
+    }
+}
+
+// liveness of every validator with a statement for the epoch, `blocks` committed in it
+pub fn liveness_records(statements: &[EpochStatement], blocks: u64) -> Vec<LivenessRecord> {
+    statements
+        .iter()
+        .map(|statement| {
+            let attestation_duties = blocks.saturating_sub(statement.proposals_made);
+            LivenessRecord {
+                validator: statement.validator,
+                epoch: statement.epoch,
+                proposal_duties: statement.proposals_made + statement.proposals_missed,
+                proposals_missed: statement.proposals_missed,
+                attestation_duties,
+                attestations_missed: attestation_duties
+                    .saturating_sub(statement.attestations_included),
+            }
+        })
+        .collect()
+}
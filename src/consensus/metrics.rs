@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// consensus counters since the node started, as `speed_getConsensusMetrics` reports them
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConsensusMetricsReport {
+    // slots the chain moved past without a block
+    pub missed_slots: u64,
+    // blocks this node proposed
+    pub proposals_produced: u64,
+    // blocks committed, ours and imported ones
+    pub blocks_committed: u64,
+    // accept votes counted for committed blocks
+    pub attestations_received: u64,
+    // one vote per active validator other than the proposer, for every committed block
+    pub attestations_expected: u64,
+    // received over expected, 0 before the first block
+    pub participation_rate: f64,
+    // blocks a finality certificate was stored for
+    pub quorums_reached: u64,
+    // slot start to certificate, null until the first certificate
+    pub avg_time_to_quorum_ms: Option<u64>,
+}
+
+// running totals behind `ConsensusMetricsReport`, kept in memory only
+#[derive(Debug, Clone, Default)]
+pub struct ConsensusMetrics {
+    missed_slots: u64,
+    proposals_produced: u64,
+    blocks_committed: u64,
+    attestations_received: u64,
+    attestations_expected: u64,
+    quorums_reached: u64,
+    time_to_quorum_ms: u64,
+}
+
+impl ConsensusMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // a committed block, the empty slots before it and the validators expected to vote on it
+    pub fn record_block(&mut self, skipped_slots: u64, attesters: usize) {
+        self.missed_slots += skipped_slots;
+        self.blocks_committed += 1;
+        self.attestations_expected += attesters as u64;
+    }
+
+    pub fn record_proposal(&mut self) {
+        self.proposals_produced += 1;
+    }
+
+    pub fn record_attestation(&mut self) {
+        self.attestations_received += 1;
+    }
+
+    // `elapsed` is measured from the start of the block's slot
+    pub fn record_quorum(&mut self, elapsed: Duration) {
+        self.quorums_reached += 1;
+        self.time_to_quorum_ms += elapsed.as_millis() as u64;
+    }
+
+    pub fn report(&self) -> ConsensusMetricsReport {
+        let participation_rate = match self.attestations_expected {
+            0 => 0.0,
+            expected => self.attestations_received as f64 / expected as f64,
+        };
+        ConsensusMetricsReport {
+            missed_slots: self.missed_slots,
+            proposals_produced: self.proposals_produced,
+            blocks_committed: self.blocks_committed,
+            attestations_received: self.attestations_received,
+            attestations_expected: self.attestations_expected,
+            participation_rate,
+            quorums_reached: self.quorums_reached,
+            avg_time_to_quorum_ms: (self.quorums_reached > 0)
+                .then(|| self.time_to_quorum_ms / self.quorums_reached),
+        }
+    }
+}
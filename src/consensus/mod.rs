@@ -1,9 +1,39 @@
+pub mod adaptive_slots;
+pub mod beacon;
+pub mod config;
 pub mod consensus_engine;
+pub mod epoch;
+pub mod epoch_checkpoint;
 pub mod error;
+pub mod finality;
+pub mod liveness;
+pub mod metrics;
 pub mod proposer;
+pub mod randomness;
+pub mod scores;
+pub mod settlement;
+pub mod signing_audit;
+pub mod slashing;
+pub mod slashing_protection;
+pub mod slot_clock;
 pub mod validator;
 
+pub use adaptive_slots::*;
+pub use beacon::*;
+pub use config::*;
 pub use consensus_engine::*;
+pub use epoch::*;
+pub use epoch_checkpoint::*;
 pub use error::*;
+pub use finality::*;
+pub use liveness::*;
+pub use metrics::*;
 pub use proposer::*;
+pub use randomness::*;
+pub use scores::*;
+pub use settlement::*;
+pub use signing_audit::*;
+pub use slashing::*;
+pub use slashing_protection::*;
+pub use slot_clock::*;
 pub use validator::*;
@@ -1,54 +1,263 @@
+use super::beacon::mix_epoch_seed;
 use super::error::ConsensusError;
-use crate::consensus::ValidatorSet;
-use alloy::primitives::Address;
+use crate::SLOTS_PER_EPOCH;
+use crate::consensus::{Validator, ValidatorSet};
+use alloy::primitives::{Address, B256};
 use rand_chacha::ChaCha20Rng;
 use rand_core::SeedableRng;
 use rand_core::TryRngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+// epochs a beacon mixed seed is kept for, the head's and the ones around it
+const EPOCH_SEEDS_KEPT: u64 = 4;
+
+// frozen validator sets kept, each covers at least one epoch
+const VALIDATOR_SNAPSHOTS_KEPT: usize = 4;
+
+// computed epoch schedules kept
+const EPOCH_SCHEDULES_KEPT: usize = 4;
+
+// proposer of every slot of an epoch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EpochSchedule {
+    pub epoch: u64,
+    pub start_slot: u64,
+    // one proposer per slot of the epoch, the first for `start_slot`
+    pub proposers: Vec<Address>,
+}
+
+impl EpochSchedule {
+    // proposer of a slot, None if the slot is in another epoch
+    pub fn proposer(&self, slot: u64) -> Option<Address> {
+        let offset = slot.checked_sub(self.start_slot)?;
+        self.proposers.get(offset as usize).copied()
+    }
+}
+
+// proposer of one upcoming slot, see `ConsensusEngine::upcoming_proposers`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposerDuty {
+    pub slot: u64,
+    pub epoch: u64,
+    pub proposer: Address,
+}
+
+// picks the proposer of a slot among the active validators
+// every validator of a chain must run the same election, embedders can bring their own
+pub trait ProposerElection: Send + Sync {
+    // `seed` is unique to the slot, `validators` is never empty and sorted by address
+    fn elect(&self, slot: u64, seed: [u8; 32], validators: &[&Validator]) -> Address;
+
+    // settings added to the chain spec hash, so nodes electing differently don't agree on it
+    fn chain_spec_bytes(&self) -> Vec<u8> {
+        Vec::new()
+    }
+}
+
+// the default election, chance proportional to stake
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StakeWeightedElection;
+
+impl ProposerElection for StakeWeightedElection {
+    fn elect(&self, _slot: u64, seed: [u8; 32], validators: &[&Validator]) -> Address {
+        let mut rng = ChaCha20Rng::from_seed(seed);
+
+        // Generate deterministic random value without gen_range
+        let random_bytes = rng.try_next_u64().unwrap();
+
+        // Weighted random selection based on stake
+        let total_stake: u64 = validators.iter().map(|v| v.staked_amount).sum();
+        let random_stake = random_bytes % total_stake;
+
+        let mut cumulative_stake = 0;
+
+        for validator in validators {
+            cumulative_stake += validator.staked_amount;
+
+            if random_stake < cumulative_stake {
+                return validator.address;
+            }
+        }
+
+        unreachable!("Should have selected a validator");
+    }
+}
 
 pub struct ProposerSelection {
     validator_set: ValidatorSet,
     randomness_seed: [u8; 32], // Derived from previous block
+    // epoch -> beacon randomness mixed into its seed
+    epoch_seeds: BTreeMap<u64, B256>,
+    // epoch -> block hash randomness mixed into its seed, before the beacon's
+    block_randomness: BTreeMap<u64, B256>,
+    // first epoch -> active validators the schedule uses from that epoch on
+    // changes to the validator set only reach the schedule once frozen here
+    validator_snapshots: BTreeMap<u64, Vec<Validator>>,
+    // epoch -> schedule computed from its snapshot and seed
+    schedules: Mutex<BTreeMap<u64, Arc<EpochSchedule>>>,
+    election: Arc<dyn ProposerElection>,
+    slots_per_epoch: u64,
 }
 
 impl ProposerSelection {
     // instantiate a new proposerselection
     pub fn new(validator_set: ValidatorSet, randomness_seed: [u8; 32]) -> Self {
-        Self {
+        let mut selection = Self {
             validator_set,
             randomness_seed,
+            epoch_seeds: BTreeMap::new(),
+            block_randomness: BTreeMap::new(),
+            validator_snapshots: BTreeMap::new(),
+            schedules: Mutex::new(BTreeMap::new()),
+            election: Arc::new(StakeWeightedElection),
+            slots_per_epoch: SLOTS_PER_EPOCH,
+        };
+        selection.freeze_validator_set(0);
+        selection
+    }
+
+    // replace the stake weighted election
+    pub fn set_election(&mut self, election: Arc<dyn ProposerElection>) {
+        self.election = election;
+        self.schedules.lock().unwrap().clear();
+    }
+
+    // epoch length of the chain, see `ConsensusConfig`
+    pub fn set_slots_per_epoch(&mut self, slots_per_epoch: u64) {
+        self.slots_per_epoch = slots_per_epoch.max(1);
+        self.schedules.lock().unwrap().clear();
+    }
+
+    // schedule epochs from `from_epoch` on with the validator set as it is now
+    pub fn freeze_validator_set(&mut self, from_epoch: u64) {
+        let active: Vec<Validator> = self
+            .validator_set
+            .get_active_validators()
+            .into_iter()
+            .cloned()
+            .collect();
+        self.validator_snapshots.insert(from_epoch, active);
+        while self.validator_snapshots.len() > VALIDATOR_SNAPSHOTS_KEPT {
+            self.validator_snapshots.pop_first();
         }
+        self.schedules
+            .lock()
+            .unwrap()
+            .retain(|&epoch, _| epoch < from_epoch);
     }
 
-    pub fn selector_proposer(&self, slot: u64) -> Result<Address, ConsensusError> {
-        let active_validators = self.validator_set.get_active_validators();
+    // frozen validator sets, by the first epoch they are scheduled for
+    pub fn validator_snapshots(&self) -> &BTreeMap<u64, Vec<Validator>> {
+        &self.validator_snapshots
+    }
+
+    // resume the validator set and its frozen copies stored before a restart
+    pub fn restore_validators(
+        &mut self,
+        validator_set: ValidatorSet,
+        snapshots: BTreeMap<u64, Vec<Validator>>,
+    ) {
+        self.validator_set = validator_set;
+        self.validator_snapshots = snapshots;
+        self.schedules.lock().unwrap().clear();
+    }
+
+    // proposers of every slot of an epoch, computed once
+    // epochs before the oldest kept validator snapshot can't be scheduled
+    pub fn epoch_schedule(&self, epoch: u64) -> Result<Arc<EpochSchedule>, ConsensusError> {
+        if let Some(schedule) = self.schedules.lock().unwrap().get(&epoch) {
+            return Ok(schedule.clone());
+        }
 
-        if active_validators.is_empty() {
+        let validators: Vec<&Validator> = self
+            .validator_snapshots
+            .range(..=epoch)
+            .next_back()
+            .map(|(_, validators)| validators.iter().collect())
+            .unwrap_or_default();
+        if validators.is_empty() {
             return Err(ConsensusError::NoActiveValidators);
         }
 
-        // Create deterministic randomness for this slot
+        let epoch_seed = self.epoch_seed(epoch);
+        let start_slot = epoch * self.slots_per_epoch;
+        let proposers = (start_slot..start_slot + self.slots_per_epoch)
+            .map(|slot| {
+                // Create deterministic randomness for this slot
+                let mut seed = epoch_seed;
+                seed[0..8].copy_from_slice(&slot.to_le_bytes());
+                self.election.elect(slot, seed, &validators)
+            })
+            .collect();
+        let schedule = Arc::new(EpochSchedule {
+            epoch,
+            start_slot,
+            proposers,
+        });
+
+        let mut schedules = self.schedules.lock().unwrap();
+        schedules.insert(epoch, schedule.clone());
+        while schedules.len() > EPOCH_SCHEDULES_KEPT {
+            schedules.pop_first();
+        }
+        Ok(schedule)
+    }
+
+    // base seed, mixed with the epoch's block randomness and then its beacon randomness
+    // when it has them
+    fn epoch_seed(&self, epoch: u64) -> [u8; 32] {
         let mut seed = self.randomness_seed;
-        seed[0..8].copy_from_slice(&slot.to_le_bytes());
+        for randomness in [&self.block_randomness, &self.epoch_seeds] {
+            if let Some(randomness) = randomness.get(&epoch) {
+                seed = mix_epoch_seed(seed, epoch, *randomness);
+            }
+        }
+        seed
+    }
 
-        let mut rng = ChaCha20Rng::from_seed(seed);
+    // schedule an epoch from the base seed mixed with external randomness
+    pub fn set_epoch_randomness(&mut self, epoch: u64, randomness: B256) {
+        self.epoch_seeds.insert(epoch, randomness);
+        self.schedules.lock().unwrap().remove(&epoch);
+        if let Some(&newest) = self.epoch_seeds.keys().next_back() {
+            self.epoch_seeds
+                .retain(|&kept, _| kept + EPOCH_SEEDS_KEPT > newest);
+        }
+    }
 
-        // Generate deterministic random value without gen_range
-        let random_bytes = rng.try_next_u64().unwrap();
+    // schedule an epoch from the base seed mixed with the hashes of the blocks before it
+    pub fn set_block_randomness(&mut self, epoch: u64, randomness: B256) {
+        self.block_randomness.insert(epoch, randomness);
+        self.schedules.lock().unwrap().remove(&epoch);
+        if let Some(&newest) = self.block_randomness.keys().next_back() {
+            self.block_randomness
+                .retain(|&kept, _| kept + EPOCH_SEEDS_KEPT > newest);
+        }
+    }
 
-        // Weighted random selection based on stake
-        let total_stake: u64 = active_validators.iter().map(|v| v.staked_amount).sum();
-        let random_stake = random_bytes % total_stake;
+    pub fn has_epoch_randomness(&self, epoch: u64) -> bool {
+        self.epoch_seeds.contains_key(&epoch)
+    }
 
-        let mut cumulative_stake = 0;
+    // epochs scheduled with external randomness, oldest first
+    pub fn randomness_epochs(&self) -> Vec<u64> {
+        self.epoch_seeds.keys().copied().collect()
+    }
 
-        for validator in active_validators {
-            cumulative_stake += validator.staked_amount;
+    // read access to the validator set
+    pub fn validator_set(&self) -> &ValidatorSet {
+        &self.validator_set
+    }
 
-            if random_stake < cumulative_stake {
-                return Ok(validator.address);
-            }
-        }
+    // write access to the validator set, for membership changes
+    pub fn validator_set_mut(&mut self) -> &mut ValidatorSet {
+        &mut self.validator_set
+    }
 
-        unreachable!("Should have selected a validator");
+    pub fn selector_proposer(&self, slot: u64) -> Result<Address, ConsensusError> {
+        let schedule = self.epoch_schedule(slot / self.slots_per_epoch)?;
+        Ok(schedule.proposer(slot).expect("slot is in its epoch"))
     }
 }
@@ -0,0 +1,35 @@
+use alloy::primitives::{B256, keccak256};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// epochs whose block randomness is kept, the head's and the ones around it
+const RANDOMNESS_EPOCHS_KEPT: usize = 4;
+
+// hashes of committed blocks folded into one value, every node following the chain
+// gets the same, no one knows it before the blocks are made
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BlockRandomness {
+    // keccak(accumulator || block hash) over every committed block
+    pub accumulator: B256,
+    // epoch -> accumulator its proposer schedule was seeded with
+    pub epochs: BTreeMap<u64, B256>,
+}
+
+impl BlockRandomness {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn mix(&mut self, block_hash: B256) {
+        self.accumulator = keccak256([self.accumulator.as_slice(), block_hash.as_slice()].concat());
+    }
+
+    // fix the accumulator as the randomness of `epoch`, returned for the schedule
+    pub fn seed_epoch(&mut self, epoch: u64) -> B256 {
+        self.epochs.insert(epoch, self.accumulator);
+        while self.epochs.len() > RANDOMNESS_EPOCHS_KEPT {
+            self.epochs.pop_first();
+        }
+        self.accumulator
+    }
+}
@@ -0,0 +1,122 @@
+use alloy::primitives::Address;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::liveness::LivenessRecord;
+
+// running totals of one validator over every epoch this node closed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidatorScore {
+    pub proposals_made: u64,
+    pub proposals_missed: u64,
+    // accept votes received for other validators' blocks, out of `attestation_duties`
+    pub attestations_included: u64,
+    pub attestation_duties: u64,
+    // receive latency of its gossiped blocks after their slot start, on our clock
+    pub latency_total_ms: u64,
+    pub latency_samples: u64,
+    pub epochs: u64,
+    pub last_epoch: Option<u64>,
+}
+
+// one validator's score, `speed_getValidatorScores`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorScoreReport {
+    pub validator: Address,
+    pub proposals_made: u64,
+    pub proposals_missed: u64,
+    pub attestations_included: u64,
+    pub attestation_duties: u64,
+    // 1.0 without duties of the kind
+    pub proposal_rate: f64,
+    pub attestation_rate: f64,
+    // null until one of its blocks was gossiped to us
+    pub avg_latency_ms: Option<u64>,
+    // proposal rate times attestation rate, 0.0 to 1.0, latency is reported but not scored
+    // since every node measures its own
+    pub score: f64,
+    pub epochs: u64,
+    pub last_epoch: Option<u64>,
+}
+
+impl ValidatorScore {
+    pub fn report(&self, validator: Address) -> ValidatorScoreReport {
+        let proposal_rate = rate(
+            self.proposals_made,
+            self.proposals_made + self.proposals_missed,
+        );
+        let attestation_rate = rate(self.attestations_included, self.attestation_duties);
+        ValidatorScoreReport {
+            validator,
+            proposals_made: self.proposals_made,
+            proposals_missed: self.proposals_missed,
+            attestations_included: self.attestations_included,
+            attestation_duties: self.attestation_duties,
+            proposal_rate,
+            attestation_rate,
+            avg_latency_ms: (self.latency_samples > 0)
+                .then(|| self.latency_total_ms / self.latency_samples),
+            score: proposal_rate * attestation_rate,
+            epochs: self.epochs,
+            last_epoch: self.last_epoch,
+        }
+    }
+}
+
+fn rate(done: u64, duties: u64) -> f64 {
+    match duties {
+        0 => 1.0,
+        duties => done.min(duties) as f64 / duties as f64,
+    }
+}
+
+// per-validator scores, folded in from the liveness records of every closed epoch
+// and kept in storage, for dashboards and reward scaling
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorScores {
+    scores: BTreeMap<Address, ValidatorScore>,
+}
+
+impl ValidatorScores {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // an epoch without blocks says nothing about who was offline, e.g. while we were, it is skipped
+    pub fn record_epoch(&mut self, records: &[LivenessRecord]) {
+        if records
+            .iter()
+            .all(|r| r.proposal_duties == r.proposals_missed)
+        {
+            return;
+        }
+        for record in records {
+            let score = self.scores.entry(record.validator).or_default();
+            score.proposals_made += record.proposal_duties - record.proposals_missed;
+            score.proposals_missed += record.proposals_missed;
+            score.attestations_included += record.attestation_duties - record.attestations_missed;
+            score.attestation_duties += record.attestation_duties;
+            score.epochs += 1;
+            score.last_epoch = Some(record.epoch);
+        }
+    }
+
+    // a block of `proposer` arrived `latency_ms` after its slot started, early blocks count as 0
+    pub fn record_latency(&mut self, proposer: Address, latency_ms: i64) {
+        let score = self.scores.entry(proposer).or_default();
+        score.latency_total_ms += latency_ms.max(0) as u64;
+        score.latency_samples += 1;
+    }
+
+    pub fn get(&self, validator: &Address) -> Option<&ValidatorScore> {
+        self.scores.get(validator)
+    }
+
+    // every scored validator, by address
+    pub fn report(&self) -> Vec<ValidatorScoreReport> {
+        self.scores
+            .iter()
+            .map(|(validator, score)| score.report(*validator))
+            .collect()
+    }
+}
@@ -0,0 +1,38 @@
+use alloy::primitives::{Address, U256};
+use serde::{Deserialize, Serialize};
+
+use super::epoch::EpochStatement;
+use crate::EpochRewardConfig;
+
+// balance changes of one validator for a closed epoch, applied to its account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EpochSettlement {
+    pub validator: Address,
+    pub epoch: u64,
+    // minted for the blocks it proposed in the epoch
+    pub reward: U256,
+    // burned for the slots it missed, never more than its balance once applied
+    pub penalty: U256,
+}
+
+// what every validator with a statement is owed or charged for the epoch, validators with
+// neither are left out; an epoch without blocks settles nothing, like for liveness
+// only committed blocks and the schedule count, so every node settles the same amounts
+pub fn epoch_settlements(
+    statements: &[EpochStatement],
+    config: &EpochRewardConfig,
+) -> Vec<EpochSettlement> {
+    if statements.iter().all(|s| s.proposals_made == 0) {
+        return Vec::new();
+    }
+    statements
+        .iter()
+        .map(|statement| EpochSettlement {
+            validator: statement.validator,
+            epoch: statement.epoch,
+            reward: config.proposal_reward * U256::from(statement.proposals_made),
+            penalty: config.inactivity_penalty * U256::from(statement.proposals_missed),
+        })
+        .filter(|s| !s.reward.is_zero() || !s.penalty.is_zero())
+        .collect()
+}
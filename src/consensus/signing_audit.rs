@@ -0,0 +1,245 @@
+use alloy::primitives::{Address, B256, keccak256};
+use alloy_signer::Signature;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use super::attestation_hash;
+use crate::{AttestationVote, unix_millis};
+
+// file name of the audit log under the data directory
+pub const SIGNING_AUDIT_FILE: &str = "signing_audit.jsonl";
+
+// what the validator key signed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SignedMessage {
+    Proposal,
+    Attestation { vote: AttestationVote },
+}
+
+impl SignedMessage {
+    // the hash the key signs for this message and block
+    pub fn signing_hash(&self, block_hash: &B256) -> B256 {
+        match self {
+            SignedMessage::Proposal => *block_hash,
+            SignedMessage::Attestation { vote } => attestation_hash(block_hash, vote),
+        }
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        match self {
+            SignedMessage::Proposal => vec![0u8],
+            SignedMessage::Attestation { vote } => {
+                let mut data = vec![1u8];
+                data.extend_from_slice(format!("{:?}", vote).as_bytes());
+                data
+            }
+        }
+    }
+}
+
+// one line of the audit log, chained to the previous line by `prev_hash`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SigningAuditEntry {
+    pub seq: u64,
+    pub at_ms: u64,
+    pub slot: u64,
+    pub block_hash: B256,
+    #[serde(flatten)]
+    pub message: SignedMessage,
+    pub signer: Address,
+    pub signature: Signature,
+    pub prev_hash: B256,
+    pub hash: B256,
+}
+
+impl SigningAuditEntry {
+    // keccak of every other field, the first entry of a log chains to zero
+    pub fn calculate_hash(&self) -> B256 {
+        let mut data = Vec::new();
+        data.extend_from_slice(self.prev_hash.as_slice());
+        data.extend_from_slice(&self.seq.to_be_bytes());
+        data.extend_from_slice(&self.at_ms.to_be_bytes());
+        data.extend_from_slice(&self.slot.to_be_bytes());
+        data.extend_from_slice(self.block_hash.as_slice());
+        data.extend_from_slice(&self.message.encode());
+        data.extend_from_slice(self.signer.as_slice());
+        data.extend_from_slice(&self.signature.as_bytes());
+        keccak256(&data)
+    }
+}
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum AuditError {
+    #[error("Line {line} is not an audit entry: {reason}")]
+    Malformed { line: usize, reason: String },
+    #[error("Entry {seq} follows entry {previous}, entries are missing")]
+    Gap { seq: u64, previous: u64 },
+    #[error("Entry {seq} does not chain to the entry before it")]
+    BrokenChain { seq: u64 },
+    #[error("Entry {seq} was modified, its hash does not match")]
+    HashMismatch { seq: u64 },
+    #[error("Entry {seq} is not signed by {signer}")]
+    BadSignature { seq: u64, signer: Address },
+}
+
+// result of checking a log, or an exported part of one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditSummary {
+    pub entries: usize,
+    pub first_seq: Option<u64>,
+    pub last_seq: Option<u64>,
+    // hash the first entry chains to, zero when the log starts at its first entry
+    pub anchor: B256,
+    // hash of the last entry, compare with a copy kept elsewhere to detect truncation
+    pub head: B256,
+}
+
+// check links, hashes and signatures of consecutive entries
+pub fn verify_entries(entries: &[SigningAuditEntry]) -> Result<AuditSummary, AuditError> {
+    let anchor = entries.first().map_or(B256::ZERO, |e| e.prev_hash);
+    let mut previous: Option<&SigningAuditEntry> = None;
+
+    for entry in entries {
+        if let Some(previous) = previous {
+            if entry.seq != previous.seq + 1 {
+                return Err(AuditError::Gap {
+                    seq: entry.seq,
+                    previous: previous.seq,
+                });
+            }
+            if entry.prev_hash != previous.hash {
+                return Err(AuditError::BrokenChain { seq: entry.seq });
+            }
+        } else if entry.seq == 0 && entry.prev_hash != B256::ZERO {
+            return Err(AuditError::BrokenChain { seq: entry.seq });
+        }
+        if entry.calculate_hash() != entry.hash {
+            return Err(AuditError::HashMismatch { seq: entry.seq });
+        }
+
+        let signing_hash = entry.message.signing_hash(&entry.block_hash);
+        match entry.signature.recover_address_from_prehash(&signing_hash) {
+            Ok(signer) if signer == entry.signer => {}
+            _ => {
+                return Err(AuditError::BadSignature {
+                    seq: entry.seq,
+                    signer: entry.signer,
+                });
+            }
+        }
+        previous = Some(entry);
+    }
+
+    Ok(AuditSummary {
+        entries: entries.len(),
+        first_seq: entries.first().map(|e| e.seq),
+        last_seq: entries.last().map(|e| e.seq),
+        anchor,
+        head: previous.map_or(anchor, |e| e.hash),
+    })
+}
+
+// read every entry of a log file, one json object per line
+pub fn read_audit_log<P: AsRef<Path>>(path: P) -> Result<Vec<SigningAuditEntry>> {
+    let path = path.as_ref();
+    let file =
+        File::open(path).with_context(|| format!("Failed to open audit log {}", path.display()))?;
+
+    let mut entries = Vec::new();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = serde_json::from_str(&line).map_err(|e| AuditError::Malformed {
+            line: index + 1,
+            reason: e.to_string(),
+        })?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+// append-only log of every signature the local validator produced
+// the file is opened on the first signature, a node that never signs leaves nothing behind
+pub struct SigningAuditLog {
+    path: PathBuf,
+    // next sequence number and the hash it chains to, once the file is open
+    tail: Option<(u64, B256)>,
+}
+
+impl SigningAuditLog {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            tail: None,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // write and sync one entry, it is on disk before the signature leaves the node
+    pub fn append(
+        &mut self,
+        slot: u64,
+        block_hash: B256,
+        message: SignedMessage,
+        signer: Address,
+        signature: Signature,
+    ) -> Result<SigningAuditEntry> {
+        let (seq, prev_hash) = match self.tail {
+            Some(tail) => tail,
+            None => self.resume()?,
+        };
+
+        let mut entry = SigningAuditEntry {
+            seq,
+            at_ms: unix_millis(SystemTime::now()),
+            slot,
+            block_hash,
+            message,
+            signer,
+            signature,
+            prev_hash,
+            hash: B256::ZERO,
+        };
+        entry.hash = entry.calculate_hash();
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open audit log {}", self.path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        file.sync_data()?;
+
+        self.tail = Some((seq + 1, entry.hash));
+        Ok(entry)
+    }
+
+    // continue the chain of an existing file, a log that fails verification is left untouched
+    fn resume(&self) -> Result<(u64, B256)> {
+        if !self.path.exists() {
+            if let Some(dir) = self.path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            return Ok((0, B256::ZERO));
+        }
+
+        let entries = read_audit_log(&self.path)?;
+        let summary = verify_entries(&entries).with_context(|| {
+            format!(
+                "Audit log {} failed verification, move it aside to start a new one",
+                self.path.display()
+            )
+        })?;
+        Ok((summary.last_seq.map_or(0, |seq| seq + 1), summary.head))
+    }
+}
@@ -0,0 +1,298 @@
+use alloy::primitives::{Address, B256};
+use alloy_signer::Signature;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use super::finality::attestation_hash;
+use crate::core::BlockHeader;
+use crate::{AttestationVote, SLOTS_PER_EPOCH};
+
+// slots a signed header is remembered for, a second block for the slot within it is caught
+pub const DOUBLE_PROPOSAL_WINDOW_SLOTS: u64 = 2 * SLOTS_PER_EPOCH;
+
+// two different blocks a proposer signed for the same slot, checkable without a node
+// both headers carry the proposer's signature over their hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoubleProposalEvidence {
+    pub proposer: Address,
+    pub slot: u64,
+    pub first: BlockHeader,
+    pub second: BlockHeader,
+}
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum EvidenceError {
+    #[error("Headers are from different proposers or slots")]
+    Mismatch,
+    #[error("Both headers are the same block {0}")]
+    SameBlock(B256),
+    #[error("Header {0} is not signed by its proposer")]
+    InvalidSignature(B256),
+    #[error("Votes are on blocks of different slots")]
+    SlotMismatch,
+    #[error("Vote on {0} is not signed by the validator")]
+    InvalidVoteSignature(B256),
+    #[error("Votes on {0} and {1} do not conflict")]
+    NoConflict(B256, B256),
+}
+
+impl DoubleProposalEvidence {
+    pub fn new(first: BlockHeader, second: BlockHeader) -> Result<Self, EvidenceError> {
+        let evidence = Self {
+            proposer: first.proposer,
+            slot: first.slot,
+            first,
+            second,
+        };
+        evidence.verify()?;
+        Ok(evidence)
+    }
+
+    // same proposer and slot, two block hashes, both signatures recover the proposer
+    pub fn verify(&self) -> Result<(), EvidenceError> {
+        for header in [&self.first, &self.second] {
+            if header.proposer != self.proposer || header.slot != self.slot {
+                return Err(EvidenceError::Mismatch);
+            }
+            if header.verify_signature().is_err() {
+                return Err(EvidenceError::InvalidSignature(header.hash()));
+            }
+        }
+        let hash = self.first.hash();
+        if hash == self.second.hash() {
+            return Err(EvidenceError::SameBlock(hash));
+        }
+        Ok(())
+    }
+}
+
+// an attestation as the validator signed it, with the header of the block it votes on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedVote {
+    pub header: BlockHeader,
+    pub vote: AttestationVote,
+    pub signature: Signature,
+}
+
+impl SignedVote {
+    pub fn block_hash(&self) -> B256 {
+        self.header.hash()
+    }
+
+    pub fn is_accept(&self) -> bool {
+        matches!(self.vote, AttestationVote::Accept)
+    }
+
+    // accept and reject of the same block, or accepts of two blocks
+    pub fn conflicts_with(&self, other: &SignedVote) -> bool {
+        if self.block_hash() == other.block_hash() {
+            self.is_accept() != other.is_accept()
+        } else {
+            self.is_accept() && other.is_accept()
+        }
+    }
+}
+
+// two votes a validator signed for blocks of the same slot that an honest validator
+// never signs together, checkable without a node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttestationEquivocationEvidence {
+    pub validator: Address,
+    pub slot: u64,
+    pub first: SignedVote,
+    pub second: SignedVote,
+}
+
+impl AttestationEquivocationEvidence {
+    pub fn new(
+        validator: Address,
+        first: SignedVote,
+        second: SignedVote,
+    ) -> Result<Self, EvidenceError> {
+        let evidence = Self {
+            validator,
+            slot: first.header.slot,
+            first,
+            second,
+        };
+        evidence.verify()?;
+        Ok(evidence)
+    }
+
+    // same slot, both signatures recover the validator, and the votes conflict
+    pub fn verify(&self) -> Result<(), EvidenceError> {
+        for vote in [&self.first, &self.second] {
+            if vote.header.slot != self.slot {
+                return Err(EvidenceError::SlotMismatch);
+            }
+            let block_hash = vote.block_hash();
+            let signer = vote
+                .signature
+                .recover_address_from_prehash(&attestation_hash(&block_hash, &vote.vote));
+            if signer.ok() != Some(self.validator) {
+                return Err(EvidenceError::InvalidVoteSignature(block_hash));
+            }
+        }
+        if !self.first.conflicts_with(&self.second) {
+            return Err(EvidenceError::NoConflict(
+                self.first.block_hash(),
+                self.second.block_hash(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+// an offence a validator is slashed for when the epoch of its slot closes
+pub trait SlashableEvidence {
+    fn offender(&self) -> Address;
+    fn slot(&self) -> u64;
+}
+
+impl SlashableEvidence for DoubleProposalEvidence {
+    fn offender(&self) -> Address {
+        self.proposer
+    }
+
+    fn slot(&self) -> u64 {
+        self.slot
+    }
+}
+
+impl SlashableEvidence for AttestationEquivocationEvidence {
+    fn offender(&self) -> Address {
+        self.validator
+    }
+
+    fn slot(&self) -> u64 {
+        self.slot
+    }
+}
+
+// evidence waiting for its epoch to close, whatever the offence
+#[derive(Debug, Clone)]
+pub enum SlashingEvidence {
+    DoubleProposal(DoubleProposalEvidence),
+    DoubleVote(AttestationEquivocationEvidence),
+}
+
+impl SlashableEvidence for SlashingEvidence {
+    fn offender(&self) -> Address {
+        match self {
+            Self::DoubleProposal(evidence) => evidence.offender(),
+            Self::DoubleVote(evidence) => evidence.offender(),
+        }
+    }
+
+    fn slot(&self) -> u64 {
+        match self {
+            Self::DoubleProposal(evidence) => evidence.slot(),
+            Self::DoubleVote(evidence) => evidence.slot(),
+        }
+    }
+}
+
+// stake taken from a validator per proven offence, burned
+pub fn slash_penalty(stake: u64, slash_percent: u64) -> u64 {
+    (stake as u128 * slash_percent.min(100) as u128 / 100) as u64
+}
+
+// stake each offender loses to `evidence`, slashes of one validator compound in order
+pub fn slash_penalties<E: SlashableEvidence>(
+    evidence: &[E],
+    stake_of: impl Fn(&Address) -> u64,
+    slash_percent: u64,
+) -> HashMap<Address, u64> {
+    let mut penalties: HashMap<Address, u64> = HashMap::new();
+    for e in evidence {
+        let offender = e.offender();
+        let slashed = penalties.entry(offender).or_default();
+        *slashed += slash_penalty(stake_of(&offender) - *slashed, slash_percent);
+    }
+    penalties
+}
+
+// first signed header seen per (proposer, slot) over the recent window
+#[derive(Debug, Default)]
+pub struct DoubleProposalDetector {
+    seen: HashMap<(Address, u64), BlockHeader>,
+    highest_slot: u64,
+}
+
+impl DoubleProposalDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // remember a header with a verified signature, evidence if the proposer already
+    // signed a different block for its slot
+    pub fn observe(&mut self, header: &BlockHeader) -> Option<DoubleProposalEvidence> {
+        if header.slot > self.highest_slot {
+            self.highest_slot = header.slot;
+            let oldest = self
+                .highest_slot
+                .saturating_sub(DOUBLE_PROPOSAL_WINDOW_SLOTS);
+            self.seen.retain(|(_, slot), _| *slot >= oldest);
+        }
+
+        match self.seen.get(&(header.proposer, header.slot)) {
+            Some(first) => DoubleProposalEvidence::new(first.clone(), header.clone()).ok(),
+            None => {
+                self.seen
+                    .insert((header.proposer, header.slot), header.clone());
+                None
+            }
+        }
+    }
+}
+
+// votes seen per (validator, slot) over the recent window, and the validators caught
+// signing conflicting ones, whose attestations are ignored from then on
+#[derive(Debug, Default)]
+pub struct AttestationEquivocationDetector {
+    seen: HashMap<(Address, u64), Vec<SignedVote>>,
+    equivocators: HashSet<Address>,
+    highest_slot: u64,
+}
+
+impl AttestationEquivocationDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // remember a vote with a verified signature, evidence if it conflicts with an earlier
+    // vote of the validator in the same slot
+    pub fn observe(
+        &mut self,
+        validator: Address,
+        vote: SignedVote,
+    ) -> Option<AttestationEquivocationEvidence> {
+        let slot = vote.header.slot;
+        if slot > self.highest_slot {
+            self.highest_slot = slot;
+            let oldest = self
+                .highest_slot
+                .saturating_sub(DOUBLE_PROPOSAL_WINDOW_SLOTS);
+            self.seen.retain(|(_, slot), _| *slot >= oldest);
+        }
+
+        let votes = self.seen.entry((validator, slot)).or_default();
+        if let Some(first) = votes.iter().find(|first| first.conflicts_with(&vote)) {
+            let evidence =
+                AttestationEquivocationEvidence::new(validator, first.clone(), vote).ok()?;
+            self.equivocators.insert(validator);
+            return Some(evidence);
+        }
+        let repeated = votes
+            .iter()
+            .any(|seen| seen.block_hash() == vote.block_hash() && seen.vote == vote.vote);
+        if !repeated {
+            votes.push(vote);
+        }
+        None
+    }
+
+    pub fn is_equivocator(&self, validator: &Address) -> bool {
+        self.equivocators.contains(validator)
+    }
+}
@@ -0,0 +1,57 @@
+use alloy::primitives::B256;
+use serde::{Deserialize, Serialize};
+
+// last slots the local validator signed for, persisted so a restarted node never signs twice
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SigningRecord {
+    pub last_proposed_slot: Option<u64>,
+    pub last_attested_slot: Option<u64>,
+    pub last_attested_block: Option<B256>,
+}
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum SlashingError {
+    #[error("Already proposed in slot {last}, refusing to sign a block for slot {slot}")]
+    DoubleProposal { slot: u64, last: u64 },
+    #[error("Already attested block {block} in slot {last}, refusing to attest slot {slot}")]
+    DoubleVote { slot: u64, last: u64, block: B256 },
+}
+
+impl SigningRecord {
+    // one block per slot, and never for a slot older than the last one
+    pub fn check_proposal(&self, slot: u64) -> Result<(), SlashingError> {
+        match self.last_proposed_slot {
+            Some(last) if slot <= last => Err(SlashingError::DoubleProposal { slot, last }),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn record_proposal(&mut self, slot: u64) {
+        self.last_proposed_slot = Some(slot);
+    }
+
+    // voting again for the same block is harmless, a different block in the slot is not
+    pub fn check_attestation(&self, slot: u64, block_hash: &B256) -> Result<(), SlashingError> {
+        let (Some(last), Some(block)) = (self.last_attested_slot, self.last_attested_block) else {
+            return Ok(());
+        };
+        if slot < last || (slot == last && block != *block_hash) {
+            return Err(SlashingError::DoubleVote { slot, last, block });
+        }
+        Ok(())
+    }
+
+    pub fn record_attestation(&mut self, slot: u64, block_hash: B256) {
+        self.last_attested_slot = Some(slot);
+        self.last_attested_block = Some(block_hash);
+    }
+
+    // whether we already signed a block or a vote in this slot
+    pub fn proposed_in(&self, slot: u64) -> bool {
+        self.last_proposed_slot == Some(slot)
+    }
+
+    pub fn attested_in(&self, slot: u64) -> bool {
+        self.last_attested_slot == Some(slot)
+    }
+}
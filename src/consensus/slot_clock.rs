@@ -0,0 +1,89 @@
+use std::time::{Duration, SystemTime};
+
+// fixed length slots counted from the chain's genesis time, the same on every node
+// that agrees on the genesis, however late it started
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotClock {
+    genesis_time: SystemTime,
+    slot_duration: Duration,
+}
+
+impl SlotClock {
+    pub fn new(genesis_time: SystemTime, slot_duration: Duration) -> Self {
+        Self {
+            genesis_time,
+            // a zero slot would put every instant in slot 0
+            slot_duration: slot_duration.max(Duration::from_millis(1)),
+        }
+    }
+
+    pub fn genesis_time(&self) -> SystemTime {
+        self.genesis_time
+    }
+
+    pub fn slot_duration(&self) -> Duration {
+        self.slot_duration
+    }
+
+    // slot running at `time`, None before genesis
+    pub fn slot_at(&self, time: SystemTime) -> Option<u64> {
+        let elapsed = time.duration_since(self.genesis_time).ok()?;
+        Some((elapsed.as_millis() / self.slot_duration.as_millis()) as u64)
+    }
+
+    pub fn current_slot(&self) -> Option<u64> {
+        self.slot_at(SystemTime::now())
+    }
+
+    pub fn slot_start(&self, slot: u64) -> SystemTime {
+        self.genesis_time + self.slot_duration * slot as u32
+    }
+
+    // first instant of the next slot
+    pub fn slot_end(&self, slot: u64) -> SystemTime {
+        self.slot_start(slot + 1)
+    }
+
+    // time left until `slot` starts, zero once it has
+    pub fn until_slot(&self, slot: u64) -> Duration {
+        self.slot_start(slot)
+            .duration_since(SystemTime::now())
+            .unwrap_or_default()
+    }
+
+    // a tick at the start of every slot from the current one on
+    pub fn ticks(&self) -> SlotTicks {
+        SlotTicks {
+            clock: *self,
+            next_slot: None,
+        }
+    }
+}
+
+// slot ticks for `tokio::select!`, an interval that stays aligned to the slot clock
+// slots slept through, e.g. while the loop was busy, are skipped rather than replayed
+#[derive(Debug, Clone)]
+pub struct SlotTicks {
+    clock: SlotClock,
+    // None until the first tick, which fires right away for the slot in progress
+    next_slot: Option<u64>,
+}
+
+impl SlotTicks {
+    // wait for the next slot to start and return it, cancel safe
+    pub async fn tick(&mut self) -> u64 {
+        let current = self.clock.current_slot();
+        let slot = match (self.next_slot, current) {
+            (Some(next), Some(current)) => next.max(current),
+            (Some(next), None) => next,
+            (None, current) => current.unwrap_or(0),
+        };
+        tokio::time::sleep(self.clock.until_slot(slot)).await;
+        self.next_slot = Some(slot + 1);
+        slot
+    }
+
+    pub fn clock(&self) -> &SlotClock {
+        &self.clock
+    }
+}
@@ -1,7 +1,13 @@
-use super::error::StakeError;
-use alloy::primitives::Address;
+use super::error::{StakeError, ValidatorError};
+use super::slashing::{DOUBLE_PROPOSAL_WINDOW_SLOTS, slash_penalty};
+use crate::PendingStake;
+use alloy::primitives::{Address, U256};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+// slots an exiting validator stays in the set, its stake remains slashable for double
+// proposals that can still be reported
+pub const EXIT_DELAY_SLOTS: u64 = DOUBLE_PROPOSAL_WINDOW_SLOTS;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Validator {
@@ -10,6 +16,53 @@ pub struct Validator {
     pub is_active: bool,
     pub last_block_proposed: u64,
     pub slash_count: u32,
+    // p2p multiaddr registered on-chain, dialed directly by other validators
+    #[serde(default)]
+    pub network_address: Option<String>,
+    // slot from which the validator is out of the set, once it asked to exit
+    #[serde(default)]
+    pub exit_slot: Option<u64>,
+}
+
+// what settling a validator's queued stake changes did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StakeSettlement {
+    pub validator: Address,
+    pub topped_up: u64,
+    pub withdrawn: u64,
+    // paid back to the balance: withdrawn stake plus top-ups that could not be applied
+    pub credit: U256,
+    pub deactivated: bool,
+    // joined the set through an on-chain registration
+    pub registered: bool,
+    // leaves the set at this slot
+    pub exit_slot: Option<u64>,
+}
+
+// a validator that left the set, `stake` is paid back to its balance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidatorExit {
+    pub validator: Address,
+    pub stake: u64,
+}
+
+// what slashing a validator for a double proposal did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlashOutcome {
+    pub validator: Address,
+    pub slashed: u64,
+    pub slash_count: u32,
+    pub deactivated: bool,
+}
+
+// the validator set and the frozen sets proposer schedules are computed from, written when
+// an epoch closes so a restarted node schedules the same proposers as its peers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredValidatorSet {
+    // every validator, active or not, sorted by address
+    pub validators: Vec<Validator>,
+    // epoch -> active validators scheduled from it on
+    pub snapshots: BTreeMap<u64, Vec<Validator>>,
 }
 
 #[derive(Debug, Clone)]
@@ -29,11 +82,23 @@ impl ValidatorSet {
         }
     }
 
-    // add a new validator
+    // a set read back from storage, validators keep their stake and status
+    pub fn from_validators(min_stake: u64, validators: Vec<Validator>) -> Self {
+        Self {
+            total_stake: validators.iter().map(|v| v.staked_amount).sum(),
+            validators: validators.into_iter().map(|v| (v.address, v)).collect(),
+            min_stake,
+        }
+    }
+
+    // add a new validator, an address already in the set keeps its stake
     pub fn add_validator(&mut self, address: Address, stake: u64) -> Result<(), StakeError> {
         if stake < self.min_stake {
             return Err(StakeError::InsufficientStake);
         }
+        if self.validators.contains_key(&address) {
+            return Err(StakeError::AlreadyValidator);
+        }
 
         let validator = Validator {
             address,
@@ -41,6 +106,8 @@ impl ValidatorSet {
             is_active: true,
             last_block_proposed: 0,
             slash_count: 0,
+            network_address: None,
+            exit_slot: None,
         };
 
         self.validators.insert(address, validator);
@@ -49,12 +116,88 @@ impl ValidatorSet {
         Ok(())
     }
 
+    // take a validator out of the set right away, with its stake
+    // unlike an exit there is no delay, the last active validator can't be removed
+    pub fn remove_validator(&mut self, address: &Address) -> Result<Validator, ValidatorError> {
+        self.ensure_not_last_active(address)?;
+        let validator = self
+            .validators
+            .remove(address)
+            .ok_or(ValidatorError::UnknownValidator(*address))?;
+        self.total_stake -= validator.staked_amount;
+        Ok(validator)
+    }
+
+    // set a validator's stake, returning the previous one
+    // below the minimum stake the validator is deactivated, raising it doesn't reactivate
+    pub fn update_stake(&mut self, address: &Address, stake: u64) -> Result<u64, ValidatorError> {
+        if stake < self.min_stake {
+            self.ensure_not_last_active(address)?;
+        }
+        let validator = self
+            .validators
+            .get_mut(address)
+            .ok_or(ValidatorError::UnknownValidator(*address))?;
+        let previous = validator.staked_amount;
+        validator.staked_amount = stake;
+        validator.is_active = validator.is_active && stake >= self.min_stake;
+        self.total_stake = self.total_stake - previous + stake;
+        Ok(previous)
+    }
+
+    // stop scheduling a validator, its stake stays, false if it already was inactive
+    pub fn deactivate(&mut self, address: &Address) -> Result<bool, ValidatorError> {
+        self.ensure_not_last_active(address)?;
+        let validator = self
+            .validators
+            .get_mut(address)
+            .ok_or(ValidatorError::UnknownValidator(*address))?;
+        let was_active = validator.is_active;
+        validator.is_active = false;
+        Ok(was_active)
+    }
+
+    // schedule a deactivated validator again, false if it already was active
+    // it needs the minimum stake and must not be exiting
+    pub fn reactivate(&mut self, address: &Address) -> Result<bool, ValidatorError> {
+        let min_stake = self.min_stake;
+        let validator = self
+            .validators
+            .get_mut(address)
+            .ok_or(ValidatorError::UnknownValidator(*address))?;
+        if validator.exit_slot.is_some() {
+            return Err(ValidatorError::Exiting(*address));
+        }
+        if validator.staked_amount < min_stake {
+            return Err(ValidatorError::InsufficientStake {
+                stake: validator.staked_amount,
+                min_stake,
+            });
+        }
+        let was_active = validator.is_active;
+        validator.is_active = true;
+        Ok(!was_active)
+    }
+
+    // an empty set can't schedule proposers, membership changes keep one active validator
+    fn ensure_not_last_active(&self, address: &Address) -> Result<(), ValidatorError> {
+        let active = self.get_active_validators();
+        if active.len() == 1 && active[0].address == *address {
+            return Err(ValidatorError::LastActiveValidator(*address));
+        }
+        Ok(())
+    }
+
     // get validators that is active and have sufficient staking amount
+    // sorted by address, proposer selection depends on this order being the same on every node
     pub fn get_active_validators(&self) -> Vec<&Validator> {
-        self.validators
+        let mut validators: Vec<&Validator> = self
+            .validators
             .values()
             .filter(|v| v.is_active && v.staked_amount >= self.min_stake)
-            .collect()
+            .collect();
+        validators.sort_by_key(|v| v.address);
+        validators
     }
 
     // check if an address is a valid validator
@@ -64,4 +207,215 @@ impl ValidatorSet {
             .map(|v| v.is_active && v.staked_amount >= self.min_stake)
             .unwrap_or(false)
     }
+
+    // accept votes, the proposer's own included, needed for a 2/3 quorum of active validators
+    pub fn quorum_size(&self) -> usize {
+        (2 * self.get_active_validators().len()).div_ceil(3)
+    }
+
+    // apply registrations, top-ups and withdrawals queued during an epoch
+    // a registration with the minimum stake adds the validator, other top-ups from
+    // non-validators are refunded, withdrawals are capped at the stake and a validator
+    // left below the minimum stake is deactivated
+    pub fn settle_stake(&mut self, address: Address, pending: &PendingStake) -> StakeSettlement {
+        let mut settlement = StakeSettlement {
+            validator: address,
+            topped_up: 0,
+            withdrawn: 0,
+            credit: U256::ZERO,
+            deactivated: false,
+            registered: false,
+            exit_slot: None,
+        };
+        let registration = match u64::try_from(pending.top_up) {
+            Ok(stake) if pending.register && !self.validators.contains_key(&address) => {
+                self.add_validator(address, stake).is_ok().then_some(stake)
+            }
+            _ => None,
+        };
+        let Some(validator) = self.validators.get_mut(&address) else {
+            settlement.credit = pending.top_up;
+            return settlement;
+        };
+
+        match registration {
+            // the top-up became the new validator's stake
+            Some(stake) => {
+                settlement.topped_up = stake;
+                settlement.registered = true;
+            }
+            None => match u64::try_from(pending.top_up)
+                .ok()
+                .filter(|top_up| validator.staked_amount.checked_add(*top_up).is_some())
+            {
+                Some(top_up) => {
+                    validator.staked_amount += top_up;
+                    self.total_stake += top_up;
+                    settlement.topped_up = top_up;
+                }
+                None => settlement.credit = pending.top_up,
+            },
+        }
+
+        let withdrawn = u64::try_from(pending.withdraw)
+            .unwrap_or(u64::MAX)
+            .min(validator.staked_amount);
+        validator.staked_amount -= withdrawn;
+        self.total_stake -= withdrawn;
+        settlement.withdrawn = withdrawn;
+        settlement.credit += U256::from(withdrawn);
+
+        let was_active = validator.is_active;
+        validator.is_active = validator.staked_amount >= self.min_stake;
+        settlement.deactivated = was_active && !validator.is_active;
+
+        settlement
+    }
+
+    // burn `slash_percent` of the stake, the validator is deactivated on its
+    // `max_slashes`th slash or once below the minimum stake, None if unknown
+    pub fn slash(
+        &mut self,
+        address: &Address,
+        slash_percent: u64,
+        max_slashes: u32,
+    ) -> Option<SlashOutcome> {
+        let validator = self.validators.get_mut(address)?;
+        let slashed = slash_penalty(validator.staked_amount, slash_percent);
+        validator.staked_amount -= slashed;
+        self.total_stake -= slashed;
+        validator.slash_count += 1;
+
+        let was_active = validator.is_active;
+        validator.is_active = validator.is_active
+            && validator.slash_count < max_slashes
+            && validator.staked_amount >= self.min_stake;
+
+        Some(SlashOutcome {
+            validator: *address,
+            slashed,
+            slash_count: validator.slash_count,
+            deactivated: was_active && !validator.is_active,
+        })
+    }
+
+    // schedule an active validator's exit at `exit_slot`, false if it is unknown, already
+    // exiting or the last active validator staying
+    pub fn request_exit(&mut self, address: &Address, exit_slot: u64) -> bool {
+        let staying = self
+            .get_active_validators()
+            .iter()
+            .filter(|v| v.exit_slot.is_none() && v.address != *address)
+            .count();
+        match self.validators.get_mut(address) {
+            Some(validator)
+                if validator.is_active && validator.exit_slot.is_none() && staying > 0 =>
+            {
+                validator.exit_slot = Some(exit_slot);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // remove validators whose exit slot was reached, sorted by address, their whole stake
+    // is released
+    pub fn process_exits(&mut self, slot: u64) -> Vec<ValidatorExit> {
+        let mut exits = Vec::new();
+        for validator in self.validators.values_mut() {
+            if validator
+                .exit_slot
+                .is_some_and(|exit_slot| exit_slot <= slot)
+            {
+                exits.push(ValidatorExit {
+                    validator: validator.address,
+                    stake: validator.staked_amount,
+                });
+                self.total_stake -= validator.staked_amount;
+                validator.staked_amount = 0;
+                validator.is_active = false;
+                validator.exit_slot = None;
+            }
+        }
+        exits.sort_by_key(|exit| exit.validator);
+        exits
+    }
+
+    // validators waiting to leave, with their exit slot, sorted by address
+    pub fn pending_exits(&self) -> Vec<(Address, u64)> {
+        let mut exits: Vec<(Address, u64)> = self
+            .validators
+            .values()
+            .filter_map(|v| v.exit_slot.map(|slot| (v.address, slot)))
+            .collect();
+        exits.sort();
+        exits
+    }
+
+    // stake of a validator, 0 if unknown
+    pub fn stake_of(&self, address: &Address) -> u64 {
+        self.validators
+            .get(address)
+            .map(|v| v.staked_amount)
+            .unwrap_or(0)
+    }
+
+    pub fn get_validator(&self, address: &Address) -> Option<&Validator> {
+        self.validators.get(address)
+    }
+
+    pub fn contains(&self, address: &Address) -> bool {
+        self.validators.contains_key(address)
+    }
+
+    // every validator, active or not, sorted by address
+    pub fn validators(&self) -> Vec<&Validator> {
+        let mut validators: Vec<&Validator> = self.validators.values().collect();
+        validators.sort_by_key(|v| v.address);
+        validators
+    }
+
+    pub fn len(&self) -> usize {
+        self.validators.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.validators.is_empty()
+    }
+
+    // stake of every validator in the set, active or not
+    pub fn total_stake(&self) -> u64 {
+        self.total_stake
+    }
+
+    // stake of the validators proposer selection and quorums count
+    pub fn active_stake(&self) -> u64 {
+        self.get_active_validators()
+            .iter()
+            .map(|v| v.staked_amount)
+            .sum()
+    }
+
+    pub fn min_stake(&self) -> u64 {
+        self.min_stake
+    }
+
+    // set registered network address, false if address is not a validator
+    pub fn set_network_address(&mut self, address: &Address, multiaddr: String) -> bool {
+        match self.validators.get_mut(address) {
+            Some(validator) => {
+                validator.network_address = Some(multiaddr);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // registered network addresses of active validators
+    pub fn network_addresses(&self) -> Vec<(Address, String)> {
+        self.get_active_validators()
+            .into_iter()
+            .filter_map(|v| v.network_address.clone().map(|addr| (v.address, addr)))
+            .collect()
+    }
 }
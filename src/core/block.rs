@@ -1,6 +1,7 @@
 use super::blockheader::BlockHeader;
+use super::header_hashing::transactions_root;
 use super::transaction::Transaction;
-use alloy::primitives::{B256, keccak256};
+use alloy::primitives::{B256, Bloom, BloomInput};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,22 +24,19 @@ impl Block {
         Self::new(BlockHeader::genesis(), Vec::new())
     }
 
-    // calculate transaction root, using simple hash, NOT an actual merkle root
-    pub fn calculate_transactions_root(transactions: &[Transaction]) -> B256 {
-        if transactions.is_empty() {
-            return B256::ZERO;
-        }
-
-        let mut data = Vec::new();
-
-        // Sort transactions by hash for deterministic ordering
-        let mut sorted_transactions = transactions.to_vec();
-        sorted_transactions.sort_by_key(|tx| tx.hash);
-
-        for tx in sorted_transactions {
-            data.extend_from_slice(tx.hash.as_slice());
+    // bloom of every address the block's transactions involve, lets address queries skip blocks
+    pub fn address_bloom(&self) -> Bloom {
+        let mut bloom = Bloom::ZERO;
+        for tx in &self.transactions {
+            for address in tx.indexed_addresses() {
+                bloom.accrue(BloomInput::Raw(address.as_slice()));
+            }
         }
+        bloom
+    }
 
-        keccak256(&data)
+    // calculate transaction root, see header_hashing::transactions_root
+    pub fn calculate_transactions_root(transactions: &[Transaction]) -> B256 {
+        transactions_root(transactions)
     }
 }
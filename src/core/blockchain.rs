@@ -1,21 +1,227 @@
-use alloy::primitives::{Address, B256};
+use alloy::primitives::{Address, B256, U256, keccak256};
 use alloy_signer::Signature;
 use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{Mutex, broadcast};
 
 use super::block::Block;
-use crate::consensus::{ConsensusEngine, ValidatorSet};
+use super::blockheader::BlockHeader;
+use super::censorship::{CensorshipReport, CensorshipTracker};
+use super::checkpoint::{Checkpoint, CheckpointError, CheckpointInfo};
+use super::consensus_events::{
+    CONSENSUS_EVENTS_KEPT, ConsensusDebugState, ConsensusEventKind, ConsensusEventLog, DebugState,
+};
+use super::fork_choice::{ForkChoice, HeadCheckpoint, MAX_REORG_DEPTH};
+use super::future_blocks::{FutureBlock, FutureBlockConfig, FutureBlockQueue};
+use super::performance::{PERFORMANCE_WINDOW, PerformanceReport, PerformanceTracker, TuningKnobs};
+use super::quarantine::{MAX_REJECTED_BLOCKS_PER_REQUEST, RejectedBlock};
+use crate::consensus::{
+    AdaptiveSlots, AttestationEquivocationEvidence, CertifiedValidator, CheckpointStatus,
+    ConsensusConfig, ConsensusEngine, ConsensusGenesis, ConsensusHead, ConsensusMetrics,
+    ConsensusMetricsReport, DoubleProposalDetector, DoubleProposalEvidence, EpochBlockUsage,
+    EpochCheckpoint, EpochProposal, EpochSchedule, EpochSettlement, EpochStatement, EpochStats,
+    FinalityCertificate, FinalityCheckpoints, LivenessRecord, ProposerDuty, ProposerElection,
+    RandomnessBeacon, SignedMessage, SigningAuditLog, SigningRecord, SlashableEvidence,
+    SlashingEvidence, ValidatorScoreReport, ValidatorScores, ValidatorSet, epoch_settlements,
+    epoch_statements, epoch_stats, liveness_records, safe_quorum, slash_penalties,
+};
 use crate::storage::Storage;
-use crate::{BlockProcessResult, ExecutionEngine, KeyPair, Transaction};
+use crate::{
+    AdaptiveSlotConfig, Attestation, BlockArrival, BlockProcessResult, BlockRewardConfig,
+    BlockTemplateReport, CallResult, ChainSpec, EpochRewardConfig, ExecutionEngine,
+    ExecutionResult, HeadFees, HeldTransaction, InclusionEstimate, KeyPair, LivenessConfig,
+    PROPAGATION_WINDOW_BLOCKS, PrefetchReport, PropagationStats, PropagationTracker,
+    QuarantineConfig, Receipt, SlashingConfig, StateUndo, Transaction, TransactionReceipt,
+    TransactionSource, TxPoolContent, ValidatorRole, gas_target_fit, unix_millis,
+};
 
 // chain manager: glue for consensus and execution engines
 
+// committed blocks buffered for slow subscribers, they catch up from storage when they lag
+pub const BLOCK_EVENTS_CAPACITY: usize = 64;
+
+// block hash -> (slot, validators that accepted it)
+type BlockVotes = HashMap<B256, (u64, HashSet<Address>)>;
+
+// a committed block and its receipts, streamed to rpc subscribers
+#[derive(Debug, Clone)]
+pub struct CommittedBlock {
+    pub block: Block,
+    pub receipts: Vec<Receipt>,
+}
+
+// state snapshot written on operator request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateSnapshotInfo {
+    pub path: String,
+    pub block_number: u64,
+    pub state_root: B256,
+    pub accounts: usize,
+}
+
+// one-call chain overview for dashboards, `speed_getChainInfo`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainInfo {
+    pub chain_id: u64,
+    // blocks start at #1 on top of the genesis parameters, so this is the chain spec hash
+    pub genesis_hash: B256,
+    pub head: HeadInfo,
+    // highest block more than half of the stake accepted, never below finalized
+    pub safe: HeadInfo,
+    // highest block a quorum of stake accepted, genesis until the first certificate
+    pub finalized: HeadInfo,
+    pub validator_count: usize,
+    pub total_stake: u64,
+    // base fee of the next block, the minimum gas price on chains without a fee market
+    pub base_fee: U256,
+    pub gas_price: U256,
+    pub sync: SyncStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeadInfo {
+    pub number: u64,
+    pub hash: B256,
+    pub slot: u64,
+}
+
+// what the safe and finalized heads report until block #1 is accepted or certified
+fn genesis_head() -> HeadInfo {
+    HeadInfo {
+        number: 0,
+        hash: B256::ZERO,
+        slot: 0,
+    }
+}
+
+// validators expected to vote on a block, the proposer's own vote is implied
+fn attesters(consensus: &ConsensusEngine) -> usize {
+    consensus
+        .validator_set()
+        .get_active_validators()
+        .len()
+        .saturating_sub(1)
+}
+
+// confirmation level a client reads the chain at, `speed_getHead`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeadTag {
+    // the head of our chain, may still be replaced by a reorg
+    Latest,
+    // more than half of the active stake accepted it
+    Safe,
+    // certified by 2/3 of the active stake, never reorged
+    Finalized,
+}
+
+// the three heads in one go, latest >= safe >= finalized
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainHeads {
+    pub latest: HeadInfo,
+    pub safe: HeadInfo,
+    pub finalized: HeadInfo,
+}
+
+// whether a committed block reached the accept quorum, `speed_getFinalityStatus`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum BlockFinality {
+    // certified, or below the finalized head
+    Finalized,
+    // proposer and accept votes seen so far, against 2/3 of the active stake
+    Pending {
+        accepted_stake: u64,
+        quorum_stake: u64,
+        voters: usize,
+    },
+}
+
+// how far our head trails the best slot seen in blocks from peers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncStatus {
+    pub syncing: bool,
+    pub network_best_slot: Option<u64>,
+    pub slots_behind: u64,
+}
+
+// where the local validator stands in the current slot, e.g. right after a restart
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DutyStatus {
+    pub slot: u64,
+    pub epoch: u64,
+    pub head_number: u64,
+    pub head_slot: u64,
+    pub proposer_of_slot: Option<Address>,
+    pub is_proposer: bool,
+    pub proposed_this_slot: bool,
+    pub attested_this_slot: bool,
+}
+
+// block this node would have proposed, built in dry-run mode
+#[derive(Debug, Clone)]
+pub struct DryRunBlock {
+    pub slot: u64,
+    pub block: Block,
+    pub execution_result: ExecutionResult,
+}
+
 #[derive(Clone)]
 pub struct Blockchain {
     pub execution_engine: Arc<ExecutionEngine>,
     pub consensus_engine: Arc<Mutex<ConsensusEngine>>,
     store: Arc<Mutex<Storage>>, // RocksDB storage
+    chain_spec_hash: B256,      // identifies the consensus parameters this chain runs with
+    // slot duration, epoch length, quorum, clock skew and gas target, shared with the engine
+    consensus_config: ConsensusConfig,
+    // epoch -> validator -> accept votes for blocks of that epoch, until the epoch is closed
+    epoch_attestations: Arc<Mutex<HashMap<u64, HashMap<Address, u64>>>>,
+    // accept votes of committed blocks, until they are final
+    block_votes: Arc<Mutex<BlockVotes>>,
+    // highest canonical block more than half of the stake accepted, rebuilt from votes after a restart
+    safe_head: Arc<Mutex<Option<HeadInfo>>>,
+    // receive latencies of recently gossiped blocks
+    propagation: Arc<Mutex<PropagationTracker>>,
+    // block import, state write and gossip backlog samples
+    performance: Arc<Mutex<PerformanceTracker>>,
+    // missed slots, proposals, vote participation and time to quorum since the node started
+    consensus_metrics: Arc<Mutex<ConsensusMetrics>>,
+    // proposals, attestations and block latency per validator, kept in storage
+    validator_scores: Arc<Mutex<ValidatorScores>>,
+    // pending transactions left out of committed blocks, per proposer
+    censorship: Arc<Mutex<CensorshipTracker>>,
+    // highest slot seen in a block from peers, even if we couldn't apply it
+    network_best_slot: Arc<Mutex<Option<u64>>>,
+    // every block committed by this node, after it is stored
+    block_events: broadcast::Sender<Arc<CommittedBlock>>,
+    // whether and how many rejected blocks are kept
+    quarantine: QuarantineConfig,
+    // penalty for double proposals
+    slashing: SlashingConfig,
+    // when validators missing their duties are deactivated
+    liveness: LivenessConfig,
+    // rewards and inactivity penalties paid into balances when an epoch closes
+    epoch_rewards: EpochRewardConfig,
+    // recent signed headers per proposer and slot
+    double_proposals: Arc<Mutex<DoubleProposalDetector>>,
+    // evidence waiting for its epoch to close
+    pending_slashes: Arc<Mutex<Vec<SlashingEvidence>>>,
+    // latest proposals, imports, rejections and epoch changes, for debug bundles
+    consensus_events: Arc<Mutex<ConsensusEventLog>>,
+    // every proposal and attestation signed by the local key, hash chained on disk
+    signing_audit: Option<Arc<Mutex<SigningAuditLog>>>,
+    // recent state checkpoints and side branches, for reorgs
+    fork_choice: Arc<Mutex<ForkChoice>>,
+    // blocks that arrived before their slot or their parent, off unless configured
+    future_blocks: Option<FutureBlockConfig>,
+    held_blocks: Arc<Mutex<FutureBlockQueue>>,
+    // the state was read back from storage at the stored head, not rebuilt
+    state_restored: bool,
 }
 
 impl Blockchain {
@@ -27,8 +233,125 @@ impl Blockchain {
         validators: Vec<(Address, u64)>, // (address, stake) pairs
         local_keypair: Option<KeyPair>,
     ) -> Result<Self> {
-        let store = Arc::new(tokio::sync::Mutex::new(Storage::new(storage_path)?));
-        let execution_engine = Arc::new(ExecutionEngine::new());
+        let storage = Storage::new(storage_path)?;
+        Self::from_storage(
+            storage,
+            min_stake,
+            slot_duration_seconds,
+            validators,
+            local_keypair,
+        )
+    }
+
+    // blockchain on already opened storage, e.g. a custom key-value store
+    pub fn from_storage(
+        storage: Storage,
+        min_stake: u64,
+        slot_duration_seconds: u64,
+        validators: Vec<(Address, u64)>, // (address, stake) pairs
+        local_keypair: Option<KeyPair>,
+    ) -> Result<Self> {
+        Self::open(
+            storage,
+            min_stake,
+            slot_duration_seconds,
+            validators,
+            local_keypair,
+            ExecutionEngine::new(),
+        )
+    }
+
+    // blockchain whose genesis block, state and slot clock come from `spec`
+    // the spec is stored on first start, a database created from another spec is refused
+    pub fn from_chain_spec(
+        storage: Storage,
+        spec: &ChainSpec,
+        local_keypair: Option<KeyPair>,
+    ) -> Result<Self> {
+        match storage.get_chain_spec()? {
+            Some(stored) if stored != *spec => {
+                return Err(anyhow!(
+                    "Database was created from a different chain spec, chain {} with genesis {}",
+                    stored.chain_id,
+                    stored.genesis_block().header.hash()
+                ));
+            }
+            Some(_) => {}
+            None => {
+                // a checkpoint is installed before the chain spec is first stored
+                match storage.get_checkpoint()? {
+                    Some(checkpoint) if checkpoint.genesis != spec.consensus_genesis() => {
+                        return Err(CheckpointError::GenesisMismatch.into());
+                    }
+                    Some(_) => {}
+                    None if storage.get_last_index()?.is_some() => {
+                        return Err(anyhow!(
+                            "Database holds a chain that was started without a chain spec"
+                        ));
+                    }
+                    None => {}
+                }
+                storage.put_chain_spec(spec)?;
+                storage.put_consensus_genesis(&spec.consensus_genesis())?;
+            }
+        }
+        let fresh = storage.get_last_index()?.is_none();
+
+        let mut blockchain = Self::open(
+            storage,
+            spec.min_stake,
+            spec.slot_duration,
+            spec.validators(),
+            local_keypair,
+            ExecutionEngine::new()
+                .with_gas_config(spec.gas.clone())
+                .with_contract_runtime(spec.contract_runtime),
+        )?;
+        blockchain.execution_engine.set_chain_id(spec.chain_id);
+        blockchain.chain_spec_hash = keccak256(
+            [
+                blockchain.chain_spec_hash.as_slice(),
+                &spec.chain_spec_bytes(),
+            ]
+            .concat(),
+        );
+
+        // block #1 builds on the genesis block, its state is the genesis allocation
+        // after a restart the stored head and the state warm-up take over
+        if fresh {
+            let genesis = spec.genesis_block();
+            *blockchain
+                .execution_engine
+                .state_manager
+                .try_lock()
+                .expect("state is not shared while the blockchain is built") = spec.genesis_state();
+            blockchain
+                .consensus_engine
+                .try_lock()
+                .expect("consensus engine is not shared while the blockchain is built")
+                .set_head(&genesis);
+            println!(
+                "🌱 Genesis block {} for chain {} with {} validators and {} funded accounts",
+                genesis.header.hash(),
+                spec.chain_id,
+                spec.validators.len(),
+                spec.alloc.len()
+            );
+        }
+        Ok(blockchain)
+    }
+
+    fn open(
+        storage: Storage,
+        min_stake: u64,
+        slot_duration_seconds: u64,
+        validators: Vec<(Address, u64)>,
+        local_keypair: Option<KeyPair>,
+        execution_engine: ExecutionEngine,
+    ) -> Result<Self> {
+        let execution_engine = Arc::new(execution_engine);
+        let chain_spec_hash =
+            Self::calculate_chain_spec_hash(min_stake, slot_duration_seconds, &validators);
 
         // Create validator set using your ValidatorSet
         let mut validator_set = ValidatorSet::new(min_stake);
@@ -36,16 +359,86 @@ impl Blockchain {
             let _ = validator_set.add_validator(address, stake);
         }
 
-        // Simple randomness seed (in production, use block hashes)
-        let randomness_seed = [1u8; 32]; // Placeholder
+        // the slot clock and proposer seed are fixed on first start, so a restarted
+        // node computes the same slots and proposer schedule as before
+        let genesis = match storage.get_consensus_genesis()? {
+            Some(genesis) => genesis,
+            None => {
+                // base seed, block hash randomness is mixed in per epoch
+                let genesis = ConsensusGenesis::new(SystemTime::now(), [1u8; 32]);
+                storage.put_consensus_genesis(&genesis)?;
+                genesis
+            }
+        };
 
         // Create consensus engine with your components
-        let consensus_engine = Arc::new(Mutex::new(ConsensusEngine::new(
-            slot_duration_seconds,
+        let consensus_config = ConsensusConfig {
+            slot_duration: slot_duration_seconds,
+            ..ConsensusConfig::default()
+        };
+        let mut consensus = ConsensusEngine::new(
+            consensus_config.clone(),
+            &genesis,
             validator_set,
-            randomness_seed,
             local_keypair,
-        )));
+        );
+
+        // there are no blocks to rebuild the state before a checkpoint from, it is the base
+        if let Some(checkpoint) = storage.get_checkpoint()? {
+            *execution_engine
+                .state_manager
+                .try_lock()
+                .expect("state is not shared while the blockchain is built") = checkpoint.state;
+        }
+
+        let validator_scores = storage.get_validator_scores()?.unwrap_or_default();
+
+        // resume from the last stored block
+        if let Some(randomness) = storage.get_block_randomness()? {
+            consensus.restore_block_randomness(randomness);
+        }
+        // registrations, exits, slashes and top-ups settled since genesis
+        if let Some(validators) = storage.get_validator_set()? {
+            println!(
+                "💾 Restored validator set of {} validators",
+                validators.validators.len()
+            );
+            consensus.restore_validator_set(validators);
+        }
+        let mut state_restored = false;
+        if let Some(head) = Self::stored_head(&storage)? {
+            // the state written with the head block, databases without one warm up instead
+            match storage.get_state()? {
+                Some((block_hash, state)) if block_hash == head.header.hash() => {
+                    println!(
+                        "💾 Restored state of {} accounts at block #{}",
+                        state.account_count(),
+                        head.header.index
+                    );
+                    *execution_engine
+                        .state_manager
+                        .try_lock()
+                        .expect("state is not shared while the blockchain is built") = state;
+                    state_restored = true;
+                }
+                Some(_) => println!("⚠️  Stored state is not at the head block, ignoring it"),
+                None => {}
+            }
+            consensus.set_head(&head);
+            consensus.set_recent_timestamps(Self::recent_timestamps(
+                &storage,
+                &head,
+                consensus_config.median_time_blocks,
+            )?);
+            execution_engine.set_head_slot(head.header.slot);
+            execution_engine.set_head_fees(HeadFees::of(&head.header));
+            println!(
+                "🔁 Resuming from block #{} at slot {}",
+                head.header.index, head.header.slot
+            );
+        }
+        let consensus_engine = Arc::new(Mutex::new(consensus));
+        let store = Arc::new(Mutex::new(storage));
 
         // let gas_config = GasConfig::default();
 
@@ -53,48 +446,321 @@ impl Blockchain {
             execution_engine,
             consensus_engine,
             store,
+            chain_spec_hash,
+            consensus_config,
+            epoch_attestations: Arc::new(Mutex::new(HashMap::new())),
+            block_votes: Arc::new(Mutex::new(HashMap::new())),
+            safe_head: Arc::new(Mutex::new(None)),
+            propagation: Arc::new(Mutex::new(PropagationTracker::new(
+                PROPAGATION_WINDOW_BLOCKS,
+            ))),
+            performance: Arc::new(Mutex::new(PerformanceTracker::new(PERFORMANCE_WINDOW))),
+            consensus_metrics: Arc::new(Mutex::new(ConsensusMetrics::new())),
+            validator_scores: Arc::new(Mutex::new(validator_scores)),
+            censorship: Arc::new(Mutex::new(CensorshipTracker::new())),
+            network_best_slot: Arc::new(Mutex::new(None)),
+            block_events: broadcast::channel(BLOCK_EVENTS_CAPACITY).0,
+            quarantine: QuarantineConfig::default(),
+            slashing: SlashingConfig::default(),
+            liveness: LivenessConfig::default(),
+            epoch_rewards: EpochRewardConfig::default(),
+            double_proposals: Arc::new(Mutex::new(DoubleProposalDetector::new())),
+            pending_slashes: Arc::new(Mutex::new(Vec::new())),
+            consensus_events: Arc::new(Mutex::new(ConsensusEventLog::new(CONSENSUS_EVENTS_KEPT))),
+            signing_audit: None,
+            fork_choice: Arc::new(Mutex::new(ForkChoice::new(MAX_REORG_DEPTH))),
+            future_blocks: None,
+            held_blocks: Arc::new(Mutex::new(FutureBlockQueue::default())),
+            state_restored,
             // gas_config,
         })
     }
 
+    // epoch length, quorum, clock skew and gas target other than the defaults, non-default
+    // consensus rules change the chain spec hash; the slot duration is the one opened with
+    pub fn with_consensus_config(mut self, config: ConsensusConfig) -> Self {
+        let config = ConsensusConfig {
+            slot_duration: self.consensus_config.slot_duration,
+            ..config
+        };
+        if config.chain_spec_bytes() != ConsensusConfig::default().chain_spec_bytes() {
+            self.chain_spec_hash =
+                keccak256([self.chain_spec_hash.as_slice(), &config.chain_spec_bytes()].concat());
+        }
+        let mut consensus = self
+            .consensus_engine
+            .try_lock()
+            .expect("consensus engine is not shared while the blockchain is built");
+        consensus.set_config(config.clone());
+        // a longer median window than the one opened with needs more of the stored blocks
+        if config.median_time_blocks > self.consensus_config.median_time_blocks {
+            let storage = self
+                .store
+                .try_lock()
+                .expect("storage is not shared while the blockchain is built");
+            let timestamps = Self::stored_head(&storage).and_then(|head| match head {
+                Some(head) => Self::recent_timestamps(&storage, &head, config.median_time_blocks),
+                None => Ok(Vec::new()),
+            });
+            match timestamps {
+                Ok(timestamps) if !timestamps.is_empty() => {
+                    consensus.set_recent_timestamps(timestamps)
+                }
+                Ok(_) => {}
+                Err(e) => println!("⚠️  Failed to load recent block timestamps: {}", e),
+            }
+        }
+        drop(consensus);
+        self.consensus_config = config;
+        self
+    }
+
+    // keep rejected blocks in storage instead of dropping them
+    pub fn with_quarantine(mut self, quarantine: QuarantineConfig) -> Self {
+        self.quarantine = quarantine;
+        self
+    }
+
+    // stake burned for double proposals, a non-default penalty changes the chain spec hash
+    pub fn with_slashing(mut self, slashing: SlashingConfig) -> Self {
+        if slashing != SlashingConfig::default() {
+            let data = [
+                slashing.slash_percent.to_be_bytes().as_slice(),
+                &slashing.max_slashes.to_be_bytes(),
+            ]
+            .concat();
+            self.chain_spec_hash = keccak256([self.chain_spec_hash.as_slice(), &data].concat());
+        }
+        self.slashing = slashing;
+        self
+    }
+
+    // deactivate validators that miss their duties, enabling it changes the chain spec hash
+    pub fn with_liveness(mut self, liveness: LivenessConfig) -> Self {
+        if liveness.enabled {
+            let data = [
+                liveness.max_missed_percent.to_be_bytes().as_slice(),
+                &liveness.min_duties.to_be_bytes(),
+                &[liveness.count_attestations as u8],
+            ]
+            .concat();
+            self.chain_spec_hash = keccak256([self.chain_spec_hash.as_slice(), &data].concat());
+        }
+        self.liveness = liveness;
+        self
+    }
+
+    // settle epoch rewards and penalties in account state, enabling it changes the chain spec hash
+    pub fn with_epoch_rewards(mut self, epoch_rewards: EpochRewardConfig) -> Self {
+        if epoch_rewards.enabled() {
+            let data = [
+                epoch_rewards.proposal_reward.to_be_bytes::<32>(),
+                epoch_rewards.inactivity_penalty.to_be_bytes::<32>(),
+            ]
+            .concat();
+            self.chain_spec_hash = keccak256([self.chain_spec_hash.as_slice(), &data].concat());
+        }
+        self.epoch_rewards = epoch_rewards;
+        self
+    }
+
+    // hold blocks that arrive early or before their parent instead of rejecting them
+    pub fn with_future_blocks(mut self, future_blocks: FutureBlockConfig) -> Self {
+        if future_blocks.enabled {
+            self.held_blocks = Arc::new(Mutex::new(FutureBlockQueue::new(future_blocks.capacity)));
+            self.future_blocks = Some(future_blocks);
+        }
+        self
+    }
+
+    pub fn quarantine_enabled(&self) -> bool {
+        self.quarantine.enabled
+    }
+
+    // append every signature of the local validator to an audit log
+    pub fn with_signing_audit(mut self, log: SigningAuditLog) -> Self {
+        self.signing_audit = Some(Arc::new(Mutex::new(log)));
+        self
+    }
+
+    // external randomness in the proposer schedule, changes the chain spec hash
+    // the beacon task feeds it through `set_epoch_randomness`
+    pub fn with_randomness_beacon(mut self, beacon: &RandomnessBeacon) -> Self {
+        self.chain_spec_hash =
+            keccak256([self.chain_spec_hash.as_slice(), &beacon.chain_spec_bytes()].concat());
+        self
+    }
+
+    // custom proposer election instead of the stake weighted one, changes the chain spec hash
+    pub fn with_proposer_election(mut self, election: Arc<dyn ProposerElection>) -> Self {
+        self.chain_spec_hash = keccak256(
+            [
+                self.chain_spec_hash.as_slice(),
+                &election.chain_spec_bytes(),
+            ]
+            .concat(),
+        );
+        self.consensus_engine
+            .try_lock()
+            .expect("consensus engine is not shared while the blockchain is built")
+            .set_proposer_election(election);
+        self
+    }
+
+    // experimental adaptive slot clock, changes the chain spec hash
+    pub fn with_adaptive_slots(mut self, config: AdaptiveSlotConfig) -> Self {
+        let adaptive = AdaptiveSlots::new(config);
+        self.chain_spec_hash = keccak256(
+            [
+                self.chain_spec_hash.as_slice(),
+                &adaptive.chain_spec_bytes(),
+            ]
+            .concat(),
+        );
+        self.consensus_engine
+            .try_lock()
+            .expect("consensus engine is not shared while the blockchain is built")
+            .enable_adaptive_slots(adaptive);
+        self
+    }
+
+    // last block written to storage, None on a fresh database
+    fn stored_head(storage: &Storage) -> Result<Option<Block>> {
+        let Some(index) = storage.get_last_index()? else {
+            return Ok(None);
+        };
+        let Some(block_hash) = storage.get_block_hash_from_index(&index)? else {
+            return Ok(None);
+        };
+        storage.get_block_from_block_hash::<Block>(&block_hash)
+    }
+
+    // timestamps of `head` and up to `count - 1` stored blocks before it, oldest first
+    fn recent_timestamps(storage: &Storage, head: &Block, count: u64) -> Result<Vec<u64>> {
+        let mut timestamps = vec![head.header.timestamp];
+        let mut parent_hash = head.header.parent_hash;
+        while (timestamps.len() as u64) < count {
+            let Some(parent) = storage.get_block_from_block_hash::<Block>(&parent_hash)? else {
+                break;
+            };
+            timestamps.push(parent.header.timestamp);
+            parent_hash = parent.header.parent_hash;
+        }
+        timestamps.reverse();
+        Ok(timestamps)
+    }
+
+    // hash of the parameters every node of this chain must agree on
+    fn calculate_chain_spec_hash(
+        min_stake: u64,
+        slot_duration_seconds: u64,
+        validators: &[(Address, u64)],
+    ) -> B256 {
+        let mut sorted_validators = validators.to_vec();
+        sorted_validators.sort();
+
+        let mut data = Vec::new();
+        data.extend_from_slice(&min_stake.to_be_bytes());
+        data.extend_from_slice(&slot_duration_seconds.to_be_bytes());
+        for (address, stake) in sorted_validators {
+            data.extend_from_slice(address.as_slice());
+            data.extend_from_slice(&stake.to_be_bytes());
+        }
+
+        keccak256(&data)
+    }
+
+    // get chain spec hash
+    pub fn chain_spec_hash(&self) -> B256 {
+        self.chain_spec_hash
+    }
+
+    pub fn consensus_config(&self) -> &ConsensusConfig {
+        &self.consensus_config
+    }
+
     /// Produce new block if choosen as proposer
     pub async fn produce_block(&self) -> Result<Block> {
         // check if this node has been choosen to propose block
-        let consensus = self.consensus_engine.lock().await;
-        let should_process = consensus.should_produce_block().await?;
+        let (should_process, slot) = {
+            let consensus = self.consensus_engine.lock().await;
+            (
+                consensus.should_produce_block().await?,
+                consensus.current_slot()?,
+            )
+        };
 
         if !should_process {
             return Err(anyhow!("Not selected as proposer for current slot"));
         }
 
-        // 2. Get pending transactions
-        let mut pending_txs = self.execution_engine.get_pending_transactions().await;
-        if pending_txs.is_empty() {
-            return Err(anyhow!("No transactions to mine"));
-        }
-
-        // 4. Simulate transaction execution
-        let valid_transactions = self
+        // selected ahead of the slot when we could, otherwise from the whole mempool now
+        let template = self
             .execution_engine
-            .simulate_execute_block(&mut pending_txs)
-            .await?;
+            .take_block_template(slot)
+            .await
+            .filter(|template| !template.transactions.is_empty());
+        let (pending_txs, transactions_root) = match template {
+            Some(template) => {
+                let report = template.report();
+                println!(
+                    "🧩 Using block template for slot {}: {} transactions, {} added and {} replaced since built",
+                    report.slot, report.transactions, report.added, report.replaced
+                );
+                (template.transactions, template.transactions_root)
+            }
+            None => {
+                // 2. Get pending transactions
+                let mut pending_txs = self.execution_engine.proposable_transactions().await;
+                if pending_txs.is_empty() {
+                    return Err(anyhow!("No transactions to mine"));
+                }
 
-        // if no valid transactions
-        if valid_transactions.is_empty() {
-            return Err(anyhow!("No valid transactions"));
-        }
+                // 4. Simulate transaction execution
+                let valid_transactions = self
+                    .execution_engine
+                    .simulate_execute_block(&mut pending_txs)
+                    .await?;
+
+                // if no valid transactions
+                if valid_transactions.is_empty() {
+                    return Err(anyhow!("No valid transactions"));
+                }
 
+                // only what simulation kept, validators reject blocks with anything else
+                let transactions_root = Block::calculate_transactions_root(&valid_transactions);
+                (valid_transactions, transactions_root)
+            }
+        };
+        let (pending_txs, transactions_root) = self.fit_gas_target(pending_txs, transactions_root);
+
+        let propagation_ms = self.reported_propagation_ms().await;
         let mut consensus = self.consensus_engine.lock().await;
 
         // 3. Create block template
-        let mut block = consensus.create_block(pending_txs).await?;
+        let mut block = consensus
+            .create_block_with_root(pending_txs, transactions_root, propagation_ms)
+            .await?;
+        self.set_execution_limits(&mut block.header);
+
+        // recorded before signing, a crash right after can't lead to a second block
+        if let Some(validator) = consensus.local_validator() {
+            self.guard_proposal(validator, block.header.slot).await?;
+        }
 
         // 7. Update engines
+        self.checkpoint_head(&consensus).await;
         let execution_result = self
             .execution_engine
             .execute_block_commit(&mut block)
             .await?;
 
+        consensus.apply_network_registrations(&execution_result.network_registrations);
+        if !execution_result.network_registrations.is_empty() {
+            self.persist_validator_set(&consensus).await;
+        }
+        let execution_output = execution_result.clone();
+
         // get finalized block
         let finalized_block = match consensus.finalize_block(block, execution_result).await {
             Ok(block) => block,
@@ -103,15 +769,127 @@ impl Blockchain {
                 return Err(e.into());
             }
         };
+        if let Some(signature) = finalized_block.header.validator_signature {
+            self.record_signature(
+                finalized_block.header.slot,
+                finalized_block.header.hash(),
+                SignedMessage::Proposal,
+                finalized_block.header.proposer,
+                signature,
+            )
+            .await;
+        }
 
+        let write_started = Instant::now();
         let _ = self.store_block(&finalized_block).await;
+        let _ = self
+            .store_execution_output(&finalized_block, &execution_output)
+            .await;
+        self.performance
+            .lock()
+            .await
+            .record_state_write(write_started.elapsed());
 
         // update consensus engine state
+        let previous = consensus.head();
+        let skipped_slots = consensus.skipped_slots(finalized_block.header.slot);
         consensus.update_best_block(&finalized_block).await?;
+        self.note_skipped_slots(&finalized_block.header, skipped_slots)
+            .await;
+        {
+            let mut metrics = self.consensus_metrics.lock().await;
+            metrics.record_proposal();
+            metrics.record_block(skipped_slots, attesters(&consensus));
+        }
+        self.close_finished_epochs(&mut consensus, &previous, finalized_block.header.slot)
+            .await;
+        self.store
+            .lock()
+            .await
+            .put_block_randomness(consensus.block_randomness())?;
+        self.persist_state(&finalized_block).await?;
+        self.observe_censorship(&finalized_block, execution_output.total_gas_used)
+            .await;
+        self.publish_block(&finalized_block, execution_output.receipts);
+        self.record_event(&finalized_block.header, ConsensusEventKind::Proposed)
+            .await;
 
         Ok(finalized_block)
     }
 
+    // what our blocks are packed up to, the block gas limit unless configured lower
+    fn block_gas_target(&self) -> U256 {
+        let block_gas_limit = self.execution_engine.gas_config().block_gas_limit;
+        self.consensus_config
+            .block_gas_target
+            .map_or(block_gas_limit, |target| target.min(block_gas_limit))
+    }
+
+    // set before execution, the base fee of a block on our head and the gas limit it is
+    // built for, validators check both
+    fn set_execution_limits(&self, header: &mut BlockHeader) {
+        header.base_fee_per_gas = self.execution_engine.next_base_fee();
+        header.gas_limit = Some(self.execution_engine.gas_config().block_gas_limit);
+    }
+
+    // drop the transactions past the gas target, they wait for a later block
+    fn fit_gas_target(
+        &self,
+        mut transactions: Vec<Transaction>,
+        transactions_root: B256,
+    ) -> (Vec<Transaction>, B256) {
+        let fit = gas_target_fit(&transactions, self.block_gas_target());
+        if fit == transactions.len() {
+            return (transactions, transactions_root);
+        }
+        println!(
+            "⛽ {} of {} transactions fit in the block gas target",
+            fit,
+            transactions.len()
+        );
+        transactions.truncate(fit);
+        let transactions_root = Block::calculate_transactions_root(&transactions);
+        (transactions, transactions_root)
+    }
+
+    /// Build the block we would propose for the current slot, without committing,
+    /// signing or updating consensus state. Returns None when not selected.
+    pub async fn dry_run_block(&self) -> Result<Option<DryRunBlock>> {
+        let propagation_ms = self.reported_propagation_ms().await;
+        let consensus = self.consensus_engine.lock().await;
+        if !consensus.should_produce_block().await? {
+            return Ok(None);
+        }
+        let slot = consensus.current_slot()?;
+
+        // same transaction selection as produce_block
+        let mut pending_txs = self.execution_engine.proposable_transactions().await;
+        let mut valid_transactions = self
+            .execution_engine
+            .simulate_execute_block(&mut pending_txs)
+            .await?;
+        valid_transactions.truncate(gas_target_fit(&valid_transactions, self.block_gas_target()));
+
+        let mut block = consensus
+            .create_block(valid_transactions, propagation_ms)
+            .await?;
+        self.set_execution_limits(&mut block.header);
+        let execution_result = self
+            .execution_engine
+            .execute_block_dry_run(&mut block)
+            .await?;
+        block.header.state_root = execution_result.state_root;
+        block.header.receipts_root = Some(execution_result.receipts_root);
+        block.header.logs_bloom = Some(execution_result.logs_bloom);
+        block.header.gas_used = Some(execution_result.total_gas_used);
+
+        Ok(Some(DryRunBlock {
+            slot,
+            block,
+            execution_result,
+        }))
+    }
+
     // process and block received from the service(from other node)
     pub async fn process_received_block(
         &self,
@@ -125,21 +903,62 @@ impl Blockchain {
         );
 
         let block_hash = block.header.hash();
+        let import_started = Instant::now();
 
         // Step 1: Verify signature first (quick check)
         if !self.verify_proposer_signature(&block, &proposer_id, &signature)? {
             println!("Blockchain: Invalid proposer signature");
-            return Ok(BlockProcessResult::Rejected(
-                block_hash,
-                "Invalid signature".to_string(),
-            ));
+            let reason = "Invalid signature".to_string();
+            self.record_event(
+                &block.header,
+                ConsensusEventKind::Rejected {
+                    reason: reason.clone(),
+                },
+            )
+            .await;
+            return Ok(BlockProcessResult::Rejected(block_hash, reason));
+        }
+
+        // too early to import, replayed by `release_future_blocks`
+        if let Some(result) = self
+            .hold_future_block(&block, proposer_id, signature)
+            .await?
+        {
+            return Ok(result);
+        }
+
+        // a second block the proposer signed for the slot is evidence, the block itself
+        // is still validated like any other
+        let mut signed_header = block.header.clone();
+        signed_header.validator_signature = Some(signature);
+        let evidence = self.double_proposals.lock().await.observe(&signed_header);
+        let equivocation = evidence.is_some();
+        if let Some(evidence) = evidence {
+            self.report_double_proposal(evidence).await?;
+        }
+
+        // a block on an earlier canonical block or a side branch may start a reorg,
+        // unless its proposer already signed another block for the slot
+        let head_hash = self.consensus_engine.lock().await.head_block().1;
+        if !equivocation && block.header.parent_hash != head_hash && block_hash != head_hash {
+            let fork_choice = self.fork_choice.lock().await;
+            let on_fork =
+                fork_choice.knows(&block.header.parent_hash) && !fork_choice.knows(&block_hash);
+            drop(fork_choice);
+            if on_fork {
+                return self.process_fork_block(block).await;
+            }
         }
 
         // Step 2: Full block validation
-        match self.validate_block(&block).await {
+        let result = match self.validate_block(&block).await {
             Ok(true) => {
                 // commit the validated block, in consensus and execution state
                 self.commit_validated_block(&block).await?;
+                self.performance
+                    .lock()
+                    .await
+                    .record_block_import(import_started.elapsed());
                 println!("Blockchain: Block {} validation passed", block.header.index);
                 Ok(BlockProcessResult::Accepted(block_hash))
             }
@@ -151,72 +970,642 @@ impl Blockchain {
                 block_hash,
                 format!("Validation error: {}", e),
             )),
+        };
+        if let Ok(BlockProcessResult::Rejected(_, reason)) = &result {
+            self.record_event(
+                &block.header,
+                ConsensusEventKind::Rejected {
+                    reason: reason.clone(),
+                },
+            )
+            .await;
         }
+        result
     }
 
-    // commit validated block by updating consensus values, and execution state
-    async fn commit_validated_block(&self, block: &Block) -> Result<()> {
-        // Execute transactions and commit state changes
-        let mut block_copy = block.clone();
-        let _ = self
-            .execution_engine
-            .execute_block_commit(&mut block_copy)
-            .await?;
-
-        // Store the block to disk
-        self.store_block(&block).await?;
-
-        // Update consensus engine state
-        let mut consensus = self.consensus_engine.lock().await;
-        consensus.update_best_block(&block).await?;
-
-        println!("Blockchain: Block {} state committed", block.header.index);
-        Ok(())
-    }
-
-    // verify block builder's signature
-    fn verify_proposer_signature(
+    // hold a block whose slot hasn't started or whose parent we don't have, None when it
+    // can be validated now; blocks past the look-ahead are rejected, a full queue holds nothing
+    async fn hold_future_block(
         &self,
         block: &Block,
-        proposer_id: &Address,
-        signature: &Signature,
-    ) -> Result<bool> {
-        if *proposer_id != block.header.proposer {
-            return Ok(false);
+        proposer_id: Address,
+        signature: Signature,
+    ) -> Result<Option<BlockProcessResult>> {
+        let Some(config) = &self.future_blocks else {
+            return Ok(None);
+        };
+        let block_hash = block.header.hash();
+        let (current_slot, head_hash) = {
+            let consensus = self.consensus_engine.lock().await;
+            let Ok(current_slot) = consensus.current_slot() else {
+                return Ok(None);
+            };
+            (current_slot, consensus.head_block().1)
+        };
+
+        let slot = block.header.slot;
+        if slot > current_slot + config.max_slots_ahead {
+            return Ok(Some(BlockProcessResult::Rejected(
+                block_hash,
+                format!(
+                    "Block is for slot {}, more than {} slots after our slot {}",
+                    slot, config.max_slots_ahead, current_slot
+                ),
+            )));
         }
+        let parent_hash = block.header.parent_hash;
+        let reason = if slot > current_slot {
+            format!("Slot {} has not started", slot)
+        } else if parent_hash != head_hash && !self.knows_block(&parent_hash).await? {
+            format!("Parent {} is not imported", parent_hash)
+        } else {
+            return Ok(None);
+        };
 
-        let block_hash = block.header.hash();
-        match signature.recover_address_from_prehash(&block_hash) {
-            Ok(recovered_address) => Ok(recovered_address == *proposer_id),
-            Err(_) => Ok(false),
+        let future = FutureBlock {
+            block: block.clone(),
+            proposer_id,
+            signature,
+        };
+        if !self.held_blocks.lock().await.insert(future) {
+            println!(
+                "⚠️  Future block queue is full, validating block #{} now",
+                block.header.index
+            );
+            return Ok(None);
         }
+        println!(
+            "⏳ Holding block #{} for slot {}: {}",
+            block.header.index, slot, reason
+        );
+        Ok(Some(BlockProcessResult::Queued(block_hash, reason)))
     }
 
-    // execute by simulating state changes
-    async fn validate_execution(&self, block: &Block) -> Result<bool> {
-        let mut block_copy = block.clone();
-
-        // Use simulate instead of commit (you already have this method)
-        match self
-            .execution_engine
-            .simulate_execute_block(&mut block_copy.transactions)
-            .await
-        {
-            Ok(valid_txs) => {
-                // Check if all transactions are valid
-                if valid_txs.len() != block.transactions.len() {
-                    println!("Blockchain: Some transactions failed validation");
-                    return Ok(false);
-                }
-
-                // For a complete check, you'd need a dry-run execution method
-                // that returns the state root without committing
-                Ok(true) // Simplified for now
-            }
-            Err(e) => {
-                println!("Blockchain: Transaction simulation failed: {}", e);
-                Ok(false)
-            }
+    // a canonical or side branch block, in memory or stored
+    async fn knows_block(&self, hash: &B256) -> Result<bool> {
+        if self.fork_choice.lock().await.knows(hash) {
+            return Ok(true);
+        }
+        Ok(self
+            .store
+            .lock()
+            .await
+            .get_block_from_block_hash::<Block>(hash)?
+            .is_some())
+    }
+
+    // held blocks whose slot started and whose parent was imported since, lowest first,
+    // to be processed again; blocks that waited too long for their parent are dropped
+    pub async fn release_future_blocks(&self) -> Result<Vec<FutureBlock>> {
+        let Some(config) = &self.future_blocks else {
+            return Ok(Vec::new());
+        };
+        let (current_slot, head_hash) = {
+            let consensus = self.consensus_engine.lock().await;
+            let Ok(current_slot) = consensus.current_slot() else {
+                return Ok(Vec::new());
+            };
+            (current_slot, consensus.head_block().1)
+        };
+        let parents = {
+            let mut held = self.held_blocks.lock().await;
+            let dropped = held.prune(current_slot.saturating_sub(config.max_wait_slots));
+            if dropped > 0 {
+                println!(
+                    "🗑️  Dropped {} held blocks, their parent didn't arrive in {} slots",
+                    dropped, config.max_wait_slots
+                );
+            }
+            held.parents()
+        };
+
+        let mut known = HashSet::new();
+        for parent in parents {
+            if parent == head_hash || self.knows_block(&parent).await? {
+                known.insert(parent);
+            }
+        }
+        Ok(self
+            .held_blocks
+            .lock()
+            .await
+            .take_ready(current_slot, |parent| known.contains(parent)))
+    }
+
+    // blocks waiting in the future block queue
+    pub async fn held_block_count(&self) -> usize {
+        self.held_blocks.lock().await.len()
+    }
+
+    // keep a block that doesn't build on our head, and switch to its branch once it's longer
+    async fn process_fork_block(&self, block: Block) -> Result<BlockProcessResult> {
+        let block_hash = block.header.hash();
+        let head = {
+            let consensus = self.consensus_engine.lock().await;
+            if consensus.scheduled_proposer(block.header.slot) != Some(block.header.proposer) {
+                return Ok(BlockProcessResult::Rejected(
+                    block_hash,
+                    format!("Not the scheduled proposer of slot {}", block.header.slot),
+                ));
+            }
+            consensus.head()
+        };
+
+        // nothing is kept that conflicts with a finalized checkpoint, e.g. from a sync peer
+        let index = block.header.index;
+        if let Some(finalized) = self.store.lock().await.get_finalized_checkpoint()?
+            && index <= finalized.number
+        {
+            return Ok(BlockProcessResult::Rejected(
+                block_hash,
+                format!(
+                    "Block #{} conflicts with the finalized checkpoint of epoch {}",
+                    index, finalized.epoch
+                ),
+            ));
+        }
+        let branch = {
+            let mut fork_choice = self.fork_choice.lock().await;
+            fork_choice.add_side_block(block);
+            fork_choice.branch(&block_hash)
+        };
+        match branch {
+            Some((fork_point, blocks)) if index > head.number => {
+                self.reorg(fork_point, blocks).await
+            }
+            _ => {
+                println!(
+                    "🍴 Block #{} {} kept on a side branch, head stays at #{}",
+                    index, block_hash, head.number
+                );
+                Ok(BlockProcessResult::Forked(block_hash))
+            }
+        }
+    }
+
+    // replace the canonical blocks after `fork_point` with `branch`, a longer branch on it
+    // the state is rewound to the fork point's checkpoint and the branch executed on top,
+    // transactions only the replaced blocks had go back to the mempool
+    async fn reorg(&self, fork_point: B256, branch: Vec<Block>) -> Result<BlockProcessResult> {
+        let tip = branch.last().expect("a branch has blocks").header.hash();
+        let head = self.consensus_engine.lock().await.head();
+        let (fork_number, fork_slot) = {
+            let fork_choice = self.fork_choice.lock().await;
+            let checkpoint = fork_choice
+                .checkpoint(&fork_point)
+                .ok_or_else(|| anyhow!("Fork point {} has no checkpoint", fork_point))?;
+            (checkpoint.head.number, checkpoint.head.slot)
+        };
+
+        // finalized blocks and closed epochs are never rewound
+        if let Some(finalized) = self.finalized_head().await
+            && fork_number < finalized.number
+        {
+            return Ok(BlockProcessResult::Rejected(
+                tip,
+                format!("Branch forks below finalized block #{}", finalized.number),
+            ));
+        }
+        if let Some(finalized) = self.store.lock().await.get_finalized_checkpoint()?
+            && fork_number < finalized.number
+        {
+            return Ok(BlockProcessResult::Rejected(
+                tip,
+                format!(
+                    "Branch forks below the finalized checkpoint of epoch {}",
+                    finalized.epoch
+                ),
+            ));
+        }
+        if self.consensus_config.epoch_of(fork_slot) != self.consensus_config.epoch_of(head.slot) {
+            return Ok(BlockProcessResult::Rejected(
+                tip,
+                format!(
+                    "Branch forks before epoch {} closed",
+                    self.consensus_config.epoch_of(head.slot)
+                ),
+            ));
+        }
+
+        let abandoned = {
+            let storage = self.store.lock().await;
+            let mut blocks = Vec::new();
+            for index in fork_number + 1..=head.number {
+                let block = storage
+                    .get_block_hash_from_index(&index)?
+                    .and_then(|hash| storage.get_block_from_block_hash::<Block>(&hash).ok()?)
+                    .ok_or_else(|| anyhow!("Canonical block #{} is missing", index))?;
+                blocks.push(block);
+            }
+            blocks
+        };
+
+        let (checkpoint, undo) = self
+            .fork_choice
+            .lock()
+            .await
+            .rewind(&fork_point)
+            .expect("checkpoint was just found");
+        self.rewind_to(&checkpoint, undo, &abandoned).await?;
+        for block in &branch {
+            self.fork_choice
+                .lock()
+                .await
+                .remove_side_block(&block.header.hash());
+        }
+
+        if let Err(e) = self.apply_blocks(&branch).await {
+            // back to the chain we had, its blocks were valid before
+            println!("⚠️  Reorg to {} failed, keeping our chain: {}", tip, e);
+            let (_, undo) = self
+                .fork_choice
+                .lock()
+                .await
+                .rewind(&fork_point)
+                .expect("the fork point keeps its checkpoint");
+            self.rewind_to(&checkpoint, undo, &branch).await?;
+            self.apply_blocks(&abandoned).await?;
+            return Ok(BlockProcessResult::Rejected(
+                tip,
+                format!("Branch is invalid: {}", e),
+            ));
+        }
+
+        // the branch took its transactions out of the mempool, the replaced blocks give theirs
+        // back and stay around, a longer branch on them can win again
+        let included: HashSet<B256> = branch
+            .iter()
+            .flat_map(|block| block.transactions.iter().map(|tx| tx.hash))
+            .collect();
+        for tx_hash in &included {
+            self.execution_engine.drop_transaction(tx_hash).await;
+        }
+        // the safe head may have been one of the replaced blocks
+        let mut safe_head = self.safe_head.lock().await;
+        if safe_head
+            .as_ref()
+            .is_some_and(|head| head.number > fork_number)
+        {
+            *safe_head = None;
+        }
+        drop(safe_head);
+        let mut returned = 0;
+        for block in &abandoned {
+            for tx in block
+                .transactions
+                .iter()
+                .filter(|tx| !included.contains(&tx.hash))
+            {
+                if self
+                    .execution_engine
+                    .add_transaction_from(tx, TransactionSource::Reorg)
+                    .await
+                    .is_ok()
+                {
+                    returned += 1;
+                }
+            }
+            self.fork_choice.lock().await.add_side_block(block.clone());
+        }
+
+        let new_head = branch.last().expect("a branch has blocks");
+        println!(
+            "🔀 Reorg at #{}: {} blocks replaced by {}, head #{} {}, {} transactions back in the mempool",
+            fork_number,
+            abandoned.len(),
+            branch.len(),
+            new_head.header.index,
+            tip,
+            returned
+        );
+        self.record_event(
+            &new_head.header,
+            ConsensusEventKind::Reorg {
+                depth: abandoned.len() as u64,
+                old_head: head.hash,
+            },
+        )
+        .await;
+        Ok(BlockProcessResult::Accepted(tip))
+    }
+
+    // state, head and indexes as they were right after the checkpoint's block, `undo` holds
+    // the changes of the checkpoints after it, newest first
+    async fn rewind_to(
+        &self,
+        checkpoint: &HeadCheckpoint,
+        undo: Vec<StateUndo>,
+        removed: &[Block],
+    ) -> Result<()> {
+        let write = {
+            let mut state = self.execution_engine.state_manager.lock().await;
+            let since = state.take_undo();
+            state.apply_undo(since);
+            for undo in undo {
+                state.apply_undo(undo);
+            }
+            state.take_write(checkpoint.head.hash, true)
+        };
+        self.execution_engine.set_head_slot(checkpoint.head.slot);
+        self.execution_engine.set_head_fees(checkpoint.fees);
+        self.consensus_engine
+            .lock()
+            .await
+            .rewind(&checkpoint.head, checkpoint.randomness.clone());
+
+        let storage = self.store.lock().await;
+        for block in removed {
+            storage.remove_block_indexes(block)?;
+        }
+        storage.put_last_index(&checkpoint.head.number)?;
+        storage.put_state(&write)?;
+        storage.put_block_randomness(&checkpoint.randomness)
+    }
+
+    // validate and commit blocks in order, on top of the current head
+    async fn apply_blocks(&self, blocks: &[Block]) -> Result<()> {
+        for block in blocks {
+            if !self.validate_block(block).await? {
+                return Err(anyhow!("Block #{} failed validation", block.header.index));
+            }
+            self.commit_validated_block(block).await?;
+        }
+        Ok(())
+    }
+
+    // remember the head and what its block changed before building on it, a reorg may
+    // rewind to it
+    async fn checkpoint_head(&self, consensus: &ConsensusEngine) {
+        let undo = self.execution_engine.state_manager.lock().await.take_undo();
+        let checkpoint = HeadCheckpoint {
+            head: consensus.head(),
+            fees: self.execution_engine.head_fees(),
+            randomness: consensus.block_randomness().clone(),
+            undo,
+        };
+        self.fork_choice.lock().await.record(checkpoint);
+    }
+
+    // blocks kept off the canonical chain, a reorg switches to them once their branch is longer
+    pub async fn side_blocks(&self) -> usize {
+        self.fork_choice.lock().await.side_blocks()
+    }
+
+    // keep the evidence and slash the proposer when the epoch of the slot closes
+    async fn report_double_proposal(&self, evidence: DoubleProposalEvidence) -> Result<()> {
+        if !self.store.lock().await.put_slashing_evidence(&evidence)? {
+            return Ok(());
+        }
+        println!(
+            "⚔️  Validator {} signed blocks {} and {} for slot {}, slashing when epoch {} closes",
+            evidence.proposer,
+            evidence.first.hash_hex(),
+            evidence.second.hash_hex(),
+            evidence.slot,
+            self.consensus_config.epoch_of(evidence.slot)
+        );
+        self.record_event(
+            &evidence.second,
+            ConsensusEventKind::DoubleProposal {
+                proposer: evidence.proposer,
+            },
+        )
+        .await;
+        self.pending_slashes
+            .lock()
+            .await
+            .push(SlashingEvidence::DoubleProposal(evidence));
+        Ok(())
+    }
+
+    // keep double vote evidence and slash the validator when the epoch of the slot closes
+    pub async fn report_double_vote(
+        &self,
+        evidence: AttestationEquivocationEvidence,
+    ) -> Result<()> {
+        if !self
+            .store
+            .lock()
+            .await
+            .put_attestation_evidence(&evidence)?
+        {
+            return Ok(());
+        }
+        println!(
+            "⚔️  Validator {} signed conflicting votes on {} and {} in slot {}, slashing when epoch {} closes",
+            evidence.validator,
+            evidence.first.block_hash(),
+            evidence.second.block_hash(),
+            evidence.slot,
+            self.consensus_config.epoch_of(evidence.slot)
+        );
+        self.record_event(
+            &evidence.second.header,
+            ConsensusEventKind::DoubleVote {
+                validator: evidence.validator,
+            },
+        )
+        .await;
+        self.pending_slashes
+            .lock()
+            .await
+            .push(SlashingEvidence::DoubleVote(evidence));
+        Ok(())
+    }
+
+    // double proposal evidence stored for a validator, oldest first
+    pub async fn get_slashing_evidence(
+        &self,
+        validator: &Address,
+    ) -> Result<Vec<DoubleProposalEvidence>> {
+        let storage = self.store.lock().await;
+        storage.get_slashing_evidence(validator)
+    }
+
+    // double vote evidence stored for a validator, oldest first
+    pub async fn get_attestation_evidence(
+        &self,
+        validator: &Address,
+    ) -> Result<Vec<AttestationEquivocationEvidence>> {
+        let storage = self.store.lock().await;
+        storage.get_attestation_evidence(validator)
+    }
+
+    // proposers of every slot of an epoch, fixed once the epoch before it had a block
+    pub async fn proposer_schedule(&self, epoch: u64) -> Option<EpochSchedule> {
+        let consensus = self.consensus_engine.lock().await;
+        consensus
+            .epoch_schedule(epoch)
+            .map(|schedule| schedule.as_ref().clone())
+    }
+
+    // proposers of the next `n_slots` slots, up to the end of the next epoch
+    pub async fn upcoming_proposers(&self, n_slots: u64) -> Result<Vec<ProposerDuty>> {
+        self.consensus_engine
+            .lock()
+            .await
+            .upcoming_proposers(n_slots)
+    }
+
+    // commit validated block by updating consensus values, and execution state
+    async fn commit_validated_block(&self, block: &Block) -> Result<()> {
+        self.checkpoint_head(&*self.consensus_engine.lock().await)
+            .await;
+
+        // Execute transactions and commit state changes
+        let mut block_copy = block.clone();
+        let execution_result = self
+            .execution_engine
+            .execute_block_commit(&mut block_copy)
+            .await?;
+
+        // Store the block to disk
+        let write_started = Instant::now();
+        self.store_block(&block).await?;
+        self.store_execution_output(block, &execution_result)
+            .await?;
+        self.performance
+            .lock()
+            .await
+            .record_state_write(write_started.elapsed());
+
+        // Update consensus engine state
+        let mut consensus = self.consensus_engine.lock().await;
+        consensus.apply_network_registrations(&execution_result.network_registrations);
+        if !execution_result.network_registrations.is_empty() {
+            self.persist_validator_set(&consensus).await;
+        }
+        let previous = consensus.head();
+        let skipped_slots = consensus.skipped_slots(block.header.slot);
+        consensus.update_best_block(&block).await?;
+        self.note_skipped_slots(&block.header, skipped_slots).await;
+        self.consensus_metrics
+            .lock()
+            .await
+            .record_block(skipped_slots, attesters(&consensus));
+        self.close_finished_epochs(&mut consensus, &previous, block.header.slot)
+            .await;
+        self.store
+            .lock()
+            .await
+            .put_block_randomness(consensus.block_randomness())?;
+        self.persist_state(block).await?;
+        self.observe_censorship(block, execution_result.total_gas_used)
+            .await;
+        self.publish_block(block, execution_result.receipts);
+        self.record_event(&block.header, ConsensusEventKind::Imported)
+            .await;
+
+        println!("Blockchain: Block {} state committed", block.header.index);
+        Ok(())
+    }
+
+    // verify block builder's signature
+    fn verify_proposer_signature(
+        &self,
+        block: &Block,
+        proposer_id: &Address,
+        signature: &Signature,
+    ) -> Result<bool> {
+        if *proposer_id != block.header.proposer {
+            return Ok(false);
+        }
+
+        let block_hash = block.header.hash();
+        match signature.recover_address_from_prehash(&block_hash) {
+            Ok(recovered_address) => Ok(recovered_address == *proposer_id),
+            Err(_) => Ok(false),
+        }
+    }
+
+    // execute by simulating state changes
+    async fn validate_execution(&self, block: &Block) -> Result<bool> {
+        let mut block_copy = block.clone();
+
+        // the base fee follows from the parent, our head, and chains without one have none
+        let base_fee = self.execution_engine.next_base_fee();
+        if block.header.base_fee_per_gas != base_fee {
+            println!(
+                "Blockchain: Base fee {:?}, expected {:?}",
+                block.header.base_fee_per_gas, base_fee
+            );
+            return Ok(false);
+        }
+        // older blocks carry no gas limit, newer ones the one of the chain
+        let block_gas_limit = self.execution_engine.gas_config().block_gas_limit;
+        if block
+            .header
+            .gas_limit
+            .is_some_and(|gas_limit| gas_limit != block_gas_limit)
+        {
+            println!(
+                "Blockchain: Gas limit {:?}, expected {}",
+                block.header.gas_limit, block_gas_limit
+            );
+            return Ok(false);
+        }
+
+        // Use simulate instead of commit (you already have this method)
+        match self
+            .execution_engine
+            .simulate_execute_block(&mut block_copy.transactions)
+            .await
+        {
+            Ok(valid_txs) => {
+                // Check if all transactions are valid
+                if valid_txs.len() != block.transactions.len() {
+                    println!("Blockchain: Some transactions failed validation");
+                    return Ok(false);
+                }
+
+                // the receipts the header commits to must be the ones execution produces
+                let result = self
+                    .execution_engine
+                    .execute_block_dry_run(&mut block_copy)
+                    .await?;
+                if result.state_root != block.header.state_root {
+                    println!(
+                        "Blockchain: State root mismatch, header 0x{} executed 0x{}",
+                        hex::encode(block.header.state_root),
+                        hex::encode(result.state_root)
+                    );
+                    return Ok(false);
+                }
+                if result.receipts_root != block.header.committed_receipts_root() {
+                    println!(
+                        "Blockchain: Receipts root mismatch, header 0x{} executed 0x{}",
+                        hex::encode(block.header.committed_receipts_root()),
+                        hex::encode(result.receipts_root)
+                    );
+                    return Ok(false);
+                }
+                if result.logs_bloom != block.header.committed_logs_bloom() {
+                    println!("Blockchain: Logs bloom mismatch");
+                    return Ok(false);
+                }
+                // the base fee of the next block depends on the gas used, a gas limit bounds it
+                let records_gas = base_fee.is_some() || block.header.gas_limit.is_some();
+                if block.header.gas_used != records_gas.then_some(result.total_gas_used) {
+                    println!(
+                        "Blockchain: Gas used {:?}, executed {}",
+                        block.header.gas_used, result.total_gas_used
+                    );
+                    return Ok(false);
+                }
+                // the chain's limit, a header leaving it out doesn't lift it
+                if result.total_gas_used > block_gas_limit {
+                    println!(
+                        "Blockchain: Gas used {} is over the block gas limit {}",
+                        result.total_gas_used, block_gas_limit
+                    );
+                    return Ok(false);
+                }
+                Ok(true)
+            }
+            Err(e) => {
+                println!("Blockchain: Transaction simulation failed: {}", e);
+                Ok(false)
+            }
         }
     }
 
@@ -248,8 +1637,170 @@ impl Blockchain {
 
     // Helper method
     // Helper function to all transaction to mempool
-    pub async fn add_transaction_to_mempool(&self, transaction: &Transaction) -> Result<B256> {
-        return self.execution_engine.add_transaction(transaction).await;
+    pub async fn add_transaction_to_mempool(
+        &self,
+        transaction: &Transaction,
+        source: TransactionSource,
+    ) -> Result<B256> {
+        return self
+            .execution_engine
+            .add_transaction_from(transaction, source)
+            .await;
+    }
+
+    // pending transactions grouped by sender and nonce, with where they came from
+    pub async fn txpool_content(&self) -> TxPoolContent {
+        self.execution_engine.txpool_content().await
+    }
+
+    // estimate when a pending transaction will be included, assuming one block per slot
+    pub async fn estimate_inclusion(&self, tx_hash: &B256) -> Option<InclusionEstimate> {
+        let mut estimate = self.execution_engine.estimate_inclusion(tx_hash).await?;
+
+        let slot_duration = self.consensus_engine.lock().await.slot_duration();
+        estimate.estimated_wait_seconds = estimate.blocks_until_inclusion * slot_duration.as_secs();
+
+        Some(estimate)
+    }
+
+    // preview a transaction against the state after block `at_block`, the head when none,
+    // older blocks only while a reorg could still rewind to them
+    pub async fn call(&self, tx: &Transaction, at_block: Option<u64>) -> Result<CallResult> {
+        let head = self.consensus_engine.lock().await.head_block().0;
+        match at_block {
+            None => self.execution_engine.call(tx, None).await,
+            Some(number) if number == head => self.execution_engine.call(tx, None).await,
+            Some(number) if number > head => Err(anyhow!("Block #{} is not known yet", number)),
+            Some(number) => {
+                let fork_choice = self.fork_choice.lock().await;
+                let (checkpoint, undo) = fork_choice
+                    .checkpoint_at(number)
+                    .ok_or_else(|| anyhow!("State at block #{} is no longer kept", number))?;
+                self.execution_engine
+                    .call(tx, Some((checkpoint, &undo)))
+                    .await
+            }
+        }
+    }
+
+    // slot of our best block
+    pub async fn head_slot(&self) -> u64 {
+        self.consensus_engine.lock().await.head_slot()
+    }
+
+    // remember how far the network has progressed
+    pub async fn note_network_slot(&self, slot: u64) {
+        let mut best = self.network_best_slot.lock().await;
+        *best = Some(best.map_or(slot, |best| best.max(slot)));
+    }
+
+    // best slot seen from peers, None until a block arrived
+    pub async fn network_best_slot(&self) -> Option<u64> {
+        *self.network_best_slot.lock().await
+    }
+
+    // how many slots our head trails the best slot seen from peers
+    pub async fn slots_behind(&self) -> u64 {
+        let head_slot = self.head_slot().await;
+        self.network_best_slot()
+            .await
+            .map_or(0, |best| best.saturating_sub(head_slot))
+    }
+
+    // gossip transactions held during sync or our proposal window, checked against the
+    // current state and admitted under the gossip policy, returns how many reached the mempool
+    pub async fn admit_held_transactions(&self, held: Vec<HeldTransaction>) -> usize {
+        let mut admitted = 0;
+        for HeldTransaction { transaction, peer } in held {
+            let result = match self
+                .execution_engine
+                .check_against_state(&transaction)
+                .await
+            {
+                Ok(()) => self
+                    .add_transaction_to_mempool(&transaction, TransactionSource::Gossip { peer })
+                    .await
+                    .map(|_| ()),
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => admitted += 1,
+                Err(e) => println!(
+                    "🗑️  Dropping held transaction {}: {}",
+                    hex::encode(&transaction.hash[..8]),
+                    e
+                ),
+            }
+        }
+        admitted
+    }
+
+    // head, validators, fees and sync status in one go
+    pub async fn chain_info(&self, chain_id: u64) -> ChainInfo {
+        let (validator_count, total_stake) = {
+            let consensus = self.consensus_engine.lock().await;
+            let validators = consensus.validator_set().get_active_validators();
+            let total_stake = validators.iter().map(|v| v.staked_amount).sum();
+            (validators.len(), total_stake)
+        };
+
+        let heads = self.heads().await;
+        let network_best_slot = self.network_best_slot().await;
+        let slots_behind = self.slots_behind().await;
+
+        ChainInfo {
+            chain_id,
+            genesis_hash: self.chain_spec_hash,
+            finalized: heads.finalized,
+            safe: heads.safe,
+            head: heads.latest,
+            validator_count,
+            total_stake,
+            base_fee: self
+                .execution_engine
+                .next_base_fee()
+                .unwrap_or(self.execution_engine.gas_config().min_gas_price),
+            gas_price: self.execution_engine.estimate_gas_price().await,
+            sync: SyncStatus {
+                syncing: slots_behind > 0,
+                network_best_slot,
+                slots_behind,
+            },
+        }
+    }
+
+    // network addresses registered on-chain by active validators
+    pub async fn validator_network_addresses(&self) -> Vec<(Address, String)> {
+        let consensus = self.consensus_engine.lock().await;
+        consensus.validator_set().network_addresses()
+    }
+
+    // write the committed state after `block` through to storage, only what changed when it
+    // builds on the stored state, all of it otherwise
+    async fn persist_state(&self, block: &Block) -> Result<()> {
+        let stored = self.store.lock().await.get_state_head()?;
+        let full = stored.map(|head| head.block_hash) != Some(block.header.parent_hash);
+        let write = self
+            .execution_engine
+            .state_manager
+            .lock()
+            .await
+            .take_write(block.header.hash(), full);
+        let written = self
+            .store
+            .lock()
+            .await
+            .put_state(&write)
+            .context("Failed to store state")?;
+        if full {
+            println!("💾 Stored the full state, {} accounts", written);
+        }
+        Ok(())
+    }
+
+    // whether the state was read back from storage when the chain was opened
+    pub fn state_restored(&self) -> bool {
+        self.state_restored
     }
 
     // call storage layer to store block
@@ -263,25 +1814,1257 @@ impl Blockchain {
         Ok(())
     }
 
-    // get last index from storage
-    pub async fn get_last_index(&self) -> Result<u64> {
-        let store = self.store.lock().await;
-        let last_index: u64 = match store
-            .get_last_index()
-            .context("Failed to retrieve last block index")?
-        {
-            Some(index) => index,
-            None => 0, // No blocks exist
-        };
-        Ok(last_index)
+    // notify subscribers, nobody listening is fine
+    fn publish_block(&self, block: &Block, receipts: Vec<Receipt>) {
+        let _ = self.block_events.send(Arc::new(CommittedBlock {
+            block: block.clone(),
+            receipts,
+        }));
     }
 
-    // get block hash by index
+    // committed blocks from now on, subscribe before reading storage to avoid gaps
+    pub fn subscribe_blocks(&self) -> broadcast::Receiver<Arc<CommittedBlock>> {
+        self.block_events.subscribe()
+    }
+
+    // stored block and receipts by number, receipts are empty if never indexed
+    pub async fn committed_block(&self, index: u64) -> Result<Option<CommittedBlock>> {
+        let storage = self.store.lock().await;
+        let Some(block_hash) = storage.get_block_hash_from_index(&index)? else {
+            return Ok(None);
+        };
+        let Some(block) = storage.get_block_from_block_hash::<Block>(&block_hash)? else {
+            return Ok(None);
+        };
+        let receipts = storage.get_block_receipts(&block_hash)?.unwrap_or_default();
+
+        Ok(Some(CommittedBlock { block, receipts }))
+    }
+
+    // store receipts and state diff of a block, used by exports and indexers
+    async fn store_execution_output(
+        &self,
+        block: &Block,
+        execution_result: &ExecutionResult,
+    ) -> Result<()> {
+        let block_hash = block.header.hash();
+        let storage = self.store.lock().await;
+        storage
+            .put_block_receipts(&block_hash, &execution_result.receipts)
+            .context("Failed to store receipts")?;
+        storage
+            .put_block_state_diff(&block_hash, &execution_result.state_diff)
+            .context("Failed to store state diff")?;
+        storage
+            .put_block_indexes(block)
+            .context("Failed to index block")?;
+
+        Ok(())
+    }
+
+    // slashing protection: one signed block per slot, persisted across restarts
+    async fn guard_proposal(&self, validator: Address, slot: u64) -> Result<()> {
+        let storage = self.store.lock().await;
+        let mut record = storage.get_signing_record(&validator)?;
+        if let Err(e) = record.check_proposal(slot) {
+            println!("🛡️  {}", e);
+            return Err(e.into());
+        }
+        record.record_proposal(slot);
+        storage.put_signing_record(&validator, &record)
+    }
+
+    // slashing protection: never vote for two different blocks of a slot
+    pub async fn guard_attestation(
+        &self,
+        validator: Address,
+        slot: u64,
+        block_hash: B256,
+    ) -> Result<()> {
+        let storage = self.store.lock().await;
+        let mut record = storage.get_signing_record(&validator)?;
+        record.check_attestation(slot, &block_hash)?;
+        record.record_attestation(slot, block_hash);
+        storage.put_signing_record(&validator, &record)
+    }
+
+    // audit a signature of the local key, a failing log is reported but never stops the validator
+    pub async fn record_signature(
+        &self,
+        slot: u64,
+        block_hash: B256,
+        message: SignedMessage,
+        signer: Address,
+        signature: Signature,
+    ) {
+        let Some(log) = &self.signing_audit else {
+            return;
+        };
+        let mut log = log.lock().await;
+        if let Err(e) = log.append(slot, block_hash, message, signer, signature) {
+            println!(
+                "⚠️  Failed to write signing audit log {}: {:#}",
+                log.path().display(),
+                e
+            );
+        }
+    }
+
+    // what a validator already signed on this node
+    pub async fn signing_record(&self, validator: &Address) -> Result<SigningRecord> {
+        let storage = self.store.lock().await;
+        storage.get_signing_record(validator)
+    }
+
+    // duties of the local validator for the current slot, logged when the service starts
+    pub async fn duty_status(&self) -> Result<DutyStatus> {
+        let (slot, head_slot, head_number, proposer, validator) = {
+            let consensus = self.consensus_engine.lock().await;
+            let slot = consensus.current_slot()?;
+            (
+                slot,
+                consensus.head_slot(),
+                consensus.head_block().0,
+                consensus.scheduled_proposer(slot),
+                consensus.local_validator(),
+            )
+        };
+        let record = match validator {
+            Some(validator) => self.signing_record(&validator).await?,
+            None => SigningRecord::default(),
+        };
+
+        Ok(DutyStatus {
+            slot,
+            epoch: self.consensus_config.epoch_of(slot),
+            head_number,
+            head_slot,
+            proposer_of_slot: proposer,
+            is_proposer: proposer.is_some() && proposer == validator,
+            proposed_this_slot: record.proposed_in(slot),
+            attested_this_slot: record.attested_in(slot),
+        })
+    }
+
+    // the local validator's duty in the current slot, None when it has none
+    pub async fn current_role(&self) -> Option<ValidatorRole> {
+        let consensus = self.consensus_engine.lock().await;
+        consensus.role_in_slot(consensus.current_slot().ok()?)
+    }
+
+    // the local validator's duty in `slot`, e.g. whether to vote on a block of that slot
+    pub async fn slot_role(&self, slot: u64) -> Option<ValidatorRole> {
+        self.consensus_engine.lock().await.role_in_slot(slot)
+    }
+
+    // next slot, when the local validator is scheduled to propose it
+    async fn own_next_slot(&self) -> Option<u64> {
+        let consensus = self.consensus_engine.lock().await;
+        let next_slot = consensus.current_slot().ok()? + 1;
+        let local = consensus.local_validator()?;
+        (consensus.scheduled_proposer(next_slot) == Some(local)).then_some(next_slot)
+    }
+
+    // warm up the block for the next slot when this node proposes it, None otherwise
+    pub async fn prefetch_for_next_slot(&self, max_transactions: usize) -> Option<PrefetchReport> {
+        let next_slot = self.own_next_slot().await?;
+
+        // warmed up already, the prefetch is consumed by the next block simulation
+        if self.execution_engine.prefetched_slot().await == Some(next_slot) {
+            return None;
+        }
+
+        Some(
+            self.execution_engine
+                .prefetch_for_proposal(next_slot, max_transactions)
+                .await,
+        )
+    }
+
+    // select the transactions of the next slot's block when this node proposes it,
+    // transactions arriving until then are added to it, None when not our slot
+    pub async fn build_template_for_next_slot(
+        &self,
+        max_transactions: usize,
+    ) -> Option<BlockTemplateReport> {
+        let next_slot = self.own_next_slot().await?;
+        if self.execution_engine.block_template_slot().await == Some(next_slot) {
+            return None;
+        }
+
+        Some(
+            self.execution_engine
+                .build_block_template(next_slot, max_transactions)
+                .await,
+        )
+    }
+
+    // count an accept vote towards the epoch of the block it attests and its finality
+    pub async fn record_attestation(&self, block_hash: &B256, validator: Address) -> Result<()> {
+        let block = {
+            let storage = self.store.lock().await;
+            storage.get_block_from_block_hash::<Block>(block_hash)?
+        };
+
+        // votes for blocks we never committed are not counted
+        let Some(block) = block else {
+            return Ok(());
+        };
+        self.consensus_metrics.lock().await.record_attestation();
+
+        {
+            let mut attestations = self.epoch_attestations.lock().await;
+            *attestations
+                .entry(self.consensus_config.epoch_of(block.header.slot))
+                .or_default()
+                .entry(validator)
+                .or_default() += 1;
+        }
+
+        let finalized_slot = self.finalized_head().await.map(|head| head.slot);
+        if finalized_slot.is_none_or(|slot| block.header.slot > slot) {
+            self.block_votes
+                .lock()
+                .await
+                .entry(*block_hash)
+                .or_insert_with(|| (block.header.slot, HashSet::new()))
+                .1
+                .insert(validator);
+            self.advance_safe_head(&block).await?;
+        }
+
+        Ok(())
+    }
+
+    // move the safe head up to a canonical block once more than half of the stake accepted it
+    async fn advance_safe_head(&self, block: &Block) -> Result<()> {
+        let block_hash = block.header.hash();
+        if self.safe_head().await.number >= block.header.index {
+            return Ok(());
+        }
+        // a block a reorg replaced keeps collecting votes, it is not safe
+        let canonical = self
+            .store
+            .lock()
+            .await
+            .get_block_hash_from_index(&block.header.index)?
+            == Some(block_hash);
+        if !canonical {
+            return Ok(());
+        }
+
+        let (accepted_stake, total_stake, _) = self.accepted_stake(&block.header).await;
+        if accepted_stake < safe_quorum(total_stake) {
+            return Ok(());
+        }
+        *self.safe_head.lock().await = Some(HeadInfo {
+            number: block.header.index,
+            hash: block_hash,
+            slot: block.header.slot,
+        });
+        Ok(())
+    }
+
+    // store a finality certificate once the votes for a committed block reach a quorum
+    // returns it the first time only, uncommitted blocks and short counts return None
+    pub async fn certify_block(
+        &self,
+        block_hash: &B256,
+        attestations: &[Attestation],
+    ) -> Result<Option<FinalityCertificate>> {
+        let block = {
+            let storage = self.store.lock().await;
+            if storage.get_finality_certificate(block_hash)?.is_some() {
+                return Ok(None);
+            }
+            storage.get_block_from_block_hash::<Block>(block_hash)?
+        };
+        let Some(block) = block else {
+            return Ok(None);
+        };
+
+        let validators = self.certified_validators().await;
+        let Some(certificate) = FinalityCertificate::assemble(
+            block.header,
+            attestations,
+            validators,
+            self.consensus_config.quorum,
+        ) else {
+            return Ok(None);
+        };
+
+        let header = &certificate.header;
+        {
+            let storage = self.store.lock().await;
+            storage.put_finality_certificate(&certificate)?;
+            // certificates can arrive out of order, the finalized head only moves up
+            let advanced = storage
+                .get_finalized_head()?
+                .is_none_or(|head| header.index > head.number);
+            if advanced {
+                storage.put_finalized_head(&HeadInfo {
+                    number: header.index,
+                    hash: certificate.block_hash,
+                    slot: header.slot,
+                })?;
+                self.block_votes
+                    .lock()
+                    .await
+                    .retain(|_, (slot, _)| *slot > header.slot);
+            }
+        }
+        self.justify_epoch_checkpoints().await?;
+        let slot_start = self.consensus_engine.lock().await.slot_start(header.slot);
+        self.consensus_metrics
+            .lock()
+            .await
+            .record_quorum(slot_start.elapsed().unwrap_or_default());
+        self.record_event(&certificate.header, ConsensusEventKind::Certified)
+            .await;
+        Ok(Some(certificate))
+    }
+
+    // justify the closed epochs' checkpoints the finalized head covers, in epoch order,
+    // like casper ffg a justified checkpoint is finalized by the next one justified on top of it
+    async fn justify_epoch_checkpoints(&self) -> Result<()> {
+        let storage = self.store.lock().await;
+        let Some(certified) = storage.get_finalized_head()? else {
+            return Ok(());
+        };
+        let mut justified = storage.get_justified_checkpoint()?;
+        let mut epoch = match &justified {
+            Some(justified) => justified.epoch + 1,
+            // a database started from a trusted checkpoint closes no epoch before it
+            None => storage.get_checkpoint()?.map_or(0, |checkpoint| {
+                self.consensus_config.epoch_of(checkpoint.block.header.slot)
+            }),
+        };
+
+        while let Some(mut checkpoint) = storage.get_epoch_checkpoint(epoch)? {
+            if !checkpoint.covered_by(certified.number) {
+                break;
+            }
+            if let Some(previous) = justified.as_mut()
+                && previous.epoch + 1 == epoch
+            {
+                previous.status = CheckpointStatus::Finalized;
+                storage.put_epoch_checkpoint(previous)?;
+                storage.put_finalized_checkpoint(previous)?;
+                println!(
+                    "🏁 Epoch {} checkpoint #{} {} finalized",
+                    previous.epoch, previous.number, previous.hash
+                );
+            }
+            checkpoint.status = CheckpointStatus::Justified;
+            storage.put_epoch_checkpoint(&checkpoint)?;
+            storage.put_justified_checkpoint(&checkpoint)?;
+            justified = Some(checkpoint);
+            epoch += 1;
+        }
+        Ok(())
+    }
+
+    // newest justified and finalized epoch checkpoints
+    pub async fn finality_checkpoints(&self) -> Result<FinalityCheckpoints> {
+        let storage = self.store.lock().await;
+        Ok(FinalityCheckpoints {
+            justified: storage.get_justified_checkpoint()?,
+            finalized: storage.get_finalized_checkpoint()?,
+        })
+    }
+
+    // checkpoint of a closed epoch, None while the epoch runs
+    pub async fn epoch_checkpoint(&self, epoch: u64) -> Result<Option<EpochCheckpoint>> {
+        self.store.lock().await.get_epoch_checkpoint(epoch)
+    }
+
+    // highest certified block, None until the first certificate is stored
+    pub async fn finalized_head(&self) -> Option<HeadInfo> {
+        let storage = self.store.lock().await;
+        storage.get_finalized_head().ok().flatten()
+    }
+
+    // finalized or how far a committed block is from the quorum, None if not committed
+    pub async fn finality_status(&self, block_hash: &B256) -> Result<Option<BlockFinality>> {
+        let (block, certified, finalized) = {
+            let storage = self.store.lock().await;
+            (
+                storage.get_block_from_block_hash::<Block>(block_hash)?,
+                storage.get_finality_certificate(block_hash)?.is_some(),
+                storage.get_finalized_head()?,
+            )
+        };
+        let Some(block) = block else {
+            return Ok(None);
+        };
+        // blocks only ever extend the head, ancestors of a final block are final
+        if certified || finalized.is_some_and(|head| block.header.index <= head.number) {
+            return Ok(Some(BlockFinality::Finalized));
+        }
+
+        let (accepted_stake, total_stake, voters) = self.accepted_stake(&block.header).await;
+        Ok(Some(BlockFinality::Pending {
+            accepted_stake,
+            quorum_stake: self.consensus_config.quorum.of(total_stake),
+            voters,
+        }))
+    }
+
+    // stake of the proposer and the accept votes of a block, the active stake, and the voter count
+    async fn accepted_stake(&self, header: &BlockHeader) -> (u64, u64, usize) {
+        let mut signers = self
+            .block_votes
+            .lock()
+            .await
+            .get(&header.hash())
+            .map(|(_, voters)| voters.clone())
+            .unwrap_or_default();
+        signers.insert(header.proposer);
+
+        let validators = self.certified_validators().await;
+        let total_stake = validators.iter().map(|v| v.stake).sum();
+        let accepted_stake = validators
+            .iter()
+            .filter(|v| signers.contains(&v.address))
+            .map(|v| v.stake)
+            .sum();
+        (accepted_stake, total_stake, signers.len() - 1)
+    }
+
+    // head of our chain, the latest block proposed or imported
+    pub async fn latest_head(&self) -> HeadInfo {
+        let consensus = self.consensus_engine.lock().await;
+        let (number, hash) = consensus.head_block();
+        HeadInfo {
+            number,
+            hash,
+            slot: consensus.head_slot(),
+        }
+    }
+
+    // highest canonical block more than half of the stake accepted, the finalized head
+    // when that is higher, genesis until either exists
+    pub async fn safe_head(&self) -> HeadInfo {
+        let finalized = self.finalized_head().await;
+        let safe = self.safe_head.lock().await.clone();
+        match (safe, finalized) {
+            (Some(safe), Some(finalized)) if finalized.number >= safe.number => finalized,
+            (Some(safe), _) => safe,
+            (None, finalized) => finalized.unwrap_or_else(genesis_head),
+        }
+    }
+
+    pub async fn heads(&self) -> ChainHeads {
+        ChainHeads {
+            latest: self.latest_head().await,
+            safe: self.safe_head().await,
+            finalized: self.finalized_head().await.unwrap_or_else(genesis_head),
+        }
+    }
+
+    // the head a client asked for by confirmation level
+    pub async fn head(&self, tag: HeadTag) -> HeadInfo {
+        match tag {
+            HeadTag::Latest => self.latest_head().await,
+            HeadTag::Safe => self.safe_head().await,
+            HeadTag::Finalized => self.finalized_head().await.unwrap_or_else(genesis_head),
+        }
+    }
+
+    // active validators and their stake, as finality certificates list them
+    async fn certified_validators(&self) -> Vec<CertifiedValidator> {
+        let consensus = self.consensus_engine.lock().await;
+        consensus
+            .validator_set()
+            .get_active_validators()
+            .iter()
+            .map(|v| CertifiedValidator {
+                address: v.address,
+                stake: v.staked_amount,
+            })
+            .collect()
+    }
+
+    // stored finality certificate of a block, null until a quorum accepted it
+    pub async fn get_finality_certificate(
+        &self,
+        block_hash: &B256,
+    ) -> Result<Option<FinalityCertificate>> {
+        let storage = self.store.lock().await;
+        storage.get_finality_certificate(block_hash)
+    }
+
+    // store a rejected block with why and from whom, no-op unless the quarantine is on
+    pub async fn quarantine_block(
+        &self,
+        block: Block,
+        proposer: Address,
+        reason: String,
+        from_peer: String,
+    ) -> Result<Option<u64>> {
+        if !self.quarantine.enabled {
+            return Ok(None);
+        }
+        let rejected = RejectedBlock {
+            sequence: 0,
+            block_hash: block.header.hash(),
+            block_number: block.header.index,
+            slot: block.header.slot,
+            proposer,
+            reason,
+            from_peer,
+            rejected_at_ms: unix_millis(SystemTime::now()),
+            block,
+        };
+        let storage = self.store.lock().await;
+        storage
+            .put_rejected_block(rejected, self.quarantine.max_blocks)
+            .map(Some)
+    }
+
+    // quarantined blocks claiming a number in `from_block..=to_block`, newest first
+    pub async fn get_rejected_blocks(
+        &self,
+        from_block: Option<u64>,
+        to_block: Option<u64>,
+    ) -> Result<Vec<RejectedBlock>> {
+        let storage = self.store.lock().await;
+        storage.get_rejected_blocks(
+            from_block,
+            to_block,
+            self.quarantine.max_blocks,
+            MAX_REJECTED_BLOCKS_PER_REQUEST,
+        )
+    }
+
+    // measure how late a gossiped block arrived, against our slot clock and the proposer's timestamp
+    pub async fn record_block_arrival(
+        &self,
+        block: &Block,
+        from_peer: String,
+        received_at_ms: u64,
+    ) -> Option<BlockArrival> {
+        let slot_start_ms = {
+            let consensus = self.consensus_engine.lock().await;
+            unix_millis(consensus.slot_start(block.header.slot))
+        };
+        let arrival = BlockArrival {
+            block_hash: block.header.hash(),
+            block_number: block.header.index,
+            slot: block.header.slot,
+            peer: from_peer,
+            received_at_ms,
+            since_slot_start_ms: received_at_ms as i64 - slot_start_ms as i64,
+            since_proposer_ms: received_at_ms as i64 - (block.header.timestamp * 1000) as i64,
+        };
+
+        let recorded = self.propagation.lock().await.record(arrival.clone());
+        if recorded {
+            self.validator_scores
+                .lock()
+                .await
+                .record_latency(block.header.proposer, arrival.since_slot_start_ms);
+        }
+        recorded.then_some(arrival)
+    }
+
+    // beacon rounds still to fetch for the current and the next epoch
+    // epoch n uses the round published when epoch n-1 started, so it is known an epoch ahead
+    pub async fn missing_beacon_rounds(&self, beacon: &RandomnessBeacon) -> Vec<(u64, u64)> {
+        let consensus = self.consensus_engine.lock().await;
+        let Ok(slot) = consensus.current_slot() else {
+            return Vec::new();
+        };
+        let epoch = self.consensus_config.epoch_of(slot);
+        [epoch, epoch + 1]
+            .into_iter()
+            .filter(|&epoch| epoch > 0 && !consensus.has_epoch_randomness(epoch))
+            .map(|epoch| {
+                let round = beacon.round_at(consensus.nominal_epoch_start(epoch - 1));
+                (epoch, round)
+            })
+            .filter(|&(_, round)| round > 0)
+            .collect()
+    }
+
+    pub async fn set_epoch_randomness(&self, epoch: u64, round: u64, randomness: B256) {
+        self.consensus_engine
+            .lock()
+            .await
+            .set_epoch_randomness(epoch, randomness);
+        println!(
+            "🎲 Epoch {} proposer schedule mixed with beacon round {}",
+            epoch, round
+        );
+        self.consensus_events.lock().await.record(
+            self.consensus_config.epoch_start_slot(epoch),
+            0,
+            B256::ZERO,
+            ConsensusEventKind::BeaconMixed { epoch, round },
+        );
+    }
+
+    // the chain moved past slots nobody proposed in, e.g. because their proposer was offline
+    async fn note_skipped_slots(&self, header: &BlockHeader, skipped_slots: u64) {
+        if skipped_slots == 0 {
+            return;
+        }
+        println!(
+            "⏭️  {} empty slots before block #{} at slot {}",
+            skipped_slots, header.index, header.slot
+        );
+        self.record_event(
+            header,
+            ConsensusEventKind::SlotsSkipped {
+                slots: skipped_slots,
+            },
+        )
+        .await;
+    }
+
+    // note a consensus event about a block for debug bundles
+    async fn record_event(&self, header: &BlockHeader, kind: ConsensusEventKind) {
+        self.consensus_events
+            .lock()
+            .await
+            .record(header.slot, header.index, header.hash(), kind);
+    }
+
+    // consensus and mempool state with the `events` latest consensus events
+    pub async fn debug_state(&self, events: usize) -> DebugState {
+        let consensus = {
+            let consensus = self.consensus_engine.lock().await;
+            let (head_number, head_hash) = consensus.head_block();
+            ConsensusDebugState {
+                head_number,
+                head_hash,
+                head_slot: consensus.head_slot(),
+                current_slot: consensus.current_slot().ok(),
+                slot_duration_ms: consensus.slot_duration().as_millis() as u64,
+                local_validator: consensus.local_validator(),
+                validators: consensus
+                    .validator_set()
+                    .get_active_validators()
+                    .iter()
+                    .map(|v| (v.address, v.staked_amount))
+                    .collect(),
+                adaptive_slots: consensus.adaptive_slots().is_some(),
+                randomness_epochs: consensus.randomness_epochs(),
+            }
+        };
+        DebugState {
+            consensus,
+            mempool: self.execution_engine.mempool_summary().await,
+            events: self.consensus_events.lock().await.recent(events),
+        }
+    }
+
+    // time left of the first `window` of the current slot, when this node proposes in it and
+    // hasn't built its block yet, None otherwise
+    pub async fn proposal_window_left(&self, window: Duration) -> Option<Duration> {
+        let consensus = self.consensus_engine.lock().await;
+        let validator = consensus.local_validator()?;
+        let slot = consensus.current_slot().ok()?;
+        if consensus.head_slot() >= slot || consensus.scheduled_proposer(slot) != Some(validator) {
+            return None;
+        }
+        let elapsed = SystemTime::now()
+            .duration_since(consensus.slot_start(slot))
+            .unwrap_or_default();
+        window.checked_sub(elapsed).filter(|left| !left.is_zero())
+    }
+
+    // p90 latency of recent gossiped blocks after their slot start, reported in our
+    // headers on adaptive slot chains, 0 when adaptive slots are off or nothing arrived yet
+    async fn reported_propagation_ms(&self) -> u64 {
+        let window = match self.consensus_engine.lock().await.adaptive_slots() {
+            Some(adaptive) => adaptive.config().window_blocks,
+            None => return 0,
+        };
+        let latency = self.propagation.lock().await.recent_latency(window);
+        latency.p90_ms.max(0) as u64
+    }
+
+    // latency percentiles over the tracked window, with the `recent` latest arrivals
+    pub async fn propagation_stats(&self, recent: usize) -> PropagationStats {
+        self.propagation.lock().await.stats(recent)
+    }
+
+    // network messages waiting for the blockchain service
+    pub async fn record_gossip_backlog(&self, queued: usize) {
+        self.performance.lock().await.record_gossip_backlog(queued);
+    }
+
+    // gossip transactions that waited for a proposal window to end
+    pub async fn record_deferred_gossip(&self, deferred: usize) {
+        self.performance
+            .lock()
+            .await
+            .record_deferred_gossip(deferred);
+    }
+
+    // check which executable pending transactions a committed block left out
+    async fn observe_censorship(&self, block: &Block, gas_used: U256) {
+        let executable = self.execution_engine.executable_transactions().await;
+        let gas_room = self
+            .execution_engine
+            .gas_config()
+            .block_gas_limit
+            .saturating_sub(gas_used);
+        self.censorship
+            .lock()
+            .await
+            .observe_block(block, &executable, gas_room);
+    }
+
+    // pending transactions skipped by at least `min_proposers` proposers
+    pub async fn censorship_report(&self, min_proposers: usize) -> CensorshipReport {
+        self.censorship.lock().await.report(min_proposers)
+    }
+
+    // runtime samples and tuning advice against the slot budget
+    pub async fn performance_report(&self, knobs: TuningKnobs) -> PerformanceReport {
+        let samples = self.performance.lock().await.samples();
+        let slot_duration = self.consensus_engine.lock().await.slot_duration();
+        PerformanceReport::new(samples, knobs, slot_duration)
+    }
+
+    // consensus counters since the node started
+    pub async fn consensus_metrics(&self) -> ConsensusMetricsReport {
+        self.consensus_metrics.lock().await.report()
+    }
+
+    // fold a closed epoch's duties into the scores, stored with the latency samples since the
+    // previous epoch
+    async fn record_validator_scores(&self, records: &[LivenessRecord]) -> Result<()> {
+        let mut scores = self.validator_scores.lock().await;
+        scores.record_epoch(records);
+        self.store.lock().await.put_validator_scores(&scores)
+    }
+
+    // every scored validator, or the one asked for
+    pub async fn validator_scores(&self, validator: Option<Address>) -> Vec<ValidatorScoreReport> {
+        let scores = self.validator_scores.lock().await;
+        match validator {
+            Some(validator) => scores
+                .get(&validator)
+                .map(|score| score.report(validator))
+                .into_iter()
+                .collect(),
+            None => scores.report(),
+        }
+    }
+
+    // stored statement of a validator for a closed epoch
+    pub async fn get_epoch_statement(
+        &self,
+        validator: &Address,
+        epoch: u64,
+    ) -> Result<Option<EpochStatement>> {
+        let storage = self.store.lock().await;
+        storage.get_epoch_statement(epoch, validator)
+    }
+
+    // stored rollup of a closed epoch
+    pub async fn get_epoch_stats(&self, epoch: u64) -> Result<Option<EpochStats>> {
+        let storage = self.store.lock().await;
+        storage.get_epoch_stats(epoch)
+    }
+
+    // write statements for every epoch that ended between the previous head and the new block
+    // accounting failures are logged, they never fail the block commit
+    async fn close_finished_epochs(
+        &self,
+        consensus: &mut ConsensusEngine,
+        previous: &ConsensusHead,
+        new_slot: u64,
+    ) {
+        if self.consensus_config.epoch_of(previous.slot) == self.consensus_config.epoch_of(new_slot)
+        {
+            return;
+        }
+        let (block_number, block_hash) = consensus.head_block();
+        for epoch in
+            self.consensus_config.epoch_of(previous.slot)..self.consensus_config.epoch_of(new_slot)
+        {
+            // the block before the new one is the last of every epoch closed here
+            let checkpoint = EpochCheckpoint {
+                epoch,
+                number: previous.number,
+                hash: previous.hash,
+                slot: previous.slot,
+                status: CheckpointStatus::Pending,
+            };
+            if let Err(e) = self.store.lock().await.put_epoch_checkpoint(&checkpoint) {
+                println!(
+                    "⚠️  Failed to store the checkpoint of epoch {}: {}",
+                    epoch, e
+                );
+            }
+
+            // evidence seen up to the end of the epoch is settled with it
+            let evidence: Vec<SlashingEvidence> = {
+                let end_slot = self.consensus_config.epoch_start_slot(epoch + 1);
+                let mut pending = self.pending_slashes.lock().await;
+                let (due, later) = pending.drain(..).partition(|e| e.slot() < end_slot);
+                *pending = later;
+                due
+            };
+            let penalties = slash_penalties(
+                &evidence,
+                |address| consensus.validator_set().stake_of(address),
+                self.slashing.slash_percent,
+            );
+
+            let liveness = match self.close_epoch(consensus, epoch, &penalties).await {
+                Ok((statements, blocks)) => {
+                    self.consensus_events.lock().await.record(
+                        new_slot,
+                        block_number,
+                        block_hash,
+                        ConsensusEventKind::EpochClosed { epoch },
+                    );
+                    if let Err(e) = self.settle_epoch(epoch, &statements).await {
+                        println!("⚠️  Failed to settle epoch {}: {}", epoch, e);
+                    }
+                    liveness_records(&statements, blocks)
+                }
+                Err(e) => {
+                    println!("⚠️  Failed to close epoch {}: {}", epoch, e);
+                    Vec::new()
+                }
+            };
+            if let Err(e) = self.record_validator_scores(&liveness).await {
+                println!("⚠️  Failed to store validator scores: {}", e);
+            }
+            // after the statements, they use the schedule the epoch ran with
+            Self::slash_validators(consensus, &evidence, &self.slashing);
+            self.deactivate_offline_validators(consensus, &liveness, new_slot)
+                .await;
+        }
+        if let Err(e) = self.justify_epoch_checkpoints().await {
+            println!("⚠️  Failed to update epoch checkpoints: {}", e);
+        }
+
+        // statements use the stake of the closed epoch, queued changes apply from here on
+        let slot = self
+            .consensus_config
+            .epoch_start_slot(self.consensus_config.epoch_of(new_slot));
+        self.settle_stake_changes(consensus, slot).await;
+        self.release_exited_validators(consensus, slot).await;
+        // the current epoch keeps the schedule it started with, the changes count from the next
+        let next_epoch = self.consensus_config.epoch_of(new_slot) + 1;
+        consensus.freeze_validator_set(next_epoch);
+        let randomness = consensus.seed_epoch_from_blocks(next_epoch);
+        println!(
+            "🎲 Epoch {} proposer schedule seeded with block randomness {}",
+            next_epoch, randomness
+        );
+        self.persist_validator_set(consensus).await;
+    }
+
+    // written when the set changes, a restart resumes with it instead of the genesis set
+    async fn persist_validator_set(&self, consensus: &ConsensusEngine) {
+        let validators = consensus.stored_validator_set();
+        if let Err(e) = self.store.lock().await.put_validator_set(&validators) {
+            println!("⚠️  Failed to store the validator set: {}", e);
+        }
+    }
+
+    // pay the epoch's rewards and penalties into account balances, before the next block
+    // executes, so its state root includes them
+    async fn settle_epoch(&self, epoch: u64, statements: &[EpochStatement]) -> Result<()> {
+        let owed = epoch_settlements(statements, &self.epoch_rewards);
+        if owed.is_empty() {
+            return Ok(());
+        }
+        let settled = self.execution_engine.apply_epoch_settlements(&owed).await;
+        self.store
+            .lock()
+            .await
+            .put_epoch_settlements(epoch, &settled)?;
+
+        let rewards: U256 = settled.iter().map(|s| s.reward).sum();
+        let penalties: U256 = settled.iter().map(|s| s.penalty).sum();
+        println!(
+            "💰 Epoch {} settled: {} rewarded, {} penalized across {} validators",
+            epoch,
+            rewards,
+            penalties,
+            settled.len()
+        );
+        Ok(())
+    }
+
+    // balance changes applied when the epoch closed, empty without rewards or penalties
+    pub async fn get_epoch_settlements(&self, epoch: u64) -> Result<Vec<EpochSettlement>> {
+        let storage = self.store.lock().await;
+        Ok(storage.get_epoch_settlements(epoch)?.unwrap_or_default())
+    }
+
+    // take validators that missed too many duties out of the set, a stake top-up brings
+    // them back; an epoch without blocks says nothing about who is offline
+    async fn deactivate_offline_validators(
+        &self,
+        consensus: &mut ConsensusEngine,
+        records: &[LivenessRecord],
+        slot: u64,
+    ) {
+        let produced = records
+            .iter()
+            .any(|r| r.proposal_duties > r.proposals_missed);
+        if !self.liveness.enabled || !produced {
+            return;
+        }
+        let (block_number, block_hash) = consensus.head_block();
+        for record in records.iter().filter(|r| r.is_offline(&self.liveness)) {
+            match consensus.validator_set_mut().deactivate(&record.validator) {
+                Ok(true) => {
+                    let missed = record.missed(&self.liveness);
+                    let duties = record.duties(&self.liveness);
+                    println!(
+                        "💤 Validator {} missed {} of {} duties in epoch {}, deactivated",
+                        record.validator, missed, duties, record.epoch
+                    );
+                    self.consensus_events.lock().await.record(
+                        slot,
+                        block_number,
+                        block_hash,
+                        ConsensusEventKind::Deactivated {
+                            validator: record.validator,
+                            missed,
+                            duties,
+                        },
+                    );
+                }
+                Ok(false) => {}
+                Err(e) => println!("⚠️  Validator {} stays active: {}", record.validator, e),
+            }
+        }
+    }
+
+    fn slash_validators(
+        consensus: &mut ConsensusEngine,
+        evidence: &[SlashingEvidence],
+        slashing: &SlashingConfig,
+    ) {
+        for outcome in consensus.slash_offenders(evidence, slashing) {
+            println!(
+                "⚔️  Validator {} slashed {} stake ({} slashes so far), stake now {}{}",
+                outcome.validator,
+                outcome.slashed,
+                outcome.slash_count,
+                consensus.validator_set().stake_of(&outcome.validator),
+                if outcome.deactivated {
+                    ", deactivated"
+                } else {
+                    ""
+                }
+            );
+        }
+    }
+
+    // move queued top-ups and withdrawals into the validator set, paying out what was released
+    async fn settle_stake_changes(&self, consensus: &mut ConsensusEngine, slot: u64) {
+        let pending = self.execution_engine.take_pending_stake().await;
+        if pending.is_empty() {
+            return;
+        }
+
+        let mut credits = Vec::new();
+        for settlement in consensus.settle_stake_changes(&pending, slot) {
+            if settlement.registered {
+                println!(
+                    "🆕 Validator {} registered with {} stake",
+                    settlement.validator, settlement.topped_up
+                );
+            }
+            println!(
+                "🥩 Validator {}: +{} stake, -{} withdrawn, stake now {}{}",
+                settlement.validator,
+                settlement.topped_up,
+                settlement.withdrawn,
+                consensus.validator_set().stake_of(&settlement.validator),
+                if settlement.deactivated {
+                    ", deactivated (below minimum stake)"
+                } else {
+                    ""
+                }
+            );
+            if let Some(exit_slot) = settlement.exit_slot {
+                println!(
+                    "👋 Validator {} leaves the set at slot {}",
+                    settlement.validator, exit_slot
+                );
+            }
+            if settlement.credit > U256::ZERO {
+                credits.push((settlement.validator, settlement.credit));
+            }
+        }
+        self.execution_engine.credit_balances(&credits).await;
+    }
+
+    // pay back the stake of validators whose exit delay passed by `slot`
+    async fn release_exited_validators(&self, consensus: &mut ConsensusEngine, slot: u64) {
+        let credits: Vec<(Address, U256)> = consensus
+            .process_exits(slot)
+            .into_iter()
+            .map(|exit| {
+                println!(
+                    "👋 Validator {} left the set, {} stake paid back",
+                    exit.validator, exit.stake
+                );
+                (exit.validator, U256::from(exit.stake))
+            })
+            .collect();
+        self.execution_engine.credit_balances(&credits).await;
+    }
+
+    async fn close_epoch(
+        &self,
+        consensus: &ConsensusEngine,
+        epoch: u64,
+        penalties: &HashMap<Address, u64>,
+    ) -> Result<(Vec<EpochStatement>, u64)> {
+        let start_slot = self.consensus_config.epoch_start_slot(epoch);
+        let end_slot = self.consensus_config.epoch_start_slot(epoch + 1);
+
+        let schedule: Vec<(u64, Address)> = (start_slot..end_slot)
+            .filter_map(|slot| consensus.scheduled_proposer(slot).map(|p| (slot, p)))
+            .collect();
+        let validators: Vec<(Address, u64)> = consensus
+            .validator_set()
+            .get_active_validators()
+            .iter()
+            .map(|v| (v.address, v.staked_amount))
+            .collect();
+        let attestations = self
+            .epoch_attestations
+            .lock()
+            .await
+            .remove(&epoch)
+            .unwrap_or_default();
+
+        let block_gas_limit = self.execution_engine.gas_config().block_gas_limit;
+        let rewards = self.execution_engine.block_rewards().await;
+
+        let storage = self.store.lock().await;
+        let (proposals, usage) = Self::epoch_blocks(&storage, start_slot, end_slot, &rewards)?;
+        let statements = epoch_statements(
+            &self.consensus_config,
+            epoch,
+            &schedule,
+            &proposals,
+            &attestations,
+            &validators,
+            penalties,
+        );
+        for statement in &statements {
+            storage.put_epoch_statement(statement)?;
+        }
+        let stats = epoch_stats(
+            &self.consensus_config,
+            epoch,
+            &statements,
+            &usage,
+            block_gas_limit,
+        );
+        storage.put_epoch_stats(&stats)?;
+
+        println!(
+            "📒 Epoch {} closed: {} blocks, {} transactions, {} validator statements stored",
+            epoch,
+            proposals.len(),
+            stats.transactions,
+            statements.len()
+        );
+        Ok((statements, proposals.len() as u64))
+    }
+
+    // committed blocks with a slot in [start_slot, end_slot), walking back from the head
+    fn epoch_blocks(
+        storage: &Storage,
+        start_slot: u64,
+        end_slot: u64,
+        rewards: &BlockRewardConfig,
+    ) -> Result<(Vec<EpochProposal>, Vec<EpochBlockUsage>)> {
+        let mut proposals = Vec::new();
+        let mut usage = Vec::new();
+        let Some(last_index) = storage.get_last_index()? else {
+            return Ok((proposals, usage));
+        };
+
+        for index in (0..=last_index).rev() {
+            let Some(block_hash) = storage.get_block_hash_from_index(&index)? else {
+                continue;
+            };
+            let Some(block) = storage.get_block_from_block_hash::<Block>(&block_hash)? else {
+                continue;
+            };
+            if block.header.slot < start_slot {
+                break;
+            }
+            if block.header.slot >= end_slot {
+                continue;
+            }
+
+            let receipts = storage.get_block_receipts(&block_hash)?.unwrap_or_default();
+            // the tips execution paid the proposer, failed transactions included
+            let fees = receipts.iter().map(|r| r.tip).sum();
+
+            proposals.push(EpochProposal {
+                slot: block.header.slot,
+                proposer: block.header.proposer,
+                fees,
+                block_reward: rewards.reward_at(block.header.index),
+            });
+            usage.push(EpochBlockUsage {
+                gas_used: receipts.iter().map(|r| r.gas_used).sum(),
+                senders: block.transactions.iter().map(|tx| tx.from).collect(),
+            });
+        }
+
+        Ok((proposals, usage))
+    }
+
+    // write the full account state to `dir`, named after the current head block
+    pub async fn write_state_snapshot(&self, dir: &Path) -> Result<StateSnapshotInfo> {
+        let block_number = self.get_last_index().await?;
+        let (json, state_root, accounts) = {
+            let state = self.execution_engine.state_manager.lock().await;
+            (
+                serde_json::to_vec(&*state)?,
+                state.get_state_root(),
+                state.account_count(),
+            )
+        };
+
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create snapshot dir {}", dir.display()))?;
+        let path = dir.join(format!("state-{}.json", block_number));
+        fs::write(&path, json)
+            .with_context(|| format!("Failed to write snapshot {}", path.display()))?;
+
+        println!(
+            "📸 State snapshot at block #{} written to {}",
+            block_number,
+            path.display()
+        );
+        Ok(StateSnapshotInfo {
+            path: path.display().to_string(),
+            block_number,
+            state_root,
+            accounts,
+        })
+    }
+
+    // the head block, its state and the block randomness, for other nodes to start from
+    // written to `dir` as `checkpoint-<block>.json`
+    pub async fn write_checkpoint(&self, dir: &Path) -> Result<CheckpointInfo> {
+        let checkpoint = {
+            // the head can't move while its state is copied
+            let consensus = self.consensus_engine.lock().await;
+            let head = consensus.head();
+            let storage = self.store.lock().await;
+            let block = storage
+                .get_block_from_block_hash::<Block>(&head.hash)?
+                .ok_or_else(|| anyhow!("No block committed yet to take a checkpoint at"))?;
+            let genesis = storage
+                .get_consensus_genesis()?
+                .ok_or_else(|| anyhow!("Database has no consensus genesis"))?;
+            drop(storage);
+            Checkpoint {
+                block,
+                state: self.execution_engine.state_manager.lock().await.clone(),
+                randomness: consensus.block_randomness().clone(),
+                genesis,
+            }
+        };
+        let head = checkpoint.head();
+        checkpoint.verify(&head.hash)?;
+
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create snapshot dir {}", dir.display()))?;
+        let path = dir.join(format!("checkpoint-{}.json", head.number));
+        checkpoint.write(&path)?;
+
+        println!(
+            "📍 Checkpoint at block #{} {} written to {}",
+            head.number,
+            head.hash,
+            path.display()
+        );
+        Ok(CheckpointInfo {
+            path: path.display().to_string(),
+            block_number: head.number,
+            block_hash: head.hash,
+            state_root: checkpoint.block.header.state_root,
+        })
+    }
+
+    // for databases without a stored state, pre-load the accounts touched in the last
+    // `blocks` blocks from their stored state diffs after a restart
+    pub async fn warm_up_state(&self, blocks: u64) -> Result<usize> {
+        if blocks == 0 || self.state_restored {
+            return Ok(0);
+        }
+
+        let diffs = {
+            let storage = self.store.lock().await;
+            let Some(last_index) = storage.get_last_index()? else {
+                return Ok(0);
+            };
+
+            let first_index = last_index.saturating_sub(blocks.saturating_sub(1));
+            let mut diffs = Vec::new();
+            for index in (first_index..=last_index).rev() {
+                let Some(block_hash) = storage.get_block_hash_from_index(&index)? else {
+                    continue;
+                };
+                diffs.extend(
+                    storage
+                        .get_block_state_diff(&block_hash)?
+                        .unwrap_or_default(),
+                );
+            }
+            diffs
+        };
+
+        Ok(self.execution_engine.warm_up_accounts(diffs).await)
+    }
+
+    // get last index from storage
+    pub async fn get_last_index(&self) -> Result<u64> {
+        let store = self.store.lock().await;
+        let last_index: u64 = match store
+            .get_last_index()
+            .context("Failed to retrieve last block index")?
+        {
+            Some(index) => index,
+            None => 0, // No blocks exist
+        };
+        Ok(last_index)
+    }
+
+    // get block hash by index
     pub async fn get_block_hash_by_index(&self, index: &u64) -> Result<Option<B256>> {
         let store = self.store.lock().await;
         store.get_block_hash_from_index(index)
     }
 
+    // receipt of a committed transaction, None while it is pending or unknown
+    pub async fn get_receipt(&self, tx_hash: &B256) -> Result<Option<TransactionReceipt>> {
+        self.store.lock().await.get_receipt(tx_hash)
+    }
+
+    // header of a stored block, canonical or replaced by a reorg
+    pub async fn get_block_header(&self, block_hash: &B256) -> Result<Option<BlockHeader>> {
+        let store = self.store.lock().await;
+        Ok(store
+            .get_block_from_block_hash::<Block>(block_hash)?
+            .map(|block| block.header))
+    }
+
     // get a block by index
     // 1) Get block hash from index
     // 2) Get block data from block hash
@@ -1,16 +1,23 @@
 use crate::{
     Attestation, AttestationVote, Block, BlockProcessResult, Blockchain, BlockchainMessage,
-    KeyPair, NetworkMessage, Transaction, ValidatorRole,
+    GossipThrottleConfig, KeyPair, NetworkMessage, ProposalPrefetchConfig, ProposerSafetyConfig,
+    RebroadcastConfig, SyncHoldingArea, SyncHoldingConfig, Transaction, TransactionSource,
+    ValidatorRole, WatchdogConfig,
+    consensus::{AttestationEquivocationDetector, SignedMessage, SignedVote, attestation_hash},
+    core::{BlockFinality, LoopHeartbeat, Watchdog},
 };
-use alloy::primitives::{Address, B256, keccak256};
+use alloy::primitives::{Address, B256, U256, keccak256};
 use alloy_signer::Signature;
 use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{
     Mutex,
     mpsc::{UnboundedReceiver, UnboundedSender},
 };
+use tokio::time::Instant;
 
 // blockchain service layer as an interface between blockchain and network
 pub struct BlockchainService {
@@ -18,7 +25,6 @@ pub struct BlockchainService {
     blockchain: Arc<Mutex<Blockchain>>,
     keypair: KeyPair,
     validator_address: Address,
-    role: ValidatorRole,
 
     // Communication channels
     from_network_receiver: UnboundedReceiver<NetworkMessage>,
@@ -27,6 +33,58 @@ pub struct BlockchainService {
     // Simple state tracking
     pending_blocks: HashMap<B256, Block>, // Blocks waiting for attestations
     received_attestations: HashMap<B256, Vec<Attestation>>,
+    // votes per validator and slot, and the validators caught signing conflicting ones
+    double_votes: AttestationEquivocationDetector,
+    // validator multiaddrs last handed to the network layer
+    announced_validator_addresses: HashSet<String>,
+
+    // dry-run mode: build blocks when selected but never broadcast them
+    dry_run: bool,
+    dry_run_stats: DryRunStats,
+
+    // proposer safety: peer count reported by the network
+    safety: ProposerSafetyConfig,
+    connected_peers: usize,
+
+    // our last block, re-broadcast once if a quorum hasn't attested in time
+    rebroadcast: RebroadcastConfig,
+    pending_proposal: Option<PendingProposal>,
+
+    // gossip transactions received while syncing, admitted once caught up
+    sync_holding: SyncHoldingConfig,
+    held_transactions: SyncHoldingArea,
+
+    // warm up our next block one slot ahead
+    proposal_prefetch: ProposalPrefetchConfig,
+
+    // gossip transactions deferred while we build our block, admitted when the window ends
+    gossip_throttle: GossipThrottleConfig,
+    deferred_transactions: SyncHoldingArea,
+    deferred_until: Option<Instant>,
+
+    // what the loop is working on, watched for stalls
+    heartbeat: Arc<LoopHeartbeat>,
+}
+
+// peer recorded for replayed blocks that end up in the quarantine
+const FUTURE_BLOCK_PEER: &str = "future-block-queue";
+
+// own block waiting for attestations
+struct PendingProposal {
+    block: Block,
+    signature: Signature,
+    quorum: usize,
+    rebroadcast_at: Instant,
+}
+
+// running totals of blocks built in dry-run mode
+#[derive(Debug, Clone, Default)]
+pub struct DryRunStats {
+    pub last_slot: Option<u64>,
+    pub slots_selected: u64,
+    pub empty_blocks: u64,
+    pub transactions_included: u64,
+    pub total_gas_used: U256,
 }
 
 impl BlockchainService {
@@ -36,36 +94,129 @@ impl BlockchainService {
         to_network: UnboundedSender<BlockchainMessage>,
         blockchain: Blockchain,
         keypair: KeyPair,
-        role: ValidatorRole,
+        dry_run: bool,
+        safety: ProposerSafetyConfig,
     ) -> Self {
         Self {
             blockchain: Arc::new(Mutex::new(blockchain)),
             validator_address: keypair.address,
             keypair,
-            role,
             from_network_receiver: from_network,
             to_network_sender: to_network,
             pending_blocks: HashMap::new(),
             received_attestations: HashMap::new(),
+            double_votes: AttestationEquivocationDetector::new(),
+            announced_validator_addresses: HashSet::new(),
+            dry_run,
+            dry_run_stats: DryRunStats::default(),
+            safety,
+            connected_peers: 0,
+            rebroadcast: RebroadcastConfig::default(),
+            pending_proposal: None,
+            sync_holding: SyncHoldingConfig::default(),
+            held_transactions: SyncHoldingArea::new(SyncHoldingConfig::default().capacity),
+            proposal_prefetch: ProposalPrefetchConfig::default(),
+            gossip_throttle: GossipThrottleConfig::default(),
+            deferred_transactions: SyncHoldingArea::new(GossipThrottleConfig::default().capacity),
+            deferred_until: None,
+            heartbeat: Arc::new(LoopHeartbeat::default()),
         }
     }
 
+    // override when and whether our own blocks are re-broadcast
+    pub fn with_rebroadcast(mut self, rebroadcast: RebroadcastConfig) -> Self {
+        self.rebroadcast = rebroadcast;
+        self
+    }
+
+    // override whether and how many gossip transactions are held during sync
+    pub fn with_sync_holding(mut self, sync_holding: SyncHoldingConfig) -> Self {
+        self.held_transactions = SyncHoldingArea::new(sync_holding.capacity);
+        self.sync_holding = sync_holding;
+        self
+    }
+
+    // override whether and how much of the next block is warmed up before our slot
+    pub fn with_proposal_prefetch(mut self, proposal_prefetch: ProposalPrefetchConfig) -> Self {
+        self.proposal_prefetch = proposal_prefetch;
+        self
+    }
+
+    // override whether and how long gossip transactions wait at the start of our slots
+    pub fn with_gossip_throttle(mut self, gossip_throttle: GossipThrottleConfig) -> Self {
+        self.deferred_transactions = SyncHoldingArea::new(gossip_throttle.capacity);
+        self.gossip_throttle = gossip_throttle;
+        self
+    }
+
+    // watchdog over this service's loop and the blockchain, consensus and state locks
+    pub async fn watchdog(&self, config: WatchdogConfig) -> Watchdog {
+        let chain = self.blockchain.lock().await;
+        let slot_clock = chain.consensus_engine.lock().await.slot_clock();
+        Watchdog::new(config, self.heartbeat.clone())
+            .with_slot_clock(slot_clock)
+            .watch_lock("blockchain", self.blockchain.clone())
+            .watch_lock("consensus", chain.consensus_engine.clone())
+            .watch_lock("state", chain.execution_engine.state_manager.clone())
+    }
+
     // start blockchain service instance
     pub async fn run(&mut self) -> Result<()> {
-        let mut block_timer = tokio::time::interval(tokio::time::Duration::from_secs(10));
+        let mut slot_ticks = {
+            let chain = self.blockchain.lock().await;
+            chain.consensus_engine.lock().await.proposal_ticks()
+        };
+        self.report_head_slot().await?;
+        self.log_duty_status().await;
+        let heartbeat = self.heartbeat.clone();
 
         loop {
             tokio::select! {
                 // Handle messages from network, message from other nodes
                 Some(msg) = self.from_network_receiver.recv() => {
-                    self.handle_network_message(msg).await?;
+                    let queued = self.from_network_receiver.len();
+                    supervised(&heartbeat, "network message", queued, async {
+                        self.blockchain.lock().await.record_gossip_backlog(queued).await;
+                        self.handle_network_message(msg).await
+                    })
+                    .await?;
                 }
 
-                // Periodical checking whether we should propose block
-                _ = block_timer.tick() => {
-                    if matches!(self.role, ValidatorRole::Proposer) {
-                        self.propose_block().await?;
-                    }
+                // attestations for our last block are overdue
+                _ = sleep_until_deadline(self.pending_proposal.as_ref().map(|p| p.rebroadcast_at)) => {
+                    self.rebroadcast_on_low_turnout()?;
+                }
+
+                // our proposal window is over, catch up on the gossip it deferred
+                _ = sleep_until_deadline(self.deferred_until) => {
+                    let queued = self.from_network_receiver.len();
+                    supervised(&heartbeat, "deferred gossip", queued, async {
+                        self.release_deferred_transactions().await;
+                        Ok(())
+                    })
+                    .await?;
+                }
+
+                // a new slot started, check whether we should propose block
+                _ = slot_ticks.tick() => {
+                    let queued = self.from_network_receiver.len();
+                    supervised(&heartbeat, "slot tick", queued, async {
+                        self.replay_future_blocks().await?;
+                        self.release_held_transactions().await;
+                        if self.dry_run {
+                            self.dry_run_block().await?;
+                        } else {
+                            // every validator proposes in the slots the schedule gives it
+                            let role = self.blockchain.lock().await.current_role().await;
+                            if role == Some(ValidatorRole::Proposer) {
+                                self.propose_block().await?;
+                            }
+                            self.prefetch_next_proposal().await;
+                        }
+                        self.release_deferred_transactions().await;
+                        Ok(())
+                    })
+                    .await?;
                 }
             }
         }
@@ -79,9 +230,19 @@ impl BlockchainService {
                 block,
                 proposer_id,
                 signature,
+                from_peer,
+                received_at_ms,
             } => {
-                self.handle_received_block(block, proposer_id, signature)
-                    .await?;
+                self.handle_received_block(
+                    *block,
+                    proposer_id,
+                    signature,
+                    from_peer,
+                    received_at_ms,
+                )
+                .await?;
+                self.replay_future_blocks().await?;
+                self.release_held_transactions().await;
             }
             // handle receiving new attestation from other nodes
             NetworkMessage::Attestation {
@@ -101,6 +262,9 @@ impl BlockchainService {
                 self.handle_received_transaction(&transaction, &from_peer)
                     .await?;
             }
+            NetworkMessage::PeerCount { connected } => {
+                self.connected_peers = connected;
+            }
         }
         Ok(())
     }
@@ -111,6 +275,8 @@ impl BlockchainService {
         block: Block,
         proposer_id: Address,
         signature: Signature,
+        from_peer: String,
+        received_at_ms: u64,
     ) -> Result<()> {
         println!(
             "Service: Received block {}, forwarding to blockchain",
@@ -123,29 +289,116 @@ impl BlockchainService {
                 "Service: Invalid block signature from {}, dropping",
                 proposer_id
             );
+            self.quarantine_block(
+                block,
+                proposer_id,
+                "invalid block signature".to_string(),
+                from_peer,
+            )
+            .await;
             return Ok(()); // Drop message immediately
         }
 
-        // blockchain layer validation
-        let blockchain_result = {
+        // only blocks signed by their proposer count towards propagation stats
+        let arrival = {
             let blockchain = self.blockchain.lock().await;
             blockchain
+                .record_block_arrival(&block, from_peer.clone(), received_at_ms)
+                .await
+        };
+        if let Some(arrival) = arrival {
+            println!(
+                "⏱️  Block #{} via {}: +{}ms after slot {} start, +{}ms after proposer timestamp",
+                arrival.block_number,
+                arrival.peer,
+                arrival.since_slot_start_ms,
+                arrival.slot,
+                arrival.since_proposer_ms
+            );
+        }
+
+        self.import_received_block(block, proposer_id, signature, from_peer)
+            .await
+    }
+
+    // blocks held for their slot or parent go through the import again once they're due
+    async fn replay_future_blocks(&mut self) -> Result<()> {
+        loop {
+            let ready = self.blockchain.lock().await.release_future_blocks().await?;
+            if ready.is_empty() {
+                return Ok(());
+            }
+            for future in ready {
+                println!(
+                    "⏩ Replaying held block #{} for slot {}",
+                    future.block.header.index, future.block.header.slot
+                );
+                self.import_received_block(
+                    future.block,
+                    future.proposer_id,
+                    future.signature,
+                    FUTURE_BLOCK_PEER.to_string(),
+                )
+                .await?;
+            }
+        }
+    }
+
+    // validate and commit a block with a checked signature, and vote on it
+    async fn import_received_block(
+        &mut self,
+        block: Block,
+        proposer_id: Address,
+        signature: Signature,
+        from_peer: String,
+    ) -> Result<()> {
+        let slot = block.header.slot;
+
+        // blockchain layer validation
+        let (blockchain_result, quarantined) = {
+            let blockchain = self.blockchain.lock().await;
+            // remember how far the network has progressed, even if we can't apply the block
+            blockchain.note_network_slot(block.header.slot).await;
+            // only copied when a rejection would be kept
+            let quarantined = blockchain.quarantine_enabled().then(|| block.clone());
+            let result = blockchain
                 .process_received_block(block, proposer_id, signature)
-                .await?
+                .await?;
+            (result, quarantined)
+        };
+
+        // we vote on blocks of the slots we weren't picked for
+        let attests = {
+            let blockchain = self.blockchain.lock().await;
+            blockchain.slot_role(slot).await == Some(ValidatorRole::Attestor)
         };
 
         // React based on blockchain's decision
         match blockchain_result {
             BlockProcessResult::Accepted(block_hash) => {
-                if matches!(self.role, ValidatorRole::Attestor) {
-                    self.create_and_send_attestation(block_hash, AttestationVote::Accept)
+                self.report_head_slot().await?;
+                self.sync_validator_addresses().await?;
+                // votes may have arrived before the block
+                self.certify_block(block_hash).await;
+
+                if attests {
+                    self.create_and_send_attestation(block_hash, slot, AttestationVote::Accept)
                         .await?;
                 }
             }
+            // nothing to vote on until its branch becomes ours
+            BlockProcessResult::Forked(_) => {}
+            // voted on when it is replayed
+            BlockProcessResult::Queued(_, _) => {}
             BlockProcessResult::Rejected(block_hash, reason) => {
-                if matches!(self.role, ValidatorRole::Attestor) {
+                if let Some(block) = quarantined {
+                    self.quarantine_block(block, proposer_id, reason.clone(), from_peer)
+                        .await;
+                }
+                if attests {
                     self.create_and_send_attestation(
                         block_hash,
+                        slot,
                         AttestationVote::Reject { reason },
                     )
                     .await?;
@@ -156,6 +409,29 @@ impl BlockchainService {
         Ok(())
     }
 
+    // keep a refused block for later inspection, a storage error only costs the copy
+    async fn quarantine_block(
+        &self,
+        block: Block,
+        proposer_id: Address,
+        reason: String,
+        from_peer: String,
+    ) {
+        let number = block.header.index;
+        let blockchain = self.blockchain.lock().await;
+        match blockchain
+            .quarantine_block(block, proposer_id, reason.clone(), from_peer)
+            .await
+        {
+            Ok(Some(sequence)) => println!(
+                "🚫 Quarantined block #{} from {} as #{}: {}",
+                number, proposer_id, sequence, reason
+            ),
+            Ok(None) => {}
+            Err(e) => println!("⚠️  Failed to quarantine block #{}: {}", number, e),
+        }
+    }
+
     // handle receiving attestations
     async fn handle_received_attestation(
         &mut self,
@@ -179,6 +455,35 @@ impl BlockchainService {
             return Ok(());
         }
 
+        // a validator that signed conflicting votes is not listened to anymore
+        if self.double_votes.is_equivocator(&validator_id) {
+            println!(
+                "Service: Ignoring attestation from equivocating validator {}",
+                validator_id
+            );
+            return Ok(());
+        }
+        // votes on blocks we have are checked against the validator's other votes in the slot
+        let header = {
+            let blockchain = self.blockchain.lock().await;
+            blockchain.get_block_header(&block_hash).await?
+        };
+        let own_block = header
+            .as_ref()
+            .is_some_and(|header| header.proposer == self.validator_address);
+        if let Some(header) = header {
+            let signed = SignedVote {
+                header,
+                vote: vote.clone(),
+                signature,
+            };
+            if let Some(evidence) = self.double_votes.observe(validator_id, signed) {
+                let blockchain = self.blockchain.lock().await;
+                blockchain.report_double_vote(evidence).await?;
+                return Ok(());
+            }
+        }
+
         // Store attestation
         let attestation = Attestation {
             validator_id,
@@ -192,8 +497,19 @@ impl BlockchainService {
             .or_insert_with(Vec::new)
             .push(attestation);
 
-        // process attestation received from other node, as a proposer
-        if matches!(self.role, ValidatorRole::Proposer) {
+        // accept votes count towards the validator's epoch statement
+        if matches!(vote, AttestationVote::Accept) {
+            {
+                let blockchain = self.blockchain.lock().await;
+                blockchain
+                    .record_attestation(&block_hash, validator_id)
+                    .await?;
+            }
+            self.certify_block(block_hash).await;
+        }
+
+        // process attestation received from other node, for a block we proposed
+        if own_block {
             self.process_attestation_as_proposer(block_hash, vote)
                 .await?;
         }
@@ -203,9 +519,9 @@ impl BlockchainService {
 
     // Adding transaction received from other node to mempool
     async fn handle_received_transaction(
-        &self,
+        &mut self,
         transaction: &Transaction,
-        from_peer: &Address,
+        from_peer: &str,
     ) -> Result<()> {
         println!(
             "Service: Received transaction {} from peer {}",
@@ -213,9 +529,49 @@ impl BlockchainService {
             from_peer
         );
 
+        // our state is stale while syncing, validate once we caught up
+        if self.is_syncing().await {
+            if self
+                .held_transactions
+                .hold(transaction.clone(), from_peer.to_string())
+            {
+                println!(
+                    "⏸️  Holding transaction until sync completes ({} held)",
+                    self.held_transactions.len()
+                );
+            } else {
+                println!(
+                    "⏸️  Sync holding area full or duplicate, {} dropped so far",
+                    self.held_transactions.dropped()
+                );
+            }
+            return Ok(());
+        }
+
+        // the start of our slot belongs to building the block
+        if let Some(left) = self.proposal_window_left().await {
+            if !self
+                .deferred_transactions
+                .hold(transaction.clone(), from_peer.to_string())
+            {
+                println!(
+                    "⏳ Proposal window queue full or duplicate, {} dropped so far",
+                    self.deferred_transactions.dropped()
+                );
+            }
+            self.deferred_until.get_or_insert(Instant::now() + left);
+            return Ok(());
+        }
+        self.release_deferred_transactions().await;
+
         // @todo No Transaction validation
         let blockchain = self.blockchain.lock().await;
-        let result = blockchain.add_transaction_to_mempool(&transaction).await;
+        let source = TransactionSource::Gossip {
+            peer: from_peer.to_string(),
+        };
+        let result = blockchain
+            .add_transaction_to_mempool(transaction, source)
+            .await;
 
         match result {
             Ok(tx_hash) => {
@@ -232,6 +588,67 @@ impl BlockchainService {
         Ok(())
     }
 
+    async fn is_syncing(&self) -> bool {
+        if !self.sync_holding.enabled {
+            return false;
+        }
+        let slots_behind = self.blockchain.lock().await.slots_behind().await;
+        slots_behind > self.sync_holding.lag_slots
+    }
+
+    // admit held transactions once the head caught up with the network
+    async fn release_held_transactions(&mut self) {
+        if self.held_transactions.is_empty() || self.is_syncing().await {
+            return;
+        }
+        let held = self.held_transactions.drain();
+        let total = held.len();
+        let admitted = self
+            .blockchain
+            .lock()
+            .await
+            .admit_held_transactions(held)
+            .await;
+        println!(
+            "▶️  Sync complete, admitted {} of {} held transactions",
+            admitted, total
+        );
+    }
+
+    // time left of our proposal window, None when gossip throttling is off or it's not our slot
+    async fn proposal_window_left(&self) -> Option<Duration> {
+        if !self.gossip_throttle.enabled {
+            return None;
+        }
+        let window = Duration::from_millis(self.gossip_throttle.window_ms);
+        let blockchain = self.blockchain.lock().await;
+        blockchain.proposal_window_left(window).await
+    }
+
+    // admit the gossip transactions deferred during our proposal window, once it is over
+    async fn release_deferred_transactions(&mut self) {
+        if self.deferred_transactions.is_empty() {
+            return;
+        }
+        if let Some(left) = self.proposal_window_left().await {
+            self.deferred_until = Some(Instant::now() + left);
+            return;
+        }
+        self.deferred_until = None;
+
+        let deferred = self.deferred_transactions.drain();
+        let total = deferred.len();
+        let admitted = {
+            let blockchain = self.blockchain.lock().await;
+            blockchain.record_deferred_gossip(total).await;
+            blockchain.admit_held_transactions(deferred).await
+        };
+        println!(
+            "▶️  Proposal window over, admitted {} of {} deferred transactions",
+            admitted, total
+        );
+    }
+
     // Helper method for Blockchain layer
     // Calls blockchain layer to validate block
     async fn validate_block(&self, block: &Block) -> Result<bool> {
@@ -250,8 +667,40 @@ impl BlockchainService {
         }
     }
 
+    // reason this node must not propose right now, None if it is safe to
+    async fn proposal_blocked_reason(&self) -> Option<String> {
+        if self.connected_peers < self.safety.min_peers {
+            return Some(format!(
+                "only {} connected peers, {} required",
+                self.connected_peers, self.safety.min_peers
+            ));
+        }
+
+        let (network_best_slot, head_slot) = {
+            let blockchain = self.blockchain.lock().await;
+            (
+                blockchain.network_best_slot().await?,
+                blockchain.head_slot().await,
+            )
+        };
+        let lag = network_best_slot.saturating_sub(head_slot);
+        if lag > self.safety.max_head_lag_slots {
+            return Some(format!(
+                "head at slot {} is {} slots behind the network (max {})",
+                head_slot, lag, self.safety.max_head_lag_slots
+            ));
+        }
+
+        None
+    }
+
     // propose new block
     async fn propose_block(&mut self) -> Result<()> {
+        if let Some(reason) = self.proposal_blocked_reason().await {
+            println!("⚠️  Refusing to propose: {}", reason);
+            return Ok(());
+        }
+
         let new_block = match {
             let blockchain = self.blockchain.lock().await;
             blockchain.produce_block().await
@@ -263,13 +712,14 @@ impl BlockchainService {
             }
         };
 
+        let signature = new_block
+            .header
+            .validator_signature
+            .ok_or_else(|| anyhow::anyhow!("Block header missing validator signature"))?;
         let block_msg = BlockchainMessage::NewBlock {
             block: new_block.clone(),
             proposer: self.validator_address,
-            signature: new_block
-                .header
-                .validator_signature
-                .ok_or_else(|| anyhow::anyhow!("Block header missing validator signature"))?,
+            signature,
         };
 
         self.to_network_sender
@@ -277,6 +727,252 @@ impl BlockchainService {
             .map_err(|_| anyhow::anyhow!("Failed to send block to network"))?;
 
         println!("Service: Block broadcasted to network");
+        self.track_own_proposal(new_block, signature).await;
+
+        self.report_head_slot().await?;
+        self.sync_validator_addresses().await?;
+        Ok(())
+    }
+
+    // the timer ticks once per slot, our next slot's block is warmed up a tick ahead
+    async fn prefetch_next_proposal(&self) {
+        if !self.proposal_prefetch.enabled {
+            return;
+        }
+
+        let (template, report) = {
+            let blockchain = self.blockchain.lock().await;
+            // built first, the template's simulation would consume the prefetch
+            let template = if self.proposal_prefetch.block_template {
+                blockchain
+                    .build_template_for_next_slot(self.proposal_prefetch.max_transactions)
+                    .await
+            } else {
+                None
+            };
+            let report = blockchain
+                .prefetch_for_next_slot(self.proposal_prefetch.max_transactions)
+                .await;
+            (template, report)
+        };
+        if let Some(template) = template {
+            println!(
+                "🧩 Block template for slot {}: {} transactions",
+                template.slot, template.transactions
+            );
+        }
+        if let Some(report) = report {
+            println!(
+                "🔥 Prefetched slot {}: {} transactions from {} senders, {} invalid signatures",
+                report.slot, report.transactions, report.senders, report.invalid_signatures
+            );
+        }
+    }
+
+    // start waiting for attestations on a block we just broadcast
+    async fn track_own_proposal(&mut self, block: Block, signature: Signature) {
+        if !self.rebroadcast.enabled {
+            return;
+        }
+
+        let (quorum, slot_duration) = {
+            let blockchain = self.blockchain.lock().await;
+            let consensus = blockchain.consensus_engine.lock().await;
+            (
+                consensus.validator_set().quorum_size(),
+                consensus.slot_duration(),
+            )
+        };
+
+        self.pending_proposal = Some(PendingProposal {
+            block,
+            signature,
+            quorum,
+            rebroadcast_at: Instant::now()
+                + slot_duration.mul_f64(self.rebroadcast.after_slot_fraction),
+        });
+    }
+
+    // re-broadcast our block once if fewer than a quorum accepted it so far
+    fn rebroadcast_on_low_turnout(&mut self) -> Result<()> {
+        let Some(pending) = self.pending_proposal.take() else {
+            return Ok(());
+        };
+
+        // our own vote is implied
+        let votes = self.accept_votes(&pending.block.header.hash()) + 1;
+        if votes >= pending.quorum {
+            return Ok(());
+        }
+
+        println!(
+            "📣 Block #{} has {} of {} attestations needed, re-broadcasting once",
+            pending.block.header.index, votes, pending.quorum
+        );
+        self.to_network_sender
+            .send(BlockchainMessage::RebroadcastBlock {
+                block: pending.block,
+                proposer: self.validator_address,
+                signature: pending.signature,
+            })
+            .map_err(|_| anyhow::anyhow!("Failed to send block to network"))?;
+
+        Ok(())
+    }
+
+    // write a finality certificate once the votes seen for a block reach a quorum
+    // certificates are a by-product, a failure is logged and never stops the service
+    async fn certify_block(&self, block_hash: B256) {
+        let Some(attestations) = self.received_attestations.get(&block_hash) else {
+            return;
+        };
+        let result = {
+            let blockchain = self.blockchain.lock().await;
+            blockchain.certify_block(&block_hash, attestations).await
+        };
+        match result {
+            Ok(Some(certificate)) => println!(
+                "🔏 Block #{} finalized with {} attestations, certificate stored",
+                certificate.header.index,
+                certificate.attestations.len()
+            ),
+            Ok(None) => {}
+            Err(e) => println!("⚠️  Failed to store finality certificate: {}", e),
+        }
+    }
+
+    // distinct other validators that accepted a block
+    fn accept_votes(&self, block_hash: &B256) -> usize {
+        self.received_attestations
+            .get(block_hash)
+            .map(|attestations| {
+                attestations
+                    .iter()
+                    .filter(|a| matches!(a.vote, AttestationVote::Accept))
+                    .filter(|a| a.validator_id != self.validator_address)
+                    .map(|a| a.validator_id)
+                    .collect::<HashSet<_>>()
+                    .len()
+            })
+            .unwrap_or(0)
+    }
+
+    // build the block we would have proposed and log it, nothing leaves the node
+    async fn dry_run_block(&mut self) -> Result<()> {
+        let dry_run = {
+            let blockchain = self.blockchain.lock().await;
+            blockchain.dry_run_block().await?
+        };
+
+        let Some(dry_run) = dry_run else {
+            return Ok(());
+        };
+
+        // only count each slot once
+        if self.dry_run_stats.last_slot == Some(dry_run.slot) {
+            return Ok(());
+        }
+
+        let stats = &mut self.dry_run_stats;
+        let tx_count = dry_run.block.transactions.len() as u64;
+        stats.last_slot = Some(dry_run.slot);
+        stats.slots_selected += 1;
+        stats.transactions_included += tx_count;
+        stats.total_gas_used += dry_run.execution_result.total_gas_used;
+        if tx_count == 0 {
+            stats.empty_blocks += 1;
+        }
+
+        println!(
+            "🧪 [dry-run] Slot {}: would propose block #{} with {} txs, gas used {}, state root 0x{}",
+            dry_run.slot,
+            dry_run.block.header.index,
+            tx_count,
+            dry_run.execution_result.total_gas_used,
+            hex::encode(dry_run.block.header.state_root)
+        );
+        println!(
+            "🧪 [dry-run] Totals: {} slots selected, {} empty, {} txs, {} gas",
+            stats.slots_selected,
+            stats.empty_blocks,
+            stats.transactions_included,
+            stats.total_gas_used
+        );
+
+        Ok(())
+    }
+
+    // dry-run totals so far
+    pub fn dry_run_stats(&self) -> &DryRunStats {
+        &self.dry_run_stats
+    }
+
+    // where we resume after a (re)start: slot, head and whether this slot's duties are done
+    async fn log_duty_status(&self) {
+        let status = {
+            let blockchain = self.blockchain.lock().await;
+            blockchain.duty_status().await
+        };
+        match status {
+            Ok(status) => println!(
+                "🧭 Slot {} (epoch {}), head #{} at slot {}, proposer {}{}{}",
+                status.slot,
+                status.epoch,
+                status.head_number,
+                status.head_slot,
+                status
+                    .proposer_of_slot
+                    .map_or("none".to_string(), |p| p.to_string()),
+                if status.is_proposer { " (us)" } else { "" },
+                match (status.proposed_this_slot, status.attested_this_slot) {
+                    (true, _) => ", already proposed this slot",
+                    (false, true) => ", already attested this slot",
+                    (false, false) => "",
+                }
+            ),
+            Err(e) => println!("⚠️  Could not compute duties: {}", e),
+        }
+    }
+
+    // tell the network layer our head slot, older gossip is dropped before validation
+    async fn report_head_slot(&self) -> Result<()> {
+        let slot = {
+            let blockchain = self.blockchain.lock().await;
+            blockchain.head_slot().await
+        };
+
+        self.to_network_sender
+            .send(BlockchainMessage::HeadSlot { slot })
+            .map_err(|_| anyhow::anyhow!("Failed to send head slot to network"))?;
+
+        Ok(())
+    }
+
+    // hand the registered validator addresses to the network layer for direct dialing,
+    // the whole set whenever it changed, so rotated and exited addresses are dropped
+    async fn sync_validator_addresses(&mut self) -> Result<()> {
+        let registered = {
+            let blockchain = self.blockchain.lock().await;
+            blockchain.validator_network_addresses().await
+        };
+
+        let addresses: HashSet<String> = registered
+            .into_iter()
+            .filter(|(validator, _)| *validator != self.validator_address)
+            .map(|(_, multiaddr)| multiaddr)
+            .collect();
+
+        if addresses == self.announced_validator_addresses {
+            return Ok(());
+        }
+        self.announced_validator_addresses = addresses.clone();
+
+        self.to_network_sender
+            .send(BlockchainMessage::ValidatorAddresses {
+                addresses: addresses.into_iter().collect(),
+            })
+            .map_err(|_| anyhow::anyhow!("Failed to send validator addresses to network"))?;
+
         Ok(())
     }
 
@@ -286,13 +982,30 @@ impl BlockchainService {
         block_hash: B256,
         vote: AttestationVote,
     ) -> Result<()> {
-        // no roll back capability, assuming our block is definitely being accepted
+        // no roll back capability, a block short of the quorum stays pending
         match vote {
             AttestationVote::Accept => {
-                println!(
-                    "Service: Received ACCEPT vote for block {}",
-                    hex::encode(block_hash)
-                );
+                let status = {
+                    let blockchain = self.blockchain.lock().await;
+                    blockchain.finality_status(&block_hash).await?
+                };
+                match status {
+                    Some(BlockFinality::Pending {
+                        accepted_stake,
+                        quorum_stake,
+                        voters,
+                    }) => println!(
+                        "Service: Received ACCEPT vote for block {}, {} voters, {}/{} stake towards finality",
+                        hex::encode(block_hash),
+                        voters,
+                        accepted_stake,
+                        quorum_stake
+                    ),
+                    _ => println!(
+                        "Service: Received ACCEPT vote for block {}",
+                        hex::encode(block_hash)
+                    ),
+                }
             }
 
             AttestationVote::Reject { reason } => {
@@ -314,8 +1027,7 @@ impl BlockchainService {
         vote: &AttestationVote,
         signature: &Signature,
     ) -> Result<bool> {
-        let message = format!("ATTEST:{}:{:?}", hex::encode(block_hash), vote);
-        self.verify_signature(&message, validator_id, signature)
+        self.verify_signature(&attestation_hash(block_hash, vote), validator_id, signature)
     }
 
     // for block signature verification before calling blockchain layer
@@ -326,19 +1038,17 @@ impl BlockchainService {
         signature: &Signature,
     ) -> Result<bool> {
         let message = hex::encode(block_hash); // Blocks are signed directly on hash
-        self.verify_signature(&message, proposer_id, signature)
+        self.verify_signature(&keccak256(message.as_bytes()), proposer_id, signature)
     }
 
     // generic verify signature method
     fn verify_signature(
         &self,
-        message: &str,
+        message_hash: &B256,
         expected_signer: &Address,
         signature: &Signature,
     ) -> Result<bool> {
-        let message_hash = keccak256(message.as_bytes());
-
-        match signature.recover_address_from_prehash(&message_hash) {
+        match signature.recover_address_from_prehash(message_hash) {
             Ok(recovered_address) => Ok(recovered_address == *expected_signer),
             Err(_) => {
                 println!("Service: Failed to recover address from signature");
@@ -351,6 +1061,7 @@ impl BlockchainService {
     async fn create_and_send_attestation(
         &self,
         block_hash: B256,
+        slot: u64,
         vote: AttestationVote,
     ) -> Result<()> {
         println!(
@@ -359,13 +1070,34 @@ impl BlockchainService {
             hex::encode(block_hash)
         );
 
-        // Create a simple attestation signature
-        // In production, you'd sign the block hash + vote
-        let message = format!("ATTEST:{}:{:?}", hex::encode(block_hash), vote);
-        // hash the message -> B256
-        let message_hash = keccak256(message.as_bytes());
+        // checked against what this key signed before, also before a restart
+        let guard = {
+            let blockchain = self.blockchain.lock().await;
+            blockchain
+                .guard_attestation(self.validator_address, slot, block_hash)
+                .await
+        };
+        if let Err(e) = guard {
+            println!("🛡️  Not attesting: {}", e);
+            return Ok(());
+        }
+
+        // sign the block hash + vote, see attestation_hash
+        let message_hash = attestation_hash(&block_hash, &vote);
         // creates signature
         let signature = self.keypair.sign_hash(&message_hash).await?;
+        {
+            let blockchain = self.blockchain.lock().await;
+            blockchain
+                .record_signature(
+                    slot,
+                    block_hash,
+                    SignedMessage::Attestation { vote: vote.clone() },
+                    self.validator_address,
+                    signature,
+                )
+                .await;
+        }
 
         // instantiate attestation msg
         let attestation_msg = BlockchainMessage::Attestation {
@@ -384,3 +1116,30 @@ impl BlockchainService {
         Ok(())
     }
 }
+
+// run one loop step, dropped half way if the watchdog restarts the stalled loop
+async fn supervised(
+    heartbeat: &LoopHeartbeat,
+    activity: &'static str,
+    queued_messages: usize,
+    step: impl Future<Output = Result<()>>,
+) -> Result<()> {
+    heartbeat.enter(activity, queued_messages);
+    let result = tokio::select! {
+        result = step => result,
+        _ = heartbeat.restart_requested() => {
+            println!("♻️  Abandoned stalled {}, continuing with the next step", activity);
+            Ok(())
+        }
+    };
+    heartbeat.leave();
+    result
+}
+
+// resolves at the deadline, never without one
+async fn sleep_until_deadline(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
@@ -1,7 +1,8 @@
-use alloy::primitives::{Address, B256, Signature, keccak256};
+use alloy::primitives::{Address, B256, Bloom, Signature, U256};
 use serde::{Deserialize, Serialize};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use super::header_hashing::HashableHeader;
 use crate::{KeyPair, SignatureError};
 
 // Block structure, uses Alloy's B256 for hashes
@@ -20,10 +21,41 @@ pub struct BlockHeader {
     pub transactions_root: B256,
     pub state_root: B256,
 
+    // adaptive slot clock, only on chains that enable it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slot_timing: Option<SlotTiming>,
+
+    // execution results, set from the block's receipts before signing and checked on import
+    // absent in headers of older blocks, which are read as an empty block's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receipts_root: Option<B256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logs_bloom: Option<Bloom>,
+
+    // fee market, only on chains with a base fee, see `BaseFeeConfig`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_fee_per_gas: Option<U256>,
+    // gas the block's transactions used and the block gas limit it was built for,
+    // absent in headers of older blocks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_limit: Option<U256>,
+
     // Ethereum-style signature (65 bytes: r + s + v)
     pub validator_signature: Option<Signature>,
 }
 
+// when the block's slot started and how long it and the following empty slots last,
+// plus the propagation latency its proposer measured, see `AdaptiveSlots`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotTiming {
+    pub slot_start_ms: u64,
+    pub slot_duration_ms: u64,
+    // p90 receive latency of recent blocks after their slot start, 0 without samples
+    pub propagation_ms: u64,
+}
+
 impl BlockHeader {
     // new blockheader
     pub fn new(
@@ -41,13 +73,17 @@ impl BlockHeader {
             parent_hash,
             transactions_root,
             state_root,
+            slot_timing: None,
+            receipts_root: None,
+            logs_bloom: None,
+            base_fee_per_gas: None,
+            gas_used: None,
+            gas_limit: None,
             timestamp: SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .unwrap()
                 .as_secs(),
             validator_signature: None,
-            // gas_limit: 0,
-            // gas_used: 0,
         }
     }
 
@@ -56,23 +92,19 @@ impl BlockHeader {
         Self::new(0, 0, Address::ZERO, B256::ZERO, B256::ZERO, B256::ZERO)
     }
 
-    // get the header hash
-    // Calculate deterministic hash of the header
+    // receipts root the header commits to, zero when it carries none
+    pub fn committed_receipts_root(&self) -> B256 {
+        self.receipts_root.unwrap_or(B256::ZERO)
+    }
+
+    // logs bloom the header commits to, empty when it carries none
+    pub fn committed_logs_bloom(&self) -> Bloom {
+        self.logs_bloom.unwrap_or(Bloom::ZERO)
+    }
+
+    // get the header hash, see HashableHeader for the encoding
     pub fn hash(&self) -> B256 {
-        let mut data = Vec::new();
-
-        // Add all consensus-critical fields in deterministic order
-        data.extend_from_slice(&self.index.to_be_bytes());
-        data.extend_from_slice(self.parent_hash.as_slice());
-        data.extend_from_slice(&self.slot.to_be_bytes());
-        data.extend_from_slice(&self.timestamp.to_be_bytes());
-        data.extend_from_slice(self.proposer.as_slice());
-        data.extend_from_slice(self.transactions_root.as_slice());
-        data.extend_from_slice(self.state_root.as_slice());
-
-        // NOTE: We don't include validator_signature in hash calculation
-        // because the signature is OF the hash, not part of it
-        keccak256(&data)
+        HashableHeader::from(self).hash()
     }
 
     // Signing message hash
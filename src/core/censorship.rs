@@ -0,0 +1,167 @@
+use alloy::primitives::{Address, B256, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::{Block, Transaction};
+
+// distinct proposers that must have skipped a transaction before it is reported
+pub const DEFAULT_CENSORSHIP_MIN_PROPOSERS: usize = 3;
+
+// a pending transaction that could have gone into a block and didn't
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExcludedTransaction {
+    pub hash: B256,
+    pub from: Address,
+    pub gas_price: U256,
+    // slot of the first block committed while it was pending
+    pub first_seen_slot: u64,
+    pub blocks_missed: u64,
+    // proposer -> blocks of theirs that left it out
+    pub proposers: BTreeMap<Address, u64>,
+}
+
+// how often one proposer left out the transactions in the report
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProposerExclusions {
+    pub proposer: Address,
+    // blocks of this proposer observed since the node started
+    pub blocks: u64,
+    // reported transactions this proposer skipped at least once
+    pub transactions: usize,
+    // blocks of this proposer that skipped a reported transaction, counted per transaction
+    pub exclusions: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CensorshipReport {
+    pub blocks_observed: u64,
+    // pending transactions being watched
+    pub tracked: usize,
+    pub min_proposers: usize,
+    // most exclusions first
+    pub proposers: Vec<ProposerExclusions>,
+    // skipped by at least `min_proposers` proposers, most blocks missed first
+    pub transactions: Vec<ExcludedTransaction>,
+}
+
+// watches pending transactions across committed blocks
+// a transaction counts as excluded by a block when it was already pending at the block
+// before, its sender's next nonce is affordable, it pays at least the cheapest fee the
+// block included and the block had room for it
+pub struct CensorshipTracker {
+    pending: HashMap<B256, ExcludedTransaction>,
+    blocks: BTreeMap<Address, u64>,
+    blocks_observed: u64,
+}
+
+impl CensorshipTracker {
+    pub fn new() -> Self {
+        Self {
+            pending: HashMap::new(),
+            blocks: BTreeMap::new(),
+            blocks_observed: 0,
+        }
+    }
+
+    // `executable` are the pending transactions the state after `block` could run,
+    // `gas_room` is the gas the block left unused
+    pub fn observe_block(
+        &mut self,
+        block: &Block,
+        executable: &[(Transaction, U256)],
+        gas_room: U256,
+    ) {
+        let proposer = block.header.proposer;
+        self.blocks_observed += 1;
+        *self.blocks.entry(proposer).or_insert(0) += 1;
+
+        let included_senders: HashSet<Address> =
+            block.transactions.iter().map(|tx| tx.from).collect();
+        let cheapest_included = block.transactions.iter().map(|tx| tx.gas_price).min();
+
+        // included, replaced or dropped transactions are forgotten
+        let still_pending: HashSet<B256> = executable.iter().map(|(tx, _)| tx.hash).collect();
+        self.pending.retain(|hash, _| still_pending.contains(hash));
+
+        for (tx, intrinsic_gas) in executable {
+            let Some(tracked) = self.pending.get_mut(&tx.hash) else {
+                self.pending.insert(
+                    tx.hash,
+                    ExcludedTransaction {
+                        hash: tx.hash,
+                        from: tx.from,
+                        gas_price: tx.gas_price,
+                        first_seen_slot: block.header.slot,
+                        blocks_missed: 0,
+                        proposers: BTreeMap::new(),
+                    },
+                );
+                continue;
+            };
+
+            // the sender's earlier transaction went in, this one wasn't runnable yet
+            if included_senders.contains(&tx.from) {
+                continue;
+            }
+            if cheapest_included.is_some_and(|cheapest| tx.gas_price < cheapest) {
+                continue;
+            }
+            if *intrinsic_gas > gas_room {
+                continue;
+            }
+
+            tracked.blocks_missed += 1;
+            *tracked.proposers.entry(proposer).or_insert(0) += 1;
+        }
+    }
+
+    pub fn report(&self, min_proposers: usize) -> CensorshipReport {
+        let mut transactions: Vec<ExcludedTransaction> = self
+            .pending
+            .values()
+            .filter(|tx| tx.proposers.len() >= min_proposers.max(1))
+            .cloned()
+            .collect();
+        transactions.sort_by(|a, b| {
+            b.blocks_missed
+                .cmp(&a.blocks_missed)
+                .then(a.first_seen_slot.cmp(&b.first_seen_slot))
+                .then(a.hash.cmp(&b.hash))
+        });
+
+        let mut by_proposer: BTreeMap<Address, (usize, u64)> = BTreeMap::new();
+        for tx in &transactions {
+            for (proposer, missed) in &tx.proposers {
+                let entry = by_proposer.entry(*proposer).or_insert((0, 0));
+                entry.0 += 1;
+                entry.1 += missed;
+            }
+        }
+        let mut proposers: Vec<ProposerExclusions> = by_proposer
+            .into_iter()
+            .map(
+                |(proposer, (transactions, exclusions))| ProposerExclusions {
+                    proposer,
+                    blocks: self.blocks.get(&proposer).copied().unwrap_or(0),
+                    transactions,
+                    exclusions,
+                },
+            )
+            .collect();
+        proposers.sort_by_key(|p| std::cmp::Reverse(p.exclusions));
+
+        CensorshipReport {
+            blocks_observed: self.blocks_observed,
+            tracked: self.pending.len(),
+            min_proposers,
+            proposers,
+            transactions,
+        }
+    }
+}
+
+impl Default for CensorshipTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
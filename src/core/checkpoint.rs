@@ -0,0 +1,167 @@
+use alloy::primitives::B256;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use super::{Block, HeadInfo};
+use crate::consensus::{BlockRandomness, ConsensusGenesis};
+use crate::{StateManager, Storage};
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum CheckpointError {
+    #[error("Checkpoint block hashes to {actual}, the config trusts {expected}")]
+    UntrustedBlock { expected: B256, actual: B256 },
+    #[error("Checkpoint state has root {actual}, block #{index} has {expected}")]
+    StateRoot {
+        index: u64,
+        expected: B256,
+        actual: B256,
+    },
+    #[error("Database has {actual:?} at block #{index} instead of checkpoint {expected}")]
+    ConflictingChain {
+        index: u64,
+        expected: B256,
+        actual: Option<B256>,
+    },
+    #[error("Block #{index} does not link back to checkpoint block #{checkpoint}")]
+    BrokenLink { index: u64, checkpoint: u64 },
+    #[error("Checkpoint is from a chain with another slot clock or proposer seed")]
+    GenesisMismatch,
+}
+
+// a node's head block, its account state and block randomness: enough for another
+// node to start at that block instead of replaying the chain from genesis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub block: Block,
+    pub state: StateManager,
+    // accumulated block hashes, they seed the proposer schedule of the coming epochs
+    pub randomness: BlockRandomness,
+    // slot clock and base seed, a node started from the checkpoint counts the same slots
+    pub genesis: ConsensusGenesis,
+}
+
+// checkpoint file written by `admin_exportCheckpoint`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointInfo {
+    pub path: String,
+    pub block_number: u64,
+    // what operators put in `checkpoint.block_hash` after checking it with a source they trust
+    pub block_hash: B256,
+    pub state_root: B256,
+}
+
+impl Checkpoint {
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = fs::read(path)
+            .with_context(|| format!("Failed to read checkpoint {}", path.display()))?;
+        serde_json::from_slice(&data)
+            .with_context(|| format!("Failed to parse checkpoint {}", path.display()))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_vec(self)?)
+            .with_context(|| format!("Failed to write checkpoint {}", path.display()))
+    }
+
+    pub fn block_hash(&self) -> B256 {
+        self.block.header.hash()
+    }
+
+    pub fn head(&self) -> HeadInfo {
+        HeadInfo {
+            number: self.block.header.index,
+            hash: self.block_hash(),
+            slot: self.block.header.slot,
+        }
+    }
+
+    // the block is the trusted one and the state is the one it committed to
+    pub fn verify(&self, trusted_hash: &B256) -> Result<(), CheckpointError> {
+        let actual = self.block_hash();
+        if actual != *trusted_hash {
+            return Err(CheckpointError::UntrustedBlock {
+                expected: *trusted_hash,
+                actual,
+            });
+        }
+        let expected = self.block.header.state_root;
+        for actual in [
+            self.state.get_state_root(),
+            self.state.computed_state_root(),
+        ] {
+            if actual != expected {
+                return Err(CheckpointError::StateRoot {
+                    index: self.block.header.index,
+                    expected,
+                    actual,
+                });
+            }
+        }
+        Ok(())
+    }
+    // start a database from the checkpoint, or check that the chain it holds links back to it
+    // the blockchain opened on it afterwards resumes from the checkpoint block and state
+    pub fn install(&self, storage: &Storage, trusted_hash: &B256) -> Result<HeadInfo> {
+        self.verify(trusted_hash)?;
+        if let Some(genesis) = storage.get_consensus_genesis()?
+            && genesis != self.genesis
+        {
+            return Err(CheckpointError::GenesisMismatch.into());
+        }
+
+        let checkpoint = self.head();
+        let Some(last_index) = storage.get_last_index()? else {
+            storage.put_consensus_genesis(&self.genesis)?;
+            storage.store_block(&self.block)?;
+            storage.put_block_randomness(&self.randomness)?;
+            // nothing before the checkpoint can be reorged, it is final by trust
+            storage.put_finalized_head(&checkpoint)?;
+            storage.put_checkpoint(self)?;
+            println!(
+                "📍 Starting from checkpoint block #{} {} at slot {}",
+                checkpoint.number, checkpoint.hash, checkpoint.slot
+            );
+            return Ok(checkpoint);
+        };
+
+        // a database that already has blocks must hold the checkpoint block and build on it
+        let stored = storage.get_block_hash_from_index(&checkpoint.number)?;
+        if stored != Some(checkpoint.hash) {
+            return Err(CheckpointError::ConflictingChain {
+                index: checkpoint.number,
+                expected: checkpoint.hash,
+                actual: stored,
+            }
+            .into());
+        }
+        let mut expected_hash = storage.get_block_hash_from_index(&last_index)?;
+        for index in (checkpoint.number + 1..=last_index).rev() {
+            let block = match expected_hash {
+                Some(hash) => storage.get_block_from_block_hash::<Block>(&hash)?,
+                None => None,
+            };
+            let Some(block) = block else {
+                return Err(CheckpointError::BrokenLink {
+                    index,
+                    checkpoint: checkpoint.number,
+                }
+                .into());
+            };
+            expected_hash = Some(block.header.parent_hash);
+        }
+        if expected_hash != Some(checkpoint.hash) {
+            return Err(CheckpointError::BrokenLink {
+                index: checkpoint.number + 1,
+                checkpoint: checkpoint.number,
+            }
+            .into());
+        }
+        println!(
+            "📍 Chain head #{} links back to checkpoint block #{}",
+            last_index, checkpoint.number
+        );
+        Ok(checkpoint)
+    }
+}
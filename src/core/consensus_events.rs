@@ -0,0 +1,134 @@
+use alloy::primitives::{Address, B256};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::SystemTime;
+
+use crate::MempoolSummary;
+use crate::unix_millis;
+
+// consensus events kept in memory for debug bundles
+pub const CONSENSUS_EVENTS_KEPT: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConsensusEventKind {
+    // produced and committed by this node
+    Proposed,
+    // received from a peer and committed
+    Imported,
+    Rejected {
+        reason: String,
+    },
+    // a block finished the epoch and its statements were written
+    EpochClosed {
+        epoch: u64,
+    },
+    // attestations for the block reached a quorum
+    Certified,
+    // beacon randomness mixed into the proposer schedule of the epoch
+    BeaconMixed {
+        epoch: u64,
+        round: u64,
+    },
+    // the proposer signed another block for the slot, evidence stored
+    DoubleProposal {
+        proposer: Address,
+    },
+    // the validator signed conflicting attestations for the slot, evidence stored
+    DoubleVote {
+        validator: Address,
+    },
+    // the validator missed more of its duties in the closed epoch than liveness allows
+    Deactivated {
+        validator: Address,
+        missed: u64,
+        duties: u64,
+    },
+    // the block's slot is more than one after its parent's, the slots between had no block
+    SlotsSkipped {
+        slots: u64,
+    },
+    // the chain switched to a longer branch, `depth` blocks from `old_head` down were replaced
+    Reorg {
+        depth: u64,
+        old_head: B256,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsensusEvent {
+    pub at_ms: u64,
+    pub slot: u64,
+    pub block_number: u64,
+    // zero for events not about a block
+    pub block_hash: B256,
+    #[serde(flatten)]
+    pub kind: ConsensusEventKind,
+}
+
+// the latest consensus events, oldest dropped first
+pub struct ConsensusEventLog {
+    events: VecDeque<ConsensusEvent>,
+    capacity: usize,
+}
+
+impl ConsensusEventLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            events: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn record(
+        &mut self,
+        slot: u64,
+        block_number: u64,
+        block_hash: B256,
+        kind: ConsensusEventKind,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(ConsensusEvent {
+            at_ms: unix_millis(SystemTime::now()),
+            slot,
+            block_number,
+            block_hash,
+            kind,
+        });
+    }
+
+    // the `count` latest events, oldest first
+    pub fn recent(&self, count: usize) -> Vec<ConsensusEvent> {
+        let skip = self.events.len().saturating_sub(count);
+        self.events.iter().skip(skip).cloned().collect()
+    }
+}
+
+// where consensus stands, as seen by this node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsensusDebugState {
+    pub head_number: u64,
+    pub head_hash: B256,
+    pub head_slot: u64,
+    // None before genesis
+    pub current_slot: Option<u64>,
+    pub slot_duration_ms: u64,
+    pub local_validator: Option<Address>,
+    // active validators and their stake
+    pub validators: Vec<(Address, u64)>,
+    pub adaptive_slots: bool,
+    pub randomness_epochs: Vec<u64>,
+}
+
+// node state served by `admin_debugState` for bug reports, nothing secret in it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugState {
+    pub consensus: ConsensusDebugState,
+    pub mempool: MempoolSummary,
+    pub events: Vec<ConsensusEvent>,
+}
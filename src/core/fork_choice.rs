@@ -0,0 +1,129 @@
+use alloy::primitives::B256;
+use std::collections::{HashMap, VecDeque};
+
+use super::block::Block;
+use crate::consensus::{BlockRandomness, ConsensusHead};
+use crate::{HeadFees, StateUndo};
+
+// canonical blocks a reorg can replace, the state changes of each of them are kept in memory
+pub const MAX_REORG_DEPTH: usize = 16;
+
+// a canonical block and what its state changed, what a reorg rewinds to
+#[derive(Clone)]
+pub struct HeadCheckpoint {
+    pub head: ConsensusHead,
+    // the next base fee follows from it
+    pub fees: HeadFees,
+    pub randomness: BlockRandomness,
+    // back to the state of the checkpoint before this one
+    pub undo: StateUndo,
+}
+
+// the latest canonical blocks we can rewind to, and the blocks off the canonical chain
+// built on them; the longest branch wins, a branch as long as ours is kept but not switched to
+pub struct ForkChoice {
+    // oldest first, the head itself has none until a block is built on it
+    checkpoints: VecDeque<HeadCheckpoint>,
+    // blocks whose parent is a checkpoint or another side block, by hash
+    side_blocks: HashMap<B256, Block>,
+    max_depth: usize,
+}
+
+impl ForkChoice {
+    pub fn new(max_depth: usize) -> Self {
+        Self {
+            checkpoints: VecDeque::new(),
+            side_blocks: HashMap::new(),
+            max_depth,
+        }
+    }
+
+    // the head is about to get a child, side blocks too old to switch to are dropped
+    // a head that was already recorded, e.g. the fork point after a reorg, keeps its checkpoint
+    pub fn record(&mut self, checkpoint: HeadCheckpoint) {
+        if let Some(last) = self.checkpoints.back_mut()
+            && last.head.hash == checkpoint.head.hash
+        {
+            last.undo.extend(checkpoint.undo);
+            return;
+        }
+        self.checkpoints.push_back(checkpoint);
+        while self.checkpoints.len() > self.max_depth {
+            self.checkpoints.pop_front();
+        }
+        if let Some(oldest) = self.checkpoints.front() {
+            let oldest = oldest.head.number;
+            self.side_blocks
+                .retain(|_, block| block.header.index > oldest);
+        }
+    }
+
+    // a canonical block below the head or a side block
+    pub fn knows(&self, hash: &B256) -> bool {
+        self.side_blocks.contains_key(hash) || self.checkpoint(hash).is_some()
+    }
+
+    pub fn checkpoint(&self, hash: &B256) -> Option<&HeadCheckpoint> {
+        self.checkpoints.iter().find(|c| c.head.hash == *hash)
+    }
+
+    // the canonical block at `number` and the undo logs of the checkpoints after it, newest
+    // first; none below the oldest checkpoint or from the head on
+    pub fn checkpoint_at(&self, number: u64) -> Option<(&HeadCheckpoint, Vec<&StateUndo>)> {
+        let position = self
+            .checkpoints
+            .iter()
+            .position(|c| c.head.number == number)?;
+        let undo = self
+            .checkpoints
+            .iter()
+            .skip(position + 1)
+            .rev()
+            .map(|c| &c.undo)
+            .collect();
+        Some((&self.checkpoints[position], undo))
+    }
+
+    pub fn add_side_block(&mut self, block: Block) {
+        self.side_blocks.insert(block.header.hash(), block);
+    }
+
+    pub fn remove_side_block(&mut self, hash: &B256) -> Option<Block> {
+        self.side_blocks.remove(hash)
+    }
+
+    pub fn side_blocks(&self) -> usize {
+        self.side_blocks.len()
+    }
+
+    // the side blocks from the fork point up to `tip`, None if they don't reach a checkpoint
+    pub fn branch(&self, tip: &B256) -> Option<(B256, Vec<Block>)> {
+        let mut blocks = Vec::new();
+        let mut hash = *tip;
+        while let Some(block) = self.side_blocks.get(&hash) {
+            hash = block.header.parent_hash;
+            blocks.push(block.clone());
+        }
+        if blocks.is_empty() || self.checkpoint(&hash).is_none() {
+            return None;
+        }
+        blocks.reverse();
+        Some((hash, blocks))
+    }
+
+    // forget the checkpoints of the blocks after `fork_point`, returning the fork point's and
+    // their undo logs, newest first
+    pub fn rewind(&mut self, fork_point: &B256) -> Option<(HeadCheckpoint, Vec<StateUndo>)> {
+        let position = self
+            .checkpoints
+            .iter()
+            .position(|c| c.head.hash == *fork_point)?;
+        let undo = self
+            .checkpoints
+            .drain(position + 1..)
+            .rev()
+            .map(|c| c.undo)
+            .collect();
+        Some((self.checkpoints[position].clone(), undo))
+    }
+}
@@ -0,0 +1,125 @@
+use alloy::primitives::{Address, B256};
+use alloy_signer::Signature;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::block::Block;
+
+// gossiped blocks that arrive before their slot or their parent, held instead of rejected
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FutureBlockConfig {
+    pub enabled: bool,
+    // blocks held at once, further ones are rejected like before
+    pub capacity: usize,
+    // how far past our current slot a block may be, blocks further ahead are rejected
+    pub max_slots_ahead: u64,
+    // slots a block waits for its parent before it is dropped
+    pub max_wait_slots: u64,
+}
+
+impl Default for FutureBlockConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            capacity: 64,
+            max_slots_ahead: 2,
+            max_wait_slots: 8,
+        }
+    }
+}
+
+// a block, its proposer and signature, replayed through the normal import once it's due
+#[derive(Debug, Clone)]
+pub struct FutureBlock {
+    pub block: Block,
+    pub proposer_id: Address,
+    pub signature: Signature,
+}
+
+// held blocks by parent hash, a parent can have several children on competing branches
+#[derive(Debug, Default)]
+pub struct FutureBlockQueue {
+    by_parent: HashMap<B256, Vec<FutureBlock>>,
+    capacity: usize,
+    len: usize,
+}
+
+impl FutureBlockQueue {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            by_parent: HashMap::new(),
+            capacity,
+            len: 0,
+        }
+    }
+
+    // false when the queue is full, a block already held is not held twice
+    pub fn insert(&mut self, future: FutureBlock) -> bool {
+        if self.contains(&future.block.header.hash()) {
+            return true;
+        }
+        if self.len >= self.capacity {
+            return false;
+        }
+        self.by_parent
+            .entry(future.block.header.parent_hash)
+            .or_default()
+            .push(future);
+        self.len += 1;
+        true
+    }
+
+    pub fn parents(&self) -> Vec<B256> {
+        self.by_parent.keys().copied().collect()
+    }
+
+    pub fn contains(&self, hash: &B256) -> bool {
+        self.by_parent
+            .values()
+            .flatten()
+            .any(|held| held.block.header.hash() == *hash)
+    }
+
+    // blocks whose slot has started and whose parent `is_known`, lowest block number first
+    pub fn take_ready(
+        &mut self,
+        current_slot: u64,
+        is_known: impl Fn(&B256) -> bool,
+    ) -> Vec<FutureBlock> {
+        let mut ready = Vec::new();
+        for (parent, siblings) in self.by_parent.iter_mut() {
+            if !is_known(parent) {
+                continue;
+            }
+            let (due, later) = siblings
+                .drain(..)
+                .partition(|held| held.block.header.slot <= current_slot);
+            *siblings = later;
+            ready.extend(due);
+        }
+        self.by_parent.retain(|_, siblings| !siblings.is_empty());
+        self.len -= ready.len();
+        ready.sort_by_key(|held| (held.block.header.index, held.block.header.slot));
+        ready
+    }
+
+    // drop blocks for slots before `oldest_slot`, their parent never showed up; returns how many
+    pub fn prune(&mut self, oldest_slot: u64) -> usize {
+        let before = self.len;
+        for siblings in self.by_parent.values_mut() {
+            siblings.retain(|held| held.block.header.slot >= oldest_slot);
+        }
+        self.by_parent.retain(|_, siblings| !siblings.is_empty());
+        self.len = self.by_parent.values().map(Vec::len).sum();
+        before - self.len
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
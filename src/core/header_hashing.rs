@@ -0,0 +1,169 @@
+use alloy::primitives::{Address, B256, Bloom, U256, keccak256};
+
+use super::blockheader::{BlockHeader, SlotTiming};
+use super::transaction::Transaction;
+
+// index, parent_hash, slot, timestamp, proposer, transactions_root, state_root
+pub const HASHABLE_HEADER_LEN: usize = 8 + 32 + 8 + 8 + 20 + 32 + 32;
+// slot start, slot duration and propagation, appended on adaptive slot chains
+pub const SLOT_TIMING_LEN: usize = 8 + 8 + 8;
+// one bit per optional field below, set when the header carries it; the byte is
+// written ahead of them, and left out when none is present
+pub const PRESENCE_LEN: usize = 1;
+pub const HAS_RECEIPTS_ROOT: u8 = 1 << 0;
+pub const HAS_LOGS_BLOOM: u8 = 1 << 1;
+pub const HAS_BASE_FEE: u8 = 1 << 2;
+pub const HAS_GAS_USED: u8 = 1 << 3;
+pub const HAS_GAS_LIMIT: u8 = 1 << 4;
+// receipts root and logs bloom, appended when the header carries them
+pub const RECEIPTS_ROOT_LEN: usize = 32;
+pub const LOGS_BLOOM_LEN: usize = 256;
+// base fee, appended on chains with a base fee, then gas used and the block gas limit
+pub const BASE_FEE_LEN: usize = 32;
+pub const GAS_USED_LEN: usize = 32;
+pub const GAS_LIMIT_LEN: usize = 32;
+
+// consensus-critical header fields, the only encoding a block hash is taken over
+// the validator signature is left out, it signs this hash
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HashableHeader {
+    pub index: u64,
+    pub parent_hash: B256,
+    pub slot: u64,
+    pub timestamp: u64,
+    pub proposer: Address,
+    pub transactions_root: B256,
+    pub state_root: B256,
+    pub slot_timing: Option<SlotTiming>,
+    pub receipts_root: Option<B256>,
+    pub logs_bloom: Option<Bloom>,
+    pub base_fee_per_gas: Option<U256>,
+    pub gas_used: Option<U256>,
+    pub gas_limit: Option<U256>,
+}
+
+impl HashableHeader {
+    // fixed size concatenation, integers big endian, in the field order above
+    // the slot timing adds its three fields when present, then the presence byte, the
+    // receipts root and logs bloom, then the base fee, gas used and gas limit; the byte
+    // tells which of these 32 byte fields are there, headers without them hash as before
+    pub fn encode(&self) -> Vec<u8> {
+        let presence = self.presence();
+        let timing_len = self.slot_timing.map_or(0, |_| SLOT_TIMING_LEN);
+        let presence_len = if presence == 0 { 0 } else { PRESENCE_LEN };
+        let receipts_len = self.receipts_root.map_or(0, |_| RECEIPTS_ROOT_LEN);
+        let bloom_len = self.logs_bloom.map_or(0, |_| LOGS_BLOOM_LEN);
+        let base_fee_len = self.base_fee_per_gas.map_or(0, |_| BASE_FEE_LEN);
+        let gas_used_len = self.gas_used.map_or(0, |_| GAS_USED_LEN);
+        let gas_limit_len = self.gas_limit.map_or(0, |_| GAS_LIMIT_LEN);
+        let mut data = vec![
+            0u8;
+            HASHABLE_HEADER_LEN
+                + timing_len
+                + presence_len
+                + receipts_len
+                + bloom_len
+                + base_fee_len
+                + gas_used_len
+                + gas_limit_len
+        ];
+        let mut offset = 0;
+        for field in [
+            &self.index.to_be_bytes()[..],
+            self.parent_hash.as_slice(),
+            &self.slot.to_be_bytes(),
+            &self.timestamp.to_be_bytes(),
+            self.proposer.as_slice(),
+            self.transactions_root.as_slice(),
+            self.state_root.as_slice(),
+        ] {
+            data[offset..offset + field.len()].copy_from_slice(field);
+            offset += field.len();
+        }
+        if let Some(timing) = self.slot_timing {
+            for field in [
+                timing.slot_start_ms,
+                timing.slot_duration_ms,
+                timing.propagation_ms,
+            ] {
+                data[offset..offset + 8].copy_from_slice(&field.to_be_bytes());
+                offset += 8;
+            }
+        }
+        if presence != 0 {
+            data[offset] = presence;
+            offset += PRESENCE_LEN;
+        }
+        if let Some(root) = self.receipts_root {
+            data[offset..offset + RECEIPTS_ROOT_LEN].copy_from_slice(root.as_slice());
+            offset += RECEIPTS_ROOT_LEN;
+        }
+        if let Some(bloom) = self.logs_bloom {
+            data[offset..offset + LOGS_BLOOM_LEN].copy_from_slice(bloom.as_slice());
+            offset += LOGS_BLOOM_LEN;
+        }
+        if let Some(base_fee) = self.base_fee_per_gas {
+            data[offset..offset + BASE_FEE_LEN].copy_from_slice(&base_fee.to_be_bytes::<32>());
+            offset += BASE_FEE_LEN;
+        }
+        if let Some(gas_used) = self.gas_used {
+            data[offset..offset + GAS_USED_LEN].copy_from_slice(&gas_used.to_be_bytes::<32>());
+            offset += GAS_USED_LEN;
+        }
+        if let Some(gas_limit) = self.gas_limit {
+            data[offset..offset + GAS_LIMIT_LEN].copy_from_slice(&gas_limit.to_be_bytes::<32>());
+        }
+        data
+    }
+
+    pub fn hash(&self) -> B256 {
+        keccak256(self.encode())
+    }
+
+    fn presence(&self) -> u8 {
+        [
+            (self.receipts_root.is_some(), HAS_RECEIPTS_ROOT),
+            (self.logs_bloom.is_some(), HAS_LOGS_BLOOM),
+            (self.base_fee_per_gas.is_some(), HAS_BASE_FEE),
+            (self.gas_used.is_some(), HAS_GAS_USED),
+            (self.gas_limit.is_some(), HAS_GAS_LIMIT),
+        ]
+        .into_iter()
+        .filter(|(present, _)| *present)
+        .fold(0, |presence, (_, bit)| presence | bit)
+    }
+}
+
+impl From<&BlockHeader> for HashableHeader {
+    fn from(header: &BlockHeader) -> Self {
+        Self {
+            index: header.index,
+            parent_hash: header.parent_hash,
+            slot: header.slot,
+            timestamp: header.timestamp,
+            proposer: header.proposer,
+            transactions_root: header.transactions_root,
+            state_root: header.state_root,
+            slot_timing: header.slot_timing,
+            receipts_root: header.receipts_root,
+            logs_bloom: header.logs_bloom,
+            base_fee_per_gas: header.base_fee_per_gas,
+            gas_used: header.gas_used,
+            gas_limit: header.gas_limit,
+        }
+    }
+}
+
+// keccak of the transaction hashes in block order, zero for an empty block
+// not a merkle root, inclusion proofs need every hash of the block
+pub fn transactions_root(transactions: &[Transaction]) -> B256 {
+    if transactions.is_empty() {
+        return B256::ZERO;
+    }
+
+    let mut data = Vec::with_capacity(transactions.len() * 32);
+    for tx in transactions {
+        data.extend_from_slice(tx.hash.as_slice());
+    }
+    keccak256(data)
+}
@@ -2,10 +2,31 @@ pub mod block;
 pub mod blockchain;
 pub mod blockchain_service;
 pub mod blockheader;
+pub mod censorship;
+pub mod checkpoint;
+pub mod consensus_events;
+pub mod fork_choice;
+pub mod future_blocks;
+pub mod header_hashing;
+pub mod performance;
+pub mod quarantine;
 pub mod transaction;
+pub mod watchdog;
 
 pub use block::Block;
-pub use blockchain::Blockchain;
+pub use blockchain::{
+    BLOCK_EVENTS_CAPACITY, BlockFinality, Blockchain, ChainHeads, ChainInfo, CommittedBlock,
+    DryRunBlock, DutyStatus, HeadInfo, HeadTag, StateSnapshotInfo, SyncStatus,
+};
 pub use blockchain_service::*;
-pub use blockheader::BlockHeader;
-pub use transaction::Transaction;
+pub use blockheader::{BlockHeader, SlotTiming};
+pub use censorship::*;
+pub use checkpoint::*;
+pub use consensus_events::*;
+pub use fork_choice::*;
+pub use future_blocks::*;
+pub use header_hashing::*;
+pub use performance::*;
+pub use quarantine::*;
+pub use transaction::{MAX_MEMO_LENGTH, Transaction, TransactionKind, TransferOutput};
+pub use watchdog::*;
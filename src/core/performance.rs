@@ -0,0 +1,257 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::{LatencyPercentiles, NodeConfig};
+
+// samples kept per metric for the performance report
+pub const PERFORMANCE_WINDOW: usize = 256;
+// block import may use this part of the slot before it is reported as slow
+pub const SLOW_IMPORT_SLOT_FRACTION: f64 = 0.25;
+// storage writes of one block slower than this at p90 point at the disk
+pub const SLOW_STATE_WRITE_MS: i64 = 100;
+// network messages waiting for the blockchain service at p90
+pub const HIGH_GOSSIP_BACKLOG: usize = 64;
+
+// queue length distribution, nearest-rank percentiles
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BacklogStats {
+    pub samples: usize,
+    pub p50: usize,
+    pub p90: usize,
+    pub max: usize,
+}
+
+impl BacklogStats {
+    pub fn from_samples(samples: Vec<usize>) -> Self {
+        let stats = LatencyPercentiles::from_samples(samples.iter().map(|s| *s as i64).collect());
+        Self {
+            samples: stats.samples,
+            p50: stats.p50_ms as usize,
+            p90: stats.p90_ms as usize,
+            max: stats.max_ms as usize,
+        }
+    }
+}
+
+// runtime samples over the last PERFORMANCE_WINDOW events of each kind
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PerformanceSamples {
+    // validating, executing and storing a gossiped block
+    pub block_import: LatencyPercentiles,
+    // writing a block, its receipts, state diff and indexes
+    pub state_write: LatencyPercentiles,
+    // network messages queued for the blockchain service when one is taken
+    pub gossip_backlog: BacklogStats,
+    // gossip transactions deferred per proposal window, see `GossipThrottleConfig`
+    pub deferred_gossip: BacklogStats,
+}
+
+// records block import, state write and gossip backlog samples
+pub struct PerformanceTracker {
+    block_imports: VecDeque<i64>,
+    state_writes: VecDeque<i64>,
+    gossip_backlog: VecDeque<usize>,
+    deferred_gossip: VecDeque<usize>,
+    capacity: usize,
+}
+
+impl PerformanceTracker {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            block_imports: VecDeque::with_capacity(capacity),
+            state_writes: VecDeque::with_capacity(capacity),
+            gossip_backlog: VecDeque::with_capacity(capacity),
+            deferred_gossip: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn record_block_import(&mut self, elapsed: Duration) {
+        push_bounded(&mut self.block_imports, millis(elapsed), self.capacity);
+    }
+
+    pub fn record_state_write(&mut self, elapsed: Duration) {
+        push_bounded(&mut self.state_writes, millis(elapsed), self.capacity);
+    }
+
+    pub fn record_gossip_backlog(&mut self, queued: usize) {
+        push_bounded(&mut self.gossip_backlog, queued, self.capacity);
+    }
+
+    pub fn record_deferred_gossip(&mut self, deferred: usize) {
+        push_bounded(&mut self.deferred_gossip, deferred, self.capacity);
+    }
+
+    pub fn samples(&self) -> PerformanceSamples {
+        PerformanceSamples {
+            block_import: LatencyPercentiles::from_samples(
+                self.block_imports.iter().copied().collect(),
+            ),
+            state_write: LatencyPercentiles::from_samples(
+                self.state_writes.iter().copied().collect(),
+            ),
+            gossip_backlog: BacklogStats::from_samples(
+                self.gossip_backlog.iter().copied().collect(),
+            ),
+            deferred_gossip: BacklogStats::from_samples(
+                self.deferred_gossip.iter().copied().collect(),
+            ),
+        }
+    }
+}
+
+fn push_bounded<T>(samples: &mut VecDeque<T>, sample: T, capacity: usize) {
+    if samples.len() == capacity {
+        samples.pop_front();
+    }
+    samples.push_back(sample);
+}
+
+fn millis(elapsed: Duration) -> i64 {
+    elapsed.as_millis() as i64
+}
+
+// settings the advisor can suggest changes to, as the node runs with them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TuningKnobs {
+    pub data_dir: String,
+    pub replay_horizon_slots: u64,
+    // tokio runtime workers, set with the TOKIO_WORKER_THREADS environment variable
+    pub worker_threads: usize,
+    pub available_cpus: usize,
+}
+
+impl TuningKnobs {
+    // runtime values are read from the current tokio runtime and the host
+    pub fn from_config(config: &NodeConfig) -> Self {
+        let available_cpus = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        let worker_threads = tokio::runtime::Handle::try_current()
+            .map(|handle| handle.metrics().num_workers())
+            .unwrap_or(available_cpus);
+
+        Self {
+            data_dir: config.data_dir.clone(),
+            replay_horizon_slots: config.replay_horizon_slots,
+            worker_threads,
+            available_cpus,
+        }
+    }
+
+    fn spare_cpus(&self) -> bool {
+        self.worker_threads < self.available_cpus
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PerformanceArea {
+    BlockImport,
+    StateWrite,
+    GossipBacklog,
+}
+
+// a bottleneck and the change that should relieve it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TuningAdvice {
+    pub area: PerformanceArea,
+    pub finding: String,
+    pub suggestion: String,
+}
+
+// returned by `speed_performanceReport`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceReport {
+    pub slot_duration_ms: u64,
+    pub samples: PerformanceSamples,
+    pub knobs: TuningKnobs,
+    // empty when nothing stands out
+    pub advice: Vec<TuningAdvice>,
+}
+
+impl PerformanceReport {
+    pub fn new(samples: PerformanceSamples, knobs: TuningKnobs, slot_duration: Duration) -> Self {
+        let slot_duration_ms = slot_duration.as_millis() as u64;
+        let advice = advise(&samples, &knobs, slot_duration_ms);
+
+        Self {
+            slot_duration_ms,
+            samples,
+            knobs,
+            advice,
+        }
+    }
+}
+
+// compare the samples against the slot budget and fixed thresholds
+fn advise(samples: &PerformanceSamples, knobs: &TuningKnobs, slot_ms: u64) -> Vec<TuningAdvice> {
+    let mut advice = Vec::new();
+    let more_workers = format!(
+        "Set TOKIO_WORKER_THREADS={} to use every core, the runtime has {} workers",
+        knobs.available_cpus, knobs.worker_threads
+    );
+
+    let writes = &samples.state_write;
+    if writes.samples > 0 && writes.p90_ms > SLOW_STATE_WRITE_MS {
+        advice.push(TuningAdvice {
+            area: PerformanceArea::StateWrite,
+            finding: format!(
+                "State writes take {} ms at p90 (threshold {} ms)",
+                writes.p90_ms, SLOW_STATE_WRITE_MS
+            ),
+            suggestion: format!(
+                "Move `data_dir` ({}) to a faster local disk, blocks are written before they are attested",
+                knobs.data_dir
+            ),
+        });
+    }
+
+    let imports = &samples.block_import;
+    let import_budget_ms = (slot_ms as f64 * SLOW_IMPORT_SLOT_FRACTION) as i64;
+    // slow writes already explain a slow import
+    if imports.samples > 0
+        && imports.p90_ms > import_budget_ms
+        && writes.p90_ms * 2 < imports.p90_ms
+    {
+        advice.push(TuningAdvice {
+            area: PerformanceArea::BlockImport,
+            finding: format!(
+                "Block import takes {} ms at p90, over {}% of the {} ms slot",
+                imports.p90_ms,
+                (SLOW_IMPORT_SLOT_FRACTION * 100.0) as u64,
+                slot_ms
+            ),
+            suggestion: if knobs.spare_cpus() {
+                more_workers.clone()
+            } else {
+                "Execution is CPU bound, run the node on a faster CPU or move other chains started with `--chains` to another host".to_string()
+            },
+        });
+    }
+
+    let backlog = &samples.gossip_backlog;
+    if backlog.samples > 0 && backlog.p90 > HIGH_GOSSIP_BACKLOG {
+        let finding = format!(
+            "{} network messages wait for the blockchain service at p90 (threshold {})",
+            backlog.p90, HIGH_GOSSIP_BACKLOG
+        );
+        let suggestion = if knobs.spare_cpus() {
+            more_workers
+        } else {
+            format!(
+                "Lower `replay_horizon_slots` from {} to {} so old blocks are dropped before validation",
+                knobs.replay_horizon_slots,
+                (knobs.replay_horizon_slots / 2).max(1)
+            )
+        };
+        advice.push(TuningAdvice {
+            area: PerformanceArea::GossipBacklog,
+            finding,
+            suggestion,
+        });
+    }
+
+    advice
+}
@@ -0,0 +1,31 @@
+use alloy::primitives::{Address, B256};
+use serde::{Deserialize, Serialize};
+
+use super::block::Block;
+
+// rejected blocks returned by one `speed_getRejectedBlocks` call
+pub const MAX_REJECTED_BLOCKS_PER_REQUEST: usize = 100;
+
+// a gossiped block this node refused, kept with why and who sent it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RejectedBlock {
+    // position in the quarantine, increases with every rejected block
+    pub sequence: u64,
+    pub block_hash: B256,
+    pub block_number: u64,
+    pub slot: u64,
+    // as claimed by the sender, unverified when the signature check failed
+    pub proposer: Address,
+    pub reason: String,
+    pub from_peer: String,
+    pub rejected_at_ms: u64,
+    pub block: Block,
+}
+
+impl RejectedBlock {
+    // whether the block claims a number in `from..=to`, open ended without a bound
+    pub fn in_range(&self, from_block: Option<u64>, to_block: Option<u64>) -> bool {
+        from_block.is_none_or(|from| self.block_number >= from)
+            && to_block.is_none_or(|to| self.block_number <= to)
+    }
+}
@@ -4,10 +4,187 @@ use std::{
     time::{SystemTime, UNIX_EPOCH},
 };
 // evm compatible fields
-use alloy::primitives::{Address, B256, U256, keccak256};
+use alloy::primitives::{Address, B256, Bytes, U256, keccak256};
 use alloy_signer::Signature;
 
-use crate::crypto::SignatureError;
+use crate::crypto::{KeyPair, SignatureError};
+use crate::{CHAIN_ID, contract_address};
+
+// r + s + v
+pub const SIGNATURE_LENGTH: usize = 65;
+
+// longest memo a transaction may carry, enough for an exchange deposit reference
+pub const MAX_MEMO_LENGTH: usize = 64;
+
+// marks the memo in the signing payload, never a kind tag and never valid utf-8,
+// so a memo can't be mistaken for the multiaddr of a registration
+const MEMO_TAG: u8 = 0xff;
+// marks the priority fee in the signing payload, after the memo
+const PRIORITY_FEE_TAG: u8 = 0xfe;
+// marks the calldata in the signing payload, after the priority fee
+const DATA_TAG: u8 = 0xfd;
+// marks the chain id in the signing payload, after the calldata
+const CHAIN_ID_TAG: u8 = 0xfc;
+
+// what a transaction does when executed, plain transfer by default
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub enum TransactionKind {
+    #[default]
+    Transfer,
+    // validator announces (or rotates) its p2p multiaddr, `to` and `amount` are unused
+    RegisterNetworkAddress {
+        multiaddr: String,
+    },
+    // sender allows `spender` to move up to `allowance` of its tokens, `amount` is unused
+    Approve {
+        spender: Address,
+        allowance: U256,
+    },
+    // sender moves `amount` from `owner` to `to`, spending the owner's allowance
+    TransferFrom {
+        owner: Address,
+    },
+    // validator locks `amount` as extra stake, effective from the next epoch
+    StakeTopUp,
+    // validator asks for `amount` of its stake back at the end of the epoch, `amount` is unused
+    StakeWithdraw {
+        amount: U256,
+    },
+    // sender pays every output at once, all or nothing, `to` and `amount` are unused
+    BatchTransfer {
+        outputs: Vec<TransferOutput>,
+    },
+    // sender claims an unregistered name, which then resolves to it, `to` and `amount` are unused
+    RegisterName {
+        name: String,
+    },
+    // owner hands a name over to `to`, `amount` is unused
+    TransferName {
+        name: String,
+    },
+    // run the precompile at `to` with `input`, the output goes into the receipt, `amount` is unused
+    Call {
+        input: Bytes,
+    },
+    // sender lets `session_key` sign transfers and calls for it until `expiry_slot`, spending
+    // at most `max_value` in value and fees, a `max_value` of zero revokes the key
+    AuthorizeSessionKey {
+        session_key: Address,
+        max_value: U256,
+        expiry_slot: u64,
+    },
+    // sender locks `amount` as stake and joins the validator set when the epoch ends,
+    // needs the minimum stake, a registered validator tops up instead
+    RegisterValidator,
+    // validator leaves the set, it stays active for the exit delay and then gets its whole
+    // stake back, `to` and `amount` are unused
+    Unstake,
+    // deploy `code` at `contract_address(from, nonce)` with `amount` as its balance,
+    // `to` is unused, needs a chain spec with a contract runtime
+    DeployContract {
+        code: Bytes,
+    },
+    // run the contract at `to` with `input`, sending it `amount` first
+    CallContract {
+        input: Bytes,
+    },
+}
+
+// one recipient of a batch transfer
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct TransferOutput {
+    pub to: Address,
+    pub amount: U256,
+}
+
+impl TransactionKind {
+    // kind specific bytes included in the transaction hash
+    // transfers add nothing, so existing transaction hashes stay the same
+    // raw bytes are length prefixed so they can't absorb the tagged fields after them,
+    // strings end at the first tag since 0xfc..=0xff never occur in utf-8
+    pub fn encode(&self) -> Vec<u8> {
+        match self {
+            TransactionKind::Transfer => Vec::new(),
+            TransactionKind::RegisterNetworkAddress { multiaddr } => {
+                let mut data = vec![1u8];
+                data.extend_from_slice(multiaddr.as_bytes());
+                data
+            }
+            TransactionKind::Approve { spender, allowance } => {
+                let mut data = vec![2u8];
+                data.extend_from_slice(spender.as_slice());
+                data.extend_from_slice(&allowance.to_be_bytes::<32>());
+                data
+            }
+            TransactionKind::TransferFrom { owner } => {
+                let mut data = vec![3u8];
+                data.extend_from_slice(owner.as_slice());
+                data
+            }
+            TransactionKind::StakeTopUp => vec![4u8],
+            TransactionKind::StakeWithdraw { amount } => {
+                let mut data = vec![5u8];
+                data.extend_from_slice(&amount.to_be_bytes::<32>());
+                data
+            }
+            TransactionKind::BatchTransfer { outputs } => {
+                let mut data = vec![6u8];
+                data.extend_from_slice(&(outputs.len() as u32).to_be_bytes());
+                for output in outputs {
+                    data.extend_from_slice(output.to.as_slice());
+                    data.extend_from_slice(&output.amount.to_be_bytes::<32>());
+                }
+                data
+            }
+            TransactionKind::RegisterName { name } => {
+                let mut data = vec![7u8];
+                data.extend_from_slice(name.as_bytes());
+                data
+            }
+            TransactionKind::TransferName { name } => {
+                let mut data = vec![8u8];
+                data.extend_from_slice(name.as_bytes());
+                data
+            }
+            TransactionKind::Call { input } => {
+                let mut data = vec![9u8];
+                data.extend_from_slice(&(input.len() as u32).to_be_bytes());
+                data.extend_from_slice(input);
+                data
+            }
+            TransactionKind::AuthorizeSessionKey {
+                session_key,
+                max_value,
+                expiry_slot,
+            } => {
+                let mut data = vec![10u8];
+                data.extend_from_slice(session_key.as_slice());
+                data.extend_from_slice(&max_value.to_be_bytes::<32>());
+                data.extend_from_slice(&expiry_slot.to_be_bytes());
+                data
+            }
+            TransactionKind::RegisterValidator => vec![11u8],
+            TransactionKind::Unstake => vec![12u8],
+            TransactionKind::DeployContract { code } => {
+                let mut data = vec![13u8];
+                data.extend_from_slice(&(code.len() as u32).to_be_bytes());
+                data.extend_from_slice(code);
+                data
+            }
+            TransactionKind::CallContract { input } => {
+                let mut data = vec![14u8];
+                data.extend_from_slice(&(input.len() as u32).to_be_bytes());
+                data.extend_from_slice(input);
+                data
+            }
+        }
+    }
+}
+
+// transactions stored before they carried a chain id were all signed for the default chain
+fn default_chain_id() -> u64 {
+    CHAIN_ID
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Transaction {
@@ -16,10 +193,26 @@ pub struct Transaction {
     pub amount: U256,   // Amount to transfer
     pub timestamp: u64, // When transaction was created
     pub nonce: u64,     // Nonce for transaction uniqueness
+    // chain the transaction is signed for, other chains refuse it
+    #[serde(default = "default_chain_id")]
+    pub chain_id: u64,
+    #[serde(default)]
+    pub kind: TransactionKind, // Transfer or special transaction
+    // free-form reference for the recipient, e.g. a deposit id, not interpreted by the chain
+    #[serde(default)]
+    pub memo: Bytes,
+    // calldata, not interpreted by the chain, paid for per byte like the rest of the encoding
+    #[serde(default)]
+    pub data: Bytes,
 
     // GAS FIELDS
     pub gas_limit: U256,
+    // the max fee per gas on chains with a base fee
+    #[serde(alias = "max_fee_per_gas")]
     pub gas_price: U256,
+    // tip above the base fee, capped by the max fee, everything above the base fee without one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_priority_fee_per_gas: Option<U256>,
 
     // Signature
     pub signature: Signature,
@@ -51,8 +244,13 @@ impl Transaction {
             amount: U256::from(amount),
             gas_limit: U256::from(gas_limit),
             gas_price: U256::from(gas_price),
+            max_priority_fee_per_gas: None,
             timestamp,
             nonce: 0, // Default nonce
+            chain_id: CHAIN_ID,
+            kind: TransactionKind::Transfer,
+            memo: Bytes::new(),
+            data: Bytes::new(),
             signature,
             hash,
         };
@@ -71,7 +269,7 @@ impl Transaction {
         let recovered_address = self
             .signature
             .recover_address_from_prehash(&calculated_hash)
-            .unwrap();
+            .map_err(|_| SignatureError::InvalidSignature)?;
 
         Ok(recovered_address)
     }
@@ -86,6 +284,12 @@ impl Transaction {
 
     // calculate transaction hash, excluding Signature
     pub fn calculate_hash(&self) -> B256 {
+        // we don't include signature here because of circular dependency
+        keccak256(self.signing_payload())
+    }
+
+    // canonical bytes of the signed fields
+    fn signing_payload(&self) -> Vec<u8> {
         let mut data = Vec::new();
 
         data.extend_from_slice(self.from.as_slice());
@@ -95,13 +299,109 @@ impl Transaction {
         data.extend_from_slice(&self.gas_price.to_be_bytes::<32>());
         data.extend_from_slice(&self.timestamp.to_be_bytes());
         data.extend_from_slice(&self.nonce.to_be_bytes());
+        data.extend_from_slice(&self.kind.encode());
+        // only present with a memo, so transactions without one keep their hash
+        if !self.memo.is_empty() {
+            data.push(MEMO_TAG);
+            data.extend_from_slice(&(self.memo.len() as u32).to_be_bytes());
+            data.extend_from_slice(&self.memo);
+        }
+        // only present with a priority fee, same as the memo
+        if let Some(priority_fee) = self.max_priority_fee_per_gas {
+            data.push(PRIORITY_FEE_TAG);
+            data.extend_from_slice(&priority_fee.to_be_bytes::<32>());
+        }
+        // only present with calldata, same as the memo
+        if !self.data.is_empty() {
+            data.push(DATA_TAG);
+            data.extend_from_slice(&(self.data.len() as u32).to_be_bytes());
+            data.extend_from_slice(&self.data);
+        }
+        // only present off the default chain, so its transactions keep their hash, moving a
+        // transaction to another chain changes its hash either way
+        if self.chain_id != CHAIN_ID {
+            data.push(CHAIN_ID_TAG);
+            data.extend_from_slice(&self.chain_id.to_be_bytes());
+        }
+        data
+    }
 
-        // we don't include signature here because of circular dependency
-        keccak256(data)
+    // memos longer than `MAX_MEMO_LENGTH` are rejected
+    pub fn has_valid_memo(&self) -> bool {
+        self.memo.len() <= MAX_MEMO_LENGTH
+    }
+
+    // size of the canonical encoding, signed fields plus the 65 byte signature
+    // transaction fees and the max transaction size are based on it
+    pub fn encoded_size(&self) -> usize {
+        self.signing_payload().len() + SIGNATURE_LENGTH
+    }
+
+    // calculate hash and sign it with the sender's keypair
+    pub async fn sign(&mut self, keypair: &KeyPair) -> Result<(), SignatureError> {
+        let tx_hash = self.calculate_hash();
+        self.signature = keypair.sign_hash(&tx_hash).await?;
+        self.hash = tx_hash;
+
+        Ok(())
+    }
+
+    // encode signed transaction for raw submission, hex encoded json
+    pub fn encode_raw(&self) -> String {
+        let bytes = serde_json::to_vec(self).expect("Transaction is always serializable");
+        format!("0x{}", hex::encode(bytes))
+    }
+
+    // decode a raw transaction submitted via RPC
+    pub fn decode_raw(raw_tx: &str) -> anyhow::Result<Self> {
+        let bytes = hex::decode(raw_tx.trim_start_matches("0x"))?;
+        let tx = serde_json::from_slice(&bytes)?;
+        Ok(tx)
     }
 
     // Helper methods for gas calculations
+    // an overflowing fee is saturated like the value, no balance can cover it
     pub fn max_transaction_cost(&self) -> U256 {
-        self.amount + (self.gas_limit * self.gas_price)
+        self.value_from_sender()
+            .saturating_add(self.gas_limit.saturating_mul(self.gas_price))
+    }
+
+    // value debited from the sender, transfer_from moves the owner's tokens instead
+    // an overflowing batch total is saturated, no balance can cover it
+    pub fn value_from_sender(&self) -> U256 {
+        match &self.kind {
+            TransactionKind::TransferFrom { .. } => U256::ZERO,
+            TransactionKind::BatchTransfer { outputs } => {
+                outputs.iter().fold(U256::ZERO, |total, output| {
+                    total.saturating_add(output.amount)
+                })
+            }
+            _ => self.amount,
+        }
+    }
+
+    // addresses a transaction involves, for address history and block blooms
+    pub fn indexed_addresses(&self) -> Vec<Address> {
+        let mut addresses = vec![self.from];
+        if self.to != Address::ZERO {
+            addresses.push(self.to);
+        }
+        match &self.kind {
+            TransactionKind::Approve { spender, .. } => addresses.push(*spender),
+            TransactionKind::TransferFrom { owner } => addresses.push(*owner),
+            TransactionKind::AuthorizeSessionKey { session_key, .. } => {
+                addresses.push(*session_key)
+            }
+            TransactionKind::BatchTransfer { outputs } => {
+                addresses.extend(outputs.iter().map(|output| output.to));
+            }
+            TransactionKind::DeployContract { .. } => {
+                addresses.push(contract_address(&self.from, self.nonce))
+            }
+            _ => {}
+        }
+        addresses.sort();
+        addresses.dedup();
+        addresses
     }
 }
@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use std::time::Duration;
+use tokio::sync::{Mutex, Notify};
+use tokio::time::Instant;
+
+use crate::WatchdogConfig;
+use crate::consensus::SlotClock;
+
+// what the service loop is working on, updated by the loop and read by the watchdog
+#[derive(Debug, Default)]
+pub struct LoopHeartbeat {
+    activity: StdMutex<LoopActivity>,
+    restart: Notify,
+}
+
+#[derive(Debug, Clone, Default)]
+struct LoopActivity {
+    // step in progress and when it started, none while waiting for work
+    current: Option<(&'static str, Instant)>,
+    queued_messages: usize,
+}
+
+impl LoopHeartbeat {
+    pub fn enter(&self, activity: &'static str, queued_messages: usize) {
+        let mut state = self.activity.lock().unwrap();
+        state.current = Some((activity, Instant::now()));
+        state.queued_messages = queued_messages;
+    }
+
+    pub fn leave(&self) {
+        self.activity.lock().unwrap().current = None;
+    }
+
+    // step in progress and how long it has been running
+    pub fn busy_for(&self, now: Instant) -> Option<(&'static str, Duration)> {
+        let state = self.activity.lock().unwrap();
+        state
+            .current
+            .map(|(activity, since)| (activity, now.saturating_duration_since(since)))
+    }
+
+    pub fn queued_messages(&self) -> usize {
+        self.activity.lock().unwrap().queued_messages
+    }
+
+    // abandon the step in progress, only a step currently waiting is woken
+    pub fn request_restart(&self) {
+        self.restart.notify_waiters();
+    }
+
+    pub async fn restart_requested(&self) {
+        self.restart.notified().await
+    }
+}
+
+// checks whether a shared lock is free without waiting for it
+struct LockProbe {
+    name: &'static str,
+    is_held: Box<dyn Fn() -> bool + Send + Sync>,
+    // first probe that found it held, cleared once it is seen free
+    held_since: Option<Instant>,
+}
+
+// one stalled loop step or lock, dumped once per stall
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StallReport {
+    // `service loop` or the name of the lock
+    pub subject: String,
+    // loop step in progress when the stall was detected
+    pub activity: Option<String>,
+    pub stalled_ms: u64,
+    pub current_slot: Option<u64>,
+    pub queued_messages: usize,
+    // every watched lock that was held at the time, a deadlock shows up as several
+    pub held_locks: Vec<String>,
+    pub alive_tasks: usize,
+    pub runtime_queue_depth: usize,
+}
+
+// monitors the blockchain service loop and the shared mutexes for stalls
+pub struct Watchdog {
+    config: WatchdogConfig,
+    heartbeat: Arc<LoopHeartbeat>,
+    locks: Vec<LockProbe>,
+    // read once so a held consensus lock can't hide the slot
+    slot_clock: Option<SlotClock>,
+    // subjects already reported for the current stall
+    reported: Vec<String>,
+}
+
+impl Watchdog {
+    pub fn new(config: WatchdogConfig, heartbeat: Arc<LoopHeartbeat>) -> Self {
+        Self {
+            config,
+            heartbeat,
+            locks: Vec::new(),
+            slot_clock: None,
+            reported: Vec::new(),
+        }
+    }
+
+    pub fn watch_lock<T: Send + 'static>(
+        mut self,
+        name: &'static str,
+        lock: Arc<Mutex<T>>,
+    ) -> Self {
+        self.locks.push(LockProbe {
+            name,
+            is_held: Box::new(move || lock.try_lock().is_err()),
+            held_since: None,
+        });
+        self
+    }
+
+    pub fn with_slot_clock(mut self, slot_clock: SlotClock) -> Self {
+        self.slot_clock = Some(slot_clock);
+        self
+    }
+
+    // probe the loop and the locks once, returns the stalls not reported yet
+    pub fn check(&mut self, now: Instant) -> Vec<StallReport> {
+        let threshold = Duration::from_millis(self.config.stall_threshold_ms);
+
+        let mut held_locks = Vec::new();
+        let mut stalled = Vec::new();
+        for probe in &mut self.locks {
+            if (probe.is_held)() {
+                let since = *probe.held_since.get_or_insert(now);
+                held_locks.push(probe.name.to_string());
+                let held_for = now.saturating_duration_since(since);
+                if held_for >= threshold {
+                    stalled.push((format!("{} lock", probe.name), None, held_for));
+                }
+            } else {
+                probe.held_since = None;
+            }
+        }
+        if let Some((activity, busy_for)) = self.heartbeat.busy_for(now)
+            && busy_for >= threshold
+        {
+            stalled.push(("service loop".to_string(), Some(activity), busy_for));
+        }
+
+        // a subject that recovered is reported again the next time it stalls
+        self.reported
+            .retain(|subject| stalled.iter().any(|(s, _, _)| s == subject));
+
+        let metrics = tokio::runtime::Handle::try_current().map(|handle| handle.metrics());
+        let mut reports = Vec::new();
+        for (subject, activity, stalled_for) in stalled {
+            if self.reported.contains(&subject) {
+                continue;
+            }
+            self.reported.push(subject.clone());
+            reports.push(StallReport {
+                subject,
+                activity: activity.map(str::to_string),
+                stalled_ms: stalled_for.as_millis() as u64,
+                current_slot: self.current_slot(),
+                queued_messages: self.heartbeat.queued_messages(),
+                held_locks: held_locks.clone(),
+                alive_tasks: metrics.as_ref().map_or(0, |m| m.num_alive_tasks()),
+                runtime_queue_depth: metrics.as_ref().map_or(0, |m| m.global_queue_depth()),
+            });
+        }
+        reports
+    }
+
+    fn current_slot(&self) -> Option<u64> {
+        self.slot_clock?.current_slot()
+    }
+
+    // probe every `check_interval_ms` until the node stops
+    pub async fn run(mut self) {
+        let mut interval =
+            tokio::time::interval(Duration::from_millis(self.config.check_interval_ms.max(1)));
+        loop {
+            interval.tick().await;
+            for report in self.check(Instant::now()) {
+                println!(
+                    "🐕 Stall detected: {} for {}ms{}, slot {:?}, {} queued messages, held locks {:?}, {} tasks alive, {} in the runtime queue",
+                    report.subject,
+                    report.stalled_ms,
+                    report
+                        .activity
+                        .as_ref()
+                        .map(|activity| format!(" in {}", activity))
+                        .unwrap_or_default(),
+                    report.current_slot,
+                    report.queued_messages,
+                    report.held_locks,
+                    report.alive_tasks,
+                    report.runtime_queue_depth,
+                );
+                if report.activity.is_some() && self.config.restart_stalled_loop {
+                    println!("♻️  Restarting the stalled service loop step");
+                    self.heartbeat.request_restart();
+                }
+            }
+        }
+    }
+}
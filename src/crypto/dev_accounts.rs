@@ -0,0 +1,50 @@
+use alloy::primitives::{Address, B256, U256};
+use alloy_signer_local::{MnemonicBuilder, coins_bip39::English};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::KeyPair;
+
+// well-known development mnemonic (same as hardhat / anvil), NEVER use it on a real network
+pub const DEV_MNEMONIC: &str = "test test test test test test test test test test test junk";
+pub const DEV_ACCOUNT_COUNT: u32 = 10;
+pub const DEV_ACCOUNT_BALANCE_ETH: u64 = 10_000;
+const WEI_PER_ETH: u64 = 1_000_000_000_000_000_000;
+
+// pre-funded account available in dev mode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DevAccount {
+    pub index: u32,
+    pub address: Address,
+    pub private_key: B256,
+    pub balance: U256,
+}
+
+// derive dev keypairs at m/44'/60'/0'/0/{index} from the dev mnemonic
+pub fn dev_keypairs(count: u32) -> Result<Vec<KeyPair>> {
+    (0..count)
+        .map(|index| {
+            let signer = MnemonicBuilder::<English>::default()
+                .phrase(DEV_MNEMONIC)
+                .index(index)?
+                .build()?;
+            Ok(KeyPair::from_signer(signer, format!("dev-{}", index)))
+        })
+        .collect()
+}
+
+// dev accounts with their keys and genesis balance
+pub fn dev_accounts() -> Result<Vec<DevAccount>> {
+    let balance = U256::from(DEV_ACCOUNT_BALANCE_ETH) * U256::from(WEI_PER_ETH);
+
+    Ok(dev_keypairs(DEV_ACCOUNT_COUNT)?
+        .into_iter()
+        .zip(0..)
+        .map(|(keypair, index)| DevAccount {
+            index,
+            address: keypair.address,
+            private_key: keypair.signer.to_bytes(),
+            balance,
+        })
+        .collect())
+}
@@ -30,6 +30,15 @@ impl KeyPair {
         }
     }
 
+    // wrap an existing signer, e.g. one derived from a mnemonic
+    pub fn from_signer(signer: PrivateKeySigner, name: String) -> Self {
+        Self {
+            address: signer.address(),
+            signer,
+            name: Some(name),
+        }
+    }
+
     // Sign a message using private key
     pub async fn sign_hash(&self, hash: &B256) -> Result<Signature, SignatureError> {
         // Use alloy_signer_local to sign the hash
@@ -1,5 +1,9 @@
+pub mod dev_accounts;
 pub mod error;
 pub mod keys;
+pub mod signature_cache;
 
+pub use dev_accounts::*;
 pub use error::SignatureError;
 pub use keys::*;
+pub use signature_cache::*;
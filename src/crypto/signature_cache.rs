@@ -0,0 +1,94 @@
+use alloy::primitives::{Address, B256};
+use alloy_signer::Signature;
+use lru::LruCache;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+
+use super::SignatureError;
+use crate::core::Transaction;
+
+// recovered senders kept, a few blocks worth of mempool traffic
+pub const SIGNATURE_CACHE_SIZE: usize = 8192;
+
+// hits and misses since start, to check the cache is doing its job
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SignatureCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+// recovered sender per verified transaction hash, shared by mempool admission and
+// block simulation so a transaction is recovered once on its way into a block
+#[derive(Debug)]
+pub struct SignatureCache {
+    inner: Mutex<CacheInner>,
+}
+
+#[derive(Debug)]
+struct CacheInner {
+    // the tx hash doesn't cover the signature, it is stored to be compared on a hit
+    senders: LruCache<B256, (Signature, Address)>,
+    stats: SignatureCacheStats,
+}
+
+impl SignatureCache {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            inner: Mutex::new(CacheInner {
+                senders: LruCache::new(capacity),
+                stats: SignatureCacheStats::default(),
+            }),
+        }
+    }
+
+    // sender recovered from the transaction signature, from the cache when seen before
+    pub fn recover_sender(&self, tx: &Transaction) -> Result<Address, SignatureError> {
+        // a body altered under a cached hash and signature must not hit
+        if tx.calculate_hash() != tx.hash {
+            return Err(SignatureError::HashMismatch);
+        }
+        {
+            let mut inner = self.inner.lock().unwrap();
+            if let Some((signature, sender)) = inner.senders.get(&tx.hash).copied()
+                && signature == tx.signature
+            {
+                inner.stats.hits += 1;
+                return Ok(sender);
+            }
+            inner.stats.misses += 1;
+        }
+
+        // recover outside the lock, this is the expensive part
+        let sender = tx.verify_signature()?;
+        self.inner
+            .lock()
+            .unwrap()
+            .senders
+            .put(tx.hash, (tx.signature, sender));
+        Ok(sender)
+    }
+
+    // signed by the account it claims to come from
+    pub fn is_signature_valid(&self, tx: &Transaction) -> bool {
+        matches!(self.recover_sender(tx), Ok(sender) if sender == tx.from)
+    }
+
+    pub fn stats(&self) -> SignatureCacheStats {
+        self.inner.lock().unwrap().stats
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().senders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for SignatureCache {
+    fn default() -> Self {
+        Self::new(SIGNATURE_CACHE_SIZE)
+    }
+}
@@ -0,0 +1,153 @@
+use alloy::primitives::{Address, B256, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::account::Account;
+use crate::core::{Block, Transaction};
+
+// transactions picked for our next block ahead of the slot, with their root,
+// kept current as transactions arrive so slot start only applies the delta
+#[derive(Debug, Clone)]
+pub struct BlockTemplate {
+    pub slot: u64,
+    // state the selection was checked against, a block committed since invalidates it
+    pub state_root: B256,
+    pub transactions: Vec<Transaction>,
+    pub transactions_root: B256,
+    max_transactions: usize,
+    // (next nonce, balance left) of each sender after its selected transactions
+    senders: HashMap<Address, (u64, U256)>,
+    added: usize,
+    replaced: usize,
+}
+
+// how a template changed since it was built, logged when it's proposed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BlockTemplateReport {
+    pub slot: u64,
+    pub transactions: usize,
+    // arrived after the template was built
+    pub added: usize,
+    // cheaper transactions pushed out of a full template by those
+    pub replaced: usize,
+}
+
+impl BlockTemplate {
+    pub fn new(slot: u64, state_root: B256, max_transactions: usize) -> Self {
+        Self {
+            slot,
+            state_root,
+            transactions: Vec::new(),
+            transactions_root: B256::ZERO,
+            max_transactions,
+            senders: HashMap::new(),
+            added: 0,
+            replaced: 0,
+        }
+    }
+
+    // for the proposal at `slot`, as long as no block changed the state
+    pub fn is_fresh(&self, slot: u64, state_root: B256) -> bool {
+        self.slot == slot && self.state_root == state_root
+    }
+
+    // selected on build, the transactions are valid in this order against `state_root`
+    pub fn fill(
+        &mut self,
+        transactions: Vec<Transaction>,
+        account_of: impl Fn(&Address) -> Account,
+    ) {
+        for tx in transactions.into_iter().take(self.max_transactions) {
+            let sender = account_of(&tx.from);
+            self.track(&tx, &sender);
+            self.transactions.push(tx);
+        }
+        self.transactions_root = Block::calculate_transactions_root(&self.transactions);
+    }
+
+    // take a transaction that arrived after the build, true if the template changed:
+    // it must be the sender's next nonce and affordable after what's already selected,
+    // a full template gives up its cheapest transaction that nothing else depends on
+    pub fn offer(&mut self, tx: &Transaction, sender: &Account) -> bool {
+        if self
+            .transactions
+            .iter()
+            .any(|selected| selected.hash == tx.hash)
+        {
+            return false;
+        }
+        let (nonce, balance) = self.sender_state(tx, sender);
+        if tx.nonce != nonce || balance < tx.max_transaction_cost() {
+            return false;
+        }
+
+        if self.transactions.len() >= self.max_transactions {
+            let Some(idx) = self.cheapest_last(tx) else {
+                return false;
+            };
+            let evicted = self.transactions.remove(idx);
+            let entry = self.senders.get_mut(&evicted.from).unwrap();
+            entry.0 -= 1;
+            entry.1 += evicted.max_transaction_cost();
+            self.replaced += 1;
+        }
+
+        self.track(tx, sender);
+        self.transactions.push(tx.clone());
+        self.transactions_root = Block::calculate_transactions_root(&self.transactions);
+        self.added += 1;
+        true
+    }
+
+    pub fn report(&self) -> BlockTemplateReport {
+        BlockTemplateReport {
+            slot: self.slot,
+            transactions: self.transactions.len(),
+            added: self.added,
+            replaced: self.replaced,
+        }
+    }
+
+    fn sender_state(&self, tx: &Transaction, sender: &Account) -> (u64, U256) {
+        self.senders
+            .get(&tx.from)
+            .copied()
+            .unwrap_or((sender.nonce, sender.balance))
+    }
+
+    fn track(&mut self, tx: &Transaction, sender: &Account) {
+        let (nonce, balance) = self.sender_state(tx, sender);
+        self.senders.insert(
+            tx.from,
+            (nonce + 1, balance.saturating_sub(tx.max_transaction_cost())),
+        );
+    }
+
+    // cheapest transaction paying less than `tx` that is the last of its sender,
+    // never one of tx's sender, the new transaction follows it
+    fn cheapest_last(&self, tx: &Transaction) -> Option<usize> {
+        self.transactions
+            .iter()
+            .enumerate()
+            .filter(|(_, selected)| {
+                selected.from != tx.from
+                    && selected.gas_price < tx.gas_price
+                    && self.senders[&selected.from].0 == selected.nonce + 1
+            })
+            .min_by_key(|(_, selected)| selected.gas_price)
+            .map(|(idx, _)| idx)
+    }
+}
+
+// how many leading transactions fit in `gas_target` by their gas limits, at least one
+// so a single large transaction is never stuck
+pub fn gas_target_fit(transactions: &[Transaction], gas_target: U256) -> usize {
+    let mut gas = U256::ZERO;
+    transactions
+        .iter()
+        .position(|tx| {
+            gas = gas.saturating_add(tx.gas_limit);
+            gas > gas_target
+        })
+        .map_or(transactions.len(), |idx| idx.max(1))
+}
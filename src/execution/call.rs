@@ -0,0 +1,13 @@
+use alloy::primitives::{Bytes, U256};
+use serde::{Deserialize, Serialize};
+
+// what a transaction would do if it were included now, nothing it did is kept
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CallResult {
+    pub success: bool,
+    pub output: Bytes,
+    // what it would be charged for, a failed one included
+    pub gas_used: U256,
+    // why it failed, none on success
+    pub error: Option<String>,
+}
@@ -0,0 +1,5 @@
+pub mod runtime;
+pub mod wasm;
+
+pub use runtime::*;
+pub use wasm::*;
@@ -0,0 +1,83 @@
+use alloy::primitives::{Address, B256, Bytes, U256, keccak256};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use super::wasm::WasmRuntime;
+
+// contract engine a chain runs, set in the chain spec, every node of the chain must agree
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContractRuntimeKind {
+    // contract transactions fail, like on chains from before contracts
+    #[default]
+    None,
+    Wasm,
+}
+
+impl ContractRuntimeKind {
+    pub fn runtime(&self) -> Option<Arc<dyn ContractRuntime>> {
+        match self {
+            ContractRuntimeKind::None => None,
+            ContractRuntimeKind::Wasm => Some(Arc::new(WasmRuntime::new())),
+        }
+    }
+}
+
+// what a contract call starts from, nothing reaches the state unless the call succeeds
+#[derive(Debug, Clone)]
+pub struct ContractCall<'a> {
+    pub contract: Address,
+    pub caller: Address,
+    pub input: &'a [u8],
+    pub code: &'a [u8],
+    // the contract's balance, including the value sent with the call
+    pub balance: U256,
+    pub storage: BTreeMap<B256, Bytes>,
+    // gas left after the intrinsic gas, one unit of fuel per unit of gas
+    pub fuel: u64,
+}
+
+// a successful call: what it returned, the contract's storage after it and what it paid out
+#[derive(Debug, Clone, Default)]
+pub struct ContractOutcome {
+    pub output: Bytes,
+    pub storage: BTreeMap<B256, Bytes>,
+    pub transfers: Vec<(Address, U256)>,
+    pub fuel_used: u64,
+}
+
+// a failed call: why, and the fuel it burned before failing, which is still paid for
+#[derive(Debug, Clone)]
+pub struct ContractFailure {
+    pub reason: String,
+    pub fuel_used: u64,
+}
+
+// failures before the contract ran burn no fuel
+impl From<String> for ContractFailure {
+    fn from(reason: String) -> Self {
+        Self {
+            reason,
+            fuel_used: 0,
+        }
+    }
+}
+
+// contract engine built into the node, picked by `ContractRuntimeKind`
+pub trait ContractRuntime: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    // checked on deploy, code that fails here never reaches the state
+    fn validate(&self, code: &[u8]) -> Result<(), String>;
+
+    // run the contract's entry point, an error fails the transaction
+    fn call(&self, call: ContractCall) -> Result<ContractOutcome, ContractFailure>;
+}
+
+// address a contract deployed by `deployer` with its `nonce`-th transaction lives at
+pub fn contract_address(deployer: &Address, nonce: u64) -> Address {
+    let mut data = deployer.to_vec();
+    data.extend_from_slice(&nonce.to_be_bytes());
+    Address::from_slice(&keccak256(data)[12..])
+}
@@ -0,0 +1,298 @@
+use alloy::primitives::{Address, B256, Bytes, U256, keccak256};
+use lru::LruCache;
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use wasmtime::{
+    Caller, Config, Engine, ExternType, Linker, Memory, Module, Store, StoreLimits,
+    StoreLimitsBuilder, bail, format_err,
+};
+
+use super::runtime::{ContractCall, ContractFailure, ContractOutcome, ContractRuntime};
+
+// a contract's linear memory can't grow past this, memory.grow returns -1 beyond it
+pub const MAX_CONTRACT_MEMORY: usize = 4 * 1024 * 1024;
+// longest value a contract may keep under one storage key
+pub const MAX_STORAGE_VALUE_LEN: usize = 4096;
+// longest output a call may return into its receipt
+pub const MAX_CONTRACT_OUTPUT_LEN: usize = 4096;
+// fuel per byte written to contract storage, on top of the instructions that wrote it
+pub const STORAGE_BYTE_FUEL: u64 = 100;
+// compiled modules kept around, by code hash
+const MODULE_CACHE_SIZE: usize = 64;
+
+// what the host functions of one call read and write
+struct Host {
+    contract: Address,
+    caller: Address,
+    input: Vec<u8>,
+    balance: U256,
+    storage: BTreeMap<B256, Bytes>,
+    transfers: Vec<(Address, U256)>,
+    output: Vec<u8>,
+    limits: StoreLimits,
+}
+
+// wasm contracts run with wasmtime, metered with fuel
+// a contract exports its `memory` and `call: () -> i32`, a non-zero result reverts the call
+// host functions, imported from "env", pointers into the contract's memory:
+//   input_size() -> i32, input_read(ptr), caller(ptr), address(ptr), balance(ptr),
+//   transfer(to_ptr, amount_ptr) -> i32, storage_read(key_ptr, value_ptr, cap) -> i32,
+//   storage_write(key_ptr, value_ptr, len), output(ptr, len)
+// addresses are 20 bytes, amounts 32 bytes big endian and storage keys 32 bytes
+pub struct WasmRuntime {
+    engine: Engine,
+    linker: Linker<Host>,
+    modules: Mutex<LruCache<B256, Module>>,
+}
+
+impl Default for WasmRuntime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WasmRuntime {
+    pub fn new() -> Self {
+        let mut config = Config::new();
+        // results must not depend on the machine a node runs on
+        // relaxed simd results are left to the host cpu, so contracts can't use it
+        config
+            .consume_fuel(true)
+            .cranelift_nan_canonicalization(true)
+            .wasm_relaxed_simd(false);
+        let engine = Engine::new(&config).expect("wasm engine config is valid");
+        let mut linker = Linker::new(&engine);
+        define_host_functions(&mut linker).expect("host functions are defined once");
+
+        Self {
+            engine,
+            linker,
+            modules: Mutex::new(LruCache::new(NonZeroUsize::new(MODULE_CACHE_SIZE).unwrap())),
+        }
+    }
+
+    // compiled once per code hash, the cache is shared by every call
+    fn module(&self, code: &[u8]) -> Result<Module, String> {
+        let hash = keccak256(code);
+        if let Some(module) = self.modules.lock().unwrap().get(&hash) {
+            return Ok(module.clone());
+        }
+        let module =
+            Module::new(&self.engine, code).map_err(|e| format!("invalid wasm module: {}", e))?;
+        self.modules.lock().unwrap().put(hash, module.clone());
+        Ok(module)
+    }
+}
+
+impl ContractRuntime for WasmRuntime {
+    fn name(&self) -> &'static str {
+        "wasm"
+    }
+
+    fn validate(&self, code: &[u8]) -> Result<(), String> {
+        let module = self.module(code)?;
+        match module.get_export("call") {
+            Some(ExternType::Func(ty))
+                if ty.params().len() == 0
+                    && ty.results().len() == 1
+                    && ty.results().all(|result| result.is_i32()) => {}
+            _ => return Err("contract must export `call: () -> i32`".to_string()),
+        }
+        if !matches!(module.get_export("memory"), Some(ExternType::Memory(_))) {
+            return Err("contract must export its `memory`".to_string());
+        }
+        // imports outside the host interface would only fail once called
+        self.linker
+            .instantiate_pre(&module)
+            .map(|_| ())
+            .map_err(|e| format!("contract imports: {}", e))
+    }
+
+    fn call(&self, call: ContractCall) -> Result<ContractOutcome, ContractFailure> {
+        let module = self.module(call.code)?;
+        let host = Host {
+            contract: call.contract,
+            caller: call.caller,
+            input: call.input.to_vec(),
+            balance: call.balance,
+            storage: call.storage,
+            transfers: Vec::new(),
+            output: Vec::new(),
+            limits: StoreLimitsBuilder::new()
+                .memory_size(MAX_CONTRACT_MEMORY)
+                .build(),
+        };
+        let mut store = Store::new(&self.engine, host);
+        store.limiter(|host| &mut host.limits);
+        store.set_fuel(call.fuel).map_err(|e| e.to_string())?;
+
+        // a failed call still burned what it ran, running out burns all of it
+        let failed = |store: &Store<Host>, reason: String| ContractFailure {
+            reason,
+            fuel_used: call.fuel - store.get_fuel().unwrap_or(0),
+        };
+        let instance = self
+            .linker
+            .instantiate(&mut store, &module)
+            .map_err(|e| failed(&store, format!("contract failed to start: {}", e)))?;
+        let entry = instance
+            .get_typed_func::<(), i32>(&mut store, "call")
+            .map_err(|e| e.to_string())?;
+        let status = entry
+            .call(&mut store, ())
+            .map_err(|e| failed(&store, format!("contract trapped: {}", e)))?;
+        if status != 0 {
+            return Err(failed(
+                &store,
+                format!("contract reverted with status {}", status),
+            ));
+        }
+
+        let fuel_used = call.fuel - store.get_fuel().map_err(|e| e.to_string())?;
+        let host = store.into_data();
+        Ok(ContractOutcome {
+            output: host.output.into(),
+            storage: host.storage,
+            transfers: host.transfers,
+            fuel_used,
+        })
+    }
+}
+
+fn memory(caller: &mut Caller<'_, Host>) -> wasmtime::Result<Memory> {
+    caller
+        .get_export("memory")
+        .and_then(|export| export.into_memory())
+        .ok_or_else(|| format_err!("contract exports no memory"))
+}
+
+fn read(caller: &mut Caller<'_, Host>, ptr: i32, len: usize) -> wasmtime::Result<Vec<u8>> {
+    let memory = memory(caller)?;
+    let mut data = vec![0u8; len];
+    memory.read(&*caller, ptr as u32 as usize, &mut data)?;
+    Ok(data)
+}
+
+fn write(caller: &mut Caller<'_, Host>, ptr: i32, data: &[u8]) -> wasmtime::Result<()> {
+    let memory = memory(caller)?;
+    memory.write(&mut *caller, ptr as u32 as usize, data)?;
+    Ok(())
+}
+
+// a length the contract passed in, never more than its memory can hold
+fn length(len: i32) -> wasmtime::Result<usize> {
+    let len = len as u32 as usize;
+    if len > MAX_CONTRACT_MEMORY {
+        bail!("length {} is larger than contract memory", len);
+    }
+    Ok(len)
+}
+
+fn define_host_functions(linker: &mut Linker<Host>) -> wasmtime::Result<()> {
+    linker.func_wrap("env", "input_size", |caller: Caller<'_, Host>| {
+        caller.data().input.len() as i32
+    })?;
+    linker.func_wrap(
+        "env",
+        "input_read",
+        |mut caller: Caller<'_, Host>, ptr: i32| {
+            let input = caller.data().input.clone();
+            write(&mut caller, ptr, &input)
+        },
+    )?;
+    linker.func_wrap("env", "caller", |mut caller: Caller<'_, Host>, ptr: i32| {
+        let address = caller.data().caller;
+        write(&mut caller, ptr, address.as_slice())
+    })?;
+    linker.func_wrap(
+        "env",
+        "address",
+        |mut caller: Caller<'_, Host>, ptr: i32| {
+            let address = caller.data().contract;
+            write(&mut caller, ptr, address.as_slice())
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "balance",
+        |mut caller: Caller<'_, Host>, ptr: i32| {
+            let balance = caller.data().balance;
+            write(&mut caller, ptr, &balance.to_be_bytes::<32>())
+        },
+    )?;
+    // 0 when paid, 1 when the contract's balance doesn't cover the amount
+    linker.func_wrap(
+        "env",
+        "transfer",
+        |mut caller: Caller<'_, Host>, to_ptr: i32, amount_ptr: i32| {
+            let to = Address::from_slice(&read(&mut caller, to_ptr, 20)?);
+            let amount = U256::from_be_slice(&read(&mut caller, amount_ptr, 32)?);
+            let host = caller.data_mut();
+            let Some(balance) = host.balance.checked_sub(amount) else {
+                return Ok(1);
+            };
+            host.balance = balance;
+            host.transfers.push((to, amount));
+            Ok::<_, wasmtime::Error>(0)
+        },
+    )?;
+    // length of the value, -1 when the key is unset, copies at most `cap` bytes
+    linker.func_wrap(
+        "env",
+        "storage_read",
+        |mut caller: Caller<'_, Host>, key_ptr: i32, value_ptr: i32, cap: i32| {
+            let key = B256::from_slice(&read(&mut caller, key_ptr, 32)?);
+            let Some(value) = caller.data().storage.get(&key).cloned() else {
+                return Ok(-1);
+            };
+            let copied = value.len().min(length(cap)?);
+            write(&mut caller, value_ptr, &value[..copied])?;
+            Ok::<_, wasmtime::Error>(value.len() as i32)
+        },
+    )?;
+    // an empty value clears the key
+    linker.func_wrap(
+        "env",
+        "storage_write",
+        |mut caller: Caller<'_, Host>, key_ptr: i32, value_ptr: i32, len: i32| {
+            let len = length(len)?;
+            if len > MAX_STORAGE_VALUE_LEN {
+                bail!(
+                    "storage value of {} bytes, max {}",
+                    len,
+                    MAX_STORAGE_VALUE_LEN
+                );
+            }
+            let fuel = caller.get_fuel()?;
+            let cost = STORAGE_BYTE_FUEL * (32 + len as u64);
+            caller.set_fuel(
+                fuel.checked_sub(cost)
+                    .ok_or_else(|| format_err!("out of gas writing storage"))?,
+            )?;
+            let key = B256::from_slice(&read(&mut caller, key_ptr, 32)?);
+            let value = read(&mut caller, value_ptr, len)?;
+            let storage = &mut caller.data_mut().storage;
+            if value.is_empty() {
+                storage.remove(&key);
+            } else {
+                storage.insert(key, value.into());
+            }
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "output",
+        |mut caller: Caller<'_, Host>, ptr: i32, len: i32| {
+            let len = length(len)?;
+            if len > MAX_CONTRACT_OUTPUT_LEN {
+                bail!("output of {} bytes, max {}", len, MAX_CONTRACT_OUTPUT_LEN);
+            }
+            let output = read(&mut caller, ptr, len)?;
+            caller.data_mut().output = output;
+            Ok(())
+        },
+    )?;
+    Ok(())
+}
@@ -1,16 +1,42 @@
 use core::fmt;
 
-use alloy::primitives::U256;
+use alloy::primitives::{Address, U256};
 
 #[derive(Debug, Clone)]
 pub enum StateTransitionError {
     InsufficientBalance { has: U256, needs: U256 },
+    // a debit the max cost check should have covered, the transaction is invalid
+    DebitExceedsBalance { has: U256, needs: U256 },
     InvalidNonce { expected: u64, got: u64 },
+    // signed for another chain, its signature must not be reusable here
+    WrongChain { expected: u64, got: u64 },
     GasPriceTooLow,
+    MaxFeeBelowBaseFee { max_fee: U256, base_fee: U256 },
+    PriorityFeeAboveMaxFee { priority_fee: U256, max_fee: U256 },
     BalanceOverflow,
     SameAddress,
     InvalidGasLimit,
+    // gas limit times gas price does not fit in a U256
+    FeeOverflow,
     InsufficientGas { provided: U256, required: U256 },
+    InvalidPayload(String),
+    InsufficientAllowance { allowance: U256, needs: U256 },
+    TransactionTooLarge { size: usize, max: usize },
+    MemoTooLong { length: usize, max: usize },
+    NameTaken(String),
+    NotNameOwner(String),
+    NoPrecompile(Address),
+    PrecompileFailed { name: String, reason: String },
+    SessionKeyRejected { key: Address, reason: String },
+    InvalidSignature,
+    ContractsDisabled,
+    NoContract(Address),
+    // the gas it burned, intrinsic plus fuel, is still paid for
+    ContractFailed {
+        contract: Address,
+        reason: String,
+        gas_used: U256,
+    },
 }
 
 impl fmt::Display for StateTransitionError {
@@ -19,9 +45,15 @@ impl fmt::Display for StateTransitionError {
             StateTransitionError::InsufficientBalance { has, needs } => {
                 write!(f, "Insufficient balance: has {}, needs {}", has, needs)
             }
+            StateTransitionError::DebitExceedsBalance { has, needs } => {
+                write!(f, "Debit of {} exceeds the balance {}", needs, has)
+            }
             StateTransitionError::InvalidNonce { expected, got } => {
                 write!(f, "Invalid nonce: expected {}, got {}", expected, got)
             }
+            StateTransitionError::WrongChain { expected, got } => {
+                write!(f, "Transaction is for chain {}, this is chain {}", got, expected)
+            }
             StateTransitionError::BalanceOverflow => {
                 write!(f, "Balance overflow occurred")
             }
@@ -31,9 +63,27 @@ impl fmt::Display for StateTransitionError {
             StateTransitionError::GasPriceTooLow => {
                 write!(f, "Gas price is too low")
             }
+            StateTransitionError::MaxFeeBelowBaseFee { max_fee, base_fee } => {
+                write!(
+                    f,
+                    "Max fee per gas {} is below the base fee {}",
+                    max_fee, base_fee
+                )
+            }
+            StateTransitionError::PriorityFeeAboveMaxFee {
+                priority_fee,
+                max_fee,
+            } => write!(
+                f,
+                "Priority fee per gas {} is above the max fee {}",
+                priority_fee, max_fee
+            ),
             StateTransitionError::InvalidGasLimit => {
                 write!(f, "Invalid gas limit set")
             }
+            StateTransitionError::FeeOverflow => {
+                write!(f, "Gas limit times gas price overflows")
+            }
             StateTransitionError::InsufficientGas { provided, required } => {
                 write!(
                     f,
@@ -41,6 +91,55 @@ impl fmt::Display for StateTransitionError {
                     provided, required
                 )
             }
+            StateTransitionError::InvalidSignature => {
+                write!(f, "Signature does not match the transaction")
+            }
+            StateTransitionError::InvalidPayload(reason) => {
+                write!(f, "Invalid transaction payload: {}", reason)
+            }
+            StateTransitionError::InsufficientAllowance { allowance, needs } => {
+                write!(
+                    f,
+                    "Insufficient allowance: allowance {}, needs {}",
+                    allowance, needs
+                )
+            }
+            StateTransitionError::TransactionTooLarge { size, max } => {
+                write!(f, "Transaction too large: {} bytes, max {}", size, max)
+            }
+            StateTransitionError::MemoTooLong { length, max } => {
+                write!(f, "Memo too long: {} bytes, max {}", length, max)
+            }
+            StateTransitionError::NameTaken(name) => {
+                write!(f, "Name '{}' is already registered", name)
+            }
+            StateTransitionError::NotNameOwner(name) => {
+                write!(f, "Sender does not own the name '{}'", name)
+            }
+            StateTransitionError::NoPrecompile(address) => {
+                write!(
+                    f,
+                    "No precompile at {}, contracts are not supported",
+                    address
+                )
+            }
+            StateTransitionError::PrecompileFailed { name, reason } => {
+                write!(f, "Precompile {} failed: {}", name, reason)
+            }
+            StateTransitionError::SessionKeyRejected { key, reason } => {
+                write!(f, "Session key {} rejected: {}", key, reason)
+            }
+            StateTransitionError::ContractsDisabled => {
+                write!(f, "Contracts are not enabled on this chain")
+            }
+            StateTransitionError::NoContract(address) => {
+                write!(f, "No contract deployed at {}", address)
+            }
+            StateTransitionError::ContractFailed {
+                contract, reason, ..
+            } => {
+                write!(f, "Contract {} failed: {}", contract, reason)
+            }
         }
     }
 }
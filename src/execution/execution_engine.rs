@@ -1,75 +1,222 @@
 use super::ExecutionError;
-use alloy::primitives::{Address, B256, U256};
-use anyhow::Result;
-use std::collections::HashMap;
+use alloy::primitives::{Address, B256, Bloom, Bytes, U256};
+use anyhow::{Result, anyhow};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::sync::Mutex;
 
-use super::{GasConfig, Mempool, Receipt, StateManager};
-use crate::StateTransition;
-use crate::core::{Block, Transaction};
+use super::{
+    AccountDiff, AdmissionPolicy, BlockRewardConfig, BlockTemplate, BlockTemplateReport,
+    CallResult, ContractRuntime, ContractRuntimeKind, ExecutionSchedule, GasConfig,
+    InclusionEstimate, Mempool, MempoolSummary, NoopPolicy, ParallelExecutionConfig,
+    PrefetchReport, ProposalPrefetch, Receipt, ReceiptEvent, StateManager, StateSnapshot,
+    StateTransitionError, StateUndo, TransactionSource, TxPolicy, TxPoolContent, check_tx_policy,
+    logs_bloom, packing_order, precompiles, receipts_root,
+};
+use crate::account::Account;
+use crate::consensus::EpochSettlement;
+use crate::core::{
+    Block, BlockHeader, HeadCheckpoint, MAX_MEMO_LENGTH, Transaction, TransactionKind,
+};
+use crate::crypto::SignatureCache;
+use crate::{
+    BlockEnv, CHAIN_ID, GasCalculator, GasStatus, PendingStake, StateTransition, TransactionGas,
+    TransactionOutcome, effective_gas_price, inject_state_lock_stall,
+};
 
 #[derive(Debug, Clone)]
 pub struct ExecutionResult {
     pub receipts: Vec<Receipt>,
     pub total_gas_used: U256,
     pub state_root: B256,
+    // committed to by the header, see `receipts_root` and `logs_bloom`
+    pub receipts_root: B256,
+    pub logs_bloom: Bloom,
+    // gas fees of the executed transactions, failed ones included, paid to the proposer
+    // on chains with a base fee only the tips, the base fee part is burned
+    pub total_fees: U256,
+    pub burned_fees: U256,
+    // one entry per transaction, in block order
+    pub gas_report: Vec<TransactionGas>,
+    // minted to the proposer after the transactions
+    pub block_reward: U256,
+    // (validator, multiaddr) registered by successful transactions in this block
+    pub network_registrations: Vec<(Address, String)>,
+    // accounts changed by this block
+    pub state_diff: Vec<AccountDiff>,
 }
 
 pub struct ExecutionEngine {
     pub state_manager: Arc<Mutex<StateManager>>,
     mempool: Arc<Mutex<Mempool>>,
     gas_config: GasConfig,
+    // embedder supplied rules, checked at admission and block simulation
+    tx_policy: Mutex<Arc<dyn TxPolicy>>,
+    // senders recovered at admission, so simulation doesn't recover them again
+    signatures: Arc<SignatureCache>,
+    // sender accounts read ahead of our next proposal, used once by block simulation
+    prefetched: Mutex<Option<ProposalPrefetch>>,
+    // slot of the last committed block, session keys are checked against it before execution
+    head_slot: AtomicU64,
+    // chain transactions must be signed for, others are refused at admission and execution
+    chain_id: AtomicU64,
+    // our next block, selected ahead of the slot and updated as transactions arrive
+    template: Mutex<Option<BlockTemplate>>,
+    // reward minted to the proposer of each block
+    block_rewards: Mutex<BlockRewardConfig>,
+    // runs contract transactions, none on chains without contracts
+    contracts: Option<Arc<dyn ContractRuntime>>,
+    // base fee and gas used of the head block, the next base fee follows from them
+    head_fees: std::sync::Mutex<HeadFees>,
+    // whether blocks run transactions of disjoint accounts side by side
+    parallel_execution: Mutex<ParallelExecutionConfig>,
+    // accounts read back from recent state diffs after a restart, they answer reads of
+    // accounts the state doesn't hold and are never part of the state or its root
+    warm_accounts: std::sync::Mutex<HashMap<Address, Account>>,
+}
+
+// what the base fee of the next block is derived from, see `GasConfig::next_base_fee`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HeadFees {
+    pub base_fee: Option<U256>,
+    pub gas_used: U256,
+}
+
+impl HeadFees {
+    pub fn of(header: &BlockHeader) -> Self {
+        Self {
+            base_fee: header.base_fee_per_gas,
+            gas_used: header.gas_used.unwrap_or_default(),
+        }
+    }
 }
 
 impl ExecutionEngine {
     pub fn new() -> Self {
+        let signatures = Arc::new(SignatureCache::default());
         Self {
             state_manager: Arc::new(Mutex::new(StateManager::new())),
-            mempool: Arc::new(Mutex::new(Mempool::new(1000))),
+            mempool: Arc::new(Mutex::new(
+                Mempool::new(1000).with_signature_cache(signatures.clone()),
+            )),
             gas_config: GasConfig::default(),
+            tx_policy: Mutex::new(Arc::new(NoopPolicy)),
+            signatures,
+            prefetched: Mutex::new(None),
+            head_slot: AtomicU64::new(0),
+            chain_id: AtomicU64::new(CHAIN_ID),
+            template: Mutex::new(None),
+            block_rewards: Mutex::new(BlockRewardConfig::default()),
+            contracts: None,
+            head_fees: std::sync::Mutex::new(HeadFees::default()),
+            parallel_execution: Mutex::new(ParallelExecutionConfig::default()),
+            warm_accounts: std::sync::Mutex::new(HashMap::new()),
         }
     }
 
-    // simulate execute_block, execute transactions without updating states
+    // gas costs and limits of a chain spec instead of the defaults
+    pub fn with_gas_config(mut self, gas_config: GasConfig) -> Self {
+        self.gas_config = gas_config;
+        self
+    }
+
+    // contract engine of a chain spec, contract transactions fail without one
+    pub fn with_contract_runtime(mut self, kind: ContractRuntimeKind) -> Self {
+        self.contracts = kind.runtime();
+        self
+    }
+
+    pub fn head_slot(&self) -> u64 {
+        self.head_slot.load(Ordering::Relaxed)
+    }
+
+    // for a node resuming from storage, block commits keep it current afterwards
+    pub fn set_head_slot(&self, slot: u64) {
+        self.head_slot.store(slot, Ordering::Relaxed);
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id.load(Ordering::Relaxed)
+    }
+
+    // for chains other than the default one, set before any transaction arrives
+    pub fn set_chain_id(&self, chain_id: u64) {
+        self.chain_id.store(chain_id, Ordering::Relaxed);
+    }
+
+    pub fn head_fees(&self) -> HeadFees {
+        *self.head_fees.lock().unwrap()
+    }
+
+    // like the head slot, set on resume and rewind, block commits keep it current
+    pub fn set_head_fees(&self, fees: HeadFees) {
+        *self.head_fees.lock().unwrap() = fees;
+    }
+
+    // base fee of the block built on the current head, none on chains without one
+    pub fn next_base_fee(&self) -> Option<U256> {
+        let head = self.head_fees();
+        self.gas_config.next_base_fee(head.base_fee, head.gas_used)
+    }
+
+    // simulate execute_block, the transactions run against the state in order and every
+    // change is reverted afterwards, those that would be invalid in the block are dropped
     pub async fn simulate_execute_block(
         &self,
         transactions: &mut [Transaction],
     ) -> Result<Vec<Transaction>> {
-        // let state = self.state_manager.lock().await;
         let mut valid_transactions = Vec::new();
-        let mut temp_nonces: HashMap<Address, u64> = HashMap::new();
-        let mut temp_balances: HashMap<Address, U256> = HashMap::new();
+        let env = BlockEnv {
+            chain_id: self.chain_id(),
+            slot: self.head_slot(),
+            base_fee: self.next_base_fee(),
+            contracts: self.contracts.as_deref(),
+        };
 
-        let state = self.state_manager.lock().await;
+        // consumed by the block it was taken for, its signatures are cached by now
+        self.prefetched.lock().await.take();
+        let mut state = self.state_manager.lock().await;
+        inject_state_lock_stall().await;
+        state.checkpoint();
 
         for tx in transactions {
-            // Get current state values (accounting for previous txs in this block)
-
-            let current_nonce = temp_nonces
-                .get(&tx.from)
-                .copied() // Convert &u64 to u64
-                .unwrap_or_else(|| state.get_nonce(&tx.from));
-
-            let current_balance = temp_balances
-                .get(&tx.from)
-                .copied() // Convert &U256 to U256
-                .unwrap_or_else(|| state.get_balance(&tx.from));
-
-            // Simple checks
-            let max_cost = tx.amount + (tx.gas_limit * tx.gas_price);
-
-            if tx.nonce == current_nonce
-                && tx.gas_limit >= U256::from(21000)
-                && current_balance >= max_cost
-            {
-                valid_transactions.push(tx.clone());
-
-                // Track changes for next transaction from same sender
-                temp_nonces.insert(tx.from, current_nonce + 1);
-                temp_balances.insert(tx.from, current_balance - max_cost);
+            if let Err(e) = self.check_tx_size(tx) {
+                println!("🚫 {}", e);
+                continue;
+            }
+            // proposers and validators both simulate, blocks with forged transactions are rejected
+            let mut candidate = tx.clone();
+            let outcome = match self.signatures.recover_sender(tx) {
+                Ok(signer) if signer == tx.from => StateTransition::execute_transaction(
+                    &mut state,
+                    &mut candidate,
+                    &self.gas_config,
+                    &env,
+                ),
+                Ok(signer) => StateTransition::execute_session_transaction(
+                    &mut state,
+                    &mut candidate,
+                    &self.gas_config,
+                    signer,
+                    &env,
+                ),
+                Err(_) => {
+                    println!(
+                        "🚫 Invalid signature for transaction {}",
+                        hex::encode(&tx.hash[..8])
+                    );
+                    continue;
+                }
+            };
+            // later transactions of the block see what this one did, as they would in it
+            match outcome {
+                TransactionOutcome::Invalid(e) => println!("🚫 {}", e),
+                _ => valid_transactions.push(tx.clone()),
             }
         }
+        state.revert();
 
         Ok(valid_transactions)
     }
@@ -79,15 +226,103 @@ impl ExecutionEngine {
         &self,
         block: &mut Block,
     ) -> Result<ExecutionResult, ExecutionError> {
+        let rewards = self.block_rewards.lock().await.clone();
+        let parallel = self.parallel_execution.lock().await.clone();
+        let mut state = self.state_manager.lock().await;
+        inject_state_lock_stall().await;
+        let result = self.execute_transactions(&mut state, block, &rewards, &parallel)?;
+        self.set_head_slot(block.header.slot);
+        self.set_head_fees(HeadFees {
+            base_fee: block.header.base_fee_per_gas,
+            gas_used: result.total_gas_used,
+        });
+        Ok(result)
+    }
+
+    // execute a block against the current state and revert it, nothing is committed
+    pub async fn execute_block_dry_run(
+        &self,
+        block: &mut Block,
+    ) -> Result<ExecutionResult, ExecutionError> {
+        let rewards = self.block_rewards.lock().await.clone();
+        let parallel = self.parallel_execution.lock().await.clone();
         let mut state = self.state_manager.lock().await;
+        state.checkpoint();
+        let result = self.execute_transactions(&mut state, block, &rewards, &parallel);
+        state.revert();
+        result
+    }
+
+    // apply block transactions to the given state
+    fn execute_transactions(
+        &self,
+        state: &mut StateManager,
+        block: &mut Block,
+        rewards: &BlockRewardConfig,
+        parallel: &ParallelExecutionConfig,
+    ) -> Result<ExecutionResult, ExecutionError> {
         let mut receipts = Vec::new();
         let mut total_gas_used = U256::ZERO;
+        let mut total_fees = U256::ZERO;
+        let mut burned_fees = U256::ZERO;
+        let mut gas_report = Vec::new();
+        let mut network_registrations = Vec::new();
+        let mut snapshot = StateSnapshot::capture(state, &block.transactions);
+        snapshot.include(state, block.header.proposer);
+        let env = BlockEnv {
+            chain_id: self.chain_id(),
+            slot: block.header.slot,
+            base_fee: block.header.base_fee_per_gas,
+            contracts: self.contracts.as_deref(),
+        };
 
-        for (idx, tx) in block.transactions.iter_mut().enumerate() {
-            match StateTransition::apply_transaction(&mut state, tx, &self.gas_config) {
-                Ok(gas_used) => {
-                    total_gas_used += gas_used;
-                    let receipt = Receipt::success(tx.hash, gas_used);
+        let proposer = block.header.proposer;
+        // blocks too small, or with transactions reaching shared state, run in order
+        let schedule = (parallel.enabled && block.transactions.len() >= parallel.min_transactions)
+            .then(|| ExecutionSchedule::plan(&block.transactions, proposer))
+            .flatten()
+            .filter(ExecutionSchedule::is_parallel);
+        let outcomes = match schedule {
+            Some(schedule) => {
+                self.execute_scheduled(state, &mut block.transactions, &schedule, &env, proposer)
+            }
+            None => block
+                .transactions
+                .iter_mut()
+                .map(|tx| self.execute_and_pay(state, tx, &env, proposer))
+                .collect(),
+        };
+
+        for (idx, (tx, (outcome, tip))) in block.transactions.iter().zip(outcomes).enumerate() {
+            // invalid transactions use no gas, nothing is paid or burned for them
+            let gas_used = outcome.gas_used();
+            let gas_price = effective_gas_price(tx, env.base_fee);
+            let burned = gas_used * env.base_fee.unwrap_or_default();
+            total_gas_used += gas_used;
+            total_fees += tip;
+            burned_fees += burned;
+            gas_report.push(TransactionGas {
+                transaction_hash: tx.hash,
+                status: GasStatus::of(&outcome),
+                gas_limit: tx.gas_limit,
+                gas_used,
+                gas_price,
+                fee: gas_used * gas_price,
+                tip,
+                burned,
+            });
+
+            match outcome {
+                TransactionOutcome::Success { gas_used, output } => {
+                    if let TransactionKind::RegisterNetworkAddress { multiaddr } = &tx.kind {
+                        network_registrations.push((tx.from, multiaddr.clone()));
+                    }
+                    let receipt = Receipt::success(tx.hash, gas_used)
+                        .with_events(ReceiptEvent::from_transaction(tx))
+                        .with_size(tx.encoded_size())
+                        .with_memo(tx.memo.clone())
+                        .with_tip(tip)
+                        .with_output(output);
                     receipts.push(receipt);
 
                     println!(
@@ -96,24 +331,40 @@ impl ExecutionEngine {
                         gas_used
                     );
                 }
-                Err(e) => {
-                    let gas_used = tx.gas_limit;
-
-                    total_gas_used += gas_used;
-
-                    let receipt = Receipt::failed(tx.hash, gas_used, e.to_string());
+                TransactionOutcome::Failed { gas_used, error } => {
+                    let receipt = Receipt::failed(tx.hash, gas_used, error.to_string())
+                        .with_size(tx.encoded_size())
+                        .with_memo(tx.memo.clone())
+                        .with_tip(tip);
                     receipts.push(receipt);
 
                     println!(
                         "❌ Transaction {} failed: {}, gas consumed: {}",
                         idx + 1,
-                        e,
+                        error,
                         gas_used
                     );
                 }
+                TransactionOutcome::Invalid(error) => {
+                    let receipt = Receipt::failed(tx.hash, U256::ZERO, error.to_string())
+                        .with_size(tx.encoded_size())
+                        .with_memo(tx.memo.clone());
+                    receipts.push(receipt);
+
+                    println!(
+                        "🚫 Transaction {} is invalid: {}, nothing charged",
+                        idx + 1,
+                        error
+                    );
+                }
             }
         }
 
+        let block_reward = rewards.reward_at(block.header.index);
+        if block_reward > U256::ZERO {
+            state.fund_account(&block.header.proposer, block_reward);
+        }
+
         let final_state_root = state.get_state_root();
 
         // print messages
@@ -128,15 +379,118 @@ impl ExecutionEngine {
             receipts.iter().filter(|r| !r.success).count()
         );
         println!("   - Total gas used: {}", total_gas_used);
+        println!("   - Fees paid to proposer: {}", total_fees);
+        if let Some(base_fee) = env.base_fee {
+            println!("   - Base fee: {}, burned: {}", base_fee, burned_fees);
+        }
+        println!("   - Block reward: {}", block_reward);
         println!("   - Final state root: 0x{}", hex::encode(final_state_root));
 
         Ok(ExecutionResult {
+            receipts_root: receipts_root(&receipts),
+            logs_bloom: logs_bloom(&receipts),
             receipts,
             total_gas_used,
             state_root: final_state_root,
+            total_fees,
+            burned_fees,
+            gas_report,
+            block_reward,
+            network_registrations,
+            state_diff: snapshot.diff(state),
         })
     }
 
+    // run one block transaction and pay its tip, returns the outcome and the tip
+    fn execute_and_pay(
+        &self,
+        state: &mut StateManager,
+        tx: &mut Transaction,
+        env: &BlockEnv,
+        proposer: Address,
+    ) -> (TransactionOutcome, U256) {
+        // signed by someone other than the sender, only a session key of the sender may
+        let outcome = match self.signatures.recover_sender(tx) {
+            Ok(signer) if signer != tx.from => StateTransition::execute_session_transaction(
+                state,
+                tx,
+                &self.gas_config,
+                signer,
+                env,
+            ),
+            Ok(_) => StateTransition::execute_transaction(state, tx, &self.gas_config, env),
+            // no signer to hold to it, so nobody is charged
+            Err(_) => TransactionOutcome::Invalid(StateTransitionError::InvalidSignature),
+        };
+        let tip = StateTransition::pay_fee(state, tx, outcome.gas_used(), env.base_fee, proposer);
+        (outcome, tip)
+    }
+
+    // run each group of the schedule on its own accounts, in parallel, then merge them back
+    // groups share no account and the proposer's tips only add up, so the state ends up as
+    // if the transactions ran in block order
+    fn execute_scheduled(
+        &self,
+        state: &mut StateManager,
+        transactions: &mut [Transaction],
+        schedule: &ExecutionSchedule,
+        env: &BlockEnv,
+        proposer: Address,
+    ) -> Vec<(TransactionOutcome, U256)> {
+        println!(
+            "🧵 Executing {} transactions in {} parallel groups",
+            transactions.len(),
+            schedule.groups.len()
+        );
+        let mut slots: Vec<Option<&mut Transaction>> = transactions.iter_mut().map(Some).collect();
+        let jobs: Vec<(StateManager, Vec<_>)> = schedule
+            .groups
+            .iter()
+            .map(|group| {
+                let txs = group
+                    .transactions
+                    .iter()
+                    .map(|&idx| (idx, slots[idx].take().expect("groups are disjoint")))
+                    .collect();
+                (state.partial(&group.accounts), txs)
+            })
+            .collect();
+
+        let results: Vec<(StateManager, Vec<_>)> = jobs
+            .into_par_iter()
+            .map(|(mut part, txs)| {
+                let outcomes = txs
+                    .into_iter()
+                    .map(|(idx, tx)| {
+                        let (outcome, tip) = self.execute_and_pay(&mut part, tx, env, proposer);
+                        (idx, outcome, tip)
+                    })
+                    .collect();
+                (part, outcomes)
+            })
+            .collect();
+
+        // merged in schedule order, each outcome back at its place in the block
+        let mut outcomes: Vec<Option<(TransactionOutcome, U256)>> =
+            (0..transactions.len()).map(|_| None).collect();
+        let mut tips = U256::ZERO;
+        for (group, (part, group_outcomes)) in schedule.groups.iter().zip(results) {
+            state.merge_partial(&part, &group.accounts);
+            for (idx, outcome, tip) in group_outcomes {
+                tips += tip;
+                outcomes[idx] = Some((outcome, tip));
+            }
+        }
+        if tips > U256::ZERO {
+            state.fund_account(&proposer, tips);
+        }
+
+        outcomes
+            .into_iter()
+            .map(|outcome| outcome.expect("every transaction is scheduled"))
+            .collect()
+    }
+
     // execution each transaction in a block
     pub async fn execute_transaction(
         &self,
@@ -148,7 +502,7 @@ impl ExecutionEngine {
         StateTransition::apply_transaction(state, tx, &self.gas_config)
             .map_err(|e| ExecutionError::TxFailed(e.to_string()))?;
 
-        let gas_used = ExecutionEngine::calculate_gas_used(&tx);
+        let gas_used = self.calculate_gas_used(&tx);
 
         Ok(gas_used)
     }
@@ -159,7 +513,7 @@ impl ExecutionEngine {
         state: &StateManager,
         tx: &Transaction,
     ) -> Result<(), ExecutionError> {
-        if tx.gas_limit < U256::from(21000) {
+        if tx.gas_limit < GasCalculator::calculate_instrinsic_gas(tx, &self.gas_config) {
             return Err(ExecutionError::InvalidTransaction(
                 "Gas limit cannot be 0".to_string(),
             ));
@@ -185,9 +539,24 @@ impl ExecutionEngine {
         Ok(())
     }
 
+    // checks that need current state, for transactions received before it was current
+    pub async fn check_against_state(&self, tx: &Transaction) -> Result<()> {
+        let state = self.state_manager.lock().await;
+        let nonce = state.get_nonce(&tx.from);
+        if tx.nonce < nonce {
+            return Err(anyhow!(
+                "Nonce {} already used, account nonce is {}",
+                tx.nonce,
+                nonce
+            ));
+        }
+        self.validate_transaction(&state, tx).await?;
+        Ok(())
+    }
+
     // calculate gas used by transaction
-    fn calculate_gas_used(tx: &Transaction) -> U256 {
-        let base_cost = U256::from(21000u64);
+    fn calculate_gas_used(&self, tx: &Transaction) -> U256 {
+        let base_cost = GasCalculator::calculate_instrinsic_gas(tx, &self.gas_config);
 
         if base_cost > tx.gas_limit {
             return tx.gas_limit;
@@ -196,11 +565,128 @@ impl ExecutionEngine {
         }
     }
 
+    // oversized transactions and memos are refused by the mempool and skipped by proposers
+    fn check_tx_size(&self, tx: &Transaction) -> Result<()> {
+        if !GasCalculator::validate_tx_size(tx, &self.gas_config) {
+            return Err(anyhow!(
+                "Transaction {} is {} bytes, max {}",
+                hex::encode(&tx.hash[..8]),
+                tx.encoded_size(),
+                self.gas_config.max_tx_size
+            ));
+        }
+        if !tx.has_valid_memo() {
+            return Err(anyhow!(
+                "Transaction {} memo is {} bytes, max {}",
+                hex::encode(&tx.hash[..8]),
+                tx.memo.len(),
+                MAX_MEMO_LENGTH
+            ));
+        }
+        Ok(())
+    }
+
     // add transaction to mempool (moved from blockchain)
     pub async fn add_transaction(&self, transaction: &Transaction) -> Result<B256> {
+        self.add_transaction_from(transaction, TransactionSource::Local)
+            .await
+    }
+
+    // add transaction to mempool, admitted under the policy for its source
+    pub async fn add_transaction_from(
+        &self,
+        transaction: &Transaction,
+        source: TransactionSource,
+    ) -> Result<B256> {
+        self.check_tx_size(transaction)?;
+        check_tx_policy(self.tx_policy.lock().await.as_ref(), transaction)?;
+        if self.contracts.is_none()
+            && matches!(
+                transaction.kind,
+                TransactionKind::DeployContract { .. } | TransactionKind::CallContract { .. }
+            )
+        {
+            return Err(anyhow!("{}", StateTransitionError::ContractsDisabled));
+        }
+        if transaction.chain_id != self.chain_id() {
+            return Err(anyhow!(
+                "{}",
+                StateTransitionError::WrongChain {
+                    expected: self.chain_id(),
+                    got: transaction.chain_id,
+                }
+            ));
+        }
+
+        // signed by the sender, or by a session key within its bounds at the current head
+        let signer = match self.signatures.recover_sender(transaction) {
+            Ok(signer) if signer != transaction.from => {
+                StateTransition::check_session_key(
+                    &*self.state_manager.lock().await,
+                    transaction,
+                    signer,
+                    self.head_slot(),
+                )
+                .map_err(|e| anyhow!("{}", e))?;
+                Some(signer)
+            }
+            signer => signer.ok(),
+        };
+
+        let tx_hash =
+            self.mempool
+                .lock()
+                .await
+                .add_recovered_transaction(transaction, source, signer)?;
+        if signer == Some(transaction.from) {
+            self.offer_to_template(transaction).await;
+        }
+        Ok(tx_hash)
+    }
+
+    // replace the application transaction policy
+    pub async fn set_tx_policy(&self, policy: Arc<dyn TxPolicy>) {
+        *self.tx_policy.lock().await = policy;
+    }
+
+    // minted to the proposer of every block executed from now on
+    pub async fn set_block_rewards(&self, rewards: BlockRewardConfig) {
+        *self.block_rewards.lock().await = rewards;
+    }
+
+    pub async fn set_parallel_execution(&self, parallel: ParallelExecutionConfig) {
+        *self.parallel_execution.lock().await = parallel;
+    }
+
+    pub async fn block_rewards(&self) -> BlockRewardConfig {
+        self.block_rewards.lock().await.clone()
+    }
+
+    // replace the mempool admission policy
+    pub async fn set_admission_policy(&self, policy: AdmissionPolicy) {
+        self.mempool.lock().await.set_policy(policy);
+    }
+
+    // pending transactions grouped by sender and nonce
+    pub async fn txpool_content(&self) -> TxPoolContent {
+        self.mempool.lock().await.content()
+    }
+
+    pub async fn mempool_summary(&self) -> MempoolSummary {
+        self.mempool.lock().await.summary()
+    }
+
+    // drop every pending transaction, returns how many were removed
+    pub async fn clear_mempool(&self) -> usize {
         let mut mempool = self.mempool.lock().await;
+        let removed = mempool.len();
+        mempool.clear_all_transactions();
+        removed
+    }
 
-        return mempool.add_transaction(transaction);
+    // drop one pending transaction, false if it is not in the mempool
+    pub async fn drop_transaction(&self, tx_hash: &B256) -> bool {
+        self.mempool.lock().await.remove_transaction(tx_hash)
     }
 
     // get all transaction from mempool
@@ -209,4 +695,360 @@ impl ExecutionEngine {
 
         return mempool.get_all_transactions();
     }
+
+    // pending transactions our own blocks may include, the policy is this node's choice
+    // so it filters what we propose and never what we accept from other proposers
+    pub async fn proposable_transactions(&self) -> Vec<Transaction> {
+        let tx_policy = self.tx_policy.lock().await.clone();
+        let mut pending = self.get_pending_transactions().await;
+        pending.retain(|tx| match check_tx_policy(tx_policy.as_ref(), tx) {
+            Ok(()) => true,
+            Err(violation) => {
+                println!("🚫 {}", violation);
+                false
+            }
+        });
+        pending
+    }
+
+    // pending transactions the current state could run next, with their intrinsic gas
+    pub async fn executable_transactions(&self) -> Vec<(Transaction, U256)> {
+        let pending = self.get_pending_transactions().await;
+        let state = self.state_manager.lock().await;
+
+        pending
+            .into_iter()
+            .filter(|tx| {
+                let account = state.get_account(&tx.from);
+                tx.nonce == account.nonce && account.balance >= tx.max_transaction_cost()
+            })
+            .map(|tx| {
+                let gas = GasCalculator::calculate_instrinsic_gas(&tx, &self.gas_config);
+                (tx, gas)
+            })
+            .collect()
+    }
+
+    // verify signatures of the transactions a block for `slot` would pack first, so building
+    // it at slot start is execution and signing only
+    pub async fn prefetch_for_proposal(&self, slot: u64, limit: usize) -> PrefetchReport {
+        let pending = self.proposable_transactions().await;
+        let candidates: Vec<&Transaction> =
+            packing_order(&pending).into_iter().take(limit).collect();
+
+        // recovered once here, simulation then hits the signature cache
+        let mut senders = HashSet::new();
+        let mut invalid_signatures = 0;
+        for tx in &candidates {
+            // session keys are checked again by simulation, recovering is what's expensive
+            if self.signatures.recover_sender(tx).is_ok() {
+                senders.insert(tx.from);
+            } else {
+                invalid_signatures += 1;
+            }
+        }
+
+        *self.prefetched.lock().await = Some(ProposalPrefetch { slot });
+
+        PrefetchReport {
+            slot,
+            transactions: candidates.len(),
+            senders: senders.len(),
+            invalid_signatures,
+        }
+    }
+
+    // select the transactions of our block for `slot` now, in packing order,
+    // so the proposal only applies what arrived since
+    pub async fn build_block_template(
+        &self,
+        slot: u64,
+        max_transactions: usize,
+    ) -> BlockTemplateReport {
+        let state_root = self.state_manager.lock().await.get_state_root();
+        let pending = self.proposable_transactions().await;
+        let mut ordered: Vec<Transaction> = packing_order(&pending).into_iter().cloned().collect();
+        let valid = self
+            .simulate_execute_block(&mut ordered)
+            .await
+            .unwrap_or_default();
+
+        // a block landing during the build leaves a stale template, the proposal rebuilds
+        let mut template = BlockTemplate::new(slot, state_root, max_transactions);
+        {
+            let state = self.state_manager.lock().await;
+            template.fill(valid, |address| state.get_account(address));
+        }
+        let report = template.report();
+        *self.template.lock().await = Some(template);
+        report
+    }
+
+    // the template for `slot` if it still holds: no block changed the state and the
+    // mempool still has every transaction in it
+    pub async fn take_block_template(&self, slot: u64) -> Option<BlockTemplate> {
+        let template = self.template.lock().await.take()?;
+        let state_root = self.state_manager.lock().await.get_state_root();
+        if !template.is_fresh(slot, state_root) {
+            return None;
+        }
+        let mempool = self.mempool.lock().await;
+        template
+            .transactions
+            .iter()
+            .all(|tx| mempool.contains(&tx.hash))
+            .then_some(template)
+    }
+
+    pub async fn block_template_slot(&self) -> Option<u64> {
+        self.template.lock().await.as_ref().map(|t| t.slot)
+    }
+
+    // a newly admitted transaction joins the template if it fits, session key transactions
+    // wait for the next build since the template doesn't track their budgets
+    async fn offer_to_template(&self, tx: &Transaction) {
+        let mut template = self.template.lock().await;
+        let Some(template) = template.as_mut() else {
+            return;
+        };
+        // the same checks the block's validators run, a transaction priced under the next
+        // base fee would get the whole proposal rejected
+        if StateTransition::check_transaction(tx, &self.gas_config, self.next_base_fee()).is_err() {
+            return;
+        }
+        let state = self.state_manager.lock().await;
+        if template.state_root == state.get_state_root() {
+            template.offer(tx, &state.get_account(&tx.from));
+        }
+    }
+
+    // slot of the prefetch waiting for the next block simulation
+    pub async fn prefetched_slot(&self) -> Option<u64> {
+        self.prefetched.lock().await.as_ref().map(|p| p.slot)
+    }
+
+    // recovered senders shared by the mempool and block simulation
+    pub fn signature_cache(&self) -> &SignatureCache {
+        &self.signatures
+    }
+
+    // gas rules blocks are executed with
+    pub fn gas_config(&self) -> &GasConfig {
+        &self.gas_config
+    }
+
+    // estimate a gas price from pending transactions, never below the configured minimum
+    pub async fn estimate_gas_price(&self) -> U256 {
+        let mempool = self.mempool.lock().await;
+
+        // never below what the next block burns per gas
+        let floor = self
+            .next_base_fee()
+            .unwrap_or_default()
+            .max(self.gas_config.min_gas_price);
+        mempool
+            .median_gas_price()
+            .map(|price| price.max(floor))
+            .unwrap_or(floor)
+    }
+
+    // simulate upcoming blocks to find when a pending transaction gets included
+    pub async fn estimate_inclusion(&self, tx_hash: &B256) -> Option<InclusionEstimate> {
+        let mempool = self.mempool.lock().await;
+
+        mempool.estimate_inclusion(tx_hash, self.gas_config.block_gas_limit)
+    }
+
+    // cache the latest known value of accounts from block state diffs, newest diff first,
+    // for the accounts the state doesn't hold; returns how many were cached
+    pub async fn warm_up_accounts(&self, diffs: Vec<AccountDiff>) -> usize {
+        let state = self.state_manager.lock().await;
+        let mut warm = self.warm_accounts.lock().unwrap();
+        let mut cached = 0;
+        for diff in diffs {
+            if warm.contains_key(&diff.address) || state.find_account(&diff.address).is_some() {
+                continue;
+            }
+            let account = Account {
+                balance: diff.balance_after,
+                nonce: diff.nonce_after,
+                address: diff.address,
+            };
+            warm.insert(diff.address, account);
+            cached += 1;
+        }
+        cached
+    }
+
+    // the state's account, or its warmed up value when the state doesn't hold it
+    async fn read_account(&self, address: &Address) -> Account {
+        if let Some(account) = self.state_manager.lock().await.find_account(address) {
+            return account;
+        }
+        self.warm_accounts
+            .lock()
+            .unwrap()
+            .get(address)
+            .cloned()
+            .unwrap_or_else(|| Account::new(*address))
+    }
+
+    // fund accounts that don't exist yet, used for dev accounts
+    pub async fn prefund_accounts(&self, balances: &[(Address, U256)]) -> usize {
+        let accounts = balances
+            .iter()
+            .map(|(address, balance)| Account {
+                balance: *balance,
+                nonce: 0,
+                address: *address,
+            })
+            .collect();
+
+        self.state_manager.lock().await.load_accounts(accounts)
+    }
+
+    // take the stake changes queued since the last epoch boundary
+    pub async fn take_pending_stake(&self) -> BTreeMap<Address, PendingStake> {
+        self.state_manager.lock().await.take_pending_stake()
+    }
+
+    // pay settled stake back to validator balances
+    pub async fn credit_balances(&self, credits: &[(Address, U256)]) {
+        let mut state = self.state_manager.lock().await;
+        for (address, amount) in credits {
+            let mut account = state.get_account(address);
+            account.balance += *amount;
+            state.set_account(*address, account);
+        }
+    }
+
+    // mint epoch rewards and burn inactivity penalties, a penalty stops at the balance
+    // returns the settlements with the penalties actually charged
+    pub async fn apply_epoch_settlements(
+        &self,
+        settlements: &[EpochSettlement],
+    ) -> Vec<EpochSettlement> {
+        let mut state = self.state_manager.lock().await;
+        settlements
+            .iter()
+            .map(|settlement| {
+                let mut account = state.get_account(&settlement.validator);
+                account.balance += settlement.reward;
+                let penalty = settlement.penalty.min(account.balance);
+                account.balance -= penalty;
+                state.set_account(settlement.validator, account);
+                EpochSettlement {
+                    penalty,
+                    ..settlement.clone()
+                }
+            })
+            .collect()
+    }
+
+    // get account balance from current state
+    pub async fn get_balance(&self, address: &Address) -> U256 {
+        self.read_account(address).await.balance
+    }
+
+    // get account nonce from current state
+    pub async fn get_nonce(&self, address: &Address) -> u64 {
+        self.read_account(address).await.nonce
+    }
+
+    // get remaining allowance of `spender` over `owner`'s tokens
+    pub async fn get_allowance(&self, owner: &Address, spender: &Address) -> U256 {
+        self.state_manager
+            .lock()
+            .await
+            .get_allowance(owner, spender)
+    }
+
+    // get the owner a registered name resolves to
+    pub async fn resolve_name(&self, name: &str) -> Option<Address> {
+        self.state_manager.lock().await.resolve_name(name)
+    }
+
+    // execute `tx` as the sender's next transaction against the state after `at_block`, the
+    // current one when none, nothing is committed; no signature is needed and the nonce is
+    // filled in, a transaction that could not be included is an error
+    pub async fn call(
+        &self,
+        tx: &Transaction,
+        at_block: Option<(&HeadCheckpoint, &[&StateUndo])>,
+    ) -> Result<CallResult> {
+        let mut candidate = tx.clone();
+        let outcome = match at_block {
+            Some((checkpoint, undo)) => {
+                let env = BlockEnv {
+                    chain_id: self.chain_id(),
+                    slot: checkpoint.head.slot,
+                    base_fee: self
+                        .gas_config
+                        .next_base_fee(checkpoint.fees.base_fee, checkpoint.fees.gas_used),
+                    contracts: self.contracts.as_deref(),
+                };
+                let mut state = self.state_manager.lock().await.rewound(undo);
+                self.execute_call(&mut state, &mut candidate, &env)
+            }
+            None => {
+                let env = BlockEnv {
+                    chain_id: self.chain_id(),
+                    slot: self.head_slot(),
+                    base_fee: self.next_base_fee(),
+                    contracts: self.contracts.as_deref(),
+                };
+                let mut state = self.state_manager.lock().await;
+                state.checkpoint();
+                let outcome = self.execute_call(&mut state, &mut candidate, &env);
+                state.revert();
+                outcome
+            }
+        };
+
+        match outcome {
+            TransactionOutcome::Success { gas_used, output } => Ok(CallResult {
+                success: true,
+                output,
+                gas_used,
+                error: None,
+            }),
+            TransactionOutcome::Failed { gas_used, error } => Ok(CallResult {
+                success: false,
+                output: Bytes::new(),
+                gas_used,
+                error: Some(error.to_string()),
+            }),
+            TransactionOutcome::Invalid(error) => Err(anyhow!("{}", error)),
+        }
+    }
+
+    fn execute_call(
+        &self,
+        state: &mut StateManager,
+        tx: &mut Transaction,
+        env: &BlockEnv,
+    ) -> TransactionOutcome {
+        tx.nonce = state.get_nonce(&tx.from);
+        tx.chain_id = env.chain_id;
+        StateTransition::execute_transaction(state, tx, &self.gas_config, env)
+    }
+
+    // run a precompile against the current state and revert it, nothing is committed
+    pub async fn call_precompile(
+        &self,
+        address: &Address,
+        caller: Address,
+        input: &[u8],
+    ) -> Result<Bytes> {
+        let precompile = precompiles()
+            .get(address)
+            .ok_or(StateTransitionError::NoPrecompile(*address))
+            .map_err(|e| anyhow!("{}", e))?;
+        let mut state = self.state_manager.lock().await;
+
+        state.checkpoint();
+        let output = precompile.call(&mut state, caller, input);
+        state.revert();
+        output.map_err(|reason| anyhow!("Precompile {} failed: {}", precompile.name(), reason))
+    }
 }
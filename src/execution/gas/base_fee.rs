@@ -0,0 +1,75 @@
+use alloy::primitives::U256;
+use serde::{Deserialize, Serialize};
+
+use super::gas_config::GasConfig;
+use crate::Transaction;
+
+// eip-1559 style fee market, blocks carry a base fee that follows how full their parent was
+// the base fee of every transaction is burned, only the tip goes to the proposer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BaseFeeConfig {
+    // base fee of the first block that has one
+    pub initial_base_fee: U256,
+    // the gas target is the block gas limit divided by this
+    pub elasticity_multiplier: u64,
+    // the base fee moves by at most 1/denominator per block
+    pub max_change_denominator: u64,
+}
+
+impl Default for BaseFeeConfig {
+    fn default() -> Self {
+        Self {
+            initial_base_fee: U256::from(1_000_000_000), // 1 gwei
+            elasticity_multiplier: 2,
+            max_change_denominator: 8,
+        }
+    }
+}
+
+impl BaseFeeConfig {
+    // gas a block should use for the base fee to stay where it is
+    pub fn gas_target(&self, block_gas_limit: U256) -> U256 {
+        block_gas_limit / U256::from(self.elasticity_multiplier.max(1))
+    }
+
+    // base fee of the child of a block with `parent_base_fee` that used `parent_gas_used`
+    // up when the parent used more than the target, down when it used less, never below
+    // `min_gas_price`, which every transaction pays anyway
+    pub fn next_base_fee(
+        &self,
+        parent_base_fee: Option<U256>,
+        parent_gas_used: U256,
+        config: &GasConfig,
+    ) -> U256 {
+        let Some(base_fee) = parent_base_fee else {
+            return self.initial_base_fee.max(config.min_gas_price);
+        };
+        let target = self.gas_target(config.block_gas_limit);
+        if target.is_zero() || parent_gas_used == target {
+            return base_fee.max(config.min_gas_price);
+        }
+
+        let denominator = U256::from(self.max_change_denominator.max(1));
+        let next = if parent_gas_used > target {
+            let delta = base_fee * (parent_gas_used - target) / target / denominator;
+            base_fee.saturating_add(delta.max(U256::from(1)))
+        } else {
+            let delta = base_fee * (target - parent_gas_used) / target / denominator;
+            base_fee.saturating_sub(delta)
+        };
+        next.max(config.min_gas_price)
+    }
+}
+
+// price per gas a transaction pays in a block with `base_fee`
+// the max fee (`gas_price`) caps it, the priority fee limits the tip above the base fee,
+// transactions without one tip everything above it
+pub fn effective_gas_price(tx: &Transaction, base_fee: Option<U256>) -> U256 {
+    match (base_fee, tx.max_priority_fee_per_gas) {
+        (Some(base_fee), Some(priority_fee)) => {
+            tx.gas_price.min(base_fee.saturating_add(priority_fee))
+        }
+        _ => tx.gas_price,
+    }
+}
@@ -1,18 +1,38 @@
 use super::gas_config::GasConfig;
+use crate::{Transaction, TransactionKind, precompiles};
 use alloy::primitives::U256;
 
 pub struct GasCalculator;
 
 impl GasCalculator {
-    // calculate gas cost execution the calldata
-    // this is a hardcoded gas amount, because no smart contract opcode calculation yet
-    pub fn calculate_instrinsic_gas(config: &GasConfig) -> U256 {
-        // let mut gas = config.intrinsic_gas;
-
-        // gas += config.gas_per_byte * U256::from(40);
-
-        // gas
-        U256::from(21000)
+    // calculate gas cost of executing the transaction
+    // a base cost plus a fee per encoded byte, every payload (calldata, call input,
+    // contract code, memo) is part of the encoding, so it is charged by its real length
+    // contract calls add the fuel they burn on top, see `StateTransition`
+    // batch transfers also pay for every recipient balance they write,
+    // name registrations for every byte of the name kept in state
+    // and calls for the work of the precompile they target
+    pub fn calculate_instrinsic_gas(tx: &Transaction, config: &GasConfig) -> U256 {
+        let outputs = match &tx.kind {
+            TransactionKind::BatchTransfer { outputs } => outputs.len(),
+            _ => 0,
+        };
+        let name_bytes = match &tx.kind {
+            TransactionKind::RegisterName { name } => name.len(),
+            _ => 0,
+        };
+        let precompile_gas = match &tx.kind {
+            TransactionKind::Call { input } => precompiles()
+                .get(&tx.to)
+                .map_or(U256::ZERO, |precompile| precompile.gas(input)),
+            _ => U256::ZERO,
+        };
+        config.intrinsic_gas
+            + config.gas_per_byte * U256::from(tx.encoded_size())
+            + config.batch_output_gas * U256::from(outputs)
+            + config.memo_byte_gas * U256::from(tx.memo.len())
+            + config.name_byte_gas * U256::from(name_bytes)
+            + precompile_gas
     }
 
     // validate gas price is valid
@@ -24,4 +44,9 @@ impl GasCalculator {
     pub fn validate_gas_limit(gas_limit: U256, config: &GasConfig) -> bool {
         gas_limit >= config.intrinsic_gas && gas_limit <= config.block_gas_limit
     }
+
+    // validate the encoded transaction fits the size limit
+    pub fn validate_tx_size(tx: &Transaction, config: &GasConfig) -> bool {
+        tx.encoded_size() <= config.max_tx_size
+    }
 }
@@ -1,11 +1,36 @@
 use alloy::primitives::U256;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone)]
+use super::base_fee::BaseFeeConfig;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GasConfig {
-    pub intrinsic_gas: U256,   // Base cost for any transaction
-    pub gas_per_byte: U256,    // Cost per byte of data
-    pub min_gas_price: U256,   // Minimum gas price
-    pub block_gas_limit: U256, // Maximum gas per block
+    pub intrinsic_gas: U256,    // Base cost for any transaction
+    pub gas_per_byte: U256,     // Cost per byte of the encoded transaction
+    pub min_gas_price: U256,    // Minimum gas price
+    pub block_gas_limit: U256,  // Maximum gas per block
+    pub max_tx_size: usize,     // Largest encoded transaction accepted, in bytes
+    pub batch_output_gas: U256, // Extra cost per output of a batch transfer
+    pub memo_byte_gas: U256,    // Extra cost per memo byte, on top of gas_per_byte
+    pub name_byte_gas: U256,    // Extra cost per byte of a registered name
+    // dynamic base fee, blocks have none and the whole gas price goes to the proposer without it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_fee: Option<BaseFeeConfig>,
+}
+
+impl GasConfig {
+    // base fee of the block after one with `parent_base_fee` that used `parent_gas_used`,
+    // none on chains without a base fee
+    pub fn next_base_fee(
+        &self,
+        parent_base_fee: Option<U256>,
+        parent_gas_used: U256,
+    ) -> Option<U256> {
+        self.base_fee
+            .as_ref()
+            .map(|base_fee| base_fee.next_base_fee(parent_base_fee, parent_gas_used, self))
+    }
 }
 
 impl Default for GasConfig {
@@ -15,6 +40,11 @@ impl Default for GasConfig {
             gas_per_byte: U256::from(4),              // Cost for transaction data
             min_gas_price: U256::from(1_000_000_000), // 1 gwei
             block_gas_limit: U256::from(1_000_000),   // 1M gas per block
+            max_tx_size: 1024,                        // plain transfers are 217 bytes
+            batch_output_gas: U256::from(2_000),      // one balance write, no signature check
+            memo_byte_gas: U256::from(16),            // stored forever in blocks and receipts
+            name_byte_gas: U256::from(1_000), // held in state for as long as the chain lives
+            base_fee: None,
         }
     }
 }
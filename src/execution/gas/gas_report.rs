@@ -0,0 +1,40 @@
+use alloy::primitives::{B256, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::TransactionOutcome;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GasStatus {
+    Success,
+    // failed while executing, its gas is still paid for
+    Failed,
+    // never executed, charged nothing
+    Invalid,
+}
+
+// where the gas of one transaction of a block went
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionGas {
+    pub transaction_hash: B256,
+    pub status: GasStatus,
+    pub gas_limit: U256,
+    pub gas_used: U256,
+    // paid per gas, the effective price on chains with a base fee
+    pub gas_price: U256,
+    // charged to the sender, `tip + burned`
+    pub fee: U256,
+    // paid to the proposer
+    pub tip: U256,
+    pub burned: U256,
+}
+
+impl GasStatus {
+    pub fn of(outcome: &TransactionOutcome) -> Self {
+        match outcome {
+            TransactionOutcome::Success { .. } => GasStatus::Success,
+            TransactionOutcome::Failed { .. } => GasStatus::Failed,
+            TransactionOutcome::Invalid(_) => GasStatus::Invalid,
+        }
+    }
+}
@@ -1,5 +1,9 @@
+pub mod base_fee;
 pub mod gas_calculator;
 pub mod gas_config;
+pub mod gas_report;
 
+pub use base_fee::*;
 pub use gas_calculator::*;
 pub use gas_config::*;
+pub use gas_report::*;
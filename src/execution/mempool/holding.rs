@@ -0,0 +1,90 @@
+use alloy::primitives::B256;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+
+use crate::core::Transaction;
+
+// gossip transactions held while the node is syncing
+pub const SYNC_HOLDING_CAPACITY: usize = 1024;
+// head trailing the network by more than this many slots counts as syncing
+pub const SYNC_LAG_SLOTS: u64 = 4;
+
+// what to do with gossip transactions that arrive while the node is catching up
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncHoldingConfig {
+    // false validates them right away against the stale state
+    pub enabled: bool,
+    pub capacity: usize,
+    pub lag_slots: u64,
+}
+
+impl Default for SyncHoldingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            capacity: SYNC_HOLDING_CAPACITY,
+            lag_slots: SYNC_LAG_SLOTS,
+        }
+    }
+}
+
+// a held transaction and the peer that relayed it, kept as the mempool source
+#[derive(Debug, Clone)]
+pub struct HeldTransaction {
+    pub transaction: Transaction,
+    pub peer: String,
+}
+
+// bounded queue of gossip transactions, admitted to the mempool once sync completes
+#[derive(Debug)]
+pub struct SyncHoldingArea {
+    held: VecDeque<HeldTransaction>,
+    hashes: HashSet<B256>,
+    capacity: usize,
+    // refused because the area was full
+    dropped: u64,
+}
+
+impl SyncHoldingArea {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            held: VecDeque::new(),
+            hashes: HashSet::new(),
+            capacity,
+            dropped: 0,
+        }
+    }
+
+    // newest transactions are refused when full, earlier nonces stay in order
+    pub fn hold(&mut self, transaction: Transaction, peer: String) -> bool {
+        if self.hashes.contains(&transaction.hash) {
+            return false;
+        }
+        if self.held.len() >= self.capacity {
+            self.dropped += 1;
+            return false;
+        }
+        self.hashes.insert(transaction.hash);
+        self.held.push_back(HeldTransaction { transaction, peer });
+        true
+    }
+
+    // everything held, in arrival order
+    pub fn drain(&mut self) -> Vec<HeldTransaction> {
+        self.hashes.clear();
+        self.held.drain(..).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.held.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.held.is_empty()
+    }
+
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+}
@@ -0,0 +1,103 @@
+use alloy::primitives::{Address, B256, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+use crate::core::Transaction;
+
+// where a pending transaction lands in the simulated upcoming blocks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InclusionEstimate {
+    pub tx_hash: B256,
+    // 1 means the next block
+    pub blocks_until_inclusion: u64,
+    // share of pending transactions paying a lower gas price, 0-100
+    pub gas_price_percentile: u8,
+    // pending transactions packed before this one
+    pub transactions_ahead: usize,
+    pub gas_ahead: U256,
+    // blocks_until_inclusion times the slot duration, assumes one block per slot
+    pub estimated_wait_seconds: u64,
+}
+
+// order transactions the way a proposer would pack them:
+// highest gas price first, but never ahead of a lower nonce from the same sender
+pub fn packing_order(transactions: &[Transaction]) -> Vec<&Transaction> {
+    let mut by_sender: HashMap<Address, Vec<&Transaction>> = HashMap::new();
+    for tx in transactions {
+        by_sender.entry(tx.from).or_default().push(tx);
+    }
+
+    let mut queues: Vec<VecDeque<&Transaction>> = by_sender
+        .into_values()
+        .map(|mut txs| {
+            txs.sort_by_key(|tx| tx.nonce);
+            txs.into()
+        })
+        .collect();
+
+    let mut ordered = Vec::with_capacity(transactions.len());
+    loop {
+        // pick the best head among all senders, ties go to the older transaction
+        let best = queues
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, queue)| queue.front().map(|tx| (idx, *tx)))
+            .max_by(|(_, a), (_, b)| {
+                a.gas_price
+                    .cmp(&b.gas_price)
+                    .then(b.timestamp.cmp(&a.timestamp))
+                    .then(b.hash.cmp(&a.hash))
+            });
+
+        match best {
+            Some((idx, _)) => ordered.push(queues[idx].pop_front().unwrap()),
+            None => break,
+        }
+    }
+
+    ordered
+}
+
+// simulate packing the mempool into blocks of `block_gas_limit` until `tx_hash` is included
+// returns None if the transaction is not pending
+pub fn simulate_inclusion(
+    transactions: &[Transaction],
+    tx_hash: &B256,
+    block_gas_limit: U256,
+) -> Option<InclusionEstimate> {
+    let target = transactions.iter().find(|tx| tx.hash == *tx_hash)?;
+
+    let cheaper = transactions
+        .iter()
+        .filter(|tx| tx.gas_price < target.gas_price)
+        .count();
+    let gas_price_percentile = (cheaper * 100 / transactions.len()) as u8;
+
+    let mut blocks = 1u64;
+    let mut block_gas = U256::ZERO;
+    let mut gas_ahead = U256::ZERO;
+
+    for (transactions_ahead, tx) in packing_order(transactions).into_iter().enumerate() {
+        // start a new block once this one is full
+        if block_gas + tx.gas_limit > block_gas_limit && block_gas > U256::ZERO {
+            blocks += 1;
+            block_gas = U256::ZERO;
+        }
+
+        if tx.hash == *tx_hash {
+            return Some(InclusionEstimate {
+                tx_hash: *tx_hash,
+                blocks_until_inclusion: blocks,
+                gas_price_percentile,
+                transactions_ahead,
+                gas_ahead,
+                estimated_wait_seconds: 0,
+            });
+        }
+
+        block_gas += tx.gas_limit;
+        gas_ahead += tx.gas_limit;
+    }
+
+    None
+}
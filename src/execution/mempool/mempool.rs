@@ -1,8 +1,12 @@
+use super::{AdmissionPolicy, InclusionEstimate, TransactionSource, simulate_inclusion};
 use crate::core::Transaction;
-use alloy::primitives::B256;
+use crate::crypto::SignatureCache;
+use alloy::primitives::{Address, B256, U256};
 use anyhow::{Result, anyhow};
 use hex;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
 
 // tx queue, ordering
 
@@ -11,8 +15,45 @@ pub struct Mempool {
     // Core storage - just the essentials
     // tx_hash, B32 -> Transaction
     transactions: HashMap<B256, Transaction>,
+    // tx_hash -> where the transaction came from
+    sources: HashMap<B256, TransactionSource>,
+    // (sender, nonce) -> tx_hash, at most one pending transaction per nonce
+    by_nonce: HashMap<(Address, u64), B256>,
+    // (gas price, tx_hash), cheapest first, evicted when the pool is full
+    by_fee: BTreeSet<(U256, B256)>,
     // Maximum number of transaction
     max_size: usize,
+    policy: AdmissionPolicy,
+    // recovered senders, shared with block simulation
+    signatures: Arc<SignatureCache>,
+}
+
+// pending transaction with its origin, as returned by txpool_content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PooledTransaction {
+    #[serde(flatten)]
+    pub transaction: Transaction,
+    pub source: TransactionSource,
+}
+
+// geth style txpool content, sender -> nonce -> transaction
+// there is no nonce-gap queue yet, so `queued` is always empty
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TxPoolContent {
+    pub pending: BTreeMap<Address, BTreeMap<u64, PooledTransaction>>,
+    pub queued: BTreeMap<Address, BTreeMap<u64, PooledTransaction>>,
+}
+
+// pool occupancy without the transactions themselves, for debug bundles
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MempoolSummary {
+    pub pending: usize,
+    pub capacity: usize,
+    pub senders: usize,
+    // source kind -> pending transactions
+    pub by_source: BTreeMap<String, usize>,
+    pub lowest_gas_price: Option<U256>,
+    pub highest_gas_price: Option<U256>,
 }
 
 impl Mempool {
@@ -20,15 +61,66 @@ impl Mempool {
     pub fn new(max_size: usize) -> Self {
         Self {
             transactions: HashMap::new(),
+            sources: HashMap::new(),
+            by_nonce: HashMap::new(),
+            by_fee: BTreeSet::new(),
             max_size,
+            policy: AdmissionPolicy::default(),
+            signatures: Arc::new(SignatureCache::default()),
         }
     }
 
-    // Add a transaction to the mempool
+    // share the signature cache with the rest of the execution engine
+    pub fn with_signature_cache(mut self, signatures: Arc<SignatureCache>) -> Self {
+        self.signatures = signatures;
+        self
+    }
+
+    // replace the admission policy
+    pub fn set_policy(&mut self, policy: AdmissionPolicy) {
+        self.policy = policy;
+    }
+
+    // Add a transaction created by this node
     pub fn add_transaction(&mut self, transaction: &Transaction) -> Result<B256> {
+        self.add_transaction_from(transaction, TransactionSource::Local)
+    }
+
+    // Add a transaction to the mempool, applying the admission policy of its source
+    pub fn add_transaction_from(
+        &mut self,
+        transaction: &Transaction,
+        source: TransactionSource,
+    ) -> Result<B256> {
+        let signer = self
+            .signatures
+            .recover_sender(transaction)
+            .ok()
+            .filter(|signer| *signer == transaction.from);
+        self.add_recovered_transaction(transaction, source, signer)
+    }
+
+    // Add a transaction whose signer the caller recovered through the shared signature cache,
+    // the sender or a session key of it checked against current state, None if neither
+    pub fn add_recovered_transaction(
+        &mut self,
+        transaction: &Transaction,
+        source: TransactionSource,
+        signer: Option<Address>,
+    ) -> Result<B256> {
         let tx_hash = transaction.hash;
 
-        if !transaction.is_signature_valid() {
+        if self.policy.for_source(&source).enforce_fee_floor
+            && transaction.gas_price < self.policy.fee_floor
+        {
+            return Err(anyhow!(
+                "Gas price {} below the mempool fee floor {}",
+                transaction.gas_price,
+                self.policy.fee_floor
+            ));
+        }
+
+        if signer.is_none() {
             return Err(anyhow!(
                 "Transaction signature failed for {}",
                 hex::encode(&tx_hash[..8])
@@ -42,11 +134,23 @@ impl Mempool {
 
         let _ = self.validate_transaction(&transaction);
 
-        self.replace_transaction_by_fee(&transaction)?;
+        // gossip delivers the same transaction more than once
+        if self.transactions.contains_key(&tx_hash) {
+            return Ok(tx_hash);
+        }
+
+        let replaced = self.replace_transaction_by_fee(&transaction)?;
+        if !replaced && self.transactions.len() >= self.max_size {
+            self.evict_cheapest(transaction)?;
+        }
 
         // Add to mempool
         // insert consumes the transaction
         self.transactions.insert(tx_hash, transaction.clone()); // consumes the value
+        self.sources.insert(tx_hash, source);
+        self.by_nonce
+            .insert((transaction.from, transaction.nonce), tx_hash);
+        self.by_fee.insert((transaction.gas_price, tx_hash));
 
         println!(
             "✅ Transaction {} added to mempool",
@@ -55,27 +159,56 @@ impl Mempool {
         Ok(tx_hash)
     }
 
-    // replace existing transaction by fee
-    fn replace_transaction_by_fee(&mut self, transaction: &Transaction) -> Result<()> {
-        if let Some(existing) = self
-            .transactions
-            .values()
-            .find(|t| t.from == transaction.from && t.nonce == transaction.nonce)
-        {
-            if transaction.gas_price > existing.gas_price {
-                println!(
-                    "⚡ Replacing tx from {} with nonce {} (new fee {} > old fee {})",
-                    transaction.from, transaction.nonce, transaction.gas_price, existing.gas_price
-                );
-                let old_hash = existing.hash;
-                self.transactions.remove(&old_hash);
-            } else {
-                println!(
-                    "❌ Duplicate nonce tx rejected (fee {} <= existing fee {})",
-                    transaction.gas_price, existing.gas_price
-                );
-            }
+    // replace existing transaction by fee, true if one was replaced
+    fn replace_transaction_by_fee(&mut self, transaction: &Transaction) -> Result<bool> {
+        let Some(existing) = self
+            .by_nonce
+            .get(&(transaction.from, transaction.nonce))
+            .and_then(|hash| self.transactions.get(hash))
+        else {
+            return Ok(false);
+        };
+
+        if transaction.gas_price <= existing.gas_price {
+            println!(
+                "❌ Duplicate nonce tx rejected (fee {} <= existing fee {})",
+                transaction.gas_price, existing.gas_price
+            );
+            return Err(anyhow!(
+                "Replacement for nonce {} must pay more than {}",
+                transaction.nonce,
+                existing.gas_price
+            ));
+        }
+
+        println!(
+            "⚡ Replacing tx from {} with nonce {} (new fee {} > old fee {})",
+            transaction.from, transaction.nonce, transaction.gas_price, existing.gas_price
+        );
+        let old_hash = existing.hash;
+        self.remove_transaction(&old_hash);
+        Ok(true)
+    }
+
+    // make room for a transaction by dropping the cheapest one, if it pays more
+    fn evict_cheapest(&mut self, transaction: &Transaction) -> Result<()> {
+        let Some(&(lowest_price, lowest_hash)) = self.by_fee.first() else {
+            return Ok(());
+        };
+        if transaction.gas_price <= lowest_price {
+            return Err(anyhow!(
+                "Mempool full with {} transactions, gas price must be above {}",
+                self.transactions.len(),
+                lowest_price
+            ));
         }
+
+        println!(
+            "🧹 Evicted tx {} (fee {}) to make room",
+            hex::encode(&lowest_hash[..8]),
+            lowest_price
+        );
+        self.remove_transaction(&lowest_hash);
         Ok(())
     }
 
@@ -105,13 +238,109 @@ impl Mempool {
         self.transactions.values().cloned().collect()
     }
 
+    // pending transactions grouped by sender and nonce, with their source
+    pub fn content(&self) -> TxPoolContent {
+        let mut content = TxPoolContent::default();
+        for (hash, transaction) in &self.transactions {
+            let source = self
+                .sources
+                .get(hash)
+                .cloned()
+                .unwrap_or(TransactionSource::Local);
+
+            content.pending.entry(transaction.from).or_default().insert(
+                transaction.nonce,
+                PooledTransaction {
+                    transaction: transaction.clone(),
+                    source,
+                },
+            );
+        }
+        content
+    }
+
+    // counts by source and the gas price range of pending transactions
+    pub fn summary(&self) -> MempoolSummary {
+        let mut by_source = BTreeMap::new();
+        for hash in self.transactions.keys() {
+            let kind = self
+                .sources
+                .get(hash)
+                .map_or(TransactionSource::Local.kind(), TransactionSource::kind);
+            *by_source.entry(kind.to_string()).or_default() += 1;
+        }
+        let senders: BTreeSet<Address> = self.transactions.values().map(|t| t.from).collect();
+
+        MempoolSummary {
+            pending: self.transactions.len(),
+            capacity: self.max_size,
+            senders: senders.len(),
+            by_source,
+            lowest_gas_price: self.by_fee.first().map(|(gas_price, _)| *gas_price),
+            highest_gas_price: self.by_fee.last().map(|(gas_price, _)| *gas_price),
+        }
+    }
+
+    // median gas price of pending transactions, None if mempool is empty
+    pub fn median_gas_price(&self) -> Option<U256> {
+        let mut gas_prices: Vec<U256> = self.transactions.values().map(|t| t.gas_price).collect();
+        if gas_prices.is_empty() {
+            return None;
+        }
+
+        gas_prices.sort();
+        Some(gas_prices[gas_prices.len() / 2])
+    }
+
+    // estimate how many blocks until a pending transaction is included
+    pub fn estimate_inclusion(
+        &self,
+        tx_hash: &B256,
+        block_gas_limit: U256,
+    ) -> Option<InclusionEstimate> {
+        let transactions = self.get_all_transactions();
+        simulate_inclusion(&transactions, tx_hash, block_gas_limit)
+    }
+
     /// Check if there are transactions to mine
+    pub fn contains(&self, tx_hash: &B256) -> bool {
+        self.transactions.contains_key(tx_hash)
+    }
+
     pub fn has_transactions(&self) -> bool {
         !self.transactions.is_empty()
     }
 
+    // Remove a single transaction, false if it is not pending
+    pub fn remove_transaction(&mut self, tx_hash: &B256) -> bool {
+        self.sources.remove(tx_hash);
+        let Some(transaction) = self.transactions.remove(tx_hash) else {
+            return false;
+        };
+        self.by_nonce.remove(&(transaction.from, transaction.nonce));
+        self.by_fee.remove(&(transaction.gas_price, *tx_hash));
+        true
+    }
+
+    // most transactions held at once, the cheapest is evicted beyond it
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    // number of pending transactions
+    pub fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
     // Clear all transactions in the mempool
     pub fn clear_all_transactions(&mut self) {
         self.transactions.clear();
+        self.sources.clear();
+        self.by_nonce.clear();
+        self.by_fee.clear();
     }
 }
@@ -1,3 +1,9 @@
+pub mod holding;
+pub mod inclusion;
 pub mod mempool;
+pub mod source;
 
-pub use mempool::Mempool;
+pub use holding::*;
+pub use inclusion::*;
+pub use mempool::*;
+pub use source::*;
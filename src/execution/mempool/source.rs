@@ -0,0 +1,97 @@
+use alloy::primitives::{Address, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::GasConfig;
+
+// where a pending transaction came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum TransactionSource {
+    // created inside this process (tests, tooling)
+    Local,
+    // submitted through this node's JSON-RPC
+    Rpc,
+    // received via gossip, with the libp2p peer id that relayed it
+    Gossip { peer: String },
+    // received while syncing blocks from peers
+    Sync,
+    // forwarded by a trusted edge node through `speed_relayTransaction`
+    Relay { relayer: Address },
+    // back from a canonical block a reorg replaced
+    Reorg,
+}
+
+// admission rules applied to one source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourcePolicy {
+    pub enforce_fee_floor: bool,
+}
+
+// node-local mempool admission policy, independent of the protocol minimum gas price
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdmissionPolicy {
+    // lowest gas price accepted from sources that enforce it
+    pub fee_floor: U256,
+    pub local: SourcePolicy,
+    pub rpc: SourcePolicy,
+    pub gossip: SourcePolicy,
+    pub sync: SourcePolicy,
+    pub relay: SourcePolicy,
+    pub reorg: SourcePolicy,
+}
+
+impl TransactionSource {
+    // source without the relaying peer, as counted in mempool summaries
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TransactionSource::Local => "local",
+            TransactionSource::Rpc => "rpc",
+            TransactionSource::Gossip { .. } => "gossip",
+            TransactionSource::Sync => "sync",
+            TransactionSource::Relay { .. } => "relay",
+            TransactionSource::Reorg => "reorg",
+        }
+    }
+}
+
+impl AdmissionPolicy {
+    pub fn for_source(&self, source: &TransactionSource) -> &SourcePolicy {
+        match source {
+            TransactionSource::Local => &self.local,
+            TransactionSource::Rpc => &self.rpc,
+            TransactionSource::Gossip { .. } => &self.gossip,
+            TransactionSource::Sync => &self.sync,
+            TransactionSource::Relay { .. } => &self.relay,
+            TransactionSource::Reorg => &self.reorg,
+        }
+    }
+}
+
+impl Default for AdmissionPolicy {
+    // local transactions and ones a block already included bypass the fee floor, everything
+    // else must pay it
+    fn default() -> Self {
+        Self {
+            fee_floor: GasConfig::default().min_gas_price,
+            local: SourcePolicy {
+                enforce_fee_floor: false,
+            },
+            rpc: SourcePolicy {
+                enforce_fee_floor: true,
+            },
+            gossip: SourcePolicy {
+                enforce_fee_floor: true,
+            },
+            sync: SourcePolicy {
+                enforce_fee_floor: true,
+            },
+            relay: SourcePolicy {
+                enforce_fee_floor: true,
+            },
+            reorg: SourcePolicy {
+                enforce_fee_floor: false,
+            },
+        }
+    }
+}
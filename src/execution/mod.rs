@@ -1,13 +1,29 @@
+pub mod block_template;
+pub mod call;
+pub mod contracts;
 pub mod error;
 pub mod execution_engine;
 pub mod gas;
 pub mod mempool;
+pub mod policy;
+pub mod precompile;
+pub mod prefetch;
 pub mod receipt;
+pub mod rewards;
+pub mod scheduler;
 pub mod state;
 
+pub use block_template::*;
+pub use call::*;
+pub use contracts::*;
 pub use error::*;
 pub use execution_engine::*;
 pub use gas::*;
 pub use mempool::*;
+pub use policy::*;
+pub use precompile::*;
+pub use prefetch::*;
 pub use receipt::*;
+pub use rewards::*;
+pub use scheduler::*;
 pub use state::*;
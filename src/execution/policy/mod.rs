@@ -0,0 +1,3 @@
+pub mod tx_policy;
+
+pub use tx_policy::*;
@@ -0,0 +1,126 @@
+use alloy::primitives::{Address, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::core::{Transaction, TransactionKind};
+
+// application specific rule a transaction must pass, checked at mempool admission
+// and when selecting our own block's transactions, never when validating other blocks
+pub trait TxPolicy: Send + Sync {
+    // short name used in rejection messages
+    fn name(&self) -> &str;
+
+    // Err(reason) rejects the transaction
+    fn check(&self, transaction: &Transaction) -> Result<(), String>;
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("Transaction rejected by {policy} policy: {reason}")]
+pub struct TxPolicyViolation {
+    pub policy: String,
+    pub reason: String,
+}
+
+// run a policy against a transaction
+pub fn check_tx_policy(
+    policy: &dyn TxPolicy,
+    transaction: &Transaction,
+) -> Result<(), TxPolicyViolation> {
+    policy
+        .check(transaction)
+        .map_err(|reason| TxPolicyViolation {
+            policy: policy.name().to_string(),
+            reason,
+        })
+}
+
+// default policy, accepts everything
+#[derive(Debug, Clone, Default)]
+pub struct NoopPolicy;
+
+impl TxPolicy for NoopPolicy {
+    fn name(&self) -> &str {
+        "noop"
+    }
+
+    fn check(&self, _transaction: &Transaction) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+// only listed senders may transact, optionally restricted to listed recipients
+// and a maximum amount per transaction
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AllowlistPolicy {
+    pub senders: HashSet<Address>,
+    // empty allows any recipient
+    pub recipients: HashSet<Address>,
+    pub max_amount: Option<U256>,
+}
+
+impl TxPolicy for AllowlistPolicy {
+    fn name(&self) -> &str {
+        "allowlist"
+    }
+
+    fn check(&self, transaction: &Transaction) -> Result<(), String> {
+        if !self.senders.contains(&transaction.from) {
+            return Err(format!("sender {} is not allowlisted", transaction.from));
+        }
+        // the tokens moved are the owner's, the sender only spends its allowance
+        if let TransactionKind::TransferFrom { owner } = &transaction.kind
+            && !self.senders.contains(owner)
+        {
+            return Err(format!("owner {} is not allowlisted", owner));
+        }
+
+        // a batch pays its outputs, `to` and `amount` are unused
+        let (recipients, amount) = match &transaction.kind {
+            TransactionKind::BatchTransfer { outputs } => {
+                let total = outputs
+                    .iter()
+                    .try_fold(U256::ZERO, |total, output| total.checked_add(output.amount))
+                    .ok_or_else(|| "batch total overflows".to_string())?;
+                (outputs.iter().map(|output| output.to).collect(), total)
+            }
+            _ => (vec![transaction.to], transaction.amount),
+        };
+
+        if !self.recipients.is_empty()
+            && let Some(to) = recipients.iter().find(|to| !self.recipients.contains(to))
+        {
+            return Err(format!("recipient {} is not allowlisted", to));
+        }
+
+        if let Some(max_amount) = self.max_amount
+            && amount > max_amount
+        {
+            return Err(format!(
+                "amount {} exceeds the maximum {}",
+                amount, max_amount
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+// policy selected in the node config
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum TxPolicyConfig {
+    #[default]
+    Noop,
+    Allowlist(AllowlistPolicy),
+}
+
+impl TxPolicyConfig {
+    pub fn build(&self) -> Arc<dyn TxPolicy> {
+        match self {
+            TxPolicyConfig::Noop => Arc::new(NoopPolicy),
+            TxPolicyConfig::Allowlist(allowlist) => Arc::new(allowlist.clone()),
+        }
+    }
+}
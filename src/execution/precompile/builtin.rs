@@ -0,0 +1,184 @@
+use alloy::primitives::{Address, B256, Bytes, U256, keccak256};
+use alloy_signer::Signature;
+
+use super::Precompile;
+use crate::StateManager;
+
+// 32 byte words of the input, rounded up
+fn words(input: &[u8]) -> u64 {
+    input.len().div_ceil(32) as u64
+}
+
+fn address_at(input: &[u8], offset: usize) -> Option<Address> {
+    input.get(offset..offset + 20).map(Address::from_slice)
+}
+
+// 0x..01, keccak256 of the input
+pub struct Keccak256Precompile;
+
+impl Precompile for Keccak256Precompile {
+    fn name(&self) -> &'static str {
+        "keccak256"
+    }
+
+    fn gas(&self, input: &[u8]) -> U256 {
+        U256::from(30 + 6 * words(input))
+    }
+
+    fn call(&self, _: &mut StateManager, _: Address, input: &[u8]) -> Result<Bytes, String> {
+        Ok(Bytes::copy_from_slice(keccak256(input).as_slice()))
+    }
+}
+
+// 0x..02, signer of a 32 byte hash followed by a 65 byte r || s || v signature,
+// empty output when the signature doesn't recover
+pub struct EcrecoverPrecompile;
+
+impl Precompile for EcrecoverPrecompile {
+    fn name(&self) -> &'static str {
+        "ecrecover"
+    }
+
+    fn gas(&self, _: &[u8]) -> U256 {
+        U256::from(3_000)
+    }
+
+    fn call(&self, _: &mut StateManager, _: Address, input: &[u8]) -> Result<Bytes, String> {
+        if input.len() != 32 + 65 {
+            return Err(format!("expected 97 bytes, got {}", input.len()));
+        }
+        let hash = B256::from_slice(&input[..32]);
+        let recovered = Signature::from_raw(&input[32..])
+            .ok()
+            .and_then(|signature| signature.recover_address_from_prehash(&hash).ok());
+
+        Ok(recovered.map_or_else(Bytes::new, |address| {
+            Bytes::copy_from_slice(address.as_slice())
+        }))
+    }
+}
+
+// 0x..03, owner of the utf-8 name in the input, empty output when it isn't registered
+pub struct NamePrecompile;
+
+impl Precompile for NamePrecompile {
+    fn name(&self) -> &'static str {
+        "names"
+    }
+
+    fn gas(&self, input: &[u8]) -> U256 {
+        U256::from(200 + 3 * words(input))
+    }
+
+    fn call(&self, state: &mut StateManager, _: Address, input: &[u8]) -> Result<Bytes, String> {
+        let name = std::str::from_utf8(input).map_err(|_| "name is not utf-8".to_string())?;
+
+        Ok(state
+            .resolve_name(name)
+            .map_or_else(Bytes::new, |owner| Bytes::copy_from_slice(owner.as_slice())))
+    }
+}
+
+// 0x..04, native token balances: `0x00 || account` returns the balance,
+// `0x01 || owner || spender` the allowance, both as 32 byte big-endian numbers
+pub struct BalancePrecompile;
+
+impl Precompile for BalancePrecompile {
+    fn name(&self) -> &'static str {
+        "balances"
+    }
+
+    fn gas(&self, _: &[u8]) -> U256 {
+        U256::from(400)
+    }
+
+    fn call(&self, state: &mut StateManager, _: Address, input: &[u8]) -> Result<Bytes, String> {
+        let value = match (input.first(), input.len()) {
+            (Some(0), 21) => state.get_balance(&address_at(input, 1).unwrap()),
+            (Some(1), 41) => state.get_allowance(
+                &address_at(input, 1).unwrap(),
+                &address_at(input, 21).unwrap(),
+            ),
+            _ => {
+                return Err("expected 0x00 || account or 0x01 || owner || spender".to_string());
+            }
+        };
+        Ok(Bytes::copy_from_slice(&value.to_be_bytes::<32>()))
+    }
+}
+
+// id of the commitments precompile, for tooling that builds its input
+pub const COMMITMENTS_PRECOMPILE: u8 = 5;
+
+// hash committed for `payload`, bound to the owner so nobody else can reveal or copy it
+pub fn memo_commitment(owner: &Address, salt: &B256, payload: &[u8]) -> B256 {
+    let mut data = owner.to_vec();
+    data.extend_from_slice(salt.as_slice());
+    data.extend_from_slice(payload);
+    keccak256(data)
+}
+
+// 0x..05, commit-reveal of memos and payloads:
+// `0x00 || commitment` records a commitment of the caller,
+// `0x01 || salt || payload` reveals it and returns the commitment,
+// `0x02 || commitment` returns 0 unknown, 1 committed or 2 revealed, then the owner
+pub struct CommitmentPrecompile;
+
+impl Precompile for CommitmentPrecompile {
+    fn name(&self) -> &'static str {
+        "commitments"
+    }
+
+    fn gas(&self, input: &[u8]) -> U256 {
+        match input.first() {
+            Some(0) => U256::from(2_000),
+            Some(1) => U256::from(2_000 + 6 * words(input)),
+            _ => U256::from(200),
+        }
+    }
+
+    fn call(
+        &self,
+        state: &mut StateManager,
+        caller: Address,
+        input: &[u8],
+    ) -> Result<Bytes, String> {
+        match input.first() {
+            Some(0) if input.len() == 33 => {
+                let commitment = B256::from_slice(&input[1..]);
+                if state.get_commitment(&commitment).is_some() {
+                    return Err(format!("{} is already committed", commitment));
+                }
+                state.set_commitment(commitment, caller, false);
+                Ok(Bytes::new())
+            }
+            Some(1) if input.len() >= 33 => {
+                let salt = B256::from_slice(&input[1..33]);
+                let commitment = memo_commitment(&caller, &salt, &input[33..]);
+                match state.get_commitment(&commitment) {
+                    None => Err(format!("{} has no commitment {}", caller, commitment)),
+                    Some(entry) if entry.revealed => {
+                        Err(format!("{} is already revealed", commitment))
+                    }
+                    Some(_) => {
+                        state.set_commitment(commitment, caller, true);
+                        Ok(Bytes::copy_from_slice(commitment.as_slice()))
+                    }
+                }
+            }
+            Some(2) if input.len() == 33 => {
+                let commitment = B256::from_slice(&input[1..]);
+                let mut output = vec![0u8; 21];
+                if let Some(entry) = state.get_commitment(&commitment) {
+                    output[0] = if entry.revealed { 2 } else { 1 };
+                    output[1..].copy_from_slice(entry.owner.as_slice());
+                }
+                Ok(Bytes::from(output))
+            }
+            _ => Err(
+                "expected 0x00 || commitment, 0x01 || salt || payload or 0x02 || commitment"
+                    .to_string(),
+            ),
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub mod builtin;
+pub mod registry;
+
+pub use builtin::*;
+pub use registry::*;
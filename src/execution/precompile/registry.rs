@@ -0,0 +1,111 @@
+use alloy::primitives::{Address, Bytes, U256};
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use super::{
+    BalancePrecompile, COMMITMENTS_PRECOMPILE, CommitmentPrecompile, EcrecoverPrecompile,
+    Keccak256Precompile, NamePrecompile,
+};
+use crate::StateManager;
+
+// addresses 0x00..01 to 0x00..ff are reserved for precompiles, value can't be sent to them
+pub fn precompile_address(id: u8) -> Address {
+    let mut address = [0u8; 20];
+    address[19] = id;
+    Address::from(address)
+}
+
+pub fn is_reserved_address(address: &Address) -> bool {
+    address.0[..19].iter().all(|b| *b == 0) && address.0[19] != 0
+}
+
+// protocol feature built into the node, run when a `Call` transaction targets its address
+// every node must run the same set, adding one is a consensus change
+pub trait Precompile: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    // gas on top of the intrinsic gas, known before execution from the input alone
+    fn gas(&self, input: &[u8]) -> U256;
+
+    // the returned bytes end up in the receipt, an error fails the transaction
+    fn call(
+        &self,
+        state: &mut StateManager,
+        caller: Address,
+        input: &[u8],
+    ) -> Result<Bytes, String>;
+}
+
+// listed by `speed_listPrecompiles`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrecompileInfo {
+    pub address: Address,
+    pub name: String,
+}
+
+#[derive(Default)]
+pub struct PrecompileRegistry {
+    precompiles: BTreeMap<Address, Box<dyn Precompile>>,
+}
+
+impl PrecompileRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // the precompiles every node runs, new protocol features are added here
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        let builtins: [(u8, Box<dyn Precompile>); 5] = [
+            (1, Box::new(Keccak256Precompile)),
+            (2, Box::new(EcrecoverPrecompile)),
+            (3, Box::new(NamePrecompile)),
+            (4, Box::new(BalancePrecompile)),
+            (COMMITMENTS_PRECOMPILE, Box::new(CommitmentPrecompile)),
+        ];
+        for (id, precompile) in builtins {
+            registry
+                .register(precompile_address(id), precompile)
+                .expect("builtin precompile addresses are distinct");
+        }
+        registry
+    }
+
+    pub fn register(&mut self, address: Address, precompile: Box<dyn Precompile>) -> Result<()> {
+        if !is_reserved_address(&address) {
+            return Err(anyhow!("{} is outside the precompile range", address));
+        }
+        if let Some(existing) = self.precompiles.get(&address) {
+            return Err(anyhow!(
+                "{} is already the {} precompile",
+                address,
+                existing.name()
+            ));
+        }
+        self.precompiles.insert(address, precompile);
+        Ok(())
+    }
+
+    pub fn get(&self, address: &Address) -> Option<&dyn Precompile> {
+        self.precompiles.get(address).map(|p| p.as_ref())
+    }
+
+    pub fn list(&self) -> Vec<PrecompileInfo> {
+        self.precompiles
+            .iter()
+            .map(|(address, precompile)| PrecompileInfo {
+                address: *address,
+                name: precompile.name().to_string(),
+            })
+            .collect()
+    }
+}
+
+static PRECOMPILES: OnceLock<PrecompileRegistry> = OnceLock::new();
+
+// the registry execution uses
+pub fn precompiles() -> &'static PrecompileRegistry {
+    PRECOMPILES.get_or_init(PrecompileRegistry::builtin)
+}
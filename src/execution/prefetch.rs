@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+// pending transactions warmed up before our slot, a block never holds more
+pub const PREFETCH_TRANSACTIONS: usize = 256;
+
+// a proposal warmed up ahead of its slot, its signatures are in the signature cache
+#[derive(Debug, Clone)]
+pub struct ProposalPrefetch {
+    pub slot: u64,
+}
+
+// what was warmed up for a slot, logged by the service
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PrefetchReport {
+    pub slot: u64,
+    pub transactions: usize,
+    pub senders: usize,
+    // signatures that failed verification, simulation skips these transactions
+    pub invalid_signatures: usize,
+}
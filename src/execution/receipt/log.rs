@@ -0,0 +1,181 @@
+use alloy::primitives::{Address, B256, Bytes, U256, keccak256};
+use serde::{Deserialize, Serialize};
+
+use super::receipt::{Receipt, ReceiptEvent};
+use crate::core::Block;
+
+// address native events are logged under, the zero address is never a precompile or account
+pub const NATIVE_LOG_ADDRESS: Address = Address::ZERO;
+
+// ethereum-style log: the emitting address, up to four topics and opaque data
+// topics[0] is the keccak of the event signature, the rest are indexed arguments
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Log {
+    pub address: Address,
+    pub topics: Vec<B256>,
+    pub data: Bytes,
+}
+
+// a log and where it was emitted, what the log index returns
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogEntry {
+    #[serde(flatten)]
+    pub log: Log,
+    pub block_hash: B256,
+    pub block_number: u64,
+    pub transaction_hash: B256,
+    pub transaction_index: u64,
+    // position among every log of the block
+    pub log_index: u64,
+}
+
+impl LogEntry {
+    // every log of a block with its location, receipts are in transaction order
+    pub fn from_block(block: &Block, receipts: &[Receipt]) -> Vec<LogEntry> {
+        let block_hash = block.header.hash();
+        let mut entries = Vec::new();
+        for (transaction_index, receipt) in receipts.iter().enumerate() {
+            for log in &receipt.logs {
+                entries.push(LogEntry {
+                    log: log.clone(),
+                    block_hash,
+                    block_number: block.header.index,
+                    transaction_hash: receipt.transaction_hash,
+                    transaction_index: transaction_index as u64,
+                    log_index: entries.len() as u64,
+                });
+            }
+        }
+        entries
+    }
+}
+
+// keccak of an event signature, the first topic of its logs
+pub fn event_topic(signature: &str) -> B256 {
+    keccak256(signature.as_bytes())
+}
+
+// an address as an indexed argument, left padded to 32 bytes
+pub fn address_topic(address: &Address) -> B256 {
+    address.into_word()
+}
+
+// 32 byte big endian words, how amounts and numbers are laid out in log data
+fn words(values: &[U256]) -> Bytes {
+    let mut data = Vec::with_capacity(values.len() * 32);
+    for value in values {
+        data.extend_from_slice(&value.to_be_bytes::<32>());
+    }
+    data.into()
+}
+
+impl Log {
+    // the log a native event is recorded as, names are indexed by their keccak
+    pub fn from_event(event: &ReceiptEvent) -> Self {
+        let native = |signature: &str, indexed: &[B256], data: Bytes| Log {
+            address: NATIVE_LOG_ADDRESS,
+            topics: std::iter::once(event_topic(signature))
+                .chain(indexed.iter().copied())
+                .collect(),
+            data,
+        };
+        match event {
+            ReceiptEvent::Transfer { from, to, amount } => native(
+                "Transfer(address,address,uint256)",
+                &[address_topic(from), address_topic(to)],
+                words(&[*amount]),
+            ),
+            ReceiptEvent::Approval {
+                owner,
+                spender,
+                allowance,
+            } => native(
+                "Approval(address,address,uint256)",
+                &[address_topic(owner), address_topic(spender)],
+                words(&[*allowance]),
+            ),
+            ReceiptEvent::NetworkAddressRegistered {
+                validator,
+                multiaddr,
+            } => native(
+                "NetworkAddressRegistered(address,string)",
+                &[address_topic(validator)],
+                Bytes::copy_from_slice(multiaddr.as_bytes()),
+            ),
+            ReceiptEvent::StakeTopUpQueued { validator, amount } => native(
+                "StakeTopUpQueued(address,uint256)",
+                &[address_topic(validator)],
+                words(&[*amount]),
+            ),
+            ReceiptEvent::StakeWithdrawalQueued { validator, amount } => native(
+                "StakeWithdrawalQueued(address,uint256)",
+                &[address_topic(validator)],
+                words(&[*amount]),
+            ),
+            ReceiptEvent::ValidatorRegistrationQueued { validator, stake } => native(
+                "ValidatorRegistrationQueued(address,uint256)",
+                &[address_topic(validator)],
+                words(&[*stake]),
+            ),
+            ReceiptEvent::ValidatorExitQueued { validator } => native(
+                "ValidatorExitQueued(address)",
+                &[address_topic(validator)],
+                Bytes::new(),
+            ),
+            ReceiptEvent::NameRegistered { name, owner } => native(
+                "NameRegistered(string,address)",
+                &[keccak256(name.as_bytes()), address_topic(owner)],
+                Bytes::copy_from_slice(name.as_bytes()),
+            ),
+            ReceiptEvent::NameTransferred { name, from, to } => native(
+                "NameTransferred(string,address,address)",
+                &[
+                    keccak256(name.as_bytes()),
+                    address_topic(from),
+                    address_topic(to),
+                ],
+                Bytes::copy_from_slice(name.as_bytes()),
+            ),
+            // logged by the precompile itself
+            ReceiptEvent::PrecompileCalled { precompile, caller } => Log {
+                address: *precompile,
+                topics: vec![
+                    event_topic("PrecompileCalled(address)"),
+                    address_topic(caller),
+                ],
+                data: Bytes::new(),
+            },
+            ReceiptEvent::SessionKeyAuthorized {
+                account,
+                session_key,
+                max_value,
+                expiry_slot,
+            } => native(
+                "SessionKeyAuthorized(address,address,uint256,uint64)",
+                &[address_topic(account), address_topic(session_key)],
+                words(&[*max_value, U256::from(*expiry_slot)]),
+            ),
+            // contract events are logged by the contract
+            ReceiptEvent::ContractDeployed { contract, deployer } => Log {
+                address: *contract,
+                topics: vec![
+                    event_topic("ContractDeployed(address)"),
+                    address_topic(deployer),
+                ],
+                data: Bytes::new(),
+            },
+            ReceiptEvent::ContractCalled {
+                contract,
+                caller,
+                value,
+            } => Log {
+                address: *contract,
+                topics: vec![
+                    event_topic("ContractCalled(address,uint256)"),
+                    address_topic(caller),
+                ],
+                data: words(&[*value]),
+            },
+        }
+    }
+}
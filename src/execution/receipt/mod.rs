@@ -1,3 +1,5 @@
+pub mod log;
 pub mod receipt;
 
+pub use log::*;
 pub use receipt::*;
@@ -1,13 +1,261 @@
-use alloy::primitives::{B256, U256};
+use alloy::primitives::{Address, B256, Bloom, BloomInput, Bytes, U256, keccak256};
+use serde::{Deserialize, Serialize};
+
+use super::log::Log;
+use crate::contract_address;
+use crate::core::{Transaction, TransactionKind};
 
 // receipt to keep track of state change status
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Receipt {
     pub transaction_hash: B256,
     pub gas_used: U256,
     pub success: bool,
     pub error_message: Option<String>,
+    // emitted by successful transactions, for indexers
+    #[serde(default)]
+    pub events: Vec<ReceiptEvent>,
+    // the events as ethereum-style logs, one per event in the same order
+    #[serde(default)]
+    pub logs: Vec<Log>,
+    // encoded transaction size the fee was charged on, 0 in receipts of older blocks
+    #[serde(default)]
+    pub size: u64,
+    // memo of the transaction, so deposits can be attributed from the receipt alone
+    #[serde(default)]
+    pub memo: Bytes,
+    // returned by the precompile a call ran, empty for every other transaction
+    #[serde(default)]
+    pub output: Bytes,
+    // paid to the proposer, the base fee part is burned; 0 in receipts of older blocks
+    #[serde(default)]
+    pub tip: U256,
+}
+
+// a stored receipt and where its transaction was included, `speed_getTransactionReceipt`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionReceipt {
+    #[serde(flatten)]
+    pub receipt: Receipt,
+    pub block_hash: B256,
+    pub block_number: u64,
+    pub transaction_index: u64,
+    // gas used by this transaction and the ones before it in the block
+    pub cumulative_gas_used: U256,
+}
+
+// state changes an indexer cares about, modelled after erc-20 events
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ReceiptEvent {
+    Transfer {
+        from: Address,
+        to: Address,
+        amount: U256,
+    },
+    Approval {
+        owner: Address,
+        spender: Address,
+        allowance: U256,
+    },
+    NetworkAddressRegistered {
+        validator: Address,
+        multiaddr: String,
+    },
+    // stake changes are queued here and applied when the epoch ends
+    StakeTopUpQueued {
+        validator: Address,
+        amount: U256,
+    },
+    StakeWithdrawalQueued {
+        validator: Address,
+        amount: U256,
+    },
+    ValidatorRegistrationQueued {
+        validator: Address,
+        stake: U256,
+    },
+    ValidatorExitQueued {
+        validator: Address,
+    },
+    NameRegistered {
+        name: String,
+        owner: Address,
+    },
+    NameTransferred {
+        name: String,
+        from: Address,
+        to: Address,
+    },
+    PrecompileCalled {
+        precompile: Address,
+        caller: Address,
+    },
+    // zero `max_value` when the key was revoked
+    SessionKeyAuthorized {
+        account: Address,
+        session_key: Address,
+        max_value: U256,
+        expiry_slot: u64,
+    },
+    ContractDeployed {
+        contract: Address,
+        deployer: Address,
+    },
+    // payouts the contract makes during the call are not evented
+    ContractCalled {
+        contract: Address,
+        caller: Address,
+        value: U256,
+    },
+}
+
+impl ReceiptEvent {
+    // events emitted by a transaction that executed successfully
+    pub fn from_transaction(tx: &Transaction) -> Vec<ReceiptEvent> {
+        match &tx.kind {
+            TransactionKind::Transfer => vec![ReceiptEvent::Transfer {
+                from: tx.from,
+                to: tx.to,
+                amount: tx.amount,
+            }],
+            TransactionKind::RegisterNetworkAddress { multiaddr } => {
+                vec![ReceiptEvent::NetworkAddressRegistered {
+                    validator: tx.from,
+                    multiaddr: multiaddr.clone(),
+                }]
+            }
+            TransactionKind::Approve { spender, allowance } => vec![ReceiptEvent::Approval {
+                owner: tx.from,
+                spender: *spender,
+                allowance: *allowance,
+            }],
+            TransactionKind::TransferFrom { owner } => vec![ReceiptEvent::Transfer {
+                from: *owner,
+                to: tx.to,
+                amount: tx.amount,
+            }],
+            TransactionKind::StakeTopUp => vec![ReceiptEvent::StakeTopUpQueued {
+                validator: tx.from,
+                amount: tx.amount,
+            }],
+            TransactionKind::StakeWithdraw { amount } => {
+                vec![ReceiptEvent::StakeWithdrawalQueued {
+                    validator: tx.from,
+                    amount: *amount,
+                }]
+            }
+            TransactionKind::RegisterValidator => {
+                vec![ReceiptEvent::ValidatorRegistrationQueued {
+                    validator: tx.from,
+                    stake: tx.amount,
+                }]
+            }
+            TransactionKind::Unstake => {
+                vec![ReceiptEvent::ValidatorExitQueued { validator: tx.from }]
+            }
+            // one transfer per output, in the order they were signed
+            TransactionKind::BatchTransfer { outputs } => outputs
+                .iter()
+                .map(|output| ReceiptEvent::Transfer {
+                    from: tx.from,
+                    to: output.to,
+                    amount: output.amount,
+                })
+                .collect(),
+            TransactionKind::RegisterName { name } => vec![ReceiptEvent::NameRegistered {
+                name: name.clone(),
+                owner: tx.from,
+            }],
+            TransactionKind::TransferName { name } => vec![ReceiptEvent::NameTransferred {
+                name: name.clone(),
+                from: tx.from,
+                to: tx.to,
+            }],
+            TransactionKind::Call { .. } => vec![ReceiptEvent::PrecompileCalled {
+                precompile: tx.to,
+                caller: tx.from,
+            }],
+            TransactionKind::AuthorizeSessionKey {
+                session_key,
+                max_value,
+                expiry_slot,
+            } => vec![ReceiptEvent::SessionKeyAuthorized {
+                account: tx.from,
+                session_key: *session_key,
+                max_value: *max_value,
+                expiry_slot: *expiry_slot,
+            }],
+            TransactionKind::DeployContract { .. } => vec![ReceiptEvent::ContractDeployed {
+                contract: contract_address(&tx.from, tx.nonce),
+                deployer: tx.from,
+            }],
+            TransactionKind::CallContract { .. } => vec![ReceiptEvent::ContractCalled {
+                contract: tx.to,
+                caller: tx.from,
+                value: tx.amount,
+            }],
+        }
+    }
+
+    // whether the event names this address, used to filter log subscriptions
+    pub fn involves(&self, address: &Address) -> bool {
+        self.addresses().contains(address)
+    }
+
+    // every address the event names, what the logs bloom is built from
+    pub fn addresses(&self) -> Vec<Address> {
+        match self {
+            ReceiptEvent::Transfer { from, to, .. }
+            | ReceiptEvent::NameTransferred { from, to, .. } => vec![*from, *to],
+            ReceiptEvent::Approval { owner, spender, .. } => vec![*owner, *spender],
+            ReceiptEvent::NetworkAddressRegistered { validator, .. }
+            | ReceiptEvent::StakeTopUpQueued { validator, .. }
+            | ReceiptEvent::StakeWithdrawalQueued { validator, .. }
+            | ReceiptEvent::ValidatorRegistrationQueued { validator, .. }
+            | ReceiptEvent::ValidatorExitQueued { validator } => vec![*validator],
+            ReceiptEvent::NameRegistered { owner, .. } => vec![*owner],
+            ReceiptEvent::PrecompileCalled { precompile, caller } => vec![*precompile, *caller],
+            ReceiptEvent::ContractDeployed { contract, deployer } => vec![*contract, *deployer],
+            ReceiptEvent::ContractCalled {
+                contract, caller, ..
+            } => vec![*contract, *caller],
+            ReceiptEvent::SessionKeyAuthorized {
+                account,
+                session_key,
+                ..
+            } => vec![*account, *session_key],
+        }
+    }
+}
+
+// keccak of the receipts in block order, zero for an empty block
+// each receipt is its transaction hash, status byte, gas used (32 bytes big endian) and the
+// keccak of its output, error messages are left out, their wording is no consensus rule
+pub fn receipts_root(receipts: &[Receipt]) -> B256 {
+    if receipts.is_empty() {
+        return B256::ZERO;
+    }
+
+    let mut data = Vec::with_capacity(receipts.len() * (32 + 1 + 32 + 32));
+    for receipt in receipts {
+        data.extend_from_slice(receipt.transaction_hash.as_slice());
+        data.push(receipt.success as u8);
+        data.extend_from_slice(&receipt.gas_used.to_be_bytes::<32>());
+        data.extend_from_slice(keccak256(&receipt.output).as_slice());
+    }
+    keccak256(data)
+}
+
+// bloom of every address the receipts' events name, empty when nothing was emitted
+pub fn logs_bloom(receipts: &[Receipt]) -> Bloom {
+    let mut bloom = Bloom::ZERO;
+    for event in receipts.iter().flat_map(|receipt| &receipt.events) {
+        for address in event.addresses() {
+            bloom.accrue(BloomInput::Raw(address.as_slice()));
+        }
+    }
+    bloom
 }
 
 impl Receipt {
@@ -17,6 +265,12 @@ impl Receipt {
             gas_used,
             success: true,
             error_message: None,
+            events: Vec::new(),
+            logs: Vec::new(),
+            size: 0,
+            memo: Bytes::new(),
+            output: Bytes::new(),
+            tip: U256::ZERO,
         }
     }
 
@@ -26,6 +280,39 @@ impl Receipt {
             gas_used,
             success: false,
             error_message: Some(error),
+            events: Vec::new(),
+            logs: Vec::new(),
+            size: 0,
+            memo: Bytes::new(),
+            output: Bytes::new(),
+            tip: U256::ZERO,
         }
     }
+
+    // also records the events as logs
+    pub fn with_events(mut self, events: Vec<ReceiptEvent>) -> Self {
+        self.logs = events.iter().map(Log::from_event).collect();
+        self.events = events;
+        self
+    }
+
+    pub fn with_size(mut self, size: usize) -> Self {
+        self.size = size as u64;
+        self
+    }
+
+    pub fn with_memo(mut self, memo: Bytes) -> Self {
+        self.memo = memo;
+        self
+    }
+
+    pub fn with_tip(mut self, tip: U256) -> Self {
+        self.tip = tip;
+        self
+    }
+
+    pub fn with_output(mut self, output: Bytes) -> Self {
+        self.output = output;
+        self
+    }
 }
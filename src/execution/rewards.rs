@@ -0,0 +1,36 @@
+use alloy::primitives::U256;
+use serde::{Deserialize, Serialize};
+
+// tokens minted to the proposer of every committed block, part of the state root
+// off by default, every validator must use the same schedule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlockRewardConfig {
+    // reward of block #1, in wei
+    pub reward: U256,
+    // the reward halves every this many blocks, 0 keeps it constant
+    pub halving_interval: u64,
+}
+
+impl Default for BlockRewardConfig {
+    fn default() -> Self {
+        Self {
+            reward: U256::ZERO,
+            halving_interval: 0,
+        }
+    }
+}
+
+impl BlockRewardConfig {
+    // reward minted for the block at `block_number`, zero once halved away
+    pub fn reward_at(&self, block_number: u64) -> U256 {
+        if self.halving_interval == 0 {
+            return self.reward;
+        }
+        let halvings = block_number.saturating_sub(1) / self.halving_interval;
+        if halvings >= 256 {
+            return U256::ZERO;
+        }
+        self.reward >> halvings as usize
+    }
+}
@@ -0,0 +1,135 @@
+use alloy::primitives::Address;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+use crate::core::{Transaction, TransactionKind};
+
+// blocks with fewer transactions run in order, splitting them costs more than it saves
+pub const PARALLEL_MIN_TRANSACTIONS: usize = 16;
+
+// run transactions that touch disjoint accounts side by side, the results are the same as
+// running them in block order, so every node may choose for itself
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParallelExecutionConfig {
+    pub enabled: bool,
+    pub min_transactions: usize,
+}
+
+impl Default for ParallelExecutionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_transactions: PARALLEL_MIN_TRANSACTIONS,
+        }
+    }
+}
+
+// transactions of a block that share no account, in block order, with the accounts they touch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionGroup {
+    pub transactions: Vec<usize>,
+    pub accounts: BTreeSet<Address>,
+}
+
+// a block's transactions split into groups that can run at the same time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionSchedule {
+    // ordered by their first transaction
+    pub groups: Vec<ExecutionGroup>,
+}
+
+impl ExecutionSchedule {
+    // none when some transaction reaches state beyond its own accounts, or pays the proposer
+    // while touching its account, such blocks run in order
+    pub fn plan(transactions: &[Transaction], proposer: Address) -> Option<Self> {
+        // union-find over transaction indices, joined through the accounts they share
+        let mut parents: Vec<usize> = (0..transactions.len()).collect();
+        let mut owners: HashMap<Address, usize> = HashMap::new();
+        let mut touched = Vec::with_capacity(transactions.len());
+
+        for (idx, tx) in transactions.iter().enumerate() {
+            let accounts = touched_accounts(tx)?;
+            if accounts.contains(&proposer) {
+                return None;
+            }
+            for address in &accounts {
+                match owners.get(address) {
+                    Some(&other) => union(&mut parents, idx, other),
+                    None => {
+                        owners.insert(*address, idx);
+                    }
+                }
+            }
+            touched.push(accounts);
+        }
+
+        let mut groups: Vec<ExecutionGroup> = Vec::new();
+        let mut group_of: HashMap<usize, usize> = HashMap::new();
+        for (idx, accounts) in touched.into_iter().enumerate() {
+            let root = find(&mut parents, idx);
+            let group = *group_of.entry(root).or_insert_with(|| {
+                groups.push(ExecutionGroup {
+                    transactions: Vec::new(),
+                    accounts: BTreeSet::new(),
+                });
+                groups.len() - 1
+            });
+            groups[group].transactions.push(idx);
+            groups[group].accounts.extend(accounts);
+        }
+
+        Some(Self { groups })
+    }
+
+    pub fn is_parallel(&self) -> bool {
+        self.groups.len() > 1
+    }
+}
+
+// every account a transaction can read or write, none for kinds that reach shared state
+// like names, stake, precompiles or contracts
+pub fn touched_accounts(tx: &Transaction) -> Option<BTreeSet<Address>> {
+    let mut accounts = BTreeSet::from([tx.from]);
+    match &tx.kind {
+        TransactionKind::Transfer => {
+            accounts.insert(tx.to);
+        }
+        // the allowance is kept under the sender
+        TransactionKind::Approve { .. } => {}
+        // the allowance is kept under the owner
+        TransactionKind::TransferFrom { owner } => {
+            accounts.insert(*owner);
+            accounts.insert(tx.to);
+        }
+        TransactionKind::BatchTransfer { outputs } => {
+            accounts.extend(outputs.iter().map(|output| output.to));
+        }
+        _ => return None,
+    }
+    Some(accounts)
+}
+
+fn find(parents: &mut [usize], idx: usize) -> usize {
+    let mut root = idx;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    // point the path straight at the root
+    let mut node = idx;
+    while parents[node] != root {
+        let next = parents[node];
+        parents[node] = root;
+        node = next;
+    }
+    root
+}
+
+fn union(parents: &mut [usize], a: usize, b: usize) {
+    let (a, b) = (find(parents, a), find(parents, b));
+    if a < b {
+        parents[b] = a;
+    } else {
+        parents[a] = b;
+    }
+}
@@ -1,5 +1,7 @@
+pub mod state_diff;
 pub mod state_manager;
 pub mod state_transition;
 
+pub use state_diff::*;
 pub use state_manager::*;
 pub use state_transition::*;
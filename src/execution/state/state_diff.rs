@@ -0,0 +1,78 @@
+use alloy::primitives::{Address, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::StateManager;
+use crate::account::Account;
+use crate::core::{Transaction, TransactionKind};
+
+// balance and nonce change of one account caused by a block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountDiff {
+    pub address: Address,
+    pub balance_before: U256,
+    pub balance_after: U256,
+    pub nonce_before: u64,
+    pub nonce_after: u64,
+}
+
+// snapshot of accounts a set of transactions can touch, taken before execution
+pub struct StateSnapshot {
+    accounts: HashMap<Address, Account>,
+}
+
+impl StateSnapshot {
+    pub fn capture(state: &StateManager, transactions: &[Transaction]) -> Self {
+        let mut accounts = HashMap::new();
+        for tx in transactions {
+            let mut touched = vec![tx.from, tx.to];
+            match &tx.kind {
+                TransactionKind::TransferFrom { owner } => touched.push(*owner),
+                TransactionKind::BatchTransfer { outputs } => {
+                    touched.extend(outputs.iter().map(|output| output.to));
+                }
+                _ => {}
+            }
+
+            for address in touched {
+                accounts
+                    .entry(address)
+                    .or_insert_with(|| state.get_account(&address));
+            }
+        }
+
+        Self { accounts }
+    }
+
+    // an account changed outside the transactions, e.g. the proposer's reward
+    pub fn include(&mut self, state: &StateManager, address: Address) {
+        self.accounts
+            .entry(address)
+            .or_insert_with(|| state.get_account(&address));
+    }
+
+    // accounts that changed since the snapshot, sorted by address
+    pub fn diff(&self, state: &StateManager) -> Vec<AccountDiff> {
+        let mut diffs: Vec<AccountDiff> = self
+            .accounts
+            .iter()
+            .filter_map(|(address, before)| {
+                let after = state.get_account(address);
+                if after == *before {
+                    return None;
+                }
+
+                Some(AccountDiff {
+                    address: *address,
+                    balance_before: before.balance,
+                    balance_after: after.balance,
+                    nonce_before: before.nonce,
+                    nonce_after: after.nonce,
+                })
+            })
+            .collect();
+
+        diffs.sort_by_key(|diff| diff.address);
+        diffs
+    }
+}
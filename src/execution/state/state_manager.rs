@@ -1,12 +1,133 @@
 use crate::account::Account;
-use alloy::primitives::{Address, B256, U256, keccak256};
+use alloy::primitives::{Address, B256, Bytes, U256, keccak256};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+// stake changes requested during an epoch, settled against the validator set when it ends
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingStake {
+    // already debited from the balance
+    pub top_up: U256,
+    pub withdraw: U256,
+    // the top-up registers a new validator
+    #[serde(default)]
+    pub register: bool,
+    // the validator asked to leave the set
+    #[serde(default)]
+    pub exit: bool,
+}
+
+// hash committed through the commitments precompile, the preimage stays off-chain until revealed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoCommitment {
+    pub owner: Address,
+    pub revealed: bool,
+}
+
+// key an account authorized to sign transfers and calls for it, within bounds
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionKey {
+    // value and fees the key may spend in total
+    pub max_value: U256,
+    pub spent: U256,
+    // last slot the key can be used in
+    pub expiry_slot: u64,
+}
+
+impl SessionKey {
+    pub fn remaining(&self) -> U256 {
+        self.max_value.saturating_sub(self.spent)
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StateManager {
     pub accounts: HashMap<Address, Account>,
+    // validator address -> registered p2p multiaddr
+    #[serde(default)]
+    pub network_addresses: HashMap<Address, String>,
+    // owner -> spender -> remaining allowance
+    #[serde(default)]
+    pub allowances: HashMap<Address, HashMap<Address, U256>>,
+    // validator -> stake changes waiting for the end of the epoch
+    #[serde(default)]
+    pub pending_stake: BTreeMap<Address, PendingStake>,
+    // registered name -> owner it resolves to
+    #[serde(default)]
+    pub names: BTreeMap<String, Address>,
+    // commitment hash -> who committed it
+    #[serde(default)]
+    pub commitments: BTreeMap<B256, MemoCommitment>,
+    // account -> session key -> its bounds
+    #[serde(default)]
+    pub session_keys: BTreeMap<Address, BTreeMap<Address, SessionKey>>,
+    // contract address -> its code, see `ContractRuntime`
+    #[serde(default)]
+    pub contract_code: BTreeMap<Address, Bytes>,
+    // contract address -> storage key -> value
+    #[serde(default)]
+    pub contract_storage: BTreeMap<Address, BTreeMap<B256, Bytes>>,
+    pub state_root: B256,
+    // not yet written to storage, see `take_write`
+    #[serde(skip)]
+    changes: StateChanges,
+    // values before each change since the oldest open checkpoint, see `checkpoint`
+    #[serde(skip)]
+    journal: Vec<JournalEntry>,
+    // journal length at each open checkpoint, innermost last
+    #[serde(skip)]
+    checkpoints: Vec<usize>,
+}
+
+// what one entry held before a change, `revert` puts it back
+#[derive(Debug, Clone)]
+enum JournalEntry {
+    Account(Address, Option<Account>),
+    NetworkAddress(Address, Option<String>),
+    Allowances(Address, Option<HashMap<Address, U256>>),
+    PendingStake(Address, Option<PendingStake>),
+    AllPendingStake(BTreeMap<Address, PendingStake>),
+    Name(String, Option<Address>),
+    Commitment(B256, Option<MemoCommitment>),
+    SessionKeys(Address, Option<BTreeMap<Address, SessionKey>>),
+    ContractCode(Address, Option<Bytes>),
+    ContractStorage(Address, Option<BTreeMap<B256, Bytes>>),
+}
+
+// the values before what changed between two `take_undo` calls, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct StateUndo(Vec<JournalEntry>);
+
+impl StateUndo {
+    // append what changed afterwards
+    pub fn extend(&mut self, later: StateUndo) {
+        self.0.extend(later.0);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+// what changed since the state was last written to storage
+#[derive(Debug, Clone, Default)]
+struct StateChanges {
+    accounts: BTreeSet<Address>,
+    // anything kept outside the accounts
+    rest: bool,
+}
+
+// the state after a block as written to storage, see `Storage::put_state`
+#[derive(Debug, Clone)]
+pub struct StateWrite {
+    pub block_hash: B256,
     pub state_root: B256,
+    // accounts to write, None for ones that no longer exist
+    pub accounts: Vec<(Address, Option<Account>)>,
+    // everything but the accounts, when any of it changed
+    pub rest: Option<StateManager>,
+    // holds every account, stored ones missing from it are deleted
+    pub full: bool,
 }
 
 impl StateManager {
@@ -14,7 +135,18 @@ impl StateManager {
     pub fn new() -> Self {
         Self {
             accounts: HashMap::new(),
+            network_addresses: HashMap::new(),
+            allowances: HashMap::new(),
+            pending_stake: BTreeMap::new(),
+            names: BTreeMap::new(),
+            commitments: BTreeMap::new(),
+            session_keys: BTreeMap::new(),
+            contract_code: BTreeMap::new(),
+            contract_storage: BTreeMap::new(),
             state_root: B256::ZERO,
+            changes: StateChanges::default(),
+            journal: Vec::new(),
+            checkpoints: Vec::new(),
         }
     }
 
@@ -26,14 +158,27 @@ impl StateManager {
             .unwrap_or_else(|| Account::new(*address))
     }
 
+    // account held in the state, none for an address it has no record of
+    pub fn find_account(&self, address: &Address) -> Option<Account> {
+        self.accounts.get(address).cloned()
+    }
+
     // Set account in the state and recalculate state root
     pub fn set_account(&mut self, address: Address, account: Account) {
+        self.record(|state| JournalEntry::Account(address, state.accounts.get(&address).cloned()));
         if account.balance == U256::ZERO && account.nonce == 0 {
             self.accounts.remove(&address);
         } else {
             self.accounts.insert(address, account);
         }
 
+        self.changes.accounts.insert(address);
+        self.calculate_state_root();
+    }
+
+    // something outside the accounts changed
+    fn rest_changed(&mut self) {
+        self.changes.rest = true;
         self.calculate_state_root();
     }
 
@@ -52,6 +197,85 @@ impl StateManager {
             data.extend_from_slice(&account.nonce.to_be_bytes());
         }
 
+        // registered network addresses are part of the on-chain state
+        let mut registrants: Vec<&Address> = self.network_addresses.keys().collect();
+        registrants.sort();
+
+        for address in registrants {
+            data.extend_from_slice(address.as_slice());
+            data.extend_from_slice(self.network_addresses[address].as_bytes());
+        }
+
+        // allowances too, ordered by owner then spender
+        let mut owners: Vec<&Address> = self.allowances.keys().collect();
+        owners.sort();
+
+        for owner in owners {
+            let mut spenders: Vec<(&Address, &U256)> = self.allowances[owner].iter().collect();
+            spenders.sort();
+
+            for (spender, allowance) in spenders {
+                data.extend_from_slice(owner.as_slice());
+                data.extend_from_slice(spender.as_slice());
+                data.extend_from_slice(&allowance.to_be_bytes::<32>());
+            }
+        }
+
+        // queued stake changes, already ordered by validator
+        for (validator, pending) in &self.pending_stake {
+            data.extend_from_slice(validator.as_slice());
+            data.extend_from_slice(&pending.top_up.to_be_bytes::<32>());
+            data.extend_from_slice(&pending.withdraw.to_be_bytes::<32>());
+        }
+
+        // registered names, already ordered by name
+        for (name, owner) in &self.names {
+            data.extend_from_slice(name.as_bytes());
+            data.extend_from_slice(owner.as_slice());
+        }
+
+        // memo commitments, already ordered by hash
+        for (commitment, entry) in &self.commitments {
+            data.extend_from_slice(commitment.as_slice());
+            data.extend_from_slice(entry.owner.as_slice());
+            data.push(entry.revealed as u8);
+        }
+
+        // session keys, ordered by account then key
+        for (account, keys) in &self.session_keys {
+            for (key, session) in keys {
+                data.extend_from_slice(account.as_slice());
+                data.extend_from_slice(key.as_slice());
+                data.extend_from_slice(&session.max_value.to_be_bytes::<32>());
+                data.extend_from_slice(&session.spent.to_be_bytes::<32>());
+                data.extend_from_slice(&session.expiry_slot.to_be_bytes());
+            }
+        }
+
+        // validator registrations among the queued stake changes
+        for (validator, _) in self.pending_stake.iter().filter(|(_, p)| p.register) {
+            data.extend_from_slice(validator.as_slice());
+        }
+
+        // and exit requests
+        for (validator, _) in self.pending_stake.iter().filter(|(_, p)| p.exit) {
+            data.extend_from_slice(validator.as_slice());
+        }
+
+        // contract code by its hash, then storage, ordered by contract then key
+        for (contract, code) in &self.contract_code {
+            data.extend_from_slice(contract.as_slice());
+            data.extend_from_slice(keccak256(code).as_slice());
+        }
+        for (contract, storage) in &self.contract_storage {
+            for (key, value) in storage {
+                data.extend_from_slice(contract.as_slice());
+                data.extend_from_slice(key.as_slice());
+                data.extend_from_slice(&(value.len() as u32).to_be_bytes());
+                data.extend_from_slice(value);
+            }
+        }
+
         self.state_root = if data.is_empty() {
             B256::ZERO
         } else {
@@ -64,6 +288,13 @@ impl StateManager {
         self.state_root
     }
 
+    // state root recomputed from the contents, differs from `get_state_root` when they were edited
+    pub fn computed_state_root(&self) -> B256 {
+        let mut copy = self.clone();
+        copy.calculate_state_root();
+        copy.state_root
+    }
+
     /// Get balance of an address
     pub fn get_balance(&self, address: &Address) -> U256 {
         self.get_account(address).balance
@@ -74,6 +305,413 @@ impl StateManager {
         self.get_account(address).nonce
     }
 
+    // register or rotate the network address of an account
+    pub fn set_network_address(&mut self, address: Address, multiaddr: String) {
+        self.record(|state| {
+            JournalEntry::NetworkAddress(address, state.network_addresses.get(&address).cloned())
+        });
+        self.network_addresses.insert(address, multiaddr);
+        self.rest_changed();
+    }
+
+    // get registered network address of an account
+    pub fn get_network_address(&self, address: &Address) -> Option<&String> {
+        self.network_addresses.get(address)
+    }
+
+    // remaining amount `spender` may move from `owner`
+    pub fn get_allowance(&self, owner: &Address, spender: &Address) -> U256 {
+        self.allowances
+            .get(owner)
+            .and_then(|spenders| spenders.get(spender))
+            .copied()
+            .unwrap_or(U256::ZERO)
+    }
+
+    // set allowance, a zero allowance is removed from state
+    pub fn set_allowance(&mut self, owner: Address, spender: Address, allowance: U256) {
+        self.record(|state| JournalEntry::Allowances(owner, state.allowances.get(&owner).cloned()));
+        if allowance == U256::ZERO {
+            if let Some(spenders) = self.allowances.get_mut(&owner) {
+                spenders.remove(&spender);
+                if spenders.is_empty() {
+                    self.allowances.remove(&owner);
+                }
+            }
+        } else {
+            self.allowances
+                .entry(owner)
+                .or_default()
+                .insert(spender, allowance);
+        }
+
+        self.rest_changed();
+    }
+
+    // owner a registered name resolves to
+    pub fn resolve_name(&self, name: &str) -> Option<Address> {
+        self.names.get(name).copied()
+    }
+
+    // register a name, or move it to a new owner
+    pub fn set_name_owner(&mut self, name: String, owner: Address) {
+        self.record(|state| JournalEntry::Name(name.clone(), state.names.get(&name).copied()));
+        self.names.insert(name, owner);
+        self.rest_changed();
+    }
+
+    pub fn get_commitment(&self, commitment: &B256) -> Option<&MemoCommitment> {
+        self.commitments.get(commitment)
+    }
+
+    // record a new commitment, or mark an existing one revealed
+    pub fn set_commitment(&mut self, commitment: B256, owner: Address, revealed: bool) {
+        self.record(|state| {
+            JournalEntry::Commitment(commitment, state.commitments.get(&commitment).cloned())
+        });
+        self.commitments
+            .insert(commitment, MemoCommitment { owner, revealed });
+        self.rest_changed();
+    }
+
+    pub fn get_session_key(&self, account: &Address, key: &Address) -> Option<&SessionKey> {
+        self.session_keys.get(account)?.get(key)
+    }
+
+    // authorize, update or, with None, revoke a session key of `account`
+    pub fn set_session_key(&mut self, account: Address, key: Address, session: Option<SessionKey>) {
+        self.record(|state| {
+            JournalEntry::SessionKeys(account, state.session_keys.get(&account).cloned())
+        });
+        match session {
+            Some(session) => {
+                self.session_keys
+                    .entry(account)
+                    .or_default()
+                    .insert(key, session);
+            }
+            None => {
+                if let Some(keys) = self.session_keys.get_mut(&account) {
+                    keys.remove(&key);
+                    if keys.is_empty() {
+                        self.session_keys.remove(&account);
+                    }
+                }
+            }
+        }
+        self.rest_changed();
+    }
+
+    pub fn get_contract_code(&self, contract: &Address) -> Option<&Bytes> {
+        self.contract_code.get(contract)
+    }
+
+    pub fn set_contract_code(&mut self, contract: Address, code: Bytes) {
+        self.record(|state| {
+            JournalEntry::ContractCode(contract, state.contract_code.get(&contract).cloned())
+        });
+        self.contract_code.insert(contract, code);
+        self.rest_changed();
+    }
+
+    // every storage slot of a contract, empty for contracts that never wrote one
+    pub fn get_contract_storage(&self, contract: &Address) -> BTreeMap<B256, Bytes> {
+        self.contract_storage
+            .get(contract)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    // replace a contract's storage after a successful call
+    pub fn set_contract_storage(&mut self, contract: Address, storage: BTreeMap<B256, Bytes>) {
+        self.record(|state| {
+            JournalEntry::ContractStorage(contract, state.contract_storage.get(&contract).cloned())
+        });
+        if storage.is_empty() {
+            self.contract_storage.remove(&contract);
+        } else {
+            self.contract_storage.insert(contract, storage);
+        }
+        self.rest_changed();
+    }
+
+    // queue a stake change for the end of the epoch
+    pub fn queue_stake_change(&mut self, validator: Address, top_up: U256, withdraw: U256) {
+        self.record_pending_stake(validator);
+        let pending = self.pending_stake.entry(validator).or_default();
+        pending.top_up += top_up;
+        pending.withdraw += withdraw;
+        self.rest_changed();
+    }
+
+    // queue `stake` for a validator that joins the set when the epoch ends
+    pub fn queue_validator_registration(&mut self, validator: Address, stake: U256) {
+        self.record_pending_stake(validator);
+        let pending = self.pending_stake.entry(validator).or_default();
+        pending.top_up += stake;
+        pending.register = true;
+        self.rest_changed();
+    }
+
+    // queue a validator's request to leave the set
+    pub fn queue_validator_exit(&mut self, validator: Address) {
+        self.record_pending_stake(validator);
+        self.pending_stake.entry(validator).or_default().exit = true;
+        self.rest_changed();
+    }
+
+    // hand over every queued stake change, leaving none behind
+    pub fn take_pending_stake(&mut self) -> BTreeMap<Address, PendingStake> {
+        let pending = std::mem::take(&mut self.pending_stake);
+        self.record(|_| JournalEntry::AllPendingStake(pending.clone()));
+        if !pending.is_empty() {
+            self.rest_changed();
+        }
+        pending
+    }
+
+    // insert accounts we don't hold yet, recalculating the state root once
+    // returns how many accounts were loaded
+    pub fn load_accounts(&mut self, accounts: Vec<Account>) -> usize {
+        let mut loaded = 0;
+        for account in accounts {
+            if self.accounts.contains_key(&account.address) {
+                continue;
+            }
+            self.record(|_| JournalEntry::Account(account.address, None));
+            self.changes.accounts.insert(account.address);
+            self.accounts.insert(account.address, account);
+            loaded += 1;
+        }
+
+        if loaded > 0 {
+            self.calculate_state_root();
+        }
+        loaded
+    }
+
+    // what `accounts` own in this state, enough to run transactions that touch nothing else
+    pub fn partial(&self, accounts: &BTreeSet<Address>) -> StateManager {
+        let mut part = StateManager::new();
+        for address in accounts {
+            if let Some(account) = self.accounts.get(address) {
+                part.accounts.insert(*address, account.clone());
+            }
+            if let Some(multiaddr) = self.network_addresses.get(address) {
+                part.network_addresses.insert(*address, multiaddr.clone());
+            }
+            if let Some(spenders) = self.allowances.get(address) {
+                part.allowances.insert(*address, spenders.clone());
+            }
+            if let Some(keys) = self.session_keys.get(address) {
+                part.session_keys.insert(*address, keys.clone());
+            }
+        }
+        part
+    }
+
+    // write back what `accounts` own in a partial state, recalculating the state root once
+    pub fn merge_partial(&mut self, part: &StateManager, accounts: &BTreeSet<Address>) {
+        for address in accounts {
+            self.record(|state| {
+                JournalEntry::Account(*address, state.accounts.get(address).cloned())
+            });
+            self.record(|state| {
+                JournalEntry::NetworkAddress(
+                    *address,
+                    state.network_addresses.get(address).cloned(),
+                )
+            });
+            self.record(|state| {
+                JournalEntry::Allowances(*address, state.allowances.get(address).cloned())
+            });
+            self.record(|state| {
+                JournalEntry::SessionKeys(*address, state.session_keys.get(address).cloned())
+            });
+            let address = *address;
+            restore_hashed(
+                &mut self.accounts,
+                address,
+                part.accounts.get(&address).cloned(),
+            );
+            restore_hashed(
+                &mut self.network_addresses,
+                address,
+                part.network_addresses.get(&address).cloned(),
+            );
+            restore_hashed(
+                &mut self.allowances,
+                address,
+                part.allowances.get(&address).cloned(),
+            );
+            restore_ordered(
+                &mut self.session_keys,
+                address,
+                part.session_keys.get(&address).cloned(),
+            );
+        }
+        self.changes.accounts.extend(accounts);
+        self.changes.rest = true;
+        self.calculate_state_root();
+    }
+
+    // start journaling, `revert` undoes every change made after this, checkpoints nest
+    pub fn checkpoint(&mut self) {
+        self.checkpoints.push(self.journal.len());
+    }
+
+    // undo every change since the innermost checkpoint and close it
+    pub fn revert(&mut self) {
+        let start = self
+            .checkpoints
+            .pop()
+            .expect("revert needs an open checkpoint");
+        while self.journal.len() > start {
+            let entry = self.journal.pop().expect("journal is longer than start");
+            self.undo(entry);
+        }
+        self.calculate_state_root();
+    }
+
+    // keep the changes since the innermost checkpoint and close it, an outer one can still
+    // revert them
+    pub fn discard_checkpoint(&mut self) {
+        self.checkpoints.pop();
+        if self.checkpoints.is_empty() {
+            self.journal.clear();
+        }
+    }
+
+    // what changed since the last call, the first call starts journaling for good; called
+    // between blocks, while no other checkpoint is open
+    pub fn take_undo(&mut self) -> StateUndo {
+        if self.checkpoints.is_empty() {
+            self.checkpoints.push(0);
+        }
+        StateUndo(std::mem::take(&mut self.journal))
+    }
+
+    // put back what changed in `undo`, changes made after it must be undone first
+    pub fn apply_undo(&mut self, undo: StateUndo) {
+        for entry in undo.0.into_iter().rev() {
+            self.undo(entry);
+        }
+        self.calculate_state_root();
+    }
+
+    // a copy with the changes since the last `take_undo` undone, then each of `undo`
+    pub fn rewound(&self, undo: &[&StateUndo]) -> StateManager {
+        let mut state = self.clone();
+        state.checkpoints.clear();
+        let since = std::mem::take(&mut state.journal);
+        state.apply_undo(StateUndo(since));
+        for undo in undo {
+            state.apply_undo((*undo).clone());
+        }
+        state
+    }
+
+    // remember an entry's value before it changes, only while a checkpoint is open
+    fn record(&mut self, entry: impl FnOnce(&StateManager) -> JournalEntry) {
+        if !self.checkpoints.is_empty() {
+            let entry = entry(self);
+            self.journal.push(entry);
+        }
+    }
+
+    fn record_pending_stake(&mut self, validator: Address) {
+        self.record(|state| {
+            JournalEntry::PendingStake(validator, state.pending_stake.get(&validator).cloned())
+        });
+    }
+
+    // restored entries still count as changed, storage then gets their old value
+    fn undo(&mut self, entry: JournalEntry) {
+        if !matches!(entry, JournalEntry::Account(..)) {
+            self.changes.rest = true;
+        }
+        match entry {
+            JournalEntry::Account(address, account) => {
+                self.changes.accounts.insert(address);
+                restore_hashed(&mut self.accounts, address, account);
+            }
+            JournalEntry::NetworkAddress(address, multiaddr) => {
+                restore_hashed(&mut self.network_addresses, address, multiaddr);
+            }
+            JournalEntry::Allowances(owner, spenders) => {
+                restore_hashed(&mut self.allowances, owner, spenders);
+            }
+            JournalEntry::PendingStake(validator, pending) => {
+                restore_ordered(&mut self.pending_stake, validator, pending);
+            }
+            JournalEntry::AllPendingStake(pending) => self.pending_stake = pending,
+            JournalEntry::Name(name, owner) => restore_ordered(&mut self.names, name, owner),
+            JournalEntry::Commitment(commitment, entry) => {
+                restore_ordered(&mut self.commitments, commitment, entry);
+            }
+            JournalEntry::SessionKeys(account, keys) => {
+                restore_ordered(&mut self.session_keys, account, keys);
+            }
+            JournalEntry::ContractCode(contract, code) => {
+                restore_ordered(&mut self.contract_code, contract, code);
+            }
+            JournalEntry::ContractStorage(contract, storage) => {
+                restore_ordered(&mut self.contract_storage, contract, storage);
+            }
+        }
+    }
+
+    // what changed since the last write, or everything with `full`, as the state after
+    // `block_hash`; nothing counts as changed afterwards
+    pub fn take_write(&mut self, block_hash: B256, full: bool) -> StateWrite {
+        let changes = std::mem::take(&mut self.changes);
+        let addresses: Vec<Address> = if full {
+            self.accounts.keys().copied().collect()
+        } else {
+            changes.accounts.into_iter().collect()
+        };
+        StateWrite {
+            block_hash,
+            state_root: self.state_root,
+            accounts: addresses
+                .into_iter()
+                .map(|address| (address, self.accounts.get(&address).cloned()))
+                .collect(),
+            rest: (full || changes.rest).then(|| self.without_accounts()),
+            full,
+        }
+    }
+
+    // state read back from storage, nothing counts as changed
+    pub fn from_stored(mut rest: StateManager, accounts: Vec<Account>) -> StateManager {
+        rest.accounts = accounts
+            .into_iter()
+            .map(|account| (account.address, account))
+            .collect();
+        rest.calculate_state_root();
+        rest.changes = StateChanges::default();
+        rest
+    }
+
+    // everything but the accounts
+    fn without_accounts(&self) -> StateManager {
+        StateManager {
+            accounts: HashMap::new(),
+            network_addresses: self.network_addresses.clone(),
+            allowances: self.allowances.clone(),
+            pending_stake: self.pending_stake.clone(),
+            names: self.names.clone(),
+            commitments: self.commitments.clone(),
+            session_keys: self.session_keys.clone(),
+            contract_code: self.contract_code.clone(),
+            contract_storage: self.contract_storage.clone(),
+            state_root: self.state_root,
+            changes: StateChanges::default(),
+            journal: Vec::new(),
+            checkpoints: Vec::new(),
+        }
+    }
+
     /// Get total number of accounts
     pub fn account_count(&self) -> usize {
         self.accounts.len()
@@ -87,3 +725,17 @@ impl StateManager {
         println!("💰 State - Funded {} with {} tokens", address, amount);
     }
 }
+
+fn restore_hashed<K: std::hash::Hash + Eq, V>(map: &mut HashMap<K, V>, key: K, value: Option<V>) {
+    match value {
+        Some(value) => map.insert(key, value),
+        None => map.remove(&key),
+    };
+}
+
+fn restore_ordered<K: Ord, V>(map: &mut BTreeMap<K, V>, key: K, value: Option<V>) {
+    match value {
+        Some(value) => map.insert(key, value),
+        None => map.remove(&key),
+    };
+}
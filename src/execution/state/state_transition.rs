@@ -1,34 +1,203 @@
+use crate::account::Account;
+use crate::core::MAX_MEMO_LENGTH;
 use crate::error::StateTransitionError;
-use crate::{GasCalculator, GasConfig, StateManager, Transaction};
-use alloy::primitives::U256;
+use crate::{
+    CHAIN_ID, ContractCall, ContractRuntime, GasCalculator, GasConfig, SessionKey, StateManager,
+    Transaction, TransactionKind, TransferOutput, contract_address, effective_gas_price,
+    is_reserved_address, precompiles,
+};
+use alloy::primitives::{Address, Bytes, U256};
 use anyhow::Result;
+use libp2p::Multiaddr;
+use std::collections::BTreeMap;
+
+// upper bound for a registered network address
+pub const MAX_MULTIADDR_LENGTH: usize = 256;
+
+// registered names are short, lowercase and never look like an address
+pub const MIN_NAME_LENGTH: usize = 3;
+pub const MAX_NAME_LENGTH: usize = 32;
 
 pub struct StateTransition;
 
 // execution layer
 
+// what the block a transaction executes in adds to the chain's gas config
+#[derive(Clone, Copy)]
+pub struct BlockEnv<'a> {
+    // transactions signed for any other chain are invalid
+    pub chain_id: u64,
+    // session keys expire by the block's slot
+    pub slot: u64,
+    // base fee of the block, none on chains without one
+    pub base_fee: Option<U256>,
+    // runs contract transactions, none on chains without contracts
+    pub contracts: Option<&'a dyn ContractRuntime>,
+}
+
+impl Default for BlockEnv<'_> {
+    fn default() -> Self {
+        Self {
+            chain_id: CHAIN_ID,
+            slot: 0,
+            base_fee: None,
+            contracts: None,
+        }
+    }
+}
+
+// how a transaction went in a block
+#[derive(Debug, Clone)]
+pub enum TransactionOutcome {
+    Success {
+        gas_used: U256,
+        output: Bytes,
+    },
+    // failed while executing, nothing it did is kept but its sender pays for `gas_used`
+    // and its nonce advances
+    Failed {
+        gas_used: U256,
+        error: StateTransitionError,
+    },
+    // could not be executed at all, a wrong nonce or a fee it can't pay, nothing is charged
+    Invalid(StateTransitionError),
+}
+
+impl TransactionOutcome {
+    pub fn gas_used(&self) -> U256 {
+        match self {
+            TransactionOutcome::Success { gas_used, .. }
+            | TransactionOutcome::Failed { gas_used, .. } => *gas_used,
+            TransactionOutcome::Invalid(_) => U256::ZERO,
+        }
+    }
+}
+
 impl StateTransition {
     pub fn apply_transaction(
         state: &mut StateManager,
         tx: &mut Transaction,
         config: &GasConfig,
     ) -> Result<U256, StateTransitionError> {
-        println!(
-            "🔄 Processing: {} → {}, amount: {}, gas_limit: {}, gas_price: {}",
-            tx.from, tx.to, tx.amount, tx.gas_limit, tx.gas_price
-        );
+        Self::apply_transaction_with_output(state, tx, config).map(|(gas_used, _)| gas_used)
+    }
 
+    // gas used and what a precompile call returned, empty for other transactions
+    pub fn apply_transaction_with_output(
+        state: &mut StateManager,
+        tx: &mut Transaction,
+        config: &GasConfig,
+    ) -> Result<(U256, Bytes), StateTransitionError> {
+        Self::apply_transaction_in_block(state, tx, config, &BlockEnv::default())
+    }
+
+    // like `apply_transaction_with_output`, priced at the block's base fee, contract
+    // transactions run on its contract runtime and fail without one
+    pub fn apply_transaction_in_block(
+        state: &mut StateManager,
+        tx: &mut Transaction,
+        config: &GasConfig,
+        env: &BlockEnv,
+    ) -> Result<(U256, Bytes), StateTransitionError> {
+        let (sender, intrinsic_gas) = Self::check(state, tx, config, env)?;
+        Self::execute_checked(state, tx, env, sender, intrinsic_gas)
+    }
+
+    // what a transaction does in a block, unlike `apply_transaction_in_block` one that fails
+    // while executing is still charged the gas it used and uses up its nonce
+    pub fn execute_transaction(
+        state: &mut StateManager,
+        tx: &mut Transaction,
+        config: &GasConfig,
+        env: &BlockEnv,
+    ) -> TransactionOutcome {
+        let (sender, intrinsic_gas) = match Self::check(state, tx, config, env) {
+            Ok(checked) => checked,
+            Err(error) => return TransactionOutcome::Invalid(error),
+        };
+        match Self::execute_checked(state, tx, env, sender.clone(), intrinsic_gas) {
+            Ok((gas_used, output)) => TransactionOutcome::Success { gas_used, output },
+            Err(error @ StateTransitionError::DebitExceedsBalance { .. }) => {
+                TransactionOutcome::Invalid(error)
+            }
+            Err(error) => {
+                let gas_used = match &error {
+                    StateTransitionError::ContractFailed { gas_used, .. } => *gas_used,
+                    _ => intrinsic_gas,
+                };
+                // nothing it did was written, the sender could afford its max cost, a fee
+                // that still doesn't fit makes the transaction invalid instead of charged
+                let mut sender = sender;
+                let Some(fee) = gas_used.checked_mul(effective_gas_price(tx, env.base_fee)) else {
+                    return TransactionOutcome::Invalid(StateTransitionError::FeeOverflow);
+                };
+                if let Err(error) = Self::debit(&mut sender, fee) {
+                    return TransactionOutcome::Invalid(error);
+                }
+                sender.nonce += 1;
+                state.set_account(tx.from, sender);
+                println!(
+                    "⛽ {} charged {} gas for a failed transaction",
+                    tx.from, gas_used
+                );
+                TransactionOutcome::Failed { gas_used, error }
+            }
+        }
+    }
+
+    // checks that need no state, returns the intrinsic gas
+    // a transaction failing them can't be charged, proposers never include it
+    pub fn check_transaction(
+        tx: &Transaction,
+        config: &GasConfig,
+        base_fee: Option<U256>,
+    ) -> Result<U256, StateTransitionError> {
         // Gas price config validation
         if !GasCalculator::validate_gas_price(tx.gas_price, config) {
             return Err(StateTransitionError::GasPriceTooLow);
         }
 
+        if let Some(base_fee) = base_fee
+            && tx.gas_price < base_fee
+        {
+            return Err(StateTransitionError::MaxFeeBelowBaseFee {
+                max_fee: tx.gas_price,
+                base_fee,
+            });
+        }
+        if let Some(priority_fee) = tx.max_priority_fee_per_gas
+            && priority_fee > tx.gas_price
+        {
+            return Err(StateTransitionError::PriorityFeeAboveMaxFee {
+                priority_fee,
+                max_fee: tx.gas_price,
+            });
+        }
+
         // Gas limit config validation
         if !GasCalculator::validate_gas_limit(tx.gas_limit, config) {
             return Err(StateTransitionError::InvalidGasLimit);
         }
+        // the max cost would wrap, a sender could pass the balance check with a tiny balance
+        if tx.gas_limit.checked_mul(tx.gas_price).is_none() {
+            return Err(StateTransitionError::FeeOverflow);
+        }
+
+        if !GasCalculator::validate_tx_size(tx, config) {
+            return Err(StateTransitionError::TransactionTooLarge {
+                size: tx.encoded_size(),
+                max: config.max_tx_size,
+            });
+        }
+
+        if !tx.has_valid_memo() {
+            return Err(StateTransitionError::MemoTooLong {
+                length: tx.memo.len(),
+                max: MAX_MEMO_LENGTH,
+            });
+        }
 
-        let intrinsic_gas = GasCalculator::calculate_instrinsic_gas(config);
+        let intrinsic_gas = GasCalculator::calculate_instrinsic_gas(tx, config);
         if tx.gas_limit < intrinsic_gas {
             return Err(StateTransitionError::InsufficientGas {
                 provided: tx.gas_limit,
@@ -37,18 +206,38 @@ impl StateTransition {
         }
 
         // STEP 1: Basic validation
-        if tx.from == tx.to {
+        if tx.kind == TransactionKind::Transfer && tx.from == tx.to {
             return Err(StateTransitionError::SameAddress);
         }
 
-        let mut sender = state.get_account(&tx.from);
-        let mut recipient = state.get_account(&tx.to);
+        Ok(intrinsic_gas)
+    }
+
+    // the sender's account, when it can pay the transaction's max cost at its nonce
+    fn check(
+        state: &StateManager,
+        tx: &Transaction,
+        config: &GasConfig,
+        env: &BlockEnv,
+    ) -> Result<(Account, U256), StateTransitionError> {
+        println!(
+            "🔄 Processing: {} → {}, amount: {}, gas_limit: {}, gas_price: {}",
+            tx.from, tx.to, tx.amount, tx.gas_limit, tx.gas_price
+        );
+        if tx.chain_id != env.chain_id {
+            return Err(StateTransitionError::WrongChain {
+                expected: env.chain_id,
+                got: tx.chain_id,
+            });
+        }
+        let intrinsic_gas = Self::check_transaction(tx, config, env.base_fee)?;
+
+        let sender = state.get_account(&tx.from);
 
         println!(
             "📖 Sender: balance={}, nonce={}",
             sender.balance, sender.nonce
         );
-        println!("📖 Recipient: balance={}", recipient.balance);
 
         // Check sender can afford maximum possible cost
         let max_cost = tx.max_transaction_cost();
@@ -75,22 +264,311 @@ impl StateTransition {
             });
         }
 
+        Ok((sender, intrinsic_gas))
+    }
+
+    // applies a checked transaction, nothing is written when it fails
+    fn execute_checked(
+        state: &mut StateManager,
+        tx: &mut Transaction,
+        env: &BlockEnv,
+        sender: Account,
+        intrinsic_gas: U256,
+    ) -> Result<(U256, Bytes), StateTransitionError> {
+        // an error may come after some writes, they are undone
+        state.checkpoint();
+        let result = Self::apply_checked(state, tx, env, sender, intrinsic_gas);
+        if result.is_ok() {
+            state.discard_checkpoint();
+        } else {
+            state.revert();
+        }
+        result
+    }
+
+    fn apply_checked(
+        state: &mut StateManager,
+        tx: &mut Transaction,
+        env: &BlockEnv,
+        sender: Account,
+        intrinsic_gas: U256,
+    ) -> Result<(U256, Bytes), StateTransitionError> {
+        // contract calls add the fuel they burned
+        let mut gas_used = intrinsic_gas;
+        let gas_price = effective_gas_price(tx, env.base_fee);
+        let gas_cost = gas_used * gas_price;
+
+        // STEP 4: Apply state changes, depending on transaction kind
+        let mut output = Bytes::new();
+        match &tx.kind {
+            TransactionKind::Transfer => {
+                Self::check_not_reserved(&tx.to)?;
+                Self::apply_transfer(state, tx, sender, gas_cost)?;
+            }
+            TransactionKind::RegisterNetworkAddress { multiaddr } => {
+                Self::apply_register_network_address(state, tx, sender, gas_cost, multiaddr)?;
+            }
+            TransactionKind::Approve { spender, allowance } => {
+                Self::apply_approve(state, tx, sender, gas_cost, *spender, *allowance)?;
+            }
+            TransactionKind::TransferFrom { owner } => {
+                Self::apply_transfer_from(state, tx, sender, gas_cost, *owner)?;
+            }
+            TransactionKind::StakeTopUp => {
+                Self::apply_stake_top_up(state, tx, sender, gas_cost)?;
+            }
+            TransactionKind::StakeWithdraw { amount } => {
+                Self::apply_stake_withdraw(state, tx, sender, gas_cost, *amount)?;
+            }
+            TransactionKind::RegisterValidator => {
+                Self::apply_register_validator(state, tx, sender, gas_cost)?;
+            }
+            TransactionKind::Unstake => {
+                Self::apply_unstake(state, tx, sender, gas_cost)?;
+            }
+            TransactionKind::BatchTransfer { outputs } => {
+                Self::apply_batch_transfer(state, tx, sender, gas_cost, outputs)?;
+            }
+            TransactionKind::RegisterName { name } => {
+                Self::apply_register_name(state, tx, sender, gas_cost, name)?;
+            }
+            TransactionKind::TransferName { name } => {
+                Self::apply_transfer_name(state, tx, sender, gas_cost, name)?;
+            }
+            TransactionKind::Call { input } => {
+                output = Self::apply_call(state, tx, gas_cost, input)?;
+            }
+            TransactionKind::AuthorizeSessionKey {
+                session_key,
+                max_value,
+                expiry_slot,
+            } => {
+                Self::apply_authorize_session_key(
+                    state,
+                    tx,
+                    sender,
+                    gas_cost,
+                    *session_key,
+                    *max_value,
+                    *expiry_slot,
+                )?;
+            }
+            TransactionKind::DeployContract { code } => {
+                let contracts = env
+                    .contracts
+                    .ok_or(StateTransitionError::ContractsDisabled)?;
+                Self::apply_deploy_contract(state, tx, sender, gas_cost, code, contracts)?;
+            }
+            TransactionKind::CallContract { input } => {
+                let contracts = env
+                    .contracts
+                    .ok_or(StateTransitionError::ContractsDisabled)?;
+                let (call_gas, call_output) = Self::apply_call_contract(
+                    state,
+                    tx,
+                    sender,
+                    intrinsic_gas,
+                    gas_price,
+                    input,
+                    contracts,
+                )?;
+                gas_used = call_gas;
+                output = call_output;
+            }
+        }
+
+        println!(
+            "🌳 New state root: 0x{}",
+            hex::encode(state.get_state_root())
+        );
+
+        Ok((gas_used, output))
+    }
+
+    // credit the gas an applied transaction paid to the block's fee recipient, its proposer
+    // the base fee part is burned, only the tip is paid
+    pub fn pay_fee(
+        state: &mut StateManager,
+        tx: &Transaction,
+        gas_used: U256,
+        base_fee: Option<U256>,
+        fee_recipient: Address,
+    ) -> U256 {
+        let tip = effective_gas_price(tx, base_fee).saturating_sub(base_fee.unwrap_or(U256::ZERO));
+        let fee = gas_used.saturating_mul(tip);
+        if fee > U256::ZERO {
+            state.fund_account(&fee_recipient, fee);
+        }
+        fee
+    }
+
+    // transaction of `tx.from` signed by one of its session keys in a block at `env.slot`,
+    // what it spends in value and fees is charged to the key
+    pub fn apply_session_transaction(
+        state: &mut StateManager,
+        tx: &mut Transaction,
+        config: &GasConfig,
+        session_key: Address,
+        env: &BlockEnv,
+    ) -> Result<(U256, Bytes), StateTransitionError> {
+        Self::check_session_key(state, tx, session_key, env.slot)?;
+        let (gas_used, output) = Self::apply_transaction_in_block(state, tx, config, env)?;
+
+        let spent = gas_used.saturating_mul(effective_gas_price(tx, env.base_fee));
+        Self::record_session_spend(
+            state,
+            tx,
+            session_key,
+            tx.value_from_sender().saturating_add(spent),
+        );
+
+        Ok((gas_used, output))
+    }
+
+    // `execute_transaction` for a transaction signed by one of the sender's session keys,
+    // invalid when the key may not sign it, a failed one still charges its gas to the key
+    pub fn execute_session_transaction(
+        state: &mut StateManager,
+        tx: &mut Transaction,
+        config: &GasConfig,
+        session_key: Address,
+        env: &BlockEnv,
+    ) -> TransactionOutcome {
+        if let Err(error) = Self::check_session_key(state, tx, session_key, env.slot) {
+            return TransactionOutcome::Invalid(error);
+        }
+        let outcome = Self::execute_transaction(state, tx, config, env);
+        let fee = outcome
+            .gas_used()
+            .saturating_mul(effective_gas_price(tx, env.base_fee));
+        match &outcome {
+            TransactionOutcome::Success { .. } => Self::record_session_spend(
+                state,
+                tx,
+                session_key,
+                tx.value_from_sender().saturating_add(fee),
+            ),
+            TransactionOutcome::Failed { .. } => {
+                Self::record_session_spend(state, tx, session_key, fee)
+            }
+            TransactionOutcome::Invalid(_) => {}
+        }
+        outcome
+    }
+
+    fn record_session_spend(
+        state: &mut StateManager,
+        tx: &Transaction,
+        session_key: Address,
+        amount: U256,
+    ) {
+        let mut session = state
+            .get_session_key(&tx.from, &session_key)
+            .cloned()
+            .expect("checked before executing");
+        session.spent = session.spent.saturating_add(amount);
+        state.set_session_key(tx.from, session_key, Some(session));
+    }
+
+    // the key is authorized by `tx.from`, unexpired at `slot`, allowed to sign this kind of
+    // transaction and has enough left for its maximum cost
+    pub fn check_session_key(
+        state: &StateManager,
+        tx: &Transaction,
+        session_key: Address,
+        slot: u64,
+    ) -> Result<(), StateTransitionError> {
+        let session = state
+            .get_session_key(&tx.from, &session_key)
+            .ok_or_else(|| StateTransitionError::SessionKeyRejected {
+                key: session_key,
+                reason: format!("not authorized by {}", tx.from),
+            })?;
+        Self::check_session_bounds(session, tx, session_key, slot)
+    }
+
+    // the bounds alone, once the key is found
+    fn check_session_bounds(
+        session: &SessionKey,
+        tx: &Transaction,
+        session_key: Address,
+        slot: u64,
+    ) -> Result<(), StateTransitionError> {
+        let rejected = |reason: String| StateTransitionError::SessionKeyRejected {
+            key: session_key,
+            reason,
+        };
+        if slot > session.expiry_slot {
+            return Err(rejected(format!("expired at slot {}", session.expiry_slot)));
+        }
+        // anything that could hand out more than the key's budget needs the account key
+        if !matches!(
+            tx.kind,
+            TransactionKind::Transfer
+                | TransactionKind::BatchTransfer { .. }
+                | TransactionKind::Call { .. }
+        ) {
+            return Err(rejected(
+                "only transfers and calls can be signed by a session key".to_string(),
+            ));
+        }
+        let max_cost = tx.max_transaction_cost();
+        if max_cost > session.remaining() {
+            return Err(rejected(format!(
+                "{} left, transaction may spend {}",
+                session.remaining(),
+                max_cost
+            )));
+        }
+        Ok(())
+    }
+
+    // take `amount` from `account`, every debit is covered by the max cost checked before
+    // executing, so falling short makes the transaction invalid rather than failed
+    fn debit(account: &mut Account, amount: U256) -> Result<(), StateTransitionError> {
+        account.balance = account.balance.checked_sub(amount).ok_or(
+            StateTransitionError::DebitExceedsBalance {
+                has: account.balance,
+                needs: amount,
+            },
+        )?;
+        Ok(())
+    }
+
+    // value can't be sent to a precompile address, whichever way it is paid
+    fn check_not_reserved(to: &Address) -> Result<(), StateTransitionError> {
+        if is_reserved_address(to) {
+            return Err(StateTransitionError::InvalidPayload(format!(
+                "{} is reserved for precompiles, value can't be sent to it",
+                to
+            )));
+        }
+        Ok(())
+    }
+
+    // move amount from sender to recipient, sender pays for gas
+    fn apply_transfer(
+        state: &mut StateManager,
+        tx: &Transaction,
+        mut sender: Account,
+        gas_cost: U256,
+    ) -> Result<(), StateTransitionError> {
+        let mut recipient = state.get_account(&tx.to);
+        println!("📖 Recipient: balance={}", recipient.balance);
+
         // 3c. Prevent integer overflow
-        if recipient.balance.checked_add(tx.amount).is_none() {
+        let Some(recipient_balance) = recipient.balance.checked_add(tx.amount) else {
             println!("❌ Overflow attack attempt!");
             return Err(StateTransitionError::BalanceOverflow);
-        }
+        };
 
-        let gas_used = intrinsic_gas;
-        let gas_cost = gas_used * tx.gas_price;
-        let total_cost = tx.amount + gas_cost;
+        let total_cost = tx.amount.saturating_add(gas_cost);
 
-        // STEP 4: Apply state changes
         sender.nonce += 1;
         // deduct total cost from sender
-        sender.balance = sender.balance.checked_sub(total_cost).unwrap();
+        Self::debit(&mut sender, total_cost)?;
         // add amount to recipient
-        recipient.balance = recipient.balance.checked_add(tx.amount).unwrap();
+        recipient.balance = recipient_balance;
 
         println!(
             "✅ New balances - Sender: {}, Recipient: {}",
@@ -100,11 +578,606 @@ impl StateTransition {
         state.set_account(tx.from, sender);
         state.set_account(tx.to, recipient);
 
+        Ok(())
+    }
+
+    // pay every output from the sender, nothing is written unless all of them can be paid
+    fn apply_batch_transfer(
+        state: &mut StateManager,
+        tx: &Transaction,
+        mut sender: Account,
+        gas_cost: U256,
+        outputs: &[TransferOutput],
+    ) -> Result<(), StateTransitionError> {
+        if tx.amount != U256::ZERO {
+            return Err(StateTransitionError::InvalidPayload(
+                "batch transfer carries its value in the outputs".to_string(),
+            ));
+        }
+        if outputs.is_empty() {
+            return Err(StateTransitionError::InvalidPayload(
+                "batch transfer needs at least one output".to_string(),
+            ));
+        }
+        if outputs.iter().any(|output| output.to == tx.from) {
+            return Err(StateTransitionError::SameAddress);
+        }
+        for output in outputs {
+            Self::check_not_reserved(&output.to)?;
+        }
+
+        // a recipient may appear more than once, credits are summed before checking
+        let mut credits: BTreeMap<Address, U256> = BTreeMap::new();
+        let mut total = U256::ZERO;
+        for output in outputs {
+            total = total
+                .checked_add(output.amount)
+                .ok_or(StateTransitionError::BalanceOverflow)?;
+            // never above the total, which did not overflow
+            let credit = credits.entry(output.to).or_insert(U256::ZERO);
+            *credit = credit.saturating_add(output.amount);
+        }
+
+        let mut recipients = Vec::with_capacity(credits.len());
+        for (address, credit) in credits {
+            let mut recipient = state.get_account(&address);
+            recipient.balance = match recipient.balance.checked_add(credit) {
+                Some(balance) => balance,
+                None => {
+                    println!("❌ Overflow attack attempt!");
+                    return Err(StateTransitionError::BalanceOverflow);
+                }
+            };
+            recipients.push((address, recipient));
+        }
+
+        // max_transaction_cost already covered total and gas
+        sender.nonce += 1;
+        Self::debit(&mut sender, total.saturating_add(gas_cost))?;
+        state.set_account(tx.from, sender);
+
+        for (address, recipient) in recipients {
+            state.set_account(address, recipient);
+        }
+
+        println!("📦 {} paid {} to {} outputs", tx.from, total, outputs.len());
+
+        Ok(())
+    }
+
+    // record the sender's p2p multiaddr on-chain, sender only pays for gas
+    fn apply_register_network_address(
+        state: &mut StateManager,
+        tx: &Transaction,
+        mut sender: Account,
+        gas_cost: U256,
+        multiaddr: &str,
+    ) -> Result<(), StateTransitionError> {
+        if tx.amount != U256::ZERO {
+            return Err(StateTransitionError::InvalidPayload(
+                "network address registration cannot carry value".to_string(),
+            ));
+        }
+
+        if multiaddr.len() > MAX_MULTIADDR_LENGTH {
+            return Err(StateTransitionError::InvalidPayload(format!(
+                "multiaddr longer than {} bytes",
+                MAX_MULTIADDR_LENGTH
+            )));
+        }
+
+        multiaddr.parse::<Multiaddr>().map_err(|e| {
+            StateTransitionError::InvalidPayload(format!("invalid multiaddr: {}", e))
+        })?;
+
+        sender.nonce += 1;
+        Self::debit(&mut sender, gas_cost)?;
+
+        println!("📇 {} registered network address {}", tx.from, multiaddr);
+
+        state.set_account(tx.from, sender);
+        state.set_network_address(tx.from, multiaddr.to_string());
+
+        Ok(())
+    }
+
+    // `a-z`, `0-9` and inner `-` only, so a name never parses as an address
+    pub fn validate_name(name: &str) -> Result<(), StateTransitionError> {
+        if name.len() < MIN_NAME_LENGTH || name.len() > MAX_NAME_LENGTH {
+            return Err(StateTransitionError::InvalidPayload(format!(
+                "name must be {} to {} characters",
+                MIN_NAME_LENGTH, MAX_NAME_LENGTH
+            )));
+        }
+        let allowed = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-';
+        if !name.chars().all(allowed) || name.starts_with('-') || name.ends_with('-') {
+            return Err(StateTransitionError::InvalidPayload(format!(
+                "invalid name '{}', use a-z, 0-9 and inner '-'",
+                name
+            )));
+        }
+        if name.starts_with("0x") {
+            return Err(StateTransitionError::InvalidPayload(
+                "name cannot start with 0x".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    // run the precompile at `to`, its gas is part of the intrinsic gas
+    // precompiles leave the state untouched when they fail, which fails the transaction
+    fn apply_call(
+        state: &mut StateManager,
+        tx: &Transaction,
+        gas_cost: U256,
+        input: &[u8],
+    ) -> Result<Bytes, StateTransitionError> {
+        if tx.amount != U256::ZERO {
+            return Err(StateTransitionError::InvalidPayload(
+                "precompile calls cannot carry value".to_string(),
+            ));
+        }
+        let precompile = precompiles()
+            .get(&tx.to)
+            .ok_or(StateTransitionError::NoPrecompile(tx.to))?;
+
+        let output = precompile.call(state, tx.from, input).map_err(|reason| {
+            StateTransitionError::PrecompileFailed {
+                name: precompile.name().to_string(),
+                reason,
+            }
+        })?;
+
+        // read after the call, the precompile may have written the sender's account
+        let mut sender = state.get_account(&tx.from);
+        sender.nonce += 1;
+        Self::debit(&mut sender, gas_cost)?;
+
         println!(
-            "🌳 New state root: 0x{}",
-            hex::encode(state.get_state_root())
+            "🧩 {} called the {} precompile, {} bytes returned",
+            tx.from,
+            precompile.name(),
+            output.len()
+        );
+
+        state.set_account(tx.from, sender);
+
+        Ok(output)
+    }
+
+    // store validated contract code at the address derived from the sender and its nonce,
+    // the code is paid for by the intrinsic gas per byte
+    fn apply_deploy_contract(
+        state: &mut StateManager,
+        tx: &Transaction,
+        mut sender: Account,
+        gas_cost: U256,
+        code: &Bytes,
+        contracts: &dyn ContractRuntime,
+    ) -> Result<(), StateTransitionError> {
+        if code.is_empty() {
+            return Err(StateTransitionError::InvalidPayload(
+                "contract code is empty".to_string(),
+            ));
+        }
+        contracts.validate(code).map_err(|reason| {
+            StateTransitionError::InvalidPayload(format!("contract code: {}", reason))
+        })?;
+
+        let address = contract_address(&tx.from, tx.nonce);
+        let mut contract = state.get_account(&address);
+        contract.balance = contract
+            .balance
+            .checked_add(tx.amount)
+            .ok_or(StateTransitionError::BalanceOverflow)?;
+
+        sender.nonce += 1;
+        Self::debit(&mut sender, tx.amount.saturating_add(gas_cost))?;
+        state.set_account(tx.from, sender);
+        state.set_account(address, contract);
+        state.set_contract_code(address, code.clone());
+
+        println!(
+            "📜 {} deployed a {} byte {} contract at {}",
+            tx.from,
+            code.len(),
+            contracts.name(),
+            address
+        );
+
+        Ok(())
+    }
+
+    // run the contract at `to` with the value sent, the gas left after the intrinsic gas is its
+    // fuel; returns the gas used, intrinsic plus burned fuel, and the call's output
+    // nothing is written when the call fails
+    fn apply_call_contract(
+        state: &mut StateManager,
+        tx: &Transaction,
+        mut sender: Account,
+        intrinsic_gas: U256,
+        gas_price: U256,
+        input: &Bytes,
+        contracts: &dyn ContractRuntime,
+    ) -> Result<(U256, Bytes), StateTransitionError> {
+        let code = state
+            .get_contract_code(&tx.to)
+            .cloned()
+            .ok_or(StateTransitionError::NoContract(tx.to))?;
+        let mut contract = state.get_account(&tx.to);
+        let balance = contract
+            .balance
+            .checked_add(tx.amount)
+            .ok_or(StateTransitionError::BalanceOverflow)?;
+
+        let fuel = (tx.gas_limit - intrinsic_gas).saturating_to::<u64>();
+        let outcome = contracts
+            .call(ContractCall {
+                contract: tx.to,
+                caller: tx.from,
+                input,
+                code: &code,
+                balance,
+                storage: state.get_contract_storage(&tx.to),
+                fuel,
+            })
+            .map_err(|failure| StateTransitionError::ContractFailed {
+                contract: tx.to,
+                reason: failure.reason,
+                gas_used: intrinsic_gas + U256::from(failure.fuel_used),
+            })?;
+
+        let gas_used = intrinsic_gas + U256::from(outcome.fuel_used);
+        // the runtime never pays out more than the balance it was given, a call that did
+        // fails rather than wrap the contract's balance
+        let overdrawn = || StateTransitionError::ContractFailed {
+            contract: tx.to,
+            reason: "payouts exceed the contract balance".to_string(),
+            gas_used,
+        };
+
+        // payouts are summed per recipient and checked before anything is written
+        let mut credits: BTreeMap<Address, U256> = BTreeMap::new();
+        let mut paid = U256::ZERO;
+        for (to, amount) in &outcome.transfers {
+            Self::check_not_reserved(to)?;
+            paid = paid.checked_add(*amount).ok_or_else(overdrawn)?;
+            // never above the total, which did not overflow
+            let credit = credits.entry(*to).or_insert(U256::ZERO);
+            *credit = credit.saturating_add(*amount);
+        }
+        let remaining = balance.checked_sub(paid).ok_or_else(overdrawn)?;
+        for (to, credit) in &credits {
+            if state.get_balance(to).checked_add(*credit).is_none() {
+                return Err(StateTransitionError::BalanceOverflow);
+            }
+        }
+
+        sender.nonce += 1;
+        Self::debit(
+            &mut sender,
+            tx.amount.saturating_add(gas_used.saturating_mul(gas_price)),
+        )?;
+        state.set_account(tx.from, sender);
+        contract.balance = remaining;
+        state.set_account(tx.to, contract);
+        state.set_contract_storage(tx.to, outcome.storage);
+        for (to, credit) in credits {
+            state.fund_account(&to, credit);
+        }
+
+        println!(
+            "📜 {} called contract {}, {} fuel burned, {} bytes returned",
+            tx.from,
+            tx.to,
+            outcome.fuel_used,
+            outcome.output.len()
+        );
+
+        Ok((gas_used, outcome.output))
+    }
+
+    // claim a free name for the sender, the length fee is part of the intrinsic gas
+    fn apply_register_name(
+        state: &mut StateManager,
+        tx: &Transaction,
+        mut sender: Account,
+        gas_cost: U256,
+        name: &str,
+    ) -> Result<(), StateTransitionError> {
+        if tx.amount != U256::ZERO {
+            return Err(StateTransitionError::InvalidPayload(
+                "name registration cannot carry value".to_string(),
+            ));
+        }
+        Self::validate_name(name)?;
+        if state.resolve_name(name).is_some() {
+            return Err(StateTransitionError::NameTaken(name.to_string()));
+        }
+
+        sender.nonce += 1;
+        Self::debit(&mut sender, gas_cost)?;
+
+        println!("🏷️  {} registered the name {}", tx.from, name);
+
+        state.set_account(tx.from, sender);
+        state.set_name_owner(name.to_string(), tx.from);
+
+        Ok(())
+    }
+
+    // hand a name the sender owns over to `to`
+    fn apply_transfer_name(
+        state: &mut StateManager,
+        tx: &Transaction,
+        mut sender: Account,
+        gas_cost: U256,
+        name: &str,
+    ) -> Result<(), StateTransitionError> {
+        if tx.amount != U256::ZERO {
+            return Err(StateTransitionError::InvalidPayload(
+                "name transfer cannot carry value".to_string(),
+            ));
+        }
+        if state.resolve_name(name) != Some(tx.from) {
+            return Err(StateTransitionError::NotNameOwner(name.to_string()));
+        }
+        if tx.to == Address::ZERO || tx.to == tx.from {
+            return Err(StateTransitionError::InvalidPayload(
+                "name transfer needs a new owner".to_string(),
+            ));
+        }
+
+        sender.nonce += 1;
+        Self::debit(&mut sender, gas_cost)?;
+
+        println!("🏷️  {} transferred the name {} to {}", tx.from, name, tx.to);
+
+        state.set_account(tx.from, sender);
+        state.set_name_owner(name.to_string(), tx.to);
+
+        Ok(())
+    }
+
+    // lock `amount` from the sender's balance, the validator set picks it up at the epoch end
+    fn apply_stake_top_up(
+        state: &mut StateManager,
+        tx: &Transaction,
+        mut sender: Account,
+        gas_cost: U256,
+    ) -> Result<(), StateTransitionError> {
+        if tx.amount == U256::ZERO {
+            return Err(StateTransitionError::InvalidPayload(
+                "stake top-up needs a non-zero amount".to_string(),
+            ));
+        }
+
+        sender.nonce += 1;
+        Self::debit(&mut sender, tx.amount.saturating_add(gas_cost))?;
+
+        println!("🥩 {} queued a stake top-up of {}", tx.from, tx.amount);
+
+        state.set_account(tx.from, sender);
+        state.queue_stake_change(tx.from, tx.amount, U256::ZERO);
+
+        Ok(())
+    }
+
+    // lock `amount` as the stake of a new validator, it joins the set at the epoch end
+    // the minimum stake is checked then, a registration below it is refunded
+    fn apply_register_validator(
+        state: &mut StateManager,
+        tx: &Transaction,
+        mut sender: Account,
+        gas_cost: U256,
+    ) -> Result<(), StateTransitionError> {
+        if tx.amount == U256::ZERO {
+            return Err(StateTransitionError::InvalidPayload(
+                "validator registration needs a non-zero stake".to_string(),
+            ));
+        }
+
+        sender.nonce += 1;
+        Self::debit(&mut sender, tx.amount.saturating_add(gas_cost))?;
+
+        println!(
+            "🥩 {} queued a validator registration with {} stake",
+            tx.from, tx.amount
+        );
+
+        state.set_account(tx.from, sender);
+        state.queue_validator_registration(tx.from, tx.amount);
+
+        Ok(())
+    }
+
+    // queue the sender's exit from the validator set, the stake is paid back once it left
+    fn apply_unstake(
+        state: &mut StateManager,
+        tx: &Transaction,
+        mut sender: Account,
+        gas_cost: U256,
+    ) -> Result<(), StateTransitionError> {
+        if tx.amount != U256::ZERO {
+            return Err(StateTransitionError::InvalidPayload(
+                "unstake cannot carry value".to_string(),
+            ));
+        }
+
+        sender.nonce += 1;
+        Self::debit(&mut sender, gas_cost)?;
+
+        println!("🥩 {} queued its exit from the validator set", tx.from);
+
+        state.set_account(tx.from, sender);
+        state.queue_validator_exit(tx.from);
+
+        Ok(())
+    }
+
+    // queue a partial withdrawal, the stake is released and credited at the epoch end
+    fn apply_stake_withdraw(
+        state: &mut StateManager,
+        tx: &Transaction,
+        mut sender: Account,
+        gas_cost: U256,
+        amount: U256,
+    ) -> Result<(), StateTransitionError> {
+        if tx.amount != U256::ZERO {
+            return Err(StateTransitionError::InvalidPayload(
+                "stake withdrawal cannot carry value".to_string(),
+            ));
+        }
+        if amount == U256::ZERO {
+            return Err(StateTransitionError::InvalidPayload(
+                "stake withdrawal needs a non-zero amount".to_string(),
+            ));
+        }
+
+        sender.nonce += 1;
+        Self::debit(&mut sender, gas_cost)?;
+
+        println!("🥩 {} queued a stake withdrawal of {}", tx.from, amount);
+
+        state.set_account(tx.from, sender);
+        state.queue_stake_change(tx.from, U256::ZERO, amount);
+
+        Ok(())
+    }
+
+    // authorize or replace a session key of the sender, zero `max_value` revokes it
+    fn apply_authorize_session_key(
+        state: &mut StateManager,
+        tx: &Transaction,
+        mut sender: Account,
+        gas_cost: U256,
+        session_key: Address,
+        max_value: U256,
+        expiry_slot: u64,
+    ) -> Result<(), StateTransitionError> {
+        if tx.amount != U256::ZERO {
+            return Err(StateTransitionError::InvalidPayload(
+                "session key authorization cannot carry value".to_string(),
+            ));
+        }
+        if session_key == tx.from || session_key == Address::ZERO {
+            return Err(StateTransitionError::InvalidPayload(format!(
+                "{} cannot be a session key of {}",
+                session_key, tx.from
+            )));
+        }
+
+        sender.nonce += 1;
+        Self::debit(&mut sender, gas_cost)?;
+        state.set_account(tx.from, sender);
+
+        let session = (max_value > U256::ZERO).then_some(SessionKey {
+            max_value,
+            spent: U256::ZERO,
+            expiry_slot,
+        });
+        match &session {
+            Some(_) => println!(
+                "🔑 {} authorized session key {} for {} until slot {}",
+                tx.from, session_key, max_value, expiry_slot
+            ),
+            None => println!("🔑 {} revoked session key {}", tx.from, session_key),
+        }
+        state.set_session_key(tx.from, session_key, session);
+
+        Ok(())
+    }
+
+    // set how much `spender` may move from the sender, replaces any previous allowance
+    fn apply_approve(
+        state: &mut StateManager,
+        tx: &Transaction,
+        mut sender: Account,
+        gas_cost: U256,
+        spender: Address,
+        allowance: U256,
+    ) -> Result<(), StateTransitionError> {
+        if tx.amount != U256::ZERO {
+            return Err(StateTransitionError::InvalidPayload(
+                "approval cannot carry value".to_string(),
+            ));
+        }
+
+        sender.nonce += 1;
+        Self::debit(&mut sender, gas_cost)?;
+
+        println!("🤝 {} approved {} to spend {}", tx.from, spender, allowance);
+
+        state.set_account(tx.from, sender);
+        state.set_allowance(tx.from, spender, allowance);
+
+        Ok(())
+    }
+
+    // move amount from owner to recipient on behalf of the sender, sender pays for gas
+    fn apply_transfer_from(
+        state: &mut StateManager,
+        tx: &Transaction,
+        mut sender: Account,
+        gas_cost: U256,
+        owner: Address,
+    ) -> Result<(), StateTransitionError> {
+        Self::check_not_reserved(&tx.to)?;
+        let allowance = state.get_allowance(&owner, &tx.from);
+        if allowance < tx.amount {
+            return Err(StateTransitionError::InsufficientAllowance {
+                allowance,
+                needs: tx.amount,
+            });
+        }
+
+        // the sender may also be the owner, its gas is paid first
+        let owner_balance = if owner == tx.from {
+            let mut paid = sender.clone();
+            Self::debit(&mut paid, gas_cost)?;
+            paid.balance
+        } else {
+            state.get_balance(&owner)
+        };
+        if owner_balance < tx.amount {
+            return Err(StateTransitionError::InsufficientBalance {
+                has: owner_balance,
+                needs: tx.amount,
+            });
+        }
+
+        if owner != tx.to && state.get_balance(&tx.to).checked_add(tx.amount).is_none() {
+            println!("❌ Overflow attack attempt!");
+            return Err(StateTransitionError::BalanceOverflow);
+        }
+
+        // all checks passed, apply one account at a time as they may overlap
+        sender.nonce += 1;
+        Self::debit(&mut sender, gas_cost)?;
+        state.set_account(tx.from, sender);
+
+        let mut owner_account = state.get_account(&owner);
+        Self::debit(&mut owner_account, tx.amount)?;
+        state.set_account(owner, owner_account);
+
+        let mut recipient = state.get_account(&tx.to);
+        recipient.balance = recipient
+            .balance
+            .checked_add(tx.amount)
+            .ok_or(StateTransitionError::BalanceOverflow)?;
+        state.set_account(tx.to, recipient);
+
+        state.set_allowance(owner, tx.from, allowance - tx.amount);
+
+        println!(
+            "✅ {} moved {} from {} to {}, remaining allowance: {}",
+            tx.from,
+            tx.amount,
+            owner,
+            tx.to,
+            allowance - tx.amount
         );
 
-        Ok(gas_used)
+        Ok(())
     }
 }
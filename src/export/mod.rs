@@ -0,0 +1,3 @@
+pub mod ndjson;
+
+pub use ndjson::*;
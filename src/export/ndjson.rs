@@ -0,0 +1,189 @@
+use alloy::primitives::B256;
+use anyhow::{Context, Result, anyhow};
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::core::{Block, BlockHeader, Transaction};
+use crate::{AccountDiff, Receipt, Storage};
+
+// one line of the export, tagged with `type` so tables can be split on load
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExportRecord<'a> {
+    Transaction {
+        block_number: u64,
+        block_hash: B256,
+        index: usize,
+        transaction: &'a Transaction,
+    },
+    Receipt {
+        block_number: u64,
+        block_hash: B256,
+        receipt: &'a Receipt,
+    },
+    StateDiff {
+        block_number: u64,
+        block_hash: B256,
+        diff: &'a AccountDiff,
+    },
+    // written last for every block, marks the block as fully exported
+    Block {
+        block_number: u64,
+        block_hash: B256,
+        header: &'a BlockHeader,
+        transaction_count: usize,
+    },
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ExportSummary {
+    pub first_block: Option<u64>,
+    pub last_block: Option<u64>,
+    pub blocks: u64,
+    pub records: u64,
+}
+
+// exports stored blocks, transactions, receipts and state diffs as newline delimited json
+pub struct NdjsonExporter {
+    storage: Storage,
+}
+
+impl NdjsonExporter {
+    pub fn new(storage: Storage) -> Self {
+        Self { storage }
+    }
+
+    // export blocks `from..=to` (to defaults to the chain head) into `path`
+    // with `resume`, continue after the last complete block already in the file
+    pub fn export<P: AsRef<Path>>(
+        &self,
+        path: P,
+        from: u64,
+        to: Option<u64>,
+        resume: bool,
+    ) -> Result<ExportSummary> {
+        let path = path.as_ref();
+        let to = match to {
+            Some(to) => to,
+            None => self.storage.get_last_index()?.unwrap_or(0),
+        };
+
+        let mut start = from;
+        let file = if resume && path.exists() {
+            if let Some(last) = Self::truncate_to_last_block(path)? {
+                start = start.max(last + 1);
+                println!("⏩ Resuming export after block #{}", last);
+            }
+            OpenOptions::new().append(true).open(path)?
+        } else {
+            File::create(path)?
+        };
+        let mut writer = BufWriter::new(file);
+
+        let mut summary = ExportSummary::default();
+        for number in start..=to {
+            let Some(block_hash) = self.storage.get_block_hash_from_index(&number)? else {
+                continue;
+            };
+            let block: Block = self
+                .storage
+                .get_block_from_block_hash(&block_hash)?
+                .ok_or_else(|| anyhow!("Block data not found for #{}", number))?;
+
+            summary.records += self.write_block(&mut writer, number, block_hash, &block)?;
+            summary.blocks += 1;
+            summary.first_block.get_or_insert(number);
+            summary.last_block = Some(number);
+        }
+
+        writer.flush()?;
+        Ok(summary)
+    }
+
+    // write all records of one block, returns the number of lines written
+    fn write_block<W: Write>(
+        &self,
+        writer: &mut W,
+        block_number: u64,
+        block_hash: B256,
+        block: &Block,
+    ) -> Result<u64> {
+        let receipts = self
+            .storage
+            .get_block_receipts(&block_hash)?
+            .unwrap_or_default();
+        let state_diff = self
+            .storage
+            .get_block_state_diff(&block_hash)?
+            .unwrap_or_default();
+
+        let mut records = Vec::new();
+        for (index, transaction) in block.transactions.iter().enumerate() {
+            records.push(ExportRecord::Transaction {
+                block_number,
+                block_hash,
+                index,
+                transaction,
+            });
+        }
+        for receipt in &receipts {
+            records.push(ExportRecord::Receipt {
+                block_number,
+                block_hash,
+                receipt,
+            });
+        }
+        for diff in &state_diff {
+            records.push(ExportRecord::StateDiff {
+                block_number,
+                block_hash,
+                diff,
+            });
+        }
+        records.push(ExportRecord::Block {
+            block_number,
+            block_hash,
+            header: &block.header,
+            transaction_count: block.transactions.len(),
+        });
+
+        for record in &records {
+            serde_json::to_writer(&mut *writer, record)?;
+            writer.write_all(b"\n")?;
+        }
+
+        Ok(records.len() as u64)
+    }
+
+    // drop records of a partially exported block, returns the last complete block number
+    fn truncate_to_last_block(path: &Path) -> Result<Option<u64>> {
+        let reader = BufReader::new(File::open(path)?);
+
+        let mut offset = 0u64;
+        let mut complete_len = 0u64;
+        let mut last_block = None;
+        for line in reader.lines() {
+            let line = line?;
+            offset += line.len() as u64 + 1;
+
+            let value: serde_json::Value = match serde_json::from_str(&line) {
+                Ok(value) => value,
+                Err(_) => break, // torn last line
+            };
+            if value["type"] == "block" {
+                last_block = value["block_number"].as_u64();
+                complete_len = offset;
+            }
+        }
+
+        OpenOptions::new()
+            .write(true)
+            .open(path)?
+            .set_len(complete_len)
+            .context("Failed to truncate partial export")?;
+
+        Ok(last_block)
+    }
+}
@@ -1,26 +1,31 @@
 pub mod account;
+pub mod cli;
 pub mod common;
+pub mod config;
 pub mod consensus;
 pub mod core;
 pub mod crypto;
 pub mod execution;
+pub mod export;
 pub mod network;
 pub mod node;
+pub mod reindex;
 pub mod rpc;
-pub mod server;
 pub mod storage;
 
 // Re-export commonly used types for convenience
 pub use account::Account;
+pub use common::*;
+pub use config::*;
 pub use consensus::Validator;
-pub use core::{Block, Blockchain, Transaction};
-pub use crypto::{KeyPair, SignatureError};
+pub use core::{Block, Blockchain, MAX_MEMO_LENGTH, Transaction, TransactionKind, TransferOutput};
+pub use crypto::{DevAccount, KeyPair, SignatureError, dev_accounts, dev_keypairs};
 pub use execution::*;
-pub use rpc::SpeedRpcImpl;
-// pub use server::SpeedBlockchainServer;
-pub use common::*;
 pub use network::*;
 pub use node::*;
+pub use rpc::{
+    AdminRpcImpl, RelayConfig, RpcAccessConfig, RpcTlsConfig, SpeedRpcImpl, SubscriptionRpcImpl,
+};
 pub use storage::Storage;
 
 // Export anyhow::Result for convenience
@@ -1,10 +1,6 @@
 use anyhow::Result;
 
-// use speed_blockchain::server::SpeedBlockchainServer;
-use std::net::SocketAddr;
-
-// Database path for RocksDB
-const SERVER_ADDR: &str = "127.0.0.1:8545";
+use speed_blockchain::cli;
 
 fn print_banner() {
     println!(
@@ -23,17 +19,12 @@ fn print_banner() {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    print_banner();
-
-    let addr: SocketAddr = SERVER_ADDR.parse()?;
-    println!("✅ Blockchain initialized\n");
-
-    println!("\n🌐 Starting RPC server...");
-    // let server = SpeedBlockchainServer::new(DB_PATH.to_string(), DIFFICULTY, addr)?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
 
-    // This starts the server and runs forever (until Ctrl+C)
-    println!("\n✅ Server is running! Press Ctrl+C to stop.");
-    // server.run().await?;
+    // only show the banner when starting a node
+    if args.first().map(String::as_str) == Some("node") {
+        print_banner();
+    }
 
-    Ok(())
+    cli::run(&args).await
 }
@@ -0,0 +1,70 @@
+use anyhow::{Result, anyhow};
+use libp2p::PeerId;
+use tokio::sync::{mpsc::UnboundedSender, oneshot};
+
+use super::{PeerInfo, PeerStats};
+
+// operator requests handled inside the network task
+#[derive(Debug)]
+pub enum NetworkAdminCommand {
+    ListPeers {
+        reply: oneshot::Sender<Vec<PeerInfo>>,
+    },
+    PeerStats {
+        reply: oneshot::Sender<Vec<PeerStats>>,
+    },
+    BanPeer {
+        peer_id: PeerId,
+        reply: oneshot::Sender<bool>,
+    },
+    UnbanPeer {
+        peer_id: PeerId,
+        reply: oneshot::Sender<bool>,
+    },
+}
+
+// cloneable handle to the network task, used by the admin rpc
+#[derive(Debug, Clone)]
+pub struct NetworkAdmin {
+    sender: UnboundedSender<NetworkAdminCommand>,
+}
+
+impl NetworkAdmin {
+    pub fn new(sender: UnboundedSender<NetworkAdminCommand>) -> Self {
+        Self { sender }
+    }
+
+    // connected and banned peers
+    pub async fn peers(&self) -> Result<Vec<PeerInfo>> {
+        let (reply, response) = oneshot::channel();
+        self.send(NetworkAdminCommand::ListPeers { reply })?;
+        Ok(response.await?)
+    }
+
+    // gossip received per peer and message type
+    pub async fn peer_stats(&self) -> Result<Vec<PeerStats>> {
+        let (reply, response) = oneshot::channel();
+        self.send(NetworkAdminCommand::PeerStats { reply })?;
+        Ok(response.await?)
+    }
+
+    // disconnect a peer and refuse new connections from it, false if already banned
+    pub async fn ban_peer(&self, peer_id: PeerId) -> Result<bool> {
+        let (reply, response) = oneshot::channel();
+        self.send(NetworkAdminCommand::BanPeer { peer_id, reply })?;
+        Ok(response.await?)
+    }
+
+    // allow a banned peer again, false if it was not banned
+    pub async fn unban_peer(&self, peer_id: PeerId) -> Result<bool> {
+        let (reply, response) = oneshot::channel();
+        self.send(NetworkAdminCommand::UnbanPeer { peer_id, reply })?;
+        Ok(response.await?)
+    }
+
+    fn send(&self, command: NetworkAdminCommand) -> Result<()> {
+        self.sender
+            .send(command)
+            .map_err(|_| anyhow!("Network service is not running"))
+    }
+}
@@ -0,0 +1,65 @@
+use alloy::primitives::keccak256;
+use libp2p::{
+    gossipsub::{self, MessageAuthenticity, MessageId, ValidationMode},
+    identity::Keypair,
+};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+// transactions travel on their own gossipsub protocol, so their signing policy
+// can differ from the strict one blocks and attestations use
+pub const TX_GOSSIP_PROTOCOL_PREFIX: &str = "/speed/tx-meshsub";
+
+// whether gossiped messages must name and be signed by their author
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GossipSigning {
+    // author peer id and signature required, unsigned messages are rejected
+    #[default]
+    Signed,
+    // our messages are published without author, unsigned messages are accepted
+    // and relayed, only the connection of the relaying peer is authenticated
+    Anonymous,
+}
+
+// signing policy per gossip topic, blocks and attestations are always signed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GossipSigningConfig {
+    pub transactions: GossipSigning,
+}
+
+impl GossipSigning {
+    pub fn authenticity(&self, key: &Keypair) -> MessageAuthenticity {
+        match self {
+            GossipSigning::Signed => MessageAuthenticity::Signed(key.clone()),
+            GossipSigning::Anonymous => MessageAuthenticity::Anonymous,
+        }
+    }
+
+    // anonymous nodes still accept signed messages from strict peers
+    pub fn validation_mode(&self) -> ValidationMode {
+        match self {
+            GossipSigning::Signed => ValidationMode::Strict,
+            GossipSigning::Anonymous => ValidationMode::Permissive,
+        }
+    }
+}
+
+// gossipsub behaviour for the transaction topic, messages without author
+// carry no sequence number, so they are identified by their content
+pub fn transaction_gossipsub(
+    key: &Keypair,
+    signing: GossipSigning,
+) -> Result<gossipsub::Behaviour, Box<dyn Error + Send + Sync>> {
+    let config = gossipsub::ConfigBuilder::default()
+        .protocol_id_prefix(TX_GOSSIP_PROTOCOL_PREFIX)
+        .validation_mode(signing.validation_mode())
+        .message_id_fn(|message| MessageId::from(keccak256(&message.data).to_vec()))
+        .build()?;
+
+    Ok(gossipsub::Behaviour::new(
+        signing.authenticity(key),
+        config,
+    )?)
+}
@@ -1,3 +1,17 @@
+pub mod admin;
+pub mod gossip_signing;
 pub mod network;
+pub mod peer_stats;
+pub mod peer_tracker;
+pub mod propagation;
+pub mod replay_guard;
+pub mod transport;
 
+pub use admin::*;
+pub use gossip_signing::*;
 pub use network::*;
+pub use peer_stats::*;
+pub use peer_tracker::*;
+pub use propagation::*;
+pub use replay_guard::*;
+pub use transport::*;
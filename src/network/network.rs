@@ -1,21 +1,42 @@
-use alloy::primitives::Address;
+use alloy::primitives::{Address, keccak256};
+use alloy_signer::Signature;
 use anyhow::Result;
 use libp2p::{
-    Swarm, SwarmBuilder,
+    Multiaddr, PeerId, Swarm, SwarmBuilder,
+    allow_block_list::{self, BlockedPeers},
     futures::StreamExt,
-    gossipsub::{self, Behaviour, IdentTopic},
-    mdns, noise,
+    gossipsub::{self, Behaviour, IdentTopic, TopicHash},
+    mdns, noise, ping,
     swarm::{NetworkBehaviour, SwarmEvent},
     tcp, yamux,
 };
-use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use std::collections::HashSet;
+use std::time::{Instant, SystemTime};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
 
-use crate::{BlockchainMessage, NetworkMessage};
+use super::admin::{NetworkAdmin, NetworkAdminCommand};
+use super::gossip_signing::{GossipSigningConfig, transaction_gossipsub};
+use super::peer_stats::{GossipKind, MessageOutcome, PeerStatsTracker};
+use super::peer_tracker::{PEER_MAINTENANCE_INTERVAL, PING_INTERVAL, PING_TIMEOUT, PeerTracker};
+use super::propagation::unix_millis;
+use super::replay_guard::{ReplayGuard, ReplayRejection, SEEN_MESSAGE_TTL};
+use crate::{Block, BlockchainMessage, NetworkMessage, inject_gossip_drop};
+
+// gossip topics, versioned by P2P_PROTOCOL_VERSION and suffixed with the chain id
+pub const GOSSIP_TOPICS: [&str; 3] = [
+    "blockchain-blocks",
+    "blockchain-transactions",
+    "blockchain-sync",
+];
+pub const P2P_PROTOCOL_VERSION: u32 = 2;
 
 #[derive(NetworkBehaviour)]
 pub struct BlockchainBehaviour {
-    pub gossipsub: Behaviour,         // For broadcasting messages
+    pub gossipsub: Behaviour,         // For broadcasting blocks and attestations
+    pub tx_gossipsub: Behaviour,      // For broadcasting transactions, own signing policy
     pub mdns: mdns::tokio::Behaviour, // For discovering local peers
+    pub ping: ping::Behaviour,        // For detecting dead peers
+    pub blocked_peers: allow_block_list::Behaviour<BlockedPeers>, // Peers banned by the operator
 }
 
 // Main function
@@ -25,9 +46,18 @@ pub struct BlockchainBehaviour {
 pub struct NetworkService {
     pub swarm: Swarm<BlockchainBehaviour>,
     pub topics: Vec<IdentTopic>,
+    // liveness of connected peers and validator peers to keep dialing
+    peer_tracker: PeerTracker,
+    // gossip received per peer and message type, for operators
+    peer_stats: PeerStatsTracker,
+    // drops replayed and historical blocks/attestations before validation
+    replay_guard: ReplayGuard,
     // Channels for blockchain communication
     to_blockchain_sender: UnboundedSender<NetworkMessage>,
     from_blockchain_receiver: UnboundedReceiver<BlockchainMessage>,
+    // operator commands from the admin rpc
+    admin_sender: UnboundedSender<NetworkAdminCommand>,
+    admin_receiver: UnboundedReceiver<NetworkAdminCommand>,
 }
 
 unsafe impl Send for NetworkService {}
@@ -36,6 +66,9 @@ unsafe impl Sync for NetworkService {}
 impl NetworkService {
     // starting a new node instance
     pub async fn new(
+        chain_id: u64,
+        replay_horizon_slots: u64,
+        gossip_signing: &GossipSigningConfig,
         to_blockchain: UnboundedSender<NetworkMessage>,
         from_blockchain: UnboundedReceiver<BlockchainMessage>,
     ) -> Result<(Self)> {
@@ -59,35 +92,59 @@ impl NetworkService {
                     gossipsub::MessageAuthenticity::Signed(key.clone()),
                     gossipsub_config,
                 )?;
+                let tx_gossipsub = transaction_gossipsub(key, gossip_signing.transactions)?;
 
                 let mdns = mdns::tokio::Behaviour::new(
                     mdns::Config::default(),
                     key.public().to_peer_id(),
                 )?;
 
-                Ok(BlockchainBehaviour { gossipsub, mdns })
+                let ping = ping::Behaviour::new(
+                    ping::Config::new()
+                        .with_interval(PING_INTERVAL)
+                        .with_timeout(PING_TIMEOUT),
+                );
+
+                Ok(BlockchainBehaviour {
+                    gossipsub,
+                    tx_gossipsub,
+                    mdns,
+                    ping,
+                    blocked_peers: allow_block_list::Behaviour::default(),
+                })
             })?
             .build();
 
-        let topics = vec![
-            IdentTopic::new("blockchain-blocks"),
-            IdentTopic::new("blockchain-transactions"),
-            IdentTopic::new("blockchain-sync"),
-        ];
+        // chains sharing a host must never see each other's messages
+        let topics = GOSSIP_TOPICS
+            .iter()
+            .map(|t| IdentTopic::new(format!("{}/{}", t, chain_id)))
+            .collect();
+
+        let (admin_sender, admin_receiver) = unbounded_channel();
+        println!(
+            "🔏 Transaction gossip signing: {:?}",
+            gossip_signing.transactions
+        );
 
         Ok(NetworkService {
             swarm,
             topics,
+            peer_tracker: PeerTracker::new(),
+            peer_stats: PeerStatsTracker::default(),
+            replay_guard: ReplayGuard::new(replay_horizon_slots, SEEN_MESSAGE_TTL),
             to_blockchain_sender: to_blockchain,
             from_blockchain_receiver: from_blockchain,
+            admin_sender,
+            admin_receiver,
         })
     }
 
     pub async fn start(&mut self, port: u16) -> Result<()> {
         // Calling swarm to subscribe to all related topics
-        for topic in &self.topics {
+        for topic in self.topics.clone() {
             // subscribe to each topic, filter out other unrelated topics
-            self.swarm.behaviour_mut().gossipsub.subscribe(&topic)?;
+            self.gossip_for(&topic).subscribe(&topic)?;
             println!("📡 Subscribed to topic: {}", topic);
         }
 
@@ -98,6 +155,8 @@ impl NetworkService {
     }
 
     pub async fn run(&mut self) -> Result<()> {
+        let mut maintenance_timer = tokio::time::interval(PEER_MAINTENANCE_INTERVAL);
+
         loop {
             tokio::select! {
                 event = self.swarm.select_next_some() => {
@@ -107,34 +166,207 @@ impl NetworkService {
                 Some(msg) = self.from_blockchain_receiver.recv() => {
                     self.handle_blockchain_message(&msg).await?;
                 }
+
+                Some(command) = self.admin_receiver.recv() => {
+                    self.handle_admin_command(command);
+                }
+
+                // Periodically prune dead peers and redial validators
+                _ = maintenance_timer.tick() => {
+                    self.maintain_peers();
+                    self.replay_guard.prune(Instant::now());
+                }
+            }
+        }
+    }
+
+    // handle for the admin rpc to list, ban and unban peers
+    pub fn admin_handle(&self) -> NetworkAdmin {
+        NetworkAdmin::new(self.admin_sender.clone())
+    }
+
+    // replies are best effort, the caller may have given up waiting
+    fn handle_admin_command(&mut self, command: NetworkAdminCommand) {
+        match command {
+            NetworkAdminCommand::ListPeers { reply } => {
+                let _ = reply.send(self.peer_tracker.peer_infos(Instant::now()));
+            }
+            NetworkAdminCommand::PeerStats { reply } => {
+                let _ = reply.send(self.peer_stats.stats());
+            }
+            NetworkAdminCommand::BanPeer { peer_id, reply } => {
+                println!("⛔ Banning peer {}", peer_id);
+                // closes existing connections and refuses new ones
+                self.swarm.behaviour_mut().blocked_peers.block_peer(peer_id);
+                let banned = self.peer_tracker.ban(peer_id);
+                self.report_peer_count();
+                let _ = reply.send(banned);
+            }
+            NetworkAdminCommand::UnbanPeer { peer_id, reply } => {
+                println!("✅ Unbanning peer {}", peer_id);
+                self.swarm
+                    .behaviour_mut()
+                    .blocked_peers
+                    .unblock_peer(peer_id);
+                let _ = reply.send(self.peer_tracker.unban(&peer_id));
+            }
+        }
+    }
+
+    // track the validator addresses announced on-chain, they are redialed whenever
+    // disconnected, addresses no longer announced stop being dialed
+    fn set_validator_peers(&mut self, addresses: &[String]) {
+        let mut tracked = HashSet::new();
+        for address in addresses {
+            match address.parse::<Multiaddr>() {
+                Ok(multiaddr) => {
+                    tracked.insert(multiaddr);
+                }
+                Err(e) => println!("Invalid validator address {}: {}", address, e),
             }
         }
+        for address in self.peer_tracker.set_validator_addresses(tracked) {
+            println!("📇 No longer tracking validator peer: {}", address);
+        }
+    }
+
+    // disconnect silent peers and redial validator peers we lost
+    fn maintain_peers(&mut self) {
+        for peer_id in self.peer_tracker.dead_peers(Instant::now()) {
+            println!("💀 Peer {} is unresponsive, disconnecting", peer_id);
+            let _ = self.swarm.disconnect_peer_id(peer_id);
+            self.peer_tracker.on_disconnected(&peer_id);
+        }
+
+        self.redial_validators();
+        self.report_peer_count();
+    }
+
+    // dial validator peers we are not connected to
+    fn redial_validators(&mut self) {
+        for address in self.peer_tracker.disconnected_validators() {
+            println!("🔁 Redialing validator peer: {}", address);
+            if let Err(e) = self.swarm.dial(address.clone()) {
+                println!("Failed to redial {}: {}", address, e);
+            }
+        }
+    }
+
+    // tell the blockchain layer how many peers we are connected to
+    fn report_peer_count(&self) {
+        let connected = self.peer_tracker.connected_count();
+        if self
+            .to_blockchain_sender
+            .send(NetworkMessage::PeerCount { connected })
+            .is_err()
+        {
+            println!("❌ Failed to send peer count to blockchain layer");
+        }
+    }
+
+    // the transaction topic has its own gossipsub behaviour and signing policy
+    fn gossip_for(&mut self, topic: &IdentTopic) -> &mut Behaviour {
+        if topic.hash() == self.topics[1].hash() {
+            &mut self.swarm.behaviour_mut().tx_gossipsub
+        } else {
+            &mut self.swarm.behaviour_mut().gossipsub
+        }
     }
 
     // Convert blockchain msg to P2P and broadcast
     async fn handle_blockchain_message(&mut self, msg: &BlockchainMessage) -> Result<()> {
-        let serialized = serde_json::to_vec(&msg)?;
-
         let topic = match &msg {
             BlockchainMessage::NewBlock { .. } => &self.topics[0],
             BlockchainMessage::Attestation { .. } => &self.topics[0],
             BlockchainMessage::NewTransaction { .. } => &self.topics[1],
+            BlockchainMessage::ValidatorAddresses { addresses } => {
+                self.set_validator_peers(addresses);
+                return Ok(());
+            }
+            BlockchainMessage::HeadSlot { slot } => {
+                self.replay_guard.set_head_slot(*slot);
+                return Ok(());
+            }
+            BlockchainMessage::RebroadcastBlock {
+                block,
+                proposer,
+                signature,
+            } => {
+                return self.rebroadcast_block(block, *proposer, *signature);
+            }
         };
+        let serialized = serde_json::to_vec(&msg)?;
 
         // broadcast message to other node, using gossipsub
+        let topic = topic.clone();
+        self.gossip_for(&topic).publish(topic.clone(), serialized)?;
+        println!("📡 Broadcasted message to topic: {}", topic);
+        Ok(())
+    }
+
+    // flood publishing reaches every connected subscriber, not only our mesh peers,
+    // lost validator connections are redialed so they get the next copy
+    fn rebroadcast_block(
+        &mut self,
+        block: &Block,
+        proposer: Address,
+        signature: Signature,
+    ) -> Result<()> {
+        self.redial_validators();
+
+        let msg = BlockchainMessage::NewBlock {
+            block: block.clone(),
+            proposer,
+            signature,
+        };
+        let topic = self.topics[0].clone();
         self.swarm
             .behaviour_mut()
             .gossipsub
-            .publish(topic.clone(), serialized)?;
-        println!("📡 Broadcasted message to topic: {}", topic);
+            .publish(topic.clone(), serde_json::to_vec(&msg)?)?;
+        println!(
+            "📣 Re-broadcasted block #{} to topic: {}",
+            block.header.index, topic
+        );
         Ok(())
     }
 
     // 1. convert P2P message received from other node,
     // 2. forward message to blockchain via mpsc channel
-    async fn handle_gossipsub_message(&self, data: Vec<u8>) -> Result<()> {
+    async fn handle_gossipsub_message(
+        &mut self,
+        source: PeerId,
+        topic: TopicHash,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        // stamp before any processing, latency stats measure the gossip path only
+        let received_at_ms = unix_millis(SystemTime::now());
+        if inject_gossip_drop() {
+            println!("💥 Dropped gossip message (fault injection)");
+            return Ok(());
+        }
+
         match serde_json::from_slice::<BlockchainMessage>(&data) {
             Ok(p2p_msg) => {
+                let kind = self.message_kind(&p2p_msg, &topic);
+
+                // the transaction topic may allow unsigned messages, blocks and votes
+                // are only taken from the strict one
+                if !self.is_expected_topic(&p2p_msg, &topic) {
+                    println!("🚫 Dropping gossip from {}: wrong topic {}", source, topic);
+                    self.peer_stats
+                        .record(source, kind, data.len(), MessageOutcome::Invalid);
+                    return Ok(());
+                }
+
+                // replayed or historical messages never reach validation
+                if let Err(reason) = self.check_replay(&p2p_msg, &data) {
+                    println!("🔁 Dropping gossip from {}: {}", source, reason);
+                    self.peer_stats
+                        .record(source, kind, data.len(), MessageOutcome::Duplicate);
+                    return Ok(());
+                }
+
                 // Convert P2P message to NetworkMessage
                 let network_msg = match p2p_msg {
                     BlockchainMessage::NewBlock {
@@ -142,9 +374,11 @@ impl NetworkService {
                         proposer,
                         signature,
                     } => NetworkMessage::NewBlock {
-                        block,
+                        block: Box::new(block),
                         proposer_id: proposer,
                         signature,
+                        from_peer: source.to_string(),
+                        received_at_ms,
                     },
                     BlockchainMessage::Attestation {
                         block_hash,
@@ -159,11 +393,22 @@ impl NetworkService {
                     },
                     BlockchainMessage::NewTransaction { transaction } => {
                         NetworkMessage::NewTransaction {
-                            transaction,
-                            from_peer: Address::ZERO, // Simplified for learning
+                            transaction: Box::new(transaction),
+                            from_peer: source.to_string(),
                         }
                     }
+                    // local only messages, a peer should never gossip them
+                    BlockchainMessage::ValidatorAddresses { .. }
+                    | BlockchainMessage::HeadSlot { .. }
+                    | BlockchainMessage::RebroadcastBlock { .. } => {
+                        println!("❌ Ignoring local-only message received from gossip");
+                        self.peer_stats
+                            .record(source, kind, data.len(), MessageOutcome::Invalid);
+                        return Ok(());
+                    }
                 };
+                self.peer_stats
+                    .record(source, kind, data.len(), MessageOutcome::Forwarded);
 
                 // Forward to blockchain layer
                 if let Err(_) = self.to_blockchain_sender.send(network_msg) {
@@ -172,18 +417,95 @@ impl NetworkService {
             }
             Err(e) => {
                 println!("❌ Failed to deserialize P2P message: {}", e);
+                let kind = self.topic_kind(&topic);
+                self.peer_stats
+                    .record(source, kind, data.len(), MessageOutcome::Invalid);
             }
         }
         Ok(())
     }
 
+    // local-only messages have no gossip type, they are counted by topic
+    fn message_kind(&self, msg: &BlockchainMessage, topic: &TopicHash) -> GossipKind {
+        match msg {
+            BlockchainMessage::NewBlock { .. } => GossipKind::Block,
+            BlockchainMessage::Attestation { .. } => GossipKind::Attestation,
+            BlockchainMessage::NewTransaction { .. } => GossipKind::Transaction,
+            _ => self.topic_kind(topic),
+        }
+    }
+
+    // the block topic also carries attestations, its messages can't be told apart
+    fn topic_kind(&self, topic: &TopicHash) -> GossipKind {
+        if *topic == self.topics[1].hash() {
+            GossipKind::Transaction
+        } else if *topic == self.topics[2].hash() {
+            GossipKind::Sync
+        } else {
+            GossipKind::Unknown
+        }
+    }
+
+    fn is_expected_topic(&self, msg: &BlockchainMessage, topic: &TopicHash) -> bool {
+        match msg {
+            BlockchainMessage::NewBlock { .. } | BlockchainMessage::Attestation { .. } => {
+                *topic == self.topics[0].hash()
+            }
+            BlockchainMessage::NewTransaction { .. } => *topic == self.topics[1].hash(),
+            _ => true,
+        }
+    }
+
+    // blocks are keyed by header hash and signature, attestations by the raw message
+    fn check_replay(
+        &mut self,
+        msg: &BlockchainMessage,
+        data: &[u8],
+    ) -> Result<(), ReplayRejection> {
+        let now = Instant::now();
+        match msg {
+            BlockchainMessage::NewBlock {
+                block, signature, ..
+            } => self.replay_guard.check_block(
+                block.header.hash(),
+                signature,
+                block.header.slot,
+                now,
+            ),
+            BlockchainMessage::Attestation { block_hash, .. } => self
+                .replay_guard
+                .check_attestation(keccak256(data), *block_hash, now),
+            _ => Ok(()),
+        }
+    }
+
     // Pass peer info to message handler
     async fn handle_behaviour_event(&mut self, event: BlockchainBehaviourEvent) -> Result<()> {
         match event {
-            BlockchainBehaviourEvent::Gossipsub(gossipsub::Event::Message { message, .. }) => {
-                self.handle_gossipsub_message(message.data).await?;
+            BlockchainBehaviourEvent::Gossipsub(gossipsub::Event::Message {
+                propagation_source,
+                message,
+                ..
+            })
+            | BlockchainBehaviourEvent::TxGossipsub(gossipsub::Event::Message {
+                propagation_source,
+                message,
+                ..
+            }) => {
+                self.peer_tracker.on_activity(&propagation_source);
+                self.handle_gossipsub_message(propagation_source, message.topic, message.data)
+                    .await?;
             }
 
+            // ping replies keep peers alive, failures count towards dead peers
+            BlockchainBehaviourEvent::Ping(ping::Event { peer, result, .. }) => match result {
+                Ok(_) => self.peer_tracker.on_activity(&peer),
+                Err(e) => {
+                    println!("📶 Ping to {} failed: {}", peer, e);
+                    self.peer_tracker.on_ping_failure(&peer);
+                }
+            },
+
             // discover peers
             BlockchainBehaviourEvent::Mdns(mdns::Event::Discovered(peers)) => {
                 for (peer_id, addr) in peers {
@@ -212,12 +534,25 @@ impl NetworkService {
                 println!("🎧 Listening on: {}, listener id: {}", address, listener_id);
             }
             // Peer connected
-            SwarmEvent::ConnectionEstablished { peer_id, .. } => {
+            SwarmEvent::ConnectionEstablished {
+                peer_id, endpoint, ..
+            } => {
                 println!("🤝 Connected to peer: {}", peer_id);
+                self.peer_tracker
+                    .on_connected(peer_id, endpoint.get_remote_address().clone());
+                self.report_peer_count();
             }
             // Peer disconnected
-            SwarmEvent::ConnectionClosed { peer_id, .. } => {
+            SwarmEvent::ConnectionClosed {
+                peer_id,
+                num_established,
+                ..
+            } => {
                 println!("👋 Disconnected from peer: {}", peer_id);
+                if num_established == 0 {
+                    self.peer_tracker.on_disconnected(&peer_id);
+                    self.report_peer_count();
+                }
             }
             // Handle protocol-specific events
             SwarmEvent::Behaviour(event) => {
@@ -0,0 +1,153 @@
+use libp2p::PeerId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+// peers with stats kept, the quietest one is forgotten to make room
+pub const MAX_TRACKED_PEERS: usize = 256;
+
+// gossip message types, by decoded content or by topic when it can't be decoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GossipKind {
+    Block,
+    Attestation,
+    Transaction,
+    Sync,
+    // undecodable message on a topic that carries several types
+    Unknown,
+}
+
+// what became of a received message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageOutcome {
+    Forwarded,
+    // replayed or too old, dropped by the replay guard
+    Duplicate,
+    // undecodable, on the wrong topic or a local-only message
+    Invalid,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MessageStats {
+    pub messages: u64,
+    pub bytes: u64,
+    pub duplicates: u64,
+    pub invalid: u64,
+}
+
+impl MessageStats {
+    fn record(&mut self, bytes: usize, outcome: MessageOutcome) {
+        self.messages += 1;
+        self.bytes += bytes as u64;
+        match outcome {
+            MessageOutcome::Forwarded => {}
+            MessageOutcome::Duplicate => self.duplicates += 1,
+            MessageOutcome::Invalid => self.invalid += 1,
+        }
+    }
+
+    fn add(&mut self, other: &MessageStats) {
+        self.messages += other.messages;
+        self.bytes += other.bytes;
+        self.duplicates += other.duplicates;
+        self.invalid += other.invalid;
+    }
+
+    // share of messages that were invalid, 0 without messages
+    pub fn invalid_ratio(&self) -> f64 {
+        if self.messages == 0 {
+            return 0.0;
+        }
+        self.invalid as f64 / self.messages as f64
+    }
+}
+
+// returned by `admin_peerStats`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerStats {
+    pub peer_id: String,
+    pub by_kind: BTreeMap<GossipKind, MessageStats>,
+    pub total: MessageStats,
+    pub invalid_ratio: f64,
+}
+
+// gossip received per relaying peer and message type, kept across reconnects
+#[derive(Debug)]
+pub struct PeerStatsTracker {
+    peers: HashMap<PeerId, BTreeMap<GossipKind, MessageStats>>,
+    capacity: usize,
+}
+
+impl PeerStatsTracker {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            peers: HashMap::new(),
+            capacity,
+        }
+    }
+
+    pub fn record(
+        &mut self,
+        peer: PeerId,
+        kind: GossipKind,
+        bytes: usize,
+        outcome: MessageOutcome,
+    ) {
+        if !self.peers.contains_key(&peer) && self.peers.len() >= self.capacity {
+            self.evict_quietest();
+        }
+        self.peers
+            .entry(peer)
+            .or_default()
+            .entry(kind)
+            .or_default()
+            .record(bytes, outcome);
+    }
+
+    // sorted by peer id
+    pub fn stats(&self) -> Vec<PeerStats> {
+        let mut stats: Vec<PeerStats> = self
+            .peers
+            .iter()
+            .map(|(peer_id, by_kind)| {
+                let mut total = MessageStats::default();
+                for stats in by_kind.values() {
+                    total.add(stats);
+                }
+                PeerStats {
+                    peer_id: peer_id.to_string(),
+                    by_kind: by_kind.clone(),
+                    invalid_ratio: total.invalid_ratio(),
+                    total,
+                }
+            })
+            .collect();
+        stats.sort_by(|a, b| a.peer_id.cmp(&b.peer_id));
+        stats
+    }
+
+    pub fn len(&self) -> usize {
+        self.peers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+
+    fn evict_quietest(&mut self) {
+        let quietest = self
+            .peers
+            .iter()
+            .min_by_key(|(_, by_kind)| by_kind.values().map(|s| s.messages).sum::<u64>())
+            .map(|(peer_id, _)| *peer_id);
+        if let Some(peer_id) = quietest {
+            self.peers.remove(&peer_id);
+        }
+    }
+}
+
+impl Default for PeerStatsTracker {
+    fn default() -> Self {
+        Self::new(MAX_TRACKED_PEERS)
+    }
+}
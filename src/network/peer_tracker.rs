@@ -0,0 +1,176 @@
+use libp2p::{Multiaddr, PeerId, multiaddr::Protocol};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+// how often peers are pinged, and how long a ping may take
+pub const PING_INTERVAL: Duration = Duration::from_secs(5);
+pub const PING_TIMEOUT: Duration = Duration::from_secs(10);
+// a peer with no ping reply or gossip for this long is considered dead
+pub const PEER_INACTIVITY_TIMEOUT: Duration = Duration::from_secs(30);
+// consecutive ping failures before a peer is considered dead
+pub const MAX_PING_FAILURES: u32 = 3;
+// how often dead peers are pruned and validator peers redialed
+pub const PEER_MAINTENANCE_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone)]
+pub struct PeerState {
+    pub remote_address: Multiaddr,
+    pub last_seen: Instant,
+    pub ping_failures: u32,
+}
+
+// peer as reported to operators
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerInfo {
+    pub peer_id: String,
+    pub address: Option<String>,
+    pub connected: bool,
+    pub banned: bool,
+    pub last_seen_secs: Option<u64>,
+    pub ping_failures: u32,
+}
+
+// tracks liveness of connected peers and the validator peers we must stay connected to
+#[derive(Debug, Default)]
+pub struct PeerTracker {
+    connected: HashMap<PeerId, PeerState>,
+    validator_addresses: HashSet<Multiaddr>,
+    // peers an operator banned, the swarm refuses their connections
+    banned: HashSet<PeerId>,
+}
+
+impl PeerTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // record a newly established connection
+    pub fn on_connected(&mut self, peer_id: PeerId, remote_address: Multiaddr) {
+        self.connected.insert(
+            peer_id,
+            PeerState {
+                remote_address,
+                last_seen: Instant::now(),
+                ping_failures: 0,
+            },
+        );
+    }
+
+    // remove a peer once all connections are closed
+    pub fn on_disconnected(&mut self, peer_id: &PeerId) {
+        self.connected.remove(peer_id);
+    }
+
+    // any sign of life (ping reply, gossip message) refreshes the peer
+    pub fn on_activity(&mut self, peer_id: &PeerId) {
+        if let Some(state) = self.connected.get_mut(peer_id) {
+            state.last_seen = Instant::now();
+            state.ping_failures = 0;
+        }
+    }
+
+    pub fn on_ping_failure(&mut self, peer_id: &PeerId) {
+        if let Some(state) = self.connected.get_mut(peer_id) {
+            state.ping_failures += 1;
+        }
+    }
+
+    // peers that stopped answering pings or went silent
+    pub fn dead_peers(&self, now: Instant) -> Vec<PeerId> {
+        self.connected
+            .iter()
+            .filter(|(_, state)| {
+                state.ping_failures >= MAX_PING_FAILURES
+                    || now.duration_since(state.last_seen) > PEER_INACTIVITY_TIMEOUT
+            })
+            .map(|(peer_id, _)| *peer_id)
+            .collect()
+    }
+
+    // replace the validator addresses that should always be dialed, returns the ones no
+    // longer among them, a rotated or exited validator's old address is dropped
+    pub fn set_validator_addresses(&mut self, addresses: HashSet<Multiaddr>) -> Vec<Multiaddr> {
+        let dropped = self
+            .validator_addresses
+            .difference(&addresses)
+            .cloned()
+            .collect();
+        self.validator_addresses = addresses;
+        dropped
+    }
+
+    // validator addresses without a live connection
+    pub fn disconnected_validators(&self) -> Vec<Multiaddr> {
+        self.validator_addresses
+            .iter()
+            .filter(|address| !self.is_address_connected(address))
+            .cloned()
+            .collect()
+    }
+
+    pub fn connected_count(&self) -> usize {
+        self.connected.len()
+    }
+
+    // false if the peer was already banned
+    pub fn ban(&mut self, peer_id: PeerId) -> bool {
+        self.connected.remove(&peer_id);
+        self.banned.insert(peer_id)
+    }
+
+    // false if the peer was not banned
+    pub fn unban(&mut self, peer_id: &PeerId) -> bool {
+        self.banned.remove(peer_id)
+    }
+
+    // connected peers followed by banned ones
+    pub fn peer_infos(&self, now: Instant) -> Vec<PeerInfo> {
+        let mut peers: Vec<PeerInfo> = self
+            .connected
+            .iter()
+            .map(|(peer_id, state)| PeerInfo {
+                peer_id: peer_id.to_string(),
+                address: Some(state.remote_address.to_string()),
+                connected: true,
+                banned: false,
+                last_seen_secs: Some(now.duration_since(state.last_seen).as_secs()),
+                ping_failures: state.ping_failures,
+            })
+            .collect();
+        peers.sort_by(|a, b| a.peer_id.cmp(&b.peer_id));
+
+        let mut banned: Vec<PeerInfo> = self
+            .banned
+            .iter()
+            .map(|peer_id| PeerInfo {
+                peer_id: peer_id.to_string(),
+                address: None,
+                connected: false,
+                banned: true,
+                last_seen_secs: None,
+                ping_failures: 0,
+            })
+            .collect();
+        banned.sort_by(|a, b| a.peer_id.cmp(&b.peer_id));
+
+        peers.extend(banned);
+        peers
+    }
+
+    // match by peer id if the address carries one, else by remote address
+    fn is_address_connected(&self, address: &Multiaddr) -> bool {
+        let peer_id = address.iter().find_map(|protocol| match protocol {
+            Protocol::P2p(peer_id) => Some(peer_id),
+            _ => None,
+        });
+
+        match peer_id {
+            Some(peer_id) => self.connected.contains_key(&peer_id),
+            None => self
+                .connected
+                .values()
+                .any(|state| &state.remote_address == address),
+        }
+    }
+}
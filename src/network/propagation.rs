@@ -0,0 +1,155 @@
+use alloy::primitives::B256;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// gossiped blocks kept for latency percentiles
+pub const PROPAGATION_WINDOW_BLOCKS: usize = 256;
+
+// one gossiped block as seen by this node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockArrival {
+    pub block_hash: B256,
+    pub block_number: u64,
+    pub slot: u64,
+    // peer that relayed the block to us
+    pub peer: String,
+    // local receive time, ms since the unix epoch
+    pub received_at_ms: u64,
+    // receive time minus the start of the block's slot on our clock
+    pub since_slot_start_ms: i64,
+    // receive time minus the proposer's header timestamp (second precision)
+    pub since_proposer_ms: i64,
+}
+
+// latency distribution in milliseconds, nearest-rank percentiles
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyPercentiles {
+    pub samples: usize,
+    pub p50_ms: i64,
+    pub p90_ms: i64,
+    pub p99_ms: i64,
+    pub max_ms: i64,
+}
+
+impl LatencyPercentiles {
+    pub fn from_samples(mut samples: Vec<i64>) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort_unstable();
+        let rank = |p: usize| samples[(samples.len() * p).div_ceil(100).max(1) - 1];
+
+        Self {
+            samples: samples.len(),
+            p50_ms: rank(50),
+            p90_ms: rank(90),
+            p99_ms: rank(99),
+            max_ms: samples[samples.len() - 1],
+        }
+    }
+}
+
+// latencies of the blocks a peer relayed first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerPropagation {
+    pub since_slot_start: LatencyPercentiles,
+    pub since_proposer: LatencyPercentiles,
+}
+
+// aggregated view returned by `speed_getPropagationStats`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PropagationStats {
+    pub blocks: usize,
+    pub since_slot_start: LatencyPercentiles,
+    pub since_proposer: LatencyPercentiles,
+    pub peers: BTreeMap<String, PeerPropagation>,
+    // newest first
+    pub recent: Vec<BlockArrival>,
+}
+
+// keeps the last gossiped blocks and their receive latencies
+pub struct PropagationTracker {
+    arrivals: VecDeque<BlockArrival>,
+    capacity: usize,
+}
+
+impl PropagationTracker {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            arrivals: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    // only the first copy of a block counts, later ones are gossip duplicates
+    pub fn record(&mut self, arrival: BlockArrival) -> bool {
+        if self
+            .arrivals
+            .iter()
+            .any(|a| a.block_hash == arrival.block_hash)
+        {
+            return false;
+        }
+        if self.arrivals.len() == self.capacity {
+            self.arrivals.pop_front();
+        }
+        self.arrivals.push_back(arrival);
+        true
+    }
+
+    // receive latency after the slot start of the last `count` blocks
+    pub fn recent_latency(&self, count: usize) -> LatencyPercentiles {
+        LatencyPercentiles::from_samples(
+            self.arrivals
+                .iter()
+                .rev()
+                .take(count)
+                .map(|a| a.since_slot_start_ms)
+                .collect(),
+        )
+    }
+
+    // percentiles over the window, per peer, plus the latest `recent` arrivals
+    pub fn stats(&self, recent: usize) -> PropagationStats {
+        let mut by_peer: BTreeMap<&str, (Vec<i64>, Vec<i64>)> = BTreeMap::new();
+        for arrival in &self.arrivals {
+            let (slot, proposer) = by_peer.entry(&arrival.peer).or_default();
+            slot.push(arrival.since_slot_start_ms);
+            proposer.push(arrival.since_proposer_ms);
+        }
+
+        let peers = by_peer
+            .into_iter()
+            .map(|(peer, (slot, proposer))| {
+                let stats = PeerPropagation {
+                    since_slot_start: LatencyPercentiles::from_samples(slot),
+                    since_proposer: LatencyPercentiles::from_samples(proposer),
+                };
+                (peer.to_string(), stats)
+            })
+            .collect();
+
+        PropagationStats {
+            blocks: self.arrivals.len(),
+            since_slot_start: LatencyPercentiles::from_samples(
+                self.arrivals
+                    .iter()
+                    .map(|a| a.since_slot_start_ms)
+                    .collect(),
+            ),
+            since_proposer: LatencyPercentiles::from_samples(
+                self.arrivals.iter().map(|a| a.since_proposer_ms).collect(),
+            ),
+            peers,
+            recent: self.arrivals.iter().rev().take(recent).cloned().collect(),
+        }
+    }
+}
+
+// wall clock in ms, used to stamp received gossip
+pub fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
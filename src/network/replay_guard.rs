@@ -0,0 +1,127 @@
+use alloy::primitives::{B256, keccak256};
+use alloy_signer::Signature;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// blocks older than this many slots behind our head are dropped at the network layer
+pub const REPLAY_HORIZON_SLOTS: u64 = 32;
+// how long processed block and attestation hashes are remembered
+pub const SEEN_MESSAGE_TTL: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ReplayRejection {
+    #[error("Message {0} was already processed")]
+    AlreadySeen(B256),
+    #[error("Slot {slot} is more than {horizon} slots behind head slot {head}")]
+    StaleSlot { slot: u64, head: u64, horizon: u64 },
+}
+
+// drops replayed or historical blocks and attestations before they reach validation
+#[derive(Debug)]
+pub struct ReplayGuard {
+    horizon_slots: u64,
+    ttl: Duration,
+    head_slot: u64,
+    // message hash -> when it was first processed
+    seen: HashMap<B256, Instant>,
+    // block hash -> slot and when it was first seen, so attestations can be checked
+    // against the horizon
+    block_slots: HashMap<B256, (u64, Instant)>,
+}
+
+impl ReplayGuard {
+    pub fn new(horizon_slots: u64, ttl: Duration) -> Self {
+        Self {
+            horizon_slots,
+            ttl,
+            head_slot: 0,
+            seen: HashMap::new(),
+            block_slots: HashMap::new(),
+        }
+    }
+
+    // head only moves forward, a late update must not widen the window
+    pub fn set_head_slot(&mut self, slot: u64) {
+        self.head_slot = self.head_slot.max(slot);
+    }
+
+    pub fn head_slot(&self) -> u64 {
+        self.head_slot
+    }
+
+    // accept a block once per signature, and only if its slot is within the horizon
+    // the signature is checked after this, so a copy with a forged one must not take the
+    // place of the genuine block
+    pub fn check_block(
+        &mut self,
+        block_hash: B256,
+        signature: &Signature,
+        slot: u64,
+        now: Instant,
+    ) -> Result<(), ReplayRejection> {
+        let message_hash = block_message_hash(&block_hash, signature);
+        self.check_seen(message_hash, now)?;
+        self.check_slot(slot)?;
+
+        self.seen.insert(message_hash, now);
+        self.block_slots.entry(block_hash).or_insert((slot, now));
+        Ok(())
+    }
+
+    // accept an attestation once, and only if the block it votes on is within the horizon
+    // attestations for blocks we have not seen are let through, validation decides
+    pub fn check_attestation(
+        &mut self,
+        message_hash: B256,
+        block_hash: B256,
+        now: Instant,
+    ) -> Result<(), ReplayRejection> {
+        self.check_seen(message_hash, now)?;
+        if let Some((slot, _)) = self.block_slots.get(&block_hash) {
+            self.check_slot(*slot)?;
+        }
+
+        self.seen.insert(message_hash, now);
+        Ok(())
+    }
+
+    // forget hashes older than the ttl
+    pub fn prune(&mut self, now: Instant) {
+        let ttl = self.ttl;
+        self.seen
+            .retain(|_, first_seen| now.duration_since(*first_seen) <= ttl);
+        self.block_slots
+            .retain(|_, (_, first_seen)| now.duration_since(*first_seen) <= ttl);
+    }
+
+    pub fn seen_count(&self) -> usize {
+        self.seen.len()
+    }
+
+    fn check_seen(&self, hash: B256, now: Instant) -> Result<(), ReplayRejection> {
+        match self.seen.get(&hash) {
+            Some(first_seen) if now.duration_since(*first_seen) <= self.ttl => {
+                Err(ReplayRejection::AlreadySeen(hash))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check_slot(&self, slot: u64) -> Result<(), ReplayRejection> {
+        if slot.saturating_add(self.horizon_slots) < self.head_slot {
+            return Err(ReplayRejection::StaleSlot {
+                slot,
+                head: self.head_slot,
+                horizon: self.horizon_slots,
+            });
+        }
+        Ok(())
+    }
+}
+
+// what a gossiped block is remembered by, its header hash and the signature it came with
+pub fn block_message_hash(block_hash: &B256, signature: &Signature) -> B256 {
+    let mut data = block_hash.to_vec();
+    data.extend_from_slice(&signature.as_bytes());
+    keccak256(data)
+}
@@ -0,0 +1,44 @@
+use anyhow::Result;
+use std::future::Future;
+use std::pin::Pin;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+
+use super::{NetworkAdmin, NetworkService};
+use crate::{BlockchainMessage, NetworkMessage};
+
+// runs a transport until it stops or fails
+pub type TransportFuture = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+
+// builds a transport on the channels to the blockchain service, for `NodeBuilder::with_network`
+pub type NetworkFactory =
+    Box<dyn FnOnce(NetworkChannels) -> Result<Box<dyn NetworkTransport>> + Send>;
+
+// the blockchain service's end of the network, handed to a transport when it is built
+pub struct NetworkChannels {
+    // blocks, attestations and transactions received from peers
+    pub to_blockchain: UnboundedSender<NetworkMessage>,
+    // what the blockchain service wants published
+    pub from_blockchain: UnboundedReceiver<BlockchainMessage>,
+}
+
+// how the node reaches its peers, libp2p gossip by default
+pub trait NetworkTransport: Send {
+    // handle for `admin_peers` and peer bans, None when the transport has no peer management
+    fn admin(&self) -> Option<NetworkAdmin>;
+
+    // listen on `port` and relay messages both ways until the transport stops
+    fn serve(self: Box<Self>, port: u16) -> TransportFuture;
+}
+
+impl NetworkTransport for NetworkService {
+    fn admin(&self) -> Option<NetworkAdmin> {
+        Some(self.admin_handle())
+    }
+
+    fn serve(mut self: Box<Self>, port: u16) -> TransportFuture {
+        Box::pin(async move {
+            self.start(port).await?;
+            self.run().await
+        })
+    }
+}
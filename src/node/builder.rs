@@ -0,0 +1,397 @@
+use alloy::primitives::Address;
+use anyhow::{Context, Result};
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::{net::TcpListener, sync::mpsc::unbounded_channel};
+
+use super::SpeedNode;
+use crate::{
+    AdminRpcImpl, Blockchain, ChainSpec, KeyPair, MIN_STAKE, NetworkAdmin, NetworkChannels,
+    NetworkFactory, NetworkService, NetworkTransport, NodeConfig, SpeedRpcImpl, Storage,
+    SubscriptionRpcImpl,
+    consensus::{ProposerElection, RandomnessBeacon, SigningAuditLog},
+    core::{BlockchainService, Checkpoint, TuningKnobs},
+    dev_accounts, init_logging, install_faults,
+    reindex::{ChainVerifier, VerifyDepth},
+    rpc::{
+        FeeProtection, RpcAccess, SubscriptionRpcServer, TransactionRelay, admin::AdminRpcServer,
+        gated_server_builder, node_features, rpc::SpeedBlockchainRpcServer, start_tls_server,
+    },
+    storage::KeyValueStore,
+};
+
+// assembles a node, for embedding it in another process with custom storage,
+// networking or proposer election; anything not replaced is wired as `speed node` does
+#[derive(Default)]
+pub struct NodeBuilder {
+    config: NodeConfig,
+    storage: Option<Storage>,
+    network: Option<NetworkFactory>,
+    election: Option<Arc<dyn ProposerElection>>,
+}
+
+impl NodeBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_config(mut self, config: NodeConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    // keep chain data in a custom key-value store instead of RocksDB under `data_dir`
+    pub fn with_storage(mut self, store: impl KeyValueStore + 'static) -> Self {
+        self.storage = Some(Storage::with_backend(store));
+        self
+    }
+
+    // reach peers through a custom transport instead of libp2p gossip
+    // the factory gets the channels to the blockchain service once they exist
+    pub fn with_network<F>(mut self, factory: F) -> Self
+    where
+        F: FnOnce(NetworkChannels) -> Result<Box<dyn NetworkTransport>> + Send + 'static,
+    {
+        self.network = Some(Box::new(factory));
+        self
+    }
+
+    // elect proposers with a custom election instead of by stake
+    pub fn with_consensus(mut self, election: impl ProposerElection + 'static) -> Self {
+        self.election = Some(Arc::new(election));
+        self
+    }
+
+    // wire and start the node, with the default implementations for what wasn't replaced
+    pub async fn build(self) -> Result<SpeedNode> {
+        let mut config = self.config;
+        // a chain spec fixes the chain id, genesis and validators, the validators file is not read
+        let dev_accounts = if config.dev {
+            dev_accounts()?
+        } else {
+            Vec::new()
+        };
+        let dev_balances: Vec<_> = dev_accounts
+            .iter()
+            .map(|a| (a.address, a.balance))
+            .collect();
+        // dev accounts join the genesis allocation, so the genesis state root commits to them
+        let chain_spec = config
+            .chain_spec
+            .as_ref()
+            .map(ChainSpec::load)
+            .transpose()?
+            .map(|spec| spec.with_dev_accounts(&dev_balances));
+        if let Some(spec) = &chain_spec {
+            config.chain_id = spec.chain_id;
+        }
+        let port = config.port;
+        let rpc_port = config.rpc_port;
+        println!(
+            "🚀 Starting SpeedNode for chain {} on port {}",
+            config.chain_id, port
+        );
+        init_logging();
+        if let Some(faults) = config.faults.clone() {
+            install_faults(faults);
+        }
+        if config.dry_run {
+            println!("🧪 Dry-run mode: blocks are built but never committed or broadcast");
+        }
+
+        // Setup KeyPair for this node
+        let keypair = KeyPair::generate(config.validator_key.clone());
+
+        // 1. Create channels, network <-> blockchain
+        let (network_to_blockchain_tx, network_to_blockchain_rx) = unbounded_channel();
+        let (blockchain_to_network_tx, blockchain_to_network_rx) = unbounded_channel();
+
+        // 2. Initialize core blockchain components
+        let storage = match self.storage {
+            Some(storage) => storage,
+            None => Storage::new(&config.data_dir)?,
+        };
+        let snapshot_dir = Path::new(&config.data_dir).join("snapshots");
+
+        // a trusted checkpoint stands in for the blocks before it, on a new database
+        if let Some(trusted) = &config.checkpoint {
+            Checkpoint::load(Path::new(&trusted.path))?
+                .install(&storage, &trusted.block_hash)
+                .context("Trusted checkpoint rejected")?;
+        }
+
+        // catch a corrupted or edited database before building on top of it
+        if config.startup_verification != VerifyDepth::None {
+            let summary = ChainVerifier::new(&storage)
+                .with_snapshot_dir(&snapshot_dir)
+                .with_dev_accounts(config.dev)
+                .with_block_rewards(config.block_rewards.clone())
+                .with_consensus_config(config.consensus.clone())
+                .verify(config.startup_verification)
+                .await
+                .context("Stored chain failed startup verification")?;
+            println!(
+                "🔍 Verified blocks #{}..=#{} ({} replayed from {})",
+                summary.first_block,
+                summary.last_block,
+                summary.replayed,
+                summary
+                    .snapshot_block
+                    .map_or("the first block".to_string(), |b| format!(
+                        "snapshot #{}",
+                        b
+                    ))
+            );
+        }
+        let blockchain = match &chain_spec {
+            Some(spec) => Blockchain::from_chain_spec(storage, spec, Some(keypair.clone()))?,
+            None => {
+                let validators: Vec<(Address, u64)> = config.load_validators()?;
+                Blockchain::from_storage(
+                    storage,
+                    MIN_STAKE,
+                    config.consensus.slot_duration,
+                    validators,
+                    Some(keypair.clone()),
+                )?
+            }
+        }
+        .with_quarantine(config.quarantine.clone())
+        .with_future_blocks(config.future_blocks.clone())
+        .with_slashing(config.slashing.clone())
+        .with_liveness(config.liveness.clone())
+        .with_epoch_rewards(config.epoch_rewards.clone())
+        .with_consensus_config(config.consensus.clone());
+
+        // without a chain spec there is no genesis block, dev accounts are funded on a new state
+        let funded = if chain_spec.is_none() && !blockchain.state_restored() {
+            blockchain
+                .execution_engine
+                .prefund_accounts(&dev_balances)
+                .await
+        } else {
+            0
+        };
+        let blockchain = if config.signing_audit.enabled {
+            let path = config.signing_audit.path(&config.data_dir);
+            println!("📜 Signatures audited in {}", path.display());
+            blockchain.with_signing_audit(SigningAuditLog::new(path))
+        } else {
+            blockchain
+        };
+        let blockchain = match self.election {
+            Some(election) => {
+                println!("🗳️  Custom proposer election");
+                blockchain.with_proposer_election(election)
+            }
+            None => blockchain,
+        };
+        let blockchain = match &config.adaptive_slots {
+            Some(adaptive_slots) => {
+                println!("🧪 Adaptive slot duration enabled: {:?}", adaptive_slots);
+                blockchain.with_adaptive_slots(adaptive_slots.clone())
+            }
+            None => blockchain,
+        };
+        let beacon = config
+            .randomness_beacon
+            .clone()
+            .map(RandomnessBeacon::new)
+            .transpose()?;
+        let blockchain = match &beacon {
+            Some(beacon) => {
+                println!(
+                    "🎲 Proposer schedule mixed with beacon {} from {}",
+                    beacon.config().chain_hash,
+                    beacon.config().url
+                );
+                blockchain.with_randomness_beacon(beacon)
+            }
+            None => blockchain,
+        };
+
+        println!("🔑 Node validator address: {}", keypair.address);
+
+        blockchain.execution_engine.set_chain_id(config.chain_id);
+        blockchain
+            .execution_engine
+            .set_admission_policy(config.mempool.clone())
+            .await;
+        blockchain
+            .execution_engine
+            .set_tx_policy(config.tx_policy.build())
+            .await;
+        blockchain
+            .execution_engine
+            .set_block_rewards(config.block_rewards.clone())
+            .await;
+        if config.parallel_execution.enabled {
+            println!(
+                "🧵 Parallel execution for blocks of {}+ transactions",
+                config.parallel_execution.min_transactions
+            );
+        }
+        blockchain
+            .execution_engine
+            .set_parallel_execution(config.parallel_execution.clone())
+            .await;
+
+        // warm up state in the background while the rpc server binds
+        let warmup_blocks = config.state_warmup_blocks;
+        let warmup = {
+            let blockchain = blockchain.clone();
+            tokio::spawn(async move { blockchain.warm_up_state(warmup_blocks).await })
+        };
+
+        // bind the rpc port, the certificate is loaded (or generated) before serving
+        let rpc_tls = match &config.rpc_tls {
+            Some(tls) => Some(tls.server_config(&config.data_dir)?),
+            None => None,
+        };
+        let rpc_ip: IpAddr = match &config.rpc_tls {
+            Some(tls) => tls.bind_address.parse()?,
+            None => IpAddr::from([127, 0, 0, 1]),
+        };
+        let rpc_listener = TcpListener::bind((rpc_ip, rpc_port)).await?;
+
+        // only serve requests once the warm-up is done
+        match warmup.await? {
+            Ok(accounts) => println!(
+                "🔥 Warmed up {} accounts from the last {} blocks",
+                accounts, warmup_blocks
+            ),
+            Err(e) => println!("⚠️  State warm-up failed: {}", e),
+        }
+
+        if config.dev {
+            match &chain_spec {
+                Some(_) => println!(
+                    "🧑‍💻 Dev mode: {} dev accounts in the genesis allocation",
+                    dev_accounts.len()
+                ),
+                None => println!(
+                    "🧑‍💻 Dev mode: funded {} of {} dev accounts",
+                    funded,
+                    dev_accounts.len()
+                ),
+            }
+            for account in &dev_accounts {
+                println!("   ({}) {}", account.index, account.address);
+            }
+        }
+
+        // 3. Create network service, the admin rpc needs a handle to it
+        let channels = NetworkChannels {
+            to_blockchain: network_to_blockchain_tx,
+            from_blockchain: blockchain_to_network_rx,
+        };
+        let network: Box<dyn NetworkTransport> = match self.network {
+            Some(factory) => factory(channels)?,
+            None => Box::new(
+                NetworkService::new(
+                    config.chain_id,
+                    config.replay_horizon_slots,
+                    &config.gossip_signing,
+                    channels.to_blockchain,
+                    channels.from_blockchain,
+                )
+                .await?,
+            ),
+        };
+
+        // namespaces and tokens can change while the servers keep running
+        let rpc_access = RpcAccess::new(&config.rpc_access)?;
+        let fee_protection = FeeProtection::new(config.max_gas_price_multiplier);
+        let mut rpc = SpeedRpcImpl::with_fee_protection(blockchain.clone(), fee_protection)
+            .with_rpc_access(rpc_access.clone(), config.admin_rpc)
+            .with_features(node_features(&config, chain_spec.as_ref()))
+            .with_dev_accounts(dev_accounts)
+            .with_chain_id(config.chain_id)
+            .with_tuning(TuningKnobs::from_config(&config))
+            .with_trusted_relayers(config.relay.trusted_relayers.clone());
+        if !config.relay.validators.is_empty() {
+            let relay = TransactionRelay::new(&config.relay, keypair.clone(), config.chain_id)?;
+            println!(
+                "📨 Relay mode: forwarding transactions to {} validators as {}",
+                config.relay.validators.len(),
+                relay.relayer()
+            );
+            rpc = rpc.with_relay(relay);
+        }
+        let mut rpc_module = rpc.into_rpc();
+        rpc_module.merge(SubscriptionRpcImpl::new(blockchain.clone()).into_rpc())?;
+        if config.admin_rpc {
+            let admin = AdminRpcImpl::new(
+                blockchain.clone(),
+                // without peer management the admin calls fail as if the network stopped
+                network
+                    .admin()
+                    .unwrap_or_else(|| NetworkAdmin::new(unbounded_channel().0)),
+                snapshot_dir,
+            )
+            .with_rpc_access(rpc_access.clone());
+            rpc_module.merge(admin.into_rpc())?;
+            println!("🛠️  Admin RPC namespace enabled");
+        }
+        // Start RPC server, shares the engines with the blockchain service
+        let rpc_handle = match rpc_tls {
+            Some(tls) => {
+                println!("🔒 RPC server listening on https://{}:{}", rpc_ip, rpc_port);
+                start_tls_server(rpc_listener, rpc_module, tls, &rpc_access)
+            }
+            None => {
+                println!("🌐 RPC server listening on {}:{}", rpc_ip, rpc_port);
+                gated_server_builder(&rpc_access)
+                    .build_from_tcp(rpc_listener.into_std()?)?
+                    .start(rpc_module)
+            }
+        };
+
+        // keeps the proposer schedule of the next epoch fed with beacon randomness
+        let beacon_task = beacon.map(|beacon| tokio::spawn(beacon.run(blockchain.clone())));
+
+        // 4. Create blockchain service
+        let mut blockchain_service = BlockchainService::new(
+            network_to_blockchain_rx,
+            blockchain_to_network_tx,
+            blockchain,
+            keypair,
+            config.dry_run,
+            config.proposer_safety.clone(),
+        )
+        .with_rebroadcast(config.rebroadcast.clone())
+        .with_sync_holding(config.sync_holding.clone())
+        .with_proposal_prefetch(config.proposal_prefetch.clone())
+        .with_gossip_throttle(config.gossip_throttle.clone());
+
+        let watchdog_task = if config.watchdog.enabled {
+            let watchdog = blockchain_service.watchdog(config.watchdog.clone()).await;
+            Some(tokio::spawn(watchdog.run()))
+        } else {
+            None
+        };
+
+        // 5. Start network service in separate task
+        let network_task = tokio::spawn(async move {
+            println!("📡 Starting network service...");
+            network.serve(port).await
+        });
+
+        // 6. Start blockchain service in separate task
+        let blockchain_task = tokio::spawn(async move {
+            println!("⛓️  Starting blockchain service...");
+            blockchain_service.run().await
+        });
+
+        println!("✅ SpeedNode started successfully!");
+
+        Ok(SpeedNode {
+            network_task,
+            blockchain_task,
+            watchdog_task,
+            beacon_task,
+            rpc_handle,
+        })
+    }
+}
@@ -1,3 +1,7 @@
+pub mod builder;
+pub mod multi_chain;
 pub mod node;
 
+pub use builder::*;
+pub use multi_chain::*;
 pub use node::*;
@@ -0,0 +1,57 @@
+use anyhow::Result;
+use tokio::{signal, task::JoinSet};
+
+use super::SpeedNode;
+use crate::MultiChainConfig;
+
+// runs several independent chains on one tokio runtime
+// each chain has its own storage, network and rpc server
+pub struct MultiChainNode {
+    nodes: Vec<(u64, SpeedNode)>,
+}
+
+impl MultiChainNode {
+    pub async fn new(config: MultiChainConfig) -> Result<Self> {
+        config.validate()?;
+
+        let mut nodes = Vec::new();
+        for chain in config.chains {
+            let chain_id = chain.chain_id;
+            nodes.push((chain_id, SpeedNode::new(chain).await?));
+        }
+
+        println!("✅ Started {} chains", nodes.len());
+        Ok(Self { nodes })
+    }
+
+    // run until every chain stopped or Ctrl+C
+    // a failing chain is logged and does not stop the others
+    pub async fn run(self) -> Result<()> {
+        let mut chains = JoinSet::new();
+        for (chain_id, mut node) in self.nodes {
+            chains.spawn(async move {
+                node.wait().await;
+                node.shutdown();
+                chain_id
+            });
+        }
+
+        loop {
+            tokio::select! {
+                finished = chains.join_next() => match finished {
+                    Some(Ok(chain_id)) => println!("⛓️  Chain {} stopped", chain_id),
+                    Some(Err(e)) => println!("❌ Chain task failed: {}", e),
+                    None => break,
+                },
+
+                _ = signal::ctrl_c() => {
+                    println!("🛑 Shutdown signal received, stopping all chains");
+                    chains.abort_all();
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
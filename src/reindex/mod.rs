@@ -0,0 +1,5 @@
+pub mod reindexer;
+pub mod verifier;
+
+pub use reindexer::*;
+pub use verifier::*;
@@ -0,0 +1,163 @@
+use anyhow::{Context, Result, anyhow};
+
+use crate::consensus::ConsensusConfig;
+use crate::core::Block;
+use crate::{BlockRewardConfig, ExecutionEngine, Storage, dev_accounts};
+
+#[derive(Debug, Clone, Default)]
+pub struct ReindexOptions {
+    // replay on top of the pre-funded dev accounts, for chains run with `--dev`
+    pub dev: bool,
+    // ignore stored progress and index every block again
+    pub restart: bool,
+    // the schedule the chain ran with, rewards are part of the state root
+    pub block_rewards: BlockRewardConfig,
+    // the epoch length the chain ran with, epoch settlements replay at its boundaries
+    pub consensus: ConsensusConfig,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReindexSummary {
+    pub blocks: u64,
+    // blocks replayed only to rebuild state, indexed by an earlier run
+    pub already_indexed: u64,
+    pub receipts_backfilled: u64,
+    pub state_diffs_backfilled: u64,
+    // blocks whose replayed state root differs from the header, their receipts may be wrong
+    pub state_root_mismatches: u64,
+}
+
+// replays stored blocks on a fresh state and backfills receipts, state diffs,
+// tx indexes, address history and blooms for databases created before they existed
+pub struct Reindexer {
+    storage: Storage,
+}
+
+impl Reindexer {
+    pub fn new(storage: Storage) -> Self {
+        Self { storage }
+    }
+
+    // state lives in memory, so every run replays from the first block,
+    // progress only lets it skip writing blocks a previous run already indexed
+    pub async fn run(&self, options: &ReindexOptions) -> Result<ReindexSummary> {
+        let mut summary = ReindexSummary::default();
+        let Some(last_index) = self.storage.get_last_index()? else {
+            return Ok(summary);
+        };
+
+        // chains started from a chain spec replay from its genesis state
+        let engine = match self.storage.get_chain_spec()? {
+            Some(spec) => {
+                let engine = ExecutionEngine::new()
+                    .with_gas_config(spec.gas.clone())
+                    .with_contract_runtime(spec.contract_runtime);
+                *engine.state_manager.lock().await = spec.genesis_state();
+                engine
+            }
+            None => ExecutionEngine::new(),
+        };
+        engine
+            .set_block_rewards(options.block_rewards.clone())
+            .await;
+        if options.dev {
+            let balances: Vec<_> = dev_accounts()?
+                .iter()
+                .map(|a| (a.address, a.balance))
+                .collect();
+            engine.prefund_accounts(&balances).await;
+        }
+
+        let progress = match options.restart {
+            true => None,
+            false => self.storage.get_reindex_progress()?,
+        };
+        if let Some(progress) = progress {
+            println!("⏩ Resuming reindex after block #{}", progress);
+        }
+
+        // databases started from a checkpoint replay on its state, from the block after it
+        let (first_index, mut parent_slot) = match self.storage.get_checkpoint()? {
+            Some(checkpoint) => {
+                *engine.state_manager.lock().await = checkpoint.state;
+                (
+                    checkpoint.block.header.index + 1,
+                    checkpoint.block.header.slot,
+                )
+            }
+            None => (0, 0),
+        };
+
+        for index in first_index..=last_index {
+            let Some(block_hash) = self.storage.get_block_hash_from_index(&index)? else {
+                continue;
+            };
+            let block: Block = self
+                .storage
+                .get_block_from_block_hash(&block_hash)?
+                .ok_or_else(|| anyhow!("Block #{} missing for hash {}", index, block_hash))?;
+
+            let mut replay = block.clone();
+            let result = engine
+                .execute_block_commit(&mut replay)
+                .await
+                .with_context(|| format!("Failed to replay block #{}", index))?;
+            summary.blocks += 1;
+            replay_epoch_settlements(
+                &self.storage,
+                &engine,
+                &options.consensus,
+                parent_slot,
+                block.header.slot,
+            )
+            .await?;
+            parent_slot = block.header.slot;
+
+            if result.state_root != block.header.state_root {
+                summary.state_root_mismatches += 1;
+                println!(
+                    "⚠️  Block #{} replayed to state root {}, header has {}",
+                    index, result.state_root, block.header.state_root
+                );
+            }
+
+            if progress.is_some_and(|progress| index <= progress) {
+                summary.already_indexed += 1;
+                continue;
+            }
+
+            // keep receipts written at commit time, only fill the gaps
+            if self.storage.get_block_receipts(&block_hash)?.is_none() {
+                self.storage
+                    .put_block_receipts(&block_hash, &result.receipts)?;
+                summary.receipts_backfilled += 1;
+            }
+            if self.storage.get_block_state_diff(&block_hash)?.is_none() {
+                self.storage
+                    .put_block_state_diff(&block_hash, &result.state_diff)?;
+                summary.state_diffs_backfilled += 1;
+            }
+            self.storage.put_block_indexes(&block)?;
+            self.storage.put_reindex_progress(index)?;
+        }
+
+        Ok(summary)
+    }
+}
+
+// apply the stored settlements of the epochs a block at `slot` closed, the node applied
+// them right after committing it, so the next block's state root includes them
+pub async fn replay_epoch_settlements(
+    storage: &Storage,
+    engine: &ExecutionEngine,
+    config: &ConsensusConfig,
+    parent_slot: u64,
+    slot: u64,
+) -> Result<()> {
+    for epoch in config.epoch_of(parent_slot)..config.epoch_of(slot) {
+        if let Some(settlements) = storage.get_epoch_settlements(epoch)? {
+            engine.apply_epoch_settlements(&settlements).await;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,390 @@
+use alloy::primitives::B256;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use super::replay_epoch_settlements;
+use crate::consensus::ConsensusConfig;
+use crate::core::Block;
+use crate::{BlockRewardConfig, ExecutionEngine, StateManager, Storage, dev_accounts};
+
+// how many of the newest stored blocks are checked before the node starts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "DepthValue", into = "DepthValue")]
+pub enum VerifyDepth {
+    #[default]
+    None,
+    Blocks(u64),
+    Full,
+}
+
+impl VerifyDepth {
+    // first block to check on a chain ending at `last_index`, None when nothing is checked
+    pub fn first_block(&self, last_index: u64) -> Option<u64> {
+        match self {
+            VerifyDepth::None | VerifyDepth::Blocks(0) => None,
+            VerifyDepth::Blocks(blocks) => Some(last_index.saturating_sub(blocks - 1).max(1)),
+            VerifyDepth::Full => Some(1),
+        }
+    }
+}
+
+impl FromStr for VerifyDepth {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(VerifyDepth::None),
+            "full" => Ok(VerifyDepth::Full),
+            other => other
+                .parse()
+                .map(VerifyDepth::Blocks)
+                .map_err(|_| format!("expected none, full or a block count, got {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for VerifyDepth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyDepth::None => write!(f, "none"),
+            VerifyDepth::Blocks(blocks) => write!(f, "{}", blocks),
+            VerifyDepth::Full => write!(f, "full"),
+        }
+    }
+}
+
+// `"none"`, `"full"` or a block count in config files
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum DepthValue {
+    Blocks(u64),
+    Named(String),
+}
+
+impl TryFrom<DepthValue> for VerifyDepth {
+    type Error = String;
+
+    fn try_from(value: DepthValue) -> Result<Self, Self::Error> {
+        match value {
+            DepthValue::Blocks(blocks) => Ok(VerifyDepth::Blocks(blocks)),
+            DepthValue::Named(name) => name.parse(),
+        }
+    }
+}
+
+impl From<VerifyDepth> for DepthValue {
+    fn from(depth: VerifyDepth) -> Self {
+        match depth {
+            VerifyDepth::Blocks(blocks) => DepthValue::Blocks(blocks),
+            other => DepthValue::Named(other.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum ChainVerificationError {
+    #[error("Block #{index} is missing from storage")]
+    MissingBlock { index: u64 },
+    #[error("Block #{index} does not hash to the hash it is stored under")]
+    HashMismatch { index: u64 },
+    #[error("Block #{index} does not point to the block before it")]
+    BrokenLink { index: u64 },
+    #[error("Block #{index} is not signed by its proposer")]
+    BadSignature { index: u64 },
+    #[error("Block #{index} transactions do not match its transactions root")]
+    TransactionsRoot { index: u64 },
+    #[error("Block #{index} replayed to state root {actual}, header has {expected}")]
+    StateRoot {
+        index: u64,
+        expected: B256,
+        actual: B256,
+    },
+    #[error("Block #{index} replayed to receipts root {actual}, header has {expected}")]
+    ReceiptsRoot {
+        index: u64,
+        expected: B256,
+        actual: B256,
+    },
+    #[error("Block #{index} replayed to a logs bloom other than its header's")]
+    LogsBloom { index: u64 },
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VerificationSummary {
+    pub first_block: u64,
+    pub last_block: u64,
+    pub verified: u64,
+    // state snapshot the replay started from, the first block when None
+    pub snapshot_block: Option<u64>,
+    // blocks executed to rebuild the state, including the verified ones
+    pub replayed: u64,
+}
+
+// checks links, hashes, signatures and transaction roots of the newest stored blocks
+// and recomputes their state roots, replaying from the latest state snapshot before them
+pub struct ChainVerifier<'a> {
+    storage: &'a Storage,
+    snapshot_dir: Option<PathBuf>,
+    // replay on top of the pre-funded dev accounts, for chains run with `--dev`
+    dev: bool,
+    block_rewards: BlockRewardConfig,
+    consensus: ConsensusConfig,
+}
+
+impl<'a> ChainVerifier<'a> {
+    pub fn new(storage: &'a Storage) -> Self {
+        Self {
+            storage,
+            snapshot_dir: None,
+            dev: false,
+            block_rewards: BlockRewardConfig::default(),
+            consensus: ConsensusConfig::default(),
+        }
+    }
+
+    // `state-<block>.json` files written by `admin_triggerSnapshot`
+    pub fn with_snapshot_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.snapshot_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn with_dev_accounts(mut self, dev: bool) -> Self {
+        self.dev = dev;
+        self
+    }
+
+    // the schedule the chain ran with, rewards are part of the state root
+    pub fn with_block_rewards(mut self, rewards: BlockRewardConfig) -> Self {
+        self.block_rewards = rewards;
+        self
+    }
+
+    // the epoch length the chain ran with, epoch settlements replay at its boundaries
+    pub fn with_consensus_config(mut self, consensus: ConsensusConfig) -> Self {
+        self.consensus = consensus;
+        self
+    }
+
+    pub async fn verify(&self, depth: VerifyDepth) -> Result<VerificationSummary> {
+        let Some(last_index) = self.storage.get_last_index()? else {
+            return Ok(VerificationSummary::default());
+        };
+        let Some(mut first_block) = depth.first_block(last_index) else {
+            return Ok(VerificationSummary::default());
+        };
+        // a database started from a checkpoint has no blocks before it to check
+        let checkpoint = self.storage.get_checkpoint()?;
+        if let Some(checkpoint) = &checkpoint {
+            first_block = first_block.max(checkpoint.block.header.index + 1);
+            if first_block > last_index {
+                return Ok(VerificationSummary::default());
+            }
+        }
+
+        // chains started from a chain spec replay from its genesis block and state
+        let spec = self.storage.get_chain_spec()?;
+        let engine = match &spec {
+            Some(spec) => ExecutionEngine::new()
+                .with_gas_config(spec.gas.clone())
+                .with_contract_runtime(spec.contract_runtime),
+            None => ExecutionEngine::new(),
+        };
+        engine.set_block_rewards(self.block_rewards.clone()).await;
+        let snapshot = self.snapshot_before(first_block)?;
+        let snapshot_block = snapshot.as_ref().map(|(block, _)| *block);
+        let replay_from = match (snapshot, checkpoint) {
+            (Some((block, state)), _) => {
+                *engine.state_manager.lock().await = state;
+                block + 1
+            }
+            (None, Some(checkpoint)) => {
+                *engine.state_manager.lock().await = checkpoint.state;
+                checkpoint.block.header.index + 1
+            }
+            (None, None) => {
+                if let Some(spec) = &spec {
+                    *engine.state_manager.lock().await = spec.genesis_state();
+                }
+                if self.dev {
+                    let balances: Vec<_> = dev_accounts()?
+                        .iter()
+                        .map(|a| (a.address, a.balance))
+                        .collect();
+                    engine.prefund_accounts(&balances).await;
+                }
+                1
+            }
+        };
+
+        let mut summary = VerificationSummary {
+            first_block,
+            last_block: last_index,
+            snapshot_block,
+            ..VerificationSummary::default()
+        };
+        let mut parent_hash = match replay_from {
+            1 => spec.map_or(B256::ZERO, |spec| spec.genesis_block().header.hash()),
+            index => self.block_hash(index - 1)?,
+        };
+        let mut parent_slot = match replay_from {
+            1 => 0,
+            index => {
+                self.storage
+                    .get_block_from_block_hash::<Block>(&parent_hash)?
+                    .ok_or(ChainVerificationError::MissingBlock { index: index - 1 })?
+                    .header
+                    .slot
+            }
+        };
+
+        for index in replay_from..=last_index {
+            let block_hash = self.block_hash(index)?;
+            let block: Block = self
+                .storage
+                .get_block_from_block_hash(&block_hash)?
+                .ok_or(ChainVerificationError::MissingBlock { index })?;
+
+            let checked = index >= first_block;
+            if checked {
+                check_block(index, &block, &block_hash, &parent_hash)?;
+            }
+
+            let mut replay = block.clone();
+            let result = engine
+                .execute_block_commit(&mut replay)
+                .await
+                .with_context(|| format!("Failed to replay block #{}", index))?;
+            summary.replayed += 1;
+            replay_epoch_settlements(
+                self.storage,
+                &engine,
+                &self.consensus,
+                parent_slot,
+                block.header.slot,
+            )
+            .await?;
+
+            if checked {
+                if result.state_root != block.header.state_root {
+                    return Err(ChainVerificationError::StateRoot {
+                        index,
+                        expected: block.header.state_root,
+                        actual: result.state_root,
+                    }
+                    .into());
+                }
+                // blocks stored before headers committed to their receipts have nothing to check
+                if let Some(expected) = block.header.receipts_root
+                    && result.receipts_root != expected
+                {
+                    return Err(ChainVerificationError::ReceiptsRoot {
+                        index,
+                        expected,
+                        actual: result.receipts_root,
+                    }
+                    .into());
+                }
+                if block
+                    .header
+                    .logs_bloom
+                    .is_some_and(|bloom| bloom != result.logs_bloom)
+                {
+                    return Err(ChainVerificationError::LogsBloom { index }.into());
+                }
+                summary.verified += 1;
+            }
+            parent_hash = block_hash;
+            parent_slot = block.header.slot;
+        }
+
+        Ok(summary)
+    }
+
+    fn block_hash(&self, index: u64) -> Result<B256> {
+        Ok(self
+            .storage
+            .get_block_hash_from_index(&index)?
+            .ok_or(ChainVerificationError::MissingBlock { index })?)
+    }
+
+    // newest snapshot taken before `first_block` whose state root matches its block
+    fn snapshot_before(&self, first_block: u64) -> Result<Option<(u64, StateManager)>> {
+        let Some(dir) = &self.snapshot_dir else {
+            return Ok(None);
+        };
+        if !dir.exists() {
+            return Ok(None);
+        }
+
+        let mut blocks: Vec<u64> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                name.strip_prefix("state-")?
+                    .strip_suffix(".json")?
+                    .parse()
+                    .ok()
+            })
+            .filter(|block| *block >= 1 && *block < first_block)
+            .collect();
+        blocks.sort_unstable();
+
+        while let Some(block) = blocks.pop() {
+            let path = dir.join(format!("state-{}.json", block));
+            let data = fs::read(&path)
+                .with_context(|| format!("Failed to read snapshot {}", path.display()))?;
+            let state: StateManager = match serde_json::from_slice(&data) {
+                Ok(state) => state,
+                Err(e) => {
+                    println!("⚠️  Skipping snapshot {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+
+            let Some(header_root) = self
+                .storage
+                .get_block_hash_from_index(&block)?
+                .and_then(|hash| self.storage.get_block_from_block_hash::<Block>(&hash).ok())
+                .flatten()
+                .map(|b| b.header.state_root)
+            else {
+                continue;
+            };
+            if state.get_state_root() != header_root || state.computed_state_root() != header_root {
+                println!(
+                    "⚠️  Skipping snapshot {}, its state root doesn't match block #{}",
+                    path.display(),
+                    block
+                );
+                continue;
+            }
+            return Ok(Some((block, state)));
+        }
+        Ok(None)
+    }
+}
+
+fn check_block(
+    index: u64,
+    block: &Block,
+    block_hash: &B256,
+    parent_hash: &B256,
+) -> Result<(), ChainVerificationError> {
+    if block.header.hash() != *block_hash {
+        return Err(ChainVerificationError::HashMismatch { index });
+    }
+    if block.header.parent_hash != *parent_hash {
+        return Err(ChainVerificationError::BrokenLink { index });
+    }
+    if block.header.verify_signature().is_err() {
+        return Err(ChainVerificationError::BadSignature { index });
+    }
+    if Block::calculate_transactions_root(&block.transactions) != block.header.transactions_root {
+        return Err(ChainVerificationError::TransactionsRoot { index });
+    }
+    Ok(())
+}
@@ -0,0 +1,308 @@
+use anyhow::{Result, anyhow};
+use jsonrpsee::server::middleware::http::ProxyGetRequestLayer;
+use jsonrpsee::server::middleware::rpc::{
+    Batch, BatchEntry, MethodResponse, Notification, Request, RpcServiceBuilder, RpcServiceT,
+};
+use jsonrpsee::server::{HttpRequest, Server, ServerBuilder};
+use jsonrpsee::types::{ErrorCode, ErrorObject, Id};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use std::task::{Context, Poll};
+use tower::ServiceBuilder;
+use tower::layer::util::{Identity, Stack};
+
+use super::RPC_NAMESPACES;
+
+// error code returned when a request carries no valid token
+pub const UNAUTHORIZED_ERROR_CODE: i32 = -32011;
+// can't be disabled, it is how namespaces are turned back on
+pub const ADMIN_NAMESPACE: &str = "admin";
+// `GET /metrics` answers with the result of `speed_getConsensusMetrics`
+pub const METRICS_PATH: &str = "/metrics";
+
+// namespaces served and tokens accepted when the node starts, both changeable at runtime
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RpcAccessConfig {
+    pub disabled_namespaces: Vec<String>,
+    // callers send `Authorization: Bearer <token>`, anyone may call when empty
+    pub tokens: Vec<String>,
+}
+
+// one version of the access rules, requests only ever see a whole one
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RpcAccessRules {
+    disabled_namespaces: BTreeSet<String>,
+    tokens: BTreeSet<String>,
+}
+
+impl RpcAccessRules {
+    pub fn serves(&self, method: &str) -> bool {
+        !self.disabled_namespaces.contains(namespace_of(method))
+    }
+
+    pub fn authorizes(&self, token: Option<&str>) -> bool {
+        self.tokens.is_empty() || token.is_some_and(|token| self.tokens.contains(token))
+    }
+}
+
+// what `admin_rpcAccess` reports, tokens are only counted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcAccessStatus {
+    pub enabled_namespaces: Vec<String>,
+    pub disabled_namespaces: Vec<String>,
+    pub tokens: usize,
+}
+
+// `eth_blockNumber` -> `eth`
+pub fn namespace_of(method: &str) -> &str {
+    method
+        .split_once('_')
+        .map_or(method, |(namespace, _)| namespace)
+}
+
+// shared by the servers and the admin api, every request loads the current rules
+// and changes swap in a new version, so nothing is restarted and no request sees half a change
+#[derive(Debug, Clone, Default)]
+pub struct RpcAccess {
+    rules: Arc<RwLock<Arc<RpcAccessRules>>>,
+}
+
+impl RpcAccess {
+    pub fn new(config: &RpcAccessConfig) -> Result<Self> {
+        let access = Self::default();
+        for namespace in &config.disabled_namespaces {
+            access.set_namespace(namespace, false)?;
+        }
+        access.rotate_tokens(config.tokens.clone());
+        Ok(access)
+    }
+
+    pub fn load(&self) -> Arc<RpcAccessRules> {
+        self.rules.read().unwrap().clone()
+    }
+
+    fn update(&self, change: impl FnOnce(&mut RpcAccessRules)) {
+        let mut rules = self.rules.write().unwrap();
+        let mut next = RpcAccessRules::clone(&rules);
+        change(&mut next);
+        *rules = Arc::new(next);
+    }
+
+    pub fn set_namespace(&self, namespace: &str, enabled: bool) -> Result<RpcAccessStatus> {
+        if namespace == ADMIN_NAMESPACE {
+            return Err(anyhow!("The admin namespace can't be toggled"));
+        }
+        if !RPC_NAMESPACES.contains(&namespace) {
+            return Err(anyhow!("Unknown RPC namespace: {}", namespace));
+        }
+
+        self.update(|rules| {
+            if enabled {
+                rules.disabled_namespaces.remove(namespace);
+            } else {
+                rules.disabled_namespaces.insert(namespace.to_string());
+            }
+        });
+        Ok(self.status())
+    }
+
+    // replaces every token, pass old and new together to rotate without locking clients out
+    pub fn rotate_tokens(&self, tokens: Vec<String>) -> RpcAccessStatus {
+        self.update(|rules| {
+            rules.tokens = tokens.into_iter().filter(|t| !t.is_empty()).collect();
+        });
+        self.status()
+    }
+
+    // namespaces a client can call right now, admin only on nodes serving it
+    pub fn served_namespaces(&self, admin: bool) -> Vec<String> {
+        let mut namespaces = self.status().enabled_namespaces;
+        if admin {
+            namespaces.push(ADMIN_NAMESPACE.to_string());
+        }
+        namespaces
+    }
+
+    pub fn status(&self) -> RpcAccessStatus {
+        let rules = self.load();
+        let (enabled, disabled): (Vec<&str>, Vec<&str>) = RPC_NAMESPACES
+            .iter()
+            .partition(|namespace| !rules.disabled_namespaces.contains(**namespace));
+        RpcAccessStatus {
+            enabled_namespaces: enabled.into_iter().map(String::from).collect(),
+            disabled_namespaces: disabled.into_iter().map(String::from).collect(),
+            tokens: rules.tokens.len(),
+        }
+    }
+}
+
+// bearer token of the http request (or websocket upgrade), read by `RpcGate`
+#[derive(Debug, Clone)]
+struct RpcToken(Option<String>);
+
+// http middleware copying the `Authorization` header into the request extensions
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RpcTokenLayer;
+
+impl<S> tower::Layer<S> for RpcTokenLayer {
+    type Service = RpcTokenService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        RpcTokenService { service }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RpcTokenService<S> {
+    service: S,
+}
+
+impl<S, B> tower::Service<HttpRequest<B>> for RpcTokenService<S>
+where
+    S: tower::Service<HttpRequest<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: HttpRequest<B>) -> Self::Future {
+        let token = request
+            .headers()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(|token| token.trim().to_string());
+        request.extensions_mut().insert(RpcToken(token));
+        self.service.call(request)
+    }
+}
+
+// server builder with both middlewares, for plain and tls servers alike
+// the metrics path is proxied after the token is read, so it is gated like the rpc call
+pub fn gated_server_builder(
+    access: &RpcAccess,
+) -> ServerBuilder<
+    Stack<ProxyGetRequestLayer, Stack<RpcTokenLayer, Identity>>,
+    Stack<RpcGateLayer, Identity>,
+> {
+    let metrics = ProxyGetRequestLayer::new([(METRICS_PATH, "speed_getConsensusMetrics")])
+        .expect("metrics path starts with /");
+    Server::builder()
+        .set_http_middleware(ServiceBuilder::new().layer(RpcTokenLayer).layer(metrics))
+        .set_rpc_middleware(RpcServiceBuilder::new().layer(RpcGateLayer {
+            access: access.clone(),
+        }))
+}
+
+#[derive(Debug, Clone)]
+pub struct RpcGateLayer {
+    access: RpcAccess,
+}
+
+impl<S> tower::Layer<S> for RpcGateLayer {
+    type Service = RpcGate<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        RpcGate {
+            service,
+            access: self.access.clone(),
+        }
+    }
+}
+
+// rpc middleware checking every call against the current access rules
+#[derive(Debug, Clone)]
+pub struct RpcGate<S> {
+    service: S,
+    access: RpcAccess,
+}
+
+fn unauthorized() -> ErrorObject<'static> {
+    ErrorObject::owned(
+        UNAUTHORIZED_ERROR_CODE,
+        "Missing or invalid RPC token",
+        None::<()>,
+    )
+}
+
+// None when the rules let the call through
+fn rejection(rules: &RpcAccessRules, request: &Request) -> Option<ErrorObject<'static>> {
+    let token = request.extensions().get::<RpcToken>();
+    if !rules.authorizes(token.and_then(|t| t.0.as_deref())) {
+        return Some(unauthorized());
+    }
+    if !rules.serves(request.method_name()) {
+        return Some(ErrorCode::MethodNotFound.into());
+    }
+    None
+}
+
+impl<S> RpcServiceT for RpcGate<S>
+where
+    S: RpcServiceT<
+            MethodResponse = MethodResponse,
+            BatchResponse = MethodResponse,
+            NotificationResponse = MethodResponse,
+        > + Clone
+        + Send
+        + Sync
+        + 'static,
+{
+    type MethodResponse = MethodResponse;
+    type NotificationResponse = MethodResponse;
+    type BatchResponse = MethodResponse;
+
+    fn call<'a>(&self, request: Request<'a>) -> impl Future<Output = MethodResponse> + Send + 'a {
+        let rejected = rejection(&self.access.load(), &request);
+        let service = self.service.clone();
+
+        async move {
+            match rejected {
+                Some(error) => MethodResponse::error(request.id, error),
+                None => service.call(request).await,
+            }
+        }
+    }
+
+    // a batch is refused as a whole when any of its calls would be
+    fn batch<'a>(&self, batch: Batch<'a>) -> impl Future<Output = MethodResponse> + Send + 'a {
+        let rules = self.access.load();
+        let rejected = batch.iter().find_map(|entry| match entry {
+            Ok(BatchEntry::Call(request)) => rejection(&rules, request),
+            _ => None,
+        });
+        let service = self.service.clone();
+
+        async move {
+            match rejected {
+                Some(error) => MethodResponse::error(Id::Null, error),
+                None => service.batch(batch).await,
+            }
+        }
+    }
+
+    fn notification<'a>(
+        &self,
+        notification: Notification<'a>,
+    ) -> impl Future<Output = MethodResponse> + Send + 'a {
+        let rules = self.access.load();
+        let token = notification.extensions().get::<RpcToken>();
+        let allowed = rules.authorizes(token.and_then(|t| t.0.as_deref()))
+            && rules.serves(notification.method_name());
+        let service = self.service.clone();
+
+        async move {
+            match allowed {
+                true => service.notification(notification).await,
+                false => MethodResponse::notification(),
+            }
+        }
+    }
+}
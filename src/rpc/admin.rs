@@ -0,0 +1,200 @@
+use alloy::primitives::B256;
+use jsonrpsee::{
+    core::{RpcResult, async_trait},
+    proc_macros::rpc,
+};
+use libp2p::PeerId;
+use std::path::PathBuf;
+
+use super::rpc::{error_to_rpc, invalid_params};
+use super::{RpcAccess, RpcAccessStatus};
+use crate::core::{
+    Blockchain, CONSENSUS_EVENTS_KEPT, CheckpointInfo, DebugState, StateSnapshotInfo,
+};
+use crate::{NetworkAdmin, PeerInfo, PeerStats, set_log_level};
+
+#[rpc(server, client)]
+// Operator methods, only served when the node runs with `admin_rpc` enabled
+pub trait AdminRpc {
+    /// List connected and banned peers
+    #[method(name = "admin_peers")]
+    async fn peers(&self) -> RpcResult<Vec<PeerInfo>>;
+    /// Gossip counts, bytes, duplicates and invalid messages per peer and message type
+    #[method(name = "admin_peerStats")]
+    async fn peer_stats(&self) -> RpcResult<Vec<PeerStats>>;
+    /// Disconnect a peer and refuse its connections, false if already banned
+    #[method(name = "admin_banPeer")]
+    async fn ban_peer(&self, peer_id: String) -> RpcResult<bool>;
+    /// Allow a banned peer again, false if it was not banned
+    #[method(name = "admin_unbanPeer")]
+    async fn unban_peer(&self, peer_id: String) -> RpcResult<bool>;
+    /// Drop every pending transaction, returns how many were removed
+    #[method(name = "admin_clearMempool")]
+    async fn clear_mempool(&self) -> RpcResult<usize>;
+    /// Drop one pending transaction, false if it was not pending
+    #[method(name = "admin_dropTransaction")]
+    async fn drop_transaction(&self, tx_hash: B256) -> RpcResult<bool>;
+    /// Set the tracing level of libp2p and jsonrpsee internals
+    #[method(name = "admin_setLogLevel")]
+    async fn set_log_level(&self, level: String) -> RpcResult<String>;
+    /// Write the current account state to the snapshot directory
+    #[method(name = "admin_triggerSnapshot")]
+    async fn trigger_snapshot(&self) -> RpcResult<StateSnapshotInfo>;
+    /// Write the head block, its state and block randomness to the snapshot directory, for
+    /// other nodes to start from
+    #[method(name = "admin_exportCheckpoint")]
+    async fn export_checkpoint(&self) -> RpcResult<CheckpointInfo>;
+    /// Consensus state, mempool summary and the latest consensus events, all by default
+    #[method(name = "admin_debugState")]
+    async fn debug_state(&self, events: Option<usize>) -> RpcResult<DebugState>;
+    /// Namespaces served and how many tokens are accepted
+    #[method(name = "admin_rpcAccess")]
+    async fn rpc_access(&self) -> RpcResult<RpcAccessStatus>;
+    /// Serve or stop serving a namespace, effective from the next request
+    #[method(name = "admin_setRpcNamespace")]
+    async fn set_rpc_namespace(
+        &self,
+        namespace: String,
+        enabled: bool,
+    ) -> RpcResult<RpcAccessStatus>;
+    /// Replace the accepted bearer tokens, an empty list lets anyone call
+    #[method(name = "admin_rotateRpcTokens")]
+    async fn rotate_rpc_tokens(&self, tokens: Vec<String>) -> RpcResult<RpcAccessStatus>;
+}
+
+pub struct AdminRpcImpl {
+    blockchain: Blockchain,
+    network: NetworkAdmin,
+    snapshot_dir: PathBuf,
+    rpc_access: RpcAccess,
+}
+
+impl AdminRpcImpl {
+    pub fn new(blockchain: Blockchain, network: NetworkAdmin, snapshot_dir: PathBuf) -> Self {
+        Self {
+            blockchain,
+            network,
+            snapshot_dir,
+            rpc_access: RpcAccess::default(),
+        }
+    }
+
+    // the access rules the rpc servers were started with
+    pub fn with_rpc_access(mut self, rpc_access: RpcAccess) -> Self {
+        self.rpc_access = rpc_access;
+        self
+    }
+}
+
+fn parse_peer_id(peer_id: &str) -> RpcResult<PeerId> {
+    peer_id.parse().map_err(invalid_params)
+}
+
+#[async_trait]
+impl AdminRpcServer for AdminRpcImpl {
+    // list peers known to the network task
+    async fn peers(&self) -> RpcResult<Vec<PeerInfo>> {
+        self.network.peers().await.map_err(error_to_rpc)
+    }
+
+    // per peer gossip stats
+    async fn peer_stats(&self) -> RpcResult<Vec<PeerStats>> {
+        self.network.peer_stats().await.map_err(error_to_rpc)
+    }
+
+    // ban a peer
+    async fn ban_peer(&self, peer_id: String) -> RpcResult<bool> {
+        let peer_id = parse_peer_id(&peer_id)?;
+        self.network.ban_peer(peer_id).await.map_err(error_to_rpc)
+    }
+
+    // unban a peer
+    async fn unban_peer(&self, peer_id: String) -> RpcResult<bool> {
+        let peer_id = parse_peer_id(&peer_id)?;
+        self.network.unban_peer(peer_id).await.map_err(error_to_rpc)
+    }
+
+    // clear the mempool
+    async fn clear_mempool(&self) -> RpcResult<usize> {
+        let removed = self.blockchain.execution_engine.clear_mempool().await;
+        println!(
+            "🧹 Mempool cleared by operator, {} transactions dropped",
+            removed
+        );
+        Ok(removed)
+    }
+
+    // drop a pending transaction
+    async fn drop_transaction(&self, tx_hash: B256) -> RpcResult<bool> {
+        let dropped = self
+            .blockchain
+            .execution_engine
+            .drop_transaction(&tx_hash)
+            .await;
+        if dropped {
+            println!("🧹 Transaction {} dropped by operator", tx_hash);
+        }
+        Ok(dropped)
+    }
+
+    // change tracing level
+    async fn set_log_level(&self, level: String) -> RpcResult<String> {
+        let level = set_log_level(&level).map_err(invalid_params)?;
+        Ok(level.to_string())
+    }
+
+    // write a state snapshot
+    async fn trigger_snapshot(&self) -> RpcResult<StateSnapshotInfo> {
+        self.blockchain
+            .write_state_snapshot(&self.snapshot_dir)
+            .await
+            .map_err(error_to_rpc)
+    }
+
+    // write a checkpoint of the head
+    async fn export_checkpoint(&self) -> RpcResult<CheckpointInfo> {
+        self.blockchain
+            .write_checkpoint(&self.snapshot_dir)
+            .await
+            .map_err(error_to_rpc)
+    }
+
+    // state for bug reports
+    async fn debug_state(&self, events: Option<usize>) -> RpcResult<DebugState> {
+        let events = events.unwrap_or(CONSENSUS_EVENTS_KEPT);
+        Ok(self.blockchain.debug_state(events).await)
+    }
+
+    // current access rules
+    async fn rpc_access(&self) -> RpcResult<RpcAccessStatus> {
+        Ok(self.rpc_access.status())
+    }
+
+    // toggle a namespace
+    async fn set_rpc_namespace(
+        &self,
+        namespace: String,
+        enabled: bool,
+    ) -> RpcResult<RpcAccessStatus> {
+        let status = self
+            .rpc_access
+            .set_namespace(&namespace, enabled)
+            .map_err(invalid_params)?;
+        println!(
+            "🔐 RPC namespace {} {} by operator",
+            namespace,
+            if enabled { "enabled" } else { "disabled" }
+        );
+        Ok(status)
+    }
+
+    // rotate tokens
+    async fn rotate_rpc_tokens(&self, tokens: Vec<String>) -> RpcResult<RpcAccessStatus> {
+        let status = self.rpc_access.rotate_tokens(tokens);
+        println!(
+            "🔐 RPC tokens rotated by operator, {} accepted",
+            status.tokens
+        );
+        Ok(status)
+    }
+}
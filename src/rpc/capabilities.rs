@@ -0,0 +1,162 @@
+use alloy::primitives::B256;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::reindex::VerifyDepth;
+use crate::{ChainSpec, ContractRuntimeKind, GOSSIP_TOPICS, NodeConfig, P2P_PROTOCOL_VERSION};
+
+// version of the JSON-RPC api exposed by this node
+pub const RPC_API_VERSION: u32 = 1;
+// version of the transaction encoding (hashing + raw format)
+pub const TRANSACTION_VERSION: u32 = 1;
+
+// RPC namespaces the node can serve, `rpc_access` may switch some off
+pub const RPC_NAMESPACES: &[&str] = &["eth", "web3", "speed", "txpool"];
+
+// node features tooling can check for before using them, whatever the config
+pub const NODE_FEATURES: &[&str] = &[
+    "allowances",
+    "batch-transfers",
+    "block-gas-limit",
+    "block-randomness",
+    "censorship-report",
+    "chain-info",
+    "chain-reorgs",
+    "chain-spec",
+    "checkpoint-sync",
+    "consensus-config",
+    "consensus-metrics",
+    "debug-state",
+    "double-vote-detection",
+    "epoch-checkpoints",
+    "epoch-settlements",
+    "epoch-statements",
+    "epoch-stats",
+    "event-logs",
+    "failed-transaction-gas",
+    "fee-protection",
+    "finality-certificates",
+    "finality-status",
+    "head-tags",
+    "idempotent-submission",
+    "inclusion-estimate",
+    "median-time",
+    "memo-commitments",
+    "mempool-sources",
+    "name-registry",
+    "peer-liveness",
+    "peer-stats",
+    "performance-report",
+    "persistent-state",
+    "precompiles",
+    "propagation-stats",
+    "proposer-lookahead",
+    "proposer-fees",
+    "proposer-schedule",
+    "read-only-calls",
+    "receipt-lookup",
+    "receipts-root",
+    "rpc-access-control",
+    "session-keys",
+    "skipped-slots",
+    "slashing",
+    "state-journal",
+    "subscriptions",
+    "transaction-memos",
+    "tx-gossip-signing-policy",
+    "validator-address-registration",
+    "validator-exit",
+    "validator-registration",
+    "validator-scores",
+    "validator-set-management",
+];
+
+// features this node runs with, the always available ones plus those its config and
+// chain spec switch on
+pub fn node_features(config: &NodeConfig, chain_spec: Option<&ChainSpec>) -> Vec<String> {
+    let prefetch = &config.proposal_prefetch;
+    let toggled = [
+        ("adaptive-slots", config.adaptive_slots.is_some()),
+        (
+            "base-fee",
+            chain_spec.is_some_and(|spec| spec.gas.base_fee.is_some()),
+        ),
+        ("block-rewards", !config.block_rewards.reward.is_zero()),
+        (
+            "block-templates",
+            prefetch.enabled && prefetch.block_template,
+        ),
+        ("dev-accounts", config.dev),
+        ("fault-injection", cfg!(feature = "fault-injection")),
+        ("future-block-queue", config.future_blocks.enabled),
+        ("gossip-throttle", config.gossip_throttle.enabled),
+        ("liveness-deactivation", config.liveness.enabled),
+        ("parallel-execution", config.parallel_execution.enabled),
+        ("proposal-prefetch", prefetch.enabled),
+        ("randomness-beacon", config.randomness_beacon.is_some()),
+        ("rejected-block-quarantine", config.quarantine.enabled),
+        ("relay-mode", !config.relay.validators.is_empty()),
+        ("signing-audit", config.signing_audit.enabled),
+        ("stall-watchdog", config.watchdog.enabled),
+        (
+            "startup-verification",
+            config.startup_verification != VerifyDepth::None,
+        ),
+        (
+            "wasm-contracts",
+            chain_spec.is_some_and(|spec| spec.contract_runtime != ContractRuntimeKind::None),
+        ),
+    ];
+
+    let mut features: Vec<String> = NODE_FEATURES.iter().map(|f| f.to_string()).collect();
+    features.extend(
+        toggled
+            .into_iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(feature, _)| feature.to_string()),
+    );
+    features.sort();
+    features
+}
+
+// client version string, `speed/v<version>/<os>-<arch>`
+pub fn client_version() -> String {
+    format!(
+        "speed/v{}/{}-{}",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    )
+}
+
+// everything a client needs to adapt to this node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeCapabilities {
+    pub client_version: String,
+    pub namespaces: Vec<String>,
+    pub protocol_versions: BTreeMap<String, u32>,
+    pub gossip_topics: Vec<String>,
+    pub chain_spec_hash: B256,
+    pub features: Vec<String>,
+}
+
+impl NodeCapabilities {
+    // collect capabilities of the running node, with the namespaces it serves right now
+    // and the features it runs with
+    pub fn collect(chain_spec_hash: B256, namespaces: Vec<String>, features: Vec<String>) -> Self {
+        let protocol_versions = BTreeMap::from([
+            ("p2p".to_string(), P2P_PROTOCOL_VERSION),
+            ("rpc".to_string(), RPC_API_VERSION),
+            ("transaction".to_string(), TRANSACTION_VERSION),
+        ]);
+
+        Self {
+            client_version: client_version(),
+            namespaces,
+            protocol_versions,
+            gossip_topics: GOSSIP_TOPICS.iter().map(|t| t.to_string()).collect(),
+            chain_spec_hash,
+            features,
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use alloy::primitives::U256;
+
+use crate::Transaction;
+
+// default multiple of the estimated gas price before a tx is considered overpaying
+pub const DEFAULT_MAX_GAS_PRICE_MULTIPLIER: u64 = 10;
+
+// user protection violations, a client can override them explicitly
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ProtectionError {
+    #[error("Gas price {gas_price} exceeds {multiplier}x the current estimate {estimate}")]
+    GasPriceTooHigh {
+        gas_price: U256,
+        estimate: U256,
+        multiplier: u64,
+    },
+    #[error("Value plus max fee {required} exceeds account balance {balance}")]
+    ExceedsBalance { required: U256, balance: U256 },
+}
+
+// guards users against fat-finger fees, shared by the wallet CLI and the RPC layer
+#[derive(Debug, Clone)]
+pub struct FeeProtection {
+    pub max_gas_price_multiplier: u64,
+}
+
+impl FeeProtection {
+    pub fn new(max_gas_price_multiplier: u64) -> Self {
+        Self {
+            max_gas_price_multiplier,
+        }
+    }
+
+    // check transaction against gas price estimate and sender balance
+    // returns every violation found, empty if the transaction is safe
+    pub fn check(&self, tx: &Transaction, estimate: U256, balance: U256) -> Vec<ProtectionError> {
+        let mut violations = Vec::new();
+
+        let max_gas_price = estimate.saturating_mul(U256::from(self.max_gas_price_multiplier));
+        if tx.gas_price > max_gas_price {
+            violations.push(ProtectionError::GasPriceTooHigh {
+                gas_price: tx.gas_price,
+                estimate,
+                multiplier: self.max_gas_price_multiplier,
+            });
+        }
+
+        let required = tx.max_transaction_cost();
+        if required > balance {
+            violations.push(ProtectionError::ExceedsBalance { required, balance });
+        }
+
+        violations
+    }
+}
+
+impl Default for FeeProtection {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_GAS_PRICE_MULTIPLIER)
+    }
+}
@@ -0,0 +1,84 @@
+use alloy::primitives::{Address, B256};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// how long a request id keeps returning the hash of its first transaction
+pub const IDEMPOTENCY_WINDOW: Duration = Duration::from_secs(600);
+// longest request id a client may send
+pub const MAX_REQUEST_ID_LEN: usize = 128;
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum RequestIdError {
+    #[error("Request id must not be empty")]
+    Empty,
+    #[error("Request id is {len} characters, max {max}")]
+    TooLong { len: usize, max: usize },
+}
+
+// remembers submissions by sender and client request id, so a wallet retrying over a
+// flaky connection gets the original transaction hash instead of adding a replacement
+#[derive(Debug)]
+pub struct IdempotencyCache {
+    window: Duration,
+    // (sender, request id) -> first transaction hash and when it was accepted
+    submitted: HashMap<(Address, String), (B256, Instant)>,
+}
+
+impl IdempotencyCache {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            submitted: HashMap::new(),
+        }
+    }
+
+    pub fn validate(request_id: &str) -> Result<(), RequestIdError> {
+        if request_id.is_empty() {
+            return Err(RequestIdError::Empty);
+        }
+        if request_id.len() > MAX_REQUEST_ID_LEN {
+            return Err(RequestIdError::TooLong {
+                len: request_id.len(),
+                max: MAX_REQUEST_ID_LEN,
+            });
+        }
+        Ok(())
+    }
+
+    // hash of the transaction first submitted with this id, if still within the window
+    pub fn lookup(&self, sender: Address, request_id: &str, now: Instant) -> Option<B256> {
+        match self.submitted.get(&(sender, request_id.to_string())) {
+            Some((tx_hash, accepted)) if now.duration_since(*accepted) <= self.window => {
+                Some(*tx_hash)
+            }
+            _ => None,
+        }
+    }
+
+    // only accepted transactions are recorded, a rejected request can be retried
+    pub fn record(&mut self, sender: Address, request_id: String, tx_hash: B256, now: Instant) {
+        self.prune(now);
+        self.submitted.insert((sender, request_id), (tx_hash, now));
+    }
+
+    // forget ids older than the window
+    pub fn prune(&mut self, now: Instant) {
+        let window = self.window;
+        self.submitted
+            .retain(|_, (_, accepted)| now.duration_since(*accepted) <= window);
+    }
+
+    pub fn len(&self) -> usize {
+        self.submitted.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.submitted.is_empty()
+    }
+}
+
+impl Default for IdempotencyCache {
+    fn default() -> Self {
+        Self::new(IDEMPOTENCY_WINDOW)
+    }
+}
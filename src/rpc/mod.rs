@@ -1,3 +1,19 @@
+pub mod access;
+pub mod admin;
+pub mod capabilities;
+pub mod fee_protection;
+pub mod idempotency;
+pub mod relay;
 pub mod rpc;
+pub mod subscriptions;
+pub mod tls;
 
+pub use access::*;
+pub use admin::AdminRpcImpl;
+pub use capabilities::*;
+pub use fee_protection::*;
+pub use idempotency::*;
+pub use relay::*;
 pub use rpc::SpeedRpcImpl;
+pub use subscriptions::*;
+pub use tls::*;
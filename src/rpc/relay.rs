@@ -0,0 +1,190 @@
+use alloy::primitives::{Address, B256, keccak256};
+use alloy_signer::Signature;
+use anyhow::{Result, anyhow};
+use jsonrpsee::core::ClientError;
+use jsonrpsee::http_client::{HeaderMap, HeaderValue, HttpClient, HttpClientBuilder};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+
+use super::rpc::SpeedBlockchainRpcClient;
+use crate::{KeyPair, Transaction};
+
+// how long a validator gets to answer a relayed transaction
+pub const RELAY_TIMEOUT_MS: u64 = 2_000;
+
+// what an edge node signs for every transaction it relays, bound to the chain
+pub fn relay_digest(chain_id: u64, tx_hash: &B256) -> B256 {
+    let mut data = b"speed-relay".to_vec();
+    data.extend_from_slice(&chain_id.to_be_bytes());
+    data.extend_from_slice(tx_hash.as_slice());
+    keccak256(data)
+}
+
+// relay mode: transactions admitted over rpc are also sent straight to validators,
+// which only accept them from relayers they trust
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RelayConfig {
+    // validator rpc endpoints to forward to, relay mode is off when empty
+    pub validators: Vec<RelayTarget>,
+    pub timeout_ms: u64,
+    // edge node addresses `speed_relayTransaction` accepts transactions from
+    pub trusted_relayers: Vec<Address>,
+}
+
+impl Default for RelayConfig {
+    fn default() -> Self {
+        Self {
+            validators: Vec::new(),
+            timeout_ms: RELAY_TIMEOUT_MS,
+            trusted_relayers: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelayTarget {
+    pub url: String,
+    // sent as a bearer token, for validators whose rpc requires one
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+// per validator counters, reported by `speed_getRelayStatus`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RelayTargetStats {
+    pub url: String,
+    pub relayed: u64,
+    pub accepted: u64,
+    // the validator answered with an error, e.g. it doesn't trust us or the nonce is stale
+    pub rejected: u64,
+    // timed out or couldn't connect
+    pub unreachable: u64,
+    pub last_error: Option<String>,
+    pub last_latency_ms: Option<u64>,
+}
+
+// the edge node's side of relay mode
+pub struct TransactionRelay {
+    targets: Vec<(String, HttpClient)>,
+    keypair: KeyPair,
+    chain_id: u64,
+    stats: Arc<Mutex<Vec<RelayTargetStats>>>,
+}
+
+impl TransactionRelay {
+    pub fn new(config: &RelayConfig, keypair: KeyPair, chain_id: u64) -> Result<Self> {
+        let mut targets = Vec::new();
+        for target in &config.validators {
+            let mut headers = HeaderMap::new();
+            if let Some(token) = &target.token {
+                headers.insert(
+                    "authorization",
+                    HeaderValue::from_str(&format!("Bearer {}", token))?,
+                );
+            }
+            let client = HttpClientBuilder::default()
+                .set_headers(headers)
+                .request_timeout(Duration::from_millis(config.timeout_ms))
+                .build(&target.url)
+                .map_err(|e| anyhow!("Invalid relay target {}: {}", target.url, e))?;
+            targets.push((target.url.clone(), client));
+        }
+
+        let stats = config
+            .validators
+            .iter()
+            .map(|target| RelayTargetStats {
+                url: target.url.clone(),
+                ..Default::default()
+            })
+            .collect();
+
+        Ok(Self {
+            targets,
+            keypair,
+            chain_id,
+            stats: Arc::new(Mutex::new(stats)),
+        })
+    }
+
+    // address validators must list in `trusted_relayers`
+    pub fn relayer(&self) -> Address {
+        self.keypair.address
+    }
+
+    // sends the transaction to every validator at once, returns how many accepted it
+    pub async fn relay(&self, tx: &Transaction) -> Result<usize> {
+        let digest = relay_digest(self.chain_id, &tx.hash);
+        let signature = self.keypair.sign_hash(&digest).await?;
+        let raw_tx = tx.encode_raw();
+        let relayer = self.relayer();
+
+        let mut requests = JoinSet::new();
+        for (index, (_, client)) in self.targets.iter().enumerate() {
+            let client = client.clone();
+            let raw_tx = raw_tx.clone();
+            requests.spawn(async move {
+                let started = Instant::now();
+                let result = client.relay_transaction(raw_tx, relayer, signature).await;
+                (index, result, started.elapsed())
+            });
+        }
+
+        let mut accepted = 0;
+        while let Some(joined) = requests.join_next().await {
+            let (index, result, elapsed) = joined?;
+            let mut stats = self.stats.lock().unwrap();
+            let target = &mut stats[index];
+            target.relayed += 1;
+            target.last_latency_ms = Some(elapsed.as_millis() as u64);
+            match result {
+                Ok(_) => {
+                    target.accepted += 1;
+                    accepted += 1;
+                }
+                Err(ClientError::Call(e)) => {
+                    println!("🚫 Relay to {} rejected: {}", target.url, e.message());
+                    target.rejected += 1;
+                    target.last_error = Some(e.message().to_string());
+                }
+                Err(e) => {
+                    println!("📴 Relay to {} failed: {}", target.url, e);
+                    target.unreachable += 1;
+                    target.last_error = Some(e.to_string());
+                }
+            }
+        }
+        Ok(accepted)
+    }
+
+    pub fn stats(&self) -> Vec<RelayTargetStats> {
+        self.stats.lock().unwrap().clone()
+    }
+}
+
+// the validator's side: the relayer must be trusted and must have signed this transaction
+pub fn verify_relayed(
+    trusted_relayers: &[Address],
+    chain_id: u64,
+    tx_hash: &B256,
+    relayer: Address,
+    signature: &Signature,
+) -> Result<()> {
+    if !trusted_relayers.contains(&relayer) {
+        return Err(anyhow!("{} is not a trusted relayer", relayer));
+    }
+    let signer = signature
+        .recover_address_from_prehash(&relay_digest(chain_id, tx_hash))
+        .map_err(|_| anyhow!("Invalid relay signature"))?;
+    if signer != relayer {
+        return Err(anyhow!(
+            "Relay signature is from {}, not {}",
+            signer,
+            relayer
+        ));
+    }
+    Ok(())
+}
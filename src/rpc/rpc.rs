@@ -1,15 +1,43 @@
+use alloy::primitives::{Address, B256, Bytes, U256};
+use alloy_signer::Signature;
 use jsonrpsee::{
     core::{RpcResult, async_trait},
     proc_macros::rpc,
-    types::{ErrorObject, error::INTERNAL_ERROR_CODE},
+    types::{
+        ErrorObject,
+        error::{INTERNAL_ERROR_CODE, INVALID_PARAMS_CODE},
+    },
 };
 
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::Mutex;
 
-use crate::core::Blockchain;
+use super::{
+    FeeProtection, IdempotencyCache, NodeCapabilities, ProtectionError, RelayTargetStats,
+    RpcAccess, TransactionRelay, UNAUTHORIZED_ERROR_CODE, client_version, node_features,
+    verify_relayed,
+};
+use crate::consensus::{
+    AttestationEquivocationEvidence, ConsensusMetricsReport, DoubleProposalEvidence,
+    EpochCheckpoint, EpochSchedule, EpochSettlement, EpochStatement, EpochStats,
+    FinalityCertificate, FinalityCheckpoints, ProposerDuty, ValidatorScoreReport,
+};
+use crate::core::{
+    BlockFinality, Blockchain, CensorshipReport, ChainInfo, DEFAULT_CENSORSHIP_MIN_PROPOSERS,
+    HeadInfo, HeadTag, PerformanceReport, RejectedBlock, Transaction, TuningKnobs,
+};
+use crate::{
+    CHAIN_ID, CallResult, DevAccount, InclusionEstimate, NodeConfig, PrecompileInfo,
+    PropagationStats, TransactionReceipt, TransactionSource, TxPoolContent, precompiles,
+};
 
-#[rpc(server)]
+// error code returned when a transaction is rejected by fee protection
+pub const FEE_PROTECTION_ERROR_CODE: i32 = -32010;
+// arrivals listed by `speed_getPropagationStats` when the caller doesn't say
+pub const DEFAULT_RECENT_ARRIVALS: usize = 16;
+
+#[rpc(server, client)]
 // Listing all RPC methods for Speed Blockchain
 pub trait SpeedBlockchainRpc {
     /// Get block count
@@ -25,23 +53,268 @@ pub trait SpeedBlockchainRpc {
         gas_limit: u64,
         gas_price: u64,
     ) -> RpcResult<String>;
+    /// Submit a signed, hex encoded transaction
+    /// `allow_unsafe_fee` overrides the fee protection checks
+    /// `request_id` makes retries safe: the same sender and id return the first hash
+    #[method(name = "eth_sendRawTransaction")]
+    async fn send_raw_transaction(
+        &self,
+        raw_tx: String,
+        allow_unsafe_fee: Option<bool>,
+        request_id: Option<String>,
+    ) -> RpcResult<B256>;
+    /// Submit a transaction forwarded by an edge node in relay mode,
+    /// `signature` is the relayer's over `relay_digest(chain_id, tx_hash)`
+    #[method(name = "speed_relayTransaction")]
+    async fn relay_transaction(
+        &self,
+        raw_tx: String,
+        relayer: Address,
+        signature: Signature,
+    ) -> RpcResult<B256>;
+    /// Get per validator counters of transactions this node relayed, empty when relay mode is off
+    #[method(name = "speed_getRelayStatus")]
+    async fn get_relay_status(&self) -> RpcResult<Vec<RelayTargetStats>>;
+    /// Get current gas price estimate
+    #[method(name = "eth_gasPrice")]
+    async fn gas_price(&self) -> RpcResult<U256>;
+    /// Get account balance
+    #[method(name = "eth_getBalance")]
+    async fn get_balance(&self, address: Address) -> RpcResult<U256>;
+    /// Get account nonce
+    #[method(name = "eth_getTransactionCount")]
+    async fn get_transaction_count(&self, address: Address) -> RpcResult<u64>;
+    /// Get remaining amount `spender` may transfer from `owner`
+    #[method(name = "speed_allowance")]
+    async fn allowance(&self, owner: Address, spender: Address) -> RpcResult<U256>;
+    /// Get the address a registered name resolves to, null if nobody registered it
+    #[method(name = "speed_resolveName")]
+    async fn resolve_name(&self, name: String) -> RpcResult<Option<Address>>;
+    /// Run a precompile read-only against the current state and return its output
+    #[method(name = "speed_callPrecompile")]
+    async fn call_precompile(
+        &self,
+        address: Address,
+        input: Bytes,
+        from: Option<Address>,
+    ) -> RpcResult<Bytes>;
+    /// Execute a hex encoded transaction, signed or not, against the state after `block` (the
+    /// head when omitted) without committing it, returning its output and gas used
+    #[method(name = "speed_call")]
+    async fn call(&self, raw_tx: String, block: Option<u64>) -> RpcResult<CallResult>;
+    /// Get the precompiles a `Call` transaction can target, by address
+    #[method(name = "speed_listPrecompiles")]
+    async fn list_precompiles(&self) -> RpcResult<Vec<PrecompileInfo>>;
+    /// Estimate how many blocks until a pending transaction is included, null if not pending
+    #[method(name = "speed_whenWillItConfirm")]
+    async fn when_will_it_confirm(&self, tx_hash: B256) -> RpcResult<Option<InclusionEstimate>>;
+    /// Get the receipt of a committed transaction with its block and gas, null until included
+    #[method(name = "speed_getTransactionReceipt")]
+    async fn get_transaction_receipt(&self, tx_hash: B256)
+    -> RpcResult<Option<TransactionReceipt>>;
+    /// Get the pre-funded dev accounts and their keys, empty unless the node runs with `--dev`
+    #[method(name = "speed_devAccounts")]
+    async fn dev_accounts(&self) -> RpcResult<Vec<DevAccount>>;
+    /// Get a validator's proposals, attestations, rewards and stake for a closed epoch
+    #[method(name = "speed_getEpochStatement")]
+    async fn get_epoch_statement(
+        &self,
+        validator: Address,
+        epoch: u64,
+    ) -> RpcResult<Option<EpochStatement>>;
+    /// Get transaction, gas, sender and participation totals of a closed epoch
+    #[method(name = "speed_getEpochStats")]
+    async fn get_epoch_stats(&self, epoch: u64) -> RpcResult<Option<EpochStats>>;
+    /// Get the rewards and inactivity penalties paid into balances when an epoch closed
+    #[method(name = "speed_getEpochSettlements")]
+    async fn get_epoch_settlements(&self, epoch: u64) -> RpcResult<Vec<EpochSettlement>>;
+    /// Get the checkpoint block of a closed epoch and whether it is justified or finalized
+    #[method(name = "speed_getEpochCheckpoint")]
+    async fn get_epoch_checkpoint(&self, epoch: u64) -> RpcResult<Option<EpochCheckpoint>>;
+    /// Get the newest justified and finalized epoch checkpoints
+    #[method(name = "speed_getFinalityCheckpoints")]
+    async fn get_finality_checkpoints(&self) -> RpcResult<FinalityCheckpoints>;
+    /// Get the quorum certificate of a block, null until enough validators accepted it
+    #[method(name = "speed_getFinalityCertificate")]
+    async fn get_finality_certificate(
+        &self,
+        block_hash: B256,
+    ) -> RpcResult<Option<FinalityCertificate>>;
+    /// Get double proposal evidence stored against a validator, oldest first
+    #[method(name = "speed_getSlashingEvidence")]
+    async fn get_slashing_evidence(
+        &self,
+        validator: Address,
+    ) -> RpcResult<Vec<DoubleProposalEvidence>>;
+    /// Get conflicting attestation evidence stored against a validator, oldest first
+    #[method(name = "speed_getAttestationEvidence")]
+    async fn get_attestation_evidence(
+        &self,
+        validator: Address,
+    ) -> RpcResult<Vec<AttestationEquivocationEvidence>>;
+    /// Get the proposer of every slot of an epoch, null for epochs that can't be scheduled
+    #[method(name = "speed_getProposerSchedule")]
+    async fn get_proposer_schedule(&self, epoch: u64) -> RpcResult<Option<EpochSchedule>>;
+    /// Get the proposers of the next `slots` slots (default one epoch), at most up to
+    /// the end of the next epoch
+    #[method(name = "speed_getUpcomingProposers")]
+    async fn get_upcoming_proposers(&self, slots: Option<u64>) -> RpcResult<Vec<ProposerDuty>>;
+    /// Get whether a block is finalized or the stake that accepted it so far, null if unknown
+    #[method(name = "speed_getFinalityStatus")]
+    async fn get_finality_status(&self, block_hash: B256) -> RpcResult<Option<BlockFinality>>;
+    /// Get the "latest", "safe" or "finalized" head
+    #[method(name = "speed_getHead")]
+    async fn get_head(&self, tag: HeadTag) -> RpcResult<HeadInfo>;
+    /// Get quarantined blocks numbered `from_block..=to_block`, newest first, at most 100
+    #[method(name = "speed_getRejectedBlocks")]
+    async fn get_rejected_blocks(
+        &self,
+        from_block: Option<u64>,
+        to_block: Option<u64>,
+    ) -> RpcResult<Vec<RejectedBlock>>;
+    /// Get receive latency percentiles of gossiped blocks, overall and per relaying peer
+    #[method(name = "speed_getPropagationStats")]
+    async fn get_propagation_stats(&self, recent: Option<usize>) -> RpcResult<PropagationStats>;
+    /// Get block import, state write and gossip backlog samples with tuning advice
+    #[method(name = "speed_performanceReport")]
+    async fn performance_report(&self) -> RpcResult<PerformanceReport>;
+    /// Get missed slots, proposals, attestation participation and average time to quorum
+    /// (also served as `GET /metrics`)
+    #[method(name = "speed_getConsensusMetrics")]
+    async fn get_consensus_metrics(&self) -> RpcResult<ConsensusMetricsReport>;
+    /// Get proposal and attestation rates, block latency and score of every validator, or of
+    /// one
+    #[method(name = "speed_getValidatorScores")]
+    async fn get_validator_scores(
+        &self,
+        validator: Option<Address>,
+    ) -> RpcResult<Vec<ValidatorScoreReport>>;
+    /// Get pending transactions left out of blocks by at least `min_proposers` proposers (default 3)
+    #[method(name = "speed_getCensorshipReport")]
+    async fn get_censorship_report(
+        &self,
+        min_proposers: Option<usize>,
+    ) -> RpcResult<CensorshipReport>;
+    /// Get chain id, genesis, head, safe and finalized heads, validators, fees and sync status in one call
+    #[method(name = "speed_getChainInfo")]
+    async fn get_chain_info(&self) -> RpcResult<ChainInfo>;
+    /// Get pending transactions by sender and nonce, each tagged with its source
+    #[method(name = "txpool_content")]
+    async fn txpool_content(&self) -> RpcResult<TxPoolContent>;
+    /// Get client name and version
+    #[method(name = "web3_clientVersion")]
+    async fn client_version(&self) -> RpcResult<String>;
+    /// Get namespaces, protocol versions and features supported by this node
+    #[method(name = "speed_capabilities")]
+    async fn capabilities(&self) -> RpcResult<NodeCapabilities>;
 }
 
-fn error_to_rpc<E: std::fmt::Display>(err: E) -> ErrorObject<'static> {
+pub(crate) fn error_to_rpc<E: std::fmt::Display>(err: E) -> ErrorObject<'static> {
     ErrorObject::owned(INTERNAL_ERROR_CODE, err.to_string(), None::<()>)
 }
+
+pub(crate) fn invalid_params<E: std::fmt::Display>(err: E) -> ErrorObject<'static> {
+    ErrorObject::owned(INVALID_PARAMS_CODE, err.to_string(), None::<()>)
+}
+
+fn protection_to_rpc(violations: &[ProtectionError]) -> ErrorObject<'static> {
+    let reasons: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+    ErrorObject::owned(
+        FEE_PROTECTION_ERROR_CODE,
+        format!(
+            "Transaction rejected by fee protection: {}",
+            reasons.join("; ")
+        ),
+        Some(reasons),
+    )
+}
+
 // Holds blockchain data
 pub struct SpeedRpcImpl {
     speed_blockchain: Arc<Mutex<Blockchain>>, // This is the "kitchen equipment"
+    fee_protection: FeeProtection,
+    // request ids of recent raw submissions
+    idempotency: Mutex<IdempotencyCache>,
+    dev_accounts: Vec<DevAccount>,
+    chain_id: u64,
+    // settings the performance report suggests changes to
+    tuning: TuningKnobs,
+    // relay mode, forwards admitted raw transactions to validators
+    relay: Option<Arc<TransactionRelay>>,
+    // edge nodes allowed to call `speed_relayTransaction`
+    trusted_relayers: Vec<Address>,
+    // namespaces `speed_capabilities` reports, as served right now
+    rpc_access: RpcAccess,
+    admin_rpc: bool,
+    // features `speed_capabilities` reports, from the node config and chain spec
+    features: Vec<String>,
 }
 
 impl SpeedRpcImpl {
     // Initialize the RPC implementation with a blockchain instance
     pub fn new(blockchain: Blockchain) -> Self {
+        Self::with_fee_protection(blockchain, FeeProtection::default())
+    }
+
+    // Initialize with custom fee protection settings
+    pub fn with_fee_protection(blockchain: Blockchain, fee_protection: FeeProtection) -> Self {
         Self {
             speed_blockchain: Arc::new(Mutex::new(blockchain)),
+            fee_protection,
+            idempotency: Mutex::new(IdempotencyCache::default()),
+            dev_accounts: Vec::new(),
+            chain_id: CHAIN_ID,
+            tuning: TuningKnobs::from_config(&NodeConfig::default()),
+            relay: None,
+            trusted_relayers: Vec::new(),
+            rpc_access: RpcAccess::default(),
+            admin_rpc: false,
+            features: node_features(&NodeConfig::default(), None),
         }
     }
+
+    // chain id reported by `speed_getChainInfo`
+    pub fn with_chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = chain_id;
+        self
+    }
+
+    // settings of the running node, for `speed_performanceReport`
+    pub fn with_tuning(mut self, tuning: TuningKnobs) -> Self {
+        self.tuning = tuning;
+        self
+    }
+
+    // forward transactions admitted through `eth_sendRawTransaction` to validators
+    pub fn with_relay(mut self, relay: TransactionRelay) -> Self {
+        self.relay = Some(Arc::new(relay));
+        self
+    }
+
+    // accept relayed transactions from these edge nodes
+    pub fn with_trusted_relayers(mut self, relayers: Vec<Address>) -> Self {
+        self.trusted_relayers = relayers;
+        self
+    }
+
+    // the access rules the servers enforce, and whether the admin namespace is merged in
+    pub fn with_rpc_access(mut self, rpc_access: RpcAccess, admin_rpc: bool) -> Self {
+        self.rpc_access = rpc_access;
+        self.admin_rpc = admin_rpc;
+        self
+    }
+
+    // features the running node was configured with
+    pub fn with_features(mut self, features: Vec<String>) -> Self {
+        self.features = features;
+        self
+    }
+
+    // expose dev accounts over RPC, only used in dev mode
+    pub fn with_dev_accounts(mut self, dev_accounts: Vec<DevAccount>) -> Self {
+        self.dev_accounts = dev_accounts;
+        self
+    }
 }
 
 // Implement the RPC methods. (SpeedBlockchainRpcServer trait is auto-generated by rpc macro)
@@ -75,4 +348,391 @@ impl SpeedBlockchainRpcServer for SpeedRpcImpl {
         // Ok(tx)
         Ok("NOT implemented".to_string())
     }
+
+    // Submit a signed transaction, checked against fee protection first
+    async fn send_raw_transaction(
+        &self,
+        raw_tx: String,
+        allow_unsafe_fee: Option<bool>,
+        request_id: Option<String>,
+    ) -> RpcResult<B256> {
+        let tx = Transaction::decode_raw(&raw_tx).map_err(invalid_params)?;
+        if let Some(request_id) = &request_id {
+            IdempotencyCache::validate(request_id).map_err(invalid_params)?;
+        }
+        // the chain lock serializes submissions, a retry can't race its original
+        let chain = self.speed_blockchain.lock().await;
+
+        let mut idempotency = self.idempotency.lock().await;
+        if let Some(request_id) = &request_id
+            && let Some(tx_hash) = idempotency.lookup(tx.from, request_id, Instant::now())
+        {
+            println!("🔂 Request {} already submitted as {}", request_id, tx_hash);
+            return Ok(tx_hash);
+        }
+
+        let estimate = chain.execution_engine.estimate_gas_price().await;
+        let balance = chain.execution_engine.get_balance(&tx.from).await;
+        let violations = self.fee_protection.check(&tx, estimate, balance);
+
+        if !violations.is_empty() {
+            if !allow_unsafe_fee.unwrap_or(false) {
+                return Err(protection_to_rpc(&violations));
+            }
+
+            for violation in &violations {
+                println!("⚠️  Fee protection overridden: {}", violation);
+            }
+        }
+
+        let tx_hash = chain
+            .add_transaction_to_mempool(&tx, TransactionSource::Rpc)
+            .await
+            .map_err(error_to_rpc)?;
+        if let Some(request_id) = request_id {
+            idempotency.record(tx.from, request_id, tx_hash, Instant::now());
+        }
+
+        // only transactions our own mempool took are relayed, without holding up the reply
+        if let Some(relay) = &self.relay {
+            let relay = relay.clone();
+            tokio::spawn(async move {
+                if let Err(e) = relay.relay(&tx).await {
+                    println!("❌ Failed to relay {}: {}", tx.hash, e);
+                }
+            });
+        }
+        Ok(tx_hash)
+    }
+
+    // transaction from a trusted edge node, already checked against its fee protection
+    async fn relay_transaction(
+        &self,
+        raw_tx: String,
+        relayer: Address,
+        signature: Signature,
+    ) -> RpcResult<B256> {
+        let tx = Transaction::decode_raw(&raw_tx).map_err(invalid_params)?;
+        verify_relayed(
+            &self.trusted_relayers,
+            self.chain_id,
+            &tx.hash,
+            relayer,
+            &signature,
+        )
+        .map_err(|e| ErrorObject::owned(UNAUTHORIZED_ERROR_CODE, e.to_string(), None::<()>))?;
+
+        let chain = self.speed_blockchain.lock().await;
+        chain
+            .add_transaction_to_mempool(&tx, TransactionSource::Relay { relayer })
+            .await
+            .map_err(error_to_rpc)
+    }
+
+    // relay counters per validator
+    async fn get_relay_status(&self) -> RpcResult<Vec<RelayTargetStats>> {
+        Ok(self
+            .relay
+            .as_ref()
+            .map_or_else(Vec::new, |relay| relay.stats()))
+    }
+
+    // get gas price estimate
+    async fn gas_price(&self) -> RpcResult<U256> {
+        let chain = self.speed_blockchain.lock().await;
+
+        Ok(chain.execution_engine.estimate_gas_price().await)
+    }
+
+    // get account balance
+    async fn get_balance(&self, address: Address) -> RpcResult<U256> {
+        let chain = self.speed_blockchain.lock().await;
+
+        Ok(chain.execution_engine.get_balance(&address).await)
+    }
+
+    // get account nonce
+    async fn get_transaction_count(&self, address: Address) -> RpcResult<u64> {
+        let chain = self.speed_blockchain.lock().await;
+
+        Ok(chain.execution_engine.get_nonce(&address).await)
+    }
+
+    // get allowance of spender over owner's tokens
+    async fn allowance(&self, owner: Address, spender: Address) -> RpcResult<U256> {
+        let chain = self.speed_blockchain.lock().await;
+
+        Ok(chain.execution_engine.get_allowance(&owner, &spender).await)
+    }
+
+    // resolve a registered name to its owner
+    async fn resolve_name(&self, name: String) -> RpcResult<Option<Address>> {
+        let chain = self.speed_blockchain.lock().await;
+
+        Ok(chain.execution_engine.resolve_name(&name).await)
+    }
+
+    // output of a precompile call, without sending a transaction
+    async fn call_precompile(
+        &self,
+        address: Address,
+        input: Bytes,
+        from: Option<Address>,
+    ) -> RpcResult<Bytes> {
+        let chain = self.speed_blockchain.lock().await;
+
+        chain
+            .execution_engine
+            .call_precompile(&address, from.unwrap_or_default(), &input)
+            .await
+            .map_err(invalid_params)
+    }
+
+    async fn call(&self, raw_tx: String, block: Option<u64>) -> RpcResult<CallResult> {
+        let tx = Transaction::decode_raw(&raw_tx).map_err(invalid_params)?;
+        let chain = self.speed_blockchain.lock().await;
+
+        chain.call(&tx, block).await.map_err(invalid_params)
+    }
+
+    async fn list_precompiles(&self) -> RpcResult<Vec<PrecompileInfo>> {
+        Ok(precompiles().list())
+    }
+
+    // simulate upcoming blocks for a pending transaction
+    async fn when_will_it_confirm(&self, tx_hash: B256) -> RpcResult<Option<InclusionEstimate>> {
+        let chain = self.speed_blockchain.lock().await;
+
+        Ok(chain.estimate_inclusion(&tx_hash).await)
+    }
+
+    // receipt from the block that included the transaction
+    async fn get_transaction_receipt(
+        &self,
+        tx_hash: B256,
+    ) -> RpcResult<Option<TransactionReceipt>> {
+        let chain = self.speed_blockchain.lock().await;
+
+        chain.get_receipt(&tx_hash).await.map_err(error_to_rpc)
+    }
+
+    // get dev accounts
+    async fn dev_accounts(&self) -> RpcResult<Vec<DevAccount>> {
+        Ok(self.dev_accounts.clone())
+    }
+
+    // get stored epoch statement, null until the epoch is closed
+    async fn get_epoch_statement(
+        &self,
+        validator: Address,
+        epoch: u64,
+    ) -> RpcResult<Option<EpochStatement>> {
+        let chain = self.speed_blockchain.lock().await;
+
+        chain
+            .get_epoch_statement(&validator, epoch)
+            .await
+            .map_err(error_to_rpc)
+    }
+
+    // get the stored rollup of a closed epoch
+    async fn get_epoch_stats(&self, epoch: u64) -> RpcResult<Option<EpochStats>> {
+        let chain = self.speed_blockchain.lock().await;
+
+        chain.get_epoch_stats(epoch).await.map_err(error_to_rpc)
+    }
+
+    // balance changes of a closed epoch, empty while it runs or when nothing was owed
+    async fn get_epoch_settlements(&self, epoch: u64) -> RpcResult<Vec<EpochSettlement>> {
+        let chain = self.speed_blockchain.lock().await;
+
+        chain
+            .get_epoch_settlements(epoch)
+            .await
+            .map_err(error_to_rpc)
+    }
+
+    // checkpoint of a closed epoch, null while it runs
+    async fn get_epoch_checkpoint(&self, epoch: u64) -> RpcResult<Option<EpochCheckpoint>> {
+        let chain = self.speed_blockchain.lock().await;
+
+        chain.epoch_checkpoint(epoch).await.map_err(error_to_rpc)
+    }
+
+    // newest justified and finalized epoch checkpoints
+    async fn get_finality_checkpoints(&self) -> RpcResult<FinalityCheckpoints> {
+        let chain = self.speed_blockchain.lock().await;
+
+        chain.finality_checkpoints().await.map_err(error_to_rpc)
+    }
+
+    // get stored finality certificate, header + accept votes + validator set commitment
+    async fn get_finality_certificate(
+        &self,
+        block_hash: B256,
+    ) -> RpcResult<Option<FinalityCertificate>> {
+        let chain = self.speed_blockchain.lock().await;
+
+        chain
+            .get_finality_certificate(&block_hash)
+            .await
+            .map_err(error_to_rpc)
+    }
+
+    // both signed headers of every double proposal seen from the validator
+    async fn get_slashing_evidence(
+        &self,
+        validator: Address,
+    ) -> RpcResult<Vec<DoubleProposalEvidence>> {
+        let chain = self.speed_blockchain.lock().await;
+
+        chain
+            .get_slashing_evidence(&validator)
+            .await
+            .map_err(error_to_rpc)
+    }
+
+    // both signed votes and block headers of every double vote seen from the validator
+    async fn get_attestation_evidence(
+        &self,
+        validator: Address,
+    ) -> RpcResult<Vec<AttestationEquivocationEvidence>> {
+        let chain = self.speed_blockchain.lock().await;
+
+        chain
+            .get_attestation_evidence(&validator)
+            .await
+            .map_err(error_to_rpc)
+    }
+
+    // proposers of an epoch, the next epoch's is known once the current one had a block
+    async fn get_proposer_schedule(&self, epoch: u64) -> RpcResult<Option<EpochSchedule>> {
+        let chain = self.speed_blockchain.lock().await;
+
+        Ok(chain.proposer_schedule(epoch).await)
+    }
+
+    // duties coming up, for pre-building payloads and watching who is next
+    async fn get_upcoming_proposers(&self, slots: Option<u64>) -> RpcResult<Vec<ProposerDuty>> {
+        let chain = self.speed_blockchain.lock().await;
+
+        chain
+            .upcoming_proposers(slots.unwrap_or(chain.consensus_config().slots_per_epoch))
+            .await
+            .map_err(error_to_rpc)
+    }
+
+    // finalized, or accepted stake against the quorum for a pending block
+    async fn get_finality_status(&self, block_hash: B256) -> RpcResult<Option<BlockFinality>> {
+        let chain = self.speed_blockchain.lock().await;
+
+        chain
+            .finality_status(&block_hash)
+            .await
+            .map_err(error_to_rpc)
+    }
+
+    // head at the confirmation level the client wants
+    async fn get_head(&self, tag: HeadTag) -> RpcResult<HeadInfo> {
+        let chain = self.speed_blockchain.lock().await;
+
+        Ok(chain.head(tag).await)
+    }
+
+    // get rejected gossip blocks kept by the quarantine, empty when it is disabled
+    async fn get_rejected_blocks(
+        &self,
+        from_block: Option<u64>,
+        to_block: Option<u64>,
+    ) -> RpcResult<Vec<RejectedBlock>> {
+        if let (Some(from), Some(to)) = (from_block, to_block)
+            && from > to
+        {
+            return Err(invalid_params(format!(
+                "from_block {} is after to_block {}",
+                from, to
+            )));
+        }
+        let chain = self.speed_blockchain.lock().await;
+
+        chain
+            .get_rejected_blocks(from_block, to_block)
+            .await
+            .map_err(error_to_rpc)
+    }
+
+    // get block propagation stats, `recent` latest arrivals are listed individually
+    async fn get_propagation_stats(&self, recent: Option<usize>) -> RpcResult<PropagationStats> {
+        let chain = self.speed_blockchain.lock().await;
+
+        Ok(chain
+            .propagation_stats(recent.unwrap_or(DEFAULT_RECENT_ARRIVALS))
+            .await)
+    }
+
+    // bottlenecks of this node and config changes that should help
+    async fn performance_report(&self) -> RpcResult<PerformanceReport> {
+        let chain = self.speed_blockchain.lock().await;
+
+        Ok(chain.performance_report(self.tuning.clone()).await)
+    }
+
+    // consensus counters since the node started
+    async fn get_consensus_metrics(&self) -> RpcResult<ConsensusMetricsReport> {
+        let chain = self.speed_blockchain.lock().await;
+
+        Ok(chain.consensus_metrics().await)
+    }
+
+    // performance of the validators over the epochs this node closed
+    async fn get_validator_scores(
+        &self,
+        validator: Option<Address>,
+    ) -> RpcResult<Vec<ValidatorScoreReport>> {
+        let chain = self.speed_blockchain.lock().await;
+
+        Ok(chain.validator_scores(validator).await)
+    }
+
+    // transactions with competitive fees that proposers keep leaving out
+    async fn get_censorship_report(
+        &self,
+        min_proposers: Option<usize>,
+    ) -> RpcResult<CensorshipReport> {
+        let chain = self.speed_blockchain.lock().await;
+
+        Ok(chain
+            .censorship_report(min_proposers.unwrap_or(DEFAULT_CENSORSHIP_MIN_PROPOSERS))
+            .await)
+    }
+
+    // chain overview for dashboards
+    async fn get_chain_info(&self) -> RpcResult<ChainInfo> {
+        let chain = self.speed_blockchain.lock().await;
+
+        Ok(chain.chain_info(self.chain_id).await)
+    }
+
+    // get mempool content
+    async fn txpool_content(&self) -> RpcResult<TxPoolContent> {
+        let chain = self.speed_blockchain.lock().await;
+
+        Ok(chain.txpool_content().await)
+    }
+
+    // get client version
+    async fn client_version(&self) -> RpcResult<String> {
+        Ok(client_version())
+    }
+
+    // get node capabilities
+    async fn capabilities(&self) -> RpcResult<NodeCapabilities> {
+        let chain = self.speed_blockchain.lock().await;
+
+        Ok(NodeCapabilities::collect(
+            chain.chain_spec_hash(),
+            self.rpc_access.served_namespaces(self.admin_rpc),
+            self.features.clone(),
+        ))
+    }
 }
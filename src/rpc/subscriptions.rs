@@ -0,0 +1,189 @@
+use alloy::primitives::{Address, B256, Bytes};
+use jsonrpsee::{
+    PendingSubscriptionSink, SubscriptionMessage, SubscriptionSink,
+    core::{SubscriptionResult, async_trait},
+    proc_macros::rpc,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
+
+use super::rpc::invalid_params;
+use crate::ReceiptEvent;
+use crate::core::{Blockchain, CommittedBlock};
+
+// what a subscriber wants to receive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SubscriptionKind {
+    NewHeads,
+    Logs,
+}
+
+// optional second parameter of `eth_subscribe`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubscriptionOptions {
+    // replay committed blocks from this number before streaming live ones
+    pub since_block: Option<u64>,
+    // logs only: events naming this address
+    pub address: Option<Address>,
+}
+
+// header of a committed block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewHead {
+    pub number: u64,
+    pub hash: B256,
+    pub parent_hash: B256,
+    pub slot: u64,
+    pub timestamp: u64,
+    pub proposer: Address,
+    pub state_root: B256,
+    pub transactions_root: B256,
+    pub transaction_count: usize,
+}
+
+// receipt event of a committed transaction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogEvent {
+    pub block_number: u64,
+    pub block_hash: B256,
+    pub transaction_hash: B256,
+    // position of the event within its block
+    pub log_index: usize,
+    pub event: ReceiptEvent,
+    // memo of the emitting transaction, empty without one
+    #[serde(default)]
+    pub memo: Bytes,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SubscriptionEvent {
+    Head(NewHead),
+    Log(LogEvent),
+}
+
+// events a committed block produces for a subscription
+pub fn subscription_events(
+    kind: SubscriptionKind,
+    options: &SubscriptionOptions,
+    committed: &CommittedBlock,
+) -> Vec<SubscriptionEvent> {
+    let header = &committed.block.header;
+    let block_hash = header.hash();
+
+    match kind {
+        SubscriptionKind::NewHeads => vec![SubscriptionEvent::Head(NewHead {
+            number: header.index,
+            hash: block_hash,
+            parent_hash: header.parent_hash,
+            slot: header.slot,
+            timestamp: header.timestamp,
+            proposer: header.proposer,
+            state_root: header.state_root,
+            transactions_root: header.transactions_root,
+            transaction_count: committed.block.transactions.len(),
+        })],
+        SubscriptionKind::Logs => committed
+            .receipts
+            .iter()
+            .flat_map(|receipt| receipt.events.iter().map(move |event| (receipt, event)))
+            .enumerate()
+            .filter(|(_, (_, event))| options.address.is_none_or(|a| event.involves(&a)))
+            .map(|(log_index, (receipt, event))| {
+                SubscriptionEvent::Log(LogEvent {
+                    block_number: header.index,
+                    block_hash,
+                    transaction_hash: receipt.transaction_hash,
+                    log_index,
+                    event: event.clone(),
+                    memo: receipt.memo.clone(),
+                })
+            })
+            .collect(),
+    }
+}
+
+#[rpc(server)]
+// Websocket subscriptions, resumable from a block number after a reconnect
+pub trait SubscriptionRpc {
+    /// Stream `newHeads` or `logs`, replaying stored blocks from `since_block` first
+    #[subscription(name = "eth_subscribe" => "eth_subscription", unsubscribe = "eth_unsubscribe", item = SubscriptionEvent)]
+    async fn subscribe(
+        &self,
+        kind: SubscriptionKind,
+        options: Option<SubscriptionOptions>,
+    ) -> SubscriptionResult;
+}
+
+pub struct SubscriptionRpcImpl {
+    blockchain: Blockchain,
+}
+
+impl SubscriptionRpcImpl {
+    pub fn new(blockchain: Blockchain) -> Self {
+        Self { blockchain }
+    }
+}
+
+async fn send_events(
+    sink: &SubscriptionSink,
+    kind: SubscriptionKind,
+    options: &SubscriptionOptions,
+    committed: &CommittedBlock,
+) -> SubscriptionResult {
+    for event in subscription_events(kind, options, committed) {
+        let message = SubscriptionMessage::from(serde_json::value::to_raw_value(&event)?);
+        sink.send(message).await?;
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl SubscriptionRpcServer for SubscriptionRpcImpl {
+    async fn subscribe(
+        &self,
+        pending: PendingSubscriptionSink,
+        kind: SubscriptionKind,
+        options: Option<SubscriptionOptions>,
+    ) -> SubscriptionResult {
+        let options = options.unwrap_or_default();
+        if kind == SubscriptionKind::NewHeads && options.address.is_some() {
+            pending
+                .reject(invalid_params("address only applies to logs"))
+                .await;
+            return Ok(());
+        }
+
+        // listen before reading the head so no block falls between replay and live
+        let mut live = self.blockchain.subscribe_blocks();
+        let head = self.blockchain.get_last_index().await?;
+        let sink = pending.accept().await?;
+        let mut next = options.since_block.unwrap_or(head + 1);
+
+        loop {
+            // replay what is stored but not sent yet: the cursor, gaps and lagged blocks
+            let head = self.blockchain.get_last_index().await?;
+            while next <= head {
+                if let Some(committed) = self.blockchain.committed_block(next).await? {
+                    send_events(&sink, kind, &options, &committed).await?;
+                }
+                next += 1;
+            }
+
+            tokio::select! {
+                _ = sink.closed() => return Ok(()),
+                received = live.recv() => match received {
+                    Ok(committed) if committed.block.header.index == next => {
+                        send_events(&sink, kind, &options, &committed).await?;
+                        next += 1;
+                    }
+                    // already replayed, or ahead of the cursor and read back from storage
+                    Ok(_) | Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => return Ok(()),
+                },
+            }
+        }
+    }
+}
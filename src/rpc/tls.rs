@@ -0,0 +1,157 @@
+use anyhow::{Context, Result, anyhow};
+use jsonrpsee::server::{Methods, ServerHandle, serve_with_graceful_shutdown, stop_channel};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::rustls::crypto::ring;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+
+use super::{RpcAccess, gated_server_builder};
+
+// serve the http/ws rpc over tls instead of plain tcp
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RpcTlsConfig {
+    // pem certificate chain and private key
+    pub cert_path: Option<String>,
+    pub key_path: Option<String>,
+    // generate a certificate on first start, for devnets
+    pub self_signed: bool,
+    // names the self-signed certificate is valid for
+    pub hostnames: Vec<String>,
+    // interface to listen on, use 0.0.0.0 to accept remote wallets
+    pub bind_address: String,
+}
+
+impl Default for RpcTlsConfig {
+    fn default() -> Self {
+        Self {
+            cert_path: None,
+            key_path: None,
+            self_signed: false,
+            hostnames: vec!["localhost".to_string(), "127.0.0.1".to_string()],
+            bind_address: "127.0.0.1".to_string(),
+        }
+    }
+}
+
+impl RpcTlsConfig {
+    // certificate and key files, self-signed ones default to <data_dir>/tls
+    pub fn paths(&self, data_dir: &str) -> Result<(PathBuf, PathBuf)> {
+        let tls_dir = Path::new(data_dir).join("tls");
+        match (&self.cert_path, &self.key_path) {
+            (Some(cert), Some(key)) => Ok((PathBuf::from(cert), PathBuf::from(key))),
+            (None, None) if self.self_signed => {
+                Ok((tls_dir.join("rpc-cert.pem"), tls_dir.join("rpc-key.pem")))
+            }
+            _ => Err(anyhow!(
+                "rpc_tls needs both cert_path and key_path, or self_signed"
+            )),
+        }
+    }
+
+    // load the certificate, generating a self-signed one if allowed and missing
+    pub fn server_config(&self, data_dir: &str) -> Result<Arc<ServerConfig>> {
+        let (cert_path, key_path) = self.paths(data_dir)?;
+        if self.self_signed && !(cert_path.exists() && key_path.exists()) {
+            generate_self_signed(&self.hostnames, &cert_path, &key_path)?;
+            println!(
+                "🔏 Generated self-signed RPC certificate {}",
+                cert_path.display()
+            );
+        }
+        load_server_config(&cert_path, &key_path)
+    }
+}
+
+// write a self-signed certificate and its key as pem files
+pub fn generate_self_signed(hostnames: &[String], cert_path: &Path, key_path: &Path) -> Result<()> {
+    let cert = rcgen::generate_simple_self_signed(hostnames.to_vec())
+        .map_err(|e| anyhow!("Failed to generate certificate: {}", e))?;
+    let cert_pem = cert
+        .serialize_pem()
+        .map_err(|e| anyhow!("Failed to encode certificate: {}", e))?;
+
+    for path in [cert_path, key_path] {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+    }
+    fs::write(cert_path, cert_pem)
+        .with_context(|| format!("Failed to write {}", cert_path.display()))?;
+    fs::write(key_path, cert.serialize_private_key_pem())
+        .with_context(|| format!("Failed to write {}", key_path.display()))?;
+    Ok(())
+}
+
+// rustls server config from pem files
+pub fn load_server_config(cert_path: &Path, key_path: &Path) -> Result<Arc<ServerConfig>> {
+    let certs = CertificateDer::pem_file_iter(cert_path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| anyhow!("Failed to read certificate {}: {}", cert_path.display(), e))?;
+    if certs.is_empty() {
+        return Err(anyhow!("No certificate found in {}", cert_path.display()));
+    }
+    let key = PrivateKeyDer::from_pem_file(key_path)
+        .map_err(|e| anyhow!("Failed to read private key {}: {}", key_path.display(), e))?;
+
+    let config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)?;
+    Ok(Arc::new(config))
+}
+
+// accept tls connections and hand them to the jsonrpsee service, http and ws alike
+pub fn start_tls_server(
+    listener: TcpListener,
+    methods: impl Into<Methods>,
+    tls: Arc<ServerConfig>,
+    access: &RpcAccess,
+) -> ServerHandle {
+    let methods: Methods = methods.into();
+    let acceptor = TlsAcceptor::from(tls);
+    let service_builder = gated_server_builder(access).to_service_builder();
+    let (stop_handle, server_handle) = stop_channel();
+
+    tokio::spawn(async move {
+        loop {
+            let stream = tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        println!("⚠️  RPC accept failed: {}", e);
+                        continue;
+                    }
+                },
+                _ = stop_handle.clone().shutdown() => break,
+            };
+
+            let acceptor = acceptor.clone();
+            let service = service_builder
+                .clone()
+                .build(methods.clone(), stop_handle.clone());
+            let stop_handle = stop_handle.clone();
+
+            // the handshake runs in the connection task so a slow client can't stall accepts
+            tokio::spawn(async move {
+                let stream = match acceptor.accept(stream).await {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        println!("⚠️  RPC TLS handshake failed: {}", e);
+                        return;
+                    }
+                };
+                let _ = serve_with_graceful_shutdown(stream, service, stop_handle.shutdown()).await;
+            });
+        }
+    });
+
+    server_handle
+}
@@ -0,0 +1,73 @@
+use anyhow::{Context, Result};
+use rocksdb::{DB, Options};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+// raw key-value operations `Storage` is built on, implement it to embed the node
+// on another database
+pub trait KeyValueStore: Send + Sync {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>>;
+    fn put(&self, key: &[u8], value: &[u8]) -> Result<()>;
+    fn delete(&self, key: &[u8]) -> Result<()>;
+}
+
+// the default store, a RocksDB database in a directory
+pub struct RocksDbStore {
+    db: DB,
+}
+
+impl RocksDbStore {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+
+        let db = DB::open(&opts, path).context("Failed to open RocksDB")?;
+        Ok(Self { db })
+    }
+}
+
+impl KeyValueStore for RocksDbStore {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.db.get(key)?)
+    }
+
+    fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        Ok(self.db.put(key, value)?)
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        Ok(self.db.delete(key)?)
+    }
+}
+
+// nothing is written to disk, for tests and short-lived embedded nodes
+#[derive(Default)]
+pub struct MemoryStore {
+    entries: Mutex<HashMap<Vec<u8>, Vec<u8>>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl KeyValueStore for MemoryStore {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.entries.lock().unwrap().get(key).cloned())
+    }
+
+    fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key.to_vec(), value.to_vec());
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        self.entries.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+pub mod backend;
 pub mod storage;
 
-pub use storage::Storage;
+pub use backend::*;
+pub use storage::{Storage, StoredStateHead, TxLocation};
@@ -1,26 +1,109 @@
-use alloy::primitives::B256;
+use alloy::primitives::{Address, B256, Bloom, U256};
 use anyhow::{Context, Result};
-use rocksdb::{DB, Options};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::path::Path;
 
-use crate::Block;
+use super::backend::{KeyValueStore, RocksDbStore};
+use crate::consensus::{
+    AttestationEquivocationEvidence, BlockRandomness, ConsensusGenesis, DoubleProposalEvidence,
+    EpochCheckpoint, EpochSettlement, EpochStatement, EpochStats, FinalityCertificate,
+    SigningRecord, StoredValidatorSet, ValidatorScores,
+};
+use crate::core::{Checkpoint, HeadInfo, RejectedBlock};
+use crate::{
+    AccountDiff, Block, ChainSpec, LogEntry, Receipt, StateManager, StateWrite, TransactionReceipt,
+    inject_storage_write_delay,
+};
 
 // persist blocks + state
 
+// key prefixes for execution output stored next to each block
+const RECEIPTS_PREFIX: &[u8] = b"receipts:";
+const STATE_DIFF_PREFIX: &[u8] = b"state_diff:";
+// tx_hash -> TxLocation, address -> [TxLocation], block_hash -> address bloom
+const TX_INDEX_PREFIX: &[u8] = b"tx_index:";
+const ADDRESS_HISTORY_PREFIX: &[u8] = b"address_history:";
+const BLOOM_PREFIX: &[u8] = b"bloom:";
+// topic -> [LogEntry], every log carrying the topic, oldest first
+const LOG_TOPIC_PREFIX: &[u8] = b"log_topic:";
+// last block index completed by `speed reindex`
+const REINDEX_PROGRESS_KEY: &[u8] = b"reindex_progress";
+// epoch_statement:{epoch}{validator} -> EpochStatement
+const EPOCH_STATEMENT_PREFIX: &[u8] = b"epoch_statement:";
+// epoch_stats:{epoch} -> EpochStats
+const EPOCH_STATS_PREFIX: &[u8] = b"epoch_stats:";
+// epoch_settlement:{epoch} -> [EpochSettlement], the balance changes applied when it closed
+const EPOCH_SETTLEMENT_PREFIX: &[u8] = b"epoch_settlement:";
+// epoch_checkpoint:{epoch} -> EpochCheckpoint, written when the epoch closes
+const EPOCH_CHECKPOINT_PREFIX: &[u8] = b"epoch_checkpoint:";
+// newest justified and finalized epoch checkpoints
+const JUSTIFIED_CHECKPOINT_KEY: &[u8] = b"justified_checkpoint";
+const FINALIZED_CHECKPOINT_KEY: &[u8] = b"finalized_checkpoint";
+// per-validator performance totals, updated when an epoch closes
+const VALIDATOR_SCORES_KEY: &[u8] = b"validator_scores";
+// validator set and its frozen copies, written when an epoch closes
+const VALIDATOR_SET_KEY: &[u8] = b"validator_set";
+// slot clock and proposer seed of this chain, kept across restarts
+const CONSENSUS_GENESIS_KEY: &[u8] = b"consensus_genesis";
+// validator -> SigningRecord, slashing protection for the local key
+const SIGNING_RECORD_PREFIX: &[u8] = b"signing_record:";
+// block_hash -> FinalityCertificate, written once a quorum accepted the block
+const FINALITY_CERTIFICATE_PREFIX: &[u8] = b"finality_certificate:";
+// validator -> Vec<DoubleProposalEvidence>, one per slot
+const SLASHING_EVIDENCE_PREFIX: &[u8] = b"slashing_evidence:";
+// validator -> Vec<AttestationEquivocationEvidence>, one per slot
+const ATTESTATION_EVIDENCE_PREFIX: &[u8] = b"attestation_evidence:";
+// highest certified block, everything up to it is final
+const FINALIZED_HEAD_KEY: &[u8] = b"finalized_head";
+// block hash randomness accumulated up to the head, and the epochs it seeded
+const BLOCK_RANDOMNESS_KEY: &[u8] = b"block_randomness";
+// chain spec the database was created from, absent for chains started without one
+const CHAIN_SPEC_KEY: &[u8] = b"chain_spec";
+// trusted checkpoint the database was started from, blocks before it were never stored
+const CHECKPOINT_KEY: &[u8] = b"checkpoint";
+// rejected_block:{sequence} -> RejectedBlock, only the newest `capacity` are kept
+const REJECTED_BLOCK_PREFIX: &[u8] = b"rejected_block:";
+// sequence the next rejected block gets
+const REJECTED_BLOCKS_NEXT_KEY: &[u8] = b"rejected_blocks_next";
+// state_account:{address} -> Account, the account column of the state after `STATE_HEAD_KEY`
+const STATE_ACCOUNT_PREFIX: &[u8] = b"state_account:";
+// every address in the account column
+const STATE_ACCOUNTS_KEY: &[u8] = b"state_accounts";
+// the rest of that state, allowances, names, stake, contracts and so on
+const STATE_REST_KEY: &[u8] = b"state_rest";
+// block the stored state belongs to, written last
+const STATE_HEAD_KEY: &[u8] = b"state_head";
+
+// block the stored state is the state after, and the root to check it against
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredStateHead {
+    pub block_hash: B256,
+    pub state_root: B256,
+}
+
+// where a transaction was included
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TxLocation {
+    pub tx_hash: B256,
+    pub block_hash: B256,
+    pub block_number: u64,
+    pub index: u64,
+}
+
 pub struct Storage {
-    db: DB,
+    db: Box<dyn KeyValueStore>,
 }
 
 impl Storage {
     // Create a new storage instance with the given path
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let mut opts = Options::default();
-        opts.create_if_missing(true);
-
-        let db = DB::open(&opts, path).context("Failed to open RocksDB")?;
+        Ok(Self::with_backend(RocksDbStore::open(path)?))
+    }
 
-        Ok(Self { db })
+    // storage on a custom key-value store, e.g. for embedding
+    pub fn with_backend(db: impl KeyValueStore + 'static) -> Self {
+        Self { db: Box::new(db) }
     }
 
     // ========== PRIMARY STORAGE: block_hash -> Block ==========
@@ -34,9 +117,10 @@ impl Storage {
         // Json encoding for readability
         let json_data =
             serde_json::to_vec_pretty(value).context("Failed to serialize block to JSON")?;
+        inject_storage_write_delay();
         // Handle rocksdb error (remove & reference)
         self.db
-            .put(block_hash, json_data)
+            .put(block_hash.as_slice(), &json_data)
             .with_context(|| format!("Failed to store data with key: {}", block_hash))?;
         Ok(())
     }
@@ -48,7 +132,7 @@ impl Storage {
     ) -> Result<Option<T>> {
         match self
             .db
-            .get(block_hash)
+            .get(block_hash.as_slice())
             .with_context(|| format!("Failed to retrieve data with key: {}", block_hash))?
         {
             Some(json_bytes) => {
@@ -69,12 +153,15 @@ impl Storage {
 
     pub fn put_index_to_block_hash(&self, index: &u64, block_hash: &B256) -> Result<()> {
         let index = index.to_le_bytes();
-        self.db.put(&index, block_hash).with_context(|| {
-            format!(
-                "Failed to store block number to hash mapping for block number: {}",
-                hex::encode(index)
-            )
-        })?;
+        inject_storage_write_delay();
+        self.db
+            .put(&index, block_hash.as_slice())
+            .with_context(|| {
+                format!(
+                    "Failed to store block number to hash mapping for block number: {}",
+                    hex::encode(index)
+                )
+            })?;
         Ok(())
     }
 
@@ -102,6 +189,7 @@ impl Storage {
     // update last index metadata
     pub fn put_last_index(&self, index: &u64) -> Result<()> {
         let index = index.to_le_bytes();
+        inject_storage_write_delay();
         self.db
             .put(b"last_index", &index)
             .context("Failed to store last index")?;
@@ -126,6 +214,528 @@ impl Storage {
         }
     }
 
+    // ========== EXECUTION OUTPUT: block_hash -> receipts / state diff ==========
+
+    pub fn put_block_receipts(&self, block_hash: &B256, receipts: &[Receipt]) -> Result<()> {
+        self.put_json(&prefixed_key(RECEIPTS_PREFIX, block_hash), &receipts)
+    }
+
+    pub fn get_block_receipts(&self, block_hash: &B256) -> Result<Option<Vec<Receipt>>> {
+        self.get_json(&prefixed_key(RECEIPTS_PREFIX, block_hash))
+    }
+
+    pub fn put_block_state_diff(
+        &self,
+        block_hash: &B256,
+        state_diff: &[AccountDiff],
+    ) -> Result<()> {
+        self.put_json(&prefixed_key(STATE_DIFF_PREFIX, block_hash), &state_diff)
+    }
+
+    pub fn get_block_state_diff(&self, block_hash: &B256) -> Result<Option<Vec<AccountDiff>>> {
+        self.get_json(&prefixed_key(STATE_DIFF_PREFIX, block_hash))
+    }
+
+    // ========== INDEXES: tx location, address history, block bloom, log topics ==========
+
+    // index a stored block, safe to run more than once for the same block
+    // logs are indexed from the block's stored receipts, store those first
+    pub fn put_block_indexes(&self, block: &Block) -> Result<()> {
+        let block_hash = block.header.hash();
+
+        for (index, tx) in block.transactions.iter().enumerate() {
+            let location = TxLocation {
+                tx_hash: tx.hash,
+                block_hash,
+                block_number: block.header.index,
+                index: index as u64,
+            };
+            self.put_json(&prefixed_key(TX_INDEX_PREFIX, tx.hash), &location)?;
+
+            for address in tx.indexed_addresses() {
+                let mut history = self.get_address_history(&address)?;
+                if !history.iter().any(|l| l.tx_hash == tx.hash) {
+                    history.push(location.clone());
+                    self.put_json(&prefixed_key(ADDRESS_HISTORY_PREFIX, address), &history)?;
+                }
+            }
+        }
+
+        let receipts = self.get_block_receipts(&block_hash)?.unwrap_or_default();
+        for entry in LogEntry::from_block(block, &receipts) {
+            for topic in &entry.log.topics {
+                let mut logs = self.get_logs(topic)?;
+                if !logs
+                    .iter()
+                    .any(|l| l.block_hash == block_hash && l.log_index == entry.log_index)
+                {
+                    logs.push(entry.clone());
+                    self.put_json(&prefixed_key(LOG_TOPIC_PREFIX, topic), &logs)?;
+                }
+            }
+        }
+
+        self.put_json(
+            &prefixed_key(BLOOM_PREFIX, block_hash),
+            &block.address_bloom(),
+        )
+    }
+
+    // undo `put_block_indexes` for a block that left the canonical chain
+    pub fn remove_block_indexes(&self, block: &Block) -> Result<()> {
+        let block_hash = block.header.hash();
+
+        for tx in &block.transactions {
+            let key = prefixed_key(TX_INDEX_PREFIX, tx.hash);
+            if self
+                .get_json::<TxLocation>(&key)?
+                .is_some_and(|location| location.block_hash == block_hash)
+            {
+                self.db.delete(&key)?;
+            }
+
+            for address in tx.indexed_addresses() {
+                let mut history = self.get_address_history(&address)?;
+                let len = history.len();
+                history.retain(|l| l.block_hash != block_hash);
+                if history.len() != len {
+                    self.put_json(&prefixed_key(ADDRESS_HISTORY_PREFIX, address), &history)?;
+                }
+            }
+        }
+
+        let receipts = self.get_block_receipts(&block_hash)?.unwrap_or_default();
+        for entry in LogEntry::from_block(block, &receipts) {
+            for topic in &entry.log.topics {
+                let mut logs = self.get_logs(topic)?;
+                let len = logs.len();
+                logs.retain(|l| l.block_hash != block_hash);
+                if logs.len() != len {
+                    self.put_json(&prefixed_key(LOG_TOPIC_PREFIX, topic), &logs)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_tx_location(&self, tx_hash: &B256) -> Result<Option<TxLocation>> {
+        self.get_json(&prefixed_key(TX_INDEX_PREFIX, tx_hash))
+    }
+
+    // receipt of a canonical transaction, found through the tx index in its block's receipts
+    pub fn get_receipt(&self, tx_hash: &B256) -> Result<Option<TransactionReceipt>> {
+        let Some(location) = self.get_tx_location(tx_hash)? else {
+            return Ok(None);
+        };
+        let receipts = self
+            .get_block_receipts(&location.block_hash)?
+            .unwrap_or_default();
+        let Some(position) = receipts
+            .iter()
+            .position(|receipt| receipt.transaction_hash == *tx_hash)
+        else {
+            return Ok(None);
+        };
+        let cumulative_gas_used = receipts[..=position]
+            .iter()
+            .fold(U256::ZERO, |gas, receipt| gas + receipt.gas_used);
+        Ok(Some(TransactionReceipt {
+            receipt: receipts[position].clone(),
+            block_hash: location.block_hash,
+            block_number: location.block_number,
+            transaction_index: location.index,
+            cumulative_gas_used,
+        }))
+    }
+
+    // transactions involving an address, oldest first
+    pub fn get_address_history(&self, address: &Address) -> Result<Vec<TxLocation>> {
+        Ok(self
+            .get_json(&prefixed_key(ADDRESS_HISTORY_PREFIX, address))?
+            .unwrap_or_default())
+    }
+
+    // canonical logs carrying a topic, oldest first, see `event_topic` and `address_topic`
+    pub fn get_logs(&self, topic: &B256) -> Result<Vec<LogEntry>> {
+        Ok(self
+            .get_json(&prefixed_key(LOG_TOPIC_PREFIX, topic))?
+            .unwrap_or_default())
+    }
+
+    pub fn get_block_bloom(&self, block_hash: &B256) -> Result<Option<Bloom>> {
+        self.get_json(&prefixed_key(BLOOM_PREFIX, block_hash))
+    }
+
+    pub fn put_reindex_progress(&self, index: u64) -> Result<()> {
+        self.put_json(REINDEX_PROGRESS_KEY, &index)
+    }
+
+    pub fn get_reindex_progress(&self) -> Result<Option<u64>> {
+        self.get_json(REINDEX_PROGRESS_KEY)
+    }
+
+    // ========== EPOCH ACCOUNTING: (epoch, validator) -> statement ==========
+
+    pub fn put_epoch_statement(&self, statement: &EpochStatement) -> Result<()> {
+        self.put_json(
+            &epoch_statement_key(statement.epoch, &statement.validator),
+            statement,
+        )
+    }
+
+    pub fn get_epoch_statement(
+        &self,
+        epoch: u64,
+        validator: &Address,
+    ) -> Result<Option<EpochStatement>> {
+        self.get_json(&epoch_statement_key(epoch, validator))
+    }
+
+    pub fn put_epoch_stats(&self, stats: &EpochStats) -> Result<()> {
+        self.put_json(
+            &prefixed_key(EPOCH_STATS_PREFIX, stats.epoch.to_be_bytes()),
+            stats,
+        )
+    }
+
+    pub fn get_epoch_stats(&self, epoch: u64) -> Result<Option<EpochStats>> {
+        self.get_json(&prefixed_key(EPOCH_STATS_PREFIX, epoch.to_be_bytes()))
+    }
+
+    pub fn put_epoch_settlements(&self, epoch: u64, settlements: &[EpochSettlement]) -> Result<()> {
+        self.put_json(
+            &prefixed_key(EPOCH_SETTLEMENT_PREFIX, epoch.to_be_bytes()),
+            &settlements,
+        )
+    }
+
+    pub fn get_epoch_settlements(&self, epoch: u64) -> Result<Option<Vec<EpochSettlement>>> {
+        self.get_json(&prefixed_key(EPOCH_SETTLEMENT_PREFIX, epoch.to_be_bytes()))
+    }
+
+    pub fn put_epoch_checkpoint(&self, checkpoint: &EpochCheckpoint) -> Result<()> {
+        self.put_json(
+            &prefixed_key(EPOCH_CHECKPOINT_PREFIX, checkpoint.epoch.to_be_bytes()),
+            checkpoint,
+        )
+    }
+
+    pub fn get_epoch_checkpoint(&self, epoch: u64) -> Result<Option<EpochCheckpoint>> {
+        self.get_json(&prefixed_key(EPOCH_CHECKPOINT_PREFIX, epoch.to_be_bytes()))
+    }
+
+    pub fn put_justified_checkpoint(&self, checkpoint: &EpochCheckpoint) -> Result<()> {
+        self.put_json(JUSTIFIED_CHECKPOINT_KEY, checkpoint)
+    }
+
+    pub fn get_justified_checkpoint(&self) -> Result<Option<EpochCheckpoint>> {
+        self.get_json(JUSTIFIED_CHECKPOINT_KEY)
+    }
+
+    pub fn put_finalized_checkpoint(&self, checkpoint: &EpochCheckpoint) -> Result<()> {
+        self.put_json(FINALIZED_CHECKPOINT_KEY, checkpoint)
+    }
+
+    pub fn get_finalized_checkpoint(&self) -> Result<Option<EpochCheckpoint>> {
+        self.get_json(FINALIZED_CHECKPOINT_KEY)
+    }
+
+    pub fn put_validator_scores(&self, scores: &ValidatorScores) -> Result<()> {
+        self.put_json(VALIDATOR_SCORES_KEY, scores)
+    }
+
+    pub fn get_validator_scores(&self) -> Result<Option<ValidatorScores>> {
+        self.get_json(VALIDATOR_SCORES_KEY)
+    }
+
+    pub fn put_validator_set(&self, validators: &StoredValidatorSet) -> Result<()> {
+        self.put_json(VALIDATOR_SET_KEY, validators)
+    }
+
+    pub fn get_validator_set(&self) -> Result<Option<StoredValidatorSet>> {
+        self.get_json(VALIDATOR_SET_KEY)
+    }
+
+    // ========== FINALITY: block_hash -> certificate ==========
+
+    pub fn put_finality_certificate(&self, certificate: &FinalityCertificate) -> Result<()> {
+        self.put_json(
+            &prefixed_key(FINALITY_CERTIFICATE_PREFIX, certificate.block_hash),
+            certificate,
+        )
+    }
+
+    pub fn get_finality_certificate(
+        &self,
+        block_hash: &B256,
+    ) -> Result<Option<FinalityCertificate>> {
+        self.get_json(&prefixed_key(FINALITY_CERTIFICATE_PREFIX, block_hash))
+    }
+
+    pub fn put_finalized_head(&self, head: &HeadInfo) -> Result<()> {
+        self.put_json(FINALIZED_HEAD_KEY, head)
+    }
+
+    pub fn get_finalized_head(&self) -> Result<Option<HeadInfo>> {
+        self.get_json(FINALIZED_HEAD_KEY)
+    }
+
+    // ========== SLASHING: validator -> double proposal evidence ==========
+
+    // false if evidence for the slot is already stored
+    pub fn put_slashing_evidence(&self, evidence: &DoubleProposalEvidence) -> Result<bool> {
+        let mut stored = self.get_slashing_evidence(&evidence.proposer)?;
+        if stored.iter().any(|e| e.slot == evidence.slot) {
+            return Ok(false);
+        }
+        stored.push(evidence.clone());
+        self.put_json(
+            &prefixed_key(SLASHING_EVIDENCE_PREFIX, evidence.proposer),
+            &stored,
+        )?;
+        Ok(true)
+    }
+
+    pub fn get_slashing_evidence(
+        &self,
+        validator: &Address,
+    ) -> Result<Vec<DoubleProposalEvidence>> {
+        Ok(self
+            .get_json(&prefixed_key(SLASHING_EVIDENCE_PREFIX, validator))?
+            .unwrap_or_default())
+    }
+
+    // ========== SLASHING: validator -> double vote evidence ==========
+
+    // false if evidence for the slot is already stored
+    pub fn put_attestation_evidence(
+        &self,
+        evidence: &AttestationEquivocationEvidence,
+    ) -> Result<bool> {
+        let mut stored = self.get_attestation_evidence(&evidence.validator)?;
+        if stored.iter().any(|e| e.slot == evidence.slot) {
+            return Ok(false);
+        }
+        stored.push(evidence.clone());
+        self.put_json(
+            &prefixed_key(ATTESTATION_EVIDENCE_PREFIX, evidence.validator),
+            &stored,
+        )?;
+        Ok(true)
+    }
+
+    pub fn get_attestation_evidence(
+        &self,
+        validator: &Address,
+    ) -> Result<Vec<AttestationEquivocationEvidence>> {
+        Ok(self
+            .get_json(&prefixed_key(ATTESTATION_EVIDENCE_PREFIX, validator))?
+            .unwrap_or_default())
+    }
+
+    // ========== ACCOUNT STATE: the state after the head block ==========
+
+    // write the state after a block, the head is moved last so a crash mid-write is detected
+    // returns how many accounts were written or deleted
+    pub fn put_state(&self, write: &StateWrite) -> Result<usize> {
+        let mut addresses: BTreeSet<Address> =
+            self.get_json(STATE_ACCOUNTS_KEY)?.unwrap_or_default();
+        let mut index_changed = false;
+        let mut written = 0;
+
+        // accounts stored before but missing from a full write are gone
+        let mut deleted = Vec::new();
+        if write.full {
+            let holds: BTreeSet<Address> = write.accounts.iter().map(|(a, _)| *a).collect();
+            deleted.extend(addresses.difference(&holds).map(|a| (*a, None)));
+        }
+
+        for (address, account) in write.accounts.iter().chain(deleted.iter()) {
+            let key = prefixed_key(STATE_ACCOUNT_PREFIX, address);
+            match account {
+                Some(account) => {
+                    self.put_json(&key, account)?;
+                    index_changed |= addresses.insert(*address);
+                }
+                None => {
+                    self.db.delete(&key)?;
+                    index_changed |= addresses.remove(address);
+                }
+            }
+            written += 1;
+        }
+
+        if index_changed {
+            self.put_json(STATE_ACCOUNTS_KEY, &addresses)?;
+        }
+        if let Some(rest) = &write.rest {
+            self.put_json(STATE_REST_KEY, rest)?;
+        }
+        self.put_json(
+            STATE_HEAD_KEY,
+            &StoredStateHead {
+                block_hash: write.block_hash,
+                state_root: write.state_root,
+            },
+        )?;
+        Ok(written)
+    }
+
+    pub fn get_state_head(&self) -> Result<Option<StoredStateHead>> {
+        self.get_json(STATE_HEAD_KEY)
+    }
+
+    // the stored state and the block it belongs to, None when there is none or it doesn't
+    // add up to its state root, e.g. after a crash while it was written
+    pub fn get_state(&self) -> Result<Option<(B256, StateManager)>> {
+        let Some(head) = self.get_state_head()? else {
+            return Ok(None);
+        };
+        let Some(rest) = self.get_json::<StateManager>(STATE_REST_KEY)? else {
+            return Ok(None);
+        };
+        let addresses: BTreeSet<Address> = self.get_json(STATE_ACCOUNTS_KEY)?.unwrap_or_default();
+        let mut accounts = Vec::with_capacity(addresses.len());
+        for address in &addresses {
+            match self.get_json(&prefixed_key(STATE_ACCOUNT_PREFIX, address))? {
+                Some(account) => accounts.push(account),
+                None => return Ok(None),
+            }
+        }
+
+        let state = StateManager::from_stored(rest, accounts);
+        if state.get_state_root() != head.state_root {
+            println!(
+                "⚠️  Stored state has root {}, expected {}, ignoring it",
+                state.get_state_root(),
+                head.state_root
+            );
+            return Ok(None);
+        }
+        Ok(Some((head.block_hash, state)))
+    }
+
+    // ========== CONSENSUS RESTART: genesis + slashing protection ==========
+
+    pub fn put_consensus_genesis(&self, genesis: &ConsensusGenesis) -> Result<()> {
+        self.put_json(CONSENSUS_GENESIS_KEY, genesis)
+    }
+
+    pub fn get_consensus_genesis(&self) -> Result<Option<ConsensusGenesis>> {
+        self.get_json(CONSENSUS_GENESIS_KEY)
+    }
+
+    pub fn put_chain_spec(&self, spec: &ChainSpec) -> Result<()> {
+        self.put_json(CHAIN_SPEC_KEY, spec)
+    }
+
+    pub fn get_chain_spec(&self) -> Result<Option<ChainSpec>> {
+        self.get_json(CHAIN_SPEC_KEY)
+    }
+
+    pub fn put_checkpoint(&self, checkpoint: &Checkpoint) -> Result<()> {
+        self.put_json(CHECKPOINT_KEY, checkpoint)
+    }
+
+    pub fn get_checkpoint(&self) -> Result<Option<Checkpoint>> {
+        self.get_json(CHECKPOINT_KEY)
+    }
+
+    pub fn put_block_randomness(&self, randomness: &BlockRandomness) -> Result<()> {
+        self.put_json(BLOCK_RANDOMNESS_KEY, randomness)
+    }
+
+    pub fn get_block_randomness(&self) -> Result<Option<BlockRandomness>> {
+        self.get_json(BLOCK_RANDOMNESS_KEY)
+    }
+
+    pub fn put_signing_record(&self, validator: &Address, record: &SigningRecord) -> Result<()> {
+        self.put_json(&prefixed_key(SIGNING_RECORD_PREFIX, validator), record)
+    }
+
+    // empty record if the validator never signed anything on this node
+    pub fn get_signing_record(&self, validator: &Address) -> Result<SigningRecord> {
+        Ok(self
+            .get_json(&prefixed_key(SIGNING_RECORD_PREFIX, validator))?
+            .unwrap_or_default())
+    }
+
+    // quarantine a rejected block, dropping the oldest one beyond `capacity`
+    pub fn put_rejected_block(&self, mut rejected: RejectedBlock, capacity: usize) -> Result<u64> {
+        let sequence = self
+            .get_json::<u64>(REJECTED_BLOCKS_NEXT_KEY)?
+            .unwrap_or_default();
+        rejected.sequence = sequence;
+        self.put_json(
+            &prefixed_key(REJECTED_BLOCK_PREFIX, sequence.to_be_bytes()),
+            &rejected,
+        )?;
+        self.put_json(REJECTED_BLOCKS_NEXT_KEY, &(sequence + 1))?;
+
+        if let Some(expired) = sequence.checked_sub(capacity as u64) {
+            self.db
+                .delete(&prefixed_key(REJECTED_BLOCK_PREFIX, expired.to_be_bytes()))
+                .context("Failed to drop expired rejected block")?;
+        }
+        Ok(sequence)
+    }
+
+    // quarantined blocks claiming a number in the range, newest first
+    pub fn get_rejected_blocks(
+        &self,
+        from_block: Option<u64>,
+        to_block: Option<u64>,
+        capacity: usize,
+        limit: usize,
+    ) -> Result<Vec<RejectedBlock>> {
+        let next = self
+            .get_json::<u64>(REJECTED_BLOCKS_NEXT_KEY)?
+            .unwrap_or_default();
+        let oldest = next.saturating_sub(capacity as u64);
+
+        let mut rejected = Vec::new();
+        for sequence in (oldest..next).rev() {
+            if rejected.len() >= limit {
+                break;
+            }
+            if let Some(block) = self.get_json::<RejectedBlock>(&prefixed_key(
+                REJECTED_BLOCK_PREFIX,
+                sequence.to_be_bytes(),
+            ))? && block.in_range(from_block, to_block)
+            {
+                rejected.push(block);
+            }
+        }
+        Ok(rejected)
+    }
+
+    fn put_json<T: Serialize>(&self, key: &[u8], value: &T) -> Result<()> {
+        let json_data = serde_json::to_vec(value).context("Failed to serialize value to JSON")?;
+        inject_storage_write_delay();
+        self.db
+            .put(key, &json_data)
+            .with_context(|| format!("Failed to store data with key: 0x{}", hex::encode(key)))?;
+        Ok(())
+    }
+
+    fn get_json<T: for<'de> Deserialize<'de>>(&self, key: &[u8]) -> Result<Option<T>> {
+        match self
+            .db
+            .get(key)
+            .with_context(|| format!("Failed to retrieve data with key: 0x{}", hex::encode(key)))?
+        {
+            Some(json_bytes) => Ok(Some(serde_json::from_slice(&json_bytes).with_context(
+                || {
+                    format!(
+                        "Failed to deserialize data with key: 0x{}",
+                        hex::encode(key)
+                    )
+                },
+            )?)),
+            None => Ok(None),
+        }
+    }
+
     // Helper method
     // Store block with all necessary indices
     pub fn store_block(&self, block: &Block) -> Result<()> {
@@ -141,3 +751,16 @@ impl Storage {
         Ok(())
     }
 }
+
+fn prefixed_key(prefix: &[u8], id: impl AsRef<[u8]>) -> Vec<u8> {
+    let mut key = prefix.to_vec();
+    key.extend_from_slice(id.as_ref());
+    key
+}
+
+fn epoch_statement_key(epoch: u64, validator: &Address) -> Vec<u8> {
+    let mut key = EPOCH_STATEMENT_PREFIX.to_vec();
+    key.extend_from_slice(&epoch.to_be_bytes());
+    key.extend_from_slice(validator.as_slice());
+    key
+}
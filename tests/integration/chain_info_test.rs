@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod chain_info {
+    use alloy::primitives::{Address, U256};
+    use speed_blockchain::{
+        BaseFeeConfig, Blockchain, ChainSpec, GasConfig, GenesisValidator, MIN_STAKE,
+        SLOT_DURATION, Storage,
+    };
+
+    #[tokio::test]
+    async fn test_chain_info_reports_head_validators_and_sync() {
+        let dir = tempfile::tempdir().unwrap();
+        let validators = vec![
+            (Address::repeat_byte(1), 200),
+            (Address::repeat_byte(2), 300),
+            // below the minimum stake, not active
+            (Address::repeat_byte(3), 1),
+        ];
+        let blockchain = Blockchain::new(
+            dir.path().to_str().unwrap(),
+            MIN_STAKE,
+            SLOT_DURATION,
+            validators,
+            None,
+        )
+        .unwrap();
+
+        let info = blockchain.chain_info(7).await;
+        assert_eq!(info.chain_id, 7);
+        assert_eq!(info.genesis_hash, blockchain.chain_spec_hash());
+        assert_eq!((info.head.number, info.head.slot), (0, 0));
+        assert_eq!(info.finalized.hash, info.head.hash);
+        assert_eq!((info.validator_count, info.total_stake), (2, 500));
+        assert_eq!(info.base_fee, GasConfig::default().min_gas_price);
+        assert!(!info.sync.syncing);
+
+        // a peer's block for a later slot we couldn't apply yet
+        blockchain.note_network_slot(5).await;
+        let info = blockchain.chain_info(7).await;
+        assert!(info.sync.syncing);
+        assert_eq!(info.sync.network_best_slot, Some(5));
+        assert_eq!(info.sync.slots_behind, 5);
+    }
+
+    #[tokio::test]
+    async fn test_chain_info_reports_the_next_base_fee() {
+        let dir = tempfile::tempdir().unwrap();
+        let spec = ChainSpec {
+            gas: GasConfig {
+                base_fee: Some(BaseFeeConfig {
+                    initial_base_fee: U256::from(3_000_000_000u64),
+                    ..BaseFeeConfig::default()
+                }),
+                ..GasConfig::default()
+            },
+            validators: vec![GenesisValidator {
+                address: Address::repeat_byte(1),
+                stake: 200,
+            }],
+            ..ChainSpec::default()
+        };
+        let storage = Storage::new(dir.path()).unwrap();
+        let blockchain = Blockchain::from_chain_spec(storage, &spec, None).unwrap();
+
+        let info = blockchain.chain_info(7).await;
+        assert_eq!(
+            Some(info.base_fee),
+            blockchain.execution_engine.next_base_fee()
+        );
+        assert_eq!(info.base_fee, U256::from(3_000_000_000u64));
+    }
+}
@@ -0,0 +1,279 @@
+#[cfg(test)]
+mod conformance {
+    use alloy::primitives::{Address, B256, Bytes, U256};
+    use serde::Deserialize;
+    use speed_blockchain::{
+        Block, ExecutionEngine, Transaction, TransactionKind,
+        consensus::{ProposerSelection, ValidatorSet},
+        core::{BlockHeader, HashableHeader, transactions_root},
+        dev_keypairs,
+    };
+    use std::fs;
+
+    use crate::integration::helpers::unsigned_transaction;
+
+    // consensus-critical test vectors live in tests/vectors, a mismatch means a
+    // change would split the network from nodes running the previous version
+
+    #[derive(Deserialize)]
+    struct VectorFile<T> {
+        cases: Vec<T>,
+    }
+
+    fn load_vectors<T: for<'de> Deserialize<'de>>(name: &str) -> Vec<T> {
+        let path = format!("{}/tests/vectors/{}", env!("CARGO_MANIFEST_DIR"), name);
+        let data = fs::read_to_string(&path).unwrap_or_else(|e| panic!("read {}: {}", path, e));
+        let file: VectorFile<T> =
+            serde_json::from_str(&data).unwrap_or_else(|e| panic!("parse {}: {}", path, e));
+        file.cases
+    }
+
+    // report every mismatch at once instead of stopping at the first one
+    fn assert_no_failures(vectors: &str, failures: Vec<String>) {
+        assert!(
+            failures.is_empty(),
+            "{} vectors failed:\n{}",
+            vectors,
+            failures.join("\n")
+        );
+    }
+
+    #[derive(Deserialize)]
+    struct VectorTransaction {
+        from: Address,
+        to: Address,
+        amount: U256,
+        timestamp: u64,
+        nonce: u64,
+        #[serde(default)]
+        kind: TransactionKind,
+        gas_limit: U256,
+        gas_price: U256,
+        #[serde(default)]
+        memo: Bytes,
+    }
+
+    impl VectorTransaction {
+        fn build(&self) -> Transaction {
+            let mut tx = Transaction {
+                from: self.from,
+                to: self.to,
+                amount: self.amount,
+                timestamp: self.timestamp,
+                nonce: self.nonce,
+                kind: self.kind.clone(),
+                gas_limit: self.gas_limit,
+                gas_price: self.gas_price,
+                memo: self.memo.clone(),
+                ..unsigned_transaction()
+            };
+            tx.hash = tx.calculate_hash();
+            tx
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct ProposerScheduleCase {
+        name: String,
+        seed: B256,
+        min_stake: u64,
+        validators: Vec<(Address, u64)>,
+        schedule: Vec<(u64, Address)>,
+    }
+
+    #[test]
+    fn test_proposer_schedule_vectors() {
+        let mut failures = Vec::new();
+
+        for case in load_vectors::<ProposerScheduleCase>("proposer_schedule.json") {
+            let mut validator_set = ValidatorSet::new(case.min_stake);
+            for (address, stake) in &case.validators {
+                let _ = validator_set.add_validator(*address, *stake);
+            }
+            let selection = ProposerSelection::new(validator_set, case.seed.0);
+
+            for (slot, expected) in &case.schedule {
+                let actual = selection.selector_proposer(*slot).unwrap();
+                if actual != *expected {
+                    failures.push(format!(
+                        "{}: slot {} expected {}, got {}",
+                        case.name, slot, expected, actual
+                    ));
+                }
+            }
+        }
+
+        assert_no_failures("proposer schedule", failures);
+    }
+
+    #[derive(Deserialize)]
+    struct TransactionHashCase {
+        name: String,
+        transaction: VectorTransaction,
+        hash: B256,
+    }
+
+    #[test]
+    fn test_transaction_hash_vectors() {
+        let mut failures = Vec::new();
+
+        for case in load_vectors::<TransactionHashCase>("transaction_hashes.json") {
+            let actual = case.transaction.build().hash;
+            if actual != case.hash {
+                failures.push(format!(
+                    "{}: expected {}, got {}",
+                    case.name, case.hash, actual
+                ));
+            }
+        }
+
+        assert_no_failures("transaction hash", failures);
+    }
+
+    #[derive(Deserialize)]
+    struct BlockHeaderCase {
+        name: String,
+        header: BlockHeader,
+        encoding: Bytes,
+        hash: B256,
+    }
+
+    #[test]
+    fn test_block_header_hash_vectors() {
+        let mut failures = Vec::new();
+
+        for case in load_vectors::<BlockHeaderCase>("block_headers.json") {
+            let encoding = HashableHeader::from(&case.header).encode();
+            if encoding[..] != case.encoding[..] {
+                failures.push(format!(
+                    "{}: encoding expected {}, got 0x{}",
+                    case.name,
+                    case.encoding,
+                    hex::encode(encoding)
+                ));
+            }
+
+            let actual = case.header.hash();
+            if actual != case.hash {
+                failures.push(format!(
+                    "{}: expected {}, got {}",
+                    case.name, case.hash, actual
+                ));
+            }
+        }
+
+        assert_no_failures("block header hash", failures);
+    }
+
+    #[derive(Deserialize)]
+    struct TransactionsRootCase {
+        name: String,
+        transaction_hashes: Vec<B256>,
+        root: B256,
+    }
+
+    #[test]
+    fn test_transactions_root_vectors() {
+        let mut failures = Vec::new();
+
+        for case in load_vectors::<TransactionsRootCase>("transactions_roots.json") {
+            let transactions: Vec<Transaction> = case
+                .transaction_hashes
+                .iter()
+                .map(|hash| Transaction {
+                    // only the hash goes into the root
+                    hash: *hash,
+                    ..unsigned_transaction()
+                })
+                .collect();
+
+            let actual = transactions_root(&transactions);
+            if actual != case.root {
+                failures.push(format!(
+                    "{}: expected {}, got {}",
+                    case.name, case.root, actual
+                ));
+            }
+            // blocks and consensus must agree on the root
+            if Block::calculate_transactions_root(&transactions) != actual {
+                failures.push(format!("{}: Block root differs", case.name));
+            }
+        }
+
+        assert_no_failures("transactions root", failures);
+    }
+
+    #[derive(Deserialize)]
+    struct GenesisAccount {
+        address: Address,
+        balance: U256,
+    }
+
+    #[derive(Deserialize)]
+    struct VectorBlock {
+        transactions: Vec<VectorTransaction>,
+        // receipt status of each transaction
+        success: Vec<bool>,
+        total_gas_used: U256,
+        state_root: B256,
+    }
+
+    #[derive(Deserialize)]
+    struct StateTransitionCase {
+        name: String,
+        genesis: Vec<GenesisAccount>,
+        blocks: Vec<VectorBlock>,
+    }
+
+    #[tokio::test]
+    async fn test_state_transition_vectors() {
+        let mut failures = Vec::new();
+        // senders sign with their dev account keys, anyone else stays unsigned
+        let dev_keys = dev_keypairs(2).unwrap();
+
+        for case in load_vectors::<StateTransitionCase>("state_transitions.json") {
+            let engine = ExecutionEngine::new();
+            {
+                let mut state = engine.state_manager.lock().await;
+                for account in &case.genesis {
+                    state.fund_account(&account.address, account.balance);
+                }
+            }
+
+            for (number, vector) in case.blocks.iter().enumerate() {
+                let mut transactions = Vec::new();
+                for vector_tx in &vector.transactions {
+                    let mut tx = vector_tx.build();
+                    if let Some(key) = dev_keys.iter().find(|key| key.address == tx.from) {
+                        tx.sign(key).await.unwrap();
+                    }
+                    transactions.push(tx);
+                }
+                let mut block = Block::new(BlockHeader::genesis(), transactions);
+                let result = engine.execute_block_commit(&mut block).await.unwrap();
+
+                let success: Vec<bool> = result.receipts.iter().map(|r| r.success).collect();
+                if success != vector.success {
+                    failures.push(format!(
+                        "{} block {}: receipts expected {:?}, got {:?}",
+                        case.name, number, vector.success, success
+                    ));
+                }
+                if result.total_gas_used != vector.total_gas_used {
+                    failures.push(format!(
+                        "{} block {}: gas used expected {}, got {}",
+                        case.name, number, vector.total_gas_used, result.total_gas_used
+                    ));
+                }
+                if result.state_root != vector.state_root {
+                    failures.push(format!(
+                        "{} block {}: state root expected {}, got {}",
+                        case.name, number, vector.state_root, result.state_root
+                    ));
+                }
+            }
+        }
+
+        assert_no_failures("state transition", failures);
+    }
+}
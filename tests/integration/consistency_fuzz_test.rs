@@ -0,0 +1,419 @@
+#[cfg(test)]
+mod consistency_fuzz {
+    use alloy::primitives::{Address, B256, U256};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use speed_blockchain::account::Account;
+    use speed_blockchain::consensus::ConsensusGenesis;
+    use speed_blockchain::core::BlockHeader;
+    use speed_blockchain::{
+        Block, BlockProcessResult, Blockchain, CHAIN_ID, GasCalculator, GasConfig, KeyPair,
+        MIN_STAKE, Receipt, ReceiptEvent, SLOT_DURATION, StateManager, StateTransition, Storage,
+        Transaction, TransactionKind, dev_keypairs, logs_bloom, receipts_root,
+    };
+    use std::path::Path;
+    use std::time::{Duration, SystemTime};
+
+    use crate::integration::helpers::unsigned_transaction;
+
+    // operations per run, `SPEED_FUZZ_SEED=<n>` replays a single run
+    const OPERATIONS: usize = 40;
+    const SEEDS: u64 = 4;
+    const ONE_TOKEN: u64 = 1_000_000_000_000_000_000;
+    const TO_GWEI: u64 = 1_000_000_000;
+
+    // the chain has no reorgs or pruning yet, blocks only ever extend the head
+    #[derive(Debug, Clone, Copy)]
+    enum Operation {
+        Commit,
+        // blocks every node must turn away without changing anything
+        WrongParent,
+        ForgedTransactionsRoot,
+        Overspend,
+        ReplayHead,
+        Restart,
+    }
+
+    impl Operation {
+        fn random(rng: &mut StdRng) -> Self {
+            match rng.gen_range(0..10) {
+                0..=4 => Operation::Commit,
+                5 => Operation::WrongParent,
+                6 => Operation::ForgedTransactionsRoot,
+                7 => Operation::Overspend,
+                8 => Operation::ReplayHead,
+                _ => Operation::Restart,
+            }
+        }
+    }
+
+    // what the node must agree with, built without it: accounts after every
+    // committed block and the blocks themselves, block n at index n - 1
+    struct ModelChain {
+        state: StateManager,
+        blocks: Vec<Block>,
+        genesis: Vec<(Address, U256)>,
+    }
+
+    impl ModelChain {
+        fn new(genesis: Vec<(Address, U256)>) -> Self {
+            let mut state = StateManager::new();
+            state.load_accounts(accounts(&genesis));
+            Self {
+                state,
+                blocks: Vec::new(),
+                genesis,
+            }
+        }
+
+        fn head(&self) -> (u64, B256, u64) {
+            self.blocks.last().map_or((0, B256::ZERO, 0), |block| {
+                (block.header.index, block.header.hash(), block.header.slot)
+            })
+        }
+    }
+
+    struct Harness {
+        dir: tempfile::TempDir,
+        validator: KeyPair,
+        senders: Vec<KeyPair>,
+        recipients: Vec<Address>,
+        rng: StdRng,
+        model: ModelChain,
+        blockchain: Option<Blockchain>,
+        // highest slot the validator signed a block for, valid or not, two blocks
+        // for one slot would get it slashed
+        signed_slot: u64,
+        // far enough back that every slot a run signs has started
+        genesis_time: u64,
+    }
+
+    fn accounts(balances: &[(Address, U256)]) -> Vec<Account> {
+        balances
+            .iter()
+            .map(|(address, balance)| Account {
+                address: *address,
+                balance: *balance,
+                nonce: 0,
+            })
+            .collect()
+    }
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    impl Harness {
+        async fn new(seed: u64) -> Self {
+            let senders = dev_keypairs(3).unwrap();
+            let genesis: Vec<(Address, U256)> = senders
+                .iter()
+                .map(|k| (k.address, U256::from(ONE_TOKEN)))
+                .collect();
+            let mut recipients: Vec<Address> = (1..=3).map(Address::repeat_byte).collect();
+            recipients.extend(senders.iter().map(|k| k.address));
+
+            let dir = tempfile::tempdir().unwrap();
+            let genesis_time = now() - 3 * OPERATIONS as u64 * SLOT_DURATION;
+            Storage::new(dir.path())
+                .unwrap()
+                .put_consensus_genesis(&ConsensusGenesis::new(
+                    SystemTime::UNIX_EPOCH + Duration::from_secs(genesis_time),
+                    [1u8; 32],
+                ))
+                .unwrap();
+            let mut harness = Self {
+                dir,
+                validator: KeyPair::generate("fuzz-validator".to_string()),
+                senders,
+                recipients,
+                rng: StdRng::seed_from_u64(seed),
+                model: ModelChain::new(genesis),
+                blockchain: None,
+                signed_slot: 0,
+                genesis_time,
+            };
+            harness.start(false).await;
+            harness
+        }
+
+        fn blockchain(&self) -> &Blockchain {
+            self.blockchain.as_ref().unwrap()
+        }
+
+        // open the node on the data dir, a restart rebuilds accounts from stored state diffs
+        async fn start(&mut self, restarted: bool) {
+            let blockchain = Blockchain::new(
+                self.dir.path().to_str().unwrap(),
+                MIN_STAKE,
+                SLOT_DURATION,
+                vec![(self.validator.address, 200)],
+                Some(self.validator.clone()),
+            )
+            .unwrap();
+            if restarted {
+                blockchain.warm_up_state(u64::MAX).await.unwrap();
+            }
+            // genesis accounts a block changed since were loaded above and are kept
+            blockchain
+                .execution_engine
+                .prefund_accounts(&self.model.genesis)
+                .await;
+            self.blockchain = Some(blockchain);
+        }
+
+        async fn restart(&mut self) {
+            self.blockchain = None;
+            check_storage_indexes(self.dir.path(), &self.model);
+            self.start(true).await;
+        }
+
+        // a block on top of the model head with transfers the model state can pay for,
+        // returned with the state it leaves
+        async fn next_block(&mut self) -> (Block, StateManager) {
+            let config = GasConfig::default();
+            let mut state = self.model.state.clone();
+            let mut transactions = Vec::new();
+            let mut receipts = Vec::new();
+            for _ in 0..self.rng.gen_range(0..=4) {
+                let sender = self.senders[self.rng.gen_range(0..self.senders.len())].clone();
+                let to = loop {
+                    let to = self.recipients[self.rng.gen_range(0..self.recipients.len())];
+                    if to != sender.address {
+                        break to;
+                    }
+                };
+                let amount = U256::from(self.rng.gen_range(1..=1_000_000u64));
+                let mut tx = transfer(&sender, to, amount, state.get_nonce(&sender.address)).await;
+                let gas_used =
+                    StateTransition::apply_transaction(&mut state, &mut tx, &config).unwrap();
+                StateTransition::pay_fee(&mut state, &tx, gas_used, None, self.validator.address);
+                receipts.push(
+                    Receipt::success(tx.hash, gas_used)
+                        .with_events(ReceiptEvent::from_transaction(&tx)),
+                );
+                transactions.push(tx);
+            }
+
+            let (index, parent_hash, _) = self.model.head();
+            self.signed_slot += self.rng.gen_range(1..=3);
+            let block = Block::new(
+                BlockHeader {
+                    index: index + 1,
+                    parent_hash,
+                    slot: self.signed_slot,
+                    timestamp: self.genesis_time + self.signed_slot * SLOT_DURATION,
+                    proposer: self.validator.address,
+                    transactions_root: Block::calculate_transactions_root(&transactions),
+                    state_root: state.get_state_root(),
+                    slot_timing: None,
+                    receipts_root: Some(receipts_root(&receipts)),
+                    logs_bloom: Some(logs_bloom(&receipts)),
+                    base_fee_per_gas: None,
+                    gas_used: None,
+                    gas_limit: None,
+                    validator_signature: None,
+                },
+                transactions,
+            );
+            (block, state)
+        }
+
+        async fn import(&self, block: Block) -> BlockProcessResult {
+            let signature = self
+                .validator
+                .sign_hash(&block.header.hash())
+                .await
+                .unwrap();
+            self.blockchain()
+                .process_received_block(block, self.validator.address, signature)
+                .await
+                .unwrap()
+        }
+
+        async fn apply(&mut self, operation: Operation) {
+            match operation {
+                Operation::Commit => {
+                    let (block, state) = self.next_block().await;
+                    let result = self.import(block.clone()).await;
+                    assert!(
+                        matches!(result, BlockProcessResult::Accepted(_)),
+                        "valid block #{} turned away: {:?}",
+                        block.header.index,
+                        result
+                    );
+                    self.model.state = state;
+                    self.model.blocks.push(block);
+                }
+                Operation::WrongParent => {
+                    let (mut block, _) = self.next_block().await;
+                    block.header.parent_hash = B256::from(self.rng.r#gen::<[u8; 32]>());
+                    self.expect_rejected(block).await;
+                }
+                Operation::ForgedTransactionsRoot => {
+                    let (mut block, _) = self.next_block().await;
+                    block.header.transactions_root = B256::repeat_byte(0xf0);
+                    self.expect_rejected(block).await;
+                }
+                Operation::Overspend => {
+                    let (mut block, _) = self.next_block().await;
+                    let sender = self.senders[0].clone();
+                    let nonce = self.model.state.get_nonce(&sender.address)
+                        + block
+                            .transactions
+                            .iter()
+                            .filter(|tx| tx.from == sender.address)
+                            .count() as u64;
+                    let balance = self.model.state.get_balance(&sender.address);
+                    let tx = transfer(&sender, self.recipients[0], balance, nonce).await;
+                    block.transactions.push(tx);
+                    block.header.transactions_root =
+                        Block::calculate_transactions_root(&block.transactions);
+                    self.expect_rejected(block).await;
+                }
+                Operation::ReplayHead => {
+                    let Some(head) = self.model.blocks.last() else {
+                        return;
+                    };
+                    self.expect_rejected(head.clone()).await;
+                }
+                Operation::Restart => self.restart().await,
+            }
+        }
+
+        async fn expect_rejected(&self, block: Block) {
+            let index = block.header.index;
+            let result = self.import(block).await;
+            assert!(
+                matches!(result, BlockProcessResult::Rejected(..)),
+                "invalid block #{} accepted",
+                index
+            );
+        }
+    }
+
+    async fn transfer(sender: &KeyPair, to: Address, amount: U256, nonce: u64) -> Transaction {
+        let mut tx = Transaction {
+            from: sender.address,
+            to,
+            amount,
+            timestamp: 1,
+            nonce,
+            kind: TransactionKind::Transfer,
+            gas_limit: U256::ZERO,
+            gas_price: U256::from(TO_GWEI),
+            ..unsigned_transaction()
+        };
+        tx.gas_limit = GasCalculator::calculate_instrinsic_gas(&tx, &GasConfig::default());
+        tx.sign(sender).await.unwrap();
+        tx
+    }
+
+    // consensus head, stored blocks and receipts, and execution state all match the model
+    async fn check_consistency(blockchain: &Blockchain, model: &ModelChain) {
+        let (number, hash, slot) = model.head();
+        let info = blockchain.chain_info(CHAIN_ID).await;
+        assert_eq!(
+            (info.head.number, info.head.hash, info.head.slot),
+            (number, hash, slot),
+            "consensus head"
+        );
+        assert_eq!(
+            blockchain.get_last_index().await.unwrap(),
+            number,
+            "storage head"
+        );
+
+        for expected in &model.blocks {
+            let index = expected.header.index;
+            let committed = blockchain
+                .committed_block(index)
+                .await
+                .unwrap()
+                .unwrap_or_else(|| panic!("block #{} missing from storage", index));
+            assert_eq!(
+                committed.block.header.hash(),
+                expected.header.hash(),
+                "stored block #{}",
+                index
+            );
+            assert_eq!(
+                committed.receipts.len(),
+                expected.transactions.len(),
+                "receipts of block #{}",
+                index
+            );
+            assert!(committed.receipts.iter().all(|r| r.success));
+        }
+        assert!(
+            blockchain
+                .committed_block(number + 1)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let state = blockchain.execution_engine.state_manager.lock().await;
+        assert_eq!(
+            state.get_state_root(),
+            model.state.get_state_root(),
+            "execution state"
+        );
+        if let Some(head) = model.blocks.last() {
+            assert_eq!(
+                state.get_state_root(),
+                head.header.state_root,
+                "state root of the head block"
+            );
+        }
+    }
+
+    // indexes only reachable through storage, checked while the node is down
+    fn check_storage_indexes(dir: &Path, model: &ModelChain) {
+        let storage = Storage::new(dir).unwrap();
+        for expected in &model.blocks {
+            let index = expected.header.index;
+            assert_eq!(
+                storage.get_block_hash_from_index(&index).unwrap(),
+                Some(expected.header.hash()),
+                "hash index of block #{}",
+                index
+            );
+            for (position, tx) in expected.transactions.iter().enumerate() {
+                let location = storage.get_tx_location(&tx.hash).unwrap().unwrap();
+                assert_eq!(
+                    (location.block_number, location.index),
+                    (index, position as u64),
+                    "location of {}",
+                    tx.hash
+                );
+            }
+        }
+    }
+
+    async fn run(seed: u64) {
+        let mut harness = Harness::new(seed).await;
+        for step in 0..OPERATIONS {
+            let operation = Operation::random(&mut harness.rng);
+            println!("🎲 seed {} step {}: {:?}", seed, step, operation);
+            harness.apply(operation).await;
+            check_consistency(harness.blockchain(), &harness.model).await;
+        }
+        harness.restart().await;
+        check_consistency(harness.blockchain(), &harness.model).await;
+    }
+
+    #[tokio::test]
+    async fn test_storage_consensus_and_execution_agree_under_random_operations() {
+        let seeds: Vec<u64> = match std::env::var("SPEED_FUZZ_SEED") {
+            Ok(seed) => vec![seed.parse().expect("SPEED_FUZZ_SEED is a number")],
+            Err(_) => (0..SEEDS).collect(),
+        };
+        for seed in seeds {
+            run(seed).await;
+        }
+    }
+}
@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod embedding {
+    use alloy::primitives::{Address, B256};
+    use speed_blockchain::consensus::{
+        ProposerElection, ProposerSelection, Validator, ValidatorSet,
+    };
+    use speed_blockchain::core::BlockHeader;
+    use speed_blockchain::storage::MemoryStore;
+    use speed_blockchain::{
+        Block, BlockchainMessage, KeyPair, MIN_STAKE, NetworkAdmin, NetworkChannels,
+        NetworkTransport, NodeBuilder, NodeConfig, Storage, TransportFuture,
+    };
+    use std::time::Duration;
+    use tokio::sync::mpsc::{UnboundedSender, unbounded_channel};
+
+    // hands everything the node publishes to the test instead of peers
+    struct LoopbackTransport {
+        channels: NetworkChannels,
+        published: UnboundedSender<BlockchainMessage>,
+    }
+
+    impl NetworkTransport for LoopbackTransport {
+        fn admin(&self) -> Option<NetworkAdmin> {
+            None
+        }
+
+        fn serve(mut self: Box<Self>, _port: u16) -> TransportFuture {
+            Box::pin(async move {
+                while let Some(message) = self.channels.from_blockchain.recv().await {
+                    let _ = self.published.send(message);
+                }
+                Ok(())
+            })
+        }
+    }
+
+    // the validator with the lowest address proposes every slot
+    struct FirstValidator;
+
+    impl ProposerElection for FirstValidator {
+        fn elect(&self, _slot: u64, _seed: [u8; 32], validators: &[&Validator]) -> Address {
+            validators[0].address
+        }
+    }
+
+    #[tokio::test]
+    async fn test_embedded_node_runs_on_custom_storage_and_network() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = dir.path().join("data");
+        let config = NodeConfig {
+            data_dir: data_dir.to_str().unwrap().to_string(),
+            rpc_port: 0,
+            ..NodeConfig::default()
+        };
+
+        let (published, mut from_node) = unbounded_channel();
+        let node = NodeBuilder::new()
+            .with_config(config)
+            .with_storage(MemoryStore::new())
+            .with_network(move |channels| {
+                Ok(Box::new(LoopbackTransport {
+                    channels,
+                    published,
+                }) as Box<dyn NetworkTransport>)
+            })
+            .with_consensus(FirstValidator)
+            .build()
+            .await
+            .unwrap();
+
+        // the blockchain service reports its head to the network layer on start
+        let message = tokio::time::timeout(Duration::from_secs(5), from_node.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(message, BlockchainMessage::HeadSlot { slot: 0 }));
+        assert!(!data_dir.exists());
+
+        node.shutdown();
+    }
+
+    #[test]
+    fn test_custom_election_and_memory_store() {
+        let mut validators = ValidatorSet::new(MIN_STAKE);
+        for i in 0..4 {
+            let address = KeyPair::generate(format!("embedded-{}", i)).address;
+            assert!(validators.add_validator(address, MIN_STAKE).is_ok());
+        }
+        let first = validators.get_active_validators()[0].address;
+        let mut selection = ProposerSelection::new(validators, [1u8; 32]);
+        selection.set_election(std::sync::Arc::new(FirstValidator));
+        assert!((0..32).all(|slot| selection.selector_proposer(slot).unwrap() == first));
+
+        let storage = Storage::with_backend(MemoryStore::new());
+        let block = Block::new(
+            BlockHeader::new(1, 3, first, B256::repeat_byte(1), B256::ZERO, B256::ZERO),
+            vec![],
+        );
+        storage.store_block(&block).unwrap();
+        assert_eq!(storage.get_last_index().unwrap(), Some(1));
+        assert_eq!(
+            storage.get_block_hash_from_index(&1).unwrap(),
+            Some(block.header.hash())
+        );
+    }
+}
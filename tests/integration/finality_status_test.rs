@@ -0,0 +1,169 @@
+#[cfg(test)]
+mod finality_status {
+    use alloy::primitives::B256;
+    use speed_blockchain::consensus::{ConsensusGenesis, attestation_hash};
+    use speed_blockchain::core::{BlockFinality, BlockHeader};
+    use speed_blockchain::{
+        Attestation, AttestationVote, Block, BlockProcessResult, Blockchain, CHAIN_ID, KeyPair,
+        MIN_STAKE, SLOT_DURATION, SLOTS_PER_EPOCH, Storage,
+    };
+    use std::time::{Duration, SystemTime};
+
+    async fn accept(key: &KeyPair, block_hash: &B256) -> Attestation {
+        let signature = key
+            .sign_hash(&attestation_hash(block_hash, &AttestationVote::Accept))
+            .await
+            .unwrap();
+        Attestation {
+            validator_id: key.address,
+            vote: AttestationVote::Accept,
+            signature,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_block_is_final_once_two_thirds_of_stake_accepted() {
+        let dir = tempfile::tempdir().unwrap();
+        let heavy = KeyPair::generate("finality-status-heavy".to_string());
+        let middle = KeyPair::generate("finality-status-middle".to_string());
+        let light = KeyPair::generate("finality-status-light".to_string());
+        // started epochs ago, so the light validator's first slot has come
+        let genesis_time =
+            SystemTime::now() - Duration::from_secs(4 * SLOTS_PER_EPOCH * SLOT_DURATION);
+        Storage::new(dir.path())
+            .unwrap()
+            .put_consensus_genesis(&ConsensusGenesis::new(genesis_time, [1u8; 32]))
+            .unwrap();
+        let blockchain = Blockchain::new(
+            dir.path().to_str().unwrap(),
+            MIN_STAKE,
+            SLOT_DURATION,
+            vec![
+                (heavy.address, 600),
+                (middle.address, 300),
+                (light.address, 100),
+            ],
+            None,
+        )
+        .unwrap();
+
+        // a block from the lightest validator, so two of three signers are not enough
+        let slot = {
+            let consensus = blockchain.consensus_engine.lock().await;
+            (1..)
+                .find(|slot| consensus.scheduled_proposer(*slot) == Some(light.address))
+                .unwrap()
+        };
+        let state_root = blockchain
+            .execution_engine
+            .state_manager
+            .lock()
+            .await
+            .get_state_root();
+        let mut header = BlockHeader {
+            index: 1,
+            parent_hash: B256::ZERO,
+            slot,
+            timestamp: (genesis_time + Duration::from_secs(slot * SLOT_DURATION))
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            proposer: light.address,
+            transactions_root: Block::calculate_transactions_root(&[]),
+            state_root,
+            slot_timing: None,
+            receipts_root: None,
+            logs_bloom: None,
+            base_fee_per_gas: None,
+            gas_used: None,
+            gas_limit: None,
+            validator_signature: None,
+        };
+        // the certificate carries the proposer's signature in the header
+        header.sign(&light).await.unwrap();
+        let block = Block::new(header, Vec::new());
+        let hash = block.header.hash();
+        let signature = light.sign_hash(&hash).await.unwrap();
+        let result = blockchain
+            .process_received_block(block, light.address, signature)
+            .await
+            .unwrap();
+        assert!(matches!(result, BlockProcessResult::Accepted(_)));
+
+        let pending = |accepted_stake, voters| {
+            Some(BlockFinality::Pending {
+                accepted_stake,
+                quorum_stake: 667,
+                voters,
+            })
+        };
+        assert_eq!(
+            blockchain.finality_status(&hash).await.unwrap(),
+            pending(100, 0)
+        );
+        assert!(
+            blockchain
+                .finality_status(&B256::repeat_byte(7))
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let mut votes = vec![accept(&middle, &hash).await];
+        blockchain
+            .record_attestation(&hash, middle.address)
+            .await
+            .unwrap();
+        assert!(
+            blockchain
+                .certify_block(&hash, &votes)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(
+            blockchain.finality_status(&hash).await.unwrap(),
+            pending(400, 1)
+        );
+        assert_eq!(blockchain.chain_info(CHAIN_ID).await.finalized.number, 0);
+
+        votes.push(accept(&heavy, &hash).await);
+        blockchain
+            .record_attestation(&hash, heavy.address)
+            .await
+            .unwrap();
+        assert!(
+            blockchain
+                .certify_block(&hash, &votes)
+                .await
+                .unwrap()
+                .is_some()
+        );
+        assert_eq!(
+            blockchain.finality_status(&hash).await.unwrap(),
+            Some(BlockFinality::Finalized)
+        );
+        let info = blockchain.chain_info(CHAIN_ID).await;
+        assert_eq!((info.finalized.number, info.finalized.hash), (1, hash));
+        assert_eq!(info.finalized, info.head);
+
+        // the finalized head is kept across restarts
+        drop(blockchain);
+        let blockchain = Blockchain::new(
+            dir.path().to_str().unwrap(),
+            MIN_STAKE,
+            SLOT_DURATION,
+            vec![
+                (heavy.address, 600),
+                (middle.address, 300),
+                (light.address, 100),
+            ],
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            blockchain.finalized_head().await.map(|h| h.hash),
+            Some(hash)
+        );
+    }
+}
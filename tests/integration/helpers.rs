@@ -0,0 +1,24 @@
+use alloy::primitives::{Address, B256, Bytes, U256};
+use alloy_signer::Signature;
+use speed_blockchain::{CHAIN_ID, Transaction, TransactionKind};
+
+// unsigned zero value transfer on the default chain, tests set the fields they care about
+// with `Transaction { nonce, ..unsigned_transaction() }`, a new optional field defaults here
+pub fn unsigned_transaction() -> Transaction {
+    Transaction {
+        from: Address::ZERO,
+        to: Address::ZERO,
+        amount: U256::ZERO,
+        timestamp: 1,
+        nonce: 0,
+        chain_id: CHAIN_ID,
+        kind: TransactionKind::Transfer,
+        gas_limit: U256::ZERO,
+        gas_price: U256::ZERO,
+        max_priority_fee_per_gas: None,
+        memo: Bytes::new(),
+        data: Bytes::new(),
+        signature: Signature::new(U256::ZERO, U256::ZERO, false),
+        hash: B256::ZERO,
+    }
+}
@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod idempotency {
+    use alloy::primitives::{Address, B256, U256};
+    use jsonrpsee::RpcModule;
+    use speed_blockchain::rpc::{IdempotencyCache, rpc::SpeedBlockchainRpcServer};
+    use speed_blockchain::{
+        Blockchain, KeyPair, MIN_STAKE, SLOT_DURATION, SpeedRpcImpl, Transaction, TransactionKind,
+        dev_keypairs,
+    };
+    use std::time::{Duration, Instant};
+
+    use crate::integration::helpers::unsigned_transaction;
+
+    async fn signed_transfer(keypair: &KeyPair, timestamp: u64) -> Transaction {
+        let mut tx = Transaction {
+            from: keypair.address,
+            to: Address::repeat_byte(0xbb),
+            amount: U256::from(1_000),
+            timestamp,
+            nonce: 0,
+            kind: TransactionKind::Transfer,
+            gas_limit: U256::from(30_000),
+            gas_price: U256::from(1_000_000_000u64),
+            ..unsigned_transaction()
+        };
+        tx.sign(keypair).await.unwrap();
+        tx
+    }
+
+    // a wallet re-signs and resends after losing the response of its first request
+    #[tokio::test]
+    async fn test_retried_request_returns_original_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let keypair = dev_keypairs(1).unwrap().remove(0);
+        let blockchain = Blockchain::new(
+            dir.path().to_str().unwrap(),
+            MIN_STAKE,
+            SLOT_DURATION,
+            vec![],
+            None,
+        )
+        .unwrap();
+        blockchain
+            .execution_engine
+            .prefund_accounts(&[(keypair.address, U256::from(10).pow(U256::from(18)))])
+            .await;
+        let module: RpcModule<_> = SpeedRpcImpl::new(blockchain.clone()).into_rpc();
+
+        let first = signed_transfer(&keypair, 1).await;
+        let retry = signed_transfer(&keypair, 2).await;
+        assert_ne!(first.hash, retry.hash);
+
+        let hash: B256 = module
+            .call(
+                "eth_sendRawTransaction",
+                (first.encode_raw(), None::<bool>, Some("wallet-1")),
+            )
+            .await
+            .unwrap();
+        assert_eq!(hash, first.hash);
+        let again: B256 = module
+            .call(
+                "eth_sendRawTransaction",
+                (retry.encode_raw(), None::<bool>, Some("wallet-1")),
+            )
+            .await
+            .unwrap();
+        assert_eq!(again, first.hash);
+
+        // the retry never reached the mempool
+        let pending = blockchain.txpool_content().await;
+        let by_nonce = &pending.pending[&keypair.address];
+        assert_eq!(by_nonce.len(), 1);
+    }
+
+    #[test]
+    fn test_request_ids_expire_and_are_scoped_to_the_sender() {
+        let mut cache = IdempotencyCache::new(Duration::from_secs(60));
+        let alice = Address::repeat_byte(0xaa);
+        let bob = Address::repeat_byte(0xbb);
+        let now = Instant::now();
+
+        cache.record(alice, "req".to_string(), B256::repeat_byte(1), now);
+        assert_eq!(cache.lookup(alice, "req", now), Some(B256::repeat_byte(1)));
+        assert_eq!(cache.lookup(bob, "req", now), None);
+
+        let later = now + Duration::from_secs(61);
+        assert_eq!(cache.lookup(alice, "req", later), None);
+        cache.prune(later);
+        assert!(cache.is_empty());
+
+        assert!(IdempotencyCache::validate("").is_err());
+        assert!(IdempotencyCache::validate(&"x".repeat(129)).is_err());
+    }
+}
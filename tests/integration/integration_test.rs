@@ -1,12 +1,14 @@
 #[cfg(test)]
 mod integration_test {
-    use alloy::primitives::{B256, U256};
+    use alloy::primitives::U256;
     use alloy_signer::Signature;
     use anyhow::Result;
-    use speed_blockchain::{Blockchain, KeyPair, Transaction};
+    use speed_blockchain::{Blockchain, KeyPair, Transaction, TransactionKind};
     use std::str::FromStr;
     use tokio;
 
+    use crate::integration::helpers::unsigned_transaction;
+
     const DB_PATH: &str = "blockchain_db";
     const TO_GWEI: u128 = 1_000_000_000;
     const TO_ETH: u128 = 1_000_000_000_000_000_000;
@@ -103,10 +105,11 @@ mod integration_test {
             amount: U256::from(1 * TO_ETH),
             timestamp: current_timestamp(),
             nonce: 0,
-            gas_limit: U256::from(21000),
+            kind: TransactionKind::Transfer,
+            gas_limit: U256::from(30000),
             gas_price: U256::from(TO_GWEI), // 1gwei
             signature: create_dummy_signature(),
-            hash: B256::ZERO,
+            ..unsigned_transaction()
         };
 
         let tx_hash = transaction.calculate_hash();
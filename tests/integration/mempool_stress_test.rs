@@ -0,0 +1,155 @@
+#[cfg(test)]
+mod mempool_stress {
+    use alloy::primitives::{Address, U256};
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+    use speed_blockchain::crypto::SignatureCache;
+    use speed_blockchain::execution::Mempool;
+    use speed_blockchain::{KeyPair, Transaction, TransactionKind};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use crate::integration::helpers::unsigned_transaction;
+
+    // the node's pool size, see `ExecutionEngine::new`
+    const CAPACITY: usize = 1_000;
+    const SENDERS: usize = 40;
+    const REPLACEMENTS: usize = 200;
+    const EVICTIONS: usize = 200;
+    const GWEI: u64 = 1_000_000_000;
+
+    // p99 budgets per operation, signatures are recovered up front so only the pool's
+    // own bookkeeping is timed; roughly 10x what a debug build measures, for noisy runners
+    const ADD_P99: Duration = Duration::from_micros(500);
+    const REPLACE_P99: Duration = Duration::from_micros(500);
+    const EVICT_P99: Duration = Duration::from_micros(500);
+    const GET_PENDING_P99: Duration = Duration::from_millis(10);
+
+    fn p99(mut samples: Vec<Duration>) -> Duration {
+        samples.sort();
+        samples[(samples.len() * 99 / 100).min(samples.len() - 1)]
+    }
+
+    async fn signed(keypair: &KeyPair, nonce: u64, gas_price: u64) -> Transaction {
+        let mut tx = Transaction {
+            from: keypair.address,
+            to: Address::repeat_byte(0xbb),
+            amount: U256::from(1_000),
+            timestamp: 1,
+            nonce,
+            kind: TransactionKind::Transfer,
+            gas_limit: U256::from(30_000),
+            gas_price: U256::from(gas_price * GWEI),
+            ..unsigned_transaction()
+        };
+        tx.sign(keypair).await.unwrap();
+        tx
+    }
+
+    // `count` transactions from random senders, nonces shuffled within each sender
+    async fn random_load(rng: &mut StdRng, keypairs: &[KeyPair], count: usize) -> Vec<Transaction> {
+        let mut per_sender = vec![0u64; keypairs.len()];
+        let mut slots = Vec::with_capacity(count);
+        for _ in 0..count {
+            let sender = rng.gen_range(0..keypairs.len());
+            slots.push((sender, per_sender[sender]));
+            per_sender[sender] += 1;
+        }
+        slots.shuffle(rng);
+
+        let mut transactions = Vec::with_capacity(count);
+        for (sender, nonce) in slots {
+            let gas_price = rng.gen_range(1..=100);
+            transactions.push(signed(&keypairs[sender], nonce, gas_price).await);
+        }
+        transactions
+    }
+
+    fn timed<T>(samples: &mut Vec<Duration>, op: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = op();
+        samples.push(start.elapsed());
+        result
+    }
+
+    #[tokio::test]
+    async fn test_full_mempool_stays_within_latency_budgets() {
+        let mut rng = StdRng::seed_from_u64(1795);
+        let keypairs: Vec<KeyPair> = (0..SENDERS)
+            .map(|i| KeyPair::generate(format!("stress-{}", i)))
+            .collect();
+        let signatures = Arc::new(SignatureCache::default());
+        let mut pool = Mempool::new(CAPACITY).with_signature_cache(signatures.clone());
+
+        let load = random_load(&mut rng, &keypairs, CAPACITY).await;
+        let replacements = {
+            let mut replacements = Vec::with_capacity(REPLACEMENTS);
+            for tx in load.choose_multiple(&mut rng, REPLACEMENTS) {
+                let keypair = keypairs.iter().find(|k| k.address == tx.from).unwrap();
+                let bumped = tx.gas_price / U256::from(GWEI) + U256::from(1);
+                replacements.push(signed(keypair, tx.nonce, bumped.to::<u64>()).await);
+            }
+            replacements
+        };
+        // fresh nonces paying more than anything pending
+        let newcomers = {
+            let mut newcomers = Vec::with_capacity(EVICTIONS);
+            for i in 0..EVICTIONS {
+                newcomers.push(signed(&keypairs[i % SENDERS], 10_000 + i as u64, 1_000).await);
+            }
+            newcomers
+        };
+        for tx in load.iter().chain(&replacements).chain(&newcomers) {
+            assert!(signatures.is_signature_valid(tx));
+        }
+
+        let mut add = Vec::new();
+        for tx in &load {
+            timed(&mut add, || pool.add_transaction(tx)).unwrap();
+        }
+        assert_eq!(pool.len(), CAPACITY);
+
+        let mut replace = Vec::new();
+        for tx in &replacements {
+            timed(&mut replace, || pool.add_transaction(tx)).unwrap();
+        }
+        assert_eq!(pool.len(), CAPACITY);
+
+        // too cheap to displace anything once full
+        let cheap = signed(&keypairs[0], 20_000, 1).await;
+        assert!(pool.add_transaction(&cheap).is_err());
+
+        let mut evict = Vec::new();
+        for tx in &newcomers {
+            timed(&mut evict, || pool.add_transaction(tx)).unwrap();
+        }
+        assert_eq!(pool.len(), CAPACITY);
+
+        let mut get_pending = Vec::new();
+        for _ in 0..50 {
+            let pending = timed(&mut get_pending, || pool.get_all_transactions());
+            assert_eq!(pending.len(), CAPACITY);
+        }
+
+        let results = [
+            ("add", p99(add), ADD_P99),
+            ("replace", p99(replace), REPLACE_P99),
+            ("evict", p99(evict), EVICT_P99),
+            ("get_pending", p99(get_pending), GET_PENDING_P99),
+        ];
+        for (op, p99, budget) in &results {
+            println!("📊 mempool {}: p99 {:?}, budget {:?}", op, p99, budget);
+        }
+        let over: Vec<String> = results
+            .iter()
+            .filter(|(_, p99, budget)| p99 > budget)
+            .map(|(op, p99, budget)| format!("{} p99 {:?} over budget {:?}", op, p99, budget))
+            .collect();
+        assert!(
+            over.is_empty(),
+            "mempool budgets exceeded:\n{}",
+            over.join("\n")
+        );
+    }
+}
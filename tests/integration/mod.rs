@@ -1 +1,15 @@
+mod helpers;
+mod chain_info_test;
+mod conformance_test;
+mod embedding_test;
+mod idempotency_test;
 mod integration_test;
+mod mempool_stress_test;
+mod reindex_test;
+mod restart_test;
+mod rpc_access_test;
+mod subscription_test;
+mod relay_test;
+mod consistency_fuzz_test;
+mod finality_status_test;
+mod multi_chain_test;
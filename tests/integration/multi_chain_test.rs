@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod multi_chain {
+    use alloy::primitives::{Address, Bytes, U256};
+    use speed_blockchain::{
+        Blockchain, ChainSpec, GenesisValidator, KeyPair, Storage, Transaction, TransactionKind,
+        dev_accounts, dev_keypairs,
+    };
+
+    use crate::integration::helpers::unsigned_transaction;
+
+    const TO_GWEI: u64 = 1_000_000_000;
+
+    // a chain with id `chain_id` funding the dev accounts, as `--dev` does on every chain
+    fn dev_chain(dir: &tempfile::TempDir, chain_id: u64) -> Blockchain {
+        let balances: Vec<_> = dev_accounts()
+            .unwrap()
+            .iter()
+            .map(|a| (a.address, a.balance))
+            .collect();
+        let spec = ChainSpec {
+            chain_id,
+            validators: vec![GenesisValidator {
+                address: Address::repeat_byte(1),
+                stake: 200,
+            }],
+            ..ChainSpec::default()
+        }
+        .with_dev_accounts(&balances);
+        let storage = Storage::new(dir.path()).unwrap();
+        Blockchain::from_chain_spec(storage, &spec, None).unwrap()
+    }
+
+    async fn signed_transfer(keypair: &KeyPair, chain_id: u64) -> Transaction {
+        let mut tx = Transaction {
+            from: keypair.address,
+            to: Address::repeat_byte(9),
+            amount: U256::from(1_000),
+            timestamp: 1,
+            nonce: 0,
+            chain_id,
+            kind: TransactionKind::Transfer,
+            gas_limit: U256::from(30_000),
+            gas_price: U256::from(TO_GWEI),
+            ..unsigned_transaction()
+        };
+        tx.sign(keypair).await.unwrap();
+        tx
+    }
+
+    #[tokio::test]
+    async fn test_transaction_signed_for_one_chain_is_refused_by_another() {
+        let (dir_a, dir_b) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let chain_a = dev_chain(&dir_a, 1);
+        let chain_b = dev_chain(&dir_b, 2);
+        let keypair = dev_keypairs(1).unwrap().remove(0);
+
+        let tx = signed_transfer(&keypair, 1).await;
+        assert!(chain_a.execution_engine.add_transaction(&tx).await.is_ok());
+        let error = chain_b
+            .execution_engine
+            .add_transaction(&tx)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("chain 1, this is chain 2"));
+
+        // a proposer of the other chain that includes it anyway gets it dropped
+        let valid = chain_b
+            .execution_engine
+            .simulate_execute_block(&mut [tx.clone()])
+            .await
+            .unwrap();
+        assert!(valid.is_empty());
+        let valid = chain_a
+            .execution_engine
+            .simulate_execute_block(&mut [tx])
+            .await
+            .unwrap();
+        assert_eq!(valid.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_chain_id_is_part_of_the_signature() {
+        let keypair = dev_keypairs(1).unwrap().remove(0);
+        let for_a = signed_transfer(&keypair, 1).await;
+        let for_b = signed_transfer(&keypair, 2).await;
+        assert_ne!(for_a.hash, for_b.hash);
+
+        // relabelling a signed transaction for another chain breaks its signature
+        let mut relabelled = for_a.clone();
+        relabelled.chain_id = 2;
+        assert_ne!(relabelled.calculate_hash(), for_a.hash);
+        assert_ne!(relabelled.verify_signature().ok(), Some(keypair.address));
+    }
+
+    #[tokio::test]
+    async fn test_trailing_fields_cant_be_moved_into_call_input() {
+        let keypair = dev_keypairs(1).unwrap().remove(0);
+        let call = |input: Vec<u8>| TransactionKind::CallContract {
+            input: Bytes::from(input),
+        };
+
+        // an input ending in the chain id trailer of another chain
+        let mut for_b = signed_transfer(&keypair, 2).await;
+        for_b.kind = call(b"run".to_vec());
+        let mut input = b"run".to_vec();
+        input.push(0xfc);
+        input.extend_from_slice(&2u64.to_be_bytes());
+        let mut for_a = signed_transfer(&keypair, 1).await;
+        for_a.kind = call(input);
+        assert_ne!(for_a.calculate_hash(), for_b.calculate_hash());
+
+        // or in the calldata trailer
+        let mut with_data = signed_transfer(&keypair, 1).await;
+        with_data.kind = call(b"run".to_vec());
+        with_data.data = Bytes::from_static(b"abcd");
+        let mut input = b"run".to_vec();
+        input.push(0xfd);
+        input.extend_from_slice(&4u32.to_be_bytes());
+        input.extend_from_slice(b"abcd");
+        let mut in_input = signed_transfer(&keypair, 1).await;
+        in_input.kind = call(input);
+        assert_ne!(with_data.calculate_hash(), in_input.calculate_hash());
+    }
+}
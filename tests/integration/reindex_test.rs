@@ -0,0 +1,85 @@
+#[cfg(test)]
+mod reindex {
+    use alloy::primitives::{Address, B256, BloomInput, U256};
+    use speed_blockchain::reindex::{ReindexOptions, Reindexer};
+    use speed_blockchain::{
+        Block, Storage, Transaction, TransactionKind, core::BlockHeader, dev_keypairs,
+    };
+
+    use crate::integration::helpers::unsigned_transaction;
+
+    // a database written before receipts and indexes existed: blocks only
+    #[tokio::test]
+    async fn test_reindex_backfills_receipts_and_indexes() {
+        let dir = tempfile::tempdir().unwrap();
+        let sender = dev_keypairs(1).unwrap().remove(0);
+        let recipient = Address::repeat_byte(0xbb);
+
+        let mut tx = Transaction {
+            from: sender.address,
+            to: recipient,
+            amount: U256::from(1_000),
+            timestamp: 1,
+            nonce: 0,
+            kind: TransactionKind::Transfer,
+            gas_limit: U256::from(30_000),
+            gas_price: U256::from(1_000_000_000u64),
+            ..unsigned_transaction()
+        };
+        tx.sign(&sender).await.unwrap();
+
+        let block = Block::new(
+            BlockHeader {
+                index: 1,
+                parent_hash: B256::ZERO,
+                slot: 1,
+                timestamp: 1,
+                proposer: sender.address,
+                transactions_root: Block::calculate_transactions_root(&[tx.clone()]),
+                state_root: B256::ZERO,
+                slot_timing: None,
+                receipts_root: None,
+                logs_bloom: None,
+                base_fee_per_gas: None,
+                gas_used: None,
+                gas_limit: None,
+                validator_signature: None,
+            },
+            vec![tx.clone()],
+        );
+        let block_hash = block.header.hash();
+
+        let storage = Storage::new(dir.path()).unwrap();
+        storage.store_block(&block).unwrap();
+        let reindexer = Reindexer::new(storage);
+
+        let options = ReindexOptions {
+            dev: true,
+            ..ReindexOptions::default()
+        };
+        let summary = reindexer.run(&options).await.unwrap();
+        assert_eq!(summary.blocks, 1);
+        assert_eq!(summary.receipts_backfilled, 1);
+        drop(reindexer);
+
+        let storage = Storage::new(dir.path()).unwrap();
+        let receipts = storage.get_block_receipts(&block_hash).unwrap().unwrap();
+        assert!(receipts[0].success);
+
+        let location = storage.get_tx_location(&tx.hash).unwrap().unwrap();
+        assert_eq!((location.block_number, location.index), (1, 0));
+        assert_eq!(
+            storage.get_address_history(&recipient).unwrap(),
+            vec![location]
+        );
+
+        let bloom = storage.get_block_bloom(&block_hash).unwrap().unwrap();
+        assert!(bloom.contains_input(BloomInput::Raw(recipient.as_slice())));
+        assert_eq!(storage.get_reindex_progress().unwrap(), Some(1));
+
+        // a second run only replays, nothing is written twice
+        let summary = Reindexer::new(storage).run(&options).await.unwrap();
+        assert_eq!(summary.already_indexed, 1);
+        assert_eq!(summary.receipts_backfilled, 0);
+    }
+}
@@ -0,0 +1,128 @@
+#[cfg(test)]
+mod relay {
+    use alloy::primitives::{Address, B256, U256};
+    use jsonrpsee::server::{Server, ServerHandle};
+    use speed_blockchain::rpc::rpc::SpeedBlockchainRpcServer;
+    use speed_blockchain::rpc::{
+        RelayConfig, RelayTarget, TransactionRelay, relay_digest, verify_relayed,
+    };
+    use speed_blockchain::{
+        Blockchain, CHAIN_ID, KeyPair, MIN_STAKE, SLOT_DURATION, SpeedRpcImpl, Transaction,
+        TransactionKind, TransactionSource, dev_keypairs,
+    };
+
+    use crate::integration::helpers::unsigned_transaction;
+
+    async fn signed_transfer(keypair: &KeyPair, nonce: u64) -> Transaction {
+        let mut tx = Transaction {
+            from: keypair.address,
+            to: Address::repeat_byte(0xbb),
+            amount: U256::from(1_000),
+            timestamp: 1,
+            nonce,
+            kind: TransactionKind::Transfer,
+            gas_limit: U256::from(30_000),
+            gas_price: U256::from(1_000_000_000u64),
+            ..unsigned_transaction()
+        };
+        tx.sign(keypair).await.unwrap();
+        tx
+    }
+
+    // validator rpc trusting `relayers`, served until the handle is dropped
+    async fn start_validator(
+        blockchain: &Blockchain,
+        relayers: Vec<Address>,
+    ) -> (String, ServerHandle) {
+        let rpc = SpeedRpcImpl::new(blockchain.clone()).with_trusted_relayers(relayers);
+        let server = Server::builder().build("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", server.local_addr().unwrap());
+        (url, server.start(rpc.into_rpc()))
+    }
+
+    fn relay_to(urls: &[&str], keypair: KeyPair) -> TransactionRelay {
+        let config = RelayConfig {
+            validators: urls
+                .iter()
+                .map(|url| RelayTarget {
+                    url: url.to_string(),
+                    token: None,
+                })
+                .collect(),
+            timeout_ms: 500,
+            trusted_relayers: Vec::new(),
+        };
+        TransactionRelay::new(&config, keypair, CHAIN_ID).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_validator_admits_transactions_from_trusted_relayers() {
+        let dir = tempfile::tempdir().unwrap();
+        let sender = dev_keypairs(1).unwrap().remove(0);
+        let blockchain = Blockchain::new(
+            dir.path().to_str().unwrap(),
+            MIN_STAKE,
+            SLOT_DURATION,
+            vec![],
+            None,
+        )
+        .unwrap();
+        blockchain
+            .execution_engine
+            .prefund_accounts(&[(sender.address, U256::from(10).pow(U256::from(18)))])
+            .await;
+
+        let edge = KeyPair::generate("relay-edge".to_string());
+        let stranger = KeyPair::generate("relay-stranger".to_string());
+        let (url, _server) = start_validator(&blockchain, vec![edge.address]).await;
+
+        // nothing listens on port 1, the validator still gets the transaction
+        let relay = relay_to(&[&url, "http://127.0.0.1:1"], edge.clone());
+        let tx = signed_transfer(&sender, 0).await;
+        assert_eq!(relay.relay(&tx).await.unwrap(), 1);
+
+        let pending = blockchain.txpool_content().await;
+        let pooled = &pending.pending[&sender.address][&0];
+        assert_eq!(pooled.transaction.hash, tx.hash);
+        assert_eq!(
+            pooled.source,
+            TransactionSource::Relay {
+                relayer: edge.address
+            }
+        );
+
+        let stats = relay.stats();
+        assert_eq!((stats[0].accepted, stats[0].unreachable), (1, 0));
+        assert_eq!((stats[1].accepted, stats[1].unreachable), (0, 1));
+        assert!(stats[1].last_error.is_some());
+
+        // an edge node the validator doesn't list is turned away
+        let untrusted = relay_to(&[&url], stranger);
+        let tx = signed_transfer(&sender, 1).await;
+        assert_eq!(untrusted.relay(&tx).await.unwrap(), 0);
+        assert_eq!(untrusted.stats()[0].rejected, 1);
+        assert!(!blockchain.txpool_content().await.pending[&sender.address].contains_key(&1));
+    }
+
+    #[tokio::test]
+    async fn test_relay_signature_is_bound_to_relayer_and_chain() {
+        let edge = KeyPair::generate("relay-edge".to_string());
+        let other = KeyPair::generate("relay-other".to_string());
+        let tx_hash = B256::repeat_byte(0x11);
+        let signature = edge
+            .sign_hash(&relay_digest(CHAIN_ID, &tx_hash))
+            .await
+            .unwrap();
+        let trusted = [edge.address, other.address];
+
+        assert!(verify_relayed(&trusted, CHAIN_ID, &tx_hash, edge.address, &signature).is_ok());
+        // claimed by another trusted relayer
+        assert!(verify_relayed(&trusted, CHAIN_ID, &tx_hash, other.address, &signature).is_err());
+        // replayed on another chain or for another transaction
+        assert!(
+            verify_relayed(&trusted, CHAIN_ID + 1, &tx_hash, edge.address, &signature).is_err()
+        );
+        assert!(verify_relayed(&trusted, CHAIN_ID, &B256::ZERO, edge.address, &signature).is_err());
+        assert!(verify_relayed(&[], CHAIN_ID, &tx_hash, edge.address, &signature).is_err());
+    }
+}
@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod restart {
+    use alloy::primitives::B256;
+    use speed_blockchain::consensus::{ConsensusGenesis, SigningRecord, SlashingError};
+    use speed_blockchain::{Block, Blockchain, KeyPair, MIN_STAKE, Storage, core::BlockHeader};
+    use std::time::{Duration, SystemTime};
+
+    const SLOT_DURATION: u64 = 10;
+
+    fn open(dir: &std::path::Path, keypair: &KeyPair) -> Blockchain {
+        Blockchain::new(
+            dir.to_str().unwrap(),
+            MIN_STAKE,
+            SLOT_DURATION,
+            vec![(keypair.address, 200)],
+            Some(keypair.clone()),
+        )
+        .unwrap()
+    }
+
+    // a node stopped mid-epoch keeps its slot clock, head and signing history
+    #[tokio::test]
+    async fn test_restart_resumes_slot_clock_head_and_duties() {
+        let dir = tempfile::tempdir().unwrap();
+        let keypair = KeyPair::generate("restart".to_string());
+
+        // started 95 seconds ago, last block in slot 7
+        let genesis_time = SystemTime::now() - Duration::from_secs(95);
+        let head = Block::new(
+            BlockHeader {
+                index: 3,
+                parent_hash: B256::repeat_byte(2),
+                slot: 7,
+                timestamp: 1,
+                proposer: keypair.address,
+                transactions_root: B256::ZERO,
+                state_root: B256::ZERO,
+                slot_timing: None,
+                receipts_root: None,
+                logs_bloom: None,
+                base_fee_per_gas: None,
+                gas_used: None,
+                gas_limit: None,
+                validator_signature: None,
+            },
+            Vec::new(),
+        );
+        {
+            let storage = Storage::new(dir.path()).unwrap();
+            storage
+                .put_consensus_genesis(&ConsensusGenesis::new(genesis_time, [1u8; 32]))
+                .unwrap();
+            storage.store_block(&head).unwrap();
+        }
+
+        let blockchain = open(dir.path(), &keypair);
+        let info = blockchain.chain_info(1).await;
+        assert_eq!((info.head.number, info.head.slot), (3, 7));
+        assert_eq!(info.head.hash, head.header.hash());
+
+        let status = blockchain.duty_status().await.unwrap();
+        assert_eq!(status.slot, 9);
+        assert!(status.is_proposer);
+        assert!(!status.attested_this_slot);
+
+        // vote in slot 9, then restart
+        blockchain
+            .guard_attestation(keypair.address, 9, B256::repeat_byte(9))
+            .await
+            .unwrap();
+        drop(blockchain);
+
+        let blockchain = open(dir.path(), &keypair);
+        let status = blockchain.duty_status().await.unwrap();
+        assert_eq!(status.slot, 9);
+        assert!(status.attested_this_slot);
+
+        // the same block again is fine, a competing block of the slot is not
+        blockchain
+            .guard_attestation(keypair.address, 9, B256::repeat_byte(9))
+            .await
+            .unwrap();
+        assert!(
+            blockchain
+                .guard_attestation(keypair.address, 9, B256::repeat_byte(10))
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_signing_record_refuses_double_signing() {
+        let mut record = SigningRecord::default();
+        assert!(record.check_proposal(5).is_ok());
+        record.record_proposal(5);
+        assert_eq!(
+            record.check_proposal(5),
+            Err(SlashingError::DoubleProposal { slot: 5, last: 5 })
+        );
+        assert!(record.check_proposal(4).is_err());
+        assert!(record.check_proposal(6).is_ok());
+
+        record.record_attestation(5, B256::repeat_byte(1));
+        assert!(record.check_attestation(4, &B256::repeat_byte(2)).is_err());
+        assert!(record.check_attestation(6, &B256::repeat_byte(2)).is_ok());
+        assert!(record.proposed_in(5) && record.attested_in(5));
+    }
+}
@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod rpc_access {
+    use jsonrpsee::RpcModule;
+    use jsonrpsee::core::client::{ClientT, Error};
+    use jsonrpsee::http_client::{HeaderMap, HeaderValue, HttpClient, HttpClientBuilder};
+    use jsonrpsee::rpc_params;
+    use speed_blockchain::RpcAccessConfig;
+    use speed_blockchain::rpc::{RpcAccess, UNAUTHORIZED_ERROR_CODE, gated_server_builder};
+
+    fn client(url: &str, token: Option<&str>) -> HttpClient {
+        let mut headers = HeaderMap::new();
+        if let Some(token) = token {
+            let value = HeaderValue::from_str(&format!("Bearer {}", token)).unwrap();
+            headers.insert("authorization", value);
+        }
+        HttpClientBuilder::default()
+            .set_headers(headers)
+            .build(url)
+            .unwrap()
+    }
+
+    async fn call(client: &HttpClient, method: &str) -> Result<u64, Error> {
+        client.request(method, rpc_params![]).await
+    }
+
+    fn error_code(result: Result<u64, Error>) -> i32 {
+        match result {
+            Err(Error::Call(error)) => error.code(),
+            other => panic!("expected a call error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_namespaces_and_tokens_change_without_a_restart() {
+        let mut module = RpcModule::new(());
+        module
+            .register_method("eth_blockNumber", |_, _, _| 7u64)
+            .unwrap();
+        module
+            .register_method("txpool_status", |_, _, _| 3u64)
+            .unwrap();
+
+        let config = RpcAccessConfig {
+            disabled_namespaces: vec!["txpool".to_string()],
+            tokens: vec!["first".to_string()],
+        };
+        let access = RpcAccess::new(&config).unwrap();
+        let server = gated_server_builder(&access)
+            .build("127.0.0.1:0")
+            .await
+            .unwrap();
+        let url = format!("http://{}", server.local_addr().unwrap());
+        let handle = server.start(module);
+
+        // what `speed_capabilities` reports follows the toggles
+        assert_eq!(access.served_namespaces(false), ["eth", "web3", "speed"]);
+
+        let anonymous = client(&url, None);
+        let first = client(&url, Some("first"));
+        let second = client(&url, Some("second"));
+
+        assert_eq!(
+            error_code(call(&anonymous, "eth_blockNumber").await),
+            UNAUTHORIZED_ERROR_CODE
+        );
+        assert_eq!(call(&first, "eth_blockNumber").await.unwrap(), 7);
+        // method not found, as if the namespace was never registered
+        assert_eq!(error_code(call(&first, "txpool_status").await), -32601);
+
+        access.set_namespace("txpool", true).unwrap();
+        assert_eq!(call(&first, "txpool_status").await.unwrap(), 3);
+        assert_eq!(
+            access.served_namespaces(true),
+            ["eth", "web3", "speed", "txpool", "admin"]
+        );
+
+        // both tokens work while clients move over, then the old one stops working
+        access.rotate_tokens(vec!["first".to_string(), "second".to_string()]);
+        assert_eq!(call(&first, "eth_blockNumber").await.unwrap(), 7);
+        assert_eq!(call(&second, "eth_blockNumber").await.unwrap(), 7);
+        access.rotate_tokens(vec!["second".to_string()]);
+        assert_eq!(
+            error_code(call(&first, "eth_blockNumber").await),
+            UNAUTHORIZED_ERROR_CODE
+        );
+        assert_eq!(call(&second, "eth_blockNumber").await.unwrap(), 7);
+
+        // without tokens anyone may call again
+        access.rotate_tokens(Vec::new());
+        assert_eq!(call(&anonymous, "eth_blockNumber").await.unwrap(), 7);
+
+        assert!(access.set_namespace("admin", false).is_err());
+        assert!(access.set_namespace("debug", false).is_err());
+
+        handle.stop().unwrap();
+    }
+}
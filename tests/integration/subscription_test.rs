@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod subscription {
+    use alloy::primitives::{Address, B256, U256};
+    use jsonrpsee::RpcModule;
+    use serde_json::json;
+    use speed_blockchain::rpc::subscriptions::{LogEvent, NewHead, SubscriptionRpcServer};
+    use speed_blockchain::{
+        Block, Blockchain, MIN_STAKE, Receipt, ReceiptEvent, SLOT_DURATION, Storage,
+        SubscriptionRpcImpl, core::BlockHeader,
+    };
+    use std::time::Duration;
+
+    fn block(index: u64, parent_hash: B256) -> Block {
+        Block::new(
+            BlockHeader {
+                index,
+                parent_hash,
+                slot: index,
+                timestamp: index,
+                proposer: Address::repeat_byte(1),
+                transactions_root: B256::ZERO,
+                state_root: B256::ZERO,
+                slot_timing: None,
+                receipts_root: None,
+                logs_bloom: None,
+                base_fee_per_gas: None,
+                gas_used: None,
+                gas_limit: None,
+                validator_signature: None,
+            },
+            Vec::new(),
+        )
+    }
+
+    fn transfer_receipt(tx: u8, from: Address, to: Address) -> Receipt {
+        Receipt::success(B256::repeat_byte(tx), U256::from(21_000)).with_events(vec![
+            ReceiptEvent::Transfer {
+                from,
+                to,
+                amount: U256::from(5),
+            },
+        ])
+    }
+
+    // an indexer reconnecting after blocks 1..=3 were committed
+    #[tokio::test]
+    async fn test_subscriptions_replay_from_cursor() {
+        let dir = tempfile::tempdir().unwrap();
+        let alice = Address::repeat_byte(0xaa);
+        let bob = Address::repeat_byte(0xbb);
+        let carol = Address::repeat_byte(0xcc);
+
+        let mut hashes = Vec::new();
+        {
+            let storage = Storage::new(dir.path()).unwrap();
+            let mut parent_hash = B256::ZERO;
+            for index in 1..=3 {
+                let block = block(index, parent_hash);
+                parent_hash = block.header.hash();
+                storage.store_block(&block).unwrap();
+                let receipts = vec![
+                    transfer_receipt(index as u8, alice, bob),
+                    transfer_receipt(index as u8 + 10, bob, carol),
+                ];
+                storage.put_block_receipts(&parent_hash, &receipts).unwrap();
+                hashes.push(parent_hash);
+            }
+        }
+
+        let blockchain = Blockchain::new(
+            dir.path().to_str().unwrap(),
+            MIN_STAKE,
+            SLOT_DURATION,
+            vec![],
+            None,
+        )
+        .unwrap();
+        let module: RpcModule<_> = SubscriptionRpcImpl::new(blockchain).into_rpc();
+
+        let mut heads = module
+            .subscribe_unbounded("eth_subscribe", ("newHeads", json!({ "since_block": 2 })))
+            .await
+            .unwrap();
+        for (number, hash) in [(2, hashes[1]), (3, hashes[2])] {
+            let (head, _) = heads.next::<NewHead>().await.unwrap().unwrap();
+            assert_eq!((head.number, head.hash), (number, hash));
+        }
+        // nothing else until a new block is committed
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), heads.next::<NewHead>())
+                .await
+                .is_err()
+        );
+
+        // only carol's transfers, log indexes count every event of the block
+        let mut logs = module
+            .subscribe_unbounded(
+                "eth_subscribe",
+                ("logs", json!({ "since_block": 1, "address": carol })),
+            )
+            .await
+            .unwrap();
+        for number in 1..=3 {
+            let (log, _) = logs.next::<LogEvent>().await.unwrap().unwrap();
+            assert_eq!(log.block_number, number);
+            assert_eq!(log.log_index, 1);
+            assert_eq!(log.transaction_hash, B256::repeat_byte(number as u8 + 10));
+        }
+
+        // address filters don't apply to heads
+        assert!(
+            module
+                .subscribe_unbounded("eth_subscribe", ("newHeads", json!({ "address": carol })))
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use speed_blockchain::consensus::{
+    AdaptiveSlots, ConsensusConfig, ConsensusEngine, ConsensusGenesis, ValidatorSet,
+};
+use speed_blockchain::core::SlotTiming;
+use speed_blockchain::{AdaptiveSlotConfig, KeyPair, MIN_STAKE, unix_millis};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn adaptive() -> AdaptiveSlots {
+    AdaptiveSlots::new(AdaptiveSlotConfig {
+        min_slot_ms: 3_000,
+        max_slot_ms: 10_000,
+        latency_multiple: 4,
+        max_step_percent: 10,
+        window_blocks: 32,
+    })
+}
+
+fn timing(slot_duration_ms: u64, propagation_ms: u64) -> SlotTiming {
+    SlotTiming {
+        slot_start_ms: 1_000_000,
+        slot_duration_ms,
+        propagation_ms,
+    }
+}
+
+fn engine(proposer: &KeyPair, local: Option<KeyPair>, genesis_time: SystemTime) -> ConsensusEngine {
+    let mut validators = ValidatorSet::new(MIN_STAKE);
+    assert!(
+        validators
+            .add_validator(proposer.address, MIN_STAKE)
+            .is_ok()
+    );
+    let config = ConsensusConfig {
+        slot_duration: 10,
+        ..ConsensusConfig::default()
+    };
+    let genesis = ConsensusGenesis::new(genesis_time, [1u8; 32]);
+    let mut engine = ConsensusEngine::new(config, &genesis, validators, local);
+    engine.enable_adaptive_slots(adaptive());
+    engine
+}
+
+#[test]
+fn test_duration_steps_towards_reported_latency_within_bounds() {
+    let adaptive = adaptive();
+
+    // 4 x 500ms is below the minimum, one 10% step at a time
+    assert_eq!(adaptive.next_duration_ms(&timing(10_000, 500)), 9_000);
+    assert_eq!(adaptive.next_duration_ms(&timing(3_200, 500)), 3_000);
+    // slow network, back up, capped at the maximum
+    assert_eq!(adaptive.next_duration_ms(&timing(5_000, 1_600)), 5_500);
+    assert_eq!(adaptive.next_duration_ms(&timing(9_800, 9_000)), 10_000);
+    // close enough to the target to reach it
+    assert_eq!(adaptive.next_duration_ms(&timing(6_100, 1_500)), 6_000);
+    // a proposer without samples keeps the pace
+    assert_eq!(adaptive.next_duration_ms(&timing(7_000, 0)), 7_000);
+}
+
+#[test]
+fn test_slot_clock_after_head() {
+    let adaptive = adaptive();
+    let head = timing(10_000, 500);
+
+    // the head's slot keeps 10s, later slots are 9s
+    assert_eq!(adaptive.slot_start_ms(&head, 4, 5), 1_010_000);
+    assert_eq!(adaptive.slot_start_ms(&head, 4, 7), 1_028_000);
+    assert_eq!(adaptive.slot_at(&head, 4, 1_009_999), 4);
+    assert_eq!(adaptive.slot_at(&head, 4, 1_010_000), 5);
+    assert_eq!(adaptive.slot_at(&head, 4, 1_027_999), 6);
+    assert_eq!(adaptive.slot_at(&head, 4, 1_028_000), 7);
+
+    let next = adaptive.timing_for(&head, 4, 7, 300);
+    assert_eq!(next.slot_start_ms, 1_028_000);
+    assert_eq!(next.slot_duration_ms, 9_000);
+    assert_eq!(next.propagation_ms, 300);
+}
+
+#[tokio::test]
+async fn test_followers_check_the_slot_timing_of_proposed_blocks() {
+    let proposer = KeyPair::generate("adaptive-proposer".to_string());
+    // whole milliseconds, as headers carry them
+    let genesis_time = UNIX_EPOCH + Duration::from_millis(unix_millis(SystemTime::now()) - 25_000);
+    let producer = engine(&proposer, Some(proposer.clone()), genesis_time);
+    let mut follower = engine(&proposer, None, genesis_time);
+
+    // no block yet, the fixed 10s clock stands in for the head
+    assert_eq!(producer.current_slot().unwrap(), 2);
+    let block = producer.create_block(vec![], 400).await.unwrap();
+    let timing = block.header.slot_timing.unwrap();
+    assert_eq!(timing.slot_start_ms, unix_millis(genesis_time) + 20_000);
+    assert_eq!(timing.slot_duration_ms, 10_000);
+    assert_eq!(timing.propagation_ms, 400);
+    assert!(follower.validate_block(&block).await.unwrap());
+
+    let mut tampered = block.clone();
+    tampered.header.slot_timing = Some(SlotTiming {
+        slot_duration_ms: 3_000,
+        ..timing
+    });
+    assert!(!follower.validate_block(&tampered).await.unwrap());
+
+    let mut untimed = block.clone();
+    untimed.header.slot_timing = None;
+    assert!(!follower.validate_block(&untimed).await.unwrap());
+
+    // the reported 400ms pulls the next slots down by 10%
+    follower.update_best_block(&block).await.unwrap();
+    assert_eq!(follower.slot_duration(), Duration::from_millis(9_000));
+    assert_eq!(
+        follower.slot_start(3),
+        genesis_time + Duration::from_secs(30)
+    );
+}
@@ -0,0 +1,91 @@
+use alloy::primitives::{Address, U256};
+use speed_blockchain::{
+    GasConfig, ReceiptEvent, StateManager, StateTransition, StateTransitionError, TransactionKind,
+    precompile_address,
+};
+
+use super::helpers::{ONE_TOKEN, funded_state, intrinsic_fee, transaction};
+
+fn owner() -> Address {
+    Address::repeat_byte(0xaa)
+}
+
+fn spender() -> Address {
+    Address::repeat_byte(0xbb)
+}
+
+fn recipient() -> Address {
+    Address::repeat_byte(0xcc)
+}
+
+fn approve(state: &mut StateManager, allowance: u64) {
+    let kind = TransactionKind::Approve {
+        spender: spender(),
+        allowance: U256::from(allowance),
+    };
+    let mut tx = transaction(owner(), spender(), 0, 0, kind);
+    StateTransition::apply_transaction(state, &mut tx, &GasConfig::default()).unwrap();
+}
+
+#[test]
+fn test_transfer_from_spends_allowance() {
+    let mut state = funded_state(&[(owner(), 10 * ONE_TOKEN), (spender(), ONE_TOKEN)]);
+    approve(&mut state, 5 * ONE_TOKEN);
+    assert_eq!(
+        state.get_allowance(&owner(), &spender()),
+        U256::from(5 * ONE_TOKEN)
+    );
+
+    let kind = TransactionKind::TransferFrom { owner: owner() };
+    let mut tx = transaction(spender(), recipient(), 0, 2 * ONE_TOKEN, kind);
+    StateTransition::apply_transaction(&mut state, &mut tx, &GasConfig::default()).unwrap();
+
+    assert_eq!(state.get_balance(&recipient()), U256::from(2 * ONE_TOKEN));
+    assert_eq!(
+        state.get_allowance(&owner(), &spender()),
+        U256::from(3 * ONE_TOKEN)
+    );
+    // the spender only paid for gas
+    assert_eq!(
+        state.get_balance(&spender()),
+        U256::from(ONE_TOKEN) - intrinsic_fee(&tx)
+    );
+
+    assert_eq!(
+        ReceiptEvent::from_transaction(&tx),
+        vec![ReceiptEvent::Transfer {
+            from: owner(),
+            to: recipient(),
+            amount: U256::from(2 * ONE_TOKEN),
+        }]
+    );
+}
+
+#[test]
+fn test_transfer_from_above_allowance_fails() {
+    let mut state = funded_state(&[(owner(), 10 * ONE_TOKEN), (spender(), ONE_TOKEN)]);
+    approve(&mut state, ONE_TOKEN);
+    let state_root = state.get_state_root();
+
+    let kind = TransactionKind::TransferFrom { owner: owner() };
+    let mut tx = transaction(spender(), recipient(), 0, 2 * ONE_TOKEN, kind);
+    let result = StateTransition::apply_transaction(&mut state, &mut tx, &GasConfig::default());
+
+    assert!(result.is_err());
+    assert_eq!(state.get_state_root(), state_root);
+}
+
+#[test]
+fn test_transfer_from_to_a_precompile_fails() {
+    let mut state = funded_state(&[(owner(), 10 * ONE_TOKEN), (spender(), ONE_TOKEN)]);
+    approve(&mut state, ONE_TOKEN);
+    let state_root = state.get_state_root();
+
+    let kind = TransactionKind::TransferFrom { owner: owner() };
+    let mut tx = transaction(spender(), precompile_address(1), 0, ONE_TOKEN, kind);
+    assert!(matches!(
+        StateTransition::apply_transaction(&mut state, &mut tx, &GasConfig::default()),
+        Err(StateTransitionError::InvalidPayload(_))
+    ));
+    assert_eq!(state.get_state_root(), state_root);
+}
@@ -0,0 +1,204 @@
+use alloy::primitives::{Address, B256, U256};
+use speed_blockchain::{
+    BaseFeeConfig, Block, BlockEnv, ChainSpec, ExecutionEngine, GasConfig, StateTransition,
+    StateTransitionError, Transaction, core::BlockHeader, dev_keypairs, effective_gas_price,
+};
+
+use super::helpers::{ONE_TOKEN, TO_GWEI, funded_state, transfer, with_intrinsic_gas};
+
+fn gwei(amount: u64) -> U256 {
+    U256::from(amount * TO_GWEI)
+}
+
+// the default 1M block gas limit, a 500k target and a 10 gwei start
+fn config() -> GasConfig {
+    GasConfig {
+        base_fee: Some(BaseFeeConfig {
+            initial_base_fee: gwei(10),
+            ..BaseFeeConfig::default()
+        }),
+        ..GasConfig::default()
+    }
+}
+
+// alice pays bob 1000 wei offering `max_fee` per gas, `priority_fee` of it to the proposer
+fn priced_transfer(nonce: u64, max_fee: U256, priority_fee: Option<U256>) -> Transaction {
+    let tx = Transaction {
+        gas_price: max_fee,
+        max_priority_fee_per_gas: priority_fee,
+        hash: B256::repeat_byte(nonce as u8),
+        ..transfer(
+            Address::repeat_byte(0xaa),
+            Address::repeat_byte(0xbb),
+            nonce,
+            1_000,
+        )
+    };
+    with_intrinsic_gas(tx, &config())
+}
+
+#[test]
+fn test_base_fee_follows_parent_gas_used() {
+    let config = config();
+    let base_fee = Some(gwei(10));
+
+    assert_eq!(config.next_base_fee(None, U256::ZERO), Some(gwei(10)));
+    // at the target it stays, a full block raises it by an eighth, an empty one lowers it
+    assert_eq!(
+        config.next_base_fee(base_fee, U256::from(500_000)),
+        Some(gwei(10))
+    );
+    assert_eq!(
+        config.next_base_fee(base_fee, U256::from(1_000_000)),
+        Some(U256::from(11_250_000_000u64))
+    );
+    assert_eq!(
+        config.next_base_fee(base_fee, U256::ZERO),
+        Some(U256::from(8_750_000_000u64))
+    );
+    // never below the minimum gas price
+    assert_eq!(
+        config.next_base_fee(Some(gwei(1)), U256::ZERO),
+        Some(config.min_gas_price)
+    );
+    // a tiny base fee still goes up
+    let tiny = GasConfig {
+        min_gas_price: U256::ZERO,
+        ..config.clone()
+    };
+    assert_eq!(
+        tiny.next_base_fee(Some(U256::from(1)), U256::from(500_001)),
+        Some(U256::from(2))
+    );
+
+    assert_eq!(
+        GasConfig::default().next_base_fee(base_fee, U256::ZERO),
+        None
+    );
+}
+
+#[test]
+fn test_only_the_tip_goes_to_the_proposer() {
+    let proposer = Address::repeat_byte(0xcc);
+    let mut state = funded_state(&[(Address::repeat_byte(0xaa), ONE_TOKEN)]);
+    let env = BlockEnv {
+        base_fee: Some(gwei(10)),
+        ..BlockEnv::default()
+    };
+
+    // pays the base fee plus its 2 gwei priority fee, not its whole max fee
+    let mut tx = priced_transfer(0, gwei(15), Some(gwei(2)));
+    assert_eq!(effective_gas_price(&tx, env.base_fee), gwei(12));
+    let (gas_used, _) =
+        StateTransition::apply_transaction_in_block(&mut state, &mut tx, &config(), &env).unwrap();
+    let tip = StateTransition::pay_fee(&mut state, &tx, gas_used, env.base_fee, proposer);
+    assert_eq!(tip, gas_used * gwei(2));
+    assert_eq!(state.get_balance(&proposer), tip);
+    assert_eq!(
+        state.get_balance(&Address::repeat_byte(0xaa)),
+        U256::from(ONE_TOKEN) - tx.amount - gas_used * gwei(12)
+    );
+
+    // without a priority fee everything above the base fee is the tip
+    let legacy = priced_transfer(1, gwei(11), None);
+    assert_eq!(effective_gas_price(&legacy, env.base_fee), gwei(11));
+    assert_eq!(effective_gas_price(&legacy, None), gwei(11));
+
+    let mut cheap = priced_transfer(1, gwei(5), None);
+    let err = StateTransition::apply_transaction_in_block(&mut state, &mut cheap, &config(), &env)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        StateTransitionError::MaxFeeBelowBaseFee { max_fee, base_fee }
+            if max_fee == gwei(5) && base_fee == gwei(10)
+    ));
+    let mut greedy = priced_transfer(1, gwei(15), Some(gwei(20)));
+    assert!(matches!(
+        StateTransition::apply_transaction_in_block(&mut state, &mut greedy, &config(), &env),
+        Err(StateTransitionError::PriorityFeeAboveMaxFee { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_blocks_burn_the_base_fee() {
+    let keypair = dev_keypairs(1).unwrap().remove(0);
+    let alice = keypair.address;
+    let proposer = Address::repeat_byte(0xcc);
+    let engine = ExecutionEngine::new().with_gas_config(config());
+    engine
+        .prefund_accounts(&[(alice, U256::from(ONE_TOKEN))])
+        .await;
+    assert_eq!(engine.next_base_fee(), Some(gwei(10)));
+
+    let mut header = BlockHeader::new(1, 1, proposer, B256::ZERO, B256::ZERO, B256::ZERO);
+    header.base_fee_per_gas = engine.next_base_fee();
+    let mut transactions = vec![
+        priced_transfer(0, gwei(15), Some(gwei(2))),
+        priced_transfer(1, gwei(11), None),
+        // priced out, included as failed and charged nothing
+        priced_transfer(2, gwei(5), None),
+    ];
+    for tx in &mut transactions {
+        tx.from = alice;
+        tx.sign(&keypair).await.unwrap();
+    }
+    let mut block = Block::new(header, transactions);
+    let result = engine.execute_block_commit(&mut block).await.unwrap();
+    assert!(result.receipts[0].success && result.receipts[1].success);
+    assert!(!result.receipts[2].success);
+
+    let (tipped, legacy) = (result.receipts[0].gas_used, result.receipts[1].gas_used);
+    // the priced out one never executed, it used no gas
+    assert_eq!(result.total_gas_used, tipped + legacy);
+    assert_eq!(result.total_fees, tipped * gwei(2) + legacy * gwei(1));
+    assert_eq!(result.burned_fees, (tipped + legacy) * gwei(10));
+    assert_eq!(engine.get_balance(&proposer).await, result.total_fees);
+
+    // the burned part left the supply
+    let supply = engine.get_balance(&alice).await
+        + engine.get_balance(&Address::repeat_byte(0xbb)).await
+        + engine.get_balance(&proposer).await;
+    assert_eq!(supply, U256::from(ONE_TOKEN) - result.burned_fees);
+
+    // the block was under its target, the next base fee comes down
+    assert_eq!(
+        engine.next_base_fee(),
+        config().next_base_fee(Some(gwei(10)), result.total_gas_used)
+    );
+    assert!(engine.next_base_fee().unwrap() < gwei(10));
+}
+
+#[test]
+fn test_chains_without_a_base_fee_keep_their_hashes() {
+    let header = BlockHeader::new(1, 1, Address::ZERO, B256::ZERO, B256::ZERO, B256::ZERO);
+    let json = serde_json::to_string(&header).unwrap();
+    assert!(!json.contains("base_fee_per_gas") && !json.contains("gas_used"));
+
+    let mut with_fee = header.clone();
+    with_fee.base_fee_per_gas = Some(gwei(10));
+    with_fee.gas_used = Some(U256::ZERO);
+    assert_ne!(with_fee.hash(), header.hash());
+
+    // the priority fee is signed, only when there is one
+    let legacy = priced_transfer(0, gwei(10), None);
+    let tipped = priced_transfer(0, gwei(10), Some(gwei(1)));
+    assert_ne!(legacy.calculate_hash(), tipped.calculate_hash());
+    let json = serde_json::to_string(&legacy).unwrap();
+    assert!(!json.contains("max_priority_fee_per_gas"));
+    // the max fee is the gas price
+    let parsed: Transaction =
+        serde_json::from_str(&json.replace("\"gas_price\"", "\"max_fee_per_gas\"")).unwrap();
+    assert_eq!(parsed.gas_price, gwei(10));
+
+    let spec = ChainSpec::default();
+    let with_base_fee = ChainSpec::from_toml(
+        "[gas.base_fee]\ninitial_base_fee = \"5000000000\"\nmax_change_denominator = 16",
+    )
+    .unwrap();
+    let base_fee = with_base_fee.gas.base_fee.clone().unwrap();
+    assert_eq!(base_fee.initial_base_fee, gwei(5));
+    assert_eq!(base_fee.elasticity_multiplier, 2);
+    assert_eq!(base_fee.max_change_denominator, 16);
+    assert_eq!(spec.gas.base_fee, None);
+    assert_ne!(spec.chain_spec_bytes(), with_base_fee.chain_spec_bytes());
+}
@@ -0,0 +1,166 @@
+use alloy::primitives::{Address, U256};
+use speed_blockchain::{
+    BlockEnv, GasCalculator, GasConfig, ReceiptEvent, StateTransition, StateTransitionError,
+    Transaction, TransactionKind, TransactionOutcome, TransferOutput, cli::parse_outputs,
+    precompile_address,
+};
+
+use super::helpers::{ONE_TOKEN, funded_state, transaction};
+
+fn payer() -> Address {
+    Address::repeat_byte(0xaa)
+}
+
+fn output(byte: u8, amount: u64) -> TransferOutput {
+    TransferOutput {
+        to: Address::repeat_byte(byte),
+        amount: U256::from(amount),
+    }
+}
+
+fn batch(outputs: Vec<TransferOutput>) -> Transaction {
+    let kind = TransactionKind::BatchTransfer { outputs };
+    Transaction {
+        gas_limit: U256::from(100_000),
+        ..transaction(payer(), Address::ZERO, 0, 0, kind)
+    }
+}
+
+#[test]
+fn test_batch_transfer_pays_every_output() {
+    let mut state = funded_state(&[(payer(), 10 * ONE_TOKEN)]);
+    let mut tx = batch(vec![
+        output(0xb1, ONE_TOKEN),
+        output(0xb2, 2 * ONE_TOKEN),
+        output(0xb1, ONE_TOKEN),
+    ]);
+    let config = GasConfig::default();
+    let gas_used = StateTransition::apply_transaction(&mut state, &mut tx, &config).unwrap();
+
+    assert_eq!(
+        state.get_balance(&Address::repeat_byte(0xb1)),
+        U256::from(2 * ONE_TOKEN)
+    );
+    assert_eq!(
+        state.get_balance(&Address::repeat_byte(0xb2)),
+        U256::from(2 * ONE_TOKEN)
+    );
+    assert_eq!(
+        state.get_balance(&payer()),
+        U256::from(6 * ONE_TOKEN) - gas_used * tx.gas_price
+    );
+    assert_eq!(state.get_account(&payer()).nonce, 1);
+
+    // each output costs extra gas, but far less than a transaction of its own
+    assert_eq!(
+        gas_used,
+        GasCalculator::calculate_instrinsic_gas(&tx, &config)
+    );
+    assert!(gas_used < config.intrinsic_gas * U256::from(3));
+
+    let events = ReceiptEvent::from_transaction(&tx);
+    assert_eq!(events.len(), 3);
+    assert_eq!(
+        events[1],
+        ReceiptEvent::Transfer {
+            from: payer(),
+            to: Address::repeat_byte(0xb2),
+            amount: U256::from(2 * ONE_TOKEN),
+        }
+    );
+}
+
+#[test]
+fn test_batch_transfer_is_all_or_nothing() {
+    // enough for the first output, not for both
+    let mut state = funded_state(&[(payer(), ONE_TOKEN + ONE_TOKEN / 2)]);
+    let mut tx = batch(vec![output(0xb1, ONE_TOKEN), output(0xb2, ONE_TOKEN)]);
+
+    assert!(
+        StateTransition::apply_transaction(&mut state, &mut tx, &GasConfig::default()).is_err()
+    );
+    assert_eq!(state.get_balance(&Address::repeat_byte(0xb1)), U256::ZERO);
+    assert_eq!(state.get_account(&payer()).nonce, 0);
+
+    // a recipient that would overflow rejects the whole batch
+    let mut state = funded_state(&[(payer(), 10 * ONE_TOKEN)]);
+    state.fund_account(&Address::repeat_byte(0xb2), U256::MAX);
+    let mut tx = batch(vec![output(0xb1, ONE_TOKEN), output(0xb2, 1)]);
+
+    assert!(
+        StateTransition::apply_transaction(&mut state, &mut tx, &GasConfig::default()).is_err()
+    );
+    assert_eq!(state.get_balance(&Address::repeat_byte(0xb1)), U256::ZERO);
+    assert_eq!(state.get_balance(&payer()), U256::from(10 * ONE_TOKEN));
+}
+
+#[test]
+fn test_batch_transfer_rejects_invalid_outputs() {
+    let config = GasConfig::default();
+
+    let mut empty = batch(Vec::new());
+    let mut state = funded_state(&[(payer(), ONE_TOKEN)]);
+    assert!(StateTransition::apply_transaction(&mut state, &mut empty, &config).is_err());
+
+    let mut to_self = batch(vec![TransferOutput {
+        to: payer(),
+        amount: U256::from(1),
+    }]);
+    assert!(StateTransition::apply_transaction(&mut state, &mut to_self, &config).is_err());
+    assert_eq!(state.get_balance(&payer()), U256::from(ONE_TOKEN));
+
+    // precompile addresses take calls, not value
+    let mut to_precompile = batch(vec![
+        output(0xb1, 1),
+        TransferOutput {
+            to: precompile_address(1),
+            amount: U256::from(1),
+        },
+    ]);
+    assert!(matches!(
+        StateTransition::apply_transaction(&mut state, &mut to_precompile, &config),
+        Err(StateTransitionError::InvalidPayload(_))
+    ));
+    assert_eq!(state.get_balance(&precompile_address(1)), U256::ZERO);
+    assert_eq!(state.get_balance(&Address::repeat_byte(0xb1)), U256::ZERO);
+}
+
+#[test]
+fn test_overflowing_fee_is_invalid() {
+    let mut state = funded_state(&[(payer(), ONE_TOKEN)]);
+    let mut transfer = batch(vec![output(0xb1, 1)]);
+    transfer.kind = TransactionKind::Transfer;
+    transfer.to = Address::repeat_byte(0xb1);
+    transfer.amount = U256::from(1);
+    // 2^15 * 2^241 wraps to zero
+    transfer.gas_limit = U256::from(32_768);
+    transfer.gas_price = U256::from(1) << 241;
+    assert_eq!(transfer.max_transaction_cost(), U256::MAX);
+
+    let root = state.get_state_root();
+    let outcome = StateTransition::execute_transaction(
+        &mut state,
+        &mut transfer,
+        &GasConfig::default(),
+        &BlockEnv::default(),
+    );
+    assert!(matches!(
+        outcome,
+        TransactionOutcome::Invalid(StateTransitionError::FeeOverflow)
+    ));
+    assert_eq!(state.get_state_root(), root);
+}
+
+#[test]
+fn test_parse_batch_outputs() {
+    let raw = format!(
+        "{}:100, {}:250",
+        Address::repeat_byte(0xb1),
+        Address::repeat_byte(0xb2)
+    );
+    assert_eq!(
+        parse_outputs(&raw).unwrap(),
+        vec![output(0xb1, 100), output(0xb2, 250)]
+    );
+    assert!(parse_outputs("0xb1").is_err());
+}
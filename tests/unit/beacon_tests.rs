@@ -0,0 +1,123 @@
+use alloy::primitives::B256;
+use sha2::{Digest, Sha256};
+use speed_blockchain::consensus::{
+    BeaconRound, BeaconScheme, ProposerSelection, RandomnessBeacon, RandomnessBeaconConfig,
+    ValidatorSet,
+};
+use speed_blockchain::{KeyPair, MIN_STAKE, SLOTS_PER_EPOCH};
+use std::time::{Duration, UNIX_EPOCH};
+
+const DST_G1: &[u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
+const DST_G2: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+fn config(scheme: BeaconScheme, public_key: &[u8]) -> RandomnessBeaconConfig {
+    RandomnessBeaconConfig {
+        chain_hash: "test".to_string(),
+        public_key: hex::encode(public_key),
+        scheme,
+        genesis_time: 1_700_000_000,
+        period_seconds: 3,
+        ..RandomnessBeaconConfig::default()
+    }
+}
+
+// a round signed the way drand signs it
+fn round(number: u64, signature: &[u8], previous_signature: Option<&[u8]>) -> BeaconRound {
+    BeaconRound {
+        round: number,
+        randomness: hex::encode(Sha256::digest(signature)),
+        signature: hex::encode(signature),
+        previous_signature: previous_signature.map(hex::encode),
+    }
+}
+
+#[test]
+fn test_chained_rounds_are_verified_against_the_previous_signature() {
+    let secret = blst::min_pk::SecretKey::key_gen(&[7u8; 32], &[]).unwrap();
+    let beacon =
+        RandomnessBeacon::new(config(BeaconScheme::Chained, &secret.sk_to_pk().compress()))
+            .unwrap();
+
+    let previous = secret.sign(b"round 9", DST_G2, &[]).compress();
+    let message = Sha256::digest([&previous[..], &10u64.to_be_bytes()].concat());
+    let signature = secret.sign(&message, DST_G2, &[]).compress();
+
+    let valid = round(10, &signature, Some(&previous));
+    assert_eq!(
+        beacon.verify(&valid).unwrap(),
+        B256::from_slice(&Sha256::digest(signature))
+    );
+
+    let replayed = BeaconRound {
+        round: 11,
+        ..valid.clone()
+    };
+    assert!(beacon.verify(&replayed).is_err());
+    let unchained = BeaconRound {
+        previous_signature: None,
+        ..valid.clone()
+    };
+    assert!(beacon.verify(&unchained).is_err());
+    let forged = BeaconRound {
+        randomness: hex::encode([1u8; 32]),
+        ..valid
+    };
+    assert!(beacon.verify(&forged).is_err());
+}
+
+#[test]
+fn test_g1_rounds_and_round_timing() {
+    let secret = blst::min_sig::SecretKey::key_gen(&[9u8; 32], &[]).unwrap();
+    let public_key = secret.sk_to_pk().compress();
+    let beacon = RandomnessBeacon::new(config(BeaconScheme::UnchainedG1, &public_key)).unwrap();
+
+    let message = Sha256::digest(42u64.to_be_bytes());
+    let signature = secret.sign(&message, DST_G1, &[]).compress();
+    assert!(beacon.verify(&round(42, &signature, None)).is_ok());
+
+    // a g2 key is refused for the g1 scheme
+    assert!(RandomnessBeacon::new(config(BeaconScheme::Unchained, &public_key)).is_err());
+
+    let genesis = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    assert_eq!(beacon.round_at(genesis - Duration::from_secs(1)), 0);
+    assert_eq!(beacon.round_at(genesis), 1);
+    assert_eq!(beacon.round_at(genesis + Duration::from_secs(7)), 3);
+}
+
+#[test]
+fn test_beacon_randomness_reschedules_only_its_epoch() {
+    let selection = || {
+        let mut validators = ValidatorSet::new(MIN_STAKE);
+        for i in 0..8 {
+            let address = KeyPair::generate(format!("beacon-{}", i)).address;
+            assert!(validators.add_validator(address, MIN_STAKE).is_ok());
+        }
+        ProposerSelection::new(validators, [1u8; 32])
+    };
+    let schedule = |selection: &ProposerSelection, epoch: u64| {
+        (epoch * SLOTS_PER_EPOCH..(epoch + 1) * SLOTS_PER_EPOCH)
+            .map(|slot| selection.selector_proposer(slot).unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    let base = selection();
+    let mut mixed = selection();
+    mixed.set_epoch_randomness(1, B256::repeat_byte(0xab));
+    assert!(mixed.has_epoch_randomness(1));
+
+    assert_eq!(schedule(&base, 0), schedule(&mixed, 0));
+    assert_ne!(schedule(&base, 1), schedule(&mixed, 1));
+    assert_eq!(schedule(&base, 2), schedule(&mixed, 2));
+
+    // every node mixing the same round gets the same schedule
+    let mut other = selection();
+    other.set_epoch_randomness(1, B256::repeat_byte(0xab));
+    assert_eq!(schedule(&mixed, 1), schedule(&other, 1));
+
+    // only the latest few epochs are kept
+    for epoch in 2..=5 {
+        mixed.set_epoch_randomness(epoch, B256::repeat_byte(epoch as u8));
+    }
+    assert!(!mixed.has_epoch_randomness(1));
+    assert!(mixed.has_epoch_randomness(2));
+}
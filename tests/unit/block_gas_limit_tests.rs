@@ -0,0 +1,101 @@
+use alloy::primitives::{Address, B256, U256};
+use speed_blockchain::core::{Block, BlockHeader};
+use speed_blockchain::{BlockProcessResult, GasConfig, KeyPair, Transaction};
+
+use super::helpers::{dry_run_block, funded_chain, import_signed, signed_transfer};
+
+#[test]
+fn test_headers_without_gas_fields_hash_as_before() {
+    let header = BlockHeader::new(
+        1,
+        1,
+        Address::repeat_byte(9),
+        B256::ZERO,
+        B256::ZERO,
+        B256::ZERO,
+    );
+    let json = serde_json::to_value(&header).unwrap();
+    assert!(json.get("gas_limit").is_none() && json.get("gas_used").is_none());
+
+    let mut limited = header.clone();
+    limited.gas_used = Some(U256::ZERO);
+    limited.gas_limit = Some(GasConfig::default().block_gas_limit);
+    assert_ne!(limited.hash(), header.hash());
+    let mut other_limit = limited.clone();
+    other_limit.gas_limit = Some(U256::from(2_000_000));
+    assert_ne!(other_limit.hash(), limited.hash());
+
+    // the same 32 bytes in another optional field is another header
+    let mut used_only = header.clone();
+    used_only.gas_used = Some(U256::from(21_000));
+    let mut limit_only = header.clone();
+    limit_only.gas_limit = Some(U256::from(21_000));
+    assert_ne!(used_only.hash(), limit_only.hash());
+}
+
+#[tokio::test]
+async fn test_blocks_are_checked_against_the_block_gas_limit() {
+    let dir = tempfile::tempdir().unwrap();
+    let proposer = KeyPair::generate("gas-limit-proposer".to_string());
+    let sender = KeyPair::generate("gas-limit-sender".to_string());
+    let blockchain = funded_chain(
+        dir.path().to_str().unwrap(),
+        proposer.address,
+        &[(sender.address, U256::from(10).pow(U256::from(18)))],
+    )
+    .await;
+    let gas_limit = blockchain.execution_engine.gas_config().block_gas_limit;
+
+    // a block as its proposer would build it, over `transactions`
+    let build = |transactions: Vec<Transaction>| async {
+        let (block, result) = dry_run_block(&blockchain, proposer.address, transactions).await;
+        (block, result.total_gas_used)
+    };
+    let import = |block: Block| import_signed(&blockchain, &proposer, block);
+
+    // every transfer fits on its own, together they use more than the block may
+    let mut transactions = Vec::new();
+    for nonce in 0..46 {
+        transactions.push(signed_transfer(&sender, nonce).await);
+    }
+    let (full, gas_used) = build(transactions.clone()).await;
+    assert!(gas_used > gas_limit);
+    // leaving the gas limit out of the header doesn't lift it
+    let mut unlimited = full.clone();
+    unlimited.header.gas_limit = None;
+    unlimited.header.gas_used = unlimited.header.base_fee_per_gas.map(|_| gas_used);
+    assert!(matches!(
+        import(unlimited).await,
+        BlockProcessResult::Rejected(_, _)
+    ));
+    assert!(matches!(
+        import(full).await,
+        BlockProcessResult::Rejected(_, _)
+    ));
+
+    let (block, gas_used) = build(transactions[..2].to_vec()).await;
+    // built for another limit, its gas used left out or misreported
+    let mut forged = block.clone();
+    forged.header.gas_limit = Some(gas_limit * U256::from(2));
+    assert!(matches!(
+        import(forged).await,
+        BlockProcessResult::Rejected(_, _)
+    ));
+    let mut forged = block.clone();
+    forged.header.gas_used = None;
+    assert!(matches!(
+        import(forged).await,
+        BlockProcessResult::Rejected(_, _)
+    ));
+    let mut forged = block.clone();
+    forged.header.gas_used = Some(gas_used - U256::from(1));
+    assert!(matches!(
+        import(forged).await,
+        BlockProcessResult::Rejected(_, _)
+    ));
+
+    assert!(matches!(
+        import(block).await,
+        BlockProcessResult::Accepted(_)
+    ));
+}
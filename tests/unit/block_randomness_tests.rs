@@ -0,0 +1,137 @@
+use alloy::primitives::{Address, B256};
+use speed_blockchain::consensus::{BlockRandomness, ProposerSelection, ValidatorSet};
+use speed_blockchain::core::{Block, BlockHeader};
+use speed_blockchain::{
+    BlockProcessResult, Blockchain, KeyPair, MIN_STAKE, SLOT_DURATION, SLOTS_PER_EPOCH,
+};
+
+use super::helpers::{genesis_slots_ago, slot_time};
+
+fn selection() -> ProposerSelection {
+    let mut validators = ValidatorSet::new(MIN_STAKE);
+    for byte in 1..=8 {
+        assert!(
+            validators
+                .add_validator(Address::repeat_byte(byte), MIN_STAKE)
+                .is_ok()
+        );
+    }
+    ProposerSelection::new(validators, [1u8; 32])
+}
+
+#[test]
+fn test_accumulator_depends_on_every_block_in_order() {
+    let mut forward = BlockRandomness::new();
+    forward.mix(B256::repeat_byte(1));
+    forward.mix(B256::repeat_byte(2));
+    let mut reversed = BlockRandomness::new();
+    reversed.mix(B256::repeat_byte(2));
+    reversed.mix(B256::repeat_byte(1));
+    assert_ne!(forward.accumulator, reversed.accumulator);
+    assert_ne!(forward.accumulator, B256::ZERO);
+
+    let seeded = forward.seed_epoch(3);
+    assert_eq!(forward.epochs[&3], seeded);
+    for epoch in 4..10 {
+        forward.seed_epoch(epoch);
+    }
+    assert_eq!(
+        forward.epochs.keys().copied().collect::<Vec<_>>(),
+        vec![6, 7, 8, 9]
+    );
+}
+
+#[test]
+fn test_block_randomness_reschedules_only_its_epoch() {
+    let base = selection();
+    let mut seeded = selection();
+    seeded.set_block_randomness(2, B256::repeat_byte(0xab));
+
+    assert_eq!(
+        base.epoch_schedule(1).unwrap(),
+        seeded.epoch_schedule(1).unwrap()
+    );
+    assert_ne!(
+        base.epoch_schedule(2).unwrap(),
+        seeded.epoch_schedule(2).unwrap()
+    );
+    assert_eq!(
+        base.epoch_schedule(3).unwrap(),
+        seeded.epoch_schedule(3).unwrap()
+    );
+
+    // beacon randomness is mixed in on top
+    let mut beacon_only = selection();
+    beacon_only.set_epoch_randomness(2, B256::repeat_byte(0xcd));
+    seeded.set_epoch_randomness(2, B256::repeat_byte(0xcd));
+    assert_ne!(
+        beacon_only.epoch_schedule(2).unwrap(),
+        seeded.epoch_schedule(2).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_epoch_close_seeds_the_next_epoch_and_survives_restarts() {
+    let dir = tempfile::tempdir().unwrap();
+    let proposer = KeyPair::generate("block-randomness-proposer".to_string());
+    genesis_slots_ago(dir.path().to_str().unwrap(), SLOTS_PER_EPOCH + 1);
+    let open = || {
+        Blockchain::new(
+            dir.path().to_str().unwrap(),
+            MIN_STAKE,
+            SLOT_DURATION,
+            vec![(proposer.address, MIN_STAKE)],
+            None,
+        )
+        .unwrap()
+    };
+    let blockchain = open();
+    let state_root = blockchain
+        .execution_engine
+        .state_manager
+        .lock()
+        .await
+        .get_state_root();
+
+    let mut parent_hash = B256::ZERO;
+    for (index, slot) in [(1, 3), (2, SLOTS_PER_EPOCH + 1)] {
+        let mut header = BlockHeader::new(
+            index,
+            slot,
+            proposer.address,
+            parent_hash,
+            B256::ZERO,
+            state_root,
+        );
+        header.timestamp = slot_time(&blockchain, slot).await;
+        header.transactions_root = Block::calculate_transactions_root(&[]);
+        header.sign(&proposer).await.unwrap();
+        parent_hash = header.hash();
+        let signature = header.validator_signature.unwrap();
+        let result = blockchain
+            .process_received_block(Block::new(header, Vec::new()), proposer.address, signature)
+            .await
+            .unwrap();
+        assert!(matches!(result, BlockProcessResult::Accepted(_)));
+    }
+
+    // epoch 0 closed on block 2, seeding epoch 2 with both block hashes
+    let randomness = blockchain
+        .consensus_engine
+        .lock()
+        .await
+        .block_randomness()
+        .clone();
+    assert_eq!(
+        randomness.epochs.keys().copied().collect::<Vec<_>>(),
+        vec![2]
+    );
+    assert_eq!(randomness.epochs[&2], randomness.accumulator);
+
+    drop(blockchain);
+    let restarted = open();
+    assert_eq!(
+        restarted.consensus_engine.lock().await.block_randomness(),
+        &randomness
+    );
+}
@@ -0,0 +1,67 @@
+use alloy::primitives::{Address, B256, U256};
+use speed_blockchain::core::{Block, BlockHeader};
+use speed_blockchain::{BlockRewardConfig, ExecutionEngine};
+
+use super::helpers::ONE_TOKEN;
+
+fn empty_block(index: u64, proposer: Address) -> Block {
+    let header = BlockHeader::new(index, index, proposer, B256::ZERO, B256::ZERO, B256::ZERO);
+    Block::new(header, vec![])
+}
+
+#[test]
+fn test_reward_halves_every_interval() {
+    let rewards = BlockRewardConfig {
+        reward: U256::from(800),
+        halving_interval: 10,
+    };
+    assert_eq!(rewards.reward_at(1), U256::from(800));
+    assert_eq!(rewards.reward_at(10), U256::from(800));
+    assert_eq!(rewards.reward_at(11), U256::from(400));
+    assert_eq!(rewards.reward_at(31), U256::from(100));
+    assert_eq!(rewards.reward_at(10_000), U256::ZERO);
+
+    let constant = BlockRewardConfig {
+        halving_interval: 0,
+        ..rewards
+    };
+    assert_eq!(constant.reward_at(10_000), U256::from(800));
+    assert_eq!(BlockRewardConfig::default().reward_at(1), U256::ZERO);
+}
+
+#[tokio::test]
+async fn test_proposer_is_credited_in_the_state_root() {
+    let proposer = Address::repeat_byte(0xaa);
+    let engine = ExecutionEngine::new();
+    let unrewarded = engine
+        .execute_block_dry_run(&mut empty_block(1, proposer))
+        .await
+        .unwrap();
+    assert_eq!(unrewarded.block_reward, U256::ZERO);
+    assert!(unrewarded.state_diff.is_empty());
+
+    engine
+        .set_block_rewards(BlockRewardConfig {
+            reward: U256::from(ONE_TOKEN),
+            halving_interval: 0,
+        })
+        .await;
+    let dry_run = engine
+        .execute_block_dry_run(&mut empty_block(1, proposer))
+        .await
+        .unwrap();
+    assert_ne!(dry_run.state_root, unrewarded.state_root);
+    assert_eq!(engine.get_balance(&proposer).await, U256::ZERO);
+
+    let result = engine
+        .execute_block_commit(&mut empty_block(1, proposer))
+        .await
+        .unwrap();
+    assert_eq!(result.block_reward, U256::from(ONE_TOKEN));
+    assert_eq!(result.state_root, dry_run.state_root);
+    assert_eq!(engine.get_balance(&proposer).await, U256::from(ONE_TOKEN));
+    // restarts rebuild the proposer's balance from the stored diff
+    assert_eq!(result.state_diff.len(), 1);
+    assert_eq!(result.state_diff[0].address, proposer);
+    assert_eq!(result.state_diff[0].balance_after, U256::from(ONE_TOKEN));
+}
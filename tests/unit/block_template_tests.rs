@@ -0,0 +1,133 @@
+use alloy::primitives::{Address, B256, U256};
+use speed_blockchain::{
+    BaseFeeConfig, Block, BlockTemplateReport, ExecutionEngine, GasConfig, KeyPair, dev_keypairs,
+};
+
+use super::helpers::{ONE_TOKEN, signed_transfer_at};
+
+async fn funded_engine(keypairs: &[KeyPair]) -> ExecutionEngine {
+    let engine = ExecutionEngine::new();
+    let balances: Vec<(Address, U256)> = keypairs
+        .iter()
+        .map(|k| (k.address, U256::from(ONE_TOKEN)))
+        .collect();
+    engine.prefund_accounts(&balances).await;
+    engine
+}
+
+#[tokio::test]
+async fn test_template_takes_arrivals_and_replaces_cheaper_transactions() {
+    let keypairs = dev_keypairs(3).unwrap();
+    let engine = funded_engine(&keypairs).await;
+    for nonce in 0..2 {
+        let tx = signed_transfer_at(&keypairs[0], nonce, 2).await;
+        engine.add_transaction(&tx).await.unwrap();
+    }
+
+    let report = engine.build_block_template(7, 3).await;
+    assert_eq!(report.transactions, 2);
+    assert_eq!(engine.block_template_slot().await, Some(7));
+
+    // fills the last place, a nonce gap is left out
+    let joined = signed_transfer_at(&keypairs[1], 0, 1).await;
+    engine.add_transaction(&joined).await.unwrap();
+    let gap = signed_transfer_at(&keypairs[1], 5, 9).await;
+    engine.add_transaction(&gap).await.unwrap();
+    // full, pushes out the cheapest transaction nothing depends on
+    let richer = signed_transfer_at(&keypairs[2], 0, 5).await;
+    engine.add_transaction(&richer).await.unwrap();
+
+    let template = engine.take_block_template(7).await.unwrap();
+    assert_eq!(
+        template.report(),
+        BlockTemplateReport {
+            slot: 7,
+            transactions: 3,
+            added: 2,
+            replaced: 1,
+        }
+    );
+    let hashes: Vec<B256> = template.transactions.iter().map(|tx| tx.hash).collect();
+    assert!(!hashes.contains(&joined.hash) && !hashes.contains(&gap.hash));
+    assert_eq!(hashes[2], richer.hash);
+    assert_eq!(
+        template.transactions_root,
+        Block::calculate_transactions_root(&template.transactions)
+    );
+    // taken by the proposal
+    assert_eq!(engine.block_template_slot().await, None);
+
+    // what it selected is what simulation accepts
+    let mut selected = template.transactions.clone();
+    assert_eq!(
+        engine
+            .simulate_execute_block(&mut selected)
+            .await
+            .unwrap()
+            .len(),
+        3
+    );
+}
+
+#[tokio::test]
+async fn test_stale_templates_are_not_proposed() {
+    let keypairs = dev_keypairs(2).unwrap();
+    let engine = funded_engine(&keypairs).await;
+    let tx = signed_transfer_at(&keypairs[0], 0, 2).await;
+    engine.add_transaction(&tx).await.unwrap();
+
+    // for another slot
+    engine.build_block_template(4, 16).await;
+    assert!(engine.take_block_template(5).await.is_none());
+
+    // a transaction in it was dropped from the mempool
+    engine.build_block_template(5, 16).await;
+    assert!(engine.drop_transaction(&tx.hash).await);
+    assert!(engine.take_block_template(5).await.is_none());
+
+    // a block changed the state since
+    engine.add_transaction(&tx).await.unwrap();
+    engine.build_block_template(6, 16).await;
+    engine
+        .credit_balances(&[(keypairs[1].address, U256::from(1))])
+        .await;
+    assert!(engine.take_block_template(6).await.is_none());
+}
+
+#[tokio::test]
+async fn test_template_refuses_transactions_priced_under_the_next_base_fee() {
+    let keypairs = dev_keypairs(2).unwrap();
+    let config = GasConfig {
+        base_fee: Some(BaseFeeConfig {
+            initial_base_fee: U256::from(10_000_000_000u64),
+            ..BaseFeeConfig::default()
+        }),
+        ..GasConfig::default()
+    };
+    let engine = ExecutionEngine::new().with_gas_config(config);
+    let balances: Vec<(Address, U256)> = keypairs
+        .iter()
+        .map(|k| (k.address, U256::from(ONE_TOKEN)))
+        .collect();
+    engine.prefund_accounts(&balances).await;
+    engine.build_block_template(3, 16).await;
+
+    // the mempool keeps it for a later base fee, the template can't include it now
+    let underpriced = signed_transfer_at(&keypairs[0], 0, 1).await;
+    engine.add_transaction(&underpriced).await.unwrap();
+    let priced = signed_transfer_at(&keypairs[1], 0, 20).await;
+    engine.add_transaction(&priced).await.unwrap();
+
+    let template = engine.take_block_template(3).await.unwrap();
+    let hashes: Vec<B256> = template.transactions.iter().map(|tx| tx.hash).collect();
+    assert_eq!(hashes, vec![priced.hash]);
+    let mut selected = template.transactions.clone();
+    assert_eq!(
+        engine
+            .simulate_execute_block(&mut selected)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+}
@@ -0,0 +1,123 @@
+use alloy::primitives::{Address, B256};
+use speed_blockchain::consensus::{
+    ConsensusConfig, ConsensusEngine, ConsensusGenesis, ValidatorSet,
+};
+use speed_blockchain::core::{Block, BlockHeader};
+use speed_blockchain::{BlockProcessResult, Blockchain, KeyPair, MIN_STAKE, SLOT_DURATION};
+use std::time::SystemTime;
+
+use super::helpers::{genesis_slots_ago, slot_time};
+
+fn block_at(index: u64, timestamp: u64) -> Block {
+    let mut header = BlockHeader::new(
+        index,
+        index,
+        Address::repeat_byte(1),
+        B256::ZERO,
+        Block::calculate_transactions_root(&[]),
+        B256::ZERO,
+    );
+    header.timestamp = timestamp;
+    Block::new(header, Vec::new())
+}
+
+// signed empty block on the head with the given timestamp
+async fn import_at(
+    blockchain: &Blockchain,
+    proposer: &KeyPair,
+    index: u64,
+    slot: u64,
+    parent_hash: B256,
+    timestamp: u64,
+) -> (B256, BlockProcessResult) {
+    let state_root = blockchain
+        .execution_engine
+        .state_manager
+        .lock()
+        .await
+        .get_state_root();
+    let mut header = BlockHeader::new(
+        index,
+        slot,
+        proposer.address,
+        parent_hash,
+        Block::calculate_transactions_root(&[]),
+        state_root,
+    );
+    header.timestamp = timestamp;
+    header.sign(proposer).await.unwrap();
+    let hash = header.hash();
+    let signature = header.validator_signature.unwrap();
+    let result = blockchain
+        .process_received_block(Block::new(header, Vec::new()), proposer.address, signature)
+        .await
+        .unwrap();
+    (hash, result)
+}
+
+#[tokio::test]
+async fn test_median_time_follows_the_latest_blocks() {
+    let config = ConsensusConfig {
+        median_time_blocks: 3,
+        ..ConsensusConfig::default()
+    };
+    let genesis = ConsensusGenesis::new(SystemTime::now(), [1u8; 32]);
+    let mut engine = ConsensusEngine::new(config, &genesis, ValidatorSet::new(MIN_STAKE), None);
+    assert_eq!(engine.median_time(), None);
+    assert_eq!(engine.min_timestamp(), 0);
+
+    // a proposer's clock jumped ahead at block #2
+    for (index, timestamp) in [(1, 100), (2, 500), (3, 110), (4, 120)] {
+        engine
+            .update_best_block(&block_at(index, timestamp))
+            .await
+            .unwrap();
+    }
+    assert_eq!(engine.head().timestamps, vec![500, 110, 120]);
+    assert_eq!(engine.median_time(), Some(120));
+    assert_eq!(engine.min_timestamp(), 120);
+
+    engine.update_best_block(&block_at(5, 130)).await.unwrap();
+    assert_eq!(engine.median_time(), Some(120));
+    assert_eq!(engine.min_timestamp(), 130);
+}
+
+#[tokio::test]
+async fn test_blocks_before_their_parent_or_too_far_ahead_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let proposer = KeyPair::generate("timestamp-proposer".to_string());
+    genesis_slots_ago(dir.path().to_str().unwrap(), 10);
+    let blockchain = Blockchain::new(
+        dir.path().to_str().unwrap(),
+        MIN_STAKE,
+        SLOT_DURATION,
+        vec![(proposer.address, 200)],
+        None,
+    )
+    .unwrap();
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    let start = slot_time(&blockchain, 1).await;
+    let (first, result) = import_at(&blockchain, &proposer, 1, 1, B256::ZERO, start).await;
+    assert!(matches!(result, BlockProcessResult::Accepted(_)));
+
+    let (_, result) = import_at(&blockchain, &proposer, 2, 2, first, start - 1).await;
+    assert!(matches!(result, BlockProcessResult::Rejected(_, _)));
+    let (_, result) = import_at(&blockchain, &proposer, 2, 4, first, now + 120).await;
+    assert!(matches!(result, BlockProcessResult::Rejected(_, _)));
+
+    // after the parent but well before its own slot
+    let (_, result) = import_at(&blockchain, &proposer, 2, 5, first, start + 1).await;
+    assert!(matches!(result, BlockProcessResult::Rejected(_, _)));
+    // a slot that hasn't started, however it is stamped
+    let timestamp = slot_time(&blockchain, 20).await;
+    let (_, result) = import_at(&blockchain, &proposer, 2, 20, first, timestamp).await;
+    assert!(matches!(result, BlockProcessResult::Rejected(_, _)));
+
+    // the same second as the parent is fine
+    let (_, result) = import_at(&blockchain, &proposer, 2, 3, first, start).await;
+    assert!(matches!(result, BlockProcessResult::Accepted(_)));
+}
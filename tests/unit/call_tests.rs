@@ -0,0 +1,73 @@
+use alloy::primitives::{Address, U256};
+use speed_blockchain::{BlockProcessResult, ExecutionEngine, KeyPair, TransactionKind};
+
+use super::helpers::{
+    ONE_TOKEN, TO_GWEI, dry_run_block, funded_chain, hashed, import_signed, signed_transfer,
+    transaction, transfer,
+};
+
+fn bob() -> Address {
+    Address::repeat_byte(0xbb)
+}
+
+#[tokio::test]
+async fn test_call_returns_gas_used_without_committing() {
+    let sender = Address::repeat_byte(0xaa);
+    let engine = ExecutionEngine::new();
+    engine
+        .prefund_accounts(&[(sender, U256::from(ONE_TOKEN))])
+        .await;
+    let root = engine.state_manager.lock().await.get_state_root();
+
+    // unsigned, and the nonce is the sender's next one whatever it says
+    let mut tx = hashed(transfer(sender, bob(), 0, 1_000));
+    tx.nonce = 7;
+    let result = engine.call(&tx, None).await.unwrap();
+    assert!(result.success);
+    assert!(result.error.is_none());
+    assert!(result.gas_used > U256::ZERO);
+    assert_eq!(engine.state_manager.lock().await.get_state_root(), root);
+    assert_eq!(engine.get_nonce(&sender).await, 0);
+
+    // one that fails while executing still reports the gas it would be charged
+    let owner = Address::repeat_byte(0xcc);
+    let kind = TransactionKind::TransferFrom { owner };
+    let tx = hashed(transaction(sender, bob(), 0, 1_000, kind));
+    let result = engine.call(&tx, None).await.unwrap();
+    assert!(!result.success);
+    assert!(result.error.is_some());
+    assert!(result.gas_used > U256::ZERO);
+
+    // one that could never be included is an error
+    let tx = hashed(transfer(sender, bob(), 0, ONE_TOKEN));
+    assert!(engine.call(&tx, None).await.is_err());
+    assert_eq!(engine.state_manager.lock().await.get_state_root(), root);
+}
+
+#[tokio::test]
+async fn test_call_at_an_earlier_block() {
+    let dir = tempfile::tempdir().unwrap();
+    let proposer = KeyPair::generate("call-proposer".to_string());
+    let sender = KeyPair::generate("call-sender".to_string());
+    let blockchain = funded_chain(
+        dir.path().to_str().unwrap(),
+        proposer.address,
+        &[(sender.address, U256::from(ONE_TOKEN))],
+    )
+    .await;
+
+    let tx = signed_transfer(&sender, 0).await;
+    let (block, _) = dry_run_block(&blockchain, proposer.address, vec![tx]).await;
+    assert!(matches!(
+        import_signed(&blockchain, &proposer, block).await,
+        BlockProcessResult::Accepted(_)
+    ));
+
+    // the whole balance before block 1, more than is left after it
+    let everything = ONE_TOKEN - 30_000 * TO_GWEI;
+    let tx = hashed(transfer(sender.address, bob(), 0, everything));
+    assert!(blockchain.call(&tx, Some(0)).await.unwrap().success);
+    assert!(blockchain.call(&tx, Some(1)).await.is_err());
+    assert!(blockchain.call(&tx, None).await.is_err());
+    assert!(blockchain.call(&tx, Some(2)).await.is_err());
+}
@@ -0,0 +1,29 @@
+use speed_blockchain::rpc::{NODE_FEATURES, node_features};
+use speed_blockchain::{ChainSpec, ContractRuntimeKind, NodeConfig};
+
+#[test]
+fn test_features_follow_the_node_config_and_chain_spec() {
+    let config = NodeConfig::default();
+    let features = node_features(&config, None);
+    assert!(
+        NODE_FEATURES
+            .iter()
+            .all(|f| features.contains(&f.to_string()))
+    );
+    for off in ["dev-accounts", "relay-mode", "wasm-contracts"] {
+        assert!(!features.contains(&off.to_string()), "{} is off", off);
+    }
+
+    let config = NodeConfig {
+        dev: true,
+        ..NodeConfig::default()
+    };
+    let spec = ChainSpec {
+        contract_runtime: ContractRuntimeKind::Wasm,
+        ..ChainSpec::default()
+    };
+    let features = node_features(&config, Some(&spec));
+    assert!(features.contains(&"dev-accounts".to_string()));
+    assert!(features.contains(&"wasm-contracts".to_string()));
+    assert!(features.is_sorted());
+}
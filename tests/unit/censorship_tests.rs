@@ -0,0 +1,107 @@
+use alloy::primitives::{Address, B256, U256};
+use speed_blockchain::core::{BlockHeader, CensorshipTracker};
+use speed_blockchain::{Block, Transaction};
+
+use super::helpers::transfer;
+
+const INTRINSIC_GAS: u64 = 21_000;
+
+fn proposer(i: u8) -> Address {
+    Address::repeat_byte(0x10 + i)
+}
+
+// 1 wei from `from` at `gas_price` wei per gas, hashed by its sender byte
+fn priced(from: u8, gas_price: u64) -> Transaction {
+    Transaction {
+        gas_limit: U256::from(INTRINSIC_GAS),
+        gas_price: U256::from(gas_price),
+        hash: B256::repeat_byte(from),
+        ..transfer(Address::repeat_byte(from), Address::repeat_byte(0xee), 0, 1)
+    }
+}
+
+fn block(slot: u64, proposer: Address, transactions: Vec<Transaction>) -> Block {
+    Block::new(
+        BlockHeader::new(slot, slot, proposer, B256::ZERO, B256::ZERO, B256::ZERO),
+        transactions,
+    )
+}
+
+fn executable(transactions: &[&Transaction]) -> Vec<(Transaction, U256)> {
+    transactions
+        .iter()
+        .map(|tx| ((*tx).clone(), U256::from(INTRINSIC_GAS)))
+        .collect()
+}
+
+#[test]
+fn test_transaction_skipped_by_several_proposers_is_reported() {
+    let mut tracker = CensorshipTracker::new();
+    let skipped = priced(0x01, 50);
+    let cheap = priced(0x02, 1);
+    let included = priced(0x03, 10);
+    let room = U256::from(1_000_000);
+
+    // first sighting, nothing is counted yet
+    tracker.observe_block(
+        &block(1, proposer(0), vec![]),
+        &executable(&[&skipped, &cheap]),
+        room,
+    );
+    for i in 1..=3 {
+        tracker.observe_block(
+            &block(1 + i as u64, proposer(i), vec![included.clone()]),
+            &executable(&[&skipped, &cheap]),
+            room,
+        );
+    }
+
+    let report = tracker.report(3);
+    assert_eq!(report.blocks_observed, 4);
+    assert_eq!(report.tracked, 2);
+    // the cheap one never paid what the blocks included
+    assert_eq!(report.transactions.len(), 1);
+    let excluded = &report.transactions[0];
+    assert_eq!(excluded.hash, skipped.hash);
+    assert_eq!(excluded.first_seen_slot, 1);
+    assert_eq!(excluded.blocks_missed, 3);
+    assert_eq!(excluded.proposers.len(), 3);
+
+    assert_eq!(report.proposers.len(), 3);
+    assert!(
+        report
+            .proposers
+            .iter()
+            .all(|p| p.blocks == 1 && p.transactions == 1 && p.exclusions == 1)
+    );
+    assert!(tracker.report(4).transactions.is_empty());
+}
+
+#[test]
+fn test_full_blocks_and_included_transactions_are_not_exclusions() {
+    let mut tracker = CensorshipTracker::new();
+    let pending = priced(0x01, 50);
+
+    tracker.observe_block(
+        &block(1, proposer(0), vec![]),
+        &executable(&[&pending]),
+        U256::ZERO,
+    );
+    // no room left in the block
+    tracker.observe_block(
+        &block(2, proposer(1), vec![]),
+        &executable(&[&pending]),
+        U256::ZERO,
+    );
+    assert_eq!(tracker.report(1).transactions.len(), 0);
+
+    // included, so no longer executable and forgotten
+    tracker.observe_block(
+        &block(3, proposer(2), vec![pending.clone()]),
+        &executable(&[]),
+        U256::from(1_000_000),
+    );
+    let report = tracker.report(1);
+    assert_eq!(report.tracked, 0);
+    assert!(report.proposers.is_empty());
+}
@@ -0,0 +1,89 @@
+use alloy::primitives::B256;
+use speed_blockchain::core::{Block, BlockHeader, HeadTag};
+use speed_blockchain::{BlockProcessResult, Blockchain, KeyPair, MIN_STAKE, SLOT_DURATION};
+
+#[test]
+fn test_head_tags_are_read_like_ethereum_block_tags() {
+    let tag: HeadTag = serde_json::from_str("\"safe\"").unwrap();
+    assert_eq!(tag, HeadTag::Safe);
+    assert_eq!(
+        serde_json::to_string(&HeadTag::Finalized).unwrap(),
+        "\"finalized\""
+    );
+    assert!(serde_json::from_str::<HeadTag>("\"pending\"").is_err());
+}
+
+#[tokio::test]
+async fn test_safe_head_follows_a_stake_majority() {
+    let dir = tempfile::tempdir().unwrap();
+    let keys: Vec<KeyPair> = (0..4)
+        .map(|i| KeyPair::generate(format!("chain-heads-{}", i)))
+        .collect();
+    let blockchain = Blockchain::new(
+        dir.path().to_str().unwrap(),
+        MIN_STAKE,
+        SLOT_DURATION,
+        keys.iter().map(|key| (key.address, 100)).collect(),
+        None,
+    )
+    .unwrap();
+    let state_root = blockchain
+        .execution_engine
+        .state_manager
+        .lock()
+        .await
+        .get_state_root();
+
+    let proposer = blockchain
+        .consensus_engine
+        .lock()
+        .await
+        .scheduled_proposer(1)
+        .unwrap();
+    let key = keys.iter().find(|key| key.address == proposer).unwrap();
+    let mut header = BlockHeader::new(
+        1,
+        1,
+        proposer,
+        B256::ZERO,
+        Block::calculate_transactions_root(&[]),
+        state_root,
+    );
+    header.sign(key).await.unwrap();
+    let signature = header.validator_signature.unwrap();
+    let hash = header.hash();
+    assert!(matches!(
+        blockchain
+            .process_received_block(Block::new(header, Vec::new()), proposer, signature)
+            .await
+            .unwrap(),
+        BlockProcessResult::Accepted(_)
+    ));
+
+    let heads = blockchain.heads().await;
+    assert_eq!((heads.latest.number, heads.latest.hash), (1, hash));
+    assert_eq!(heads.safe.number, 0);
+    assert_eq!(heads.finalized.number, 0);
+
+    // proposer and one vote are half of the stake, not more
+    let voters: Vec<_> = keys
+        .iter()
+        .filter(|key| key.address != proposer)
+        .map(|key| key.address)
+        .collect();
+    blockchain
+        .record_attestation(&hash, voters[0])
+        .await
+        .unwrap();
+    assert_eq!(blockchain.head(HeadTag::Safe).await.number, 0);
+
+    blockchain
+        .record_attestation(&hash, voters[1])
+        .await
+        .unwrap();
+    let safe = blockchain.head(HeadTag::Safe).await;
+    assert_eq!((safe.number, safe.hash, safe.slot), (1, hash, 1));
+    // 300 of 400 is short of the finality quorum
+    assert_eq!(blockchain.head(HeadTag::Finalized).await.number, 0);
+    assert_eq!(blockchain.chain_info(1).await.safe, safe);
+}
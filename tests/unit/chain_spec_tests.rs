@@ -0,0 +1,223 @@
+use alloy::primitives::{Address, B256, U256};
+use speed_blockchain::core::{Block, BlockHeader};
+use speed_blockchain::{BlockProcessResult, Blockchain, ChainSpec, KeyPair, Storage};
+use std::fs;
+
+const TOML_SPEC: &str = r#"
+chain_id = 7
+genesis_time = 1700000000
+slot_duration = 6
+min_stake = 50
+
+[gas]
+min_gas_price = "2000000000"
+
+[[validators]]
+address = "0x1111111111111111111111111111111111111111"
+stake = 100
+
+[alloc]
+"0x2222222222222222222222222222222222222222" = "1000000000000000000000"
+"#;
+
+const JSON_SPEC: &str = r#"{
+    "chain_id": 7,
+    "genesis_time": 1700000000,
+    "slot_duration": 6,
+    "min_stake": 50,
+    "gas": { "min_gas_price": "0x77359400" },
+    "validators": [
+        { "address": "0x1111111111111111111111111111111111111111", "stake": 100 }
+    ],
+    "alloc": {
+        "0x2222222222222222222222222222222222222222": "0x3635c9adc5dea00000"
+    }
+}"#;
+
+fn spec_for(validator: Address) -> ChainSpec {
+    let mut spec = ChainSpec::from_toml(TOML_SPEC).unwrap();
+    spec.validators[0].address = validator;
+    spec
+}
+
+#[test]
+fn test_toml_and_json_specs_describe_the_same_chain() {
+    let dir = tempfile::tempdir().unwrap();
+    let toml_path = dir.path().join("spec.toml");
+    let json_path = dir.path().join("spec.json");
+    fs::write(&toml_path, TOML_SPEC).unwrap();
+    fs::write(&json_path, JSON_SPEC).unwrap();
+
+    let spec = ChainSpec::load(&toml_path).unwrap();
+    assert_eq!(spec, ChainSpec::load(&json_path).unwrap());
+    assert_eq!(spec.chain_id, 7);
+    assert_eq!(spec.gas.min_gas_price, U256::from(2_000_000_000u64));
+    // fields left out keep their defaults
+    assert_eq!(spec.gas.intrinsic_gas, U256::from(21_000));
+    assert_eq!(
+        spec.alloc[&Address::repeat_byte(0x22)],
+        U256::from(10).pow(U256::from(21))
+    );
+
+    let genesis = spec.genesis_block();
+    assert_eq!((genesis.header.index, genesis.header.slot), (0, 0));
+    assert_eq!(genesis.header.timestamp, 1_700_000_000);
+    assert_eq!(
+        genesis.header.state_root,
+        spec.genesis_state().get_state_root()
+    );
+    // any change to the genesis allocation is a different genesis block
+    let mut other = spec.clone();
+    other
+        .alloc
+        .insert(Address::repeat_byte(0x33), U256::from(1));
+    assert_ne!(other.genesis_block().header.hash(), genesis.header.hash());
+}
+
+#[test]
+fn test_dev_accounts_are_part_of_the_genesis_state() {
+    let spec = spec_for(Address::repeat_byte(0x11));
+    let funded = Address::repeat_byte(0x22);
+    let dev = Address::repeat_byte(0x44);
+    let with_dev = spec
+        .clone()
+        .with_dev_accounts(&[(funded, U256::from(1)), (dev, U256::from(5))]);
+
+    // the spec's own balance is kept, the dev account is added
+    assert_eq!(with_dev.alloc[&funded], spec.alloc[&funded]);
+    assert_eq!(with_dev.genesis_state().get_balance(&dev), U256::from(5));
+    assert_eq!(
+        with_dev.genesis_block().header.state_root,
+        with_dev.genesis_state().get_state_root()
+    );
+    assert_ne!(
+        with_dev.genesis_block().header.hash(),
+        spec.genesis_block().header.hash()
+    );
+
+    // a database started without dev accounts refuses them
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().to_str().unwrap();
+    drop(Blockchain::from_chain_spec(Storage::new(path).unwrap(), &spec, None).unwrap());
+    assert!(Blockchain::from_chain_spec(Storage::new(path).unwrap(), &with_dev, None).is_err());
+}
+
+#[test]
+fn test_invalid_specs_are_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("spec.toml");
+
+    let below_min_stake = TOML_SPEC.replace("stake = 100", "stake = 10");
+    fs::write(&path, below_min_stake).unwrap();
+    let error = ChainSpec::load(&path).unwrap_err().to_string();
+    assert!(error.contains("below the minimum of 50"), "{}", error);
+
+    let no_genesis_time = TOML_SPEC.replace("genesis_time = 1700000000\n", "");
+    fs::write(&path, no_genesis_time).unwrap();
+    let error = ChainSpec::load(&path).unwrap_err().to_string();
+    assert!(error.contains("no genesis_time"), "{}", error);
+}
+
+#[test]
+fn test_nodes_with_the_same_spec_start_from_the_same_genesis() {
+    let spec = spec_for(Address::repeat_byte(0x11));
+    let open = || {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::new(dir.path().to_str().unwrap()).unwrap();
+        (
+            Blockchain::from_chain_spec(storage, &spec, None).unwrap(),
+            dir,
+        )
+    };
+    let (first, _first_dir) = open();
+    let (second, _second_dir) = open();
+
+    assert_eq!(first.chain_spec_hash(), second.chain_spec_hash());
+    let genesis_hash = spec.genesis_block().header.hash();
+    for blockchain in [&first, &second] {
+        let consensus = blockchain.consensus_engine.try_lock().unwrap();
+        assert_eq!(consensus.head_block(), (0, genesis_hash));
+    }
+    assert_eq!(
+        first.execution_engine.gas_config().min_gas_price,
+        U256::from(2_000_000_000u64)
+    );
+
+    // the spec is part of the chain spec hash
+    let dir = tempfile::tempdir().unwrap();
+    let mut other = spec.clone();
+    other.chain_id = 8;
+    let storage = Storage::new(dir.path().to_str().unwrap()).unwrap();
+    let blockchain = Blockchain::from_chain_spec(storage, &other, None).unwrap();
+    assert_ne!(blockchain.chain_spec_hash(), first.chain_spec_hash());
+}
+
+#[tokio::test]
+async fn test_block_one_builds_on_the_genesis_block() {
+    let dir = tempfile::tempdir().unwrap();
+    let proposer = KeyPair::generate("chain-spec-proposer".to_string());
+    let spec = spec_for(proposer.address);
+    let storage = Storage::new(dir.path().to_str().unwrap()).unwrap();
+    let blockchain = Blockchain::from_chain_spec(storage, &spec, None).unwrap();
+
+    let (balance, state_root) = {
+        let state = blockchain.execution_engine.state_manager.lock().await;
+        (
+            state.get_account(&Address::repeat_byte(0x22)).balance,
+            state.get_state_root(),
+        )
+    };
+    assert_eq!(balance, spec.alloc[&Address::repeat_byte(0x22)]);
+
+    let block = |parent_hash: B256| {
+        let proposer = proposer.clone();
+        async move {
+            let mut header = BlockHeader::new(
+                1,
+                1,
+                proposer.address,
+                parent_hash,
+                Block::calculate_transactions_root(&[]),
+                state_root,
+            );
+            header.timestamp = spec.genesis_time + spec.slot_duration;
+            header.sign(&proposer).await.unwrap();
+            let signature = header.validator_signature.unwrap();
+            (Block::new(header, Vec::new()), signature)
+        }
+    };
+
+    // a chain without a genesis block would start from a zero parent
+    let (orphan, signature) = block(B256::ZERO).await;
+    assert!(matches!(
+        blockchain
+            .process_received_block(orphan, proposer.address, signature)
+            .await
+            .unwrap(),
+        BlockProcessResult::Rejected(..)
+    ));
+
+    let (first, signature) = block(spec.genesis_block().header.hash()).await;
+    assert!(matches!(
+        blockchain
+            .process_received_block(first, proposer.address, signature)
+            .await
+            .unwrap(),
+        BlockProcessResult::Accepted(_)
+    ));
+    drop(blockchain);
+
+    // the database stays bound to the spec it was created from
+    let mut other = spec.clone();
+    other.genesis_time += 1;
+    let storage = Storage::new(dir.path().to_str().unwrap()).unwrap();
+    let error = Blockchain::from_chain_spec(storage, &other, None)
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(error.contains("different chain spec"), "{}", error);
+
+    let storage = Storage::new(dir.path().to_str().unwrap()).unwrap();
+    let blockchain = Blockchain::from_chain_spec(storage, &spec, None).unwrap();
+    assert_eq!(blockchain.get_last_index().await.unwrap(), 1);
+}
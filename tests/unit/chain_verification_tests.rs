@@ -0,0 +1,179 @@
+use alloy::primitives::B256;
+use speed_blockchain::core::BlockHeader;
+use speed_blockchain::reindex::{ChainVerificationError, ChainVerifier, VerifyDepth};
+use speed_blockchain::storage::MemoryStore;
+use speed_blockchain::{Block, ExecutionEngine, KeyPair, Storage, dev_accounts};
+
+// signed empty blocks on top of the dev accounts, so every state root is non-zero
+// `tamper` gets each block before it is signed
+async fn chain(blocks: u64, tamper: impl Fn(&mut Block)) -> (Storage, ExecutionEngine) {
+    let storage = Storage::with_backend(MemoryStore::new());
+    let engine = ExecutionEngine::new();
+    let balances: Vec<_> = dev_accounts()
+        .unwrap()
+        .iter()
+        .map(|a| (a.address, a.balance))
+        .collect();
+    engine.prefund_accounts(&balances).await;
+
+    extend(&storage, &engine, blocks, tamper).await;
+    (storage, engine)
+}
+
+// append blocks up to `last_index`, executed on `engine`
+async fn extend(
+    storage: &Storage,
+    engine: &ExecutionEngine,
+    last_index: u64,
+    tamper: impl Fn(&mut Block),
+) {
+    let proposer = KeyPair::generate("verify-proposer".to_string());
+    let first_index = storage
+        .get_last_index()
+        .unwrap()
+        .map_or(1, |index| index + 1);
+    let mut parent_hash = match first_index {
+        1 => B256::ZERO,
+        index => storage
+            .get_block_hash_from_index(&(index - 1))
+            .unwrap()
+            .unwrap(),
+    };
+
+    for index in first_index..=last_index {
+        let header = BlockHeader::new(
+            index,
+            index,
+            proposer.address,
+            parent_hash,
+            Block::calculate_transactions_root(&[]),
+            B256::ZERO,
+        );
+        let mut block = Block::new(header, vec![]);
+        block.header.state_root = engine
+            .execute_block_commit(&mut block)
+            .await
+            .unwrap()
+            .state_root;
+        tamper(&mut block);
+        block.header.sign(&proposer).await.unwrap();
+        storage.store_block(&block).unwrap();
+        parent_hash = block.header.hash();
+    }
+}
+
+fn verification_error(error: anyhow::Error) -> ChainVerificationError {
+    error.downcast::<ChainVerificationError>().unwrap()
+}
+
+#[test]
+fn test_depth_parses_from_flags_and_config() {
+    assert_eq!("none".parse::<VerifyDepth>(), Ok(VerifyDepth::None));
+    assert_eq!("full".parse::<VerifyDepth>(), Ok(VerifyDepth::Full));
+    assert_eq!("16".parse::<VerifyDepth>(), Ok(VerifyDepth::Blocks(16)));
+    assert!("all".parse::<VerifyDepth>().is_err());
+
+    let depth: VerifyDepth = serde_json::from_str("64").unwrap();
+    assert_eq!(depth, VerifyDepth::Blocks(64));
+    let depth: VerifyDepth = serde_json::from_str("\"full\"").unwrap();
+    assert_eq!(depth, VerifyDepth::Full);
+    assert_eq!(
+        serde_json::to_string(&VerifyDepth::None).unwrap(),
+        "\"none\""
+    );
+
+    assert_eq!(VerifyDepth::Blocks(3).first_block(10), Some(8));
+    assert_eq!(VerifyDepth::Blocks(30).first_block(10), Some(1));
+    assert_eq!(VerifyDepth::Blocks(0).first_block(10), None);
+}
+
+#[tokio::test]
+async fn test_intact_chain_verifies_at_every_depth() {
+    let (storage, _) = chain(5, |_| {}).await;
+    let verifier = ChainVerifier::new(&storage).with_dev_accounts(true);
+
+    let summary = verifier.verify(VerifyDepth::Full).await.unwrap();
+    assert_eq!((summary.first_block, summary.last_block), (1, 5));
+    assert_eq!(summary.verified, 5);
+
+    // the state is still rebuilt from the first block
+    let summary = verifier.verify(VerifyDepth::Blocks(2)).await.unwrap();
+    assert_eq!(summary.verified, 2);
+    assert_eq!(summary.replayed, 5);
+
+    let summary = verifier.verify(VerifyDepth::None).await.unwrap();
+    assert_eq!(summary.verified, 0);
+
+    // replayed without the dev accounts every state root differs
+    let error = ChainVerifier::new(&storage)
+        .verify(VerifyDepth::Blocks(1))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        verification_error(error),
+        ChainVerificationError::StateRoot { index: 5, .. }
+    ));
+}
+
+#[tokio::test]
+async fn test_tampered_block_is_found_within_the_depth_only() {
+    let (storage, _) = chain(5, |block| {
+        if block.header.index == 3 {
+            block.header.state_root = B256::repeat_byte(0xbd);
+        }
+    })
+    .await;
+    let verifier = ChainVerifier::new(&storage).with_dev_accounts(true);
+
+    let error = verifier.verify(VerifyDepth::Blocks(3)).await.unwrap_err();
+    assert!(matches!(
+        verification_error(error),
+        ChainVerificationError::StateRoot { index: 3, .. }
+    ));
+    assert_eq!(
+        verifier
+            .verify(VerifyDepth::Blocks(2))
+            .await
+            .unwrap()
+            .verified,
+        2
+    );
+
+    // signed by someone other than its proposer
+    let (storage, _) = chain(2, |block| {
+        if block.header.index == 2 {
+            block.header.proposer = KeyPair::generate("someone-else".to_string()).address;
+        }
+    })
+    .await;
+    let error = ChainVerifier::new(&storage)
+        .with_dev_accounts(true)
+        .verify(VerifyDepth::Full)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        verification_error(error),
+        ChainVerificationError::BadSignature { index: 2 }
+    );
+}
+
+#[tokio::test]
+async fn test_replay_starts_from_the_latest_snapshot_before_the_depth() {
+    let (storage, engine) = chain(3, |_| {}).await;
+    let dir = tempfile::tempdir().unwrap();
+    let state = serde_json::to_vec(&*engine.state_manager.lock().await).unwrap();
+    std::fs::write(dir.path().join("state-3.json"), state).unwrap();
+
+    // more blocks on top of the snapshot
+    extend(&storage, &engine, 6, |_| {}).await;
+
+    // no dev accounts needed, they are in the snapshot
+    let summary = ChainVerifier::new(&storage)
+        .with_snapshot_dir(dir.path())
+        .verify(VerifyDepth::Blocks(2))
+        .await
+        .unwrap();
+    assert_eq!(summary.snapshot_block, Some(3));
+    assert_eq!(summary.replayed, 3);
+    assert_eq!(summary.verified, 2);
+}
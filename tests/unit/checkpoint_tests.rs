@@ -0,0 +1,220 @@
+use alloy::primitives::{Address, B256, U256};
+use speed_blockchain::consensus::ConsensusGenesis;
+use speed_blockchain::core::{Block, BlockHeader, Checkpoint, CheckpointError, HeadInfo};
+use speed_blockchain::reindex::{ChainVerifier, VerifyDepth};
+use speed_blockchain::{
+    BlockProcessResult, Blockchain, KeyPair, MIN_STAKE, SLOT_DURATION, Storage,
+};
+use std::path::Path;
+use std::time::SystemTime;
+
+use super::helpers::{genesis_slots_ago, slot_time};
+
+// empty block on top of the head, with the head's state root
+async fn import(
+    blockchain: &Blockchain,
+    proposer: &KeyPair,
+    slot: u64,
+    parent_hash: B256,
+) -> BlockProcessResult {
+    let state_root = blockchain
+        .execution_engine
+        .state_manager
+        .lock()
+        .await
+        .get_state_root();
+    let mut header = BlockHeader::new(
+        blockchain.get_last_index().await.unwrap() + 1,
+        slot,
+        proposer.address,
+        parent_hash,
+        Block::calculate_transactions_root(&[]),
+        state_root,
+    );
+    header.timestamp = slot_time(blockchain, slot).await;
+    header.sign(proposer).await.unwrap();
+    let signature = header.validator_signature.unwrap();
+    blockchain
+        .process_received_block(Block::new(header, Vec::new()), proposer.address, signature)
+        .await
+        .unwrap()
+}
+
+async fn head_hash(blockchain: &Blockchain) -> B256 {
+    let index = blockchain.get_last_index().await.unwrap();
+    blockchain
+        .get_block_hash_by_index(&index)
+        .await
+        .unwrap()
+        .unwrap_or(B256::ZERO)
+}
+
+// a funded account and blocks #1 and #2 on a node following the chain, exported as a checkpoint
+async fn source_checkpoint(dir: &Path, proposer: &KeyPair) -> (Checkpoint, B256) {
+    let path = dir.join("source");
+    // the other chains share its genesis and go up to slot 3
+    genesis_slots_ago(path.to_str().unwrap(), 3);
+    let blockchain = Blockchain::new(
+        path.to_str().unwrap(),
+        MIN_STAKE,
+        SLOT_DURATION,
+        vec![(proposer.address, 200)],
+        None,
+    )
+    .unwrap();
+    blockchain
+        .execution_engine
+        .prefund_accounts(&[(Address::repeat_byte(0xaa), U256::from(1_000))])
+        .await;
+    for slot in 1..=2 {
+        let parent_hash = head_hash(&blockchain).await;
+        assert!(matches!(
+            import(&blockchain, proposer, slot, parent_hash).await,
+            BlockProcessResult::Accepted(_)
+        ));
+    }
+
+    let info = blockchain
+        .write_checkpoint(&dir.join("snapshots"))
+        .await
+        .unwrap();
+    assert_eq!(info.block_number, 2);
+    assert_eq!(info.block_hash, head_hash(&blockchain).await);
+    let checkpoint = Checkpoint::load(Path::new(&info.path)).unwrap();
+    (checkpoint, info.block_hash)
+}
+
+fn checkpoint_error(error: anyhow::Error) -> CheckpointError {
+    error.downcast::<CheckpointError>().unwrap()
+}
+
+#[tokio::test]
+async fn test_checkpoint_must_match_the_trusted_hash_and_its_state_root() {
+    let dir = tempfile::tempdir().unwrap();
+    let proposer = KeyPair::generate("checkpoint-proposer".to_string());
+    let (checkpoint, hash) = source_checkpoint(dir.path(), &proposer).await;
+    assert_eq!(checkpoint.verify(&hash), Ok(()));
+
+    assert_eq!(
+        checkpoint.verify(&B256::repeat_byte(1)),
+        Err(CheckpointError::UntrustedBlock {
+            expected: B256::repeat_byte(1),
+            actual: hash,
+        })
+    );
+
+    let mut tampered = checkpoint.clone();
+    tampered
+        .state
+        .fund_account(&Address::repeat_byte(0xbb), U256::from(5));
+    assert!(matches!(
+        tampered.verify(&hash),
+        Err(CheckpointError::StateRoot { index: 2, .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_new_node_starts_at_the_checkpoint_and_builds_on_it() {
+    let dir = tempfile::tempdir().unwrap();
+    let proposer = KeyPair::generate("checkpoint-proposer".to_string());
+    let (checkpoint, hash) = source_checkpoint(dir.path(), &proposer).await;
+    let path = dir.path().join("new");
+
+    let storage = Storage::new(&path).unwrap();
+    let head = checkpoint.install(&storage, &hash).unwrap();
+    assert_eq!(
+        head,
+        HeadInfo {
+            number: 2,
+            hash,
+            slot: 2
+        }
+    );
+    assert_eq!(storage.get_block_hash_from_index(&1).unwrap(), None);
+
+    let blockchain = Blockchain::from_storage(
+        storage,
+        MIN_STAKE,
+        SLOT_DURATION,
+        vec![(proposer.address, 200)],
+        None,
+    )
+    .unwrap();
+    assert_eq!(head_hash(&blockchain).await, hash);
+    assert_eq!(blockchain.finalized_head().await, Some(head.clone()));
+    assert_eq!(
+        blockchain
+            .execution_engine
+            .state_manager
+            .lock()
+            .await
+            .get_state_root(),
+        checkpoint.block.header.state_root
+    );
+
+    // the next block must link to the checkpoint block
+    assert!(!matches!(
+        import(&blockchain, &proposer, 3, B256::repeat_byte(7)).await,
+        BlockProcessResult::Accepted(_)
+    ));
+    assert!(matches!(
+        import(&blockchain, &proposer, 3, hash).await,
+        BlockProcessResult::Accepted(_)
+    ));
+    drop(blockchain);
+
+    // on restart the chain is checked back to the checkpoint and verified from it
+    let storage = Storage::new(&path).unwrap();
+    assert_eq!(checkpoint.install(&storage, &hash).unwrap(), head);
+    let summary = ChainVerifier::new(&storage)
+        .verify(VerifyDepth::Full)
+        .await
+        .unwrap();
+    assert_eq!((summary.first_block, summary.verified), (3, 1));
+}
+
+#[tokio::test]
+async fn test_database_with_another_chain_refuses_the_checkpoint() {
+    let dir = tempfile::tempdir().unwrap();
+    let proposer = KeyPair::generate("checkpoint-proposer".to_string());
+    let (checkpoint, hash) = source_checkpoint(dir.path(), &proposer).await;
+
+    // other slot clock
+    let storage = Storage::new(dir.path().join("other-genesis")).unwrap();
+    storage
+        .put_consensus_genesis(&ConsensusGenesis::new(SystemTime::UNIX_EPOCH, [2u8; 32]))
+        .unwrap();
+    assert_eq!(
+        checkpoint_error(checkpoint.install(&storage, &hash).unwrap_err()),
+        CheckpointError::GenesisMismatch
+    );
+
+    // same genesis, but block #2 is at another slot
+    let path = dir.path().join("other-chain");
+    let storage = Storage::new(&path).unwrap();
+    storage.put_consensus_genesis(&checkpoint.genesis).unwrap();
+    let blockchain = Blockchain::from_storage(
+        storage,
+        MIN_STAKE,
+        SLOT_DURATION,
+        vec![(proposer.address, 200)],
+        None,
+    )
+    .unwrap();
+    for slot in [1, 3] {
+        let parent_hash = head_hash(&blockchain).await;
+        import(&blockchain, &proposer, slot, parent_hash).await;
+    }
+    let other = head_hash(&blockchain).await;
+    drop(blockchain);
+
+    let storage = Storage::new(&path).unwrap();
+    assert_eq!(
+        checkpoint_error(checkpoint.install(&storage, &hash).unwrap_err()),
+        CheckpointError::ConflictingChain {
+            index: 2,
+            expected: hash,
+            actual: Some(other),
+        }
+    );
+}
@@ -0,0 +1,94 @@
+use alloy::primitives::U256;
+use speed_blockchain::consensus::{ConsensusConfig, Quorum};
+use speed_blockchain::{
+    Blockchain, KeyPair, MIN_STAKE, SLOT_DURATION, SLOTS_PER_EPOCH, Transaction, gas_target_fit,
+};
+
+use super::helpers::numbered_transfer;
+
+#[test]
+fn test_quorum_rounds_the_needed_stake_up() {
+    let quorum = Quorum::default();
+    assert_eq!(quorum.of(300), 200);
+    assert_eq!(quorum.of(100), 67);
+    assert_eq!(quorum.of(0), 0);
+
+    let three_quarters = Quorum {
+        numerator: 3,
+        denominator: 4,
+    };
+    assert_eq!(three_quarters.of(10), 8);
+    assert_eq!(three_quarters.of(u64::MAX), u64::MAX - u64::MAX / 4);
+}
+
+#[test]
+fn test_epoch_helpers_follow_the_configured_length() {
+    let config = ConsensusConfig::default();
+    assert_eq!(config.slots_per_epoch, SLOTS_PER_EPOCH);
+    assert_eq!(config.epoch_of(SLOTS_PER_EPOCH), 1);
+
+    let config = ConsensusConfig {
+        slots_per_epoch: 4,
+        ..ConsensusConfig::default()
+    };
+    assert_eq!(config.epoch_of(3), 0);
+    assert_eq!(config.epoch_of(4), 1);
+    assert_eq!(config.epoch_start_slot(3), 12);
+    assert_eq!(config.epoch_end_slot(3), 15);
+
+    // fields left out of a config file keep their defaults
+    let parsed: ConsensusConfig = serde_json::from_str(r#"{"slots_per_epoch": 4}"#).unwrap();
+    assert_eq!(parsed, config);
+}
+
+#[test]
+fn test_gas_target_keeps_the_leading_transactions_that_fit() {
+    let txs: Vec<Transaction> = (1..=3).map(numbered_transfer).collect();
+    assert_eq!(gas_target_fit(&txs, U256::from(1_000_000)), 3);
+    assert_eq!(gas_target_fit(&txs, U256::from(60_000)), 2);
+    // a transaction above the target on its own still gets a block
+    assert_eq!(gas_target_fit(&txs, U256::from(10_000)), 1);
+    assert_eq!(gas_target_fit(&[], U256::ZERO), 0);
+}
+
+#[tokio::test]
+async fn test_custom_epoch_length_schedules_and_hashes_the_chain_spec() {
+    let dir = tempfile::tempdir().unwrap();
+    let proposer = KeyPair::generate("consensus-config-proposer".to_string());
+    let validators = vec![(proposer.address, 200)];
+    let open = |name: &str| {
+        Blockchain::new(
+            dir.path().join(name).to_str().unwrap(),
+            MIN_STAKE,
+            SLOT_DURATION,
+            validators.clone(),
+            None,
+        )
+        .unwrap()
+    };
+
+    let default = open("default");
+    // the gas target is local to the proposer, it is no consensus rule
+    let targeted = open("targeted").with_consensus_config(ConsensusConfig {
+        block_gas_target: Some(U256::from(100_000)),
+        ..ConsensusConfig::default()
+    });
+    assert_eq!(targeted.chain_spec_hash(), default.chain_spec_hash());
+
+    let short = open("short").with_consensus_config(ConsensusConfig {
+        slots_per_epoch: 4,
+        // the slot duration the chain was opened with is kept
+        slot_duration: 1,
+        ..ConsensusConfig::default()
+    });
+    assert_ne!(short.chain_spec_hash(), default.chain_spec_hash());
+    assert_eq!(short.consensus_config().slot_duration, SLOT_DURATION);
+
+    let schedule = short
+        .consensus_engine
+        .lock()
+        .await
+        .epoch_schedule(1)
+        .unwrap();
+    assert_eq!((schedule.start_slot, schedule.proposers.len()), (4, 4));
+}
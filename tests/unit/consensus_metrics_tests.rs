@@ -0,0 +1,126 @@
+use alloy::primitives::B256;
+use speed_blockchain::consensus::{ConsensusMetrics, attestation_hash};
+use speed_blockchain::core::{Block, BlockHeader};
+use speed_blockchain::{
+    Attestation, AttestationVote, BlockProcessResult, Blockchain, KeyPair, MIN_STAKE, SLOT_DURATION,
+};
+use std::time::Duration;
+
+use super::helpers::{genesis_slots_ago, slot_time};
+
+#[test]
+fn test_metrics_report_rates_and_averages() {
+    let mut metrics = ConsensusMetrics::new();
+    let report = metrics.report();
+    assert_eq!(report.participation_rate, 0.0);
+    assert_eq!(report.avg_time_to_quorum_ms, None);
+
+    metrics.record_block(2, 3);
+    metrics.record_block(0, 3);
+    metrics.record_proposal();
+    for _ in 0..3 {
+        metrics.record_attestation();
+    }
+    metrics.record_quorum(Duration::from_millis(400));
+    metrics.record_quorum(Duration::from_millis(800));
+
+    let report = metrics.report();
+    assert_eq!(report.missed_slots, 2);
+    assert_eq!(report.proposals_produced, 1);
+    assert_eq!(report.blocks_committed, 2);
+    assert_eq!(
+        (report.attestations_received, report.attestations_expected),
+        (3, 6)
+    );
+    assert_eq!(report.participation_rate, 0.5);
+    assert_eq!(report.quorums_reached, 2);
+    assert_eq!(report.avg_time_to_quorum_ms, Some(600));
+}
+
+#[tokio::test]
+async fn test_blockchain_counts_missed_slots_votes_and_quorums() {
+    let dir = tempfile::tempdir().unwrap();
+    let keys: Vec<KeyPair> = (0..4)
+        .map(|i| KeyPair::generate(format!("consensus-metrics-{}", i)))
+        .collect();
+    genesis_slots_ago(dir.path().to_str().unwrap(), 3);
+    let blockchain = Blockchain::new(
+        dir.path().to_str().unwrap(),
+        MIN_STAKE,
+        SLOT_DURATION,
+        keys.iter().map(|key| (key.address, 100)).collect(),
+        None,
+    )
+    .unwrap();
+    let state_root = blockchain
+        .execution_engine
+        .state_manager
+        .lock()
+        .await
+        .get_state_root();
+
+    // slots 1 and 2 pass without a block
+    let proposer = blockchain
+        .consensus_engine
+        .lock()
+        .await
+        .scheduled_proposer(3)
+        .unwrap();
+    let key = keys.iter().find(|key| key.address == proposer).unwrap();
+    let mut header = BlockHeader::new(
+        1,
+        3,
+        proposer,
+        B256::ZERO,
+        Block::calculate_transactions_root(&[]),
+        state_root,
+    );
+    header.timestamp = slot_time(&blockchain, 3).await;
+    header.sign(key).await.unwrap();
+    let signature = header.validator_signature.unwrap();
+    let hash = header.hash();
+    assert!(matches!(
+        blockchain
+            .process_received_block(Block::new(header, Vec::new()), proposer, signature)
+            .await
+            .unwrap(),
+        BlockProcessResult::Accepted(_)
+    ));
+
+    // two of the three other validators accept, with the proposer that is a quorum
+    let mut votes = Vec::new();
+    for voter in keys.iter().filter(|key| key.address != proposer).take(2) {
+        let signature = voter
+            .sign_hash(&attestation_hash(&hash, &AttestationVote::Accept))
+            .await
+            .unwrap();
+        votes.push(Attestation {
+            validator_id: voter.address,
+            vote: AttestationVote::Accept,
+            signature,
+        });
+        blockchain
+            .record_attestation(&hash, voter.address)
+            .await
+            .unwrap();
+    }
+    assert!(
+        blockchain
+            .certify_block(&hash, &votes)
+            .await
+            .unwrap()
+            .is_some()
+    );
+
+    let report = blockchain.consensus_metrics().await;
+    assert_eq!(report.missed_slots, 2);
+    // the block was imported, not proposed here
+    assert_eq!(report.proposals_produced, 0);
+    assert_eq!(report.blocks_committed, 1);
+    assert_eq!(
+        (report.attestations_received, report.attestations_expected),
+        (2, 3)
+    );
+    assert_eq!(report.quorums_reached, 1);
+    assert!(report.avg_time_to_quorum_ms.is_some());
+}
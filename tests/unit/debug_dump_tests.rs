@@ -0,0 +1,92 @@
+use alloy::primitives::{B256, U256};
+use speed_blockchain::cli::tail_lines;
+use speed_blockchain::core::{ConsensusEventKind, ConsensusEventLog};
+use speed_blockchain::{KeyPair, Mempool, NodeConfig, REDACTED, TransactionSource};
+
+use super::helpers::signed_transfer_at;
+
+#[test]
+fn test_event_log_keeps_the_latest_events() {
+    let mut log = ConsensusEventLog::new(3);
+    for number in 1..=5 {
+        log.record(
+            number + 10,
+            number,
+            B256::repeat_byte(number as u8),
+            ConsensusEventKind::Imported,
+        );
+    }
+    log.record(
+        16,
+        0,
+        B256::ZERO,
+        ConsensusEventKind::EpochClosed { epoch: 0 },
+    );
+
+    let numbers: Vec<u64> = log.recent(10).iter().map(|e| e.block_number).collect();
+    assert_eq!(numbers, vec![4, 5, 0]);
+    let latest = log.recent(1);
+    assert_eq!(latest[0].kind, ConsensusEventKind::EpochClosed { epoch: 0 });
+    assert_eq!(latest[0].slot, 16);
+
+    let json = serde_json::to_value(&latest[0]).unwrap();
+    assert_eq!(json["type"], "epoch_closed");
+    assert_eq!(json["epoch"], 0);
+}
+
+#[tokio::test]
+async fn test_mempool_summary_counts_sources_and_gas_prices() {
+    let alice = KeyPair::generate("alice".to_string());
+    let bob = KeyPair::generate("bob".to_string());
+    let mut mempool = Mempool::new(10);
+
+    let gossip = TransactionSource::Gossip {
+        peer: "12D3KooW".to_string(),
+    };
+    let pending = [
+        (
+            signed_transfer_at(&alice, 0, 2).await,
+            TransactionSource::Rpc,
+        ),
+        (
+            signed_transfer_at(&alice, 1, 5).await,
+            TransactionSource::Rpc,
+        ),
+        (signed_transfer_at(&bob, 0, 3).await, gossip),
+    ];
+    for (tx, source) in pending {
+        mempool.add_transaction_from(&tx, source).unwrap();
+    }
+
+    let summary = mempool.summary();
+    assert_eq!(summary.pending, 3);
+    assert_eq!(summary.capacity, 10);
+    assert_eq!(summary.senders, 2);
+    assert_eq!(summary.by_source["rpc"], 2);
+    assert_eq!(summary.by_source["gossip"], 1);
+    assert_eq!(summary.lowest_gas_price, Some(U256::from(2_000_000_000u64)));
+    assert_eq!(
+        summary.highest_gas_price,
+        Some(U256::from(5_000_000_000u64))
+    );
+
+    assert_eq!(Mempool::new(10).summary().lowest_gas_price, None);
+}
+
+#[test]
+fn test_config_is_redacted_and_logs_are_tailed() {
+    let config = NodeConfig {
+        validator_key: "validator-1".to_string(),
+        ..NodeConfig::default()
+    };
+    let redacted = serde_json::to_string(&config.redacted()).unwrap();
+    assert!(!redacted.contains("validator-1"));
+    assert_eq!(config.redacted().validator_key, REDACTED);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("node.log");
+    std::fs::write(&path, "one\ntwo\nthree\nfour\n").unwrap();
+    assert_eq!(tail_lines(&path, 2).unwrap(), vec!["three", "four"]);
+    assert_eq!(tail_lines(&path, 10).unwrap().len(), 4);
+    assert!(tail_lines(dir.path().join("missing.log"), 2).is_err());
+}
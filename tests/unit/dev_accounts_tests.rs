@@ -0,0 +1,18 @@
+use alloy::primitives::address;
+use speed_blockchain::{crypto::DEV_ACCOUNT_COUNT, dev_accounts};
+
+#[test]
+fn test_dev_accounts_match_published_mnemonic() {
+    let accounts = dev_accounts().unwrap();
+
+    assert_eq!(accounts.len(), DEV_ACCOUNT_COUNT as usize);
+    // same addresses as hardhat / anvil for the "test ... junk" mnemonic
+    assert_eq!(
+        accounts[0].address,
+        address!("f39Fd6e51aad88F6F4ce6aB8827279cffFb92266")
+    );
+    assert_eq!(
+        accounts[1].address,
+        address!("70997970C51812dc3A010C7d01b50e0d17dc79C8")
+    );
+}
@@ -0,0 +1,215 @@
+use alloy::primitives::{Address, B256};
+use speed_blockchain::consensus::{
+    AttestationEquivocationDetector, AttestationEquivocationEvidence, EvidenceError, SignedVote,
+    attestation_hash,
+};
+use speed_blockchain::core::{Block, BlockHeader};
+use speed_blockchain::{
+    AttestationVote, BlockProcessResult, Blockchain, KeyPair, MIN_STAKE, SLOT_DURATION,
+    SLOTS_PER_EPOCH, SlashingConfig,
+};
+
+use super::helpers::{genesis_slots_ago, slot_time};
+
+fn header(slot: u64, timestamp: u64) -> BlockHeader {
+    let mut header = BlockHeader::new(
+        slot,
+        slot,
+        Address::repeat_byte(0xaa),
+        B256::ZERO,
+        B256::ZERO,
+        B256::ZERO,
+    );
+    header.timestamp = timestamp;
+    header
+}
+
+fn reject() -> AttestationVote {
+    AttestationVote::Reject {
+        reason: "Invalid state root".to_string(),
+    }
+}
+
+async fn signed_vote(
+    validator: &KeyPair,
+    header: &BlockHeader,
+    vote: AttestationVote,
+) -> SignedVote {
+    let signature = validator
+        .sign_hash(&attestation_hash(&header.hash(), &vote))
+        .await
+        .unwrap();
+    SignedVote {
+        header: header.clone(),
+        vote,
+        signature,
+    }
+}
+
+#[tokio::test]
+async fn test_detector_catches_conflicting_votes_in_a_slot() {
+    let validator = KeyPair::generate("double-vote-validator".to_string());
+    let first = header(3, 1);
+    let second = header(3, 2);
+    let mut detector = AttestationEquivocationDetector::new();
+
+    // rejecting both blocks of an equivocating proposer is what honest validators do
+    assert!(
+        detector
+            .observe(
+                validator.address,
+                signed_vote(&validator, &first, reject()).await
+            )
+            .is_none()
+    );
+    assert!(
+        detector
+            .observe(
+                validator.address,
+                signed_vote(&validator, &second, reject()).await
+            )
+            .is_none()
+    );
+    // and so is accepting one of them after rejecting the other, or repeating a vote
+    let accept = signed_vote(&validator, &first, AttestationVote::Accept).await;
+    let evidence = detector.observe(validator.address, accept).unwrap();
+    assert_eq!((evidence.validator, evidence.slot), (validator.address, 3));
+    assert_eq!(evidence.verify(), Ok(()));
+    assert!(detector.is_equivocator(&validator.address));
+
+    // accepting two blocks of a slot is a double vote too, votes in other slots are not
+    let other = KeyPair::generate("double-vote-other".to_string());
+    let mut detector = AttestationEquivocationDetector::new();
+    for (slot_header, expect_evidence) in [(&first, false), (&header(4, 1), false), (&second, true)]
+    {
+        let vote = signed_vote(&other, slot_header, AttestationVote::Accept).await;
+        assert_eq!(
+            detector.observe(other.address, vote).is_some(),
+            expect_evidence
+        );
+    }
+    let repeated = signed_vote(&other, &header(5, 1), AttestationVote::Accept).await;
+    assert!(detector.observe(other.address, repeated.clone()).is_none());
+    assert!(detector.observe(other.address, repeated).is_none());
+}
+
+#[tokio::test]
+async fn test_double_vote_evidence_checks_signatures_and_conflict() {
+    let validator = KeyPair::generate("double-vote-evidence".to_string());
+    let impostor = KeyPair::generate("double-vote-impostor".to_string());
+    let first = header(3, 1);
+
+    let accept = signed_vote(&validator, &first, AttestationVote::Accept).await;
+    let forged = signed_vote(&impostor, &first, reject()).await;
+    assert_eq!(
+        AttestationEquivocationEvidence::new(validator.address, accept.clone(), forged)
+            .unwrap_err(),
+        EvidenceError::InvalidVoteSignature(first.hash())
+    );
+
+    let later = signed_vote(&validator, &header(4, 1), AttestationVote::Accept).await;
+    assert_eq!(
+        AttestationEquivocationEvidence::new(validator.address, accept.clone(), later).unwrap_err(),
+        EvidenceError::SlotMismatch
+    );
+
+    let rejected = signed_vote(&validator, &header(3, 2), reject()).await;
+    assert!(matches!(
+        AttestationEquivocationEvidence::new(validator.address, accept, rejected),
+        Err(EvidenceError::NoConflict(..))
+    ));
+}
+
+#[tokio::test]
+async fn test_double_vote_is_slashed_when_its_epoch_closes() {
+    let dir = tempfile::tempdir().unwrap();
+    let keys = [
+        KeyPair::generate("double-vote-chain-first".to_string()),
+        KeyPair::generate("double-vote-chain-second".to_string()),
+    ];
+    genesis_slots_ago(dir.path().to_str().unwrap(), SLOTS_PER_EPOCH + 1);
+    let blockchain = Blockchain::new(
+        dir.path().to_str().unwrap(),
+        MIN_STAKE,
+        SLOT_DURATION,
+        keys.iter().map(|key| (key.address, 200)).collect(),
+        None,
+    )
+    .unwrap()
+    .with_slashing(SlashingConfig {
+        slash_percent: 10,
+        max_slashes: 3,
+    });
+    let state_root = blockchain
+        .execution_engine
+        .state_manager
+        .lock()
+        .await
+        .get_state_root();
+
+    let import = |index: u64, slot: u64, parent_hash: B256| {
+        let blockchain = blockchain.clone();
+        let keys = keys.clone();
+        async move {
+            let proposer = blockchain
+                .consensus_engine
+                .lock()
+                .await
+                .scheduled_proposer(slot)
+                .unwrap();
+            let key = keys.iter().find(|key| key.address == proposer).unwrap();
+            let mut header = BlockHeader::new(
+                index,
+                slot,
+                proposer,
+                parent_hash,
+                Block::calculate_transactions_root(&[]),
+                state_root,
+            );
+            header.timestamp = slot_time(&blockchain, slot).await;
+            header.sign(key).await.unwrap();
+            let signature = header.validator_signature.unwrap();
+            let hash = header.hash();
+            let result = blockchain
+                .process_received_block(Block::new(header, Vec::new()), proposer, signature)
+                .await
+                .unwrap();
+            assert!(matches!(result, BlockProcessResult::Accepted(_)));
+            hash
+        }
+    };
+
+    let first = import(1, 1, B256::ZERO).await;
+    let header = blockchain.get_block_header(&first).await.unwrap().unwrap();
+    let voter = &keys[1];
+    let evidence = AttestationEquivocationEvidence::new(
+        voter.address,
+        signed_vote(voter, &header, AttestationVote::Accept).await,
+        signed_vote(voter, &header, reject()).await,
+    )
+    .unwrap();
+    blockchain
+        .report_double_vote(evidence.clone())
+        .await
+        .unwrap();
+    // the same slot is only stored and slashed once
+    blockchain.report_double_vote(evidence).await.unwrap();
+    let stored = blockchain
+        .get_attestation_evidence(&voter.address)
+        .await
+        .unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].verify(), Ok(()));
+
+    let stake = || async {
+        blockchain
+            .consensus_engine
+            .lock()
+            .await
+            .validator_set()
+            .stake_of(&voter.address)
+    };
+    assert_eq!(stake().await, 200);
+    import(2, SLOTS_PER_EPOCH + 1, first).await;
+    assert_eq!(stake().await, 180);
+}
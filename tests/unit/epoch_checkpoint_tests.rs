@@ -0,0 +1,116 @@
+use alloy::primitives::B256;
+use speed_blockchain::consensus::CheckpointStatus;
+use speed_blockchain::core::{Block, BlockHeader};
+use speed_blockchain::{
+    BlockProcessResult, Blockchain, KeyPair, MIN_STAKE, SLOT_DURATION, SLOTS_PER_EPOCH,
+};
+
+use super::helpers::{genesis_slots_ago, slot_time};
+
+// signed empty block on `parent_hash`, with the current state root
+async fn import(
+    blockchain: &Blockchain,
+    proposer: &KeyPair,
+    index: u64,
+    slot: u64,
+    parent_hash: B256,
+) -> (B256, BlockProcessResult) {
+    let state_root = blockchain
+        .execution_engine
+        .state_manager
+        .lock()
+        .await
+        .get_state_root();
+    let mut header = BlockHeader::new(
+        index,
+        slot,
+        proposer.address,
+        parent_hash,
+        Block::calculate_transactions_root(&[]),
+        state_root,
+    );
+    header.timestamp = slot_time(blockchain, slot).await;
+    header.sign(proposer).await.unwrap();
+    let hash = header.hash();
+    let signature = header.validator_signature.unwrap();
+    let result = blockchain
+        .process_received_block(Block::new(header, Vec::new()), proposer.address, signature)
+        .await
+        .unwrap();
+    (hash, result)
+}
+
+#[tokio::test]
+async fn test_checkpoints_are_justified_then_finalized_by_the_next_epoch() {
+    let dir = tempfile::tempdir().unwrap();
+    let proposer = KeyPair::generate("epoch-checkpoint-proposer".to_string());
+    genesis_slots_ago(dir.path().to_str().unwrap(), 2 * SLOTS_PER_EPOCH);
+    let blockchain = Blockchain::new(
+        dir.path().to_str().unwrap(),
+        MIN_STAKE,
+        SLOT_DURATION,
+        vec![(proposer.address, 200)],
+        None,
+    )
+    .unwrap();
+
+    // the only validator's signature is a quorum on its own
+    let (first, _) = import(&blockchain, &proposer, 1, 1, B256::ZERO).await;
+    assert!(
+        blockchain
+            .certify_block(&first, &[])
+            .await
+            .unwrap()
+            .is_some()
+    );
+    assert_eq!(blockchain.epoch_checkpoint(0).await.unwrap(), None);
+
+    // block #2 closes epoch 0, its checkpoint is the certified block #1
+    let (second, _) = import(&blockchain, &proposer, 2, SLOTS_PER_EPOCH, first).await;
+    let checkpoint = blockchain.epoch_checkpoint(0).await.unwrap().unwrap();
+    assert_eq!((checkpoint.number, checkpoint.hash), (1, first));
+    assert_eq!(checkpoint.status, CheckpointStatus::Justified);
+    let checkpoints = blockchain.finality_checkpoints().await.unwrap();
+    assert_eq!(checkpoints.justified, Some(checkpoint));
+    assert_eq!(checkpoints.finalized, None);
+
+    // epoch 1 closes on an uncertified block and stays pending until its certificate
+    import(&blockchain, &proposer, 3, 2 * SLOTS_PER_EPOCH, second).await;
+    let checkpoint = blockchain.epoch_checkpoint(1).await.unwrap().unwrap();
+    assert_eq!(
+        (checkpoint.number, checkpoint.status),
+        (2, CheckpointStatus::Pending)
+    );
+    assert_eq!(
+        blockchain.finality_checkpoints().await.unwrap().finalized,
+        None
+    );
+
+    blockchain.certify_block(&second, &[]).await.unwrap();
+    let checkpoints = blockchain.finality_checkpoints().await.unwrap();
+    assert_eq!(checkpoints.justified.map(|c| c.epoch), Some(1));
+    let finalized = checkpoints.finalized.unwrap();
+    assert_eq!((finalized.epoch, finalized.number), (0, 1));
+    assert_eq!(
+        blockchain
+            .epoch_checkpoint(0)
+            .await
+            .unwrap()
+            .unwrap()
+            .status,
+        CheckpointStatus::Finalized
+    );
+
+    // a competing block #1 is refused instead of kept on a side branch
+    let (_, result) = import(&blockchain, &proposer, 1, 2, B256::ZERO).await;
+    match result {
+        BlockProcessResult::Rejected(_, reason) => {
+            assert!(
+                reason.contains("finalized checkpoint of epoch 0"),
+                "{}",
+                reason
+            )
+        }
+        other => panic!("expected a rejection, got {:?}", other),
+    }
+}
@@ -0,0 +1,147 @@
+use alloy::primitives::{Address, B256, U256};
+use speed_blockchain::consensus::{EpochSettlement, EpochStatement, epoch_settlements};
+use speed_blockchain::core::{Block, BlockHeader};
+use speed_blockchain::reindex::{ChainVerifier, VerifyDepth};
+use speed_blockchain::{
+    Blockchain, EpochRewardConfig, KeyPair, MIN_STAKE, SLOT_DURATION, SLOTS_PER_EPOCH, Storage,
+};
+
+use super::helpers::{genesis_slots_ago, slot_time};
+
+fn statement(validator: u8, made: u64, missed: u64) -> EpochStatement {
+    EpochStatement {
+        validator: Address::repeat_byte(validator),
+        epoch: 2,
+        start_slot: 16,
+        end_slot: 23,
+        proposals_made: made,
+        proposals_missed: missed,
+        attestations_included: 0,
+        rewards: U256::ZERO,
+        penalties: U256::ZERO,
+        end_stake: 100,
+    }
+}
+
+fn config() -> EpochRewardConfig {
+    EpochRewardConfig {
+        proposal_reward: U256::from(1_000),
+        inactivity_penalty: U256::from(300),
+    }
+}
+
+#[test]
+fn test_settlements_pay_proposals_and_charge_missed_slots() {
+    let settlements = epoch_settlements(
+        &[statement(1, 3, 1), statement(2, 0, 2), statement(3, 0, 0)],
+        &config(),
+    );
+    // validator 3 had no duties, it is left out
+    assert_eq!(
+        settlements,
+        vec![
+            EpochSettlement {
+                validator: Address::repeat_byte(1),
+                epoch: 2,
+                reward: U256::from(3_000),
+                penalty: U256::from(300),
+            },
+            EpochSettlement {
+                validator: Address::repeat_byte(2),
+                epoch: 2,
+                reward: U256::ZERO,
+                penalty: U256::from(600),
+            },
+        ]
+    );
+
+    // nobody proposed, the epoch says nothing about who was offline
+    assert!(epoch_settlements(&[statement(1, 0, 4), statement(2, 0, 4)], &config()).is_empty());
+    assert!(epoch_settlements(&[statement(1, 3, 1)], &EpochRewardConfig::default()).is_empty());
+}
+
+#[tokio::test]
+async fn test_epoch_close_settles_into_the_state_root_and_replays() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().to_str().unwrap();
+    let proposer = KeyPair::generate("epoch-settlement-proposer".to_string());
+    genesis_slots_ago(path, SLOTS_PER_EPOCH + 1);
+    let blockchain = Blockchain::new(
+        path,
+        MIN_STAKE,
+        SLOT_DURATION,
+        vec![(proposer.address, 200)],
+        None,
+    )
+    .unwrap()
+    .with_epoch_rewards(config());
+
+    let mut parent_hash = B256::ZERO;
+    for (index, slot) in [(1, 1), (2, SLOTS_PER_EPOCH), (3, SLOTS_PER_EPOCH + 1)] {
+        let state_root = blockchain
+            .execution_engine
+            .state_manager
+            .lock()
+            .await
+            .get_state_root();
+        let mut header = BlockHeader::new(
+            index,
+            slot,
+            proposer.address,
+            parent_hash,
+            Block::calculate_transactions_root(&[]),
+            state_root,
+        );
+        header.timestamp = slot_time(&blockchain, slot).await;
+        header.sign(&proposer).await.unwrap();
+        parent_hash = header.hash();
+        let signature = header.validator_signature.unwrap();
+        blockchain
+            .process_received_block(Block::new(header, Vec::new()), proposer.address, signature)
+            .await
+            .unwrap();
+    }
+
+    // block #2 closed epoch 0, the only validator proposed block #1 and missed slot 0
+    let missed = blockchain
+        .get_epoch_statement(&proposer.address, 0)
+        .await
+        .unwrap()
+        .unwrap()
+        .proposals_missed;
+    assert!(missed > 0);
+    // penalties stop at the balance
+    let penalty = U256::from(1_000).min(U256::from(300 * missed));
+    assert_eq!(
+        blockchain.get_epoch_settlements(0).await.unwrap(),
+        vec![EpochSettlement {
+            validator: proposer.address,
+            epoch: 0,
+            reward: U256::from(1_000),
+            penalty,
+        }]
+    );
+    assert_eq!(
+        blockchain
+            .execution_engine
+            .get_balance(&proposer.address)
+            .await,
+        U256::from(1_000) - penalty
+    );
+    assert!(
+        blockchain
+            .get_epoch_settlements(1)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    drop(blockchain);
+
+    // block #3 committed to the settled balance, replaying the chain applies it the same way
+    let storage = Storage::new(path).unwrap();
+    let summary = ChainVerifier::new(&storage)
+        .verify(VerifyDepth::Full)
+        .await
+        .unwrap();
+    assert_eq!(summary.verified, 3);
+}
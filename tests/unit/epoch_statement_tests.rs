@@ -0,0 +1,139 @@
+use alloy::primitives::{Address, U256};
+use speed_blockchain::consensus::{ConsensusConfig, EpochProposal, epoch_statements};
+use speed_blockchain::{
+    BaseFeeConfig, BlockProcessResult, Blockchain, ChainSpec, GasConfig, GenesisValidator, KeyPair,
+    SLOTS_PER_EPOCH, Storage, TransactionKind, dev_keypairs,
+};
+use std::collections::HashMap;
+
+use super::helpers::{dry_run_block_at, import_signed, signed_transfer_at};
+
+#[test]
+fn test_statement_counts_made_and_missed_proposals() {
+    let alice = Address::repeat_byte(1);
+    let bob = Address::repeat_byte(2);
+    let epoch = 3;
+    let config = ConsensusConfig::default();
+    let start = epoch * SLOTS_PER_EPOCH;
+    assert_eq!(config.epoch_of(start + SLOTS_PER_EPOCH - 1), epoch);
+
+    // alice scheduled twice and proposed once, bob scheduled once and proposed
+    let schedule = vec![(start, alice), (start + 1, bob), (start + 2, alice)];
+    let proposals = vec![
+        EpochProposal {
+            slot: start,
+            proposer: alice,
+            fees: U256::from(300),
+            block_reward: U256::ZERO,
+        },
+        EpochProposal {
+            slot: start + 1,
+            proposer: bob,
+            fees: U256::from(50),
+            block_reward: U256::from(10),
+        },
+    ];
+    let attestations = HashMap::from([(bob, 1)]);
+    let validators = vec![(alice, 100), (bob, 200)];
+
+    let penalties = HashMap::from([(alice, 10)]);
+    let statements = epoch_statements(
+        &config,
+        epoch,
+        &schedule,
+        &proposals,
+        &attestations,
+        &validators,
+        &penalties,
+    );
+    let alice_statement = &statements[0];
+    assert_eq!(alice_statement.start_slot, start);
+    assert_eq!(alice_statement.end_slot, start + SLOTS_PER_EPOCH - 1);
+    assert_eq!(alice_statement.proposals_made, 1);
+    assert_eq!(alice_statement.proposals_missed, 1);
+    assert_eq!(alice_statement.attestations_included, 0);
+    assert_eq!(alice_statement.rewards, U256::from(300));
+    assert_eq!(alice_statement.end_stake, 100);
+    assert_eq!(alice_statement.penalties, U256::from(10));
+
+    let bob_statement = &statements[1];
+    assert_eq!(bob_statement.proposals_made, 1);
+    assert_eq!(bob_statement.proposals_missed, 0);
+    assert_eq!(bob_statement.attestations_included, 1);
+    assert_eq!(bob_statement.rewards, U256::from(60));
+    assert_eq!(bob_statement.penalties, U256::ZERO);
+}
+
+#[tokio::test]
+async fn test_statement_fees_match_what_the_proposer_was_paid() {
+    let dir = tempfile::tempdir().unwrap();
+    let proposer = KeyPair::generate("statement-proposer".to_string());
+    let sender = dev_keypairs(1).unwrap().remove(0);
+    let spec = ChainSpec {
+        genesis_time: 1_700_000_000,
+        gas: GasConfig {
+            base_fee: Some(BaseFeeConfig::default()),
+            ..GasConfig::default()
+        },
+        validators: vec![GenesisValidator {
+            address: proposer.address,
+            stake: 200,
+        }],
+        alloc: [(sender.address, U256::from(10).pow(U256::from(18)))].into(),
+        ..ChainSpec::default()
+    };
+    let storage = Storage::new(dir.path().to_str().unwrap()).unwrap();
+    let blockchain = Blockchain::from_chain_spec(storage, &spec, None).unwrap();
+
+    // a transfer and one that fails while executing, both pay a tip and burn the base fee
+    let transfer = signed_transfer_at(&sender, 0, 20).await;
+    let mut failing = signed_transfer_at(&sender, 1, 20).await;
+    failing.kind = TransactionKind::TransferFrom {
+        owner: Address::repeat_byte(0xcc),
+    };
+    failing.sign(&sender).await.unwrap();
+    let (mut block, result) = dry_run_block_at(
+        &blockchain,
+        proposer.address,
+        (1, 1),
+        spec.genesis_block().header.hash(),
+        vec![transfer, failing],
+    )
+    .await;
+    assert!(!result.receipts[1].success);
+    assert!(result.receipts[1].tip > U256::ZERO);
+    assert!(result.burned_fees > U256::ZERO);
+    block.header.timestamp = spec.genesis_time + spec.slot_duration;
+    let parent_hash = block.header.hash();
+    assert!(matches!(
+        import_signed(&blockchain, &proposer, block).await,
+        BlockProcessResult::Accepted(_)
+    ));
+    let paid = blockchain
+        .execution_engine
+        .get_balance(&proposer.address)
+        .await;
+
+    // the first block of epoch 1 closes epoch 0
+    let (mut block, _) = dry_run_block_at(
+        &blockchain,
+        proposer.address,
+        (2, SLOTS_PER_EPOCH),
+        parent_hash,
+        Vec::new(),
+    )
+    .await;
+    block.header.timestamp = spec.genesis_time + SLOTS_PER_EPOCH * spec.slot_duration;
+    assert!(matches!(
+        import_signed(&blockchain, &proposer, block).await,
+        BlockProcessResult::Accepted(_)
+    ));
+
+    let statement = blockchain
+        .get_epoch_statement(&proposer.address, 0)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(statement.proposals_made, 1);
+    assert_eq!(statement.rewards, paid);
+}
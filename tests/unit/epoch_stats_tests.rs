@@ -0,0 +1,79 @@
+use alloy::primitives::{Address, U256};
+use speed_blockchain::SLOTS_PER_EPOCH;
+use speed_blockchain::consensus::{
+    ConsensusConfig, EpochBlockUsage, EpochProposal, epoch_statements, epoch_stats,
+};
+use speed_blockchain::storage::Storage;
+use std::collections::HashMap;
+
+#[test]
+fn test_rollup_counts_transactions_senders_fullness_and_participation() {
+    let alice = Address::repeat_byte(1);
+    let bob = Address::repeat_byte(2);
+    let carol = Address::repeat_byte(3);
+    let sender_a = Address::repeat_byte(0xa0);
+    let sender_b = Address::repeat_byte(0xb0);
+    let epoch = 2;
+    let start = epoch * SLOTS_PER_EPOCH;
+
+    // alice proposes once and misses once, bob only attests, carol does nothing
+    let schedule = vec![(start, alice), (start + 1, alice)];
+    let proposals = vec![EpochProposal {
+        slot: start,
+        proposer: alice,
+        fees: U256::from(10),
+        block_reward: U256::ZERO,
+    }];
+    let attestations = HashMap::from([(bob, 1)]);
+    let validators = vec![(alice, 100), (bob, 100), (carol, 100)];
+    let penalties = HashMap::new();
+    let config = ConsensusConfig::default();
+    let statements = epoch_statements(
+        &config,
+        epoch,
+        &schedule,
+        &proposals,
+        &attestations,
+        &validators,
+        &penalties,
+    );
+
+    let blocks = vec![
+        EpochBlockUsage {
+            gas_used: U256::from(500),
+            senders: vec![sender_a, sender_b, sender_a],
+        },
+        EpochBlockUsage {
+            gas_used: U256::from(0),
+            senders: vec![],
+        },
+    ];
+    let stats = epoch_stats(&config, epoch, &statements, &blocks, U256::from(1_000));
+
+    assert_eq!(stats.start_slot, start);
+    assert_eq!(stats.end_slot, start + SLOTS_PER_EPOCH - 1);
+    assert_eq!(stats.blocks, 2);
+    assert_eq!(stats.missed_slots, 1);
+    assert_eq!(stats.transactions, 3);
+    assert_eq!(stats.total_gas, U256::from(500));
+    assert_eq!(stats.unique_senders, 2);
+    assert_eq!(stats.avg_block_fullness, 0.25);
+    assert_eq!(stats.active_validators, 3);
+    assert_eq!(stats.participating_validators, 2);
+
+    // an epoch without blocks has nothing to average
+    let empty = epoch_stats(&config, epoch + 1, &[], &[], U256::from(1_000));
+    assert_eq!(empty.avg_block_fullness, 0.0);
+    assert_eq!(empty.unique_senders, 0);
+}
+
+#[test]
+fn test_rollups_are_stored_per_epoch() {
+    let dir = tempfile::tempdir().unwrap();
+    let storage = Storage::new(dir.path().to_str().unwrap()).unwrap();
+    let stats = epoch_stats(&ConsensusConfig::default(), 4, &[], &[], U256::from(1_000));
+
+    storage.put_epoch_stats(&stats).unwrap();
+    assert_eq!(storage.get_epoch_stats(4).unwrap(), Some(stats));
+    assert_eq!(storage.get_epoch_stats(5).unwrap(), None);
+}
@@ -0,0 +1,100 @@
+use alloy::primitives::{Address, B256, U256};
+use speed_blockchain::core::{Block, BlockHeader};
+use speed_blockchain::storage::Storage;
+use speed_blockchain::{
+    Log, NATIVE_LOG_ADDRESS, Receipt, ReceiptEvent, address_topic, event_topic, precompile_address,
+};
+
+use super::helpers::numbered_transfer;
+
+#[test]
+fn test_native_events_become_logs() {
+    let log = Log::from_event(&ReceiptEvent::Transfer {
+        from: Address::repeat_byte(1),
+        to: Address::repeat_byte(2),
+        amount: U256::from(5),
+    });
+    assert_eq!(log.address, NATIVE_LOG_ADDRESS);
+    assert_eq!(
+        log.topics,
+        vec![
+            event_topic("Transfer(address,address,uint256)"),
+            address_topic(&Address::repeat_byte(1)),
+            address_topic(&Address::repeat_byte(2)),
+        ]
+    );
+    assert_eq!(log.data.as_ref(), &U256::from(5).to_be_bytes::<32>()[..]);
+
+    // precompiles log under their own address
+    let log = Log::from_event(&ReceiptEvent::PrecompileCalled {
+        precompile: precompile_address(2),
+        caller: Address::repeat_byte(1),
+    });
+    assert_eq!(log.address, precompile_address(2));
+
+    let receipt = Receipt::success(B256::repeat_byte(1), U256::from(21_000))
+        .with_events(ReceiptEvent::from_transaction(&numbered_transfer(1)));
+    assert_eq!(receipt.logs.len(), receipt.events.len());
+}
+
+#[test]
+fn test_logs_are_indexed_by_topic_until_their_block_leaves_the_chain() {
+    let dir = tempfile::tempdir().unwrap();
+    let storage = Storage::new(dir.path()).unwrap();
+    let transactions = vec![numbered_transfer(1), numbered_transfer(2)];
+    let header = BlockHeader::new(
+        1,
+        1,
+        Address::repeat_byte(9),
+        B256::ZERO,
+        Block::calculate_transactions_root(&transactions),
+        B256::ZERO,
+    );
+    let block = Block::new(header, transactions);
+    let block_hash = block.header.hash();
+    let receipts: Vec<Receipt> = block
+        .transactions
+        .iter()
+        .map(|tx| {
+            Receipt::success(tx.hash, U256::from(21_000))
+                .with_events(ReceiptEvent::from_transaction(tx))
+        })
+        .collect();
+    storage.store_block(&block).unwrap();
+    storage.put_block_receipts(&block_hash, &receipts).unwrap();
+    storage.put_block_indexes(&block).unwrap();
+    // indexing again doesn't duplicate
+    storage.put_block_indexes(&block).unwrap();
+
+    let transfers = storage
+        .get_logs(&event_topic("Transfer(address,address,uint256)"))
+        .unwrap();
+    assert_eq!(transfers.len(), 2);
+    assert_eq!(
+        (
+            transfers[1].block_hash,
+            transfers[1].transaction_hash,
+            transfers[1].transaction_index,
+            transfers[1].log_index
+        ),
+        (block_hash, B256::repeat_byte(2), 1, 1)
+    );
+    let received = storage
+        .get_logs(&address_topic(&Address::repeat_byte(2)))
+        .unwrap();
+    assert_eq!(received.len(), 2);
+    assert!(
+        storage
+            .get_logs(&address_topic(&Address::repeat_byte(3)))
+            .unwrap()
+            .is_empty()
+    );
+
+    storage.remove_block_indexes(&block).unwrap();
+    assert!(
+        storage
+            .get_logs(&event_topic("Transfer(address,address,uint256)"))
+            .unwrap()
+            .is_empty()
+    );
+}
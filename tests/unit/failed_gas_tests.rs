@@ -0,0 +1,234 @@
+use alloy::primitives::{Address, B256, Bytes, U256};
+use speed_blockchain::{
+    Block, BlockEnv, ExecutionEngine, GasCalculator, GasConfig, GasStatus, StateTransition,
+    StateTransitionError, Transaction, TransactionKind, TransactionOutcome, WasmRuntime,
+    contract_address, core::BlockHeader, dev_keypairs,
+};
+
+use super::helpers::{ONE_TOKEN, TO_GWEI, funded_state, hashed, transaction, transfer};
+
+// runs until the fuel is gone
+const SPIN: &str = r#"(module
+  (memory (export "memory") 1)
+  (func (export "call") (result i32)
+    (loop $spin (br $spin))
+    (i32.const 0)))"#;
+
+// reverts right away
+const REVERT: &str = r#"(module
+  (memory (export "memory") 1)
+  (func (export "call") (result i32) (i32.const 1)))"#;
+
+fn alice() -> Address {
+    Address::repeat_byte(0xaa)
+}
+
+// spends an allowance alice was never given, fails once it runs
+fn unapproved(nonce: u64) -> Transaction {
+    let kind = TransactionKind::TransferFrom {
+        owner: Address::repeat_byte(0xbb),
+    };
+    hashed(transaction(
+        alice(),
+        Address::repeat_byte(0xcc),
+        nonce,
+        1_000,
+        kind,
+    ))
+}
+
+#[tokio::test]
+async fn test_failed_transactions_pay_for_the_gas_they_used() {
+    let proposer = Address::repeat_byte(0xdd);
+    let keypair = dev_keypairs(1).unwrap().remove(0);
+    let engine = ExecutionEngine::new();
+    engine
+        .prefund_accounts(&[(keypair.address, U256::from(ONE_TOKEN))])
+        .await;
+
+    let mut transactions = vec![
+        unapproved(0),
+        // a stale nonce after the failure took nonce 0
+        unapproved(0),
+        hashed(transfer(alice(), Address::repeat_byte(0xbb), 1, 1_000)),
+    ];
+    for tx in &mut transactions {
+        tx.from = keypair.address;
+        tx.sign(&keypair).await.unwrap();
+    }
+    let (failed, paid) = (transactions[0].clone(), transactions[2].clone());
+    let header = BlockHeader::new(1, 1, proposer, B256::ZERO, B256::ZERO, B256::ZERO);
+    let mut block = Block::new(header, transactions);
+    let result = engine.execute_block_commit(&mut block).await.unwrap();
+
+    // the failure is charged its intrinsic gas, not its gas limit
+    let intrinsic = GasCalculator::calculate_instrinsic_gas(&failed, &GasConfig::default());
+    assert!(!result.receipts[0].success);
+    assert_eq!(result.receipts[0].gas_used, intrinsic);
+    assert!(intrinsic < failed.gas_limit);
+    assert!(!result.receipts[1].success);
+    assert_eq!(result.receipts[1].gas_used, U256::ZERO);
+    assert!(result.receipts[2].success);
+
+    let statuses: Vec<GasStatus> = result.gas_report.iter().map(|gas| gas.status).collect();
+    assert_eq!(
+        statuses,
+        vec![GasStatus::Failed, GasStatus::Invalid, GasStatus::Success]
+    );
+    let charged = result.gas_report[0].fee;
+    assert_eq!(charged, intrinsic * U256::from(TO_GWEI));
+    assert_eq!(result.gas_report[0].tip, charged);
+    assert_eq!(result.gas_report[1].fee, U256::ZERO);
+    assert_eq!(
+        result.total_gas_used,
+        intrinsic + result.receipts[2].gas_used
+    );
+    let fees: U256 = result.gas_report.iter().map(|gas| gas.fee).sum();
+    assert_eq!(result.total_fees, fees);
+    assert_eq!(engine.get_balance(&proposer).await, fees);
+
+    // the failure used up nonce 0, the transfer after it went through
+    assert_eq!(engine.get_nonce(&keypair.address).await, 2);
+    assert_eq!(
+        engine.get_balance(&keypair.address).await,
+        U256::from(ONE_TOKEN) - paid.amount - fees
+    );
+}
+
+#[test]
+fn test_outcomes_of_a_single_transaction() {
+    let mut state = funded_state(&[(alice(), ONE_TOKEN)]);
+    let config = GasConfig::default();
+    let env = BlockEnv::default();
+
+    let mut tx = unapproved(0);
+    let outcome = StateTransition::execute_transaction(&mut state, &mut tx, &config, &env);
+    assert!(matches!(
+        outcome,
+        TransactionOutcome::Failed {
+            error: StateTransitionError::InsufficientAllowance { .. },
+            ..
+        }
+    ));
+    assert_eq!(state.get_nonce(&alice()), 1);
+
+    // nothing changes for a transaction that can't be charged
+    let root = state.get_state_root();
+    let mut tx = unapproved(0);
+    let outcome = StateTransition::execute_transaction(&mut state, &mut tx, &config, &env);
+    assert!(matches!(
+        outcome,
+        TransactionOutcome::Invalid(StateTransitionError::InvalidNonce { .. })
+    ));
+    assert_eq!(outcome.gas_used(), U256::ZERO);
+    assert_eq!(state.get_state_root(), root);
+
+    // apply_transaction still leaves the state untouched on any failure
+    let mut tx = unapproved(1);
+    assert!(StateTransition::apply_transaction(&mut state, &mut tx, &config).is_err());
+    assert_eq!(state.get_state_root(), root);
+}
+
+#[test]
+fn test_failed_transaction_with_an_overflowing_fee_is_not_charged() {
+    let mut state = funded_state(&[(alice(), ONE_TOKEN)]);
+    let root = state.get_state_root();
+
+    // fails on the missing allowance, its fee wraps to zero
+    let mut tx = unapproved(0);
+    tx.gas_limit = U256::from(32_768);
+    tx.gas_price = U256::from(1) << 241;
+    let outcome = StateTransition::execute_transaction(
+        &mut state,
+        &mut tx,
+        &GasConfig::default(),
+        &BlockEnv::default(),
+    );
+
+    assert!(matches!(
+        outcome,
+        TransactionOutcome::Invalid(StateTransitionError::FeeOverflow)
+    ));
+    assert_eq!(state.get_balance(&alice()), U256::from(ONE_TOKEN));
+    assert_eq!(state.get_nonce(&alice()), 0);
+    assert_eq!(state.get_state_root(), root);
+}
+
+#[test]
+fn test_failed_contract_calls_pay_for_the_fuel_they_burned() {
+    let runtime = WasmRuntime::new();
+    let mut state = funded_state(&[(alice(), ONE_TOKEN)]);
+    let config = GasConfig {
+        max_tx_size: 16 * 1024,
+        ..GasConfig::default()
+    };
+    let env = BlockEnv {
+        contracts: Some(&runtime),
+        ..BlockEnv::default()
+    };
+    let mut execute = |tx: &mut Transaction| {
+        tx.gas_limit = GasCalculator::calculate_instrinsic_gas(tx, &config) + U256::from(50_000);
+        StateTransition::execute_transaction(&mut state, tx, &config, &env)
+    };
+
+    for (nonce, code) in [(0, SPIN), (1, REVERT)] {
+        let code = Bytes::copy_from_slice(code.as_bytes());
+        let kind = TransactionKind::DeployContract { code };
+        let mut deploy = hashed(transaction(alice(), Address::ZERO, nonce, 0, kind));
+        assert!(matches!(
+            execute(&mut deploy),
+            TransactionOutcome::Success { .. }
+        ));
+    }
+
+    // out of fuel, the whole gas limit is burned
+    let input = Bytes::new();
+    let kind = TransactionKind::CallContract { input };
+    let mut spin = hashed(transaction(
+        alice(),
+        contract_address(&alice(), 0),
+        2,
+        0,
+        kind.clone(),
+    ));
+    let outcome = execute(&mut spin);
+    assert!(matches!(outcome, TransactionOutcome::Failed { .. }));
+    assert_eq!(outcome.gas_used(), spin.gas_limit);
+
+    // a revert only pays for what ran before it
+    let mut revert = hashed(transaction(
+        alice(),
+        contract_address(&alice(), 1),
+        3,
+        0,
+        kind,
+    ));
+    let outcome = execute(&mut revert);
+    let intrinsic = GasCalculator::calculate_instrinsic_gas(&revert, &config);
+    assert!(matches!(outcome, TransactionOutcome::Failed { .. }));
+    assert!(outcome.gas_used() > intrinsic && outcome.gas_used() < revert.gas_limit);
+}
+
+#[tokio::test]
+async fn test_transactions_that_cannot_be_charged_are_not_packed() {
+    let keypair = dev_keypairs(1).unwrap().remove(0);
+    let engine = ExecutionEngine::new();
+    engine
+        .prefund_accounts(&[(keypair.address, U256::from(ONE_TOKEN))])
+        .await;
+
+    let mut underpriced = hashed(transfer(alice(), alice(), 0, 1_000));
+    underpriced.from = keypair.address;
+    underpriced.gas_price = U256::from(1);
+    underpriced.sign(&keypair).await.unwrap();
+    let mut to_self = underpriced.clone();
+    to_self.gas_price = U256::from(TO_GWEI);
+    to_self.to = keypair.address;
+    to_self.sign(&keypair).await.unwrap();
+
+    let packed = engine
+        .simulate_execute_block(&mut [underpriced, to_self])
+        .await
+        .unwrap();
+    assert!(packed.is_empty());
+}
@@ -0,0 +1,48 @@
+use alloy::primitives::U256;
+use speed_blockchain::{KeyPair, Transaction, rpc::FeeProtection, rpc::ProtectionError};
+
+use super::helpers::{TO_GWEI, signed_transfer_at};
+
+// a signed transfer at `gwei` per gas
+async fn transfer(gwei: u64) -> Transaction {
+    signed_transfer_at(&KeyPair::generate("alice".into()), 0, gwei).await
+}
+
+#[tokio::test]
+async fn test_reasonable_fee_passes() {
+    let tx = transfer(2).await;
+    let violations = FeeProtection::default().check(&tx, U256::from(TO_GWEI), U256::MAX);
+
+    assert!(violations.is_empty());
+}
+
+#[tokio::test]
+async fn test_gas_price_above_multiplier_is_flagged() {
+    let tx = transfer(50).await;
+    let violations = FeeProtection::new(10).check(&tx, U256::from(TO_GWEI), U256::MAX);
+
+    assert!(matches!(
+        violations.as_slice(),
+        [ProtectionError::GasPriceTooHigh { multiplier: 10, .. }]
+    ));
+}
+
+#[tokio::test]
+async fn test_cost_above_balance_is_flagged() {
+    let tx = transfer(1).await;
+    let violations = FeeProtection::default().check(&tx, U256::from(TO_GWEI), U256::from(1));
+
+    assert!(matches!(
+        violations.as_slice(),
+        [ProtectionError::ExceedsBalance { .. }]
+    ));
+}
+
+#[tokio::test]
+async fn test_raw_transaction_roundtrip() {
+    let tx = transfer(1).await;
+    let decoded = Transaction::decode_raw(&tx.encode_raw()).unwrap();
+
+    assert_eq!(decoded.hash, tx.hash);
+    assert!(decoded.is_signature_valid());
+}
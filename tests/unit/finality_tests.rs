@@ -0,0 +1,172 @@
+use alloy::primitives::B256;
+use speed_blockchain::{
+    Attestation, AttestationVote, KeyPair,
+    consensus::{
+        CertifiedValidator, FinalityCertificate, FinalityError, Quorum, attestation_hash,
+        validator_set_commitment,
+    },
+    core::BlockHeader,
+};
+
+fn validators(keys: &[KeyPair]) -> Vec<CertifiedValidator> {
+    keys.iter()
+        .map(|key| CertifiedValidator {
+            address: key.address,
+            stake: 1000,
+        })
+        .collect()
+}
+
+async fn signed_header(proposer: &KeyPair) -> BlockHeader {
+    let mut header = BlockHeader::new(
+        7,
+        3,
+        proposer.address,
+        B256::repeat_byte(0x11),
+        B256::ZERO,
+        B256::repeat_byte(0x22),
+    );
+    header.sign(proposer).await.unwrap();
+    header
+}
+
+async fn vote(key: &KeyPair, block_hash: &B256, vote: AttestationVote) -> Attestation {
+    let signature = key
+        .sign_hash(&attestation_hash(block_hash, &vote))
+        .await
+        .unwrap();
+    Attestation {
+        validator_id: key.address,
+        vote,
+        signature,
+    }
+}
+
+fn keys() -> Vec<KeyPair> {
+    ["finality-a", "finality-b", "finality-c", "finality-d"]
+        .into_iter()
+        .map(|name| KeyPair::generate(name.to_string()))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_certificate_needs_two_thirds_of_the_set() {
+    let keys = keys();
+    let header = signed_header(&keys[0]).await;
+    let hash = header.hash();
+
+    // proposer + one vote is 2 of the 3 needed out of 4
+    let one = vec![vote(&keys[1], &hash, AttestationVote::Accept).await];
+    assert!(
+        FinalityCertificate::assemble(header.clone(), &one, validators(&keys), Quorum::default())
+            .is_none()
+    );
+
+    let mut votes = one;
+    // rejects, duplicates and outsiders don't count
+    votes.push(
+        vote(
+            &keys[2],
+            &hash,
+            AttestationVote::Reject {
+                reason: "late".to_string(),
+            },
+        )
+        .await,
+    );
+    votes.push(vote(&keys[1], &hash, AttestationVote::Accept).await);
+    let outsider = KeyPair::generate("finality-outsider".to_string());
+    votes.push(vote(&outsider, &hash, AttestationVote::Accept).await);
+    assert!(
+        FinalityCertificate::assemble(header.clone(), &votes, validators(&keys), Quorum::default())
+            .is_none()
+    );
+
+    votes.push(vote(&keys[3], &hash, AttestationVote::Accept).await);
+    let certificate =
+        FinalityCertificate::assemble(header, &votes, validators(&keys), Quorum::default())
+            .expect("quorum reached");
+
+    assert_eq!(certificate.block_hash, hash);
+    assert_eq!(certificate.attestations.len(), 2);
+    assert_eq!(
+        certificate.validator_set_commitment,
+        validator_set_commitment(&validators(&keys))
+    );
+    assert_eq!(certificate.verify(), Ok(()));
+
+    // survives the round trip an external verifier makes
+    let json = serde_json::to_string(&certificate).unwrap();
+    let decoded: FinalityCertificate = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded.verify(), Ok(()));
+}
+
+#[tokio::test]
+async fn test_tampered_certificate_fails_verification() {
+    let keys = keys();
+    let header = signed_header(&keys[0]).await;
+    let hash = header.hash();
+    let mut votes = Vec::new();
+    for key in &keys[1..] {
+        votes.push(vote(key, &hash, AttestationVote::Accept).await);
+    }
+    let certificate =
+        FinalityCertificate::assemble(header, &votes, validators(&keys), Quorum::default())
+            .unwrap();
+
+    let mut altered = certificate.clone();
+    altered.header.state_root = B256::repeat_byte(0x33);
+    assert!(matches!(
+        altered.verify(),
+        Err(FinalityError::HashMismatch { .. })
+    ));
+
+    let mut inflated = certificate.clone();
+    inflated.validators[0].stake += 1;
+    assert_eq!(inflated.verify(), Err(FinalityError::CommitmentMismatch));
+
+    let mut forged = certificate.clone();
+    forged.attestations[0].validator = keys[0].address;
+    assert!(matches!(
+        forged.verify(),
+        Err(FinalityError::InvalidAttestation(_))
+    ));
+
+    let mut short = certificate;
+    short.attestations.truncate(1);
+    assert_eq!(
+        short.verify(),
+        Err(FinalityError::NoQuorum {
+            stake: 2000,
+            quorum: 2667
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_quorum_is_weighted_by_stake() {
+    let keys = keys();
+    let mut set = validators(&keys);
+    set[0].stake = 7000;
+    let header = signed_header(&keys[0]).await;
+    let hash = header.hash();
+
+    // the proposer alone holds 7000 of 10000
+    let certificate =
+        FinalityCertificate::assemble(header.clone(), &[], set.clone(), Quorum::default()).unwrap();
+    assert!(certificate.attestations.is_empty());
+
+    // three of four validators hold less than 2/3 without it
+    set[0].stake = 1000;
+    set[3].stake = 7000;
+    let mut votes = Vec::new();
+    for key in &keys[1..3] {
+        votes.push(vote(key, &hash, AttestationVote::Accept).await);
+    }
+    assert!(
+        FinalityCertificate::assemble(header.clone(), &votes, set.clone(), Quorum::default())
+            .is_none()
+    );
+    votes.push(vote(&keys[3], &hash, AttestationVote::Accept).await);
+    assert!(FinalityCertificate::assemble(header, &votes, set, Quorum::default()).is_some());
+}
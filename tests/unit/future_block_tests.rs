@@ -0,0 +1,153 @@
+use alloy::primitives::{Address, B256, U256};
+use alloy_signer::Signature;
+use speed_blockchain::core::{
+    Block, BlockHeader, FutureBlock, FutureBlockConfig, FutureBlockQueue,
+};
+use speed_blockchain::{BlockProcessResult, Blockchain, KeyPair, MIN_STAKE};
+use std::time::Duration;
+
+// signed empty block with the current state root
+async fn signed_block(
+    blockchain: &Blockchain,
+    proposer: &KeyPair,
+    index: u64,
+    slot: u64,
+    parent_hash: B256,
+) -> (Block, Signature) {
+    let state_root = blockchain
+        .execution_engine
+        .state_manager
+        .lock()
+        .await
+        .get_state_root();
+    let mut header = BlockHeader::new(
+        index,
+        slot,
+        proposer.address,
+        parent_hash,
+        Block::calculate_transactions_root(&[]),
+        state_root,
+    );
+    header.sign(proposer).await.unwrap();
+    let signature = header.validator_signature.unwrap();
+    (Block::new(header, Vec::new()), signature)
+}
+
+fn held(index: u64, slot: u64, parent_hash: B256) -> FutureBlock {
+    let header = BlockHeader::new(
+        index,
+        slot,
+        Address::repeat_byte(1),
+        parent_hash,
+        Block::calculate_transactions_root(&[]),
+        B256::ZERO,
+    );
+    FutureBlock {
+        block: Block::new(header, Vec::new()),
+        proposer_id: Address::repeat_byte(1),
+        signature: Signature::new(U256::ZERO, U256::ZERO, false),
+    }
+}
+
+#[test]
+fn test_queue_releases_children_of_known_parents_once_their_slot_started() {
+    let mut queue = FutureBlockQueue::new(2);
+    let parent = B256::repeat_byte(1);
+    assert!(queue.insert(held(2, 5, parent)));
+    assert!(
+        queue.insert(held(2, 5, parent)),
+        "a held block is not held twice"
+    );
+    assert!(queue.insert(held(3, 6, B256::repeat_byte(2))));
+    assert!(
+        !queue.insert(held(4, 7, B256::repeat_byte(3))),
+        "queue is full"
+    );
+    assert_eq!(queue.len(), 2);
+
+    // slot not started, then parent unknown
+    assert!(queue.take_ready(4, |_| true).is_empty());
+    assert!(queue.take_ready(5, |hash| *hash != parent).is_empty());
+    let ready = queue.take_ready(5, |hash| *hash == parent);
+    assert_eq!(ready.len(), 1);
+    assert_eq!(ready[0].block.header.index, 2);
+
+    // the orphan waited too long for its parent
+    assert_eq!(queue.prune(7), 1);
+    assert!(queue.is_empty());
+}
+
+#[tokio::test]
+async fn test_early_and_orphaned_blocks_are_held_and_replayed() {
+    let dir = tempfile::tempdir().unwrap();
+    let proposer = KeyPair::generate("future-block-proposer".to_string());
+    // one second slots, the chain starts at slot 0
+    let blockchain = Blockchain::new(
+        dir.path().to_str().unwrap(),
+        MIN_STAKE,
+        1,
+        vec![(proposer.address, 200)],
+        None,
+    )
+    .unwrap()
+    .with_future_blocks(FutureBlockConfig::default());
+
+    // too far ahead of our clock
+    let (block, signature) = signed_block(&blockchain, &proposer, 1, 10, B256::ZERO).await;
+    assert!(matches!(
+        blockchain
+            .process_received_block(block, proposer.address, signature)
+            .await
+            .unwrap(),
+        BlockProcessResult::Rejected(_, _)
+    ));
+
+    // next slot's block is held until the slot starts
+    let (first, signature) = signed_block(&blockchain, &proposer, 1, 1, B256::ZERO).await;
+    let first_hash = first.header.hash();
+    assert!(matches!(
+        blockchain
+            .process_received_block(first, proposer.address, signature)
+            .await
+            .unwrap(),
+        BlockProcessResult::Queued(hash, _) if hash == first_hash
+    ));
+    assert!(blockchain.release_future_blocks().await.unwrap().is_empty());
+    assert_eq!(blockchain.held_block_count().await, 1);
+
+    tokio::time::sleep(Duration::from_millis(2_100)).await;
+    let ready = blockchain.release_future_blocks().await.unwrap();
+    assert_eq!(ready.len(), 1);
+    for future in ready {
+        assert!(matches!(
+            blockchain
+                .process_received_block(future.block, future.proposer_id, future.signature)
+                .await
+                .unwrap(),
+            BlockProcessResult::Accepted(_)
+        ));
+    }
+
+    // #3 arrives before #2 and waits for it
+    let (second, second_signature) = signed_block(&blockchain, &proposer, 2, 2, first_hash).await;
+    let (third, signature) = signed_block(&blockchain, &proposer, 3, 2, second.header.hash()).await;
+    assert!(matches!(
+        blockchain
+            .process_received_block(third, proposer.address, signature)
+            .await
+            .unwrap(),
+        BlockProcessResult::Queued(_, _)
+    ));
+    assert!(blockchain.release_future_blocks().await.unwrap().is_empty());
+    assert!(matches!(
+        blockchain
+            .process_received_block(second, proposer.address, second_signature)
+            .await
+            .unwrap(),
+        BlockProcessResult::Accepted(_)
+    ));
+    let ready = blockchain.release_future_blocks().await.unwrap();
+    assert_eq!(ready.len(), 1);
+    assert_eq!(ready[0].block.header.index, 3);
+    assert_eq!(blockchain.held_block_count().await, 0);
+}
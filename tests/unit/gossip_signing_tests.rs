@@ -0,0 +1,31 @@
+use libp2p::{gossipsub::ValidationMode, identity::Keypair};
+use speed_blockchain::{GossipSigning, NodeConfig, transaction_gossipsub};
+
+#[test]
+fn test_transaction_gossip_is_signed_by_default() {
+    let config: NodeConfig = serde_json::from_str("{}").unwrap();
+    assert_eq!(config.gossip_signing.transactions, GossipSigning::Signed);
+    assert!(matches!(
+        GossipSigning::Signed.validation_mode(),
+        ValidationMode::Strict
+    ));
+
+    let config: NodeConfig =
+        serde_json::from_str(r#"{"gossip_signing": {"transactions": "anonymous"}}"#).unwrap();
+    assert_eq!(config.gossip_signing.transactions, GossipSigning::Anonymous);
+}
+
+#[test]
+fn test_anonymous_relay_publishes_unsigned_and_accepts_both() {
+    let key = Keypair::generate_ed25519();
+    let signing = GossipSigning::Anonymous;
+    assert!(!signing.authenticity(&key).is_signing());
+    assert!(matches!(
+        signing.validation_mode(),
+        ValidationMode::Permissive
+    ));
+
+    // both policies build a valid transaction gossip behaviour
+    assert!(transaction_gossipsub(&key, GossipSigning::Signed).is_ok());
+    assert!(transaction_gossipsub(&key, GossipSigning::Anonymous).is_ok());
+}
@@ -0,0 +1,64 @@
+use speed_blockchain::consensus::ConsensusGenesis;
+use speed_blockchain::core::PerformanceTracker;
+use speed_blockchain::storage::MemoryStore;
+use speed_blockchain::{Blockchain, KeyPair, MIN_STAKE, SLOT_DURATION, Storage};
+use std::time::{Duration, SystemTime};
+
+// 100ms into slot 2
+fn blockchain(validator: &KeyPair, local: &KeyPair) -> Blockchain {
+    let genesis_time = SystemTime::now() - Duration::from_millis(2 * SLOT_DURATION * 1_000 + 100);
+    let storage = Storage::with_backend(MemoryStore::new());
+    storage
+        .put_consensus_genesis(&ConsensusGenesis::new(genesis_time, [1u8; 32]))
+        .unwrap();
+    Blockchain::from_storage(
+        storage,
+        MIN_STAKE,
+        SLOT_DURATION,
+        vec![(validator.address, MIN_STAKE)],
+        Some(local.clone()),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_proposal_window_covers_the_start_of_our_slots_only() {
+    let proposer = KeyPair::generate("throttle-proposer".to_string());
+    let chain = blockchain(&proposer, &proposer);
+
+    let left = chain
+        .proposal_window_left(Duration::from_secs(1))
+        .await
+        .unwrap();
+    assert!(left > Duration::ZERO && left <= Duration::from_millis(900));
+    // the window is already over
+    assert!(
+        chain
+            .proposal_window_left(Duration::from_millis(50))
+            .await
+            .is_none()
+    );
+
+    // never scheduled, never throttled
+    let follower = KeyPair::generate("throttle-follower".to_string());
+    let chain = blockchain(&proposer, &follower);
+    assert!(
+        chain
+            .proposal_window_left(Duration::from_secs(1))
+            .await
+            .is_none()
+    );
+}
+
+#[test]
+fn test_deferred_gossip_is_sampled_per_window() {
+    let mut tracker = PerformanceTracker::new(4);
+    for deferred in [3, 12, 7] {
+        tracker.record_deferred_gossip(deferred);
+    }
+
+    let samples = tracker.samples();
+    assert_eq!(samples.deferred_gossip.samples, 3);
+    assert_eq!(samples.deferred_gossip.p50, 7);
+    assert_eq!(samples.deferred_gossip.max, 12);
+}
@@ -0,0 +1,191 @@
+use alloy::primitives::{Address, B256, Bytes, U256};
+use alloy_signer::Signature;
+use speed_blockchain::consensus::ConsensusGenesis;
+use speed_blockchain::core::{Block, BlockHeader};
+use speed_blockchain::{
+    BlockProcessResult, Blockchain, CHAIN_ID, ExecutionResult, GasCalculator, GasConfig, KeyPair,
+    MIN_STAKE, SLOT_DURATION, StateManager, Storage, Transaction, TransactionKind,
+};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const TO_GWEI: u64 = 1_000_000_000;
+pub const ONE_TOKEN: u64 = 1_000_000_000_000_000_000;
+
+// stores a genesis `slots` slots in the past, so blocks up to that slot have started
+pub fn genesis_slots_ago(path: &str, slots: u64) {
+    let genesis_time = SystemTime::now() - Duration::from_secs(slots * SLOT_DURATION);
+    Storage::new(path)
+        .unwrap()
+        .put_consensus_genesis(&ConsensusGenesis::new(genesis_time, [1u8; 32]))
+        .unwrap();
+}
+
+// unix seconds inside `slot`, a timestamp its blocks can carry
+pub async fn slot_time(blockchain: &Blockchain, slot: u64) -> u64 {
+    let start = blockchain.consensus_engine.lock().await.slot_start(slot);
+    start.duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+// 1000 wei to 0xbb..bb at 1 gwei, signed by `keypair`
+pub async fn signed_transfer(keypair: &KeyPair, nonce: u64) -> Transaction {
+    signed_transfer_at(keypair, nonce, 1).await
+}
+
+// the same transfer at `gwei` per gas
+pub async fn signed_transfer_at(keypair: &KeyPair, nonce: u64, gwei: u64) -> Transaction {
+    let mut tx = Transaction {
+        gas_price: U256::from(gwei * TO_GWEI),
+        ..transfer(keypair.address, Address::repeat_byte(0xbb), nonce, 1_000)
+    };
+    tx.sign(keypair).await.unwrap();
+    tx
+}
+
+// unsigned zero value transfer on the default chain, tests set the fields they care about
+// with `Transaction { nonce, ..unsigned_transaction() }`, a new optional field defaults here
+pub fn unsigned_transaction() -> Transaction {
+    Transaction {
+        from: Address::ZERO,
+        to: Address::ZERO,
+        amount: U256::ZERO,
+        timestamp: 1,
+        nonce: 0,
+        chain_id: CHAIN_ID,
+        kind: TransactionKind::Transfer,
+        gas_limit: U256::ZERO,
+        gas_price: U256::ZERO,
+        max_priority_fee_per_gas: None,
+        memo: Bytes::new(),
+        data: Bytes::new(),
+        signature: Signature::new(U256::ZERO, U256::ZERO, false),
+        hash: B256::ZERO,
+    }
+}
+
+// `amount` wei from `from` to `to` with 30k gas at 1 gwei, unsigned and unhashed, tests
+// override the rest with `Transaction { gas_price, ..transaction(..) }`
+pub fn transaction(
+    from: Address,
+    to: Address,
+    nonce: u64,
+    amount: u64,
+    kind: TransactionKind,
+) -> Transaction {
+    Transaction {
+        from,
+        to,
+        amount: U256::from(amount),
+        nonce,
+        kind,
+        gas_limit: U256::from(30_000),
+        gas_price: U256::from(TO_GWEI),
+        ..unsigned_transaction()
+    }
+}
+
+pub fn transfer(from: Address, to: Address, nonce: u64, amount: u64) -> Transaction {
+    transaction(from, to, nonce, amount, TransactionKind::Transfer)
+}
+
+// 5 wei from 0x01..01 with nonce `id` and hash 0x`id`..`id`, for tests that store or pack
+// transactions without running them
+pub fn numbered_transfer(id: u8) -> Transaction {
+    let (from, to) = (Address::repeat_byte(1), Address::repeat_byte(2));
+    Transaction {
+        gas_price: U256::from(1),
+        hash: B256::repeat_byte(id),
+        ..transfer(from, to, id as u64, 5)
+    }
+}
+
+// `tx` with exactly its intrinsic gas under `config` as the limit
+pub fn with_intrinsic_gas(mut tx: Transaction, config: &GasConfig) -> Transaction {
+    tx.gas_limit = GasCalculator::calculate_instrinsic_gas(&tx, config);
+    tx
+}
+
+// `tx` with its hash set, for tests that look it up without signing it
+pub fn hashed(mut tx: Transaction) -> Transaction {
+    tx.hash = tx.calculate_hash();
+    tx
+}
+
+// what the sender pays for the intrinsic gas under the default gas config
+pub fn intrinsic_fee(tx: &Transaction) -> U256 {
+    GasCalculator::calculate_instrinsic_gas(tx, &GasConfig::default()) * tx.gas_price
+}
+
+// a fresh state holding `balances`, in wei
+pub fn funded_state(balances: &[(Address, u64)]) -> StateManager {
+    let mut state = StateManager::new();
+    for (address, balance) in balances {
+        state.fund_account(address, U256::from(*balance));
+    }
+    state
+}
+
+// a chain in `path` whose only validator is `proposer`, with `balances` prefunded
+pub async fn funded_chain(
+    path: &str,
+    proposer: Address,
+    balances: &[(Address, U256)],
+) -> Blockchain {
+    let blockchain =
+        Blockchain::new(path, MIN_STAKE, SLOT_DURATION, vec![(proposer, 200)], None).unwrap();
+    blockchain.execution_engine.prefund_accounts(balances).await;
+    blockchain
+}
+
+// block #1 over `transactions` as `proposer` would build it on the current state, unsigned
+pub async fn dry_run_block(
+    blockchain: &Blockchain,
+    proposer: Address,
+    transactions: Vec<Transaction>,
+) -> (Block, ExecutionResult) {
+    dry_run_block_at(blockchain, proposer, (1, 1), B256::ZERO, transactions).await
+}
+
+// the same for block `(index, slot)` on `parent_hash`, which must be the head
+pub async fn dry_run_block_at(
+    blockchain: &Blockchain,
+    proposer: Address,
+    (index, slot): (u64, u64),
+    parent_hash: B256,
+    transactions: Vec<Transaction>,
+) -> (Block, ExecutionResult) {
+    let mut header = BlockHeader::new(
+        index,
+        slot,
+        proposer,
+        parent_hash,
+        Block::calculate_transactions_root(&transactions),
+        B256::ZERO,
+    );
+    header.gas_limit = Some(blockchain.execution_engine.gas_config().block_gas_limit);
+    header.base_fee_per_gas = blockchain.execution_engine.next_base_fee();
+    let mut block = Block::new(header, transactions);
+    let result = blockchain
+        .execution_engine
+        .execute_block_dry_run(&mut block)
+        .await
+        .unwrap();
+    block.header.state_root = result.state_root;
+    block.header.receipts_root = Some(result.receipts_root);
+    block.header.logs_bloom = Some(result.logs_bloom);
+    block.header.gas_used = Some(result.total_gas_used);
+    (block, result)
+}
+
+// sign `block` as `proposer` and hand it to the chain as if a peer sent it
+pub async fn import_signed(
+    blockchain: &Blockchain,
+    proposer: &KeyPair,
+    mut block: Block,
+) -> BlockProcessResult {
+    block.header.sign(proposer).await.unwrap();
+    let signature = block.header.validator_signature.unwrap();
+    blockchain
+        .process_received_block(block, proposer.address, signature)
+        .await
+        .unwrap()
+}
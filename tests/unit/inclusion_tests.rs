@@ -0,0 +1,56 @@
+use alloy::primitives::{Address, B256, U256};
+use speed_blockchain::{Transaction, packing_order, simulate_inclusion};
+
+use super::helpers::{TO_GWEI, transfer};
+
+fn pending(sender: u8, nonce: u64, gas_price: u64, id: u8) -> Transaction {
+    Transaction {
+        gas_limit: U256::from(21_000),
+        gas_price: U256::from(gas_price * TO_GWEI),
+        hash: B256::repeat_byte(id),
+        ..transfer(
+            Address::repeat_byte(sender),
+            Address::repeat_byte(0xff),
+            nonce,
+            1,
+        )
+    }
+}
+
+#[test]
+fn test_higher_gas_price_packed_first_within_nonce_order() {
+    let txs = vec![
+        pending(1, 0, 1, 1),
+        pending(1, 1, 50, 2), // must wait for nonce 0
+        pending(2, 0, 10, 3),
+    ];
+
+    let order: Vec<B256> = packing_order(&txs).iter().map(|tx| tx.hash).collect();
+    assert_eq!(
+        order,
+        vec![
+            B256::repeat_byte(3),
+            B256::repeat_byte(1),
+            B256::repeat_byte(2)
+        ]
+    );
+}
+
+#[test]
+fn test_cheap_transaction_waits_for_later_blocks() {
+    // room for two transactions per block
+    let block_gas_limit = U256::from(42_000);
+    let txs = vec![
+        pending(1, 0, 5, 1),
+        pending(2, 0, 5, 2),
+        pending(3, 0, 5, 3),
+        pending(4, 0, 1, 4),
+    ];
+
+    let estimate = simulate_inclusion(&txs, &B256::repeat_byte(4), block_gas_limit).unwrap();
+    assert_eq!(estimate.blocks_until_inclusion, 2);
+    assert_eq!(estimate.transactions_ahead, 3);
+    assert_eq!(estimate.gas_price_percentile, 0);
+
+    assert!(simulate_inclusion(&txs, &B256::repeat_byte(9), block_gas_limit).is_none());
+}
@@ -0,0 +1,154 @@
+use alloy::primitives::{Address, B256, U256};
+use speed_blockchain::consensus::{EpochStatement, liveness_records};
+use speed_blockchain::core::{Block, BlockHeader, ConsensusEventKind};
+use speed_blockchain::{
+    BlockProcessResult, Blockchain, KeyPair, LivenessConfig, MIN_STAKE, SLOT_DURATION,
+    SLOTS_PER_EPOCH,
+};
+
+use super::helpers::{genesis_slots_ago, slot_time};
+
+fn statement(validator: u8, made: u64, missed: u64, attestations: u64) -> EpochStatement {
+    EpochStatement {
+        validator: Address::repeat_byte(validator),
+        epoch: 3,
+        start_slot: 24,
+        end_slot: 31,
+        proposals_made: made,
+        proposals_missed: missed,
+        attestations_included: attestations,
+        rewards: U256::ZERO,
+        penalties: U256::ZERO,
+        end_stake: 100,
+    }
+}
+
+#[test]
+fn test_offline_validators_missed_more_than_the_allowed_share() {
+    let config = LivenessConfig {
+        enabled: true,
+        ..LivenessConfig::default()
+    };
+    // 6 blocks in the epoch
+    let records = liveness_records(
+        &[
+            statement(1, 3, 1, 2),
+            statement(2, 1, 2, 5),
+            statement(3, 2, 2, 0),
+            statement(4, 0, 1, 0),
+        ],
+        6,
+    );
+    assert_eq!(records[1].proposal_duties, 3);
+    assert_eq!(
+        (
+            records[1].attestation_duties,
+            records[1].attestations_missed
+        ),
+        (5, 0)
+    );
+    let offline = |config: &LivenessConfig| -> Vec<bool> {
+        records.iter().map(|r| r.is_offline(config)).collect()
+    };
+    // half missed is not more than half, one duty is too few to judge
+    assert_eq!(offline(&config), vec![false, true, false, false]);
+
+    // with votes counted, validator 3 missed 2 proposals and all 4 votes
+    let with_votes = LivenessConfig {
+        count_attestations: true,
+        ..config.clone()
+    };
+    assert_eq!(offline(&with_votes), vec![false, false, true, true]);
+}
+
+#[tokio::test]
+async fn test_validator_missing_its_slots_is_deactivated_at_epoch_close() {
+    let dir = tempfile::tempdir().unwrap();
+    let keys = [
+        KeyPair::generate("liveness-online".to_string()),
+        KeyPair::generate("liveness-offline".to_string()),
+    ];
+    genesis_slots_ago(dir.path().to_str().unwrap(), 2 * SLOTS_PER_EPOCH);
+    let blockchain = Blockchain::new(
+        dir.path().to_str().unwrap(),
+        MIN_STAKE,
+        SLOT_DURATION,
+        keys.iter().map(|key| (key.address, 200)).collect(),
+        None,
+    )
+    .unwrap()
+    .with_liveness(LivenessConfig {
+        enabled: true,
+        max_missed_percent: 50,
+        min_duties: 1,
+        count_attestations: false,
+    });
+    let state_root = blockchain
+        .execution_engine
+        .state_manager
+        .lock()
+        .await
+        .get_state_root();
+    let online = keys[0].clone();
+    let offline = keys[1].address;
+
+    // only the online validator proposes, through epoch 0 and into epoch 1
+    let mut parent_hash = B256::ZERO;
+    let mut index = 0;
+    let mut offline_slots = 0;
+    for slot in 1..2 * SLOTS_PER_EPOCH {
+        let proposer = blockchain
+            .consensus_engine
+            .lock()
+            .await
+            .scheduled_proposer(slot)
+            .unwrap();
+        if proposer != online.address {
+            offline_slots += (slot < SLOTS_PER_EPOCH) as u64;
+            continue;
+        }
+        index += 1;
+        let mut header = BlockHeader::new(
+            index,
+            slot,
+            proposer,
+            parent_hash,
+            Block::calculate_transactions_root(&[]),
+            state_root,
+        );
+        header.timestamp = slot_time(&blockchain, slot).await;
+        header.sign(&online).await.unwrap();
+        let signature = header.validator_signature.unwrap();
+        parent_hash = header.hash();
+        let result = blockchain
+            .process_received_block(Block::new(header, Vec::new()), proposer, signature)
+            .await
+            .unwrap();
+        assert!(matches!(result, BlockProcessResult::Accepted(_)));
+        if slot >= SLOTS_PER_EPOCH {
+            break;
+        }
+    }
+    assert!(
+        offline_slots > 0,
+        "the offline validator had no slot in epoch 0"
+    );
+    assert!(index > 1, "epoch 1 had no slot for the online validator");
+
+    let consensus = blockchain.consensus_engine.lock().await;
+    assert!(!consensus.validator_set().is_active_validator(&offline));
+    assert!(
+        consensus
+            .validator_set()
+            .is_active_validator(&online.address)
+    );
+    // its stake stays, only the schedule stops picking it
+    assert_eq!(consensus.validator_set().stake_of(&offline), 200);
+    drop(consensus);
+
+    let events = blockchain.debug_state(20).await.events;
+    assert!(events.iter().any(|event| matches!(
+        event.kind,
+        ConsensusEventKind::Deactivated { validator, .. } if validator == offline
+    )));
+}
@@ -0,0 +1,118 @@
+use alloy::primitives::{Address, B256, Bytes};
+use speed_blockchain::{
+    COMMITMENTS_PRECOMPILE, GasConfig, StateManager, StateTransition, StateTransitionError,
+    Transaction, TransactionKind, memo_commitment, precompile_address,
+};
+
+use super::helpers::{ONE_TOKEN, funded_state, transaction, with_intrinsic_gas};
+
+fn alice() -> Address {
+    Address::repeat_byte(0xaa)
+}
+
+fn bob() -> Address {
+    Address::repeat_byte(0xbb)
+}
+
+fn call(from: Address, nonce: u64, input: Vec<u8>) -> Transaction {
+    let kind = TransactionKind::Call {
+        input: Bytes::from(input),
+    };
+    let to = precompile_address(COMMITMENTS_PRECOMPILE);
+    with_intrinsic_gas(transaction(from, to, nonce, 0, kind), &GasConfig::default())
+}
+
+fn with_prefix(prefix: u8, parts: &[&[u8]]) -> Vec<u8> {
+    let mut input = vec![prefix];
+    for part in parts {
+        input.extend_from_slice(part);
+    }
+    input
+}
+
+fn apply(state: &mut StateManager, mut tx: Transaction) -> Result<Bytes, StateTransitionError> {
+    StateTransition::apply_transaction_with_output(state, &mut tx, &GasConfig::default())
+        .map(|(_, output)| output)
+}
+
+#[test]
+fn test_commit_then_reveal_in_a_later_transaction() {
+    let mut state = funded_state(&[(alice(), ONE_TOKEN), (bob(), ONE_TOKEN)]);
+
+    let salt = B256::repeat_byte(0x5a);
+    let bid = b"bid: 42";
+    let commitment = memo_commitment(&alice(), &salt, bid);
+
+    let root_before = state.get_state_root();
+    apply(
+        &mut state,
+        call(alice(), 0, with_prefix(0, &[commitment.as_slice()])),
+    )
+    .unwrap();
+    assert_ne!(state.get_state_root(), root_before);
+    let status = apply(
+        &mut state,
+        call(bob(), 0, with_prefix(2, &[commitment.as_slice()])),
+    )
+    .unwrap();
+    assert_eq!(status[0], 1);
+    assert_eq!(&status[1..], alice().as_slice());
+
+    // the commitment is bound to alice, bob can't reveal it with the same preimage
+    assert!(matches!(
+        apply(
+            &mut state,
+            call(bob(), 1, with_prefix(1, &[salt.as_slice(), bid])),
+        ),
+        Err(StateTransitionError::PrecompileFailed { .. })
+    ));
+    // nor can alice reveal a different payload
+    assert!(
+        apply(
+            &mut state,
+            call(alice(), 1, with_prefix(1, &[salt.as_slice(), b"bid: 1"])),
+        )
+        .is_err()
+    );
+
+    let revealed = apply(
+        &mut state,
+        call(alice(), 1, with_prefix(1, &[salt.as_slice(), bid])),
+    )
+    .unwrap();
+    assert_eq!(revealed.as_ref(), commitment.as_slice());
+    assert!(state.get_commitment(&commitment).unwrap().revealed);
+
+    // a commitment is revealed once and can't be made again
+    assert!(
+        apply(
+            &mut state,
+            call(alice(), 2, with_prefix(1, &[salt.as_slice(), bid])),
+        )
+        .is_err()
+    );
+    assert!(
+        apply(
+            &mut state,
+            call(bob(), 1, with_prefix(0, &[commitment.as_slice()])),
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn test_unknown_commitments_and_malformed_input() {
+    let mut state = funded_state(&[(alice(), ONE_TOKEN)]);
+
+    let status = apply(
+        &mut state,
+        call(alice(), 0, with_prefix(2, &[B256::ZERO.as_slice()])),
+    )
+    .unwrap();
+    assert_eq!(status.as_ref(), &[0u8; 21]);
+
+    // a commitment must be exactly 32 bytes
+    assert!(apply(&mut state, call(alice(), 1, with_prefix(0, &[&[1u8; 31]]))).is_err());
+    assert!(apply(&mut state, call(alice(), 1, vec![9u8])).is_err());
+    assert_eq!(state.get_nonce(&alice()), 1);
+}
@@ -0,0 +1,86 @@
+use alloy::primitives::{Address, Bytes, U256};
+use speed_blockchain::{
+    Block, ExecutionEngine, GasCalculator, GasConfig, MAX_MEMO_LENGTH, Transaction,
+    cli::parse_memo, core::BlockHeader, dev_keypairs,
+};
+
+use super::helpers::{TO_GWEI, transfer};
+
+fn memo_transfer(from: Address, memo: Bytes) -> Transaction {
+    Transaction {
+        memo,
+        ..transfer(from, Address::repeat_byte(0xbb), 0, 1_000)
+    }
+}
+
+#[tokio::test]
+async fn test_memo_is_signed_and_charged_per_byte() {
+    let config = GasConfig::default();
+    let keypair = dev_keypairs(1).unwrap().remove(0);
+    let plain = memo_transfer(keypair.address, Bytes::new());
+    let mut tagged = memo_transfer(keypair.address, Bytes::from_static(b"deposit-4711"));
+
+    // tag + length + 12 bytes, each also pays the memo byte gas
+    assert_eq!(tagged.encoded_size() - plain.encoded_size(), 17);
+    assert_eq!(
+        GasCalculator::calculate_instrinsic_gas(&tagged, &config)
+            - GasCalculator::calculate_instrinsic_gas(&plain, &config),
+        U256::from(4 * 17 + 16 * 12)
+    );
+
+    tagged.sign(&keypair).await.unwrap();
+    assert_ne!(tagged.hash, plain.calculate_hash());
+    assert!(tagged.is_signature_valid());
+
+    // the memo can't be swapped after signing
+    let mut altered = tagged.clone();
+    altered.memo = Bytes::from_static(b"deposit-4712");
+    assert!(!altered.is_signature_valid());
+
+    // raw submissions carry it through
+    let decoded = Transaction::decode_raw(&tagged.encode_raw()).unwrap();
+    assert_eq!(decoded.memo, tagged.memo);
+    assert!(decoded.is_signature_valid());
+}
+
+#[tokio::test]
+async fn test_memo_lands_in_the_receipt() {
+    let keypair = dev_keypairs(1).unwrap().remove(0);
+    let mut tx = memo_transfer(keypair.address, Bytes::from_static(b"invoice 42"));
+    tx.sign(&keypair).await.unwrap();
+
+    let engine = ExecutionEngine::new();
+    engine
+        .prefund_accounts(&[(keypair.address, U256::from(TO_GWEI) * U256::from(1_000_000))])
+        .await;
+    let mut block = Block::new(BlockHeader::genesis(), vec![tx]);
+    let result = engine.execute_block_commit(&mut block).await.unwrap();
+
+    assert!(result.receipts[0].success);
+    assert_eq!(result.receipts[0].memo, Bytes::from_static(b"invoice 42"));
+}
+
+#[tokio::test]
+async fn test_long_memos_are_refused() {
+    let keypair = dev_keypairs(1).unwrap().remove(0);
+    let mut tx = memo_transfer(keypair.address, Bytes::from(vec![7u8; MAX_MEMO_LENGTH + 1]));
+    tx.sign(&keypair).await.unwrap();
+
+    let engine = ExecutionEngine::new();
+    engine
+        .prefund_accounts(&[(keypair.address, U256::from(TO_GWEI) * U256::from(1_000_000))])
+        .await;
+    let err = engine.add_transaction(&tx).await.unwrap_err();
+    assert!(err.to_string().contains("memo is 65 bytes, max 64"));
+
+    let valid = engine
+        .simulate_execute_block(&mut [tx.clone()])
+        .await
+        .unwrap();
+    assert!(valid.is_empty());
+
+    // the cli refuses before signing
+    assert_eq!(parse_memo("0x0102").unwrap(), Bytes::from_static(&[1, 2]));
+    assert_eq!(parse_memo("ref-1").unwrap(), Bytes::from_static(b"ref-1"));
+    assert!(parse_memo(&"x".repeat(MAX_MEMO_LENGTH + 1)).is_err());
+}
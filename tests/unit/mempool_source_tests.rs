@@ -0,0 +1,45 @@
+use alloy::primitives::{Address, U256};
+use speed_blockchain::{KeyPair, Mempool, Transaction, TransactionSource};
+
+use super::helpers::transfer;
+
+// below the default 1 gwei fee floor
+const CHEAP_GAS_PRICE: u64 = 1_000;
+
+async fn cheap_transfer(keypair: &KeyPair) -> Transaction {
+    let mut tx = Transaction {
+        gas_limit: U256::from(21_000),
+        gas_price: U256::from(CHEAP_GAS_PRICE),
+        ..transfer(keypair.address, Address::repeat_byte(0xff), 0, 1)
+    };
+    tx.sign(keypair).await.unwrap();
+    tx
+}
+
+#[tokio::test]
+async fn test_fee_floor_applies_per_source() {
+    let keypair = KeyPair::generate("alice".to_string());
+    let tx = cheap_transfer(&keypair).await;
+    let mut mempool = Mempool::new(10);
+
+    let peer = TransactionSource::Gossip {
+        peer: "12D3KooW".to_string(),
+    };
+    assert!(mempool.add_transaction_from(&tx, peer).is_err());
+    assert!(
+        mempool
+            .add_transaction_from(&tx, TransactionSource::Rpc)
+            .is_err()
+    );
+
+    // local transactions bypass the fee floor
+    mempool
+        .add_transaction_from(&tx, TransactionSource::Local)
+        .unwrap();
+
+    let content = mempool.content();
+    let pooled = &content.pending[&keypair.address][&0];
+    assert_eq!(pooled.transaction.hash, tx.hash);
+    assert_eq!(pooled.source, TransactionSource::Local);
+    assert!(content.queued.is_empty());
+}
@@ -1 +1,77 @@
 pub mod transaction_tests;
+pub mod fee_protection_tests;
+pub mod allowance_tests;
+pub mod inclusion_tests;
+pub mod dev_accounts_tests;
+pub mod mempool_source_tests;
+pub mod replay_guard_tests;
+pub mod epoch_statement_tests;
+pub mod tx_policy_tests;
+pub mod rpc_tls_tests;
+pub mod propagation_tests;
+pub mod rebroadcast_tests;
+pub mod stake_tests;
+pub mod tx_size_fee_tests;
+pub mod gossip_signing_tests;
+pub mod performance_tests;
+pub mod signature_cache_tests;
+pub mod sync_holding_tests;
+pub mod batch_transfer_tests;
+pub mod finality_tests;
+pub mod peer_stats_tests;
+pub mod peer_tracker_tests;
+pub mod prefetch_tests;
+pub mod memo_tests;
+pub mod watchdog_tests;
+pub mod quarantine_tests;
+pub mod adaptive_slot_tests;
+pub mod beacon_tests;
+pub mod debug_dump_tests;
+pub mod gossip_throttle_tests;
+pub mod name_registry_tests;
+pub mod signing_audit_tests;
+pub mod censorship_tests;
+pub mod chain_verification_tests;
+pub mod precompile_tests;
+pub mod epoch_stats_tests;
+pub mod memo_commitment_tests;
+pub mod session_key_tests;
+pub mod block_template_tests;
+pub mod slashing_tests;
+pub mod block_reward_tests;
+pub mod proposer_fee_tests;
+pub mod validator_registration_tests;
+pub mod validator_exit_tests;
+pub mod proposer_schedule_tests;
+pub mod block_randomness_tests;
+pub mod chain_spec_tests;
+pub mod skipped_slot_tests;
+pub mod reorg_tests;
+pub mod chain_heads_tests;
+pub mod double_vote_tests;
+pub mod validator_set_tests;
+pub mod liveness_tests;
+pub mod consensus_metrics_tests;
+pub mod validator_role_tests;
+pub mod proposer_lookahead_tests;
+pub mod slot_clock_tests;
+pub mod checkpoint_tests;
+pub mod epoch_checkpoint_tests;
+pub mod validator_score_tests;
+pub mod epoch_settlement_tests;
+pub mod consensus_config_tests;
+pub mod future_block_tests;
+pub mod block_timestamp_tests;
+pub mod receipt_lookup_tests;
+pub mod receipts_root_tests;
+pub mod event_log_tests;
+pub mod wasm_contract_tests;
+pub mod base_fee_tests;
+pub mod failed_gas_tests;
+pub mod block_gas_limit_tests;
+pub mod parallel_execution_tests;
+pub mod state_persistence_tests;
+pub mod state_journal_tests;
+pub mod call_tests;
+pub mod capabilities_tests;
+pub mod helpers;
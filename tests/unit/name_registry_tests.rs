@@ -0,0 +1,97 @@
+use alloy::primitives::{Address, U256};
+use speed_blockchain::{
+    GasConfig, ReceiptEvent, StateTransition, StateTransitionError, Transaction, TransactionKind,
+};
+
+use super::helpers::{ONE_TOKEN, funded_state, transaction, with_intrinsic_gas};
+
+fn alice() -> Address {
+    Address::repeat_byte(0xaa)
+}
+
+fn bob() -> Address {
+    Address::repeat_byte(0xbb)
+}
+
+fn named(from: Address, to: Address, nonce: u64, kind: TransactionKind) -> Transaction {
+    with_intrinsic_gas(transaction(from, to, nonce, 0, kind), &GasConfig::default())
+}
+
+fn register(name: &str) -> TransactionKind {
+    TransactionKind::RegisterName {
+        name: name.to_string(),
+    }
+}
+
+#[test]
+fn test_registered_names_resolve_and_move_with_transfers() {
+    let mut state = funded_state(&[(alice(), ONE_TOKEN), (bob(), ONE_TOKEN)]);
+    let config = GasConfig::default();
+
+    let mut tx = named(alice(), Address::ZERO, 0, register("alice-shop"));
+    StateTransition::apply_transaction(&mut state, &mut tx, &config).unwrap();
+    assert_eq!(state.resolve_name("alice-shop"), Some(alice()));
+    assert_eq!(
+        ReceiptEvent::from_transaction(&tx),
+        vec![ReceiptEvent::NameRegistered {
+            name: "alice-shop".to_string(),
+            owner: alice(),
+        }]
+    );
+
+    // first come first served
+    let mut taken = named(bob(), Address::ZERO, 0, register("alice-shop"));
+    assert!(matches!(
+        StateTransition::apply_transaction(&mut state, &mut taken, &config),
+        Err(StateTransitionError::NameTaken(_))
+    ));
+
+    let root = state.get_state_root();
+    let kind = TransactionKind::TransferName {
+        name: "alice-shop".to_string(),
+    };
+    let mut stolen = named(bob(), bob(), 0, kind.clone());
+    assert!(matches!(
+        StateTransition::apply_transaction(&mut state, &mut stolen, &config),
+        Err(StateTransitionError::NotNameOwner(_))
+    ));
+
+    let mut transfer = named(alice(), bob(), 1, kind);
+    StateTransition::apply_transaction(&mut state, &mut transfer, &config).unwrap();
+    assert_eq!(state.resolve_name("alice-shop"), Some(bob()));
+    assert_ne!(state.get_state_root(), root);
+}
+
+#[test]
+fn test_registration_fee_grows_with_the_name() {
+    let config = GasConfig::default();
+    let short = named(alice(), Address::ZERO, 0, register("abc"));
+    let long = named(alice(), Address::ZERO, 0, register("abcdefghij"));
+
+    // seven more bytes in the hash payload and seven more stored characters
+    let difference = long.gas_limit - short.gas_limit;
+    assert_eq!(
+        difference,
+        (config.gas_per_byte + config.name_byte_gas) * U256::from(7)
+    );
+}
+
+#[test]
+fn test_names_that_could_be_misread_are_rejected() {
+    for name in [
+        "ab",
+        "Alice",
+        "-shop",
+        "shop-",
+        "0xabc",
+        "shop.eth",
+        &"a".repeat(33),
+    ] {
+        assert!(
+            StateTransition::validate_name(name).is_err(),
+            "{} should be rejected",
+            name
+        );
+    }
+    assert!(StateTransition::validate_name("alice-2").is_ok());
+}
@@ -0,0 +1,181 @@
+use alloy::primitives::{Address, B256, U256};
+use speed_blockchain::{
+    BaseFeeConfig, Block, ExecutionEngine, ExecutionResult, ExecutionSchedule, GasConfig, KeyPair,
+    ParallelExecutionConfig, Transaction, TransactionKind, TransferOutput, core::BlockHeader,
+    dev_keypairs,
+};
+use std::sync::OnceLock;
+
+use super::helpers::{self, ONE_TOKEN, TO_GWEI, hashed};
+
+// dev keys, so the senders can sign what is executed
+fn keys() -> &'static [KeyPair] {
+    static KEYS: OnceLock<Vec<KeyPair>> = OnceLock::new();
+    KEYS.get_or_init(|| dev_keypairs(10).unwrap())
+}
+
+fn account(n: u8) -> Address {
+    keys()[n as usize].address
+}
+
+fn proposer() -> Address {
+    Address::repeat_byte(0xee)
+}
+
+fn transaction(from: u8, to: u8, nonce: u64, kind: TransactionKind) -> Transaction {
+    hashed(Transaction {
+        gas_price: U256::from(2 * TO_GWEI),
+        max_priority_fee_per_gas: Some(U256::from(TO_GWEI)),
+        ..helpers::transaction(account(from), account(to), nonce, 1_000, kind)
+    })
+}
+
+fn with_amount(mut tx: Transaction, amount: u64) -> Transaction {
+    tx.amount = U256::from(amount);
+    tx.hash = tx.calculate_hash();
+    tx
+}
+
+fn transfer(from: u8, to: u8, nonce: u64) -> Transaction {
+    transaction(from, to, nonce, TransactionKind::Transfer)
+}
+
+async fn execute(mut transactions: Vec<Transaction>, parallel: bool) -> (ExecutionResult, B256) {
+    for tx in &mut transactions {
+        let key = keys().iter().find(|key| key.address == tx.from).unwrap();
+        tx.sign(key).await.unwrap();
+    }
+    let config = GasConfig {
+        base_fee: Some(BaseFeeConfig {
+            initial_base_fee: U256::from(TO_GWEI),
+            ..BaseFeeConfig::default()
+        }),
+        ..GasConfig::default()
+    };
+    let engine = ExecutionEngine::new().with_gas_config(config);
+    let funded: Vec<(Address, U256)> = (1..=8)
+        .map(|n| (account(n), U256::from(ONE_TOKEN)))
+        .collect();
+    engine.prefund_accounts(&funded).await;
+    engine
+        .set_parallel_execution(ParallelExecutionConfig {
+            enabled: parallel,
+            min_transactions: 0,
+        })
+        .await;
+
+    let mut header = BlockHeader::new(1, 1, proposer(), B256::ZERO, B256::ZERO, B256::ZERO);
+    header.base_fee_per_gas = engine.next_base_fee();
+    let mut block = Block::new(header, transactions);
+    let result = engine.execute_block_commit(&mut block).await.unwrap();
+    let root = engine.state_manager.lock().await.computed_state_root();
+    (result, root)
+}
+
+#[test]
+fn test_transactions_are_grouped_by_the_accounts_they_touch() {
+    let outputs = vec![
+        TransferOutput {
+            to: account(6),
+            amount: U256::from(1),
+        },
+        TransferOutput {
+            to: account(9),
+            amount: U256::from(1),
+        },
+    ];
+    let transactions = vec![
+        transfer(1, 2, 0),
+        transfer(3, 4, 0),
+        // joins the first group through 2
+        transfer(2, 5, 0),
+        transaction(6, 0, 0, TransactionKind::BatchTransfer { outputs }),
+        // the allowance is the owner's, so it joins its group
+        transaction(7, 8, 0, TransactionKind::TransferFrom { owner: account(3) }),
+    ];
+    let schedule = ExecutionSchedule::plan(&transactions, proposer()).unwrap();
+    let groups: Vec<Vec<usize>> = schedule
+        .groups
+        .iter()
+        .map(|group| group.transactions.clone())
+        .collect();
+    assert_eq!(groups, vec![vec![0, 2], vec![1, 4], vec![3]]);
+    assert!(schedule.is_parallel());
+    assert!(schedule.groups[2].accounts.contains(&account(9)));
+
+    // paying the proposer from inside a group, or reaching shared state, runs in order
+    let mut to_proposer = transfer(1, 2, 0);
+    to_proposer.to = proposer();
+    assert!(ExecutionSchedule::plan(&[transfer(3, 4, 0), to_proposer], proposer()).is_none());
+    let stake = transaction(1, 1, 0, TransactionKind::StakeTopUp);
+    assert!(ExecutionSchedule::plan(&[transfer(3, 4, 0), stake], proposer()).is_none());
+
+    let chained = ExecutionSchedule::plan(&[transfer(1, 2, 0), transfer(2, 1, 0)], proposer());
+    assert!(!chained.unwrap().is_parallel());
+}
+
+#[tokio::test]
+async fn test_parallel_execution_matches_block_order() {
+    let owner = account(3);
+    let transactions = vec![
+        transfer(1, 2, 0),
+        with_amount(
+            transaction(
+                3,
+                7,
+                0,
+                TransactionKind::Approve {
+                    spender: account(7),
+                    allowance: U256::from(5_000),
+                },
+            ),
+            0,
+        ),
+        transfer(4, 5, 0),
+        transfer(2, 6, 0),
+        transaction(7, 8, 0, TransactionKind::TransferFrom { owner }),
+        // more than what is left of the allowance, fails and still pays its gas
+        with_amount(
+            transaction(7, 8, 1, TransactionKind::TransferFrom { owner }),
+            4_500,
+        ),
+        // a reused nonce, invalid and charged nothing
+        transfer(4, 5, 0),
+        with_amount(
+            transaction(
+                5,
+                0,
+                0,
+                TransactionKind::BatchTransfer {
+                    outputs: vec![TransferOutput {
+                        to: account(4),
+                        amount: U256::from(300),
+                    }],
+                },
+            ),
+            0,
+        ),
+        transfer(1, 2, 1),
+    ];
+
+    let (sequential, sequential_root) = execute(transactions.clone(), false).await;
+    let (parallel, parallel_root) = execute(transactions.clone(), true).await;
+    let schedule = ExecutionSchedule::plan(&transactions, proposer()).unwrap();
+    assert_eq!(schedule.groups.len(), 3);
+
+    assert_eq!(parallel.state_root, sequential.state_root);
+    assert_eq!(parallel_root, sequential_root);
+    assert_eq!(parallel.state_root, parallel_root);
+    assert_eq!(parallel.receipts_root, sequential.receipts_root);
+    assert_eq!(parallel.gas_report, sequential.gas_report);
+    assert_eq!(parallel.total_fees, sequential.total_fees);
+    assert_eq!(parallel.burned_fees, sequential.burned_fees);
+    assert_eq!(parallel.state_diff, sequential.state_diff);
+
+    let successes: Vec<bool> = parallel.receipts.iter().map(|r| r.success).collect();
+    assert_eq!(
+        successes,
+        vec![true, true, true, true, true, false, false, true, true]
+    );
+    assert!(parallel.total_fees > U256::ZERO);
+}
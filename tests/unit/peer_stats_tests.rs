@@ -0,0 +1,75 @@
+use libp2p::PeerId;
+use speed_blockchain::{GossipKind, MessageOutcome, PeerStatsTracker};
+
+#[test]
+fn test_peer_stats_split_by_kind_and_outcome() {
+    let mut tracker = PeerStatsTracker::default();
+    let useful = PeerId::random();
+    let noisy = PeerId::random();
+
+    tracker.record(useful, GossipKind::Block, 1200, MessageOutcome::Forwarded);
+    tracker.record(
+        useful,
+        GossipKind::Attestation,
+        300,
+        MessageOutcome::Forwarded,
+    );
+    tracker.record(useful, GossipKind::Block, 1200, MessageOutcome::Duplicate);
+
+    tracker.record(
+        noisy,
+        GossipKind::Transaction,
+        250,
+        MessageOutcome::Forwarded,
+    );
+    for _ in 0..3 {
+        tracker.record(noisy, GossipKind::Unknown, 40, MessageOutcome::Invalid);
+    }
+
+    let stats = tracker.stats();
+    assert_eq!(stats.len(), 2);
+
+    let useful_stats = stats
+        .iter()
+        .find(|s| s.peer_id == useful.to_string())
+        .unwrap();
+    assert_eq!(useful_stats.total.messages, 3);
+    assert_eq!(useful_stats.total.bytes, 2700);
+    assert_eq!(useful_stats.total.duplicates, 1);
+    assert_eq!(useful_stats.invalid_ratio, 0.0);
+    let blocks = &useful_stats.by_kind[&GossipKind::Block];
+    assert_eq!((blocks.messages, blocks.duplicates), (2, 1));
+
+    let noisy_stats = stats
+        .iter()
+        .find(|s| s.peer_id == noisy.to_string())
+        .unwrap();
+    assert_eq!(noisy_stats.total.invalid, 3);
+    assert_eq!(noisy_stats.invalid_ratio, 0.75);
+    assert_eq!(noisy_stats.by_kind[&GossipKind::Unknown].bytes, 120);
+}
+
+#[test]
+fn test_peer_stats_forget_quietest_peer_when_full() {
+    let mut tracker = PeerStatsTracker::new(2);
+    let busy = PeerId::random();
+    let quiet = PeerId::random();
+
+    tracker.record(busy, GossipKind::Block, 100, MessageOutcome::Forwarded);
+    tracker.record(busy, GossipKind::Block, 100, MessageOutcome::Forwarded);
+    tracker.record(
+        quiet,
+        GossipKind::Transaction,
+        100,
+        MessageOutcome::Forwarded,
+    );
+
+    let newcomer = PeerId::random();
+    tracker.record(newcomer, GossipKind::Sync, 10, MessageOutcome::Invalid);
+
+    assert_eq!(tracker.len(), 2);
+    let peers: Vec<String> = tracker.stats().into_iter().map(|s| s.peer_id).collect();
+    assert!(peers.contains(&busy.to_string()));
+    assert!(peers.contains(&newcomer.to_string()));
+    assert!(!peers.contains(&quiet.to_string()));
+}
@@ -0,0 +1,42 @@
+use libp2p::Multiaddr;
+use speed_blockchain::PeerTracker;
+use std::collections::HashSet;
+
+fn address(host: u8) -> Multiaddr {
+    format!("/ip4/10.0.0.{}/tcp/4001", host).parse().unwrap()
+}
+
+// what the next maintenance tick redials, sorted for comparison
+fn redialed(tracker: &PeerTracker) -> Vec<String> {
+    let mut addresses: Vec<String> = tracker
+        .disconnected_validators()
+        .iter()
+        .map(|address| address.to_string())
+        .collect();
+    addresses.sort();
+    addresses
+}
+
+#[test]
+fn test_rotated_validator_address_stops_being_dialed() {
+    let mut tracker = PeerTracker::new();
+    let dropped = tracker.set_validator_addresses(HashSet::from([address(1), address(2)]));
+    assert!(dropped.is_empty());
+    assert_eq!(
+        redialed(&tracker),
+        vec![address(1).to_string(), address(2).to_string()]
+    );
+
+    // validator 1 rotated to a new address
+    let dropped = tracker.set_validator_addresses(HashSet::from([address(3), address(2)]));
+    assert_eq!(dropped, vec![address(1)]);
+    assert_eq!(
+        redialed(&tracker),
+        vec![address(2).to_string(), address(3).to_string()]
+    );
+
+    // both exited
+    let dropped = tracker.set_validator_addresses(HashSet::new());
+    assert_eq!(dropped.len(), 2);
+    assert!(redialed(&tracker).is_empty());
+}
@@ -0,0 +1,80 @@
+use speed_blockchain::core::{PerformanceArea, PerformanceReport, PerformanceTracker, TuningKnobs};
+use std::time::Duration;
+
+const SLOT: Duration = Duration::from_secs(10);
+
+fn knobs(worker_threads: usize, available_cpus: usize) -> TuningKnobs {
+    TuningKnobs {
+        data_dir: "blockchain_db".to_string(),
+        replay_horizon_slots: 32,
+        worker_threads,
+        available_cpus,
+    }
+}
+
+#[test]
+fn test_samples_are_kept_for_a_bounded_window() {
+    let mut tracker = PerformanceTracker::new(4);
+    for ms in [10, 20, 30, 40, 500] {
+        tracker.record_block_import(Duration::from_millis(ms));
+    }
+    tracker.record_gossip_backlog(3);
+
+    let samples = tracker.samples();
+    // the first sample fell out of the window
+    assert_eq!(samples.block_import.samples, 4);
+    assert_eq!(samples.block_import.p50_ms, 30);
+    assert_eq!(samples.block_import.max_ms, 500);
+    assert_eq!(samples.gossip_backlog.max, 3);
+    assert_eq!(samples.state_write.samples, 0);
+
+    // a healthy node gets no advice
+    let report = PerformanceReport::new(samples, knobs(4, 4), SLOT);
+    assert_eq!(report.slot_duration_ms, 10_000);
+    assert!(report.advice.is_empty());
+}
+
+#[test]
+fn test_bottlenecks_come_with_config_suggestions() {
+    let mut tracker = PerformanceTracker::new(16);
+    for _ in 0..10 {
+        tracker.record_block_import(Duration::from_millis(3_000));
+        tracker.record_state_write(Duration::from_millis(50));
+        tracker.record_gossip_backlog(200);
+    }
+
+    // spare cores: raise the worker count
+    let report = PerformanceReport::new(tracker.samples(), knobs(2, 8), SLOT);
+    let areas: Vec<_> = report.advice.iter().map(|a| a.area).collect();
+    assert_eq!(
+        areas,
+        vec![PerformanceArea::BlockImport, PerformanceArea::GossipBacklog]
+    );
+    assert!(
+        report.advice[0]
+            .suggestion
+            .contains("TOKIO_WORKER_THREADS=8")
+    );
+
+    // every core in use: shed old gossip instead
+    let report = PerformanceReport::new(tracker.samples(), knobs(8, 8), SLOT);
+    assert!(
+        report.advice[1]
+            .suggestion
+            .contains("`replay_horizon_slots` from 32 to 16")
+    );
+
+    // slow writes explain the slow import, only the disk is reported
+    for _ in 0..10 {
+        tracker.record_state_write(Duration::from_millis(2_500));
+    }
+    let report = PerformanceReport::new(tracker.samples(), knobs(8, 8), SLOT);
+    assert_eq!(report.advice[0].area, PerformanceArea::StateWrite);
+    assert!(report.advice[0].suggestion.contains("blockchain_db"));
+    assert!(
+        report
+            .advice
+            .iter()
+            .all(|a| a.area != PerformanceArea::BlockImport)
+    );
+}
@@ -0,0 +1,146 @@
+use alloy::primitives::{Address, Bytes, U256, keccak256};
+use speed_blockchain::{
+    GasCalculator, GasConfig, Keccak256Precompile, KeyPair, PrecompileRegistry, ReceiptEvent,
+    StateTransition, StateTransitionError, Transaction, TransactionKind, precompile_address,
+    precompiles,
+};
+
+use super::helpers::{ONE_TOKEN, TO_GWEI, funded_state, transaction, with_intrinsic_gas};
+
+fn alice() -> Address {
+    Address::repeat_byte(0xaa)
+}
+
+fn call(to: Address, nonce: u64, input: &[u8]) -> Transaction {
+    let kind = TransactionKind::Call {
+        input: Bytes::copy_from_slice(input),
+    };
+    with_intrinsic_gas(
+        transaction(alice(), to, nonce, 0, kind),
+        &GasConfig::default(),
+    )
+}
+
+#[test]
+fn test_builtin_registry_and_reserved_addresses() {
+    let names: Vec<String> = precompiles().list().into_iter().map(|p| p.name).collect();
+    assert_eq!(
+        names,
+        ["keccak256", "ecrecover", "names", "balances", "commitments"]
+    );
+    assert_eq!(
+        precompiles().list()[0].address,
+        "0x0000000000000000000000000000000000000001"
+            .parse::<Address>()
+            .unwrap()
+    );
+
+    let mut registry = PrecompileRegistry::builtin();
+    assert!(
+        registry
+            .register(precompile_address(1), Box::new(Keccak256Precompile))
+            .is_err()
+    );
+    assert!(
+        registry
+            .register(alice(), Box::new(Keccak256Precompile))
+            .is_err()
+    );
+    assert!(
+        registry
+            .register(precompile_address(0x20), Box::new(Keccak256Precompile))
+            .is_ok()
+    );
+}
+
+#[test]
+fn test_call_returns_output_and_charges_precompile_gas() {
+    let mut state = funded_state(&[(alice(), ONE_TOKEN)]);
+    let config = GasConfig::default();
+
+    let mut tx = call(precompile_address(1), 0, b"speed");
+    let (gas_used, output) =
+        StateTransition::apply_transaction_with_output(&mut state, &mut tx, &config).unwrap();
+    assert_eq!(output.as_ref(), keccak256(b"speed").as_slice());
+    // one word of input on top of the transaction bytes
+    let mut plain = tx.clone();
+    plain.to = Address::repeat_byte(0x42);
+    assert_eq!(
+        gas_used,
+        GasCalculator::calculate_instrinsic_gas(&plain, &config) + U256::from(36)
+    );
+    assert_eq!(state.get_nonce(&alice()), 1);
+    assert_eq!(
+        state.get_balance(&alice()),
+        U256::from(ONE_TOKEN) - gas_used * U256::from(TO_GWEI)
+    );
+    assert_eq!(
+        ReceiptEvent::from_transaction(&tx),
+        vec![ReceiptEvent::PrecompileCalled {
+            precompile: precompile_address(1),
+            caller: alice(),
+        }]
+    );
+
+    // the balances precompile reads what the call above left
+    let mut input = vec![0u8];
+    input.extend_from_slice(alice().as_slice());
+    let balance = state.get_balance(&alice());
+    let mut tx = call(precompile_address(4), 1, &input);
+    let (_, output) =
+        StateTransition::apply_transaction_with_output(&mut state, &mut tx, &config).unwrap();
+    assert_eq!(output.as_ref(), &balance.to_be_bytes::<32>());
+}
+
+#[tokio::test]
+async fn test_ecrecover_and_names() {
+    let mut state = funded_state(&[(alice(), ONE_TOKEN)]);
+    let config = GasConfig::default();
+
+    let signer = KeyPair::generate("precompile-signer".to_string());
+    let hash = keccak256(b"message");
+    let signature = signer.sign_hash(&hash).await.unwrap();
+    let mut input = hash.to_vec();
+    input.extend_from_slice(&signature.as_bytes());
+    let mut tx = call(precompile_address(2), 0, &input);
+    let (_, output) =
+        StateTransition::apply_transaction_with_output(&mut state, &mut tx, &config).unwrap();
+    assert_eq!(output.as_ref(), signer.address.as_slice());
+
+    state.set_name_owner("alice-shop".to_string(), alice());
+    let mut tx = call(precompile_address(3), 1, b"alice-shop");
+    let (_, output) =
+        StateTransition::apply_transaction_with_output(&mut state, &mut tx, &config).unwrap();
+    assert_eq!(output.as_ref(), alice().as_slice());
+    let mut tx = call(precompile_address(3), 2, b"nobody");
+    let (_, output) =
+        StateTransition::apply_transaction_with_output(&mut state, &mut tx, &config).unwrap();
+    assert!(output.is_empty());
+}
+
+#[test]
+fn test_reserved_addresses_only_take_calls() {
+    let mut state = funded_state(&[(alice(), ONE_TOKEN)]);
+    let config = GasConfig::default();
+
+    let mut transfer = call(precompile_address(1), 0, &[]);
+    transfer.kind = TransactionKind::Transfer;
+    transfer.amount = U256::from(1);
+    assert!(matches!(
+        StateTransition::apply_transaction(&mut state, &mut transfer, &config),
+        Err(StateTransitionError::InvalidPayload(_))
+    ));
+
+    let mut unknown = call(precompile_address(0x99), 0, &[]);
+    assert!(matches!(
+        StateTransition::apply_transaction(&mut state, &mut unknown, &config),
+        Err(StateTransitionError::NoPrecompile(_))
+    ));
+
+    let mut bad_input = call(precompile_address(4), 0, &[7u8]);
+    assert!(matches!(
+        StateTransition::apply_transaction(&mut state, &mut bad_input, &config),
+        Err(StateTransitionError::PrecompileFailed { .. })
+    ));
+    assert_eq!(state.get_nonce(&alice()), 0);
+}
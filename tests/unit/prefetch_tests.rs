@@ -0,0 +1,77 @@
+use alloy::primitives::{Address, U256};
+use speed_blockchain::account::Account;
+use speed_blockchain::crypto::SignatureCacheStats;
+use speed_blockchain::{ExecutionEngine, KeyPair, PrefetchReport, dev_keypairs};
+
+use super::helpers::{ONE_TOKEN, signed_transfer};
+
+async fn engine_with_pending(keypairs: &[KeyPair]) -> ExecutionEngine {
+    let engine = ExecutionEngine::new();
+    let balances: Vec<(Address, U256)> = keypairs
+        .iter()
+        .map(|k| (k.address, U256::from(ONE_TOKEN)))
+        .collect();
+    engine.prefund_accounts(&balances).await;
+    for keypair in keypairs {
+        for nonce in 0..2 {
+            let tx = signed_transfer(keypair, nonce).await;
+            engine.add_transaction(&tx).await.unwrap();
+        }
+    }
+    engine
+}
+
+#[tokio::test]
+async fn test_prefetch_warms_signatures_and_senders_for_one_block() {
+    let keypairs = dev_keypairs(2).unwrap();
+    let engine = engine_with_pending(&keypairs).await;
+
+    let report = engine.prefetch_for_proposal(9, 3).await;
+    assert_eq!(
+        report,
+        PrefetchReport {
+            slot: 9,
+            transactions: 3,
+            senders: 2,
+            invalid_signatures: 0,
+        }
+    );
+    assert_eq!(engine.prefetched_slot().await, Some(9));
+    // 4 recovered at admission, 3 served from the cache while prefetching
+    assert_eq!(
+        engine.signature_cache().stats(),
+        SignatureCacheStats { hits: 3, misses: 4 }
+    );
+
+    let mut pending = engine.get_pending_transactions().await;
+    pending.sort_by_key(|tx| tx.nonce);
+    let valid = engine.simulate_execute_block(&mut pending).await.unwrap();
+    assert_eq!(valid.len(), 4);
+    // consumed by the block it was taken for
+    assert_eq!(engine.prefetched_slot().await, None);
+}
+
+#[tokio::test]
+async fn test_prefetch_is_ignored_once_the_state_changed() {
+    let keypairs = dev_keypairs(1).unwrap();
+    let engine = engine_with_pending(&keypairs).await;
+    engine.prefetch_for_proposal(3, 16).await;
+
+    // a block lands before our slot and empties the sender
+    {
+        let mut state = engine.state_manager.lock().await;
+        state.set_account(
+            keypairs[0].address,
+            Account {
+                address: keypairs[0].address,
+                balance: U256::ZERO,
+                nonce: 0,
+            },
+        );
+        state.fund_account(&Address::repeat_byte(0xcc), U256::from(1));
+    }
+
+    let mut pending = engine.get_pending_transactions().await;
+    let valid = engine.simulate_execute_block(&mut pending).await.unwrap();
+    assert!(valid.is_empty());
+}
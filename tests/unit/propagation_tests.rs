@@ -0,0 +1,49 @@
+use alloy::primitives::B256;
+use speed_blockchain::{BlockArrival, LatencyPercentiles, PropagationTracker};
+
+fn arrival(hash: u8, peer: &str, since_slot_start_ms: i64) -> BlockArrival {
+    BlockArrival {
+        block_hash: B256::repeat_byte(hash),
+        block_number: hash as u64,
+        slot: hash as u64,
+        peer: peer.to_string(),
+        received_at_ms: 0,
+        since_slot_start_ms,
+        since_proposer_ms: since_slot_start_ms - 100,
+    }
+}
+
+#[test]
+fn test_latency_percentiles_nearest_rank() {
+    let stats = LatencyPercentiles::from_samples((1..=100).rev().collect());
+    assert_eq!(stats.samples, 100);
+    assert_eq!((stats.p50_ms, stats.p90_ms, stats.p99_ms), (50, 90, 99));
+    assert_eq!(stats.max_ms, 100);
+
+    assert_eq!(
+        LatencyPercentiles::from_samples(Vec::new()),
+        LatencyPercentiles::default()
+    );
+}
+
+#[test]
+fn test_tracker_aggregates_per_peer_and_ignores_duplicates() {
+    let mut tracker = PropagationTracker::new(3);
+    assert!(tracker.record(arrival(1, "peer-a", 100)));
+    assert!(tracker.record(arrival(2, "peer-b", 300)));
+    // the same block relayed again by another peer
+    assert!(!tracker.record(arrival(2, "peer-a", 900)));
+    assert!(tracker.record(arrival(3, "peer-a", 200)));
+    // window is full, block 1 is evicted
+    assert!(tracker.record(arrival(4, "peer-b", 500)));
+
+    let stats = tracker.stats(2);
+    assert_eq!(stats.blocks, 3);
+    assert_eq!(stats.since_slot_start.max_ms, 500);
+    assert_eq!(stats.since_proposer.p50_ms, 200);
+    assert_eq!(stats.peers["peer-a"].since_slot_start.samples, 1);
+    assert_eq!(stats.peers["peer-b"].since_slot_start.p50_ms, 300);
+
+    let recent: Vec<u64> = stats.recent.iter().map(|a| a.block_number).collect();
+    assert_eq!(recent, vec![4, 3]);
+}
@@ -0,0 +1,53 @@
+use alloy::primitives::{Address, B256, U256};
+use speed_blockchain::{
+    Block, ExecutionEngine, GasConfig, Transaction, core::BlockHeader, dev_keypairs,
+};
+
+use super::helpers::{ONE_TOKEN, TO_GWEI, transfer, with_intrinsic_gas};
+
+fn priced_transfer(from: Address, to: Address, nonce: u64, gas_price: u64) -> Transaction {
+    let tx = Transaction {
+        gas_price: U256::from(gas_price),
+        hash: B256::repeat_byte(nonce as u8),
+        ..transfer(from, to, nonce, 1_000)
+    };
+    with_intrinsic_gas(tx, &GasConfig::default())
+}
+
+#[tokio::test]
+async fn test_gas_fees_are_paid_to_the_proposer() {
+    let keypair = dev_keypairs(1).unwrap().remove(0);
+    let alice = keypair.address;
+    let bob = Address::repeat_byte(0xbb);
+    let proposer = Address::repeat_byte(0xcc);
+    let engine = ExecutionEngine::new();
+    engine
+        .prefund_accounts(&[(alice, U256::from(ONE_TOKEN))])
+        .await;
+
+    let mut paid = priced_transfer(alice, bob, 0, 2 * TO_GWEI);
+    paid.sign(&keypair).await.unwrap();
+    // a stale nonce, the sender is charged nothing and neither is the proposer paid
+    let mut failed = priced_transfer(alice, bob, 0, TO_GWEI);
+    failed.sign(&keypair).await.unwrap();
+    let header = BlockHeader::new(1, 1, proposer, B256::ZERO, B256::ZERO, B256::ZERO);
+    let mut block = Block::new(header, vec![paid.clone(), failed]);
+    let result = engine.execute_block_commit(&mut block).await.unwrap();
+    assert!(result.receipts[0].success);
+    assert!(!result.receipts[1].success);
+
+    let fee = result.receipts[0].gas_used * paid.gas_price;
+    assert_eq!(result.total_fees, fee);
+    assert_eq!(engine.get_balance(&proposer).await, fee);
+    assert_eq!(
+        engine.get_balance(&alice).await,
+        U256::from(ONE_TOKEN) - paid.amount - fee
+    );
+
+    // nothing is burned, the supply only moved between accounts
+    let supply = engine.get_balance(&alice).await
+        + engine.get_balance(&bob).await
+        + engine.get_balance(&proposer).await;
+    assert_eq!(supply, U256::from(ONE_TOKEN));
+    assert!(result.state_diff.iter().any(|d| d.address == proposer));
+}
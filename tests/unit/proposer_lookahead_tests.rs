@@ -0,0 +1,35 @@
+use speed_blockchain::{Blockchain, KeyPair, MIN_STAKE, SLOT_DURATION, SLOTS_PER_EPOCH};
+
+#[tokio::test]
+async fn test_upcoming_proposers_follow_the_schedule() {
+    let dir = tempfile::tempdir().unwrap();
+    let keys = [
+        KeyPair::generate("lookahead-first".to_string()),
+        KeyPair::generate("lookahead-second".to_string()),
+    ];
+    let blockchain = Blockchain::new(
+        dir.path().to_str().unwrap(),
+        MIN_STAKE,
+        SLOT_DURATION,
+        keys.iter().map(|key| (key.address, 200)).collect(),
+        None,
+    )
+    .unwrap();
+    let consensus = blockchain.consensus_engine.lock().await;
+    let current_slot = consensus.current_slot().unwrap();
+
+    let duties = consensus.upcoming_proposers(5).unwrap();
+    assert_eq!(duties.len(), 5);
+    for (offset, duty) in duties.iter().enumerate() {
+        assert_eq!(duty.slot, current_slot + 1 + offset as u64);
+        assert_eq!(duty.epoch, duty.slot / SLOTS_PER_EPOCH);
+        assert_eq!(Some(duty.proposer), consensus.scheduled_proposer(duty.slot));
+    }
+
+    // nothing is promised past the next epoch
+    let duties = consensus.upcoming_proposers(10 * SLOTS_PER_EPOCH).unwrap();
+    let last = duties.last().unwrap();
+    assert_eq!(last.slot, 2 * SLOTS_PER_EPOCH - 1);
+    assert_eq!(duties.len() as u64, last.slot - current_slot);
+    assert!(consensus.upcoming_proposers(0).unwrap().is_empty());
+}
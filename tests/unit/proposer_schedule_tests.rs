@@ -0,0 +1,65 @@
+use alloy::primitives::Address;
+use speed_blockchain::consensus::{ProposerSelection, ValidatorSet};
+use speed_blockchain::{MIN_STAKE, SLOTS_PER_EPOCH};
+
+fn selection() -> ProposerSelection {
+    let mut validators = ValidatorSet::new(MIN_STAKE);
+    for byte in 1..=4 {
+        assert!(
+            validators
+                .add_validator(Address::repeat_byte(byte), MIN_STAKE)
+                .is_ok()
+        );
+    }
+    ProposerSelection::new(validators, [1u8; 32])
+}
+
+#[test]
+fn test_epoch_schedule_matches_the_slot_proposers() {
+    let selection = selection();
+    let schedule = selection.epoch_schedule(3).unwrap();
+    assert_eq!(schedule.start_slot, 3 * SLOTS_PER_EPOCH);
+    assert_eq!(schedule.proposers.len() as u64, SLOTS_PER_EPOCH);
+
+    for slot in schedule.start_slot..schedule.start_slot + SLOTS_PER_EPOCH {
+        assert_eq!(
+            schedule.proposer(slot),
+            Some(selection.selector_proposer(slot).unwrap())
+        );
+    }
+    assert_eq!(schedule.proposer(schedule.start_slot - 1), None);
+    assert_eq!(
+        schedule.proposer(schedule.start_slot + SLOTS_PER_EPOCH),
+        None
+    );
+}
+
+#[test]
+fn test_validator_changes_reschedule_from_the_frozen_epoch_only() {
+    let mut selection = selection();
+    let before: Vec<_> = (0..4)
+        .map(|epoch| selection.epoch_schedule(epoch).unwrap())
+        .collect();
+
+    // a heavy newcomer, the schedule ignores it until the set is frozen
+    let newcomer = Address::repeat_byte(9);
+    assert!(
+        selection
+            .validator_set_mut()
+            .add_validator(newcomer, 1_000 * MIN_STAKE)
+            .is_ok()
+    );
+    assert_eq!(selection.epoch_schedule(1).unwrap(), before[1]);
+    assert_eq!(selection.epoch_schedule(2).unwrap(), before[2]);
+
+    selection.freeze_validator_set(2);
+    assert_eq!(selection.epoch_schedule(1).unwrap(), before[1]);
+    assert_ne!(selection.epoch_schedule(2).unwrap(), before[2]);
+    assert!(
+        selection
+            .epoch_schedule(3)
+            .unwrap()
+            .proposers
+            .contains(&newcomer)
+    );
+}
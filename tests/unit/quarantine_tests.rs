@@ -0,0 +1,86 @@
+use alloy::primitives::{Address, B256};
+use speed_blockchain::core::{Block, BlockHeader};
+use speed_blockchain::{Blockchain, MIN_STAKE, QuarantineConfig, SLOT_DURATION};
+
+fn blockchain(dir: &tempfile::TempDir, quarantine: QuarantineConfig) -> Blockchain {
+    Blockchain::new(
+        dir.path().to_str().unwrap(),
+        MIN_STAKE,
+        SLOT_DURATION,
+        vec![],
+        None,
+    )
+    .unwrap()
+    .with_quarantine(quarantine)
+}
+
+fn block(number: u64) -> Block {
+    let header = BlockHeader::new(
+        number,
+        number + 10,
+        Address::repeat_byte(0xaa),
+        B256::repeat_byte(number as u8),
+        B256::ZERO,
+        B256::ZERO,
+    );
+    Block::new(header, vec![])
+}
+
+async fn quarantine(chain: &Blockchain, number: u64) -> Option<u64> {
+    chain
+        .quarantine_block(
+            block(number),
+            Address::repeat_byte(0xaa),
+            format!("bad block {}", number),
+            "peer-a".to_string(),
+        )
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_rejected_blocks_are_dropped_when_quarantine_is_disabled() {
+    let dir = tempfile::tempdir().unwrap();
+    let chain = blockchain(&dir, QuarantineConfig::default());
+
+    assert!(!chain.quarantine_enabled());
+    assert_eq!(quarantine(&chain, 1).await, None);
+    assert!(
+        chain
+            .get_rejected_blocks(None, None)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_quarantine_keeps_newest_blocks_and_filters_by_number() {
+    let dir = tempfile::tempdir().unwrap();
+    let chain = blockchain(
+        &dir,
+        QuarantineConfig {
+            enabled: true,
+            max_blocks: 3,
+        },
+    );
+
+    for number in 1..=5 {
+        assert_eq!(quarantine(&chain, number).await, Some(number - 1));
+    }
+
+    // the two oldest were pruned, newest first
+    let all = chain.get_rejected_blocks(None, None).await.unwrap();
+    let numbers: Vec<u64> = all.iter().map(|r| r.block_number).collect();
+    assert_eq!(numbers, vec![5, 4, 3]);
+    assert_eq!(all[0].reason, "bad block 5");
+    assert_eq!(all[0].from_peer, "peer-a");
+    assert_eq!(all[0].slot, 15);
+    assert_eq!(all[0].block_hash, block(5).header.hash());
+
+    let ranged = chain.get_rejected_blocks(Some(2), Some(4)).await.unwrap();
+    let numbers: Vec<u64> = ranged.iter().map(|r| r.block_number).collect();
+    assert_eq!(numbers, vec![4, 3]);
+    let open = chain.get_rejected_blocks(Some(5), None).await.unwrap();
+    assert_eq!(open.len(), 1);
+}
@@ -0,0 +1,35 @@
+use alloy::primitives::Address;
+use speed_blockchain::consensus::ValidatorSet;
+use speed_blockchain::{NodeConfig, check_config};
+
+#[test]
+fn test_quorum_size_is_two_thirds_of_active_validators() {
+    let mut validators = ValidatorSet::new(100);
+    assert_eq!(validators.quorum_size(), 0);
+
+    for (i, expected) in [(1, 1), (2, 2), (3, 2), (4, 3), (5, 4), (6, 4), (7, 5)] {
+        assert!(
+            validators
+                .add_validator(Address::repeat_byte(i), 100)
+                .is_ok()
+        );
+        assert_eq!(validators.quorum_size(), expected, "{} validators", i);
+    }
+
+    // below the minimum stake, not counted
+    let _ = validators.add_validator(Address::repeat_byte(99), 1);
+    assert_eq!(validators.quorum_size(), 5);
+}
+
+#[test]
+fn test_rebroadcast_fraction_must_be_inside_the_slot() {
+    let mut config = NodeConfig::default();
+    config.rebroadcast.after_slot_fraction = 1.5;
+    let report = check_config(&config);
+    assert!(
+        report
+            .errors
+            .iter()
+            .any(|e| e.contains("rebroadcast.after_slot_fraction"))
+    );
+}
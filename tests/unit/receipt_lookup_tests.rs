@@ -0,0 +1,71 @@
+use alloy::primitives::{Address, B256, U256};
+use speed_blockchain::Receipt;
+use speed_blockchain::core::{Block, BlockHeader};
+use speed_blockchain::storage::Storage;
+
+use super::helpers::numbered_transfer;
+
+#[test]
+fn test_receipt_is_found_by_transaction_hash_with_its_block() {
+    let dir = tempfile::tempdir().unwrap();
+    let storage = Storage::new(dir.path()).unwrap();
+    let transactions = vec![numbered_transfer(1), numbered_transfer(2)];
+    let header = BlockHeader::new(
+        1,
+        1,
+        Address::repeat_byte(9),
+        B256::ZERO,
+        Block::calculate_transactions_root(&transactions),
+        B256::ZERO,
+    );
+    let block = Block::new(header, transactions);
+    let block_hash = block.header.hash();
+    storage.store_block(&block).unwrap();
+    storage.put_block_indexes(&block).unwrap();
+    storage
+        .put_block_receipts(
+            &block_hash,
+            &[
+                Receipt::success(B256::repeat_byte(1), U256::from(21_000)),
+                Receipt::failed(
+                    B256::repeat_byte(2),
+                    U256::from(25_000),
+                    "insufficient balance".to_string(),
+                ),
+            ],
+        )
+        .unwrap();
+
+    let receipt = storage.get_receipt(&B256::repeat_byte(2)).unwrap().unwrap();
+    assert_eq!(
+        (
+            receipt.block_hash,
+            receipt.block_number,
+            receipt.transaction_index
+        ),
+        (block_hash, 1, 1)
+    );
+    assert!(!receipt.receipt.success);
+    assert_eq!(receipt.receipt.gas_used, U256::from(25_000));
+    assert_eq!(receipt.cumulative_gas_used, U256::from(46_000));
+
+    // the receipt fields sit next to the location
+    let json = serde_json::to_value(&receipt).unwrap();
+    assert_eq!(json["success"], false);
+    assert_eq!(json["transaction_index"], 1);
+
+    // unknown, and reorged out of the canonical chain
+    assert!(
+        storage
+            .get_receipt(&B256::repeat_byte(3))
+            .unwrap()
+            .is_none()
+    );
+    storage.remove_block_indexes(&block).unwrap();
+    assert!(
+        storage
+            .get_receipt(&B256::repeat_byte(1))
+            .unwrap()
+            .is_none()
+    );
+}
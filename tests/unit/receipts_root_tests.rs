@@ -0,0 +1,116 @@
+use alloy::primitives::{Address, B256, Bloom, BloomInput, U256};
+use speed_blockchain::core::BlockHeader;
+use speed_blockchain::{
+    BlockProcessResult, KeyPair, Receipt, ReceiptEvent, logs_bloom, receipts_root,
+};
+
+use super::helpers::{dry_run_block, funded_chain, import_signed, signed_transfer};
+
+#[test]
+fn test_receipts_root_and_logs_bloom_commit_to_execution_results() {
+    assert_eq!(receipts_root(&[]), B256::ZERO);
+    assert_eq!(logs_bloom(&[]), Bloom::ZERO);
+
+    let transfer = ReceiptEvent::Transfer {
+        from: Address::repeat_byte(1),
+        to: Address::repeat_byte(2),
+        amount: U256::from(5),
+    };
+    let receipts = vec![
+        Receipt::success(B256::repeat_byte(1), U256::from(21_000)).with_events(vec![transfer]),
+        Receipt::failed(
+            B256::repeat_byte(2),
+            U256::from(30_000),
+            "nonce".to_string(),
+        ),
+    ];
+    let root = receipts_root(&receipts);
+    assert_ne!(root, B256::ZERO);
+
+    // error wording is no consensus rule, status and gas are
+    let mut reworded = receipts.clone();
+    reworded[1].error_message = Some("bad nonce".to_string());
+    assert_eq!(receipts_root(&reworded), root);
+    let mut regassed = receipts.clone();
+    regassed[0].gas_used = U256::from(21_001);
+    assert_ne!(receipts_root(&regassed), root);
+
+    let bloom = logs_bloom(&receipts);
+    assert!(bloom.contains_input(BloomInput::Raw(Address::repeat_byte(1).as_slice())));
+    assert!(bloom.contains_input(BloomInput::Raw(Address::repeat_byte(2).as_slice())));
+    assert!(!bloom.contains_input(BloomInput::Raw(Address::repeat_byte(3).as_slice())));
+}
+
+#[test]
+fn test_headers_without_receipt_commitments_hash_as_before() {
+    let header = BlockHeader::new(
+        1,
+        1,
+        Address::repeat_byte(9),
+        B256::ZERO,
+        B256::ZERO,
+        B256::ZERO,
+    );
+    let legacy_hash = header.hash();
+    let json = serde_json::to_value(&header).unwrap();
+    assert!(json.get("receipts_root").is_none());
+
+    let mut committed = header.clone();
+    committed.receipts_root = Some(B256::ZERO);
+    committed.logs_bloom = Some(Bloom::ZERO);
+    assert_ne!(committed.hash(), legacy_hash);
+    assert_eq!(
+        committed.committed_receipts_root(),
+        header.committed_receipts_root()
+    );
+}
+
+#[tokio::test]
+async fn test_blocks_with_wrong_state_root_receipts_root_or_bloom_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let proposer = KeyPair::generate("receipts-root-proposer".to_string());
+    let sender = KeyPair::generate("receipts-root-sender".to_string());
+    let blockchain = funded_chain(
+        dir.path().to_str().unwrap(),
+        proposer.address,
+        &[(sender.address, U256::from(10).pow(U256::from(18)))],
+    )
+    .await;
+
+    let tx = signed_transfer(&sender, 0).await;
+    let (block, result) = dry_run_block(&blockchain, proposer.address, vec![tx]).await;
+    let import = |block| import_signed(&blockchain, &proposer, block);
+
+    // left out, forged, and a bloom missing the transfer
+    let mut forged = block.clone();
+    forged.header.receipts_root = None;
+    forged.header.logs_bloom = None;
+    assert!(matches!(
+        import(forged.clone()).await,
+        BlockProcessResult::Rejected(_, _)
+    ));
+    forged.header.receipts_root = Some(B256::repeat_byte(7));
+    forged.header.logs_bloom = Some(result.logs_bloom);
+    assert!(matches!(
+        import(forged.clone()).await,
+        BlockProcessResult::Rejected(_, _)
+    ));
+    forged.header.receipts_root = Some(result.receipts_root);
+    forged.header.logs_bloom = Some(Bloom::ZERO);
+    assert!(matches!(
+        import(forged.clone()).await,
+        BlockProcessResult::Rejected(_, _)
+    ));
+    // receipts right, but a state root the transactions don't lead to
+    forged.header.logs_bloom = Some(result.logs_bloom);
+    forged.header.state_root = B256::repeat_byte(9);
+    assert!(matches!(
+        import(forged).await,
+        BlockProcessResult::Rejected(_, _)
+    ));
+
+    assert!(matches!(
+        import(block).await,
+        BlockProcessResult::Accepted(_)
+    ));
+}
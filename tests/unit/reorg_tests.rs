@@ -0,0 +1,154 @@
+use alloy::primitives::{B256, U256};
+use speed_blockchain::core::{Block, BlockHeader, ConsensusEventKind};
+use speed_blockchain::{BlockProcessResult, Blockchain, KeyPair, Transaction, TransactionSource};
+
+use super::helpers::{dry_run_block, funded_chain, genesis_slots_ago, signed_transfer};
+
+async fn signed_block(
+    proposer: &KeyPair,
+    index: u64,
+    slot: u64,
+    parent_hash: B256,
+    transactions: Vec<Transaction>,
+    state_root: B256,
+) -> Block {
+    let mut header = BlockHeader::new(
+        index,
+        slot,
+        proposer.address,
+        parent_hash,
+        Block::calculate_transactions_root(&transactions),
+        state_root,
+    );
+    header.sign(proposer).await.unwrap();
+    Block::new(header, transactions)
+}
+
+async fn import(blockchain: &Blockchain, block: &Block) -> BlockProcessResult {
+    let signature = block.header.validator_signature.unwrap();
+    blockchain
+        .process_received_block(block.clone(), block.header.proposer, signature)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_longer_branch_replaces_the_head() {
+    let dir = tempfile::tempdir().unwrap();
+    let proposer = KeyPair::generate("reorg-proposer".to_string());
+    let sender = KeyPair::generate("reorg-sender".to_string());
+    genesis_slots_ago(dir.path().to_str().unwrap(), 3);
+    let funded = U256::from(10).pow(U256::from(18));
+    let blockchain = funded_chain(
+        dir.path().to_str().unwrap(),
+        proposer.address,
+        &[(sender.address, funded)],
+    )
+    .await;
+    let genesis_root = blockchain
+        .execution_engine
+        .state_manager
+        .lock()
+        .await
+        .get_state_root();
+
+    // our chain: a1 carries a transfer
+    let tx = signed_transfer(&sender, 0).await;
+    let (mut a1, a1_result) = dry_run_block(&blockchain, proposer.address, vec![tx.clone()]).await;
+    let a1_root = a1_result.state_root;
+    a1.header.sign(&proposer).await.unwrap();
+    let a1_hash = a1.header.hash();
+    assert!(matches!(
+        import(&blockchain, &a1).await,
+        BlockProcessResult::Accepted(_)
+    ));
+
+    // a branch as long as ours is kept aside, a longer one is switched to
+    // empty blocks leave their parent's state, so they commit its root
+    let b1 = signed_block(&proposer, 1, 2, B256::ZERO, Vec::new(), genesis_root).await;
+    assert!(matches!(
+        import(&blockchain, &b1).await,
+        BlockProcessResult::Forked(_)
+    ));
+    assert_eq!(blockchain.side_blocks().await, 1);
+    let b2 = signed_block(&proposer, 2, 3, b1.header.hash(), Vec::new(), genesis_root).await;
+    let b2_hash = b2.header.hash();
+    assert!(matches!(
+        import(&blockchain, &b2).await,
+        BlockProcessResult::Accepted(hash) if hash == b2_hash
+    ));
+
+    assert_eq!(
+        blockchain.consensus_engine.lock().await.head_block(),
+        (2, b2_hash)
+    );
+    assert_eq!(blockchain.get_last_index().await.unwrap(), 2);
+    assert_eq!(
+        blockchain
+            .execution_engine
+            .get_balance(&sender.address)
+            .await,
+        funded
+    );
+    let state_root = || async {
+        blockchain
+            .execution_engine
+            .state_manager
+            .lock()
+            .await
+            .get_state_root()
+    };
+    assert_eq!(state_root().await, genesis_root);
+    // the transfer only a1 had is pending again
+    let content = blockchain.txpool_content().await;
+    let pending = &content.pending[&sender.address][&0];
+    assert_eq!(pending.transaction.hash, tx.hash);
+    assert_eq!(pending.source, TransactionSource::Reorg);
+    let events = blockchain.debug_state(10).await.events;
+    assert!(events.iter().any(|event| event.kind
+        == ConsensusEventKind::Reorg {
+            depth: 1,
+            old_head: a1_hash
+        }));
+
+    // the replaced block is a side block now, our old branch can still win
+    let a2 = signed_block(&proposer, 2, 4, a1_hash, Vec::new(), a1_root).await;
+    assert!(matches!(
+        import(&blockchain, &a2).await,
+        BlockProcessResult::Forked(_)
+    ));
+    let a3 = signed_block(&proposer, 3, 5, a2.header.hash(), Vec::new(), a1_root).await;
+    let a3_hash = a3.header.hash();
+    assert!(matches!(
+        import(&blockchain, &a3).await,
+        BlockProcessResult::Accepted(hash) if hash == a3_hash
+    ));
+    assert!(
+        blockchain
+            .execution_engine
+            .get_balance(&sender.address)
+            .await
+            < funded
+    );
+    assert_eq!(state_root().await, a1_root);
+    let pending = blockchain.execution_engine.get_pending_transactions().await;
+    assert!(!pending.iter().any(|pending| pending.hash == tx.hash));
+
+    // a longer branch that doesn't execute leaves the head where it was
+    let c3 = signed_block(&proposer, 3, 6, a2.header.hash(), Vec::new(), a1_root).await;
+    assert!(matches!(
+        import(&blockchain, &c3).await,
+        BlockProcessResult::Forked(_)
+    ));
+    let replayed = signed_transfer(&sender, 0).await;
+    let c4 = signed_block(&proposer, 4, 7, c3.header.hash(), vec![replayed], a1_root).await;
+    assert!(matches!(
+        import(&blockchain, &c4).await,
+        BlockProcessResult::Rejected(..)
+    ));
+    assert_eq!(
+        blockchain.consensus_engine.lock().await.head_block(),
+        (3, a3_hash)
+    );
+    assert_eq!(blockchain.get_last_index().await.unwrap(), 3);
+}
@@ -0,0 +1,98 @@
+use alloy::primitives::{B256, U256};
+use alloy_signer::Signature;
+use speed_blockchain::{ReplayGuard, ReplayRejection, block_message_hash};
+use std::time::{Duration, Instant};
+
+const TTL: Duration = Duration::from_secs(60);
+
+fn signature(byte: u64) -> Signature {
+    Signature::new(U256::from(byte), U256::from(byte), false)
+}
+
+#[test]
+fn test_replayed_and_stale_blocks_are_rejected() {
+    let mut guard = ReplayGuard::new(4, TTL);
+    guard.set_head_slot(10);
+    let now = Instant::now();
+
+    let sig = signature(1);
+    assert!(
+        guard
+            .check_block(B256::repeat_byte(1), &sig, 9, now)
+            .is_ok()
+    );
+    assert_eq!(
+        guard.check_block(B256::repeat_byte(1), &sig, 9, now),
+        Err(ReplayRejection::AlreadySeen(block_message_hash(
+            &B256::repeat_byte(1),
+            &sig
+        )))
+    );
+    assert!(matches!(
+        guard.check_block(B256::repeat_byte(2), &sig, 5, now),
+        Err(ReplayRejection::StaleSlot { slot: 5, .. })
+    ));
+
+    // attestations for a block that fell behind the horizon are dropped too
+    guard.set_head_slot(20);
+    assert!(matches!(
+        guard.check_attestation(B256::repeat_byte(3), B256::repeat_byte(1), now),
+        Err(ReplayRejection::StaleSlot { slot: 9, .. })
+    ));
+}
+
+#[test]
+fn test_seen_hashes_expire_after_ttl() {
+    let mut guard = ReplayGuard::new(4, TTL);
+    let now = Instant::now();
+
+    guard
+        .check_attestation(B256::repeat_byte(1), B256::ZERO, now)
+        .unwrap();
+    assert_eq!(guard.seen_count(), 1);
+
+    let later = now + TTL + Duration::from_secs(1);
+    guard.prune(later);
+    assert_eq!(guard.seen_count(), 0);
+    assert!(
+        guard
+            .check_attestation(B256::repeat_byte(1), B256::ZERO, later)
+            .is_ok()
+    );
+}
+
+#[test]
+fn test_forged_copy_does_not_shadow_the_genuine_block() {
+    let mut guard = ReplayGuard::new(4, TTL);
+    guard.set_head_slot(10);
+    let now = Instant::now();
+
+    // a peer relays the header first with a garbage signature, it fails verification later
+    assert!(
+        guard
+            .check_block(B256::repeat_byte(1), &signature(7), 9, now)
+            .is_ok()
+    );
+    assert!(
+        guard
+            .check_block(B256::repeat_byte(1), &signature(1), 9, now)
+            .is_ok()
+    );
+}
+
+#[test]
+fn test_slot_near_the_top_of_the_range_does_not_overflow() {
+    let mut guard = ReplayGuard::new(4, TTL);
+    guard.set_head_slot(10);
+
+    assert!(
+        guard
+            .check_block(
+                B256::repeat_byte(1),
+                &signature(1),
+                u64::MAX,
+                Instant::now()
+            )
+            .is_ok()
+    );
+}
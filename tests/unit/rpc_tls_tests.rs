@@ -0,0 +1,37 @@
+use speed_blockchain::RpcTlsConfig;
+
+#[test]
+fn test_self_signed_certificate_is_generated_once() {
+    let dir = tempfile::tempdir().unwrap();
+    let data_dir = dir.path().to_str().unwrap();
+    let config = RpcTlsConfig {
+        self_signed: true,
+        ..Default::default()
+    };
+
+    config.server_config(data_dir).unwrap();
+    let (cert_path, key_path) = config.paths(data_dir).unwrap();
+    let cert = std::fs::read_to_string(&cert_path).unwrap();
+    assert!(cert.starts_with("-----BEGIN CERTIFICATE-----"));
+    assert!(key_path.exists());
+
+    // restarting reuses the certificate wallets already trust
+    config.server_config(data_dir).unwrap();
+    assert_eq!(std::fs::read_to_string(&cert_path).unwrap(), cert);
+}
+
+#[test]
+fn test_tls_requires_cert_and_key() {
+    let config = RpcTlsConfig {
+        cert_path: Some("cert.pem".to_string()),
+        ..Default::default()
+    };
+    assert!(config.paths("db").is_err());
+
+    let missing = RpcTlsConfig {
+        cert_path: Some("/nonexistent/cert.pem".to_string()),
+        key_path: Some("/nonexistent/key.pem".to_string()),
+        ..Default::default()
+    };
+    assert!(missing.server_config("db").is_err());
+}
@@ -0,0 +1,229 @@
+use alloy::primitives::{Address, B256, U256};
+use speed_blockchain::core::BlockHeader;
+use speed_blockchain::{
+    Block, BlockEnv, ExecutionEngine, GasConfig, KeyPair, ReceiptEvent, SessionKey, StateManager,
+    StateTransition, StateTransitionError, Transaction, TransactionKind,
+};
+
+use super::helpers::{self, ONE_TOKEN, TO_GWEI, funded_state, with_intrinsic_gas};
+
+fn at_slot(slot: u64) -> BlockEnv<'static> {
+    BlockEnv {
+        slot,
+        ..BlockEnv::default()
+    }
+}
+
+fn alice() -> Address {
+    Address::repeat_byte(0xaa)
+}
+
+fn bob() -> Address {
+    Address::repeat_byte(0xbb)
+}
+
+fn session() -> Address {
+    Address::repeat_byte(0x5e)
+}
+
+fn transaction(nonce: u64, to: Address, amount: u64, kind: TransactionKind) -> Transaction {
+    let tx = helpers::transaction(alice(), to, nonce, amount, kind);
+    with_intrinsic_gas(tx, &GasConfig::default())
+}
+
+fn authorize(nonce: u64, max_value: U256, expiry_slot: u64) -> Transaction {
+    transaction(
+        nonce,
+        session(),
+        0,
+        TransactionKind::AuthorizeSessionKey {
+            session_key: session(),
+            max_value,
+            expiry_slot,
+        },
+    )
+}
+
+#[test]
+fn test_authorize_and_spend_within_bounds() {
+    let mut state = funded_state(&[(alice(), ONE_TOKEN)]);
+    let config = GasConfig::default();
+
+    let mut tx = authorize(0, U256::from(ONE_TOKEN / 100), 50);
+    StateTransition::apply_transaction(&mut state, &mut tx, &config).unwrap();
+    assert_eq!(
+        state.get_session_key(&alice(), &session()),
+        Some(&SessionKey {
+            max_value: U256::from(ONE_TOKEN / 100),
+            spent: U256::ZERO,
+            expiry_slot: 50,
+        })
+    );
+    assert_eq!(
+        ReceiptEvent::from_transaction(&tx),
+        vec![ReceiptEvent::SessionKeyAuthorized {
+            account: alice(),
+            session_key: session(),
+            max_value: U256::from(ONE_TOKEN / 100),
+            expiry_slot: 50,
+        }]
+    );
+
+    // value and fees both count against the budget, the account pays as usual
+    let balance = state.get_balance(&alice());
+    let mut tx = transaction(1, bob(), 1_000, TransactionKind::Transfer);
+    let (gas_used, _) = StateTransition::apply_session_transaction(
+        &mut state,
+        &mut tx,
+        &config,
+        session(),
+        &at_slot(50),
+    )
+    .unwrap();
+    let cost = U256::from(1_000) + gas_used * U256::from(TO_GWEI);
+    assert_eq!(state.get_balance(&bob()), U256::from(1_000));
+    assert_eq!(state.get_balance(&alice()), balance - cost);
+    assert_eq!(state.get_nonce(&alice()), 2);
+    assert_eq!(
+        state.get_session_key(&alice(), &session()).unwrap().spent,
+        cost
+    );
+}
+
+#[test]
+fn test_session_key_bounds_are_enforced() {
+    let mut state = funded_state(&[(alice(), ONE_TOKEN)]);
+    let config = GasConfig::default();
+    let mut tx = authorize(0, U256::from(ONE_TOKEN / 100), 50);
+    StateTransition::apply_transaction(&mut state, &mut tx, &config).unwrap();
+
+    let rejected = |state: &mut StateManager, mut tx: Transaction, slot: u64| {
+        matches!(
+            StateTransition::apply_session_transaction(
+                state,
+                &mut tx,
+                &config,
+                session(),
+                &at_slot(slot)
+            ),
+            Err(StateTransitionError::SessionKeyRejected { .. })
+        )
+    };
+
+    // over budget
+    let over = transaction(1, bob(), ONE_TOKEN / 100, TransactionKind::Transfer);
+    assert!(rejected(&mut state, over, 10));
+    // expired
+    let late = transaction(1, bob(), 1_000, TransactionKind::Transfer);
+    assert!(rejected(&mut state, late, 51));
+    // only the account key may hand out allowances or more session keys
+    let approve = transaction(
+        1,
+        bob(),
+        0,
+        TransactionKind::Approve {
+            spender: bob(),
+            allowance: U256::from(ONE_TOKEN),
+        },
+    );
+    assert!(rejected(&mut state, approve, 10));
+    let mut escalate = authorize(1, U256::from(ONE_TOKEN), u64::MAX);
+    escalate.to = bob();
+    assert!(rejected(&mut state, escalate, 10));
+    // keys of another account don't carry over
+    let mut other = transaction(0, alice(), 1_000, TransactionKind::Transfer);
+    other.from = bob();
+    assert!(rejected(&mut state, other, 10));
+    assert_eq!(state.get_nonce(&alice()), 1);
+
+    // zero max value revokes
+    let mut tx = authorize(1, U256::ZERO, 0);
+    StateTransition::apply_transaction(&mut state, &mut tx, &config).unwrap();
+    assert!(state.get_session_key(&alice(), &session()).is_none());
+    let revoked = transaction(2, bob(), 1_000, TransactionKind::Transfer);
+    assert!(rejected(&mut state, revoked, 10));
+}
+
+#[tokio::test]
+async fn test_mempool_admits_session_signed_transactions() {
+    let engine = ExecutionEngine::new();
+    let session_keypair = KeyPair::generate("session-key".to_string());
+    let stranger = KeyPair::generate("session-stranger".to_string());
+    {
+        let mut state = engine.state_manager.lock().await;
+        state.fund_account(&alice(), U256::from(ONE_TOKEN));
+        state.set_session_key(
+            alice(),
+            session_keypair.address,
+            Some(SessionKey {
+                max_value: U256::from(ONE_TOKEN / 100),
+                spent: U256::ZERO,
+                expiry_slot: 20,
+            }),
+        );
+    }
+
+    let mut tx = transaction(0, bob(), 1_000, TransactionKind::Transfer);
+    tx.sign(&session_keypair).await.unwrap();
+    assert_eq!(tx.from, alice());
+    assert!(engine.add_transaction(&tx).await.is_ok());
+
+    let mut over = transaction(1, bob(), ONE_TOKEN / 100, TransactionKind::Transfer);
+    over.sign(&session_keypair).await.unwrap();
+    assert!(engine.add_transaction(&over).await.is_err());
+
+    let mut forged = transaction(1, bob(), 1_000, TransactionKind::Transfer);
+    forged.sign(&stranger).await.unwrap();
+    assert!(engine.add_transaction(&forged).await.is_err());
+
+    // the head moved past the expiry
+    engine.set_head_slot(21);
+    let mut late = transaction(1, bob(), 1_000, TransactionKind::Transfer);
+    late.sign(&session_keypair).await.unwrap();
+    assert!(engine.add_transaction(&late).await.is_err());
+
+    // blocks built after expiry skip what the pool already holds
+    engine.set_head_slot(20);
+    let mut pending = vec![tx.clone()];
+    assert_eq!(
+        engine
+            .simulate_execute_block(&mut pending)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+    engine.set_head_slot(21);
+    assert!(
+        engine
+            .simulate_execute_block(&mut pending)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_block_transaction_without_a_valid_signature_charges_nobody() {
+    let engine = ExecutionEngine::new();
+    engine
+        .prefund_accounts(&[(alice(), U256::from(ONE_TOKEN))])
+        .await;
+
+    let mut unsigned = transaction(0, bob(), 1_000, TransactionKind::Transfer);
+    unsigned.hash = unsigned.calculate_hash();
+    let header = BlockHeader::new(
+        1,
+        1,
+        Address::repeat_byte(0xee),
+        B256::ZERO,
+        B256::ZERO,
+        B256::ZERO,
+    );
+    let mut block = Block::new(header, vec![unsigned]);
+    let result = engine.execute_block_commit(&mut block).await.unwrap();
+    assert!(!result.receipts[0].success);
+    assert_eq!(result.receipts[0].gas_used, U256::ZERO);
+    assert_eq!(engine.get_balance(&alice()).await, U256::from(ONE_TOKEN));
+    assert_eq!(engine.get_nonce(&alice()).await, 0);
+}
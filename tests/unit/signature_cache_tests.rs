@@ -0,0 +1,64 @@
+use alloy::primitives::U256;
+use alloy_signer::Signature;
+use speed_blockchain::crypto::{SignatureCache, SignatureCacheStats};
+use speed_blockchain::{ExecutionEngine, dev_keypairs};
+
+use super::helpers::signed_transfer;
+
+#[tokio::test]
+async fn test_sender_recovered_at_admission_is_reused_by_simulation() {
+    let keypair = dev_keypairs(1).unwrap().remove(0);
+    let engine = ExecutionEngine::new();
+    engine
+        .prefund_accounts(&[(keypair.address, U256::from(10).pow(U256::from(18)))])
+        .await;
+
+    let tx = signed_transfer(&keypair, 0).await;
+    engine.add_transaction(&tx).await.unwrap();
+    assert_eq!(
+        engine.signature_cache().stats(),
+        SignatureCacheStats { hits: 0, misses: 1 }
+    );
+
+    let mut pending = engine.get_pending_transactions().await;
+    let valid = engine.simulate_execute_block(&mut pending).await.unwrap();
+    assert_eq!(valid.len(), 1);
+    assert_eq!(
+        engine.signature_cache().stats(),
+        SignatureCacheStats { hits: 1, misses: 1 }
+    );
+}
+
+#[tokio::test]
+async fn test_cached_hash_with_another_signature_is_checked_again() {
+    let keypairs = dev_keypairs(2).unwrap();
+    let cache = SignatureCache::new(2);
+
+    let tx = signed_transfer(&keypairs[0], 0).await;
+    assert!(cache.is_signature_valid(&tx));
+
+    // same signed fields, signature of another key: not the claimed sender
+    let mut forged = tx.clone();
+    forged.signature = keypairs[1].sign_hash(&tx.hash).await.unwrap();
+    assert!(!cache.is_signature_valid(&forged));
+    assert!(cache.is_signature_valid(&tx));
+
+    // an unrecoverable signature is rejected instead of panicking
+    forged.signature = Signature::new(U256::ZERO, U256::ZERO, false);
+    assert!(!cache.is_signature_valid(&forged));
+
+    // a changed body under the cached hash and signature is not the signed transaction
+    let mut tampered = tx.clone();
+    tampered.to = keypairs[1].address;
+    tampered.amount = U256::from(999_999);
+    assert!(!cache.is_signature_valid(&tampered));
+    let stats = cache.stats();
+    assert!(cache.recover_sender(&tampered).is_err());
+    assert_eq!(cache.stats(), stats);
+
+    // least recently used entries are evicted
+    for nonce in 1..=3 {
+        assert!(cache.is_signature_valid(&signed_transfer(&keypairs[0], nonce).await));
+    }
+    assert_eq!(cache.len(), 2);
+}
@@ -0,0 +1,139 @@
+use alloy::primitives::{B256, U256};
+use alloy_signer::Signature;
+use speed_blockchain::consensus::{
+    AuditError, SignedMessage, SigningAuditLog, read_audit_log, verify_entries,
+};
+use speed_blockchain::{AttestationVote, KeyPair};
+
+async fn sign(keypair: &KeyPair, message: &SignedMessage, block_hash: B256) -> Signature {
+    keypair
+        .sign_hash(&message.signing_hash(&block_hash))
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_audit_log_chains_entries_across_restarts() {
+    let keypair = KeyPair::generate("audited-validator".to_string());
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit").join("signing_audit.jsonl");
+
+    let proposal = SignedMessage::Proposal;
+    let attestation = SignedMessage::Attestation {
+        vote: AttestationVote::Accept,
+    };
+    let mut log = SigningAuditLog::new(&path);
+    let signature = sign(&keypair, &proposal, B256::repeat_byte(1)).await;
+    log.append(
+        3,
+        B256::repeat_byte(1),
+        proposal,
+        keypair.address,
+        signature,
+    )
+    .unwrap();
+    let signature = sign(&keypair, &attestation, B256::repeat_byte(2)).await;
+    log.append(
+        4,
+        B256::repeat_byte(2),
+        attestation.clone(),
+        keypair.address,
+        signature,
+    )
+    .unwrap();
+
+    // a restarted node continues the same chain
+    let mut log = SigningAuditLog::new(&path);
+    let signature = sign(&keypair, &attestation, B256::repeat_byte(3)).await;
+    let last = log
+        .append(
+            5,
+            B256::repeat_byte(3),
+            attestation,
+            keypair.address,
+            signature,
+        )
+        .unwrap();
+
+    let entries = read_audit_log(&path).unwrap();
+    let summary = verify_entries(&entries).unwrap();
+    assert_eq!(summary.entries, 3);
+    assert_eq!(summary.last_seq, Some(2));
+    assert_eq!(summary.anchor, B256::ZERO);
+    assert_eq!(summary.head, last.hash);
+
+    // an exported run verifies on its own, anchored at the entry before it
+    let tail = verify_entries(&entries[1..]).unwrap();
+    assert_eq!(tail.anchor, entries[0].hash);
+    assert_eq!(tail.head, last.hash);
+}
+
+#[tokio::test]
+async fn test_tampered_audit_logs_fail_verification() {
+    let keypair = KeyPair::generate("audited-validator".to_string());
+    let other = KeyPair::generate("someone-else".to_string());
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("signing_audit.jsonl");
+
+    let mut log = SigningAuditLog::new(&path);
+    for slot in 1..=3u64 {
+        let block_hash = B256::from(U256::from(slot));
+        let signature = sign(&keypair, &SignedMessage::Proposal, block_hash).await;
+        log.append(
+            slot,
+            block_hash,
+            SignedMessage::Proposal,
+            keypair.address,
+            signature,
+        )
+        .unwrap();
+    }
+    let entries = read_audit_log(&path).unwrap();
+
+    let mut edited = entries.clone();
+    edited[1].slot = 9;
+    assert_eq!(
+        verify_entries(&edited),
+        Err(AuditError::HashMismatch { seq: 1 })
+    );
+
+    let removed = vec![entries[0].clone(), entries[2].clone()];
+    assert_eq!(
+        verify_entries(&removed),
+        Err(AuditError::Gap {
+            seq: 2,
+            previous: 0
+        })
+    );
+
+    // rehashed after swapping the signature, but the key doesn't match
+    let mut forged = entries.clone();
+    forged[2].signature = sign(&other, &SignedMessage::Proposal, forged[2].block_hash).await;
+    forged[2].hash = forged[2].calculate_hash();
+    assert_eq!(
+        verify_entries(&forged),
+        Err(AuditError::BadSignature {
+            seq: 2,
+            signer: keypair.address
+        })
+    );
+
+    // a log that no longer verifies is never appended to
+    std::fs::write(
+        &path,
+        format!("{}\n", serde_json::to_string(&edited[1]).unwrap()),
+    )
+    .unwrap();
+    let mut log = SigningAuditLog::new(&path);
+    let signature = sign(&keypair, &SignedMessage::Proposal, B256::ZERO).await;
+    assert!(
+        log.append(
+            4,
+            B256::ZERO,
+            SignedMessage::Proposal,
+            keypair.address,
+            signature
+        )
+        .is_err()
+    );
+}
@@ -0,0 +1,87 @@
+use alloy::primitives::B256;
+use speed_blockchain::core::{Block, BlockHeader, ConsensusEventKind};
+use speed_blockchain::{
+    BlockProcessResult, Blockchain, KeyPair, MIN_STAKE, SLOT_DURATION, SLOTS_PER_EPOCH,
+};
+
+use super::helpers::{genesis_slots_ago, slot_time};
+
+#[tokio::test]
+async fn test_chain_continues_after_empty_slots() {
+    let dir = tempfile::tempdir().unwrap();
+    let keys = [
+        KeyPair::generate("skipped-slot-first".to_string()),
+        KeyPair::generate("skipped-slot-second".to_string()),
+    ];
+    genesis_slots_ago(dir.path().to_str().unwrap(), 2 * SLOTS_PER_EPOCH + 1);
+    let blockchain = Blockchain::new(
+        dir.path().to_str().unwrap(),
+        MIN_STAKE,
+        SLOT_DURATION,
+        keys.iter().map(|key| (key.address, 200)).collect(),
+        None,
+    )
+    .unwrap();
+    let state_root = blockchain
+        .execution_engine
+        .state_manager
+        .lock()
+        .await
+        .get_state_root();
+
+    let import = |index: u64, slot: u64, parent_hash: B256| {
+        let blockchain = blockchain.clone();
+        let keys = keys.clone();
+        async move {
+            let proposer = blockchain
+                .consensus_engine
+                .lock()
+                .await
+                .scheduled_proposer(slot)
+                .unwrap();
+            let key = keys.iter().find(|key| key.address == proposer).unwrap();
+            let mut header = BlockHeader::new(
+                index,
+                slot,
+                proposer,
+                parent_hash,
+                Block::calculate_transactions_root(&[]),
+                state_root,
+            );
+            header.timestamp = slot_time(&blockchain, slot).await;
+            header.sign(key).await.unwrap();
+            let signature = header.validator_signature.unwrap();
+            let hash = header.hash();
+            let result = blockchain
+                .process_received_block(Block::new(header, Vec::new()), proposer, signature)
+                .await
+                .unwrap();
+            (result, hash)
+        }
+    };
+
+    let (result, first) = import(1, 3, B256::ZERO).await;
+    assert!(matches!(result, BlockProcessResult::Accepted(_)));
+
+    // a slot at or before the head is refused
+    let (result, _) = import(2, 2, first).await;
+    assert!(matches!(result, BlockProcessResult::Rejected(..)));
+
+    // nobody proposed in slots 4..=8 or in the whole next epoch, the scheduled
+    // proposer of a later slot still extends the chain
+    let slot = 2 * SLOTS_PER_EPOCH + 1;
+    let (result, _) = import(2, slot, first).await;
+    assert!(matches!(result, BlockProcessResult::Accepted(_)));
+    assert_eq!(blockchain.head_slot().await, slot);
+
+    let events = blockchain.debug_state(10).await.events;
+    assert!(events.iter().any(|event| event.slot == slot
+        && event.kind
+            == ConsensusEventKind::SlotsSkipped {
+                slots: slot - 3 - 1
+            }));
+
+    // the empty epoch was closed with the rest, all of its slots missed
+    let stats = blockchain.get_epoch_stats(1).await.unwrap().unwrap();
+    assert_eq!((stats.blocks, stats.missed_slots), (0, SLOTS_PER_EPOCH));
+}
@@ -0,0 +1,217 @@
+use alloy::primitives::{Address, B256, U256};
+use speed_blockchain::consensus::{
+    DoubleProposalDetector, DoubleProposalEvidence, EvidenceError, ValidatorSet, slash_penalties,
+};
+use speed_blockchain::core::{Block, BlockHeader};
+use speed_blockchain::{
+    BlockProcessResult, Blockchain, KeyPair, MIN_STAKE, SLOT_DURATION, SLOTS_PER_EPOCH,
+    SlashingConfig,
+};
+
+use super::helpers::{genesis_slots_ago, slot_time};
+
+async fn signed_header(
+    proposer: &KeyPair,
+    index: u64,
+    slot: u64,
+    parent_hash: B256,
+) -> BlockHeader {
+    let mut header = BlockHeader::new(
+        index,
+        slot,
+        proposer.address,
+        parent_hash,
+        B256::ZERO,
+        B256::ZERO,
+    );
+    header.sign(proposer).await.unwrap();
+    header
+}
+
+#[tokio::test]
+async fn test_detector_turns_two_blocks_in_a_slot_into_evidence() {
+    let proposer = KeyPair::generate("slashing-proposer".to_string());
+    let mut detector = DoubleProposalDetector::new();
+
+    let first = signed_header(&proposer, 1, 4, B256::repeat_byte(1)).await;
+    assert!(detector.observe(&first).is_none());
+    // the same block again, e.g. a rebroadcast
+    assert!(detector.observe(&first).is_none());
+    // the next slot
+    let next = signed_header(&proposer, 2, 5, first.hash()).await;
+    assert!(detector.observe(&next).is_none());
+
+    let second = signed_header(&proposer, 1, 4, B256::repeat_byte(2)).await;
+    let evidence = detector.observe(&second).expect("double proposal");
+    assert_eq!((evidence.proposer, evidence.slot), (proposer.address, 4));
+    assert_eq!(evidence.verify(), Ok(()));
+
+    // evidence can't be made up from unsigned or unrelated headers
+    let mut forged = second.clone();
+    forged.validator_signature = first.validator_signature;
+    assert!(matches!(
+        DoubleProposalEvidence::new(first.clone(), forged),
+        Err(EvidenceError::InvalidSignature(_))
+    ));
+    assert_eq!(
+        DoubleProposalEvidence::new(first.clone(), next).unwrap_err(),
+        EvidenceError::Mismatch
+    );
+    assert!(matches!(
+        DoubleProposalEvidence::new(first.clone(), first),
+        Err(EvidenceError::SameBlock(_))
+    ));
+}
+
+#[test]
+fn test_slashing_burns_stake_and_deactivates_after_the_threshold() {
+    let validator = Address::repeat_byte(1);
+    let mut set = ValidatorSet::new(100);
+    assert!(set.add_validator(validator, 1000).is_ok());
+    assert!(set.add_validator(Address::repeat_byte(2), 1000).is_ok());
+
+    let first = set.slash(&validator, 10, 2).unwrap();
+    assert_eq!((first.slashed, first.slash_count), (100, 1));
+    assert!(!first.deactivated);
+    assert_eq!(set.stake_of(&validator), 900);
+
+    let second = set.slash(&validator, 10, 2).unwrap();
+    assert_eq!(second.slashed, 90);
+    assert!(second.deactivated);
+    assert!(!set.is_active_validator(&validator));
+    assert!(set.slash(&Address::repeat_byte(3), 10, 2).is_none());
+}
+
+#[tokio::test]
+async fn test_double_proposal_is_slashed_when_its_epoch_closes() {
+    let dir = tempfile::tempdir().unwrap();
+    let proposer = KeyPair::generate("slashing-chain-proposer".to_string());
+    genesis_slots_ago(dir.path().to_str().unwrap(), SLOTS_PER_EPOCH + 1);
+    let blockchain = Blockchain::new(
+        dir.path().to_str().unwrap(),
+        MIN_STAKE,
+        SLOT_DURATION,
+        vec![(proposer.address, 200)],
+        None,
+    )
+    .unwrap()
+    .with_slashing(SlashingConfig {
+        slash_percent: 10,
+        max_slashes: 3,
+    });
+    let state_root = blockchain
+        .execution_engine
+        .state_manager
+        .lock()
+        .await
+        .get_state_root();
+
+    let import = |header: BlockHeader| {
+        let blockchain = blockchain.clone();
+        async move {
+            let signature = header.validator_signature.unwrap();
+            let block = Block::new(header, Vec::new());
+            blockchain
+                .process_received_block(block, proposer.address, signature)
+                .await
+                .unwrap()
+        }
+    };
+    let block = |index: u64, slot: u64, parent_hash: B256, timestamp: u64| {
+        let proposer = proposer.clone();
+        async move {
+            let mut header = signed_header(&proposer, index, slot, parent_hash).await;
+            header.timestamp = timestamp;
+            header.transactions_root = Block::calculate_transactions_root(&[]);
+            header.state_root = state_root;
+            header.sign(&proposer).await.unwrap();
+            header
+        }
+    };
+
+    let first = block(1, 3, B256::ZERO, slot_time(&blockchain, 3).await).await;
+    assert!(matches!(
+        import(first.clone()).await,
+        BlockProcessResult::Accepted(_)
+    ));
+    // another block for slot 3, it can't extend the chain but is proof enough
+    let second = block(1, 3, B256::ZERO, slot_time(&blockchain, 3).await + 1).await;
+    assert!(matches!(
+        import(second).await,
+        BlockProcessResult::Rejected(..)
+    ));
+    let evidence = blockchain
+        .get_slashing_evidence(&proposer.address)
+        .await
+        .unwrap();
+    assert_eq!(evidence.len(), 1);
+    assert_eq!(evidence[0].verify(), Ok(()));
+
+    // the stake stays until the epoch closes
+    let stake = || async {
+        blockchain
+            .consensus_engine
+            .lock()
+            .await
+            .validator_set()
+            .stake_of(&proposer.address)
+    };
+    assert_eq!(stake().await, 200);
+
+    let next_epoch = block(
+        2,
+        SLOTS_PER_EPOCH + 1,
+        first.hash(),
+        slot_time(&blockchain, SLOTS_PER_EPOCH + 1).await,
+    )
+    .await;
+    assert!(matches!(
+        import(next_epoch).await,
+        BlockProcessResult::Accepted(_)
+    ));
+    assert_eq!(stake().await, 180);
+    let statement = blockchain
+        .get_epoch_statement(&proposer.address, 0)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(statement.penalties, U256::from(20));
+    assert_eq!(statement.end_stake, 200);
+
+    // a restarted node resumes with the slashed set, not the genesis validators
+    let schedule = blockchain.proposer_schedule(2).await;
+    drop(blockchain);
+    let restarted = Blockchain::new(
+        dir.path().to_str().unwrap(),
+        MIN_STAKE,
+        SLOT_DURATION,
+        vec![(proposer.address, 200)],
+        None,
+    )
+    .unwrap();
+    {
+        let consensus = restarted.consensus_engine.lock().await;
+        let validator = consensus
+            .validator_set()
+            .get_validator(&proposer.address)
+            .unwrap();
+        assert_eq!((validator.staked_amount, validator.slash_count), (180, 1));
+        assert_eq!(consensus.validator_set().total_stake(), 180);
+    }
+    assert_eq!(restarted.proposer_schedule(2).await, schedule);
+}
+
+#[test]
+fn test_penalties_compound_for_repeated_evidence() {
+    // 10% of 1000, then 10% of the 900 left
+    let proposer = Address::repeat_byte(7);
+    let header = BlockHeader::new(1, 1, proposer, B256::ZERO, B256::ZERO, B256::ZERO);
+    let evidence = DoubleProposalEvidence {
+        proposer,
+        slot: 1,
+        first: header.clone(),
+        second: header,
+    };
+    let penalties = slash_penalties(&[evidence.clone(), evidence], |_| 1000, 10);
+    assert_eq!(penalties[&proposer], 190);
+}
@@ -0,0 +1,44 @@
+use speed_blockchain::consensus::SlotClock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[test]
+fn test_slots_count_from_genesis() {
+    let genesis = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let clock = SlotClock::new(genesis, Duration::from_secs(10));
+
+    assert_eq!(clock.slot_at(genesis - Duration::from_secs(1)), None);
+    assert_eq!(clock.slot_at(genesis), Some(0));
+    assert_eq!(
+        clock.slot_at(genesis + Duration::from_millis(9_999)),
+        Some(0)
+    );
+    assert_eq!(clock.slot_at(genesis + Duration::from_secs(95)), Some(9));
+    assert_eq!(clock.slot_start(9), genesis + Duration::from_secs(90));
+    assert_eq!(clock.slot_end(9), clock.slot_start(10));
+    assert_eq!(clock.until_slot(0), Duration::ZERO);
+
+    // the same genesis gives the same slots, whenever the clock was built
+    let restarted = SlotClock::new(genesis, Duration::from_secs(10));
+    assert_eq!(restarted.current_slot(), clock.current_slot());
+}
+
+#[tokio::test]
+async fn test_ticks_fire_at_slot_starts_and_skip_missed_slots() {
+    let slot = Duration::from_millis(200);
+    // half way through slot 3
+    let clock = SlotClock::new(SystemTime::now() - slot * 7 / 2, slot);
+    let mut ticks = clock.ticks();
+
+    // the slot in progress right away, then the next one once it starts
+    let first = ticks.tick().await;
+    assert_eq!(first, 3);
+    let second = ticks.tick().await;
+    assert_eq!(second, 4);
+    assert!(SystemTime::now() >= clock.slot_start(4));
+
+    // a busy loop doesn't get the slots it slept through
+    tokio::time::sleep(slot * 3).await;
+    let current = clock.current_slot().unwrap();
+    assert!(current >= 7);
+    assert_eq!(ticks.tick().await, current);
+}
@@ -0,0 +1,85 @@
+use alloy::primitives::{Address, U256};
+use speed_blockchain::consensus::ValidatorSet;
+use speed_blockchain::{GasConfig, PendingStake, StateTransition, Transaction, TransactionKind};
+
+use super::helpers::{ONE_TOKEN, funded_state, intrinsic_fee, transaction};
+
+fn validator() -> Address {
+    Address::repeat_byte(0xaa)
+}
+
+fn stake_transaction(amount: u64, nonce: u64, kind: TransactionKind) -> Transaction {
+    transaction(validator(), Address::ZERO, nonce, amount, kind)
+}
+
+fn pending(top_up: u64, withdraw: u64) -> PendingStake {
+    PendingStake {
+        top_up: U256::from(top_up),
+        withdraw: U256::from(withdraw),
+        register: false,
+        exit: false,
+    }
+}
+
+#[test]
+fn test_stake_transactions_are_queued_until_the_epoch_ends() {
+    let mut state = funded_state(&[(validator(), ONE_TOKEN)]);
+
+    let mut top_up = stake_transaction(500, 0, TransactionKind::StakeTopUp);
+    StateTransition::apply_transaction(&mut state, &mut top_up, &GasConfig::default()).unwrap();
+    let kind = TransactionKind::StakeWithdraw {
+        amount: U256::from(200),
+    };
+    let mut withdraw = stake_transaction(0, 1, kind);
+    StateTransition::apply_transaction(&mut state, &mut withdraw, &GasConfig::default()).unwrap();
+
+    // the top-up is locked right away, the withdrawal only costs gas
+    assert_eq!(
+        state.get_balance(&validator()),
+        U256::from(ONE_TOKEN) - U256::from(500) - intrinsic_fee(&top_up) - intrinsic_fee(&withdraw)
+    );
+    let root_with_pending = state.get_state_root();
+
+    let queued = state.take_pending_stake();
+    assert_eq!(queued[&validator()], pending(500, 200));
+    assert!(state.take_pending_stake().is_empty());
+    assert_ne!(state.get_state_root(), root_with_pending);
+}
+
+#[test]
+fn test_withdrawal_with_value_is_rejected() {
+    let mut state = funded_state(&[(validator(), ONE_TOKEN)]);
+    let kind = TransactionKind::StakeWithdraw {
+        amount: U256::from(200),
+    };
+    let mut tx = stake_transaction(1, 0, kind);
+
+    assert!(
+        StateTransition::apply_transaction(&mut state, &mut tx, &GasConfig::default()).is_err()
+    );
+    assert!(state.pending_stake.is_empty());
+}
+
+#[test]
+fn test_settlement_updates_stake_and_deactivates_below_minimum() {
+    let mut validators = ValidatorSet::new(100);
+    assert!(validators.add_validator(validator(), 150).is_ok());
+
+    let settlement = validators.settle_stake(validator(), &pending(50, 20));
+    assert_eq!((settlement.topped_up, settlement.withdrawn), (50, 20));
+    assert_eq!(settlement.credit, U256::from(20));
+    assert_eq!(validators.stake_of(&validator()), 180);
+    assert!(validators.is_active_validator(&validator()));
+
+    // withdrawals are capped at the stake, dropping below the minimum deactivates
+    let settlement = validators.settle_stake(validator(), &pending(0, 1_000));
+    assert_eq!(settlement.withdrawn, 180);
+    assert!(settlement.deactivated);
+    assert!(!validators.is_active_validator(&validator()));
+
+    // top-ups from unknown addresses are refunded
+    let stranger = Address::repeat_byte(0xbb);
+    let settlement = validators.settle_stake(stranger, &pending(300, 0));
+    assert_eq!(settlement.credit, U256::from(300));
+    assert_eq!(validators.stake_of(&stranger), 0);
+}
@@ -0,0 +1,116 @@
+use alloy::primitives::{Address, B256, U256};
+use speed_blockchain::core::BlockHeader;
+use speed_blockchain::{Block, ExecutionEngine, KeyPair, StateManager};
+
+use super::helpers::{TO_GWEI, transfer};
+
+#[test]
+fn test_revert_undoes_changes_since_the_checkpoint() {
+    let (alice, bob) = (Address::repeat_byte(0xaa), Address::repeat_byte(0xbb));
+    let mut state = StateManager::new();
+    state.fund_account(&alice, U256::from(100));
+    let root = state.get_state_root();
+
+    state.checkpoint();
+    state.fund_account(&alice, U256::from(5));
+    state.fund_account(&bob, U256::from(7));
+    state.set_allowance(alice, bob, U256::from(3));
+    state.set_name_owner("alice".to_string(), alice);
+    state.queue_stake_change(alice, U256::from(1), U256::ZERO);
+
+    // an inner checkpoint kept by `discard_checkpoint` is still undone by the outer one
+    state.checkpoint();
+    state.fund_account(&alice, U256::from(1));
+    state.discard_checkpoint();
+    state.checkpoint();
+    state.fund_account(&bob, U256::from(1));
+    state.revert();
+    assert_eq!(state.get_balance(&alice), U256::from(106));
+    assert_eq!(state.get_balance(&bob), U256::from(7));
+
+    state.revert();
+    assert_eq!(state.get_state_root(), root);
+    assert_eq!(state.get_balance(&alice), U256::from(100));
+    assert_eq!(state.account_count(), 1);
+    assert_eq!(state.get_allowance(&alice, &bob), U256::ZERO);
+    assert!(state.take_pending_stake().is_empty());
+
+    // without a checkpoint nothing is journaled, changes stay
+    state.fund_account(&bob, U256::from(1));
+    assert_ne!(state.get_state_root(), root);
+}
+
+#[test]
+fn test_undo_logs_step_back_one_block_at_a_time() {
+    let (alice, bob) = (Address::repeat_byte(0xaa), Address::repeat_byte(0xbb));
+    let mut state = StateManager::new();
+    state.fund_account(&alice, U256::from(100));
+    let first = state.get_state_root();
+    // starts journaling, nothing changed before it is part of any log
+    assert!(state.take_undo().is_empty());
+
+    // a block whose transaction reverted keeps only what the block kept
+    state.fund_account(&bob, U256::from(7));
+    state.checkpoint();
+    state.set_name_owner("bob".to_string(), bob);
+    state.revert();
+    let block_one = state.take_undo();
+    let second = state.get_state_root();
+    state.set_allowance(alice, bob, U256::from(3));
+
+    // a copy steps back without touching the state
+    assert_eq!(state.rewound(&[]).get_state_root(), second);
+    assert_eq!(state.rewound(&[&block_one]).get_state_root(), first);
+    assert_eq!(state.get_allowance(&alice, &bob), U256::from(3));
+
+    let since = state.take_undo();
+    state.apply_undo(since);
+    assert_eq!(state.get_state_root(), second);
+    state.apply_undo(block_one);
+    assert_eq!(state.get_state_root(), first);
+    assert_eq!(state.account_count(), 1);
+    assert_eq!(state.get_allowance(&alice, &bob), U256::ZERO);
+}
+
+#[tokio::test]
+async fn test_simulation_follows_execution_and_leaves_state_untouched() {
+    let sender = KeyPair::generate("journal-sender".to_string());
+    let engine = ExecutionEngine::new();
+    // enough for both transfers at the gas they use, not at their maximum cost
+    engine
+        .prefund_accounts(&[(sender.address, U256::from(52_000 * TO_GWEI + 2_000))])
+        .await;
+
+    let mut transactions = Vec::new();
+    for nonce in 0..2 {
+        let mut tx = transfer(sender.address, Address::repeat_byte(0xbb), nonce, 1_000);
+        tx.sign(&sender).await.unwrap();
+        transactions.push(tx);
+    }
+    let root = engine.state_manager.lock().await.get_state_root();
+
+    let valid = engine
+        .simulate_execute_block(&mut transactions)
+        .await
+        .unwrap();
+    assert_eq!(valid.len(), 2);
+    assert_eq!(engine.state_manager.lock().await.get_state_root(), root);
+    assert_eq!(engine.get_nonce(&sender.address).await, 0);
+
+    let header = BlockHeader::new(
+        1,
+        1,
+        Address::repeat_byte(0xee),
+        B256::ZERO,
+        B256::ZERO,
+        B256::ZERO,
+    );
+    let mut block = Block::new(header, valid);
+    let dry_run = engine.execute_block_dry_run(&mut block).await.unwrap();
+    assert_eq!(engine.state_manager.lock().await.get_state_root(), root);
+
+    let result = engine.execute_block_commit(&mut block).await.unwrap();
+    assert!(result.receipts.iter().all(|receipt| receipt.success));
+    assert_eq!(result.state_root, dry_run.state_root);
+    assert_eq!(engine.get_nonce(&sender.address).await, 2);
+}
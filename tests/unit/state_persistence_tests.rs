@@ -0,0 +1,125 @@
+use alloy::primitives::{Address, B256, U256};
+use speed_blockchain::storage::{MemoryStore, Storage};
+use speed_blockchain::{AccountDiff, BlockProcessResult, ExecutionEngine, KeyPair, StateManager};
+
+use super::helpers::{ONE_TOKEN, dry_run_block, funded_chain, import_signed, signed_transfer};
+
+#[test]
+fn test_state_is_written_and_read_back() {
+    let storage = Storage::with_backend(MemoryStore::new());
+    let (alice, bob) = (Address::repeat_byte(0xaa), Address::repeat_byte(0xbb));
+    let mut state = StateManager::new();
+    state.fund_account(&alice, U256::from(ONE_TOKEN));
+    state.fund_account(&bob, U256::from(5));
+    state.set_allowance(alice, bob, U256::from(7));
+    assert_eq!(storage.get_state().unwrap().map(|(hash, _)| hash), None);
+
+    let write = state.take_write(B256::repeat_byte(1), true);
+    assert_eq!(storage.put_state(&write).unwrap(), 2);
+    let (block_hash, stored) = storage.get_state().unwrap().unwrap();
+    assert_eq!(block_hash, B256::repeat_byte(1));
+    assert_eq!(stored.get_state_root(), state.get_state_root());
+    assert_eq!(stored.get_allowance(&alice, &bob), U256::from(7));
+
+    // only what changed since is written, an emptied account is deleted
+    let mut account = state.get_account(&bob);
+    account.balance = U256::ZERO;
+    state.set_account(bob, account);
+    let write = state.take_write(B256::repeat_byte(2), false);
+    assert!(write.rest.is_none());
+    assert_eq!(storage.put_state(&write).unwrap(), 1);
+    let (block_hash, stored) = storage.get_state().unwrap().unwrap();
+    assert_eq!(block_hash, B256::repeat_byte(2));
+    assert_eq!(stored.account_count(), 1);
+    assert_eq!(stored.get_state_root(), state.get_state_root());
+    assert!(
+        state
+            .take_write(B256::repeat_byte(3), false)
+            .accounts
+            .is_empty()
+    );
+
+    // accounts that don't add up to the recorded root are not trusted
+    state.fund_account(&bob, U256::from(1));
+    let mut write = state.take_write(B256::repeat_byte(3), false);
+    write.state_root = B256::repeat_byte(0xff);
+    storage.put_state(&write).unwrap();
+    assert!(storage.get_state().unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_restarted_node_resumes_with_its_state() {
+    let dir = tempfile::tempdir().unwrap();
+    let proposer = KeyPair::generate("state-proposer".to_string());
+    let sender = KeyPair::generate("state-sender".to_string());
+    let recipient = Address::repeat_byte(0xbb);
+    let path = dir.path().to_str().unwrap();
+
+    let blockchain = funded_chain(
+        path,
+        proposer.address,
+        &[(sender.address, U256::from(ONE_TOKEN))],
+    )
+    .await;
+    assert!(!blockchain.state_restored());
+    let tx = signed_transfer(&sender, 0).await;
+    let (block, _) = dry_run_block(&blockchain, proposer.address, vec![tx]).await;
+    assert!(matches!(
+        import_signed(&blockchain, &proposer, block).await,
+        BlockProcessResult::Accepted(_)
+    ));
+
+    let (root, balance) = {
+        let state = blockchain.execution_engine.state_manager.lock().await;
+        (state.get_state_root(), state.get_balance(&sender.address))
+    };
+    drop(blockchain);
+
+    // nothing is prefunded or warmed up this time, the state comes from storage
+    let blockchain = funded_chain(path, proposer.address, &[]).await;
+    assert!(blockchain.state_restored());
+    assert_eq!(blockchain.warm_up_state(u64::MAX).await.unwrap(), 0);
+    let engine = &blockchain.execution_engine;
+    assert_eq!(engine.state_manager.lock().await.get_state_root(), root);
+    assert_eq!(engine.get_balance(&sender.address).await, balance);
+    assert_eq!(engine.get_balance(&recipient).await, U256::from(1_000));
+    assert_eq!(engine.get_nonce(&sender.address).await, 1);
+}
+
+#[tokio::test]
+async fn test_warm_up_answers_reads_without_changing_the_state() {
+    let (alice, bob, carol) = (
+        Address::repeat_byte(0xaa),
+        Address::repeat_byte(0xbb),
+        Address::repeat_byte(0xcc),
+    );
+    let engine = ExecutionEngine::new();
+    engine
+        .prefund_accounts(&[(alice, U256::from(ONE_TOKEN))])
+        .await;
+    let root = engine.state_manager.lock().await.get_state_root();
+
+    let diff = |address: Address, balance: u64, nonce: u64| AccountDiff {
+        address,
+        balance_before: U256::ZERO,
+        balance_after: U256::from(balance),
+        nonce_before: 0,
+        nonce_after: nonce,
+    };
+    // newest first, an emptied account is remembered as empty
+    let diffs = vec![
+        diff(bob, 7, 2),
+        diff(alice, 1, 1),
+        diff(bob, 9, 1),
+        diff(carol, 0, 0),
+    ];
+    assert_eq!(engine.warm_up_accounts(diffs).await, 2);
+
+    assert_eq!(engine.state_manager.lock().await.get_state_root(), root);
+    assert_eq!(engine.state_manager.lock().await.account_count(), 1);
+    assert_eq!(engine.get_balance(&bob).await, U256::from(7));
+    assert_eq!(engine.get_nonce(&bob).await, 2);
+    // the state's own accounts come first
+    assert_eq!(engine.get_balance(&alice).await, U256::from(ONE_TOKEN));
+    assert_eq!(engine.get_balance(&carol).await, U256::ZERO);
+}
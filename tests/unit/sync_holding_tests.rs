@@ -0,0 +1,71 @@
+use alloy::primitives::U256;
+use speed_blockchain::{
+    Blockchain, HeldTransaction, MIN_STAKE, SLOT_DURATION, SyncHoldingArea, TransactionSource,
+    dev_keypairs,
+};
+
+use super::helpers::signed_transfer;
+
+#[tokio::test]
+async fn test_holding_area_is_bounded_and_keeps_arrival_order() {
+    let keypair = dev_keypairs(1).unwrap().remove(0);
+    let mut area = SyncHoldingArea::new(2);
+
+    let first = signed_transfer(&keypair, 0).await;
+    assert!(area.hold(first.clone(), "peer-a".to_string()));
+    assert!(!area.hold(first.clone(), "peer-b".to_string()));
+    assert!(area.hold(signed_transfer(&keypair, 1).await, "peer-a".to_string()));
+    assert!(!area.hold(signed_transfer(&keypair, 2).await, "peer-a".to_string()));
+    assert_eq!((area.len(), area.dropped()), (2, 1));
+
+    let held = area.drain();
+    assert_eq!(held[0].transaction.hash, first.hash);
+    assert_eq!(held[1].transaction.nonce, 1);
+    assert!(area.is_empty());
+}
+
+// transactions gossiped while syncing are checked against the synced state
+#[tokio::test]
+async fn test_held_transactions_are_revalidated_on_admission() {
+    let dir = tempfile::tempdir().unwrap();
+    let keypair = dev_keypairs(1).unwrap().remove(0);
+    let blockchain = Blockchain::new(
+        dir.path().to_str().unwrap(),
+        MIN_STAKE,
+        SLOT_DURATION,
+        vec![],
+        None,
+    )
+    .unwrap();
+
+    blockchain.note_network_slot(12).await;
+    assert_eq!(blockchain.slots_behind().await, 12);
+
+    // sync brought the account to nonce 1, funded
+    {
+        let mut state = blockchain.execution_engine.state_manager.lock().await;
+        let mut account = state.get_account(&keypair.address);
+        account.nonce = 1;
+        account.balance = U256::from(10).pow(U256::from(18));
+        state.set_account(keypair.address, account);
+    }
+
+    let held = vec![
+        HeldTransaction {
+            transaction: signed_transfer(&keypair, 0).await,
+            peer: "peer-a".to_string(),
+        },
+        HeldTransaction {
+            transaction: signed_transfer(&keypair, 1).await,
+            peer: "peer-b".to_string(),
+        },
+    ];
+    assert_eq!(blockchain.admit_held_transactions(held).await, 1);
+
+    let content = blockchain.txpool_content().await;
+    let pooled = &content.pending[&keypair.address][&1];
+    assert!(matches!(
+        &pooled.source,
+        TransactionSource::Gossip { peer } if peer == "peer-b"
+    ));
+}
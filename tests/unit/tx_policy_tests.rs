@@ -0,0 +1,109 @@
+use alloy::primitives::{Address, U256};
+use speed_blockchain::{
+    AllowlistPolicy, ExecutionEngine, TransactionKind, TransferOutput, TxPolicyConfig,
+    check_tx_policy, dev_keypairs,
+};
+use std::sync::Arc;
+
+use super::helpers::{signed_transfer, transaction, transfer};
+
+#[test]
+fn test_allowlist_policy_from_config() {
+    let alice = Address::repeat_byte(1);
+    let bob = Address::repeat_byte(2);
+    let config: TxPolicyConfig = serde_json::from_value(serde_json::json!({
+        "type": "allowlist",
+        "senders": [alice],
+        "max_amount": "1000",
+    }))
+    .unwrap();
+    let policy = config.build();
+
+    assert!(check_tx_policy(policy.as_ref(), &transfer(alice, bob, 0, 1000)).is_ok());
+
+    let violation = check_tx_policy(policy.as_ref(), &transfer(bob, alice, 0, 1)).unwrap_err();
+    assert_eq!(violation.policy, "allowlist");
+    assert!(check_tx_policy(policy.as_ref(), &transfer(alice, bob, 0, 1001)).is_err());
+
+    // recipients restrict where allowlisted senders may send
+    let restricted = AllowlistPolicy {
+        senders: [alice].into(),
+        recipients: [alice].into(),
+        max_amount: None,
+    };
+    assert!(check_tx_policy(&restricted, &transfer(alice, bob, 0, 1)).is_err());
+}
+
+#[test]
+fn test_allowlist_checks_every_batch_output_and_the_total() {
+    let alice = Address::repeat_byte(1);
+    let bob = Address::repeat_byte(2);
+    let policy = AllowlistPolicy {
+        senders: [alice].into(),
+        recipients: [bob].into(),
+        max_amount: Some(U256::from(1000)),
+    };
+    let batch = |outputs: &[(Address, u64)]| {
+        let outputs = outputs
+            .iter()
+            .map(|(to, amount)| TransferOutput {
+                to: *to,
+                amount: U256::from(*amount),
+            })
+            .collect();
+        transaction(alice, bob, 0, 0, TransactionKind::BatchTransfer { outputs })
+    };
+
+    assert!(check_tx_policy(&policy, &batch(&[(bob, 600), (bob, 400)])).is_ok());
+    // `to` is allowlisted but the batch pays someone else
+    assert!(check_tx_policy(&policy, &batch(&[(bob, 1), (alice, 1)])).is_err());
+    // every output is under the maximum, their sum isn't
+    assert!(check_tx_policy(&policy, &batch(&[(bob, 600), (bob, 401)])).is_err());
+}
+
+#[test]
+fn test_allowlist_checks_the_owner_of_a_transfer_from() {
+    let alice = Address::repeat_byte(1);
+    let bob = Address::repeat_byte(2);
+    let policy = AllowlistPolicy {
+        senders: [alice].into(),
+        recipients: Default::default(),
+        max_amount: None,
+    };
+    let transfer_from =
+        |owner| transaction(alice, alice, 0, 1, TransactionKind::TransferFrom { owner });
+
+    assert!(check_tx_policy(&policy, &transfer_from(alice)).is_ok());
+    // an allowlisted spender can't move tokens out of an account the policy excludes
+    let violation = check_tx_policy(&policy, &transfer_from(bob)).unwrap_err();
+    assert!(violation.reason.contains("owner"));
+}
+
+#[tokio::test]
+async fn test_policy_filters_our_proposals_but_not_blocks_we_validate() {
+    let keypair = dev_keypairs(1).unwrap().remove(0);
+    let engine = ExecutionEngine::new();
+    engine
+        .prefund_accounts(&[(keypair.address, U256::from(10).pow(U256::from(18)))])
+        .await;
+    let tx = signed_transfer(&keypair, 0).await;
+    engine.add_transaction(&tx).await.unwrap();
+
+    // another node's policy would have let it through, ours doesn't
+    engine
+        .set_tx_policy(Arc::new(AllowlistPolicy {
+            senders: [Address::repeat_byte(1)].into(),
+            recipients: Default::default(),
+            max_amount: None,
+        }))
+        .await;
+    assert!(engine.proposable_transactions().await.is_empty());
+
+    // a block from a proposer that included it is still valid here
+    let valid = engine
+        .simulate_execute_block(&mut [tx.clone()])
+        .await
+        .unwrap();
+    assert_eq!(valid.len(), 1);
+    assert_eq!(valid[0].hash, tx.hash);
+}
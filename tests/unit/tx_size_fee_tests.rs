@@ -0,0 +1,116 @@
+use alloy::primitives::{Address, Bytes, U256};
+use speed_blockchain::{
+    Block, ExecutionEngine, GasCalculator, GasConfig, StateTransition, Transaction,
+    TransactionKind, core::BlockHeader, dev_keypairs,
+};
+
+use super::helpers::{self, TO_GWEI, funded_state};
+
+fn transaction(from: Address, nonce: u64, kind: TransactionKind) -> Transaction {
+    helpers::transaction(from, Address::repeat_byte(0xbb), nonce, 1_000, kind)
+}
+
+fn registration(multiaddr_len: usize) -> TransactionKind {
+    TransactionKind::RegisterNetworkAddress {
+        multiaddr: format!("/dns4/{}/tcp/4001", "a".repeat(multiaddr_len)),
+    }
+}
+
+#[tokio::test]
+async fn test_gas_is_charged_per_encoded_byte() {
+    let config = GasConfig::default();
+    let keypair = dev_keypairs(1).unwrap().remove(0);
+    let sender = keypair.address;
+
+    let mut transfer = transaction(sender, 0, TransactionKind::Transfer);
+    transfer.sign(&keypair).await.unwrap();
+    assert_eq!(transfer.encoded_size(), 217);
+    assert_eq!(
+        GasCalculator::calculate_instrinsic_gas(&transfer, &config),
+        U256::from(21_000 + 4 * 217)
+    );
+
+    // a longer multiaddr pays for every extra byte
+    let short = transaction(sender, 0, registration(8));
+    let long = transaction(sender, 0, registration(108));
+    assert_eq!(long.encoded_size() - short.encoded_size(), 100);
+    assert_eq!(
+        GasCalculator::calculate_instrinsic_gas(&long, &config)
+            - GasCalculator::calculate_instrinsic_gas(&short, &config),
+        U256::from(400)
+    );
+
+    // receipts record the size the fee was charged on
+    let engine = ExecutionEngine::new();
+    engine
+        .prefund_accounts(&[(sender, U256::from(TO_GWEI) * U256::from(1_000_000))])
+        .await;
+    let mut block = Block::new(BlockHeader::genesis(), vec![transfer.clone()]);
+    let result = engine.execute_block_commit(&mut block).await.unwrap();
+    assert!(result.receipts[0].success);
+    assert_eq!(result.receipts[0].size, 217);
+    assert_eq!(result.total_gas_used, U256::from(21_868));
+}
+
+#[tokio::test]
+async fn test_calldata_is_signed_and_charged_per_byte() {
+    let config = GasConfig::default();
+    let keypair = dev_keypairs(1).unwrap().remove(0);
+    let plain = transaction(keypair.address, 0, TransactionKind::Transfer);
+    let mut with_data = plain.clone();
+    with_data.data = Bytes::from(vec![0xab; 100]);
+
+    // tag + length + 100 bytes
+    assert_eq!(with_data.encoded_size() - plain.encoded_size(), 105);
+    assert_eq!(
+        GasCalculator::calculate_instrinsic_gas(&with_data, &config)
+            - GasCalculator::calculate_instrinsic_gas(&plain, &config),
+        U256::from(4 * 105)
+    );
+
+    with_data.sign(&keypair).await.unwrap();
+    assert_ne!(with_data.hash, plain.calculate_hash());
+    assert!(with_data.is_signature_valid());
+
+    // the calldata can't be swapped after signing
+    let mut altered = with_data.clone();
+    altered.data = Bytes::from(vec![0xcd; 100]);
+    assert!(!altered.is_signature_valid());
+
+    let decoded = Transaction::decode_raw(&with_data.encode_raw()).unwrap();
+    assert_eq!(decoded.data, with_data.data);
+    assert!(decoded.is_signature_valid());
+}
+
+#[tokio::test]
+async fn test_oversized_transactions_are_refused() {
+    let config = GasConfig::default();
+    let keypair = dev_keypairs(1).unwrap().remove(0);
+    let mut tx = transaction(keypair.address, 0, registration(1_000));
+    tx.sign(&keypair).await.unwrap();
+    assert!(tx.encoded_size() > config.max_tx_size);
+
+    // mempool admission
+    let engine = ExecutionEngine::new();
+    engine
+        .prefund_accounts(&[(keypair.address, U256::from(TO_GWEI) * U256::from(1_000_000))])
+        .await;
+    let err = engine.add_transaction(&tx).await.unwrap_err();
+    assert!(err.to_string().contains("max 1024"));
+
+    // block simulation skips it, so validators reject blocks carrying it
+    let valid = engine
+        .simulate_execute_block(&mut [tx.clone()])
+        .await
+        .unwrap();
+    assert!(valid.is_empty());
+
+    let mut state = funded_state(&[(keypair.address, TO_GWEI * 1_000_000)]);
+    let result = StateTransition::apply_transaction(&mut state, &mut tx, &config);
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .starts_with("Transaction too large")
+    );
+}
@@ -0,0 +1,78 @@
+use alloy::primitives::{Address, U256};
+use speed_blockchain::consensus::{EXIT_DELAY_SLOTS, ValidatorExit, ValidatorSet};
+use speed_blockchain::{GasConfig, ReceiptEvent, StateTransition, Transaction, TransactionKind};
+
+use super::helpers::{ONE_TOKEN, funded_state, transaction};
+
+fn leaving() -> Address {
+    Address::repeat_byte(0xaa)
+}
+
+fn staying() -> Address {
+    Address::repeat_byte(0xbb)
+}
+
+fn unstake(amount: u64, nonce: u64) -> Transaction {
+    transaction(
+        leaving(),
+        Address::ZERO,
+        nonce,
+        amount,
+        TransactionKind::Unstake,
+    )
+}
+
+#[test]
+fn test_unstake_queues_an_exit() {
+    let mut state = funded_state(&[(leaving(), ONE_TOKEN)]);
+    let root_before = state.get_state_root();
+
+    let mut with_value = unstake(1, 0);
+    assert!(
+        StateTransition::apply_transaction(&mut state, &mut with_value, &GasConfig::default())
+            .is_err()
+    );
+
+    let mut tx = unstake(0, 0);
+    StateTransition::apply_transaction(&mut state, &mut tx, &GasConfig::default()).unwrap();
+    let pending = &state.pending_stake[&leaving()];
+    assert!(pending.exit);
+    assert_eq!(pending.top_up, U256::ZERO);
+    assert_ne!(state.get_state_root(), root_before);
+    assert_eq!(
+        ReceiptEvent::from_transaction(&tx),
+        vec![ReceiptEvent::ValidatorExitQueued {
+            validator: leaving()
+        }]
+    );
+}
+
+#[test]
+fn test_exiting_validator_stays_until_the_delay_passed() {
+    let mut validators = ValidatorSet::new(100);
+    assert!(validators.add_validator(leaving(), 500).is_ok());
+    assert!(validators.add_validator(staying(), 300).is_ok());
+
+    let exit_slot = 32 + EXIT_DELAY_SLOTS;
+    assert!(validators.request_exit(&leaving(), exit_slot));
+    assert!(!validators.request_exit(&leaving(), exit_slot + 1));
+    assert_eq!(validators.pending_exits(), vec![(leaving(), exit_slot)]);
+    // the last validator staying can't leave
+    assert!(!validators.request_exit(&staying(), exit_slot));
+
+    // still proposing and attesting until the exit slot
+    assert!(validators.process_exits(exit_slot - 1).is_empty());
+    assert!(validators.is_active_validator(&leaving()));
+
+    assert_eq!(
+        validators.process_exits(exit_slot),
+        vec![ValidatorExit {
+            validator: leaving(),
+            stake: 500,
+        }]
+    );
+    assert!(!validators.is_active_validator(&leaving()));
+    assert_eq!(validators.stake_of(&leaving()), 0);
+    assert!(validators.pending_exits().is_empty());
+    assert!(validators.process_exits(exit_slot + 1).is_empty());
+}
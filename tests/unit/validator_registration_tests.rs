@@ -0,0 +1,84 @@
+use alloy::primitives::{Address, U256};
+use speed_blockchain::consensus::ValidatorSet;
+use speed_blockchain::{
+    GasConfig, PendingStake, ReceiptEvent, StateTransition, Transaction, TransactionKind,
+};
+
+use super::helpers::{ONE_TOKEN, funded_state, transaction};
+
+fn candidate() -> Address {
+    Address::repeat_byte(0xcc)
+}
+
+fn registration(stake: u64) -> Transaction {
+    transaction(
+        candidate(),
+        Address::ZERO,
+        0,
+        stake,
+        TransactionKind::RegisterValidator,
+    )
+}
+
+fn registering(stake: u64, withdraw: u64) -> PendingStake {
+    PendingStake {
+        top_up: U256::from(stake),
+        withdraw: U256::from(withdraw),
+        register: true,
+        exit: false,
+    }
+}
+
+#[test]
+fn test_registration_is_queued_in_the_state() {
+    let mut state = funded_state(&[(candidate(), ONE_TOKEN)]);
+    let mut top_up = state.clone();
+
+    let mut tx = registration(500);
+    StateTransition::apply_transaction(&mut state, &mut tx, &GasConfig::default()).unwrap();
+    assert_eq!(state.pending_stake[&candidate()], registering(500, 0));
+    assert!(state.get_balance(&candidate()) < U256::from(ONE_TOKEN - 500));
+    assert_eq!(
+        ReceiptEvent::from_transaction(&tx),
+        vec![ReceiptEvent::ValidatorRegistrationQueued {
+            validator: candidate(),
+            stake: U256::from(500),
+        }]
+    );
+
+    // a plain top-up of the same amount commits to a different state
+    let mut tx = registration(500);
+    tx.kind = TransactionKind::StakeTopUp;
+    StateTransition::apply_transaction(&mut top_up, &mut tx, &GasConfig::default()).unwrap();
+    assert_ne!(top_up.get_state_root(), state.get_state_root());
+
+    let mut empty = registration(0);
+    empty.nonce = 1;
+    assert!(
+        StateTransition::apply_transaction(&mut state, &mut empty, &GasConfig::default()).is_err()
+    );
+}
+
+#[test]
+fn test_settlement_adds_registered_validators_with_the_minimum_stake() {
+    let mut validators = ValidatorSet::new(100);
+
+    let settlement = validators.settle_stake(candidate(), &registering(150, 20));
+    assert!(settlement.registered);
+    assert_eq!((settlement.topped_up, settlement.withdrawn), (150, 20));
+    assert_eq!(settlement.credit, U256::from(20));
+    assert!(validators.is_active_validator(&candidate()));
+    assert_eq!(validators.stake_of(&candidate()), 130);
+
+    // registering again only tops up
+    let settlement = validators.settle_stake(candidate(), &registering(10, 0));
+    assert!(!settlement.registered);
+    assert_eq!(validators.stake_of(&candidate()), 140);
+
+    // below the minimum stake the registration is refunded
+    let small = Address::repeat_byte(0xdd);
+    let settlement = validators.settle_stake(small, &registering(99, 0));
+    assert!(!settlement.registered);
+    assert_eq!(settlement.credit, U256::from(99));
+    assert!(!validators.is_active_validator(&small));
+}
@@ -0,0 +1,57 @@
+use speed_blockchain::{
+    Blockchain, KeyPair, MIN_STAKE, SLOT_DURATION, SLOTS_PER_EPOCH, ValidatorRole,
+};
+
+#[tokio::test]
+async fn test_role_follows_the_proposer_schedule_each_slot() {
+    let dir = tempfile::tempdir().unwrap();
+    let keys = [
+        KeyPair::generate("validator-role-local".to_string()),
+        KeyPair::generate("validator-role-other".to_string()),
+    ];
+    let local = keys[0].address;
+    let blockchain = Blockchain::new(
+        dir.path().to_str().unwrap(),
+        MIN_STAKE,
+        SLOT_DURATION,
+        keys.iter().map(|key| (key.address, 200)).collect(),
+        Some(keys[0].clone()),
+    )
+    .unwrap();
+
+    let mut roles = Vec::new();
+    for slot in 1..2 * SLOTS_PER_EPOCH {
+        let proposer = blockchain
+            .consensus_engine
+            .lock()
+            .await
+            .scheduled_proposer(slot)
+            .unwrap();
+        let role = blockchain.slot_role(slot).await;
+        let expected = match proposer == local {
+            true => ValidatorRole::Proposer,
+            false => ValidatorRole::Attestor,
+        };
+        assert_eq!(role, Some(expected), "slot {}", slot);
+        roles.push(expected);
+    }
+    // the same node takes both duties over two epochs, no fixed role
+    assert!(roles.contains(&ValidatorRole::Proposer));
+    assert!(roles.contains(&ValidatorRole::Attestor));
+}
+
+#[tokio::test]
+async fn test_nodes_without_an_active_validator_have_no_duties() {
+    let dir = tempfile::tempdir().unwrap();
+    let validator = KeyPair::generate("validator-role-validator".to_string());
+    let observer = Blockchain::new(
+        dir.path().to_str().unwrap(),
+        MIN_STAKE,
+        SLOT_DURATION,
+        vec![(validator.address, 200)],
+        Some(KeyPair::generate("validator-role-observer".to_string())),
+    )
+    .unwrap();
+    assert_eq!(observer.slot_role(1).await, None);
+    assert_eq!(observer.current_role().await, None);
+}
@@ -0,0 +1,106 @@
+use alloy::primitives::{Address, B256};
+use speed_blockchain::consensus::{LivenessRecord, ValidatorScores};
+use speed_blockchain::core::{Block, BlockHeader};
+use speed_blockchain::{Blockchain, KeyPair, MIN_STAKE, SLOT_DURATION, SLOTS_PER_EPOCH};
+
+use super::helpers::{genesis_slots_ago, slot_time};
+
+fn record(validator: u8, proposals: (u64, u64), attestations: (u64, u64)) -> LivenessRecord {
+    LivenessRecord {
+        validator: Address::repeat_byte(validator),
+        epoch: 4,
+        proposal_duties: proposals.0,
+        proposals_missed: proposals.1,
+        attestation_duties: attestations.0,
+        attestations_missed: attestations.1,
+    }
+}
+
+#[test]
+fn test_scores_add_up_epoch_duties_and_latency() {
+    let mut scores = ValidatorScores::new();
+    scores.record_epoch(&[record(1, (4, 1), (2, 0)), record(2, (0, 0), (4, 2))]);
+    scores.record_epoch(&[record(1, (4, 0), (4, 3)), record(2, (2, 2), (6, 0))]);
+    // nobody proposed, e.g. we were offline ourselves
+    scores.record_epoch(&[record(1, (3, 3), (0, 0)), record(2, (3, 3), (0, 0))]);
+    scores.record_latency(Address::repeat_byte(1), 300);
+    scores.record_latency(Address::repeat_byte(1), -100);
+
+    let report = scores.report();
+    assert_eq!(report.len(), 2);
+    let first = &report[0];
+    assert_eq!((first.proposals_made, first.proposals_missed), (7, 1));
+    assert_eq!(
+        (first.attestations_included, first.attestation_duties),
+        (3, 6)
+    );
+    assert_eq!((first.epochs, first.last_epoch), (2, Some(4)));
+    assert_eq!(first.proposal_rate, 0.875);
+    assert_eq!(first.attestation_rate, 0.5);
+    assert_eq!(first.score, 0.4375);
+    // an early block counts as no latency
+    assert_eq!(first.avg_latency_ms, Some(150));
+
+    let second = &report[1];
+    assert_eq!((second.proposal_rate, second.attestation_rate), (0.0, 0.8));
+    assert_eq!(second.score, 0.0);
+    assert_eq!(second.avg_latency_ms, None);
+
+    // a validator without duties yet scores full
+    let mut fresh = ValidatorScores::new();
+    fresh.record_latency(Address::repeat_byte(3), 50);
+    assert_eq!(fresh.report()[0].score, 1.0);
+}
+
+#[tokio::test]
+async fn test_scores_are_updated_at_epoch_close_and_kept_across_restarts() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().to_str().unwrap();
+    let proposer = KeyPair::generate("validator-score-proposer".to_string());
+    let validators = vec![(proposer.address, 200)];
+    genesis_slots_ago(path, SLOTS_PER_EPOCH);
+    let blockchain =
+        Blockchain::new(path, MIN_STAKE, SLOT_DURATION, validators.clone(), None).unwrap();
+
+    let mut parent_hash = B256::ZERO;
+    for (index, slot) in [(1, 1), (2, SLOTS_PER_EPOCH)] {
+        let state_root = blockchain
+            .execution_engine
+            .state_manager
+            .lock()
+            .await
+            .get_state_root();
+        let mut header = BlockHeader::new(
+            index,
+            slot,
+            proposer.address,
+            parent_hash,
+            Block::calculate_transactions_root(&[]),
+            state_root,
+        );
+        header.timestamp = slot_time(&blockchain, slot).await;
+        header.sign(&proposer).await.unwrap();
+        parent_hash = header.hash();
+        let signature = header.validator_signature.unwrap();
+        blockchain
+            .process_received_block(Block::new(header, Vec::new()), proposer.address, signature)
+            .await
+            .unwrap();
+    }
+
+    // block #2 closed epoch 0, where the only validator proposed block #1
+    let scores = blockchain.validator_scores(Some(proposer.address)).await;
+    assert_eq!(scores.len(), 1);
+    assert_eq!((scores[0].epochs, scores[0].last_epoch), (1, Some(0)));
+    assert_eq!(scores[0].proposals_made, 1);
+    assert!(
+        blockchain
+            .validator_scores(Some(Address::repeat_byte(9)))
+            .await
+            .is_empty()
+    );
+    drop(blockchain);
+
+    let blockchain = Blockchain::new(path, MIN_STAKE, SLOT_DURATION, validators, None).unwrap();
+    assert_eq!(blockchain.validator_scores(None).await, scores);
+}
@@ -0,0 +1,99 @@
+use alloy::primitives::Address;
+use speed_blockchain::consensus::{StakeError, ValidatorError, ValidatorSet};
+
+fn validator(byte: u8) -> Address {
+    Address::repeat_byte(byte)
+}
+
+fn set() -> ValidatorSet {
+    let mut set = ValidatorSet::new(100);
+    assert!(set.add_validator(validator(1), 300).is_ok());
+    assert!(set.add_validator(validator(2), 200).is_ok());
+    set
+}
+
+#[test]
+fn test_membership_changes_keep_total_stake() {
+    let mut set = set();
+    // adding an address twice used to count its stake twice
+    assert_eq!(
+        set.add_validator(validator(1), 500),
+        Err(StakeError::AlreadyValidator)
+    );
+    assert_eq!((set.len(), set.total_stake()), (2, 500));
+
+    assert_eq!(set.update_stake(&validator(1), 400), Ok(300));
+    assert_eq!(set.total_stake(), 600);
+    // below the minimum the validator stops counting, its stake is still in the set
+    assert_eq!(set.update_stake(&validator(2), 50), Ok(200));
+    assert!(!set.is_active_validator(&validator(2)));
+    assert_eq!((set.total_stake(), set.active_stake()), (450, 400));
+    // raising it again doesn't reactivate on its own
+    assert_eq!(set.update_stake(&validator(2), 250), Ok(50));
+    assert!(!set.is_active_validator(&validator(2)));
+    assert_eq!(set.reactivate(&validator(2)), Ok(true));
+    assert_eq!(set.active_stake(), 650);
+
+    let removed = set.remove_validator(&validator(2)).unwrap();
+    assert_eq!(removed.staked_amount, 250);
+    assert!(!set.contains(&validator(2)));
+    assert_eq!((set.len(), set.total_stake()), (1, 400));
+    assert_eq!(
+        set.update_stake(&validator(2), 300),
+        Err(ValidatorError::UnknownValidator(validator(2)))
+    );
+}
+
+#[test]
+fn test_deactivation_never_empties_the_active_set() {
+    let mut set = set();
+    assert_eq!(set.deactivate(&validator(2)), Ok(true));
+    assert_eq!(set.deactivate(&validator(2)), Ok(false));
+    assert_eq!(set.get_active_validators().len(), 1);
+    assert_eq!(set.total_stake(), 500);
+
+    // validator 1 is the only one left to propose
+    let last = ValidatorError::LastActiveValidator(validator(1));
+    assert_eq!(set.deactivate(&validator(1)), Err(last.clone()));
+    assert_eq!(set.update_stake(&validator(1), 10), Err(last.clone()));
+    assert_eq!(set.remove_validator(&validator(1)).unwrap_err(), last);
+    assert_eq!(set.update_stake(&validator(1), 150), Ok(300));
+
+    // an exiting validator isn't brought back, nor one below the minimum stake
+    assert_eq!(set.reactivate(&validator(2)), Ok(true));
+    assert!(set.request_exit(&validator(2), 10));
+    assert_eq!(set.deactivate(&validator(2)), Ok(true));
+    assert_eq!(
+        set.reactivate(&validator(2)),
+        Err(ValidatorError::Exiting(validator(2)))
+    );
+    assert!(set.add_validator(validator(3), 100).is_ok());
+    assert_eq!(set.update_stake(&validator(3), 50), Ok(100));
+    assert_eq!(
+        set.reactivate(&validator(3)),
+        Err(ValidatorError::InsufficientStake {
+            stake: 50,
+            min_stake: 100
+        })
+    );
+}
+
+#[test]
+fn test_queries_list_every_validator_in_address_order() {
+    let mut set = ValidatorSet::new(100);
+    assert!(set.is_empty());
+    for byte in [3, 1, 2] {
+        assert!(
+            set.add_validator(validator(byte), 100 * byte as u64)
+                .is_ok()
+        );
+    }
+    assert_eq!(set.deactivate(&validator(2)), Ok(true));
+
+    let addresses: Vec<Address> = set.validators().iter().map(|v| v.address).collect();
+    assert_eq!(addresses, vec![validator(1), validator(2), validator(3)]);
+    assert_eq!(set.get_active_validators().len(), 2);
+    assert_eq!(set.get_validator(&validator(3)).unwrap().staked_amount, 300);
+    assert!(set.get_validator(&validator(4)).is_none());
+    assert_eq!(set.min_stake(), 100);
+}
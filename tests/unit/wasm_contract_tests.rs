@@ -0,0 +1,314 @@
+use alloy::primitives::{Address, B256, Bytes, U256};
+use speed_blockchain::{
+    BlockEnv, ChainSpec, ContractCall, ContractFailure, ContractOutcome, ContractRuntime,
+    ContractRuntimeKind, ExecutionEngine, GasCalculator, GasConfig, ReceiptEvent, StateManager,
+    StateTransition, StateTransitionError, Transaction, TransactionKind, WasmRuntime,
+    contract_address, precompile_address,
+};
+
+use super::helpers::{self, ONE_TOKEN, TO_GWEI, funded_state, with_intrinsic_gas};
+
+// fuel on top of the intrinsic gas
+const CALL_GAS: u64 = 100_000;
+
+// adds one to the little endian u64 under the zero key and returns the new count
+const COUNTER: &str = r#"(module
+  (import "env" "storage_read" (func $read (param i32 i32 i32) (result i32)))
+  (import "env" "storage_write" (func $write (param i32 i32 i32)))
+  (import "env" "output" (func $output (param i32 i32)))
+  (memory (export "memory") 1)
+  (func (export "call") (result i32)
+    (drop (call $read (i32.const 0) (i32.const 32) (i32.const 8)))
+    (i64.store (i32.const 32) (i64.add (i64.load (i32.const 32)) (i64.const 1)))
+    (call $write (i32.const 0) (i32.const 32) (i32.const 8))
+    (call $output (i32.const 32) (i32.const 8))
+    (i32.const 0)))"#;
+
+// pays 1000 wei to its caller, reverts when its balance doesn't cover it
+const FAUCET: &str = r#"(module
+  (import "env" "caller" (func $caller (param i32)))
+  (import "env" "transfer" (func $transfer (param i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (data (i32.const 94) "\03\e8")
+  (func (export "call") (result i32)
+    (call $caller (i32.const 0))
+    (call $transfer (i32.const 0) (i32.const 64))))"#;
+
+// pays 1000 wei to the keccak256 precompile
+const TO_PRECOMPILE: &str = r#"(module
+  (import "env" "transfer" (func $transfer (param i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (data (i32.const 19) "\01")
+  (data (i32.const 94) "\03\e8")
+  (func (export "call") (result i32)
+    (call $transfer (i32.const 0) (i32.const 64))))"#;
+
+// never returns, runs until the fuel is gone
+const SPIN: &str = r#"(module
+  (memory (export "memory") 1)
+  (func (export "call") (result i32)
+    (loop $spin (br $spin))
+    (i32.const 0)))"#;
+
+// a broken runtime that pays out twice the balance it was given
+struct Overpaying;
+
+impl ContractRuntime for Overpaying {
+    fn name(&self) -> &'static str {
+        "overpaying"
+    }
+
+    fn validate(&self, _code: &[u8]) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn call(&self, call: ContractCall) -> Result<ContractOutcome, ContractFailure> {
+        Ok(ContractOutcome {
+            output: Bytes::new(),
+            storage: call.storage,
+            transfers: vec![(call.caller, call.balance), (call.caller, call.balance)],
+            fuel_used: 1,
+        })
+    }
+}
+
+fn alice() -> Address {
+    Address::repeat_byte(0xaa)
+}
+
+// contract code is larger than the default transaction size limit
+fn config() -> GasConfig {
+    GasConfig {
+        max_tx_size: 16 * 1024,
+        ..GasConfig::default()
+    }
+}
+
+fn transaction(to: Address, nonce: u64, amount: u64, kind: TransactionKind) -> Transaction {
+    let tx = helpers::transaction(alice(), to, nonce, amount, kind);
+    let mut tx = with_intrinsic_gas(tx, &config());
+    tx.gas_limit += U256::from(CALL_GAS);
+    tx
+}
+
+fn deploy(nonce: u64, code: &str, amount: u64) -> Transaction {
+    let code = Bytes::copy_from_slice(code.as_bytes());
+    transaction(
+        Address::ZERO,
+        nonce,
+        amount,
+        TransactionKind::DeployContract { code },
+    )
+}
+
+fn call(contract: Address, nonce: u64) -> Transaction {
+    let input = Bytes::new();
+    transaction(contract, nonce, 0, TransactionKind::CallContract { input })
+}
+
+fn apply(
+    state: &mut StateManager,
+    runtime: &WasmRuntime,
+    tx: &mut Transaction,
+) -> Result<(U256, Bytes), StateTransitionError> {
+    let env = BlockEnv {
+        contracts: Some(runtime),
+        ..BlockEnv::default()
+    };
+    StateTransition::apply_transaction_in_block(state, tx, &config(), &env)
+}
+
+#[test]
+fn test_counter_keeps_its_storage_between_calls() {
+    let runtime = WasmRuntime::new();
+    let mut state = funded_state(&[(alice(), ONE_TOKEN)]);
+    let contract = contract_address(&alice(), 0);
+
+    let mut tx = deploy(0, COUNTER, 0);
+    apply(&mut state, &runtime, &mut tx).unwrap();
+    assert_eq!(
+        state.get_contract_code(&contract).unwrap().as_ref(),
+        COUNTER.as_bytes()
+    );
+    assert_eq!(
+        ReceiptEvent::from_transaction(&tx),
+        vec![ReceiptEvent::ContractDeployed {
+            contract,
+            deployer: alice(),
+        }]
+    );
+
+    for (nonce, count) in [(1, 1u64), (2, 2)] {
+        let mut tx = call(contract, nonce);
+        let balance = state.get_balance(&alice());
+        let (gas_used, output) = apply(&mut state, &runtime, &mut tx).unwrap();
+        assert_eq!(output.as_ref(), &count.to_le_bytes());
+        // the burned fuel is paid for on top of the intrinsic gas, the rest is left unpaid
+        let intrinsic = GasCalculator::calculate_instrinsic_gas(&tx, &config());
+        assert!(gas_used > intrinsic && gas_used < tx.gas_limit);
+        assert_eq!(
+            state.get_balance(&alice()),
+            balance - gas_used * U256::from(TO_GWEI)
+        );
+    }
+    assert_eq!(
+        state.get_contract_storage(&contract).get(&B256::ZERO),
+        Some(&Bytes::copy_from_slice(&2u64.to_le_bytes()))
+    );
+}
+
+#[test]
+fn test_contract_pays_out_of_its_balance() {
+    let runtime = WasmRuntime::new();
+    let mut state = funded_state(&[(alice(), ONE_TOKEN)]);
+    let contract = contract_address(&alice(), 0);
+
+    let mut tx = deploy(0, FAUCET, 5000);
+    apply(&mut state, &runtime, &mut tx).unwrap();
+    assert_eq!(state.get_balance(&contract), U256::from(5000));
+
+    let balance = state.get_balance(&alice());
+    let mut tx = call(contract, 1);
+    let (gas_used, _) = apply(&mut state, &runtime, &mut tx).unwrap();
+    assert_eq!(state.get_balance(&contract), U256::from(4000));
+    assert_eq!(
+        state.get_balance(&alice()),
+        balance + U256::from(1000) - gas_used * U256::from(TO_GWEI)
+    );
+    assert_eq!(
+        ReceiptEvent::from_transaction(&tx),
+        vec![ReceiptEvent::ContractCalled {
+            contract,
+            caller: alice(),
+            value: U256::ZERO,
+        }]
+    );
+}
+
+#[test]
+fn test_contract_cannot_pay_a_precompile() {
+    let runtime = WasmRuntime::new();
+    let mut state = funded_state(&[(alice(), ONE_TOKEN)]);
+    let contract = contract_address(&alice(), 0);
+
+    let mut tx = deploy(0, TO_PRECOMPILE, 5000);
+    apply(&mut state, &runtime, &mut tx).unwrap();
+    let root = state.get_state_root();
+
+    let mut tx = call(contract, 1);
+    assert!(matches!(
+        apply(&mut state, &runtime, &mut tx),
+        Err(StateTransitionError::InvalidPayload(_))
+    ));
+    assert_eq!(state.get_state_root(), root);
+    assert_eq!(state.get_balance(&precompile_address(1)), U256::ZERO);
+}
+
+#[test]
+fn test_payouts_above_the_contract_balance_fail_the_call() {
+    let runtime = Overpaying;
+    let env = BlockEnv {
+        contracts: Some(&runtime),
+        ..BlockEnv::default()
+    };
+    let mut state = funded_state(&[(alice(), ONE_TOKEN)]);
+    let contract = contract_address(&alice(), 0);
+
+    let mut tx = deploy(0, COUNTER, 5000);
+    StateTransition::apply_transaction_in_block(&mut state, &mut tx, &config(), &env).unwrap();
+    let root = state.get_state_root();
+
+    let mut tx = call(contract, 1);
+    let err = StateTransition::apply_transaction_in_block(&mut state, &mut tx, &config(), &env)
+        .unwrap_err();
+    assert!(
+        matches!(err, StateTransitionError::ContractFailed { contract: failed, .. } if failed == contract)
+    );
+    assert_eq!(state.get_state_root(), root);
+    assert_eq!(state.get_balance(&contract), U256::from(5000));
+}
+
+#[test]
+fn test_failed_calls_leave_the_state_untouched() {
+    let runtime = WasmRuntime::new();
+    let mut state = funded_state(&[(alice(), ONE_TOKEN)]);
+
+    // not enough to pay out, the contract reverts
+    let mut tx = deploy(0, FAUCET, 500);
+    apply(&mut state, &runtime, &mut tx).unwrap();
+    let faucet = contract_address(&alice(), 0);
+    let root = state.get_state_root();
+    let mut tx = call(faucet, 1);
+    let err = apply(&mut state, &runtime, &mut tx).unwrap_err();
+    assert!(
+        matches!(err, StateTransitionError::ContractFailed { contract, .. } if contract == faucet)
+    );
+    assert_eq!(state.get_state_root(), root);
+
+    // runs out of fuel
+    let mut tx = deploy(1, SPIN, 0);
+    apply(&mut state, &runtime, &mut tx).unwrap();
+    let spin = contract_address(&alice(), 1);
+    let root = state.get_state_root();
+    let mut tx = call(spin, 2);
+    assert!(matches!(
+        apply(&mut state, &runtime, &mut tx),
+        Err(StateTransitionError::ContractFailed { .. })
+    ));
+    assert_eq!(state.get_state_root(), root);
+
+    // nothing deployed there
+    let mut tx = call(Address::repeat_byte(0x42), 2);
+    assert!(matches!(
+        apply(&mut state, &runtime, &mut tx),
+        Err(StateTransitionError::NoContract(_))
+    ));
+}
+
+#[test]
+fn test_deploy_rejects_invalid_code() {
+    let runtime = WasmRuntime::new();
+    let mut state = funded_state(&[(alice(), ONE_TOKEN)]);
+
+    for code in [
+        "not wasm",
+        // no `call` export
+        r#"(module (memory (export "memory") 1))"#,
+        // imports outside the host interface
+        r#"(module
+  (import "env" "exit" (func))
+  (memory (export "memory") 1)
+  (func (export "call") (result i32) (i32.const 0)))"#,
+    ] {
+        assert!(runtime.validate(code.as_bytes()).is_err());
+        let mut tx = deploy(0, code, 0);
+        assert!(matches!(
+            apply(&mut state, &runtime, &mut tx),
+            Err(StateTransitionError::InvalidPayload(_))
+        ));
+    }
+    assert_eq!(state.get_nonce(&alice()), 0);
+}
+
+#[tokio::test]
+async fn test_chains_without_a_runtime_reject_contracts() {
+    let mut state = funded_state(&[(alice(), ONE_TOKEN)]);
+    let mut tx = deploy(0, COUNTER, 0);
+    assert!(matches!(
+        StateTransition::apply_transaction_with_output(&mut state, &mut tx, &config()),
+        Err(StateTransitionError::ContractsDisabled)
+    ));
+
+    let engine = ExecutionEngine::new().with_gas_config(config());
+    let err = engine.add_transaction(&tx).await.unwrap_err();
+    assert_eq!(err.to_string(), "Contracts are not enabled on this chain");
+
+    // the runtime is part of the chain spec hash, only when there is one
+    let spec = ChainSpec::default();
+    let wasm = ChainSpec::from_toml("contract_runtime = \"wasm\"").unwrap();
+    assert_eq!(spec.contract_runtime, ContractRuntimeKind::None);
+    assert_eq!(wasm.contract_runtime, ContractRuntimeKind::Wasm);
+    assert_ne!(spec.chain_spec_bytes(), wasm.chain_spec_bytes());
+    assert!(ContractRuntimeKind::None.runtime().is_none());
+    assert_eq!(ContractRuntimeKind::Wasm.runtime().unwrap().name(), "wasm");
+}
@@ -0,0 +1,103 @@
+use speed_blockchain::WatchdogConfig;
+use speed_blockchain::consensus::SlotClock;
+use speed_blockchain::core::{LoopHeartbeat, Watchdog};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+fn config() -> WatchdogConfig {
+    WatchdogConfig {
+        stall_threshold_ms: 100,
+        ..WatchdogConfig::default()
+    }
+}
+
+#[tokio::test]
+async fn test_held_lock_is_reported_once_per_stall() {
+    let state = Arc::new(Mutex::new(0u64));
+    let consensus = Arc::new(Mutex::new(0u64));
+    let heartbeat = Arc::new(LoopHeartbeat::default());
+    let mut watchdog = Watchdog::new(config(), heartbeat)
+        .with_slot_clock(SlotClock::new(
+            SystemTime::now() - Duration::from_secs(95),
+            Duration::from_secs(10),
+        ))
+        .watch_lock("state", state.clone())
+        .watch_lock("consensus", consensus.clone());
+    let start = Instant::now();
+
+    let guard = state.lock().await;
+    assert!(watchdog.check(start).is_empty());
+    assert!(watchdog.check(start + Duration::from_millis(50)).is_empty());
+
+    let reports = watchdog.check(start + Duration::from_millis(150));
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].subject, "state lock");
+    assert_eq!(reports[0].stalled_ms, 150);
+    assert_eq!(reports[0].held_locks, vec!["state".to_string()]);
+    assert_eq!(reports[0].current_slot, Some(9));
+    // still the same stall
+    assert!(
+        watchdog
+            .check(start + Duration::from_millis(300))
+            .is_empty()
+    );
+
+    drop(guard);
+    assert!(
+        watchdog
+            .check(start + Duration::from_millis(400))
+            .is_empty()
+    );
+
+    // a second stall is reported again, with every lock held at the time
+    let _state = state.lock().await;
+    let _consensus = consensus.lock().await;
+    watchdog.check(start + Duration::from_millis(500));
+    let reports = watchdog.check(start + Duration::from_millis(700));
+    assert_eq!(reports.len(), 2);
+    assert!(
+        reports
+            .iter()
+            .all(|r| r.held_locks == vec!["state".to_string(), "consensus".to_string()])
+    );
+}
+
+#[tokio::test]
+async fn test_stalled_loop_step_is_reported_and_can_be_restarted() {
+    let heartbeat = Arc::new(LoopHeartbeat::default());
+    let mut watchdog = Watchdog::new(config(), heartbeat.clone());
+
+    // waiting for work is not a stall
+    assert!(
+        watchdog
+            .check(Instant::now() + Duration::from_secs(60))
+            .is_empty()
+    );
+
+    heartbeat.enter("slot tick", 3);
+    let reports = watchdog.check(Instant::now() + Duration::from_millis(200));
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].subject, "service loop");
+    assert_eq!(reports[0].activity.as_deref(), Some("slot tick"));
+    assert_eq!(reports[0].queued_messages, 3);
+    assert_eq!(reports[0].current_slot, None);
+
+    // the step in progress is woken so the loop can drop it
+    let waiting = heartbeat.clone();
+    let step = tokio::spawn(async move { waiting.restart_requested().await });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    heartbeat.request_restart();
+    tokio::time::timeout(Duration::from_secs(1), step)
+        .await
+        .expect("restart wakes the stalled step")
+        .unwrap();
+
+    heartbeat.leave();
+    assert!(
+        watchdog
+            .check(Instant::now() + Duration::from_secs(60))
+            .is_empty()
+    );
+}